@@ -42,6 +42,7 @@ export default defineConfig({
           { text: "Introduction", link: "/config/introduction" },
           { text: "Basic", link: "/config/basic" },
           { text: "Advanced", link: "/config/advanced" },
+          { text: "Messages", link: "/config/messages" },
         ],
       },
       {
@@ -16,6 +16,18 @@ pub struct AuthenticationConfig {
     pub prevent_proxy_connections: bool,
     #[serde_inline_default("https://sessionserver.mojang.com/session/minecraft/hasJoined?username={username}&serverId={server_hash}&ip={ip}".to_string())]
     pub prevent_proxy_connection_auth_url: String,
+    /// Lists the keys player chat keys are signed with.
+    #[serde_inline_default("https://api.minecraftservices.com/publickeys".to_string())]
+    pub public_keys_url: String,
+    /// How long connecting to the authentication server may take, in milliseconds.
+    #[serde_inline_default(2000)]
+    pub connect_timeout_ms: u64,
+    /// How long a whole request to the authentication server may take, in milliseconds.
+    #[serde_inline_default(5000)]
+    pub timeout_ms: u64,
+    /// Let players join in offline mode when the authentication server is unreachable.
+    #[serde_inline_default(false)]
+    pub offline_fallback: bool,
     /// Player profile handling.
     #[serde(default)]
     pub player_profile: PlayerProfileConfig,
@@ -33,6 +45,10 @@ impl Default for AuthenticationConfig {
             textures: Default::default(),
             auth_url: "https://sessionserver.mojang.com/session/minecraft/hasJoined?username={username}&serverId={server_hash}".to_string(),
             prevent_proxy_connection_auth_url: "https://sessionserver.mojang.com/session/minecraft/hasJoined?username={username}&serverId={server_hash}&ip={ip}".to_string(),
+            public_keys_url: "https://api.minecraftservices.com/publickeys".to_string(),
+            connect_timeout_ms: 2000,
+            timeout_ms: 5000,
+            offline_fallback: false,
         }
     }
 }
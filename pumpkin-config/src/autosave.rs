@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Periodically writes changed chunks to disk, a few chunks every tick
+pub struct AutosaveConfig {
+    #[serde_inline_default(true)]
+    pub enabled: bool,
+    /// How often an autosave starts, in seconds
+    #[serde_inline_default(300)]
+    pub interval: u64,
+    /// How many chunks are saved per tick while an autosave is running
+    #[serde_inline_default(32)]
+    pub chunks_per_tick: usize,
+}
+
+impl Default for AutosaveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: 300,
+            chunks_per_tick: 32,
+        }
+    }
+}
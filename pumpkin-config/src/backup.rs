@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Archives of the world folders, written on a schedule and with `/backup now`
+pub struct BackupConfig {
+    /// Whether backups are written on a schedule, `/backup now` always works
+    #[serde_inline_default(false)]
+    pub enabled: bool,
+    /// How often a scheduled backup is written, in seconds
+    #[serde_inline_default(21600)]
+    pub interval: u64,
+    /// The folder the archives are written to
+    #[serde(default = "default_folder")]
+    pub folder: PathBuf,
+    /// The oldest backups are deleted once there are more, 0 keeps all of them
+    #[serde_inline_default(10)]
+    pub max_backups: usize,
+    /// Backups older than this many days are deleted, 0 keeps them forever
+    #[serde_inline_default(0)]
+    pub max_age_days: u64,
+    /// The zstd compression level, from 1 (fastest) to 22 (smallest)
+    #[serde_inline_default(3)]
+    pub compression_level: i32,
+}
+
+fn default_folder() -> PathBuf {
+    PathBuf::from("backups")
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: 21600,
+            folder: default_folder(),
+            max_backups: 10,
+            max_age_days: 0,
+            compression_level: 3,
+        }
+    }
+}
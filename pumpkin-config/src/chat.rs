@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct ChatConfig {
+    /// Strips all chat signatures and sends every message as a system message, so chat can't be reported.
+    /// Overrides `enforce_secure_chat`
+    #[serde_inline_default(false)]
+    pub no_chat_reports: bool,
+}
+
+impl Default for ChatConfig {
+    fn default() -> Self {
+        Self {
+            no_chat_reports: false,
+        }
+    }
+}
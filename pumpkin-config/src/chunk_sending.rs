@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// How chunks are streamed to players
+pub struct ChunkSendingConfig {
+    /// How many chunks a player gets at most every tick
+    #[serde_inline_default(16)]
+    pub chunks_per_tick: u32,
+    /// Whether chunks in the direction a player moves are sent first, otherwise the nearest are
+    #[serde_inline_default(true)]
+    pub prioritize_direction: bool,
+}
+
+impl Default for ChunkSendingConfig {
+    fn default() -> Self {
+        Self {
+            chunks_per_tick: 16,
+            prioritize_direction: true,
+        }
+    }
+}
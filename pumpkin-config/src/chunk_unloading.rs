@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Periodically unloads chunks no player, portal, plugin or `/forceload` needs anymore
+pub struct ChunkUnloadingConfig {
+    #[serde_inline_default(true)]
+    pub enabled: bool,
+    /// How often unused chunks are unloaded, in seconds
+    #[serde_inline_default(30)]
+    pub interval: u64,
+    /// Whether changed chunks get saved before they are unloaded, otherwise they stay loaded until the next save
+    #[serde_inline_default(true)]
+    pub save: bool,
+}
+
+impl Default for ChunkUnloadingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: 30,
+            save: true,
+        }
+    }
+}
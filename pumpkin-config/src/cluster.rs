@@ -0,0 +1,48 @@
+use std::net::{Ipv4Addr, SocketAddr};
+
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+/// Connects multiple Pumpkin servers, so they share chat, player counts and transfer targets
+pub struct ClusterConfig {
+    #[serde_inline_default(false)]
+    pub enabled: bool,
+    /// The name of this server in the cluster, must be unique
+    #[serde_inline_default("lobby".to_string())]
+    pub node_name: String,
+    /// The address other nodes connect to
+    #[serde_inline_default(SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 25580))]
+    pub address: SocketAddr,
+    /// The cluster addresses of the other nodes, as `host:port`
+    #[serde_inline_default(Vec::new())]
+    pub peers: Vec<String>,
+    /// Shared by all nodes, connections with a different secret are rejected
+    #[serde_inline_default("".to_string())]
+    pub secret: String,
+    /// The address players get transferred to when joining this node, as `host:port`
+    pub public_address: Option<String>,
+    /// Whether chat messages are shown on all nodes
+    #[serde_inline_default(true)]
+    pub relay_chat: bool,
+    /// Whether the server list shows the players of all nodes
+    #[serde_inline_default(true)]
+    pub global_player_count: bool,
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            node_name: "lobby".to_string(),
+            address: SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 25580),
+            peers: Vec::new(),
+            secret: "".to_string(),
+            public_address: None,
+            relay_chat: true,
+            global_player_count: true,
+        }
+    }
+}
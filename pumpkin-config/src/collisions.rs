@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// How entities bump into each other
+pub struct CollisionConfig {
+    /// Whether entities push each other, players included. Minigames may want to turn it off,
+    /// clients are then told that no team collides. There is no gamerule for it
+    #[serde_inline_default(true)]
+    pub entity_pushing: bool,
+}
+
+impl Default for CollisionConfig {
+    fn default() -> Self {
+        Self {
+            entity_pushing: true,
+        }
+    }
+}
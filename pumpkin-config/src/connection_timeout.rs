@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// How long a connection may stay in a state before reaching Play, connections which stall get closed
+pub struct ConnectionTimeoutConfig {
+    /// How long a connection has to send its handshake, in seconds
+    #[serde_inline_default(10)]
+    pub handshake: u64,
+    /// How long a connection has to finish logging in, in seconds
+    #[serde_inline_default(30)]
+    pub login: u64,
+    /// How long a connection has to finish the configuration, in seconds
+    #[serde_inline_default(60)]
+    pub config: u64,
+}
+
+impl Default for ConnectionTimeoutConfig {
+    fn default() -> Self {
+        Self {
+            handshake: 10,
+            login: 30,
+            config: 60,
+        }
+    }
+}
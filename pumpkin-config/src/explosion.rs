@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Explosions, e.g. of TNT
+pub struct ExplosionConfig {
+    /// Whether explosions destroy blocks. They still hurt and push players when this is off
+    #[serde_inline_default(true)]
+    pub block_damage: bool,
+}
+
+impl Default for ExplosionConfig {
+    fn default() -> Self {
+        Self { block_damage: true }
+    }
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Lets Bedrock players join through Geyser with Floodgate
+pub struct FloodgateConfig {
+    /// Whether Floodgate logins are accepted
+    #[serde_inline_default(false)]
+    pub enabled: bool,
+    /// The key Floodgate encrypts its data with, this must be the same file Geyser uses
+    #[serde_inline_default("key.pem".to_string())]
+    pub key_file: String,
+    /// Put in front of Bedrock usernames, so they don't collide with Java usernames
+    #[serde_inline_default(".".to_string())]
+    pub username_prefix: String,
+    /// Bedrock usernames may contain spaces, Java usernames can't
+    #[serde_inline_default(true)]
+    pub replace_spaces: bool,
+}
+
+impl Default for FloodgateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            key_file: "key.pem".to_string(),
+            username_prefix: ".".to_string(),
+            replace_spaces: true,
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use std::sync::{Arc, RwLock};
+
+/// A configuration which can be replaced while the Server is running.
+///
+/// Code which got the old configuration, e.g. a task started with a config section, keeps it
+/// until it drops its `Arc`
+pub struct ConfigHandle<T> {
+    current: RwLock<Arc<T>>,
+}
+
+impl<T> ConfigHandle<T> {
+    pub fn new(config: T) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(config)),
+        }
+    }
+
+    /// The current configuration, it stays valid even after a reload
+    pub fn get(&self) -> Arc<T> {
+        self.current
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    pub(crate) fn replace(&self, config: T) {
+        *self.current.write().unwrap_or_else(|err| err.into_inner()) = Arc::new(config);
+    }
+}
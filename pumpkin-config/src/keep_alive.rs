@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct KeepAliveConfig {
+    /// How often a keep alive is sent to players, in seconds
+    #[serde_inline_default(15)]
+    pub interval: u64,
+    /// How long a player has to answer a keep alive before getting kicked, in seconds
+    #[serde_inline_default(15)]
+    pub timeout: u64,
+}
+
+impl Default for KeepAliveConfig {
+    fn default() -> Self {
+        Self {
+            interval: 15,
+            timeout: 15,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+/// Announces the Server to clients in the local network, so it shows up in their LAN section
+pub struct LANBroadcastConfig {
+    /// Whether the Server is announced in the local network
+    #[serde_inline_default(false)]
+    pub enabled: bool,
+    /// The MOTD shown in the LAN section, the Server MOTD is used when not set
+    pub motd: Option<String>,
+    /// The port clients should connect to, the Server port is used when not set.
+    /// Useful when the Server is behind a proxy
+    pub port: Option<u16>,
+}
+
+impl Default for LANBroadcastConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            motd: None,
+            port: None,
+        }
+    }
+}
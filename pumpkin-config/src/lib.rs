@@ -1,6 +1,6 @@
 use log::warn;
 use logging::LoggingConfig;
-use pumpkin_core::{Difficulty, GameMode};
+use pumpkin_core::{Difficulty, GameMode, GeneratorType};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 // TODO: when https://github.com/rust-lang/rfcs/pull/3681 gets merged, replace serde-inline-default with native syntax
@@ -9,14 +9,39 @@ use serde_inline_default::serde_inline_default;
 use std::{
     fs,
     net::{Ipv4Addr, SocketAddr},
-    path::Path,
+    path::{Path, PathBuf},
     sync::LazyLock,
 };
 
 pub mod auth;
+pub mod autosave;
+pub mod backup;
+pub mod chat;
+pub mod chunk_sending;
+pub mod chunk_unloading;
+pub mod cluster;
+pub mod collisions;
+pub mod connection_timeout;
+pub mod explosion;
+pub mod floodgate;
+pub mod handle;
+pub mod keep_alive;
+pub mod lan_broadcast;
 pub mod logging;
+pub mod messages;
+pub mod metrics;
+pub mod movement;
+pub mod overrides;
+pub mod packet_dump;
 pub mod proxy;
+pub mod rate_limit;
 pub mod resource_pack;
+pub mod send_queue;
+pub mod skins;
+pub mod structures;
+pub mod tab_list;
+pub mod weather;
+pub mod worlds;
 
 pub use auth::AuthenticationConfig;
 pub use commands::CommandsConfig;
@@ -29,13 +54,45 @@ pub mod compression;
 mod pvp;
 mod rcon;
 
+use autosave::AutosaveConfig;
+use backup::BackupConfig;
+use chat::ChatConfig;
+use chunk_sending::ChunkSendingConfig;
+use chunk_unloading::ChunkUnloadingConfig;
+use cluster::ClusterConfig;
+use collisions::CollisionConfig;
+use connection_timeout::ConnectionTimeoutConfig;
+use explosion::ExplosionConfig;
+use floodgate::FloodgateConfig;
+use handle::ConfigHandle;
+use keep_alive::KeepAliveConfig;
+use lan_broadcast::LANBroadcastConfig;
+use messages::MessagesConfiguration;
+use metrics::MetricsConfig;
+use movement::MovementConfig;
+use overrides::{config_path, overrides, ConfigOverrides};
+use packet_dump::PacketDumpConfig;
 use proxy::ProxyConfig;
+use rate_limit::PacketRateLimitConfig;
 use resource_pack::ResourcePackConfig;
+use send_queue::SendQueueConfig;
+use skins::SkinConfig;
+use structures::StructuresConfig;
+use tab_list::TabListConfig;
+use weather::WeatherConfig;
+use worlds::{
+    default_flat_biome, default_flat_layers, default_pvp, default_spawn_chunk_radius,
+    default_spawn_protection, WorldConfig,
+};
+
+pub static ADVANCED_CONFIG: LazyLock<ConfigHandle<AdvancedConfiguration>> =
+    LazyLock::new(|| ConfigHandle::new(AdvancedConfiguration::load()));
 
-pub static ADVANCED_CONFIG: LazyLock<AdvancedConfiguration> =
-    LazyLock::new(AdvancedConfiguration::load);
+pub static BASIC_CONFIG: LazyLock<ConfigHandle<BasicConfiguration>> =
+    LazyLock::new(|| ConfigHandle::new(BasicConfiguration::load()));
 
-pub static BASIC_CONFIG: LazyLock<BasicConfiguration> = LazyLock::new(BasicConfiguration::load);
+pub static MESSAGES: LazyLock<ConfigHandle<MessagesConfiguration>> =
+    LazyLock::new(|| ConfigHandle::new(MessagesConfiguration::load()));
 
 /// The idea is that Pumpkin should very customizable.
 /// You can Enable or Disable Features depending on your needs.
@@ -46,11 +103,34 @@ pub static BASIC_CONFIG: LazyLock<BasicConfiguration> = LazyLock::new(BasicConfi
 #[serde(default)]
 pub struct AdvancedConfiguration {
     pub proxy: ProxyConfig,
+    pub floodgate: FloodgateConfig,
     pub authentication: AuthenticationConfig,
     pub packet_compression: CompressionConfig,
+    pub packet_rate_limit: PacketRateLimitConfig,
+    pub keep_alive: KeepAliveConfig,
+    pub connection_timeout: ConnectionTimeoutConfig,
+    pub send_queue: SendQueueConfig,
+    pub autosave: AutosaveConfig,
+    pub backup: BackupConfig,
+    pub chunk_unloading: ChunkUnloadingConfig,
+    pub chunk_sending: ChunkSendingConfig,
+    pub weather: WeatherConfig,
+    pub explosions: ExplosionConfig,
+    pub collisions: CollisionConfig,
+    pub movement: MovementConfig,
+    pub structures: StructuresConfig,
+    /// Worlds which are loaded in addition to the main world
+    pub worlds: Vec<WorldConfig>,
     pub resource_pack: ResourcePackConfig,
+    pub tab_list: TabListConfig,
     pub commands: CommandsConfig,
+    pub chat: ChatConfig,
     pub rcon: RCONConfig,
+    pub lan_broadcast: LANBroadcastConfig,
+    pub metrics: MetricsConfig,
+    pub cluster: ClusterConfig,
+    pub packet_dump: PacketDumpConfig,
+    pub skins: SkinConfig,
     pub pvp: PVPConfig,
     pub logging: LoggingConfig,
 }
@@ -61,16 +141,32 @@ pub struct BasicConfiguration {
     /// The address to bind the server to.
     #[serde(default = "default_server_address")]
     pub server_address: SocketAddr,
+    /// An optional Unix domain socket path to additionally listen on.
+    /// Useful for proxies running on the same host.
+    #[serde(default)]
+    pub server_unix_socket: Option<PathBuf>,
     /// The seed for world generation.
     #[serde(default = "String::new")]
     pub seed: String,
+    /// The directory the world is stored in, e.g. the folder of an existing vanilla world.
+    #[serde(default = "default_level_name")]
+    pub level_name: PathBuf,
+    /// How the terrain of new chunks is generated.
+    #[serde(default)]
+    pub generator: GeneratorType,
+    /// The layers of the flat generator from bottom to top, like vanilla's superflat presets.
+    #[serde(default = "default_flat_layers")]
+    pub flat_layers: String,
+    /// The biome of the flat generator.
+    #[serde(default = "default_flat_biome")]
+    pub flat_biome: String,
     /// The maximum number of players allowed on the server.
     #[serde_inline_default(10000)]
     pub max_players: u32,
-    /// The maximum view distance for players.
+    /// The maximum view distance for players, chunks are sent this far.
     #[serde_inline_default(10)]
     pub view_distance: u8,
-    /// The maximum simulated view distance.
+    /// How far around players blocks and entities are ticked, at most the view distance.
     #[serde_inline_default(10)]
     pub simulation_distance: u8,
     /// The default game difficulty.
@@ -79,9 +175,21 @@ pub struct BasicConfiguration {
     /// Whether the Nether dimension is enabled.
     #[serde_inline_default(true)]
     pub allow_nether: bool,
+    /// Whether the End dimension is enabled.
+    #[serde_inline_default(true)]
+    pub allow_end: bool,
+    /// Players who are no operator can't change blocks this many blocks around the spawn of the main world.
+    #[serde(default = "default_spawn_protection")]
+    pub spawn_protection: u32,
+    /// Chunks this many chunks around the spawn of the main world always stay loaded.
+    #[serde(default = "default_spawn_chunk_radius")]
+    pub spawn_chunk_radius: u32,
     /// Whether the server is in hardcore mode.
     #[serde_inline_default(false)]
     pub hardcore: bool,
+    /// Whether players can hurt each other in the main world and its dimensions.
+    #[serde(default = "default_pvp")]
+    pub pvp: bool,
     /// Whether online mode is enabled. Requires valid Minecraft accounts.
     #[serde_inline_default(true)]
     pub online_mode: bool,
@@ -97,28 +205,76 @@ pub struct BasicConfiguration {
     /// Whether to remove IPs from logs or not
     #[serde_inline_default(true)]
     pub scrub_ips: bool,
+    /// Whether players transferred from another server are allowed to join
+    #[serde_inline_default(false)]
+    pub accept_transfers: bool,
+    /// Whether players must sign their chat messages. Only works when online mode is enabled.
+    #[serde_inline_default(true)]
+    pub enforce_secure_chat: bool,
+    /// Players who don't move, chat or interact for this many minutes get kicked, 0 disables it
+    #[serde_inline_default(0)]
+    pub player_idle_timeout: u32,
+    /// The server brand shown to clients, e.g. in the debug screen. Defaults to the Pumpkin version
+    #[serde(default)]
+    pub server_brand: Option<String>,
 }
 
 fn default_server_address() -> SocketAddr {
     SocketAddr::new(Ipv4Addr::new(0, 0, 0, 0).into(), 25565)
 }
 
+fn default_level_name() -> PathBuf {
+    PathBuf::from("world")
+}
+
 impl Default for BasicConfiguration {
     fn default() -> Self {
         Self {
             server_address: default_server_address(),
+            server_unix_socket: None,
             seed: "".to_string(),
+            level_name: default_level_name(),
+            generator: GeneratorType::Normal,
+            flat_layers: default_flat_layers(),
+            flat_biome: default_flat_biome(),
             max_players: 100000,
             view_distance: 10,
             simulation_distance: 10,
             default_difficulty: Difficulty::Normal,
             allow_nether: true,
+            allow_end: true,
+            spawn_protection: default_spawn_protection(),
+            spawn_chunk_radius: default_spawn_chunk_radius(),
             hardcore: false,
+            pvp: default_pvp(),
             online_mode: true,
             encryption: true,
             motd: "A Blazing fast Pumpkin Server!".to_string(),
             default_gamemode: GameMode::Survival,
             scrub_ips: true,
+            accept_transfers: false,
+            enforce_secure_chat: true,
+            player_idle_timeout: 0,
+            server_brand: None,
+        }
+    }
+}
+
+impl BasicConfiguration {
+    /// The main world, named after the folder in `level_name`
+    pub fn main_world(&self) -> WorldConfig {
+        WorldConfig {
+            name: self.level_name.file_name().map_or_else(
+                || "world".to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            ),
+            seed: self.seed.clone(),
+            generator: self.generator.clone(),
+            flat_layers: self.flat_layers.clone(),
+            flat_biome: self.flat_biome.clone(),
+            spawn_protection: self.spawn_protection,
+            spawn_chunk_radius: self.spawn_chunk_radius,
+            pvp: self.pvp,
         }
     }
 }
@@ -128,39 +284,71 @@ trait LoadConfiguration {
     where
         Self: Sized + Default + Serialize + DeserializeOwned,
     {
-        let path = Self::get_path();
+        let path = config_path(Self::get_path());
+
+        if path.exists() {
+            return Self::try_load().unwrap_or_else(|err| panic!("{err}"));
+        }
 
-        let config = if path.exists() {
-            let file_content = fs::read_to_string(path)
-                .unwrap_or_else(|_| panic!("Couldn't read configuration file at {:?}", path));
+        let mut content = Self::default();
+
+        if let Err(err) = fs::write(&path, toml::to_string(&content).unwrap()) {
+            warn!(
+                "Couldn't write default config to {:?}. Reason: {}. This is is proberbly caused by an Config update, Just delete the old Config and start Pumpkin again",
+                path, err
+            );
+        }
+
+        content.apply_overrides(overrides());
+        content
+            .validate()
+            .unwrap_or_else(|err| panic!("Invalid configuration: {err}"));
+        content
+    }
+
+    /// Reads and validates the configuration file, without panicking so it can be used for reloads
+    fn try_load() -> Result<Self, String>
+    where
+        Self: Sized + Default + Serialize + DeserializeOwned,
+    {
+        let path = config_path(Self::get_path());
+        let mut config = if path.exists() {
+            let file_content = fs::read_to_string(&path).map_err(|err| {
+                format!(
+                    "Couldn't read configuration file at {:?}. Reason: {}",
+                    path, err
+                )
+            })?;
 
-            toml::from_str(&file_content).unwrap_or_else(|err| {
-                panic!(
+            toml::from_str(&file_content).map_err(|err| {
+                format!(
                     "Couldn't parse config at {:?}. Reason: {}. This is is proberbly caused by an Config update, Just delete the old Config and start Pumpkin again",
                     path,
                     err.message()
                 )
-            })
+            })?
         } else {
-            let content = Self::default();
-
-            if let Err(err) = fs::write(path, toml::to_string(&content).unwrap()) {
-                warn!(
-                    "Couldn't write default config to {:?}. Reason: {}. This is is proberbly caused by an Config update, Just delete the old Config and start Pumpkin again",
-                    path, err
-                );
-            }
-
-            content
+            Self::default()
         };
 
-        config.validate();
+        config.apply_overrides(overrides());
         config
+            .validate()
+            .map_err(|err| format!("Invalid config at {:?}: {}", path, err))?;
+        Ok(config)
     }
 
+    /// Applies the overrides, e.g. from command line flags, on top of the file
+    fn apply_overrides(&mut self, _overrides: &ConfigOverrides) {}
+
     fn get_path() -> &'static Path;
 
-    fn validate(&self);
+    /// Keys which only get applied after a restart, also covers every key nested in them
+    fn restart_required() -> &'static [&'static str] {
+        &[]
+    }
+
+    fn validate(&self) -> Result<(), String>;
 }
 
 impl LoadConfiguration for AdvancedConfiguration {
@@ -168,8 +356,67 @@ impl LoadConfiguration for AdvancedConfiguration {
         Path::new("features.toml")
     }
 
-    fn validate(&self) {
-        self.resource_pack.validate()
+    fn apply_overrides(&mut self, overrides: &ConfigOverrides) {
+        overrides.apply_advanced(self);
+    }
+
+    fn restart_required() -> &'static [&'static str] {
+        &[
+            "floodgate.key_file",
+            "authentication.connect_timeout_ms",
+            "authentication.timeout_ms",
+            "commands.use_console",
+            "rcon",
+            "lan_broadcast",
+            "metrics",
+            "cluster",
+            "packet_dump.capture_file",
+            "skins.cache_dir",
+            "skins.cache_ttl_secs",
+            "collisions.entity_pushing",
+            "logging",
+            "worlds",
+        ]
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        self.resource_pack.validate()?;
+        worlds::validate(&self.worlds)?;
+        self.weather.validate()?;
+        if self.keep_alive.interval == 0 {
+            return Err("Keep alive interval must be at least 1 second".into());
+        }
+        if self.keep_alive.timeout == 0 {
+            return Err("Keep alive timeout must be at least 1 second".into());
+        }
+        if self.autosave.interval == 0 {
+            return Err("Autosave interval must be at least 1 second".into());
+        }
+        if self.autosave.chunks_per_tick == 0 {
+            return Err("Autosave must save at least 1 chunk per tick".into());
+        }
+        if self.rcon.max_failed_logins > 0 && self.rcon.ban_duration == 0 {
+            return Err("RCON ban duration must be at least 1 second".into());
+        }
+        if self.backup.interval == 0 {
+            return Err("Backup interval must be at least 1 second".into());
+        }
+        if !(1..=22).contains(&self.backup.compression_level) {
+            return Err("Backup compression level must be between 1 and 22".into());
+        }
+        if self.chunk_unloading.interval == 0 {
+            return Err("Chunk unloading interval must be at least 1 second".into());
+        }
+        if self.movement.max_speed <= 0.0 || self.movement.max_elytra_speed <= 0.0 {
+            return Err("Players must be allowed to move faster than 0 blocks per tick".into());
+        }
+        if self.chunk_sending.chunks_per_tick == 0 {
+            return Err("At least 1 chunk must be sent per tick".into());
+        }
+        if self.cluster.enabled && self.cluster.secret.is_empty() {
+            return Err("A cluster secret must be set when the cluster is enabled".into());
+        }
+        Ok(())
     }
 }
 
@@ -178,17 +425,132 @@ impl LoadConfiguration for BasicConfiguration {
         Path::new("configuration.toml")
     }
 
-    fn validate(&self) {
-        assert!(self.view_distance >= 2, "View distance must be at least 2");
-        assert!(
-            self.view_distance <= 32,
-            "View distance must be less than 32"
-        );
-        if self.online_mode {
-            assert!(
-                self.encryption,
-                "When Online Mode is enabled, Encryption must be enabled"
-            )
+    fn apply_overrides(&mut self, overrides: &ConfigOverrides) {
+        overrides.apply_basic(self);
+    }
+
+    fn restart_required() -> &'static [&'static str] {
+        &[
+            "server_address",
+            "server_unix_socket",
+            "seed",
+            "level_name",
+            "generator",
+            "flat_layers",
+            "flat_biome",
+            "allow_nether",
+            "allow_end",
+            "spawn_protection",
+            "spawn_chunk_radius",
+            "online_mode",
+            "encryption",
+        ]
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.view_distance < 2 {
+            return Err("View distance must be at least 2".into());
+        }
+        if self.view_distance > 32 {
+            return Err("View distance must be less than 32".into());
+        }
+        if self.simulation_distance < 2 {
+            return Err("Simulation distance must be at least 2".into());
+        }
+        if self.simulation_distance > 32 {
+            return Err("Simulation distance must be less than 32".into());
+        }
+        if self.online_mode && !self.encryption {
+            return Err("When Online Mode is enabled, Encryption must be enabled".into());
+        }
+        Ok(())
+    }
+}
+
+/// What changed when the configuration got reloaded
+#[derive(Default)]
+pub struct ReloadSummary {
+    /// Every key which changed, nested keys are separated by `.`
+    pub changed: Vec<String>,
+    /// The changed keys which only get applied after a restart
+    pub restart_required: Vec<String>,
+}
+
+impl ReloadSummary {
+    fn record<C: LoadConfiguration + Serialize>(&mut self, old: &C, new: &C) {
+        let (Ok(old), Ok(new)) = (toml::Value::try_from(old), toml::Value::try_from(new)) else {
+            return;
+        };
+        let mut changed = Vec::new();
+        changed_keys("", &old, &new, &mut changed);
+        let path = C::get_path();
+        for key in changed {
+            let needs_restart = C::restart_required().iter().any(|restart_key| {
+                key == *restart_key
+                    || key
+                        .strip_prefix(restart_key)
+                        .is_some_and(|rest| rest.starts_with('.'))
+            });
+            if needs_restart {
+                warn!(
+                    "{key} in {:?} changed, this only gets applied after a restart",
+                    path
+                );
+                self.restart_required.push(key.clone());
+            } else {
+                log::info!("{key} in {:?} changed", path);
+            }
+            self.changed.push(key);
         }
     }
 }
+
+fn changed_keys(prefix: &str, old: &toml::Value, new: &toml::Value, out: &mut Vec<String>) {
+    let (toml::Value::Table(old), toml::Value::Table(new)) = (old, new) else {
+        if old != new {
+            out.push(prefix.to_string());
+        }
+        return;
+    };
+    let key_path = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{prefix}.{key}")
+        }
+    };
+    for (key, new_value) in new {
+        match old.get(key) {
+            Some(old_value) => changed_keys(&key_path(key), old_value, new_value, out),
+            None => out.push(key_path(key)),
+        }
+    }
+    // Unset optional keys are not serialized
+    out.extend(
+        old.keys()
+            .filter(|key| !new.contains_key(*key))
+            .map(|key| key_path(key)),
+    );
+}
+
+/// Re-reads all configuration files and applies them.
+/// Nothing gets applied when one of them is invalid
+pub fn reload() -> Result<ReloadSummary, String> {
+    let basic = BasicConfiguration::try_load()?;
+    let advanced = AdvancedConfiguration::try_load()?;
+    let messages = MessagesConfiguration::try_load()?;
+
+    let mut summary = ReloadSummary::default();
+    summary.record(&*BASIC_CONFIG.get(), &basic);
+    summary.record(&*ADVANCED_CONFIG.get(), &advanced);
+    summary.record(&*MESSAGES.get(), &messages);
+
+    BASIC_CONFIG.replace(basic);
+    ADVANCED_CONFIG.replace(advanced);
+    MESSAGES.replace(messages);
+    log::info!(
+        "Reloaded configuration, {} keys changed",
+        summary.changed.len()
+    );
+    Ok(summary)
+}
@@ -0,0 +1,118 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+use crate::LoadConfiguration;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Messages shown to players when they get kicked or disconnected.
+///
+/// `&` followed by a color or formatting code is turned into a color code, e.g. `&c` for red
+pub struct MessagesConfiguration {
+    /// The client did not answer keep alives in time
+    #[serde_inline_default("Timed out".to_string())]
+    pub timed_out: String,
+    /// The player was idle for longer than `player_idle_timeout`
+    #[serde_inline_default("You have been idle for too long!".to_string())]
+    pub idle_timeout: String,
+    /// The Server reached `max_players`, {max_players} is replaced
+    #[serde_inline_default("The server is full!".to_string())]
+    pub server_full: String,
+    /// The Server shuts down
+    #[serde_inline_default("Server closed".to_string())]
+    pub server_closed: String,
+    /// The player is not on the whitelist
+    #[serde_inline_default("You are not whitelisted on this server!".to_string())]
+    pub not_whitelisted: String,
+    /// The player is banned, {reason} is replaced
+    #[serde_inline_default("You are banned from this server.\nReason: {reason}".to_string())]
+    pub banned: String,
+    /// The client uses an older protocol, {version} and {protocol} are replaced
+    #[serde_inline_default("Client outdated ({client_protocol}), Server uses Minecraft {version}, Protocol {protocol}".to_string())]
+    pub outdated_client: String,
+    /// The client uses a newer protocol, {version} and {protocol} are replaced
+    #[serde_inline_default("Server outdated, Server uses Minecraft {version}, Protocol {protocol}".to_string())]
+    pub outdated_server: String,
+    /// The player was transferred here, but `accept_transfers` is disabled
+    #[serde_inline_default("Transfers are disabled on this Server".to_string())]
+    pub transfers_disabled: String,
+    /// The player declined a forced resource pack
+    #[serde_inline_default("You must accept the resource pack to play on this Server".to_string())]
+    pub resource_pack_declined: String,
+    /// The client exceeded the packet rate limit
+    #[serde_inline_default("Sending too many packets".to_string())]
+    pub too_many_packets: String,
+    /// The player moved too fast, flew or went through blocks too often
+    #[serde_inline_default("Illegal movement".to_string())]
+    pub illegal_movement: String,
+    /// The player has a name which is not allowed
+    #[serde_inline_default("Invalid characters in username".to_string())]
+    pub invalid_username: String,
+    /// The player's account is flagged by Mojang
+    #[serde_inline_default("Your account can't join".to_string())]
+    pub account_not_allowed: String,
+    /// Velocity forwarding is enabled, but the player connected directly
+    #[serde_inline_default("This server requires you to connect with Velocity.".to_string())]
+    pub velocity_required: String,
+}
+
+impl Default for MessagesConfiguration {
+    fn default() -> Self {
+        Self {
+            timed_out: "Timed out".to_string(),
+            idle_timeout: "You have been idle for too long!".to_string(),
+            server_full: "The server is full!".to_string(),
+            server_closed: "Server closed".to_string(),
+            not_whitelisted: "You are not whitelisted on this server!".to_string(),
+            banned: "You are banned from this server.\nReason: {reason}".to_string(),
+            outdated_client:
+                "Client outdated ({client_protocol}), Server uses Minecraft {version}, Protocol {protocol}"
+                    .to_string(),
+            outdated_server: "Server outdated, Server uses Minecraft {version}, Protocol {protocol}"
+                .to_string(),
+            transfers_disabled: "Transfers are disabled on this Server".to_string(),
+            resource_pack_declined: "You must accept the resource pack to play on this Server"
+                .to_string(),
+            too_many_packets: "Sending too many packets".to_string(),
+            illegal_movement: "Illegal movement".to_string(),
+            invalid_username: "Invalid characters in username".to_string(),
+            account_not_allowed: "Your account can't join".to_string(),
+            velocity_required: "This server requires you to connect with Velocity.".to_string(),
+        }
+    }
+}
+
+impl LoadConfiguration for MessagesConfiguration {
+    fn get_path() -> &'static Path {
+        Path::new("messages.toml")
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Replaces every `{key}` in `template` with its value and turns `&` codes into color codes
+pub fn format_message(template: &str, placeholders: &[(&str, &str)]) -> String {
+    let mut message = template.to_string();
+    for (key, value) in placeholders {
+        message = message.replace(&format!("{{{key}}}"), value);
+    }
+
+    let mut formatted = String::with_capacity(message.len());
+    let mut chars = message.chars().peekable();
+    while let Some(c) = chars.next() {
+        match chars.peek() {
+            Some(&code) if c == '&' && is_format_code(code) => formatted.push('§'),
+            _ => formatted.push(c),
+        }
+    }
+    formatted
+}
+
+const fn is_format_code(code: char) -> bool {
+    matches!(code, '0'..='9' | 'a'..='f' | 'A'..='F' | 'k'..='o' | 'K'..='O' | 'r' | 'R')
+}
@@ -0,0 +1,30 @@
+use std::net::{Ipv4Addr, SocketAddr};
+
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+/// Exports Server metrics in the Prometheus text format
+pub struct MetricsConfig {
+    /// Whether the metrics endpoint is enabled
+    #[serde_inline_default(false)]
+    pub enabled: bool,
+    /// The network address and port the metrics endpoint listens on
+    #[serde_inline_default(default_metrics_address())]
+    pub address: SocketAddr,
+}
+
+fn default_metrics_address() -> SocketAddr {
+    SocketAddr::new(Ipv4Addr::new(0, 0, 0, 0).into(), 9225)
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: default_metrics_address(),
+        }
+    }
+}
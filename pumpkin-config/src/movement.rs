@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// How the Server checks the moves players send, their client decides where they go
+pub struct MovementConfig {
+    /// Whether moves which are too fast, fly or go through blocks are put back
+    #[serde_inline_default(true)]
+    pub validate: bool,
+    /// How many blocks players may move per tick, like in vanilla
+    #[serde_inline_default(10.0)]
+    pub max_speed: f64,
+    /// How many blocks gliding players may move per tick, like in vanilla
+    #[serde_inline_default(17.3)]
+    pub max_elytra_speed: f64,
+    /// Players who lag may catch up on at most this many ticks with one move
+    #[serde_inline_default(20)]
+    pub max_lag_ticks: u32,
+    /// Ticks players may float in the air without being allowed to fly, like in vanilla
+    #[serde_inline_default(80)]
+    pub max_floating_ticks: u32,
+    /// Players are kicked once they were put back more often than this, one is forgiven every
+    /// second. 0 never kicks
+    #[serde_inline_default(10)]
+    pub max_violations: u32,
+}
+
+impl Default for MovementConfig {
+    fn default() -> Self {
+        Self {
+            validate: true,
+            max_speed: 10.0,
+            max_elytra_speed: 17.3,
+            max_lag_ticks: 20,
+            max_floating_ticks: 80,
+            max_violations: 10,
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use crate::{AdvancedConfiguration, BasicConfiguration};
+
+static OVERRIDES: OnceLock<ConfigOverrides> = OnceLock::new();
+
+/// Values which take precedence over the configuration files, e.g. from command line flags.
+/// They also apply when the configuration gets reloaded
+#[derive(Default, Clone)]
+pub struct ConfigOverrides {
+    /// The directory the configuration files are in, the working directory by default
+    pub config_dir: Option<PathBuf>,
+    pub port: Option<u16>,
+    pub level_name: Option<PathBuf>,
+    pub online_mode: Option<bool>,
+    pub max_players: Option<u32>,
+    pub motd: Option<String>,
+    pub use_console: Option<bool>,
+}
+
+impl ConfigOverrides {
+    pub(crate) fn apply_basic(&self, config: &mut BasicConfiguration) {
+        if let Some(port) = self.port {
+            config.server_address = SocketAddr::new(config.server_address.ip(), port);
+        }
+        if let Some(level_name) = &self.level_name {
+            config.level_name.clone_from(level_name);
+        }
+        if let Some(online_mode) = self.online_mode {
+            config.online_mode = online_mode;
+        }
+        if let Some(max_players) = self.max_players {
+            config.max_players = max_players;
+        }
+        if let Some(motd) = &self.motd {
+            config.motd.clone_from(motd);
+        }
+    }
+
+    pub(crate) fn apply_advanced(&self, config: &mut AdvancedConfiguration) {
+        if let Some(use_console) = self.use_console {
+            config.commands.use_console = use_console;
+        }
+    }
+}
+
+/// Sets the overrides, must be called before any configuration is accessed
+pub fn set_overrides(overrides: ConfigOverrides) {
+    if OVERRIDES.set(overrides).is_err() {
+        log::warn!("Configuration overrides were already set, ignoring the new ones");
+    }
+}
+
+pub(crate) fn overrides() -> &'static ConfigOverrides {
+    OVERRIDES.get_or_init(ConfigOverrides::default)
+}
+
+/// The path of a configuration file, relative to the configuration directory
+pub(crate) fn config_path(file: &Path) -> PathBuf {
+    match &overrides().config_dir {
+        Some(dir) => dir.join(file),
+        None => file.to_path_buf(),
+    }
+}
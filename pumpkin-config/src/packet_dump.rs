@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+/// Logs every packet sent and received, useful when debugging protocol issues
+pub struct PacketDumpConfig {
+    /// Whether packets are dumped
+    #[serde_inline_default(false)]
+    pub enabled: bool,
+    /// Whether the packet data is logged as hex dump, otherwise only state, id and length are logged
+    #[serde_inline_default(false)]
+    pub hex_dump: bool,
+    /// When set, packets are written to this binary capture file instead of the log
+    pub capture_file: Option<String>,
+    /// Only dump packets with these ids, every packet is dumped when empty
+    #[serde_inline_default(Vec::new())]
+    pub packet_ids: Vec<i32>,
+}
+
+impl Default for PacketDumpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hex_dump: false,
+            capture_file: None,
+            packet_ids: Vec::new(),
+        }
+    }
+}
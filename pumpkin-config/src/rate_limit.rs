@@ -0,0 +1,109 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Limits how many packets and bytes a single connection may send
+pub struct PacketRateLimitConfig {
+    /// Whether inbound packets are rate limited
+    #[serde_inline_default(false)]
+    pub enabled: bool,
+    /// What happens when a connection exceeds its limits
+    #[serde_inline_default(RateLimitAction::Kick)]
+    pub action: RateLimitAction,
+    #[serde(deserialize_with = "handshake")]
+    pub handshake: RateLimit,
+    #[serde(deserialize_with = "status")]
+    pub status: RateLimit,
+    #[serde(deserialize_with = "login")]
+    pub login: RateLimit,
+    #[serde(deserialize_with = "config")]
+    pub config: RateLimit,
+    #[serde(deserialize_with = "play")]
+    pub play: RateLimit,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RateLimitAction {
+    /// Kick the connection
+    Kick,
+    /// Stop reading from the connection until the current second is over
+    Throttle,
+}
+
+#[derive(Serialize, Clone, Copy)]
+/// The limits for a single connection state. 0 means unlimited
+pub struct RateLimit {
+    pub packets_per_second: u32,
+    pub bytes_per_second: u32,
+}
+
+impl RateLimit {
+    const fn new(packets_per_second: u32, bytes_per_second: u32) -> Self {
+        Self {
+            packets_per_second,
+            bytes_per_second,
+        }
+    }
+}
+
+const HANDSHAKE: RateLimit = RateLimit::new(10, 4096);
+const STATUS: RateLimit = RateLimit::new(10, 4096);
+const LOGIN: RateLimit = RateLimit::new(20, 64 * 1024);
+const CONFIG: RateLimit = RateLimit::new(50, 64 * 1024);
+const PLAY: RateLimit = RateLimit::new(500, 1024 * 1024);
+
+/// Reads the limits of a state, a field left out keeps the default of that state
+fn with_default<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    default: RateLimit,
+) -> Result<RateLimit, D::Error> {
+    #[derive(Deserialize)]
+    struct PartialRateLimit {
+        packets_per_second: Option<u32>,
+        bytes_per_second: Option<u32>,
+    }
+
+    let limit = PartialRateLimit::deserialize(deserializer)?;
+    Ok(RateLimit {
+        packets_per_second: limit
+            .packets_per_second
+            .unwrap_or(default.packets_per_second),
+        bytes_per_second: limit.bytes_per_second.unwrap_or(default.bytes_per_second),
+    })
+}
+
+fn handshake<'de, D: Deserializer<'de>>(deserializer: D) -> Result<RateLimit, D::Error> {
+    with_default(deserializer, HANDSHAKE)
+}
+
+fn status<'de, D: Deserializer<'de>>(deserializer: D) -> Result<RateLimit, D::Error> {
+    with_default(deserializer, STATUS)
+}
+
+fn login<'de, D: Deserializer<'de>>(deserializer: D) -> Result<RateLimit, D::Error> {
+    with_default(deserializer, LOGIN)
+}
+
+fn config<'de, D: Deserializer<'de>>(deserializer: D) -> Result<RateLimit, D::Error> {
+    with_default(deserializer, CONFIG)
+}
+
+fn play<'de, D: Deserializer<'de>>(deserializer: D) -> Result<RateLimit, D::Error> {
+    with_default(deserializer, PLAY)
+}
+
+impl Default for PacketRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            action: RateLimitAction::Kick,
+            handshake: HANDSHAKE,
+            status: STATUS,
+            login: LOGIN,
+            config: CONFIG,
+            play: PLAY,
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::net::{Ipv4Addr, SocketAddr};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
 use serde::{Deserialize, Serialize};
 use serde_inline_default::serde_inline_default;
@@ -19,6 +19,16 @@ pub struct RCONConfig {
     /// If 0 there is no limit
     #[serde(default)]
     pub max_connections: u32,
+    /// How many wrong passwords an address may send before it gets banned.
+    /// If 0 there is no limit
+    #[serde_inline_default(5)]
+    pub max_failed_logins: u32,
+    /// How many seconds an address stays banned, failed logins older than this are forgotten
+    #[serde_inline_default(300)]
+    pub ban_duration: u64,
+    /// The addresses which may connect, if empty every address may
+    #[serde(default)]
+    pub allowed_addresses: Vec<IpAddr>,
     /// RCON Logging
     pub logging: RCONLogging,
 }
@@ -62,6 +72,9 @@ impl Default for RCONConfig {
             address: default_rcon_address(),
             password: "".to_string(),
             max_connections: 0,
+            max_failed_logins: 5,
+            ban_duration: 300,
+            allowed_addresses: Vec::new(),
             logging: Default::default(),
         }
     }
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Limits how much data may wait to be written to a single connection
+pub struct SendQueueConfig {
+    /// Connections with more bytes waiting than this get closed, they stopped reading.
+    /// 0 means unlimited
+    #[serde_inline_default(64 * 1024 * 1024)]
+    pub max_bytes: u64,
+}
+
+impl Default for SendQueueConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
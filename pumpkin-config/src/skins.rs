@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Caches player skins and capes on disk
+pub struct SkinConfig {
+    /// The directory cached skins are stored in
+    #[serde_inline_default("cache/skins".to_string())]
+    pub cache_dir: String,
+    /// How long a cached skin is used before it gets fetched again, in seconds
+    #[serde_inline_default(86400)]
+    pub cache_ttl_secs: u64,
+    /// Fetch the skin of the premium account with the same name for players in offline mode
+    #[serde_inline_default(false)]
+    pub offline_skins: bool,
+    /// Resolves a username to its UUID
+    #[serde_inline_default("https://api.mojang.com/users/profiles/minecraft/{username}".to_string())]
+    pub profile_lookup_url: String,
+    /// Returns the signed profile properties of a UUID
+    #[serde_inline_default("https://sessionserver.mojang.com/session/minecraft/profile/{uuid}?unsigned=false".to_string())]
+    pub profile_url: String,
+}
+
+impl Default for SkinConfig {
+    fn default() -> Self {
+        Self {
+            cache_dir: "cache/skins".to_string(),
+            cache_ttl_secs: 86400,
+            offline_skins: false,
+            profile_lookup_url: "https://api.mojang.com/users/profiles/minecraft/{username}"
+                .to_string(),
+            profile_url:
+                "https://sessionserver.mojang.com/session/minecraft/profile/{uuid}?unsigned=false"
+                    .to_string(),
+        }
+    }
+}
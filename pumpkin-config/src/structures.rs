@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// Structures generated into new chunks of normal worlds
+pub struct StructuresConfig {
+    /// Small underground rooms with a spawner and a chest
+    #[serde_inline_default(true)]
+    pub dungeons: bool,
+    /// The crumbled walls of small stone brick houses
+    #[serde_inline_default(true)]
+    pub surface_ruins: bool,
+}
+
+impl Default for StructuresConfig {
+    fn default() -> Self {
+        Self {
+            dungeons: true,
+            surface_ruins: true,
+        }
+    }
+}
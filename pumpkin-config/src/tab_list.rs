@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// The text shown above and below the player list.
+///
+/// Supports the placeholders `{player}`, `{online}`, `{max_players}`, `{motd}` and
+/// `{stat:<criterion>}` for the statistics of the player, written like scoreboard criteria,
+/// e.g. `{stat:minecraft.custom:minecraft.deaths}`
+pub struct TabListConfig {
+    /// Leave blank for none
+    #[serde_inline_default("".to_string())]
+    pub header: String,
+    /// Leave blank for none
+    #[serde_inline_default("".to_string())]
+    pub footer: String,
+}
+
+impl Default for TabListConfig {
+    fn default() -> Self {
+        Self {
+            header: "".to_string(),
+            footer: "".to_string(),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use serde_inline_default::serde_inline_default;
+
+#[serde_inline_default]
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+/// How long the weather lasts when it changes by itself, all durations are in seconds.
+/// Every time the weather changes a random duration between the minimum and the maximum is picked
+pub struct WeatherConfig {
+    /// How long it stays clear until it starts raining or thundering
+    #[serde_inline_default(600)]
+    pub min_clear_duration: u32,
+    #[serde_inline_default(9000)]
+    pub max_clear_duration: u32,
+    #[serde_inline_default(600)]
+    pub min_rain_duration: u32,
+    #[serde_inline_default(1200)]
+    pub max_rain_duration: u32,
+    /// Thunder only shows while it rains
+    #[serde_inline_default(180)]
+    pub min_thunder_duration: u32,
+    #[serde_inline_default(780)]
+    pub max_thunder_duration: u32,
+}
+
+impl WeatherConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        let durations = [
+            ("clear", self.min_clear_duration, self.max_clear_duration),
+            ("rain", self.min_rain_duration, self.max_rain_duration),
+            (
+                "thunder",
+                self.min_thunder_duration,
+                self.max_thunder_duration,
+            ),
+        ];
+        for (weather, min, max) in durations {
+            if min == 0 {
+                return Err(format!(
+                    "Minimum {weather} duration must be at least 1 second"
+                ));
+            }
+            if min > max {
+                return Err(format!(
+                    "Minimum {weather} duration must not be longer than the maximum"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for WeatherConfig {
+    fn default() -> Self {
+        Self {
+            min_clear_duration: 600,
+            max_clear_duration: 9000,
+            min_rain_duration: 600,
+            max_rain_duration: 1200,
+            min_thunder_duration: 180,
+            max_thunder_duration: 780,
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use pumpkin_core::GeneratorType;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone)]
+/// A world which is loaded in addition to the main world
+pub struct WorldConfig {
+    /// Players use this name to switch to the world, it is also the folder the world is stored in
+    pub name: String,
+    /// Empty uses the seed of the main world
+    #[serde(default)]
+    pub seed: String,
+    #[serde(default)]
+    pub generator: GeneratorType,
+    #[serde(default = "default_flat_layers")]
+    pub flat_layers: String,
+    #[serde(default = "default_flat_biome")]
+    pub flat_biome: String,
+    /// Players who are no operator can't change blocks this many blocks around the spawn, 0 disables it
+    #[serde(default = "default_spawn_protection")]
+    pub spawn_protection: u32,
+    /// Chunks this many chunks around the spawn always stay loaded
+    #[serde(default = "default_spawn_chunk_radius")]
+    pub spawn_chunk_radius: u32,
+    /// Whether players can hurt each other in this world, `pvp.enabled` turns it off everywhere
+    #[serde(default = "default_pvp")]
+    pub pvp: bool,
+}
+
+pub(crate) fn default_flat_layers() -> String {
+    "minecraft:bedrock,2*minecraft:dirt,minecraft:grass_block".to_string()
+}
+
+pub(crate) fn default_flat_biome() -> String {
+    "minecraft:plains".to_string()
+}
+
+pub(crate) fn default_spawn_protection() -> u32 {
+    16
+}
+
+pub(crate) fn default_spawn_chunk_radius() -> u32 {
+    2
+}
+
+pub(crate) fn default_pvp() -> bool {
+    true
+}
+
+/// Checks the names of the additional worlds, they become part of a resource location
+pub(crate) fn validate(worlds: &[WorldConfig]) -> Result<(), String> {
+    for (i, world) in worlds.iter().enumerate() {
+        if world.name.is_empty()
+            || !world.name.chars().all(|c| {
+                c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '-' | '.')
+            })
+        {
+            return Err(format!(
+                "World name \"{}\" may only contain a-z, 0-9, _, - and .",
+                world.name
+            ));
+        }
+        if worlds[..i].iter().any(|other| other.name == world.name) {
+            return Err(format!("World name \"{}\" is used twice", world.name));
+        }
+    }
+    Ok(())
+}
@@ -15,6 +15,20 @@ pub enum Difficulty {
     Hard,
 }
 
+/// How the terrain of new chunks is generated
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GeneratorType {
+    /// Oceans, hills, mountains and caves, like vanilla
+    #[default]
+    Normal,
+    /// Configurable layers of blocks, like vanilla's superflat
+    Flat,
+    /// The name of a generator provided by a plugin
+    #[serde(untagged)]
+    Custom(String),
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ProfileAction {
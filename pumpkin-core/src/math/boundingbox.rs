@@ -33,6 +33,40 @@ impl BoundingBox {
         }
     }
 
+    /// The box grown by the amounts on both sides of each axis
+    pub fn expand(&self, x: f64, y: f64, z: f64) -> Self {
+        Self {
+            min_x: self.min_x - x,
+            min_y: self.min_y - y,
+            min_z: self.min_z - z,
+            max_x: self.max_x + x,
+            max_y: self.max_y + y,
+            max_z: self.max_z + z,
+        }
+    }
+
+    /// The box moved by `delta`
+    pub fn offset(&self, delta: &Vector3<f64>) -> Self {
+        Self {
+            min_x: self.min_x + delta.x,
+            min_y: self.min_y + delta.y,
+            min_z: self.min_z + delta.z,
+            max_x: self.max_x + delta.x,
+            max_y: self.max_y + delta.y,
+            max_z: self.max_z + delta.z,
+        }
+    }
+
+    /// Whether both boxes overlap, boxes which only touch don't
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.min_x < other.max_x
+            && self.max_x > other.min_x
+            && self.min_y < other.max_y
+            && self.max_y > other.min_y
+            && self.min_z < other.max_z
+            && self.max_z > other.min_z
+    }
+
     pub fn squared_magnitude(&self, pos: Vector3<f64>) -> f64 {
         let d = f64::max(f64::max(self.min_x - pos.x, pos.x - self.max_x), 0.0);
         let e = f64::max(f64::max(self.min_y - pos.y, pos.y - self.max_y), 0.0);
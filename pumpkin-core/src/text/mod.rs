@@ -40,18 +40,22 @@ impl<'a> TextComponent<'a> {
         }
     }
 
+    /// Text the client translates into its language, `with` fills in the placeholders of the
+    /// translation
+    pub fn translate(key: impl Into<Cow<'a, str>>, with: Vec<TextComponent<'a>>) -> Self {
+        Self {
+            content: TextContent::Translate {
+                translate: key.into(),
+                with: with.into_iter().map(|text| Text(Box::new(text))).collect(),
+            },
+            style: Style::default(),
+        }
+    }
+
     pub fn to_pretty_console(self) -> String {
         let style = self.style;
         let color = style.color;
-        let mut text = match self.content {
-            TextContent::Text { text } => text.into_owned(),
-            TextContent::Translate { translate, with: _ } => translate.into_owned(),
-            TextContent::EntityNames {
-                selector,
-                separator: _,
-            } => selector.into_owned(),
-            TextContent::Keybind { keybind } => keybind.into_owned(),
-        };
+        let mut text = self.content.into_plain();
         if let Some(color) = color {
             text = color.console_color(&text).to_string();
         }
@@ -69,6 +73,11 @@ impl<'a> TextComponent<'a> {
         }
         text
     }
+
+    /// The text without any colors or styles, e.g. for RCON clients
+    pub fn to_plain(self) -> String {
+        self.content.into_plain()
+    }
 }
 
 impl<'a> serde::Serialize for TextComponent<'a> {
@@ -180,3 +189,17 @@ pub enum TextContent<'a> {
     /// https://minecraft.fandom.com/wiki/Controls#Configurable_controls
     Keybind { keybind: Cow<'a, str> },
 }
+
+impl TextContent<'_> {
+    fn into_plain(self) -> String {
+        match self {
+            Self::Text { text } => text.into_owned(),
+            Self::Translate { translate, with: _ } => translate.into_owned(),
+            Self::EntityNames {
+                selector,
+                separator: _,
+            } => selector.into_owned(),
+            Self::Keybind { keybind } => keybind.into_owned(),
+        }
+    }
+}
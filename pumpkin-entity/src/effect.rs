@@ -0,0 +1,149 @@
+/// The status effects entities can have, e.g. from potions or food. The values are the protocol
+/// ids of the `minecraft:mob_effect` registry
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum StatusEffect {
+    Speed = 0,
+    Slowness,
+    Haste,
+    MiningFatigue,
+    Strength,
+    InstantHealth,
+    InstantDamage,
+    JumpBoost,
+    Nausea,
+    Regeneration,
+    Resistance,
+    FireResistance,
+    WaterBreathing,
+    Invisibility,
+    Blindness,
+    NightVision,
+    Hunger,
+    Weakness,
+    Poison,
+    Wither,
+    HealthBoost,
+    Absorption,
+    Saturation,
+    Glowing,
+    Levitation,
+    Luck,
+    Unluck,
+    SlowFalling,
+    ConduitPower,
+    DolphinsGrace,
+    BadOmen,
+    HeroOfTheVillage,
+    Darkness,
+    TrialOmen,
+    RaidOmen,
+    WindCharged,
+    Weaving,
+    Oozing,
+    Infested,
+}
+
+impl StatusEffect {
+    /// All effects, ordered by their protocol id
+    pub const ALL: [StatusEffect; 39] = [
+        Self::Speed,
+        Self::Slowness,
+        Self::Haste,
+        Self::MiningFatigue,
+        Self::Strength,
+        Self::InstantHealth,
+        Self::InstantDamage,
+        Self::JumpBoost,
+        Self::Nausea,
+        Self::Regeneration,
+        Self::Resistance,
+        Self::FireResistance,
+        Self::WaterBreathing,
+        Self::Invisibility,
+        Self::Blindness,
+        Self::NightVision,
+        Self::Hunger,
+        Self::Weakness,
+        Self::Poison,
+        Self::Wither,
+        Self::HealthBoost,
+        Self::Absorption,
+        Self::Saturation,
+        Self::Glowing,
+        Self::Levitation,
+        Self::Luck,
+        Self::Unluck,
+        Self::SlowFalling,
+        Self::ConduitPower,
+        Self::DolphinsGrace,
+        Self::BadOmen,
+        Self::HeroOfTheVillage,
+        Self::Darkness,
+        Self::TrialOmen,
+        Self::RaidOmen,
+        Self::WindCharged,
+        Self::Weaving,
+        Self::Oozing,
+        Self::Infested,
+    ];
+
+    /// The effect with a registry id like `minecraft:speed`, the namespace can be left out
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        Self::ALL.into_iter().find(|effect| effect.name() == name)
+    }
+
+    /// The registry id of the effect, without the namespace
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Speed => "speed",
+            Self::Slowness => "slowness",
+            Self::Haste => "haste",
+            Self::MiningFatigue => "mining_fatigue",
+            Self::Strength => "strength",
+            Self::InstantHealth => "instant_health",
+            Self::InstantDamage => "instant_damage",
+            Self::JumpBoost => "jump_boost",
+            Self::Nausea => "nausea",
+            Self::Regeneration => "regeneration",
+            Self::Resistance => "resistance",
+            Self::FireResistance => "fire_resistance",
+            Self::WaterBreathing => "water_breathing",
+            Self::Invisibility => "invisibility",
+            Self::Blindness => "blindness",
+            Self::NightVision => "night_vision",
+            Self::Hunger => "hunger",
+            Self::Weakness => "weakness",
+            Self::Poison => "poison",
+            Self::Wither => "wither",
+            Self::HealthBoost => "health_boost",
+            Self::Absorption => "absorption",
+            Self::Saturation => "saturation",
+            Self::Glowing => "glowing",
+            Self::Levitation => "levitation",
+            Self::Luck => "luck",
+            Self::Unluck => "unluck",
+            Self::SlowFalling => "slow_falling",
+            Self::ConduitPower => "conduit_power",
+            Self::DolphinsGrace => "dolphins_grace",
+            Self::BadOmen => "bad_omen",
+            Self::HeroOfTheVillage => "hero_of_the_village",
+            Self::Darkness => "darkness",
+            Self::TrialOmen => "trial_omen",
+            Self::RaidOmen => "raid_omen",
+            Self::WindCharged => "wind_charged",
+            Self::Weaving => "weaving",
+            Self::Oozing => "oozing",
+            Self::Infested => "infested",
+        }
+    }
+
+    /// Whether the effect does everything at once instead of lasting for a while
+    pub const fn is_instant(&self) -> bool {
+        matches!(
+            self,
+            Self::InstantHealth | Self::InstantDamage | Self::Saturation
+        )
+    }
+}
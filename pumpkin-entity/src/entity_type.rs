@@ -1,7 +1,85 @@
 // TODO
-#[derive(Clone)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(i32)]
 pub enum EntityType {
+    Arrow = 4,
+    Boat = 10,
+    Chicken = 19,
+    Cow = 22,
+    Creeper = 23,
+    EnderPearl = 32,
+    Enderman = 33,
+    ExperienceOrb = 38,
+    FallingBlock = 40,
+    Husk = 54,
+    Item = 58,
+    Minecart = 69,
+    Pig = 77,
+    Rabbit = 84,
+    Sheep = 87,
+    Skeleton = 91,
+    Snowball = 97,
+    Spider = 100,
+    Stray = 102,
+    Tnt = 106,
+    Witch = 118,
+    Wolf = 122,
     Zombie = 124,
+    ZombieVillager = 126,
     Player = 128,
 }
+
+impl EntityType {
+    /// The width and the height of the entity's bounding box, like in vanilla
+    pub const fn dimensions(&self) -> (f64, f64) {
+        match self {
+            Self::Arrow => (0.5, 0.5),
+            Self::Boat => (1.375, 0.5625),
+            Self::Chicken => (0.4, 0.7),
+            Self::Cow => (0.9, 1.4),
+            Self::Creeper => (0.6, 1.7),
+            Self::Enderman => (0.6, 2.9),
+            Self::ExperienceOrb => (0.5, 0.5),
+            Self::FallingBlock | Self::Tnt => (0.98, 0.98),
+            Self::EnderPearl | Self::Item | Self::Snowball => (0.25, 0.25),
+            Self::Husk | Self::Zombie | Self::ZombieVillager | Self::Witch => (0.6, 1.95),
+            Self::Minecart => (0.98, 0.7),
+            Self::Pig => (0.9, 0.9),
+            Self::Rabbit => (0.4, 0.5),
+            Self::Sheep => (0.9, 1.3),
+            Self::Skeleton | Self::Stray => (0.6, 1.99),
+            Self::Spider => (1.4, 0.9),
+            Self::Wolf => (0.6, 0.85),
+            Self::Player => (0.6, 1.8),
+        }
+    }
+
+    /// How far away players see entities of the type, in chunks, like in vanilla. Players with
+    /// a smaller view distance only see them that far
+    pub const fn tracking_range(&self) -> i32 {
+        match self {
+            Self::Player => 32,
+            Self::Chicken
+            | Self::Cow
+            | Self::Pig
+            | Self::Rabbit
+            | Self::Sheep
+            | Self::Wolf
+            | Self::FallingBlock
+            | Self::Tnt
+            | Self::Boat => 10,
+            Self::Creeper
+            | Self::Enderman
+            | Self::Husk
+            | Self::Skeleton
+            | Self::Spider
+            | Self::Stray
+            | Self::Witch
+            | Self::Zombie
+            | Self::ZombieVillager
+            | Self::Minecart => 8,
+            Self::ExperienceOrb | Self::Item => 6,
+            Self::Arrow | Self::EnderPearl | Self::Snowball => 4,
+        }
+    }
+}
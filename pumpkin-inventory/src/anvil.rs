@@ -0,0 +1,44 @@
+use crate::{Container, WindowType};
+use pumpkin_world::item::ItemStack;
+
+/// The item an anvil works on, the material or item it is combined with and the result. Every
+/// player uses their own window, its items go back into their inventory once they close it
+pub struct Anvil([Option<ItemStack>; 3]);
+
+impl Anvil {
+    pub const LEFT_SLOT: usize = 0;
+    pub const RIGHT_SLOT: usize = 1;
+    pub const RESULT_SLOT: usize = 2;
+
+    pub fn new() -> Self {
+        Self([None; 3])
+    }
+}
+
+impl Default for Anvil {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Container for Anvil {
+    fn window_type(&self) -> &'static WindowType {
+        &WindowType::Anvil
+    }
+
+    fn window_name(&self) -> &'static str {
+        "Repair & Name"
+    }
+
+    fn all_slots(&mut self) -> Vec<&mut Option<ItemStack>> {
+        self.0.iter_mut().collect()
+    }
+
+    fn all_slots_ref(&self) -> Vec<Option<&ItemStack>> {
+        self.0.iter().map(|slot| slot.as_ref()).collect()
+    }
+
+    fn accepts(&self, slot: usize, _item: &ItemStack) -> bool {
+        slot != Self::RESULT_SLOT
+    }
+}
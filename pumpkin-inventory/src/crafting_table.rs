@@ -0,0 +1,40 @@
+use crate::{Container, WindowType};
+use pumpkin_world::item::ItemStack;
+
+/// The 3x3 crafting grid of a crafting table with the result in slot 0. Every player crafts in
+/// their own grid, its items go back into their inventory once they close it
+pub struct CraftingTable([Option<ItemStack>; 10]);
+
+impl CraftingTable {
+    pub fn new() -> Self {
+        Self([None; 10])
+    }
+}
+
+impl Default for CraftingTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Container for CraftingTable {
+    fn window_type(&self) -> &'static WindowType {
+        &WindowType::CraftingTable
+    }
+
+    fn window_name(&self) -> &'static str {
+        "Crafting"
+    }
+
+    fn all_slots(&mut self) -> Vec<&mut Option<ItemStack>> {
+        self.0.iter_mut().collect()
+    }
+
+    fn all_slots_ref(&self) -> Vec<Option<&ItemStack>> {
+        self.0.iter().map(|slot| slot.as_ref()).collect()
+    }
+
+    fn crafting_grid_width(&self) -> Option<usize> {
+        Some(3)
+    }
+}
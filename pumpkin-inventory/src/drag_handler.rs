@@ -73,6 +73,9 @@ impl DragHandler {
             Err(InventoryError::MultiplePlayersDragging)?
         }
         let mut slots = container.all_slots();
+        if drag.slots.iter().any(|slot| *slot >= slots.len()) {
+            Err(InventoryError::InvalidSlot)?
+        }
         let slots_cloned = slots
             .iter()
             .map(|stack| stack.map(|item| item.to_owned()))
@@ -80,56 +83,59 @@ impl DragHandler {
         let Some(carried_item) = maybe_carried_item else {
             return Ok(());
         };
+        let max_stack_size = carried_item.max_stack_size();
         match drag.drag_type {
             // This is only valid in Creative GameMode.
             // Checked in any function that uses this function.
             MouseDragType::Middle => {
+                let full_stack = carried_item.with_count(max_stack_size);
                 for slot in &drag.slots {
-                    *slots[*slot] = *maybe_carried_item;
+                    *slots[*slot] = Some(full_stack);
                 }
             }
             MouseDragType::Right => {
-                let mut single_item = *carried_item;
-                single_item.item_count = 1;
-
-                let changing_slots =
-                    drag.possibly_changing_slots(&slots_cloned, carried_item.item_id);
-                changing_slots.for_each(|slot| {
-                    if carried_item.item_count != 0 {
-                        carried_item.item_count -= 1;
-                        if let Some(stack) = &mut slots[slot] {
-                            // TODO: Check for stack max here
-                            if stack.item_count + 1 < 64 {
-                                stack.item_count += 1;
-                            } else {
-                                carried_item.item_count += 1;
-                            }
-                        } else {
-                            *slots[slot] = Some(single_item)
+                let changing_slots = drag.possibly_changing_slots(&slots_cloned, *carried_item);
+                for slot in changing_slots {
+                    if carried_item.item_count == 0 {
+                        break;
+                    }
+                    match slots[slot].as_mut() {
+                        Some(stack) if stack.item_count >= max_stack_size => {}
+                        Some(stack) => {
+                            stack.item_count += 1;
+                            carried_item.item_count -= 1;
+                        }
+                        None => {
+                            *slots[slot] = Some(carried_item.with_count(1));
+                            carried_item.item_count -= 1;
                         }
                     }
-                });
+                }
 
                 if carried_item.item_count == 0 {
                     *maybe_carried_item = None
                 }
             }
             MouseDragType::Left => {
-                // TODO: Handle dragging a stack with greater amount than item allows as max unstackable
-                // In that specific case, follow MouseDragType::Right behaviours instead!
-
-                let changing_slots =
-                    drag.possibly_changing_slots(&slots_cloned, carried_item.item_id);
+                let changing_slots = drag.possibly_changing_slots(&slots_cloned, *carried_item);
                 let amount_of_slots = changing_slots.clone().count();
-                let (amount_per_slot, remainder) =
+                if amount_of_slots == 0 {
+                    return Ok(());
+                }
+                // Every slot gets the same amount, what doesn't fit stays carried
+                let (amount_per_slot, _) =
                     (carried_item.item_count as usize).div_rem_euclid(&amount_of_slots);
-                let mut item_in_each_slot = *carried_item;
-                item_in_each_slot.item_count = amount_per_slot as u8;
-                changing_slots.for_each(|slot| *slots[slot] = Some(item_in_each_slot));
+                for slot in changing_slots {
+                    let current = slots[slot].map_or(0, |stack| stack.item_count);
+                    let added = (amount_per_slot as u8).min(max_stack_size.saturating_sub(current));
+                    if added == 0 {
+                        continue;
+                    }
+                    *slots[slot] = Some(carried_item.with_count(current + added));
+                    carried_item.item_count -= added;
+                }
 
-                if remainder > 0 {
-                    carried_item.item_count = remainder as u8;
-                } else {
+                if carried_item.item_count == 0 {
                     *maybe_carried_item = None
                 }
             }
@@ -148,14 +154,14 @@ impl Drag {
     fn possibly_changing_slots<'a>(
         &'a self,
         slots: &'a [Option<ItemStack>],
-        carried_item_id: u32,
+        carried_item: ItemStack,
     ) -> impl Iterator<Item = usize> + 'a + Clone {
         self.slots.iter().filter_map(move |slot_index| {
             let slot = &slots[*slot_index];
 
             match slot {
                 Some(item_slot) => {
-                    if item_slot.item_id == carried_item_id {
+                    if *item_slot == carried_item {
                         Some(*slot_index)
                     } else {
                         None
@@ -0,0 +1,81 @@
+use crate::container_click::MouseClick;
+use crate::{handle_item_change, Container, InventoryError, WindowType};
+use pumpkin_world::global_registry::{self, ITEM_REGISTRY};
+use pumpkin_world::item::ItemStack;
+
+/// The item an enchanting table enchants and the lapis lazuli it uses up. Every player enchants in
+/// their own window, its items go back into their inventory once they close it
+pub struct EnchantingTable([Option<ItemStack>; 2]);
+
+impl EnchantingTable {
+    pub const ITEM_SLOT: usize = 0;
+    pub const LAPIS_SLOT: usize = 1;
+
+    pub fn new() -> Self {
+        Self([None; 2])
+    }
+
+    pub fn is_lapis(item: &ItemStack) -> bool {
+        global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id)
+            == Some("minecraft:lapis_lazuli")
+    }
+}
+
+impl Default for EnchantingTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Container for EnchantingTable {
+    fn window_type(&self) -> &'static WindowType {
+        &WindowType::EnchantmentTable
+    }
+
+    fn window_name(&self) -> &'static str {
+        "Enchant"
+    }
+
+    fn handle_item_change(
+        &mut self,
+        carried_item: &mut Option<ItemStack>,
+        slot: usize,
+        mouse_click: MouseClick,
+    ) -> Result<(), InventoryError> {
+        let item_slot = self.0.get_mut(slot).ok_or(InventoryError::InvalidSlot)?;
+        let Some(carried) = carried_item else {
+            handle_item_change(carried_item, item_slot, mouse_click);
+            return Ok(());
+        };
+        match slot {
+            Self::LAPIS_SLOT if !Self::is_lapis(carried) => {}
+            // Only one item is enchanted at a time
+            Self::ITEM_SLOT if item_slot.is_none() => {
+                *item_slot = Some(carried.with_count(1));
+                carried.item_count -= 1;
+                if carried.item_count == 0 {
+                    *carried_item = None;
+                }
+            }
+            Self::ITEM_SLOT if carried.item_count > 1 => {}
+            _ => handle_item_change(carried_item, item_slot, mouse_click),
+        }
+        Ok(())
+    }
+
+    fn all_slots(&mut self) -> Vec<&mut Option<ItemStack>> {
+        self.0.iter_mut().collect()
+    }
+
+    fn all_slots_ref(&self) -> Vec<Option<&ItemStack>> {
+        self.0.iter().map(|slot| slot.as_ref()).collect()
+    }
+
+    fn accepts(&self, slot: usize, item: &ItemStack) -> bool {
+        match slot {
+            Self::ITEM_SLOT => item.item_count == 1,
+            Self::LAPIS_SLOT => Self::is_lapis(item),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{Container, WindowType};
+use pumpkin_world::item::ItemStack;
+
+/// The slots of a furnace, blast furnace or smoker: what is cooked, the fuel and the result.
+/// Players looking into the same furnace share it
+pub struct Furnace {
+    window_type: &'static WindowType,
+    slots: [Option<ItemStack>; 3],
+}
+
+impl Furnace {
+    pub const INPUT_SLOT: usize = 0;
+    pub const FUEL_SLOT: usize = 1;
+    pub const RESULT_SLOT: usize = 2;
+
+    /// `window_type` has to be the one of a furnace, blast furnace or smoker
+    pub fn with_items(window_type: &'static WindowType, slots: [Option<ItemStack>; 3]) -> Self {
+        Self { window_type, slots }
+    }
+
+    pub fn is_furnace(window_type: &WindowType) -> bool {
+        matches!(
+            window_type,
+            WindowType::Furnace | WindowType::BlastFurnace | WindowType::Smoker
+        )
+    }
+}
+
+impl Container for Furnace {
+    fn window_type(&self) -> &'static WindowType {
+        self.window_type
+    }
+
+    fn window_name(&self) -> &'static str {
+        match self.window_type {
+            WindowType::BlastFurnace => "Blast Furnace",
+            WindowType::Smoker => "Smoker",
+            _ => "Furnace",
+        }
+    }
+
+    fn all_slots(&mut self) -> Vec<&mut Option<ItemStack>> {
+        self.slots.iter_mut().collect()
+    }
+
+    fn all_slots_ref(&self) -> Vec<Option<&ItemStack>> {
+        self.slots.iter().map(|slot| slot.as_ref()).collect()
+    }
+}
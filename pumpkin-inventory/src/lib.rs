@@ -3,15 +3,23 @@ use crate::player::PlayerInventory;
 use num_derive::{FromPrimitive, ToPrimitive};
 use pumpkin_world::item::ItemStack;
 
+mod anvil;
 pub mod container_click;
+mod crafting_table;
 pub mod drag_handler;
+mod enchanting_table;
 mod error;
+mod furnace;
 mod open_container;
 pub mod player;
 pub mod window_property;
 
+pub use anvil::Anvil;
+pub use crafting_table::CraftingTable;
+pub use enchanting_table::EnchantingTable;
 pub use error::InventoryError;
-pub use open_container::OpenContainer;
+pub use furnace::Furnace;
+pub use open_container::{Chest, OpenContainer};
 
 /// https://wiki.vg/Inventory
 #[derive(Debug, ToPrimitive, FromPrimitive, Clone, Copy, Eq, PartialEq)]
@@ -67,10 +75,8 @@ pub trait Container: Sync + Send {
         mouse_click: MouseClick,
     ) -> Result<(), InventoryError> {
         let mut all_slots = self.all_slots();
-        if slot > all_slots.len() {
-            Err(InventoryError::InvalidSlot)?
-        }
-        handle_item_change(carried_item, all_slots[slot], mouse_click);
+        let item_slot = all_slots.get_mut(slot).ok_or(InventoryError::InvalidSlot)?;
+        handle_item_change(carried_item, item_slot, mouse_click);
         Ok(())
     }
 
@@ -89,8 +95,22 @@ pub trait Container: Sync + Send {
     fn internal_pumpkin_id(&self) -> u64 {
         0
     }
+
+    /// Whether the stack may be put into a slot, e.g. enchanting tables only take lapis lazuli as
+    /// lapis
+    fn accepts(&self, _slot: usize, _item: &ItemStack) -> bool {
+        true
+    }
+
+    /// The width of the square crafting grid of the container, None if it has none. The result
+    /// is in slot 0 and the grid follows row by row
+    fn crafting_grid_width(&self) -> Option<usize> {
+        None
+    }
 }
 
+/// Picks up the stack in a slot, the whole stack with a left click and the bigger half with a
+/// right click
 pub fn handle_item_take(
     carried_item: &mut Option<ItemStack>,
     item_slot: &mut Option<ItemStack>,
@@ -106,13 +126,18 @@ pub fn handle_item_take(
             *item_slot = None;
         }
         MouseClick::Right => {
-            let half = item.item_count / 2;
-            item.item_count -= half;
-            new_item.item_count = half;
+            let taken = item.item_count.div_ceil(2);
+            new_item.item_count = taken;
+            item.item_count -= taken;
+            if item.item_count == 0 {
+                *item_slot = None;
+            }
         }
     }
     *carried_item = Some(new_item);
 }
+
+/// Clicks on a slot: picks up, puts down, combines or swaps the stack in it with the carried one
 pub fn handle_item_change(
     carried_slot: &mut Option<ItemStack>,
     current_slot: &mut Option<ItemStack>,
@@ -121,25 +146,23 @@ pub fn handle_item_change(
     match (current_slot.as_mut(), carried_slot.as_mut()) {
         // Swap or combine current and carried
         (Some(current), Some(carried)) => {
-            if current.item_id == carried.item_id {
+            if *current == *carried {
                 combine_stacks(carried_slot, current, mouse_click);
-            } else if mouse_click == MouseClick::Left {
-                let carried = *carried;
-                *carried_slot = Some(current.to_owned());
-                *current_slot = Some(carried.to_owned());
+            } else {
+                std::mem::swap(current_slot, carried_slot);
             }
         }
         // Put held stack into empty slot
         (None, Some(carried)) => match mouse_click {
             MouseClick::Left => {
-                *current_slot = Some(carried.to_owned());
-                *carried_slot = None;
+                *current_slot = carried_slot.take();
             }
             MouseClick::Right => {
+                *current_slot = Some(carried.with_count(1));
                 carried.item_count -= 1;
-                let mut new = *carried;
-                new.item_count = 1;
-                *current_slot = Some(new);
+                if carried.item_count == 0 {
+                    *carried_slot = None;
+                }
             }
         },
         // Take stack into carried
@@ -148,6 +171,8 @@ pub fn handle_item_change(
     }
 }
 
+/// Puts as many of the carried items onto a stack of the same item as fit, all of them with a
+/// left click and one with a right click
 pub fn combine_stacks(
     carried_slot: &mut Option<ItemStack>,
     slot: &mut ItemStack,
@@ -157,22 +182,46 @@ pub fn combine_stacks(
         return;
     };
 
-    let carried_change = match mouse_click {
+    let wanted = match mouse_click {
         MouseClick::Left => carried_item.item_count,
         MouseClick::Right => 1,
     };
+    let moved = wanted.min(slot.max_stack_size().saturating_sub(slot.item_count));
+    slot.item_count += moved;
+    carried_item.item_count -= moved;
+    if carried_item.item_count == 0 {
+        *carried_slot = None;
+    }
+}
 
-    // TODO: Check for item stack max size here
-    if slot.item_count + carried_change <= 64 {
-        slot.item_count += carried_change;
-        carried_item.item_count -= carried_change;
-        if carried_item.item_count == 0 {
-            *carried_slot = None;
+/// Moves a stack into the given slots, first onto stacks of the same item and then into empty
+/// slots, in the order of the slots. What doesn't fit stays in `item`
+pub fn move_into_slots<'a>(
+    item: &mut Option<ItemStack>,
+    slots: impl IntoIterator<Item = &'a mut Option<ItemStack>>,
+) {
+    let mut empty_slots = Vec::new();
+    for slot in slots {
+        let Some(moving) = item else {
+            return;
+        };
+        match slot {
+            Some(stack) if *stack == *moving => combine_stacks(item, stack, MouseClick::Left),
+            Some(_) => {}
+            None => empty_slots.push(slot),
+        }
+    }
+    for slot in empty_slots {
+        let Some(moving) = item else {
+            return;
+        };
+        let max = moving.max_stack_size();
+        if moving.item_count <= max {
+            *slot = item.take();
+        } else {
+            *slot = Some(moving.with_count(max));
+            moving.item_count -= max;
         }
-    } else {
-        let left_over = slot.item_count + carried_change - 64;
-        slot.item_count = 64;
-        carried_item.item_count = left_over;
     }
 }
 
@@ -190,6 +239,13 @@ impl<'a, 'b> OptionallyCombinedContainer<'a, 'b> {
             container,
         }
     }
+    /// How many slots belong to the open container, 0 if only the player inventory is open
+    pub fn container_slots(&self) -> usize {
+        self.container
+            .as_ref()
+            .map_or(0, |container| container.all_slots_ref().len())
+    }
+
     /// Returns None if the slot is in the players inventory, Returns Some(Option<&ItemStack>) if it's inside of the container
     pub fn get_slot_excluding_inventory(&self, slot: usize) -> Option<Option<&ItemStack>> {
         self.container.as_ref()?.all_slots_ref().get(slot).copied()
@@ -212,6 +268,30 @@ impl<'a> Container for OptionallyCombinedContainer<'a, 'a> {
             .unwrap_or(self.inventory.window_name())
     }
 
+    fn handle_item_change(
+        &mut self,
+        carried_item: &mut Option<ItemStack>,
+        slot: usize,
+        mouse_click: MouseClick,
+    ) -> Result<(), InventoryError> {
+        // Only the player inventory has the armor slots, which don't take every item
+        if self.container.is_none() {
+            return self
+                .inventory
+                .handle_item_change(carried_item, slot, mouse_click);
+        }
+        // Some containers don't take every item
+        if slot < self.container_slots() {
+            if let Some(container) = &mut self.container {
+                return container.handle_item_change(carried_item, slot, mouse_click);
+            }
+        }
+        let mut all_slots = self.all_slots();
+        let item_slot = all_slots.get_mut(slot).ok_or(InventoryError::InvalidSlot)?;
+        handle_item_change(carried_item, item_slot, mouse_click);
+        Ok(())
+    }
+
     fn all_slots(&mut self) -> Vec<&mut Option<ItemStack>> {
         let slots = match &mut self.container {
             Some(container) => {
@@ -234,4 +314,11 @@ impl<'a> Container for OptionallyCombinedContainer<'a, 'a> {
             None => self.inventory.all_slots_ref(),
         }
     }
+
+    fn crafting_grid_width(&self) -> Option<usize> {
+        match &self.container {
+            Some(container) => container.crafting_grid_width(),
+            None => self.inventory.crafting_grid_width(),
+        }
+    }
 }
@@ -37,23 +37,42 @@ impl OpenContainer {
     }
 
     pub fn empty(player_id: i32) -> Self {
+        Self::new(player_id, Box::new(Chest::new()))
+    }
+
+    pub fn new(player_id: i32, container: Box<dyn Container>) -> Self {
         Self {
             players: vec![player_id],
-            container: Arc::new(Mutex::new(Box::new(Chest::new()))),
+            container: Arc::new(Mutex::new(container)),
         }
     }
 
+    /// The container, no matter which players opened it
+    pub fn container(&self) -> &Arc<Mutex<Box<dyn Container>>> {
+        &self.container
+    }
+
     pub fn all_player_ids(&self) -> Vec<i32> {
         self.players.clone()
     }
 }
 
-struct Chest([Option<ItemStack>; 27]);
+pub struct Chest([Option<ItemStack>; 27]);
 
 impl Chest {
     pub fn new() -> Self {
         Self([None; 27])
     }
+
+    pub fn with_items(items: [Option<ItemStack>; 27]) -> Self {
+        Self(items)
+    }
+}
+
+impl Default for Chest {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 impl Container for Chest {
     fn window_type(&self) -> &'static WindowType {
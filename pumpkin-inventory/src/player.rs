@@ -1,7 +1,7 @@
 use std::sync::atomic::AtomicU32;
 
 use crate::container_click::MouseClick;
-use crate::{handle_item_change, Container, InventoryError, WindowType};
+use crate::{handle_item_change, move_into_slots, Container, InventoryError, WindowType};
 use pumpkin_world::item::ItemStack;
 
 pub struct PlayerInventory {
@@ -63,12 +63,21 @@ impl PlayerInventory {
             return Ok(());
         }
         let slot_condition = self.slot_condition(slot)?;
-        if let Some(item) = item {
-            if slot_condition(&item) {
-                *self.all_slots()[slot] = Some(item);
+        match item {
+            Some(item) if item.item_count == 0 || item.item_count > item.max_stack_size() => {
+                Err(InventoryError::InvalidPacket)
+            }
+            Some(item) => {
+                if slot_condition(&item) {
+                    *self.all_slots()[slot] = Some(item);
+                }
+                Ok(())
+            }
+            None => {
+                *self.all_slots()[slot] = None;
+                Ok(())
             }
         }
-        Ok(())
     }
     #[allow(clippy::type_complexity)]
     pub fn slot_condition(
@@ -90,10 +99,7 @@ impl PlayerInventory {
     }
     pub fn get_slot(&mut self, slot: usize) -> Result<&mut Option<ItemStack>, InventoryError> {
         match slot {
-            0 => {
-                // TODO: Add crafting check here
-                Ok(&mut self.crafting_output)
-            }
+            0 => Ok(&mut self.crafting_output),
             1..=4 => Ok(&mut self.crafting[slot - 1]),
             5..=8 => Ok(&mut self.armor[slot - 5]),
             9..=44 => Ok(&mut self.items[slot - 9]),
@@ -101,16 +107,75 @@ impl PlayerInventory {
             _ => Err(InventoryError::InvalidSlot),
         }
     }
+    /// The armor slot an item goes into, None if it can't be worn
+    pub fn armor_slot(item: &ItemStack) -> Option<usize> {
+        if item.is_helmet() {
+            Some(5)
+        } else if item.is_chestplate() {
+            Some(6)
+        } else if item.is_leggings() {
+            Some(7)
+        } else if item.is_boots() {
+            Some(8)
+        } else {
+            None
+        }
+    }
+
+    /// The worn armor, pieces are only worn in their own slot
+    pub fn worn_armor(&self) -> impl Iterator<Item = &ItemStack> {
+        self.armor.iter().enumerate().filter_map(|(index, item)| {
+            item.as_ref()
+                .filter(|item| Self::armor_slot(item) == Some(index + 5))
+        })
+    }
+
+    /// The armor points and toughness of the worn armor
+    pub fn armor_points(&self) -> (f32, f32) {
+        self.worn_armor().map(|item| item.armor()).fold(
+            (0.0, 0.0),
+            |(armor, toughness), (points, piece_toughness)| {
+                (armor + points, toughness + piece_toughness)
+            },
+        )
+    }
+
+    /// Puts a stack into the hotbar and the inventory, what doesn't fit stays in `item`
+    pub fn insert(&mut self, item: &mut Option<ItemStack>) {
+        let (inventory, hotbar) = self.items.split_at_mut(27);
+        move_into_slots(item, hotbar.iter_mut().chain(inventory.iter_mut()));
+    }
+
+    /// Moves the items of the crafting grid into the inventory, the grid is emptied once the
+    /// inventory gets closed. Items which don't fit stay in the grid
+    pub fn clear_crafting_grid(&mut self) {
+        for index in 0..self.crafting.len() {
+            let mut item = self.crafting[index].take();
+            self.insert(&mut item);
+            self.crafting[index] = item;
+        }
+    }
+
     pub fn set_selected(&mut self, slot: usize) {
         assert!((0..9).contains(&slot));
         self.selected = slot;
     }
 
+    /// The selected hotbar slot, from 0 to 8
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
     pub fn held_item(&self) -> Option<&ItemStack> {
         debug_assert!((0..9).contains(&self.selected));
         self.items[self.selected + 36 - 9].as_ref()
     }
 
+    pub fn held_item_mut(&mut self) -> &mut Option<ItemStack> {
+        debug_assert!((0..9).contains(&self.selected));
+        &mut self.items[self.selected + 36 - 9]
+    }
+
     pub fn slots(&self) -> Vec<Option<&ItemStack>> {
         let mut slots = vec![self.crafting_output.as_ref()];
         slots.extend(self.crafting.iter().map(|c| c.as_ref()));
@@ -173,4 +238,8 @@ impl Container for PlayerInventory {
     fn all_combinable_slots_mut(&mut self) -> Vec<&mut Option<ItemStack>> {
         self.items.iter_mut().collect()
     }
+
+    fn crafting_grid_width(&self) -> Option<usize> {
+        Some(2)
+    }
 }
@@ -8,7 +8,7 @@ impl Plugin for TestPlugin {
     }
 
     fn on_unload(&self) {
-        todo!()
+        println!("Goodbye World uwu");
     }
 }
 
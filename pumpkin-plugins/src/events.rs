@@ -0,0 +1,168 @@
+use std::{sync::Arc, time::Duration};
+
+use pumpkin_world::{level::Level, stats::PlayerStats};
+use uuid::Uuid;
+
+/// The status a client reports for a resource pack the Server sent
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ResourcePackStatus {
+    SuccessfullyDownloaded,
+    Declined,
+    FailedDownload,
+    Accepted,
+    Downloaded,
+    InvalidUrl,
+    FailedToReload,
+    Discarded,
+}
+
+impl ResourcePackStatus {
+    pub const fn from_id(id: i32) -> Option<Self> {
+        Some(match id {
+            0 => Self::SuccessfullyDownloaded,
+            1 => Self::Declined,
+            2 => Self::FailedDownload,
+            3 => Self::Accepted,
+            4 => Self::Downloaded,
+            5 => Self::InvalidUrl,
+            6 => Self::FailedToReload,
+            7 => Self::Discarded,
+            _ => return None,
+        })
+    }
+
+    /// Whether the client is done with the resource pack, no further status follows
+    pub const fn is_final(self) -> bool {
+        !matches!(self, Self::Accepted | Self::Downloaded)
+    }
+}
+
+/// Fired every time a joining player reports the status of the Server resource pack
+pub struct ResourcePackStatusEvent<'a> {
+    pub player_name: &'a str,
+    pub player_uuid: Uuid,
+    /// The id of the resource pack, as sent to the client
+    pub pack_uuid: Uuid,
+    pub status: ResourcePackStatus,
+}
+
+/// Fired before the tab list header and footer get sent to a player, plugins can change both
+pub struct TabListEvent<'a> {
+    pub player_name: &'a str,
+    pub player_uuid: Uuid,
+    /// The header with placeholders already replaced, empty for none
+    pub header: String,
+    /// The footer with placeholders already replaced, empty for none
+    pub footer: String,
+}
+
+/// Fired when a player enters the configuration phase, plugins can request and store cookies here
+pub struct PlayerConfigurationEvent<'a> {
+    pub player_name: &'a str,
+    pub player_uuid: Uuid,
+    /// Whether the player was transferred here from another server
+    pub transferred: bool,
+    /// Whether the player plays on Bedrock and joined through Geyser with Floodgate
+    pub bedrock: bool,
+    /// Cookies the client should send back, every answer fires a `CookieResponseEvent`
+    pub request_cookies: Vec<String>,
+    /// Cookies the client should store, they are kept across transfers
+    pub store_cookies: Vec<(String, Vec<u8>)>,
+}
+
+/// Fired when a client answers a cookie request
+pub struct CookieResponseEvent<'a> {
+    pub player_name: &'a str,
+    pub player_uuid: Uuid,
+    pub key: &'a str,
+    /// None if the client has no cookie stored for this key
+    pub payload: Option<&'a [u8]>,
+}
+
+/// Fired when a player is about to be kicked for being idle, plugins can cancel it.
+/// A cancelled player is checked again after another `player_idle_timeout`
+pub struct PlayerIdleKickEvent<'a> {
+    pub player_name: &'a str,
+    pub player_uuid: Uuid,
+    /// How long the player has not moved, chatted or interacted
+    pub idle_time: Duration,
+    pub cancelled: bool,
+}
+
+/// Fired when another server in the cluster sends a plugin message
+pub struct ClusterMessageEvent<'a> {
+    /// The name of the node which sent the message
+    pub node: &'a str,
+    pub channel: &'a str,
+    pub payload: &'a [u8],
+}
+
+/// Fired after the worlds were saved, by an autosave, `/save-all` or the shutdown
+pub struct WorldSaveEvent {
+    /// How many chunks were written
+    pub saved_chunks: usize,
+    /// How long the save took, autosaves are spread over several ticks
+    pub duration: Duration,
+    /// Whether this was a periodic autosave
+    pub autosave: bool,
+}
+
+/// Fired for every world once all worlds are loaded.
+/// Plugins can keep chunks of it loaded with `Level::add_ticket` and `TicketType::Plugin`
+pub struct WorldLoadEvent<'a> {
+    pub name: &'a str,
+    pub level: &'a Arc<Level>,
+}
+
+/// Fired after the weather of a world changed, by itself or with `/weather`
+pub struct WeatherChangeEvent<'a> {
+    /// The name of the world
+    pub world: &'a str,
+    pub raining: bool,
+    /// It only thunders while it rains
+    pub thundering: bool,
+}
+
+/// Fired after a game rule of a world was changed with `/gamerule`
+pub struct GameRuleChangeEvent<'a> {
+    /// The name of the world
+    pub world: &'a str,
+    pub rule: &'a str,
+    pub value: &'a str,
+}
+
+/// Fired before a player hurts another entity with a melee attack, plugins can change the damage
+/// or cancel the attack
+pub struct PlayerAttackEvent<'a> {
+    /// The name of the world
+    pub world: &'a str,
+    pub attacker_name: &'a str,
+    pub attacker_uuid: Uuid,
+    pub target_entity_id: i32,
+    /// The uuid of the player who gets hurt, None if the target is no player
+    pub target_uuid: Option<Uuid>,
+    /// The damage before armor, with the attack cooldown and critical hits applied
+    pub damage: f32,
+    pub critical: bool,
+    pub cancelled: bool,
+}
+
+/// Fired after a player changed their client settings, like the language or the view distance
+pub struct PlayerSettingsChangeEvent<'a> {
+    pub player_name: &'a str,
+    pub player_uuid: Uuid,
+    /// The language of the client, e.g. `en_us`
+    pub locale: &'a str,
+    /// How far chunks are sent to the player, their setting limited by the Server
+    pub view_distance: u8,
+    /// The skin layers the player shows, one bit for every layer
+    pub skin_parts: u8,
+}
+
+/// Fired whenever the statistics of a player get saved, like when they leave and on every
+/// autosave. Plugins can keep their own copy, e.g. for leaderboards
+pub struct PlayerStatsSaveEvent<'a> {
+    pub player_name: &'a str,
+    pub player_uuid: Uuid,
+    pub stats: &'a PlayerStats,
+}
@@ -1,4 +1,41 @@
-pub trait Plugin {
+use pumpkin_world::{
+    advancement::Advancement,
+    world_gen::{ChunkGenerator, Seed},
+};
+
+use crate::events::{
+    ClusterMessageEvent, CookieResponseEvent, GameRuleChangeEvent, PlayerAttackEvent,
+    PlayerConfigurationEvent, PlayerIdleKickEvent, PlayerSettingsChangeEvent, PlayerStatsSaveEvent,
+    ResourcePackStatusEvent, TabListEvent, WeatherChangeEvent, WorldLoadEvent, WorldSaveEvent,
+};
+
+pub trait Plugin: Send + Sync {
     fn on_load(&self);
     fn on_unload(&self);
+
+    fn on_resource_pack_status(&self, _event: &ResourcePackStatusEvent) {}
+    fn on_tab_list(&self, _event: &mut TabListEvent) {}
+    fn on_player_configuration(&self, _event: &mut PlayerConfigurationEvent) {}
+    fn on_cookie_response(&self, _event: &CookieResponseEvent) {}
+    fn on_player_idle_kick(&self, _event: &mut PlayerIdleKickEvent) {}
+    fn on_cluster_message(&self, _event: &ClusterMessageEvent) {}
+    fn on_world_save(&self, _event: &WorldSaveEvent) {}
+    fn on_world_load(&self, _event: &WorldLoadEvent) {}
+    fn on_weather_change(&self, _event: &WeatherChangeEvent) {}
+    fn on_game_rule_change(&self, _event: &GameRuleChangeEvent) {}
+    fn on_player_attack(&self, _event: &mut PlayerAttackEvent) {}
+    fn on_player_settings_change(&self, _event: &PlayerSettingsChangeEvent) {}
+    fn on_player_stats_save(&self, _event: &PlayerStatsSaveEvent) {}
+
+    /// Returns the generator called `name`, if this plugin provides it.
+    /// Asked for every world whose configured generator is not built in
+    fn chunk_generator(&self, _name: &str, _seed: Seed) -> Option<Box<dyn ChunkGenerator>> {
+        None
+    }
+
+    /// Advancements this plugin adds, by their id. Asked once when the Server starts, they
+    /// replace built-in advancements and those of datapacks with the same id
+    fn advancements(&self) -> Vec<(String, Advancement)> {
+        Vec::new()
+    }
 }
@@ -4,20 +4,67 @@ use std::{
     ffi::OsStr,
     fs,
     path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
 };
 
+/// How much time a plugin spent handling events
+pub struct PluginTimings {
+    /// The file name of the plugin
+    pub name: String,
+    events: AtomicU64,
+    nanos: AtomicU64,
+}
+
+impl PluginTimings {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            events: AtomicU64::new(0),
+            nanos: AtomicU64::new(0),
+        }
+    }
+
+    /// The amount of events fired to the plugin
+    pub fn events(&self) -> u64 {
+        self.events.load(Ordering::Relaxed)
+    }
+
+    /// The total time the plugin spent handling events
+    pub fn time(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::Relaxed))
+    }
+
+    fn record(&self, time: Duration) {
+        self.events.fetch_add(1, Ordering::Relaxed);
+        self.nanos
+            .fetch_add(time.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
 pub struct PluginLoader {
     plugins: Vec<Box<dyn Plugin>>,
+    /// The event timings of each plugin, in the same order as `plugins`
+    timings: Vec<PluginTimings>,
+    /// The loaded libraries, these must outlive the plugins created from them
+    libraries: Vec<Library>,
 }
 
 impl PluginLoader {
     pub fn new() -> Self {
         Self {
             plugins: Vec::new(),
+            timings: Vec::new(),
+            libraries: Vec::new(),
         }
     }
 
     pub fn load_plugin<P: AsRef<OsStr>>(&mut self, path: P) {
+        let name = Path::new(path.as_ref())
+            .file_name()
+            .unwrap_or(path.as_ref())
+            .to_string_lossy()
+            .into_owned();
         let lib = unsafe { Library::new(path).expect("Failed to load plugin") };
         unsafe {
             let plugin_entry_point: Symbol<fn() -> Box<dyn Plugin>> = lib
@@ -28,6 +75,18 @@ impl PluginLoader {
             plugin.on_load();
             self.plugins.push(plugin);
         }
+        self.timings.push(PluginTimings::new(name));
+        self.libraries.push(lib);
+    }
+
+    /// Calls `on_unload` on every plugin.
+    /// Their libraries stay loaded until the loader is dropped, because worlds may still use
+    /// chunk generators the plugins provided
+    pub fn unload_plugins(&mut self) {
+        for plugin in self.plugins.drain(..) {
+            plugin.on_unload();
+        }
+        self.timings.clear();
     }
 
     pub fn load_plugins_from_directory<P: AsRef<Path>>(&mut self, dir: P) {
@@ -61,4 +120,17 @@ impl PluginLoader {
     pub fn get_plugins(&self) -> &Vec<Box<dyn Plugin>> {
         &self.plugins
     }
+
+    pub fn get_timings(&self) -> &[PluginTimings] {
+        &self.timings
+    }
+
+    /// Calls `f` for every loaded plugin, used to fire events
+    pub fn for_each<F: FnMut(&dyn Plugin)>(&self, mut f: F) {
+        for (plugin, timings) in self.plugins.iter().zip(&self.timings) {
+            let start = Instant::now();
+            f(plugin.as_ref());
+            timings.record(start.elapsed());
+        }
+    }
 }
@@ -158,6 +158,21 @@ impl ByteBuffer {
         }
     }
 
+    pub fn put_var_long(&mut self, value: VarLongType) {
+        let mut val = value as u64;
+        loop {
+            let mut b: u8 = val as u8 & 0b01111111;
+            val >>= 7;
+            if val != 0 {
+                b |= 0b10000000;
+            }
+            self.buffer.put_u8(b);
+            if val == 0 {
+                break;
+            }
+        }
+    }
+
     pub fn put_bit_set(&mut self, set: &BitSet) {
         self.put_var_int(&set.0);
         for b in set.1 {
@@ -391,6 +406,15 @@ impl ByteBuffer {
         self.buffer.put_slice(src)
     }
 
+    /// Writes `value` the way packets deriving `Serialize` are written, e.g. a `Slot`
+    pub fn put_serialize<T: serde::Serialize>(&mut self, value: &T) {
+        let mut serializer = serializer::Serializer::new(ByteBuffer::empty());
+        value
+            .serialize(&mut serializer)
+            .expect("Could not serialize value");
+        self.put(serializer.output.buf());
+    }
+
     pub fn put<T: Buf>(&mut self, src: T)
     where
         Self: Sized,
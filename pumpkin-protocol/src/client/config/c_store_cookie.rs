@@ -0,0 +1,22 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+#[derive(Serialize)]
+#[packet(0x0A)]
+pub struct CStoreCookie<'a> {
+    key: &'a str,
+    payload_length: VarInt,
+    payload: &'a [u8],
+}
+
+impl<'a> CStoreCookie<'a> {
+    pub fn new(key: &'a str, payload: &'a [u8]) -> Self {
+        Self {
+            key,
+            payload_length: payload.len().into(),
+            payload,
+        }
+    }
+}
@@ -5,6 +5,7 @@ mod c_finish_config;
 mod c_known_packs;
 mod c_plugin_message;
 mod c_registry_data;
+mod c_store_cookie;
 
 pub use c_add_resource_pack::*;
 pub use c_config_disconnect::*;
@@ -13,3 +14,4 @@ pub use c_finish_config::*;
 pub use c_known_packs::*;
 pub use c_plugin_message::*;
 pub use c_registry_data::*;
+pub use c_store_cookie::*;
@@ -0,0 +1,15 @@
+use pumpkin_macros::packet;
+
+use crate::Identifier;
+
+#[derive(serde::Serialize)]
+#[packet(0x05)]
+pub struct CLoginCookieRequest {
+    key: Identifier,
+}
+
+impl CLoginCookieRequest {
+    pub fn new(key: Identifier) -> Self {
+        Self { key }
+    }
+}
@@ -1,9 +1,11 @@
+mod c_cookie_request;
 mod c_encryption_request;
 mod c_login_disconnect;
 mod c_login_success;
 mod c_plugin_request;
 mod c_set_compression;
 
+pub use c_cookie_request::*;
 pub use c_encryption_request::*;
 pub use c_login_disconnect::*;
 pub use c_login_success::*;
@@ -0,0 +1,33 @@
+use pumpkin_macros::packet;
+
+use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt};
+
+/// Answers the client asking for the statistics, it only shows the statistics sent here
+#[packet(0x04)]
+pub struct CAwardStatistics<'a> {
+    statistics: &'a [Statistic],
+}
+
+pub struct Statistic {
+    /// The id in the `minecraft:stat_type` registry
+    pub category_id: VarInt,
+    /// The id in the registry of the category, e.g. a block for `minecraft:mined`
+    pub statistic_id: VarInt,
+    pub value: VarInt,
+}
+
+impl<'a> CAwardStatistics<'a> {
+    pub fn new(statistics: &'a [Statistic]) -> Self {
+        Self { statistics }
+    }
+}
+
+impl<'a> ClientPacket for CAwardStatistics<'a> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_list::<Statistic>(self.statistics, |p, v| {
+            p.put_var_int(&v.category_id);
+            p.put_var_int(&v.statistic_id);
+            p.put_var_int(&v.value);
+        });
+    }
+}
@@ -0,0 +1,32 @@
+use pumpkin_core::math::position::WorldPosition;
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+/// Plays an animation of a block, e.g. opening a chest
+#[derive(Serialize)]
+#[packet(0x08)]
+pub struct CBlockAction<'a> {
+    location: &'a WorldPosition,
+    action_id: u8,
+    action_parameter: u8,
+    /// The id of the block in the `minecraft:block` registry, not its state id
+    block_type: VarInt,
+}
+
+impl<'a> CBlockAction<'a> {
+    pub fn new(
+        location: &'a WorldPosition,
+        action_id: u8,
+        action_parameter: u8,
+        block_type: VarInt,
+    ) -> Self {
+        Self {
+            location,
+            action_id,
+            action_parameter,
+            block_type,
+        }
+    }
+}
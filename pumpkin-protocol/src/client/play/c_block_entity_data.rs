@@ -0,0 +1,36 @@
+use pumpkin_core::math::position::WorldPosition;
+use pumpkin_macros::packet;
+use pumpkin_world::block::BlockEntity;
+
+use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt};
+
+/// Updates the data of a block entity, e.g. after a sign was edited
+#[packet(0x07)]
+pub struct CBlockEntityData<'a> {
+    block_entity: &'a BlockEntity,
+    type_id: VarInt,
+}
+
+impl<'a> CBlockEntityData<'a> {
+    /// None if clients don't know the type of the block entity
+    pub fn new(block_entity: &'a BlockEntity) -> Option<Self> {
+        let type_id = block_entity.type_id()?;
+        Some(Self {
+            block_entity,
+            type_id: VarInt(type_id as i32),
+        })
+    }
+}
+
+impl<'a> ClientPacket for CBlockEntityData<'a> {
+    fn write(&self, buf: &mut ByteBuffer) {
+        buf.put_i64(WorldPosition(self.block_entity.position).as_long());
+        buf.put_var_int(&self.type_id);
+        let nbt = fastnbt::to_bytes_with_opts(
+            &self.block_entity.client_data(),
+            fastnbt::SerOpts::network_nbt(),
+        )
+        .unwrap();
+        buf.put_slice(&nbt);
+    }
+}
@@ -1,9 +1,8 @@
-use std::collections::HashMap;
-
-use crate::{bytebuf::ByteBuffer, BitSet, ClientPacket, VarInt};
+use super::c_update_light::{write_light_data, ALL_LIGHT_SECTIONS};
+use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt};
 use itertools::Itertools;
 use pumpkin_macros::packet;
-use pumpkin_world::{chunk::ChunkData, DIRECT_PALETTE_BITS};
+use pumpkin_world::{biome::Biome, chunk::ChunkData};
 
 #[packet(0x27)]
 pub struct CChunkData<'a>(pub &'a ChunkData);
@@ -22,105 +21,119 @@ impl<'a> ClientPacket for CChunkData<'a> {
         buf.put_slice(&heightmap_nbt);
 
         let mut data_buf = ByteBuffer::empty();
-        self.0.blocks.iter_subchunks().for_each(|chunk| {
-            let block_count = chunk.iter().filter(|block| !block.is_air()).count() as i16;
+        let biomes = self.0.biomes.iter_subchunks();
+        for (section, biomes) in self.0.blocks.sections().iter().zip(biomes) {
             // Block count
-            data_buf.put_i16(block_count);
-            //// Block states
-
-            let palette = chunk.iter().dedup().collect_vec();
-            // TODO: make dynamic block_size work
-            // TODO: make direct block_size work
-            enum PaletteType {
-                Indirect(u32),
-                Direct,
-            }
-            let palette_type = {
-                let palette_bit_len = 64 - (palette.len() as i64 - 1).leading_zeros();
-                if palette_bit_len > 8 {
-                    PaletteType::Direct
-                } else if palette_bit_len > 3 {
-                    PaletteType::Indirect(palette_bit_len)
-                } else {
-                    PaletteType::Indirect(4)
+            data_buf.put_i16(section.non_air_blocks() as i16);
+            //// Block states, the sections are stored in the network format
+            // Bits per entry
+            data_buf.put_u8(section.bits() as u8);
+            match section.palette() {
+                // Single valued
+                Some([block]) if section.bits() == 0 => {
+                    data_buf.put_var_int(&VarInt(block.get_id_mojang_repr()))
                 }
-                // TODO: fix indirect palette to work correctly
-                // PaletteType::Direct
-            };
-
-            let mut block_data_array = Vec::new();
-            match palette_type {
-                PaletteType::Indirect(block_size) => {
-                    // Bits per entry
-                    data_buf.put_u8(block_size as u8);
+                Some(palette) => {
                     // Palette length
                     data_buf.put_var_int(&VarInt(palette.len() as i32));
-                    let mut palette_map = HashMap::new();
-                    palette.iter().enumerate().for_each(|(i, id)| {
-                        palette_map.insert(*id, i);
+                    for block in palette {
                         // Palette
-                        data_buf.put_var_int(&VarInt(id.get_id_mojang_repr()));
-                    });
-                    for block_clump in chunk.chunks(64 / block_size as usize) {
-                        let mut out_long: i64 = 0;
-                        for block in block_clump.iter().rev() {
-                            let index = palette_map
-                                .get(block)
-                                .expect("Its just got added, ofc it should be there");
-                            out_long = out_long << block_size | (*index as i64);
-                        }
-                        block_data_array.push(out_long);
-                    }
-                }
-                PaletteType::Direct => {
-                    // Bits per entry
-                    data_buf.put_u8(DIRECT_PALETTE_BITS as u8);
-                    for block_clump in chunk.chunks(64 / DIRECT_PALETTE_BITS as usize) {
-                        let mut out_long: i64 = 0;
-                        let mut shift = 0;
-                        for block in block_clump {
-                            out_long |= (block.get_id() as i64) << shift;
-                            shift += DIRECT_PALETTE_BITS;
-                        }
-                        block_data_array.push(out_long);
+                        data_buf.put_var_int(&VarInt(block.get_id_mojang_repr()));
                     }
                 }
+                // Direct
+                None => {}
             }
-
             // Data array length
-            // TODO: precompute this and omit making the `block_data_array`
-            data_buf.put_var_int(&VarInt(block_data_array.len() as i32));
+            data_buf.put_var_int(&VarInt(section.data().len() as i32));
             // Data array
-            for data_int in block_data_array {
-                data_buf.put_i64(data_int);
+            for long in section.data() {
+                data_buf.put_i64(*long);
             }
 
             //// Biomes
-            // TODO: make biomes work
-            data_buf.put_u8(0);
-            data_buf.put_var_int(&VarInt(0));
-            data_buf.put_var_int(&VarInt(0));
-        });
+            write_biomes(&mut data_buf, biomes);
+        }
 
         // Size
         buf.put_var_int(&VarInt(data_buf.buf().len() as i32));
         // Data
         buf.put_slice(data_buf.buf());
 
-        // TODO: block entities
-        buf.put_var_int(&VarInt(0));
+        // Block entities of types clients don't know are left out
+        let block_entities = self
+            .0
+            .block_entities
+            .values()
+            .filter_map(|entity| Some((entity, entity.type_id()?)))
+            .collect_vec();
+        buf.put_var_int(&VarInt(block_entities.len() as i32));
+        for (entity, type_id) in block_entities {
+            let position = entity.position;
+            // Packed XZ, relative to the chunk
+            buf.put_u8((((position.x & 15) << 4) | (position.z & 15)) as u8);
+            buf.put_i16(position.y as i16);
+            buf.put_var_int(&VarInt(type_id as i32));
+            let nbt =
+                fastnbt::to_bytes_with_opts(&entity.client_data(), fastnbt::SerOpts::network_nbt())
+                    .unwrap();
+            buf.put_slice(&nbt);
+        }
+
+        write_light_data(buf, &self.0.light, ALL_LIGHT_SECTIONS);
+    }
+}
+
+/// Biomes with more different entries than this in a subchunk use the direct palette
+const MAX_INDIRECT_BIOME_BITS: u32 = 3;
+
+/// Writes the biomes of a subchunk as a paletted container
+fn write_biomes(data_buf: &mut ByteBuffer, biomes: &[Biome]) {
+    let palette = biomes.iter().copied().unique().collect_vec();
+    if palette.len() == 1 {
+        // Single valued
+        data_buf.put_u8(0);
+        data_buf.put_var_int(&VarInt(palette[0].network_id()));
+        data_buf.put_var_int(&VarInt(0));
+        return;
+    }
+
+    let palette_bits = (usize::BITS - (palette.len() - 1).leading_zeros()).max(1);
+    let (bits, palette) = if palette_bits <= MAX_INDIRECT_BIOME_BITS {
+        (palette_bits, Some(palette))
+    } else {
+        let direct_bits = usize::BITS - (Biome::ALL.len() - 1).leading_zeros();
+        (direct_bits, None)
+    };
 
-        // TODO
-        buf.put_bit_set(&BitSet(VarInt(1), &[0]));
-        buf.put_bit_set(&BitSet(VarInt(1), &[0]));
-        buf.put_bit_set(&BitSet(VarInt(1), &[0]));
-        buf.put_bit_set(&BitSet(VarInt(1), &[0]));
-        // buf.put_bit_set(&BitSet(VarInt(0), vec![]));
-        // buf.put_bit_set(&BitSet(VarInt(0), vec![]));
-        // buf.put_bit_set(&BitSet(VarInt(0), vec![]));
-        // buf.put_bit_set(&BitSet(VarInt(0), vec![]));
+    // Bits per entry
+    data_buf.put_u8(bits as u8);
+    if let Some(palette) = &palette {
+        // Palette
+        data_buf.put_var_int(&VarInt(palette.len() as i32));
+        for biome in palette {
+            data_buf.put_var_int(&VarInt(biome.network_id()));
+        }
+    }
 
-        buf.put_var_int(&VarInt(0));
-        buf.put_var_int(&VarInt(0));
+    // Entries never span two longs, the first entry goes into the lowest bits
+    let data = biomes
+        .chunks(64 / bits as usize)
+        .map(|entries| {
+            entries.iter().enumerate().fold(0i64, |long, (i, biome)| {
+                let value = match &palette {
+                    Some(palette) => {
+                        palette.iter().position(|entry| entry == biome).unwrap() as i64
+                    }
+                    None => biome.network_id() as i64,
+                };
+                long | value << (i as u32 * bits)
+            })
+        })
+        .collect_vec();
+    // Data array
+    data_buf.put_var_int(&VarInt(data.len() as i32));
+    for long in data {
+        data_buf.put_i64(long);
     }
 }
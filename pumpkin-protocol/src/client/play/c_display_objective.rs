@@ -0,0 +1,21 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+/// Shows an objective in a display slot, an empty name clears the slot
+#[derive(Serialize)]
+#[packet(0x57)]
+pub struct CDisplayObjective<'a> {
+    position: VarInt,
+    score_name: &'a str,
+}
+
+impl<'a> CDisplayObjective<'a> {
+    pub fn new(position: VarInt, score_name: &'a str) -> Self {
+        Self {
+            position,
+            score_name,
+        }
+    }
+}
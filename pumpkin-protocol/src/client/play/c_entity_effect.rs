@@ -0,0 +1,35 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+/// Gives an entity a status effect, or replaces the one it has
+#[derive(Serialize)]
+#[packet(0x76)]
+pub struct CEntityEffect {
+    entity_id: VarInt,
+    effect_id: VarInt,
+    amplifier: VarInt,
+    /// In ticks, -1 for an effect which lasts forever
+    duration: VarInt,
+    /// 0x01 ambient, 0x02 shows particles, 0x04 shows the icon, 0x08 blends the effect in
+    flags: i8,
+}
+
+impl CEntityEffect {
+    pub fn new(
+        entity_id: VarInt,
+        effect_id: VarInt,
+        amplifier: VarInt,
+        duration: VarInt,
+        flags: i8,
+    ) -> Self {
+        Self {
+            entity_id,
+            effect_id,
+            amplifier,
+            duration,
+            flags,
+        }
+    }
+}
@@ -1,7 +1,8 @@
+use pumpkin_core::text::TextComponent;
 use pumpkin_macros::packet;
 use serde::Serialize;
 
-use crate::VarInt;
+use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt};
 
 #[derive(Serialize)]
 #[packet(0x58)]
@@ -33,3 +34,63 @@ impl<T> Metadata<T> {
         Self { index, typ, value }
     }
 }
+
+/// A value of the entity metadata, it is written with the id of its type
+pub enum MetadataValue<'a> {
+    Byte(i8),
+    VarInt(VarInt),
+    Float(f32),
+    OptionalText(Option<TextComponent<'a>>),
+    Boolean(bool),
+    /// The id of an `EntityPose`
+    Pose(VarInt),
+}
+
+impl MetadataValue<'_> {
+    const fn type_id(&self) -> i32 {
+        match self {
+            Self::Byte(_) => 0,
+            Self::VarInt(_) => 1,
+            Self::Float(_) => 3,
+            Self::OptionalText(_) => 6,
+            Self::Boolean(_) => 8,
+            Self::Pose(_) => 20,
+        }
+    }
+}
+
+/// Sets several entries of the entity metadata at once, by their index
+#[packet(0x58)]
+pub struct CSetEntityData<'a> {
+    entity_id: VarInt,
+    entries: &'a [(u8, MetadataValue<'a>)],
+}
+
+impl<'a> CSetEntityData<'a> {
+    pub fn new(entity_id: VarInt, entries: &'a [(u8, MetadataValue<'a>)]) -> Self {
+        Self { entity_id, entries }
+    }
+}
+
+impl<'a> ClientPacket for CSetEntityData<'a> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_var_int(&self.entity_id);
+        for (index, value) in self.entries {
+            bytebuf.put_u8(*index);
+            bytebuf.put_var_int(&value.type_id().into());
+            match value {
+                MetadataValue::Byte(value) => bytebuf.put_i8(*value),
+                MetadataValue::VarInt(value) | MetadataValue::Pose(value) => {
+                    bytebuf.put_var_int(value);
+                }
+                MetadataValue::Float(value) => bytebuf.put_f32(*value),
+                MetadataValue::OptionalText(text) => {
+                    bytebuf.put_option(text, |p, text| p.put_slice(&text.encode()));
+                }
+                MetadataValue::Boolean(value) => bytebuf.put_bool(*value),
+            }
+        }
+        // The index which ends the entries
+        bytebuf.put_u8(255);
+    }
+}
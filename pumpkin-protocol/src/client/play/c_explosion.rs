@@ -0,0 +1,70 @@
+use pumpkin_macros::packet;
+use pumpkin_world::global_registry;
+
+use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt};
+
+/// Shows an explosion with the vanilla particles and sound, the client removes the destroyed
+/// blocks by itself and adds the knockback to its velocity
+#[packet(0x20)]
+pub struct CExplosion<'a> {
+    x: f64,
+    y: f64,
+    z: f64,
+    strength: f32,
+    /// The destroyed blocks, relative to the block the explosion is in
+    blocks: &'a [(i8, i8, i8)],
+    knockback: (f32, f32, f32),
+    destroy_blocks: bool,
+}
+
+impl<'a> CExplosion<'a> {
+    pub fn new(
+        x: f64,
+        y: f64,
+        z: f64,
+        strength: f32,
+        blocks: &'a [(i8, i8, i8)],
+        knockback: (f32, f32, f32),
+        destroy_blocks: bool,
+    ) -> Self {
+        Self {
+            x,
+            y,
+            z,
+            strength,
+            blocks,
+            knockback,
+            destroy_blocks,
+        }
+    }
+}
+
+impl<'a> ClientPacket for CExplosion<'a> {
+    fn write(&self, buf: &mut ByteBuffer) {
+        buf.put_f64(self.x);
+        buf.put_f64(self.y);
+        buf.put_f64(self.z);
+        buf.put_f32(self.strength);
+        buf.put_list(self.blocks, |buf, (x, y, z)| {
+            buf.put_i8(*x);
+            buf.put_i8(*y);
+            buf.put_i8(*z);
+        });
+        buf.put_f32(self.knockback.0);
+        buf.put_f32(self.knockback.1);
+        buf.put_f32(self.knockback.2);
+        // Block interaction, 0 keeps the blocks and 1 destroys them
+        buf.put_var_int(&VarInt(i32::from(self.destroy_blocks)));
+        // Small and large particles, neither has extra data
+        for particle in ["minecraft:explosion", "minecraft:explosion_emitter"] {
+            let id = global_registry::get_protocol_id("minecraft:particle_type", particle);
+            buf.put_var_int(&VarInt(id as i32));
+        }
+        // The sound is a registry id plus one, 0 would be followed by an inline sound event
+        let sound = global_registry::get_protocol_id(
+            "minecraft:sound_event",
+            "minecraft:entity.generic.explode",
+        );
+        buf.put_var_int(&VarInt(sound as i32 + 1));
+    }
+}
@@ -0,0 +1,25 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+/// Puts the vehicle the client steers back where the server has it
+#[derive(Serialize)]
+#[packet(0x31)]
+pub struct CMoveVehicle {
+    x: f64,
+    y: f64,
+    z: f64,
+    yaw: f32,
+    pitch: f32,
+}
+
+impl CMoveVehicle {
+    pub fn new(x: f64, y: f64, z: f64, yaw: f32, pitch: f32) -> Self {
+        Self {
+            x,
+            y,
+            z,
+            yaw,
+            pitch,
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use pumpkin_core::math::position::WorldPosition;
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+#[derive(Serialize)]
+#[packet(0x34)]
+pub struct COpenSignEditor<'a> {
+    location: &'a WorldPosition,
+    is_front_text: bool,
+}
+
+impl<'a> COpenSignEditor<'a> {
+    pub fn new(location: &'a WorldPosition, is_front_text: bool) -> Self {
+        Self {
+            location,
+            is_front_text,
+        }
+    }
+}
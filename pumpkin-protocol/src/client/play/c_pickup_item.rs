@@ -0,0 +1,23 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+/// Shows an item or experience orb flying into the entity which collected it
+#[derive(Serialize)]
+#[packet(0x6F)]
+pub struct CPickupItem {
+    collected_entity_id: VarInt,
+    collector_entity_id: VarInt,
+    count: VarInt,
+}
+
+impl CPickupItem {
+    pub fn new(collected_entity_id: VarInt, collector_entity_id: VarInt, count: VarInt) -> Self {
+        Self {
+            collected_entity_id,
+            collector_entity_id,
+            count,
+        }
+    }
+}
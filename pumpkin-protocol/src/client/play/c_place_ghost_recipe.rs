@@ -0,0 +1,17 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+/// Shows the ingredients of a recipe in the crafting grid of a window, the answer to a player
+/// who clicked a recipe they don't have the items for
+#[derive(Serialize)]
+#[packet(0x37)]
+pub struct CPlaceGhostRecipe<'a> {
+    window_id: i8,
+    recipe: &'a str,
+}
+
+impl<'a> CPlaceGhostRecipe<'a> {
+    pub fn new(window_id: i8, recipe: &'a str) -> Self {
+        Self { window_id, recipe }
+    }
+}
@@ -58,10 +58,41 @@ impl<'a> CPlayerChatMessage<'a> {
 
 #[derive(Serialize)]
 pub struct PreviousMessage<'a> {
+    /// The id of the message in the client's signature cache + 1, 0 when the full signature follows
     message_id: VarInt,
+    /// Only written when `message_id` is 0, without a boolean prefix
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_signature"
+    )]
     signature: Option<&'a [u8]>,
 }
 
+fn serialize_signature<S: serde::Serializer>(
+    signature: &Option<&[u8]>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_bytes(signature.unwrap_or_default())
+}
+
+impl<'a> PreviousMessage<'a> {
+    /// References a message the client has cached by its id
+    pub fn cached(message_id: i32) -> Self {
+        Self {
+            message_id: VarInt(message_id + 1),
+            signature: None,
+        }
+    }
+
+    /// Sends the full signature of a message
+    pub fn full(signature: &'a [u8]) -> Self {
+        Self {
+            message_id: VarInt(0),
+            signature: Some(signature),
+        }
+    }
+}
+
 #[derive(Serialize)]
 #[repr(i32)]
 pub enum FilterType<'a> {
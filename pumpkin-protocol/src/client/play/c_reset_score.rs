@@ -0,0 +1,19 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+/// Removes the score of a holder in an objective, or in all of them if there is none
+#[derive(Serialize)]
+#[packet(0x44)]
+pub struct CResetScore<'a> {
+    entity_name: &'a str,
+    objective_name: Option<&'a str>,
+}
+
+impl<'a> CResetScore<'a> {
+    pub fn new(entity_name: &'a str, objective_name: Option<&'a str>) -> Self {
+        Self {
+            entity_name,
+            objective_name,
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use pumpkin_core::math::position::WorldPosition;
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+#[derive(Serialize)]
+#[packet(0x47)]
+pub struct CRespawn<'a> {
+    dimension_type: VarInt,
+    dimension_name: &'a str,
+    hashed_seed: i64,
+    game_mode: u8,
+    previous_gamemode: i8,
+    debug: bool,
+    is_flat: bool,
+    death_location: Option<(&'a str, WorldPosition)>,
+    portal_cooldown: VarInt,
+    /// 0x01 keeps the attributes, 0x02 the entity metadata
+    data_kept: u8,
+}
+
+impl<'a> CRespawn<'a> {
+    #[expect(clippy::too_many_arguments)]
+    pub fn new(
+        dimension_type: VarInt,
+        dimension_name: &'a str,
+        hashed_seed: i64,
+        game_mode: u8,
+        previous_gamemode: i8,
+        debug: bool,
+        is_flat: bool,
+        death_location: Option<(&'a str, WorldPosition)>,
+        portal_cooldown: VarInt,
+        data_kept: u8,
+    ) -> Self {
+        Self {
+            dimension_type,
+            dimension_name,
+            hashed_seed,
+            game_mode,
+            previous_gamemode,
+            debug,
+            is_flat,
+            death_location,
+            portal_cooldown,
+            data_kept,
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+/// Tells the client how far away chunks are sent, it forgets chunks further away
+#[derive(Serialize)]
+#[packet(0x55)]
+pub struct CSetChunkCacheRadius {
+    view_distance: VarInt,
+}
+
+impl CSetChunkCacheRadius {
+    pub fn new(view_distance: VarInt) -> Self {
+        Self { view_distance }
+    }
+}
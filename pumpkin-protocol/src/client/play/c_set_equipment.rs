@@ -0,0 +1,36 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+use crate::{slot::Slot, VarInt};
+
+/// The equipment slots of entities as clients know them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(i8)]
+pub enum EquipmentSlot {
+    MainHand = 0,
+    OffHand,
+    Boots,
+    Leggings,
+    Chestplate,
+    Helmet,
+    Body,
+}
+
+/// Shows the item an entity holds or wears in one of its slots
+#[derive(Serialize)]
+#[packet(0x5B)]
+pub struct CSetEquipment {
+    entity_id: VarInt,
+    slot: i8,
+    item: Slot,
+}
+
+impl CSetEquipment {
+    pub fn new(entity_id: VarInt, slot: EquipmentSlot, item: Slot) -> Self {
+        Self {
+            entity_id,
+            slot: slot as i8,
+            item,
+        }
+    }
+}
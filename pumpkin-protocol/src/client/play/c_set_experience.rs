@@ -0,0 +1,22 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+#[derive(Serialize)]
+#[packet(0x5C)]
+pub struct CSetExperience {
+    progress: f32,
+    level: VarInt,
+    total_experience: VarInt,
+}
+
+impl CSetExperience {
+    pub fn new(progress: f32, level: VarInt, total_experience: VarInt) -> Self {
+        Self {
+            progress,
+            level,
+            total_experience,
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+/// Tells clients who rides an entity, entities not in the list get off
+#[derive(Serialize)]
+#[packet(0x5F)]
+pub struct CSetPassengers<'a> {
+    entity_id: VarInt,
+    count: VarInt,
+    passengers: &'a [VarInt],
+}
+
+impl<'a> CSetPassengers<'a> {
+    pub fn new(entity_id: VarInt, passengers: &'a [VarInt]) -> Self {
+        Self {
+            entity_id,
+            count: VarInt(passengers.len() as i32),
+            passengers,
+        }
+    }
+}
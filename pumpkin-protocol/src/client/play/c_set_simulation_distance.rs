@@ -0,0 +1,19 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+/// Tells the client how far away entities and blocks are ticked
+#[derive(Serialize)]
+#[packet(0x62)]
+pub struct CSetSimulationDistance {
+    simulation_distance: VarInt,
+}
+
+impl CSetSimulationDistance {
+    pub fn new(simulation_distance: VarInt) -> Self {
+        Self {
+            simulation_distance,
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use pumpkin_core::text::TextComponent;
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+#[derive(Serialize)]
+#[packet(0x6A)]
+pub struct CTabListHeaderFooter<'a> {
+    header: TextComponent<'a>,
+    footer: TextComponent<'a>,
+}
+
+impl<'a> CTabListHeaderFooter<'a> {
+    pub fn new(header: TextComponent<'a>, footer: TextComponent<'a>) -> Self {
+        Self { header, footer }
+    }
+}
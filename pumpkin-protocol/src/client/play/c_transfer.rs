@@ -0,0 +1,17 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+#[derive(Serialize)]
+#[packet(0x73)]
+pub struct CTransfer<'a> {
+    host: &'a str,
+    port: VarInt,
+}
+
+impl<'a> CTransfer<'a> {
+    pub fn new(host: &'a str, port: VarInt) -> Self {
+        Self { host, port }
+    }
+}
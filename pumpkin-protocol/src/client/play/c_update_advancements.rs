@@ -0,0 +1,103 @@
+use pumpkin_core::text::TextComponent;
+use pumpkin_macros::packet;
+
+use crate::{bytebuf::ByteBuffer, slot::Slot, ClientPacket, VarInt};
+
+/// Adds and removes advancements of the advancement screen and updates the progress. Clients show
+/// a toast for advancements which got done, unless `reset` is set
+#[packet(0x74)]
+pub struct CUpdateAdvancements<'a> {
+    reset: bool,
+    added: &'a [AdvancementEntry<'a>],
+    removed: &'a [&'a str],
+    progress: &'a [AdvancementProgressEntry<'a>],
+}
+
+pub struct AdvancementEntry<'a> {
+    pub id: &'a str,
+    pub parent: Option<&'a str>,
+    pub display: Option<AdvancementDisplay<'a>>,
+    /// One criterion of every group has to be met
+    pub requirements: &'a [Vec<String>],
+    pub sends_telemetry_data: bool,
+}
+
+pub struct AdvancementDisplay<'a> {
+    pub title: &'a TextComponent<'a>,
+    pub description: &'a TextComponent<'a>,
+    pub icon: Slot,
+    /// 0 for a task, 1 for a challenge and 2 for a goal
+    pub frame: VarInt,
+    pub background: Option<&'a str>,
+    pub show_toast: bool,
+    pub hidden: bool,
+    /// Where the advancement is shown in its tab
+    pub x: f32,
+    pub y: f32,
+}
+
+pub struct AdvancementProgressEntry<'a> {
+    pub id: &'a str,
+    /// Every criterion, with when it was met in milliseconds since the unix epoch
+    pub criteria: Vec<(&'a str, Option<i64>)>,
+}
+
+impl<'a> CUpdateAdvancements<'a> {
+    pub fn new(
+        reset: bool,
+        added: &'a [AdvancementEntry<'a>],
+        removed: &'a [&'a str],
+        progress: &'a [AdvancementProgressEntry<'a>],
+    ) -> Self {
+        Self {
+            reset,
+            added,
+            removed,
+            progress,
+        }
+    }
+}
+
+impl<'a> ClientPacket for CUpdateAdvancements<'a> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_bool(self.reset);
+        bytebuf.put_list::<AdvancementEntry>(self.added, |p, v| {
+            p.put_string(v.id);
+            p.put_option(&v.parent, |p, v| p.put_string(v));
+            p.put_option(&v.display, |p, v| {
+                p.put_slice(&v.title.encode());
+                p.put_slice(&v.description.encode());
+                p.put_serialize(&v.icon);
+                p.put_var_int(&v.frame);
+                let mut flags = 0;
+                if v.background.is_some() {
+                    flags |= 0x01;
+                }
+                if v.show_toast {
+                    flags |= 0x02;
+                }
+                if v.hidden {
+                    flags |= 0x04;
+                }
+                p.put_i32(flags);
+                if let Some(background) = v.background {
+                    p.put_string(background);
+                }
+                p.put_f32(v.x);
+                p.put_f32(v.y);
+            });
+            p.put_list::<Vec<String>>(v.requirements, |p, v| {
+                p.put_list::<String>(v, |p, v| p.put_string(v));
+            });
+            p.put_bool(v.sends_telemetry_data);
+        });
+        bytebuf.put_list::<&str>(self.removed, |p, v| p.put_string(v));
+        bytebuf.put_list::<AdvancementProgressEntry>(self.progress, |p, v| {
+            p.put_string(v.id);
+            p.put_list::<(&str, Option<i64>)>(&v.criteria, |p, (criterion, date)| {
+                p.put_string(criterion);
+                p.put_option(date, |p, v| p.put_i64(*v));
+            });
+        });
+    }
+}
@@ -0,0 +1,49 @@
+use pumpkin_macros::packet;
+
+use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt};
+
+/// Sets the attributes of an entity, e.g. its movement speed
+#[packet(0x75)]
+pub struct CUpdateAttributes<'a> {
+    entity_id: VarInt,
+    attributes: &'a [Attribute<'a>],
+}
+
+pub struct Attribute<'a> {
+    /// The protocol id of the `minecraft:attribute` registry
+    pub id: VarInt,
+    pub base_value: f64,
+    pub modifiers: &'a [AttributeModifier<'a>],
+}
+
+pub struct AttributeModifier<'a> {
+    /// Identifies the modifier, e.g. `minecraft:effect.speed`
+    pub id: &'a str,
+    pub amount: f64,
+    /// 0 adds the amount, 1 multiplies the base value and 2 the total value
+    pub operation: i8,
+}
+
+impl<'a> CUpdateAttributes<'a> {
+    pub fn new(entity_id: VarInt, attributes: &'a [Attribute<'a>]) -> Self {
+        Self {
+            entity_id,
+            attributes,
+        }
+    }
+}
+
+impl<'a> ClientPacket for CUpdateAttributes<'a> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_var_int(&self.entity_id);
+        bytebuf.put_list::<Attribute>(self.attributes, |p, v| {
+            p.put_var_int(&v.id);
+            p.put_f64(v.base_value);
+            p.put_list::<AttributeModifier>(v.modifiers, |p, v| {
+                p.put_string(v.id);
+                p.put_f64(v.amount);
+                p.put_i8(v.operation);
+            });
+        });
+    }
+}
@@ -0,0 +1,105 @@
+use pumpkin_macros::packet;
+use pumpkin_world::{
+    chunk::{
+        light::{LightArray, SECTION_LIGHT_BYTES},
+        ChunkData, ChunkLight,
+    },
+    WORLD_HEIGHT,
+};
+
+use crate::{bytebuf::ByteBuffer, BitSet, ClientPacket, VarInt};
+
+/// The sections of the world plus the one below and the one above it, light reaches into those
+const LIGHT_SECTIONS: usize = WORLD_HEIGHT / 16 + 2;
+
+/// All sections, including the ones below and above the world
+pub(crate) const ALL_LIGHT_SECTIONS: u64 = (1 << LIGHT_SECTIONS) - 1;
+
+/// The sky above the world is always fully lit
+static FULL_SKY: [u8; SECTION_LIGHT_BYTES] = [0xFF; SECTION_LIGHT_BYTES];
+
+/// Updates the light of some sections of a chunk the client already has
+#[packet(0x2A)]
+pub struct CUpdateLight<'a> {
+    chunk: &'a ChunkData,
+    sections: u64,
+}
+
+impl<'a> CUpdateLight<'a> {
+    /// Bit i of `sections` stands for section i of the world
+    pub fn new(chunk: &'a ChunkData, sections: u32) -> Self {
+        Self {
+            chunk,
+            // The lowest bit is the section below the world
+            sections: (sections as u64) << 1,
+        }
+    }
+}
+
+impl<'a> ClientPacket for CUpdateLight<'a> {
+    fn write(&self, buf: &mut ByteBuffer) {
+        buf.put_var_int(&VarInt(self.chunk.position.x));
+        buf.put_var_int(&VarInt(self.chunk.position.z));
+        write_light_data(buf, &self.chunk.light, self.sections);
+    }
+}
+
+/// Writes the light of the given sections, bit 0 of `sections` stands for the section below the world.
+/// Sections without any light are only marked as empty
+pub(crate) fn write_light_data(buf: &mut ByteBuffer, light: &ChunkLight, sections: u64) {
+    let mut sky = LightMasks::default();
+    let mut block = LightMasks::default();
+    for section in (0..LIGHT_SECTIONS).filter(|section| sections & (1 << section) != 0) {
+        let bit = 1 << section;
+        if let Some(sky_light) = &light.sky {
+            if section == LIGHT_SECTIONS - 1 {
+                sky.add(bit, &FULL_SKY);
+            } else {
+                sky.add_section(bit, sky_light, section);
+            }
+        }
+        block.add_section(bit, &light.block, section);
+    }
+
+    buf.put_bit_set(&BitSet(VarInt(1), &[sky.mask]));
+    buf.put_bit_set(&BitSet(VarInt(1), &[block.mask]));
+    buf.put_bit_set(&BitSet(VarInt(1), &[sky.empty_mask]));
+    buf.put_bit_set(&BitSet(VarInt(1), &[block.empty_mask]));
+    sky.write_arrays(buf);
+    block.write_arrays(buf);
+}
+
+#[derive(Default)]
+struct LightMasks<'a> {
+    mask: i64,
+    empty_mask: i64,
+    arrays: Vec<&'a [u8]>,
+}
+
+impl<'a> LightMasks<'a> {
+    fn add(&mut self, bit: i64, array: &'a [u8]) {
+        if array.iter().all(|byte| *byte == 0) {
+            self.empty_mask |= bit;
+        } else {
+            self.mask |= bit;
+            self.arrays.push(array);
+        }
+    }
+
+    /// Sections outside the world have no light of their own
+    fn add_section(&mut self, bit: i64, light: &'a LightArray, section: usize) {
+        if section == 0 || section == LIGHT_SECTIONS - 1 {
+            self.empty_mask |= bit;
+        } else {
+            self.add(bit, light.section(section - 1));
+        }
+    }
+
+    fn write_arrays(&self, buf: &mut ByteBuffer) {
+        buf.put_var_int(&VarInt(self.arrays.len() as i32));
+        for array in &self.arrays {
+            buf.put_var_int(&VarInt(array.len() as i32));
+            buf.put_slice(array);
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use pumpkin_core::text::TextComponent;
+use pumpkin_macros::packet;
+
+use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt};
+
+/// Creates, changes or removes an objective
+#[packet(0x5E)]
+pub struct CUpdateObjectives<'a> {
+    objective_name: &'a str,
+    mode: i8,
+    /// The display name and how the scores are shown, unless it gets removed
+    display: Option<(TextComponent<'a>, VarInt)>,
+}
+
+impl<'a> CUpdateObjectives<'a> {
+    /// `render_type` is 0 for numbers and 1 for hearts
+    pub fn create(
+        objective_name: &'a str,
+        display_name: TextComponent<'a>,
+        render_type: VarInt,
+    ) -> Self {
+        Self {
+            objective_name,
+            mode: 0,
+            display: Some((display_name, render_type)),
+        }
+    }
+
+    pub fn remove(objective_name: &'a str) -> Self {
+        Self {
+            objective_name,
+            mode: 1,
+            display: None,
+        }
+    }
+
+    pub fn update(
+        objective_name: &'a str,
+        display_name: TextComponent<'a>,
+        render_type: VarInt,
+    ) -> Self {
+        Self {
+            objective_name,
+            mode: 2,
+            display: Some((display_name, render_type)),
+        }
+    }
+}
+
+impl<'a> ClientPacket for CUpdateObjectives<'a> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_string(self.objective_name);
+        bytebuf.put_i8(self.mode);
+        if let Some((display_name, render_type)) = &self.display {
+            bytebuf.put_slice(&display_name.encode());
+            bytebuf.put_var_int(render_type);
+            // The scores use the default number format
+            bytebuf.put_bool(false);
+        }
+    }
+}
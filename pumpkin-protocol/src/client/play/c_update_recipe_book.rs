@@ -0,0 +1,59 @@
+use pumpkin_macros::packet;
+
+use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt};
+
+/// Unlocks or locks recipes in the recipe book of the client
+#[packet(0x41)]
+pub struct CUpdateRecipeBook<'a> {
+    action: RecipeBookAction,
+    settings: &'a RecipeBookSettings,
+    recipes: &'a [&'a str],
+    /// The recipes shown as new, only sent with `RecipeBookAction::Init`
+    highlighted: &'a [&'a str],
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RecipeBookAction {
+    /// Replaces every unlocked recipe
+    Init,
+    Add,
+    Remove,
+}
+
+/// Whether the recipe book of every kind of window is open and only shows what can be crafted.
+/// Indexed by the crafting table, the furnace, the blast furnace and the smoker
+#[derive(Clone, Copy, Default)]
+pub struct RecipeBookSettings {
+    pub open: [bool; 4],
+    pub filtering: [bool; 4],
+}
+
+impl<'a> CUpdateRecipeBook<'a> {
+    pub fn new(
+        action: RecipeBookAction,
+        settings: &'a RecipeBookSettings,
+        recipes: &'a [&'a str],
+        highlighted: &'a [&'a str],
+    ) -> Self {
+        Self {
+            action,
+            settings,
+            recipes,
+            highlighted,
+        }
+    }
+}
+
+impl<'a> ClientPacket for CUpdateRecipeBook<'a> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_var_int(&VarInt(self.action as i32));
+        for book in 0..4 {
+            bytebuf.put_bool(self.settings.open[book]);
+            bytebuf.put_bool(self.settings.filtering[book]);
+        }
+        bytebuf.put_list::<&str>(self.recipes, |p, v| p.put_string(v));
+        if self.action == RecipeBookAction::Init {
+            bytebuf.put_list::<&str>(self.highlighted, |p, v| p.put_string(v));
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use pumpkin_macros::packet;
+
+use crate::{bytebuf::ByteBuffer, slot::Slot, ClientPacket, VarInt};
+
+/// Tells the client every recipe, the recipe book only shows those it knows
+#[packet(0x77)]
+pub struct CUpdateRecipes<'a> {
+    recipes: &'a [RecipeEntry<'a>],
+}
+
+pub struct RecipeEntry<'a> {
+    pub id: &'a str,
+    pub data: RecipeData<'a>,
+}
+
+pub enum RecipeData<'a> {
+    Shaped {
+        group: &'a str,
+        /// 0 for building, 1 for redstone, 2 for equipment and 3 for misc
+        category: VarInt,
+        width: VarInt,
+        height: VarInt,
+        /// Row by row, every ingredient is any of its items
+        ingredients: Vec<Vec<Slot>>,
+        result: Slot,
+        show_notification: bool,
+    },
+    Shapeless {
+        group: &'a str,
+        category: VarInt,
+        ingredients: Vec<Vec<Slot>>,
+        result: Slot,
+    },
+    /// Recipes of furnaces, blast furnaces and smokers
+    Cooking {
+        block: CookingBlock,
+        group: &'a str,
+        /// 0 for food, 1 for blocks and 2 for misc
+        category: VarInt,
+        /// Any of these items
+        ingredient: Vec<Slot>,
+        result: Slot,
+        experience: f32,
+        cooking_time: VarInt,
+    },
+}
+
+#[derive(Clone, Copy)]
+pub enum CookingBlock {
+    Furnace,
+    BlastFurnace,
+    Smoker,
+}
+
+impl RecipeData<'_> {
+    /// The id in the `minecraft:recipe_serializer` registry
+    const fn serializer(&self) -> i32 {
+        match self {
+            Self::Shaped { .. } => 0,
+            Self::Shapeless { .. } => 1,
+            Self::Cooking { block, .. } => match block {
+                CookingBlock::Furnace => 15,
+                CookingBlock::BlastFurnace => 16,
+                CookingBlock::Smoker => 17,
+            },
+        }
+    }
+}
+
+impl<'a> CUpdateRecipes<'a> {
+    pub fn new(recipes: &'a [RecipeEntry<'a>]) -> Self {
+        Self { recipes }
+    }
+}
+
+impl<'a> ClientPacket for CUpdateRecipes<'a> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        let put_ingredient = |p: &mut ByteBuffer, v: &Vec<Slot>| {
+            p.put_list::<Slot>(v, |p, v| p.put_serialize(v));
+        };
+        bytebuf.put_list::<RecipeEntry>(self.recipes, |p, v| {
+            p.put_string(v.id);
+            p.put_var_int(&v.data.serializer().into());
+            match &v.data {
+                RecipeData::Shaped {
+                    group,
+                    category,
+                    width,
+                    height,
+                    ingredients,
+                    result,
+                    show_notification,
+                } => {
+                    p.put_string(group);
+                    p.put_var_int(category);
+                    p.put_var_int(width);
+                    p.put_var_int(height);
+                    for ingredient in ingredients {
+                        put_ingredient(p, ingredient);
+                    }
+                    p.put_serialize(result);
+                    p.put_bool(*show_notification);
+                }
+                RecipeData::Shapeless {
+                    group,
+                    category,
+                    ingredients,
+                    result,
+                } => {
+                    p.put_string(group);
+                    p.put_var_int(category);
+                    p.put_list::<Vec<Slot>>(ingredients, put_ingredient);
+                    p.put_serialize(result);
+                }
+                RecipeData::Cooking {
+                    block: _,
+                    group,
+                    category,
+                    ingredient,
+                    result,
+                    experience,
+                    cooking_time,
+                } => {
+                    p.put_string(group);
+                    p.put_var_int(category);
+                    put_ingredient(p, ingredient);
+                    p.put_serialize(result);
+                    p.put_f32(*experience);
+                    p.put_var_int(cooking_time);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,32 @@
+use pumpkin_macros::packet;
+
+use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt};
+
+/// Sets the score of a holder in an objective, holders are mostly player names
+#[packet(0x61)]
+pub struct CUpdateScore<'a> {
+    entity_name: &'a str,
+    objective_name: &'a str,
+    value: VarInt,
+}
+
+impl<'a> CUpdateScore<'a> {
+    pub fn new(entity_name: &'a str, objective_name: &'a str, value: VarInt) -> Self {
+        Self {
+            entity_name,
+            objective_name,
+            value,
+        }
+    }
+}
+
+impl<'a> ClientPacket for CUpdateScore<'a> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_string(self.entity_name);
+        bytebuf.put_string(self.objective_name);
+        bytebuf.put_var_int(&self.value);
+        // The holder's name is shown and the number formatted like the objective says
+        bytebuf.put_bool(false);
+        bytebuf.put_bool(false);
+    }
+}
@@ -0,0 +1,35 @@
+use pumpkin_core::math::vector3::Vector3;
+use pumpkin_macros::packet;
+
+use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt};
+
+/// Changes many blocks of one chunk section at once, instead of a block update for every block
+#[packet(0x49)]
+pub struct CUpdateSectionBlocks<'a> {
+    /// The position of the section, in sections
+    section: Vector3<i32>,
+    /// The changed blocks by their position in the world and their new state id
+    blocks: &'a [(Vector3<i32>, i32)],
+}
+
+impl<'a> CUpdateSectionBlocks<'a> {
+    pub fn new(section: Vector3<i32>, blocks: &'a [(Vector3<i32>, i32)]) -> Self {
+        Self { section, blocks }
+    }
+}
+
+impl<'a> ClientPacket for CUpdateSectionBlocks<'a> {
+    fn write(&self, buf: &mut ByteBuffer) {
+        let Vector3 { x, y, z } = self.section;
+        buf.put_i64(
+            ((i64::from(x) & 0x3F_FFFF) << 42)
+                | ((i64::from(z) & 0x3F_FFFF) << 20)
+                | (i64::from(y) & 0xF_FFFF),
+        );
+        buf.put_var_int(&VarInt(self.blocks.len() as i32));
+        for (position, state) in self.blocks {
+            let relative = ((position.x & 15) << 8) | ((position.z & 15) << 4) | (position.y & 15);
+            buf.put_var_long((i64::from(*state) << 12) | i64::from(relative));
+        }
+    }
+}
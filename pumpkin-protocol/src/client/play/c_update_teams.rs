@@ -0,0 +1,76 @@
+use pumpkin_core::text::TextComponent;
+use pumpkin_macros::packet;
+
+use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt};
+
+/// Creates, changes or removes a team or changes its members
+#[packet(0x60)]
+pub struct CUpdateTeams<'a> {
+    team_name: &'a str,
+    method: TeamMethod<'a>,
+}
+
+pub enum TeamMethod<'a> {
+    Create(TeamInfo<'a>, &'a [String]),
+    Remove,
+    Update(TeamInfo<'a>),
+    AddEntities(&'a [String]),
+    RemoveEntities(&'a [String]),
+}
+
+/// How the names of the members look and which rules they follow
+pub struct TeamInfo<'a> {
+    pub display_name: TextComponent<'a>,
+    /// 0x01 allows friendly fire, 0x02 lets members see invisible members
+    pub friendly_flags: i8,
+    /// `always`, `never`, `hideForOtherTeams` or `hideForOwnTeam`
+    pub name_tag_visibility: &'a str,
+    /// `always`, `never`, `pushOtherTeams` or `pushOwnTeam`
+    pub collision_rule: &'a str,
+    /// The id of the color of the names, 21 leaves them white
+    pub color: VarInt,
+    pub prefix: TextComponent<'a>,
+    pub suffix: TextComponent<'a>,
+}
+
+impl<'a> CUpdateTeams<'a> {
+    pub fn new(team_name: &'a str, method: TeamMethod<'a>) -> Self {
+        Self { team_name, method }
+    }
+}
+
+fn put_team_info(bytebuf: &mut ByteBuffer, info: &TeamInfo) {
+    bytebuf.put_slice(&info.display_name.encode());
+    bytebuf.put_i8(info.friendly_flags);
+    bytebuf.put_string(info.name_tag_visibility);
+    bytebuf.put_string(info.collision_rule);
+    bytebuf.put_var_int(&info.color);
+    bytebuf.put_slice(&info.prefix.encode());
+    bytebuf.put_slice(&info.suffix.encode());
+}
+
+impl<'a> ClientPacket for CUpdateTeams<'a> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_string(self.team_name);
+        match &self.method {
+            TeamMethod::Create(info, entities) => {
+                bytebuf.put_i8(0);
+                put_team_info(bytebuf, info);
+                bytebuf.put_list::<String>(entities, |p, v| p.put_string(v));
+            }
+            TeamMethod::Remove => bytebuf.put_i8(1),
+            TeamMethod::Update(info) => {
+                bytebuf.put_i8(2);
+                put_team_info(bytebuf, info);
+            }
+            TeamMethod::AddEntities(entities) => {
+                bytebuf.put_i8(3);
+                bytebuf.put_list::<String>(entities, |p, v| p.put_string(v));
+            }
+            TeamMethod::RemoveEntities(entities) => {
+                bytebuf.put_i8(4);
+                bytebuf.put_list::<String>(entities, |p, v| p.put_string(v));
+            }
+        }
+    }
+}
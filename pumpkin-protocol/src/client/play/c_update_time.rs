@@ -0,0 +1,25 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+#[derive(Serialize)]
+#[packet(0x64)]
+pub struct CUpdateTime {
+    world_age: i64,
+    time_of_day: i64,
+}
+
+impl CUpdateTime {
+    /// Clients keep advancing the time between updates, unless `advancing` is false
+    pub fn new(world_age: i64, time_of_day: i64, advancing: bool) -> Self {
+        // A negative time of day stops the sun, -0 would be the same as 0
+        let time_of_day = match (advancing, time_of_day) {
+            (true, _) => time_of_day,
+            (false, 0) => -1,
+            (false, time_of_day) => -time_of_day,
+        };
+        Self {
+            world_age,
+            time_of_day,
+        }
+    }
+}
@@ -1,89 +1,151 @@
 mod c_acknowledge_block;
 mod c_actionbar;
+mod c_award_statistics;
+mod c_block_action;
 mod c_block_destroy_stage;
+mod c_block_entity_data;
 mod c_block_update;
 mod c_center_chunk;
 mod c_change_difficulty;
 mod c_chunk_data;
 mod c_close_container;
 mod c_disguised_chat_message;
+mod c_display_objective;
 mod c_entity_animation;
+mod c_entity_effect;
 mod c_entity_metadata;
 mod c_entity_status;
 mod c_entity_velocity;
+mod c_explosion;
 mod c_game_event;
 mod c_head_rot;
 mod c_hurt_animation;
 mod c_keep_alive;
 mod c_login;
+mod c_move_vehicle;
 mod c_open_screen;
+mod c_open_sign_editor;
 mod c_particle;
+mod c_pickup_item;
 mod c_ping_response;
+mod c_place_ghost_recipe;
 mod c_play_disconnect;
 mod c_player_abilities;
 mod c_player_chat_message;
 mod c_player_info_update;
 mod c_player_remove;
 mod c_remove_entities;
+mod c_remove_entity_effect;
+mod c_reset_score;
+mod c_respawn;
+mod c_set_chunk_cache_radius;
 mod c_set_container_content;
 mod c_set_container_property;
 mod c_set_container_slot;
+mod c_set_equipment;
+mod c_set_experience;
 mod c_set_health;
 mod c_set_held_item;
+mod c_set_passengers;
+mod c_set_simulation_distance;
 mod c_set_title;
+mod c_spawn_experience_orb;
 mod c_spawn_player;
 mod c_subtitle;
 mod c_sync_player_position;
 mod c_system_chat_message;
+mod c_tab_list_header_footer;
 mod c_teleport_entity;
+mod c_transfer;
 mod c_unload_chunk;
+mod c_update_advancements;
+mod c_update_attributes;
 mod c_update_entity_pos;
 mod c_update_entity_pos_rot;
 mod c_update_entity_rot;
+mod c_update_light;
+mod c_update_objectives;
+mod c_update_recipe_book;
+mod c_update_recipes;
+mod c_update_score;
+mod c_update_section_blocks;
+mod c_update_teams;
+mod c_update_time;
 mod c_worldevent;
 mod player_action;
 
 pub use c_acknowledge_block::*;
 pub use c_actionbar::*;
+pub use c_award_statistics::*;
+pub use c_block_action::*;
 pub use c_block_destroy_stage::*;
+pub use c_block_entity_data::*;
 pub use c_block_update::*;
 pub use c_center_chunk::*;
 pub use c_change_difficulty::*;
 pub use c_chunk_data::*;
 pub use c_close_container::*;
 pub use c_disguised_chat_message::*;
+pub use c_display_objective::*;
 pub use c_entity_animation::*;
+pub use c_entity_effect::*;
 pub use c_entity_metadata::*;
 pub use c_entity_status::*;
 pub use c_entity_velocity::*;
+pub use c_explosion::*;
 pub use c_game_event::*;
 pub use c_head_rot::*;
 pub use c_hurt_animation::*;
 pub use c_keep_alive::*;
 pub use c_login::*;
+pub use c_move_vehicle::*;
 pub use c_open_screen::*;
+pub use c_open_sign_editor::*;
 pub use c_particle::*;
+pub use c_pickup_item::*;
 pub use c_ping_response::*;
+pub use c_place_ghost_recipe::*;
 pub use c_play_disconnect::*;
 pub use c_player_abilities::*;
 pub use c_player_chat_message::*;
 pub use c_player_info_update::*;
 pub use c_player_remove::*;
 pub use c_remove_entities::*;
+pub use c_remove_entity_effect::*;
+pub use c_reset_score::*;
+pub use c_respawn::*;
+pub use c_set_chunk_cache_radius::*;
 pub use c_set_container_content::*;
 pub use c_set_container_property::*;
 pub use c_set_container_slot::*;
+pub use c_set_equipment::*;
+pub use c_set_experience::*;
 pub use c_set_health::*;
 pub use c_set_held_item::*;
+pub use c_set_passengers::*;
+pub use c_set_simulation_distance::*;
 pub use c_set_title::*;
+pub use c_spawn_experience_orb::*;
 pub use c_spawn_player::*;
 pub use c_subtitle::*;
 pub use c_sync_player_position::*;
 pub use c_system_chat_message::*;
+pub use c_tab_list_header_footer::*;
 pub use c_teleport_entity::*;
+pub use c_transfer::*;
 pub use c_unload_chunk::*;
+pub use c_update_advancements::*;
+pub use c_update_attributes::*;
 pub use c_update_entity_pos::*;
 pub use c_update_entity_pos_rot::*;
 pub use c_update_entity_rot::*;
+pub use c_update_light::*;
+pub use c_update_objectives::*;
+pub use c_update_recipe_book::*;
+pub use c_update_recipes::*;
+pub use c_update_score::*;
+pub use c_update_section_blocks::*;
+pub use c_update_teams::*;
+pub use c_update_time::*;
 pub use c_worldevent::*;
 pub use player_action::*;
@@ -5,7 +5,8 @@ pub enum PlayerAction<'a> {
         name: &'a str,
         properties: &'a [Property],
     },
-    InitializeChat(u8),
+    /// None removes the chat session
+    InitializeChat(Option<InitChat<'a>>),
     /// Gamemode ?
     UpdateGameMode(VarInt),
     /// Listed ?
@@ -13,3 +14,12 @@ pub enum PlayerAction<'a> {
     UpdateLatency(u8),
     UpdateDisplayName(u8),
 }
+
+/// The chat session of a player, lets other clients verify the player's messages
+pub struct InitChat<'a> {
+    pub session_id: uuid::Uuid,
+    /// When the public key expires, in epoch milliseconds
+    pub expires_at: i64,
+    pub public_key: &'a [u8],
+    pub key_signature: &'a [u8],
+}
@@ -10,6 +10,7 @@ pub mod packet_decoder;
 pub mod packet_encoder;
 pub mod server;
 pub mod slot;
+pub mod version;
 
 /// To current Minecraft protocol
 /// Don't forget to change this when porting
@@ -142,6 +143,10 @@ pub enum PacketError {
     FailedWrite,
     #[error("failed to flush decoder")]
     FailedFinish,
+    #[error("failed to compress packet")]
+    CompressionFailed,
+    #[error("failed to decompress packet")]
+    DecompressionFailed,
     #[error("failed to write encoded packet to connection")]
     ConnectionWrite,
     #[error("packet exceeds maximum length")]
@@ -200,7 +205,11 @@ pub struct StatusResponse {
     /// The icon displayed, Optional
     pub favicon: Option<String>,
     /// Players are forced to use Secure chat
+    #[serde(rename = "enforcesSecureChat")]
     pub enforce_secure_chat: bool,
+    /// Tells clients that chat messages are never signed, so they don't warn about it
+    #[serde(rename = "preventsChatReports")]
+    pub prevents_chat_reports: bool,
 }
 #[derive(Serialize)]
 pub struct Version {
@@ -221,7 +230,7 @@ pub struct Players {
     pub sample: Vec<Sample>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, PartialEq)]
 pub struct Sample {
     /// Players Name
     pub name: String,
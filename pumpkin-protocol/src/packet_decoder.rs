@@ -1,10 +1,8 @@
 use aes::cipher::{generic_array::GenericArray, BlockDecryptMut, BlockSizeUser, KeyIvInit};
 use bytes::{Buf, BytesMut};
 
-use std::io::Write;
-
 use bytes::BufMut;
-use flate2::write::ZlibDecoder;
+use libdeflater::Decompressor;
 
 use crate::{
     bytebuf::ByteBuffer, PacketError, RawPacket, VarInt, VarIntDecodeError, MAX_PACKET_SIZE,
@@ -20,6 +18,8 @@ pub struct PacketDecoder {
     buf: BytesMut,
     decompress_buf: BytesMut,
     compression: bool,
+    /// Created once compression gets enabled and reused for every packet
+    decompressor: Option<Decompressor>,
     cipher: Option<Cipher>,
 }
 
@@ -60,11 +60,14 @@ impl PacketDecoder {
 
                 self.decompress_buf.put_bytes(0, data_len as usize);
 
-                // TODO: use libdeflater or zune-inflate?
-                let mut z = ZlibDecoder::new(&mut self.decompress_buf[..]);
-
-                z.write_all(r).map_err(|_| PacketError::FailedWrite)?;
-                z.finish().map_err(|_| PacketError::FailedFinish)?;
+                let decompressor = self.decompressor.get_or_insert_with(Decompressor::new);
+                let decompressed_len = decompressor
+                    .zlib_decompress(r, &mut self.decompress_buf)
+                    .map_err(|_| PacketError::DecompressionFailed)?;
+                // The client lied about the uncompressed size
+                if decompressed_len != data_len as usize {
+                    Err(PacketError::DecompressionFailed)?
+                }
 
                 let total_packet_len = VarInt(packet_len).written_size() + packet_len as usize;
 
@@ -117,6 +120,7 @@ impl PacketDecoder {
     /// Sets ZLib Deompression
     pub fn set_compression(&mut self, compression: bool) {
         self.compression = compression;
+        self.decompressor = compression.then(Decompressor::new);
     }
 
     fn decrypt_bytes(cipher: &mut Cipher, bytes: &mut [u8]) {
@@ -4,10 +4,7 @@ use aes::cipher::{generic_array::GenericArray, BlockEncryptMut, BlockSizeUser, K
 use bytes::{BufMut, BytesMut};
 use pumpkin_config::compression::CompressionInfo;
 
-use std::io::Read;
-
-use flate2::bufread::ZlibEncoder;
-use flate2::Compression;
+use libdeflater::{CompressionLvl, Compressor};
 
 use crate::{bytebuf::ByteBuffer, ClientPacket, PacketError, VarInt, MAX_PACKET_SIZE};
 
@@ -19,18 +16,30 @@ type Cipher = cfb8::Encryptor<aes::Aes128>;
 #[derive(Default)]
 pub struct PacketEncoder {
     buf: BytesMut,
+    /// Scratch buffer for compressed packets, kept around so we don't allocate for every packet
     compress_buf: Vec<u8>,
     compression: Option<CompressionInfo>,
+    /// Created once compression gets enabled and reused for every packet
+    compressor: Option<Compressor>,
     cipher: Option<Cipher>,
 }
 
 impl PacketEncoder {
     pub fn append_packet<P: ClientPacket>(&mut self, packet: &P) -> Result<(), PacketError> {
+        self.append_packet_with_id(P::PACKET_ID, packet)
+    }
+
+    /// Appends a packet using a different id, used when talking to clients on older protocol versions
+    pub fn append_packet_with_id<P: ClientPacket>(
+        &mut self,
+        id: i32,
+        packet: &P,
+    ) -> Result<(), PacketError> {
         let start_len = self.buf.len();
         let mut writer = (&mut self.buf).writer();
 
         let mut packet_buf = ByteBuffer::empty();
-        VarInt(P::PACKET_ID)
+        VarInt(id)
             .encode(&mut writer)
             .map_err(|_| PacketError::EncodeID)?;
         packet.write(&mut packet_buf);
@@ -41,23 +50,24 @@ impl PacketEncoder {
 
         let data_len = self.buf.len() - start_len;
 
-        if let Some(compression) = &self.compression {
+        if let (Some(compression), Some(compressor)) = (&self.compression, &mut self.compressor) {
             if data_len > compression.threshold as usize {
-                let mut z =
-                    ZlibEncoder::new(&self.buf[start_len..], Compression::new(compression.level));
-
-                self.compress_buf.clear();
+                let bound = compressor.zlib_compress_bound(data_len);
+                if self.compress_buf.len() < bound {
+                    self.compress_buf.resize(bound, 0);
+                }
+                let compressed_len = compressor
+                    .zlib_compress(&self.buf[start_len..], &mut self.compress_buf)
+                    .map_err(|_| PacketError::CompressionFailed)?;
 
                 let data_len_size = VarInt(data_len as i32).written_size();
 
-                let packet_len = data_len_size + z.read_to_end(&mut self.compress_buf).unwrap();
+                let packet_len = data_len_size + compressed_len;
 
                 if packet_len >= MAX_PACKET_SIZE as usize {
                     Err(PacketError::TooLong)?
                 }
 
-                drop(z);
-
                 self.buf.truncate(start_len);
 
                 let mut writer = (&mut self.buf).writer();
@@ -68,7 +78,8 @@ impl PacketEncoder {
                 VarInt(data_len as i32)
                     .encode(&mut writer)
                     .map_err(|_| PacketError::EncodeData)?;
-                self.buf.extend_from_slice(&self.compress_buf);
+                self.buf
+                    .extend_from_slice(&self.compress_buf[..compressed_len]);
             } else {
                 let data_len_size = 1;
                 let packet_len = data_len_size + data_len;
@@ -118,6 +129,27 @@ impl PacketEncoder {
         Ok(())
     }
 
+    /// Encodes a packet on its own, so the same bytes can be appended to many encoders with `append_encoded`.
+    /// The packet is compressed like this encoder compresses packets
+    pub fn encode_packet_with_id<P: ClientPacket>(
+        &mut self,
+        id: i32,
+        packet: &P,
+    ) -> Result<BytesMut, PacketError> {
+        let start_len = self.buf.len();
+        self.append_packet_with_id(id, packet)?;
+        Ok(self.buf.split_off(start_len))
+    }
+
+    /// Appends a packet encoded by `encode_packet_with_id`, from an encoder using the same compression
+    pub fn append_encoded(&mut self, encoded: &[u8]) {
+        self.buf.extend_from_slice(encoded);
+    }
+
+    pub const fn compression(&self) -> Option<&CompressionInfo> {
+        self.compression.as_ref()
+    }
+
     pub fn set_encryption(&mut self, key: Option<&[u8; 16]>) {
         if let Some(key) = key {
             assert!(self.cipher.is_none(), "encryption is already enabled");
@@ -132,6 +164,9 @@ impl PacketEncoder {
 
     /// Enables ZLib Compression
     pub fn set_compression(&mut self, compression: Option<CompressionInfo>) {
+        self.compressor = compression.as_ref().map(|compression| {
+            Compressor::new(CompressionLvl::new(compression.level as i32).unwrap_or_default())
+        });
         self.compression = compression;
     }
 
@@ -0,0 +1,23 @@
+use bytes::Bytes;
+use pumpkin_macros::packet;
+
+use crate::{
+    bytebuf::{ByteBuffer, DeserializerError},
+    server::login::get_cookie_payload,
+    Identifier, ServerPacket,
+};
+
+#[packet(0x01)]
+pub struct SConfigCookieResponse {
+    pub key: Identifier,
+    pub payload: Option<Bytes>,
+}
+
+impl ServerPacket for SConfigCookieResponse {
+    fn read(bytebuf: &mut ByteBuffer) -> Result<Self, DeserializerError> {
+        Ok(Self {
+            key: bytebuf.get_string()?,
+            payload: bytebuf.get_option(get_cookie_payload)?,
+        })
+    }
+}
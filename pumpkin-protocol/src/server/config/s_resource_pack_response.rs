@@ -0,0 +1,21 @@
+use pumpkin_macros::packet;
+
+use crate::{
+    bytebuf::{ByteBuffer, DeserializerError},
+    ServerPacket, VarInt,
+};
+
+#[packet(0x06)]
+pub struct SConfigResourcePackResponse {
+    pub uuid: uuid::Uuid,
+    pub result: VarInt,
+}
+
+impl ServerPacket for SConfigResourcePackResponse {
+    fn read(bytebuf: &mut ByteBuffer) -> Result<Self, DeserializerError> {
+        Ok(Self {
+            uuid: bytebuf.get_uuid()?,
+            result: bytebuf.get_var_int()?,
+        })
+    }
+}
@@ -1,8 +1,10 @@
+mod s_cookie_response;
 mod s_encryption_response;
 mod s_login_response;
 mod s_login_start;
 mod s_plugin_response;
 
+pub use s_cookie_response::*;
 pub use s_encryption_response::*;
 pub use s_login_response::*;
 pub use s_login_start::*;
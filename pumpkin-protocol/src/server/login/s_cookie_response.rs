@@ -0,0 +1,38 @@
+use bytes::Bytes;
+use pumpkin_macros::packet;
+
+use crate::{
+    bytebuf::{ByteBuffer, DeserializerError},
+    Identifier, ServerPacket,
+};
+
+/// The maximum size of a cookie payload in bytes
+pub const MAX_COOKIE_SIZE: usize = 5120;
+
+#[packet(0x04)]
+pub struct SLoginCookieResponse {
+    pub key: Identifier,
+    pub payload: Option<Bytes>,
+}
+
+impl ServerPacket for SLoginCookieResponse {
+    fn read(bytebuf: &mut ByteBuffer) -> Result<Self, DeserializerError> {
+        Ok(Self {
+            key: bytebuf.get_string()?,
+            payload: bytebuf.get_option(get_cookie_payload)?,
+        })
+    }
+}
+
+/// Reads a length prefixed cookie payload, used by the Login and Config cookie responses
+pub(crate) fn get_cookie_payload(bytebuf: &mut ByteBuffer) -> Result<Bytes, DeserializerError> {
+    let len = bytebuf.get_var_int()?.0;
+    let len = usize::try_from(len)
+        .map_err(|_| DeserializerError::Message("Negative cookie length".to_string()))?;
+    if len > MAX_COOKIE_SIZE {
+        return Err(DeserializerError::Message(format!(
+            "Cookie payload is too big ({len} > {MAX_COOKIE_SIZE})"
+        )));
+    }
+    bytebuf.copy_to_bytes(len)
+}
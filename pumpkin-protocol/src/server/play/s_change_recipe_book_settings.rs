@@ -0,0 +1,13 @@
+use pumpkin_macros::packet;
+use serde::Deserialize;
+
+use crate::VarInt;
+
+#[derive(Deserialize)]
+#[packet(0x28)]
+pub struct SChangeRecipeBookSettings {
+    /// 0 for the crafting table, 1 for the furnace, 2 for the blast furnace and 3 for the smoker
+    pub book_id: VarInt,
+    pub book_open: bool,
+    pub filter_active: bool,
+}
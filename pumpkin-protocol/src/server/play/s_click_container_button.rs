@@ -0,0 +1,11 @@
+use pumpkin_macros::packet;
+use serde::Deserialize;
+
+/// Sent when the player clicks a button of a window, e.g. one of the options of an enchanting
+/// table
+#[derive(Deserialize)]
+#[packet(0x0D)]
+pub struct SClickContainerButton {
+    pub window_id: i8,
+    pub button_id: i8,
+}
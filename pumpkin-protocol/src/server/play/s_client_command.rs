@@ -0,0 +1,19 @@
+use num_derive::FromPrimitive;
+use pumpkin_macros::packet;
+use serde::Deserialize;
+
+use crate::VarInt;
+
+#[derive(Deserialize)]
+#[packet(0x09)]
+pub struct SClientCommand {
+    pub action_id: VarInt,
+}
+
+#[derive(FromPrimitive)]
+pub enum ClientCommandAction {
+    /// Sent when the player clicks respawn after dying
+    PerformRespawn = 0,
+    /// Sent when the player opens the statistics menu
+    RequestStats,
+}
@@ -0,0 +1,20 @@
+use pumpkin_macros::packet;
+
+use crate::{
+    bytebuf::{ByteBuffer, DeserializerError},
+    ServerPacket, VarInt,
+};
+
+#[packet(0x03)]
+pub struct SMessageAcknowledgement {
+    /// How many of the messages we sent were seen since the last acknowledgement
+    pub message_count: VarInt,
+}
+
+impl ServerPacket for SMessageAcknowledgement {
+    fn read(bytebuf: &mut ByteBuffer) -> Result<Self, DeserializerError> {
+        Ok(Self {
+            message_count: bytebuf.get_var_int()?,
+        })
+    }
+}
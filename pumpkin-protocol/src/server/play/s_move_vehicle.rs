@@ -0,0 +1,12 @@
+use pumpkin_macros::packet;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[packet(0x1E)]
+pub struct SMoveVehicle {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub yaw: f32,
+    pub pitch: f32,
+}
@@ -0,0 +1,9 @@
+use pumpkin_macros::packet;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[packet(0x1F)]
+pub struct SPaddleBoat {
+    pub left_paddle: bool,
+    pub right_paddle: bool,
+}
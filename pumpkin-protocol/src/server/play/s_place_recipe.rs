@@ -0,0 +1,13 @@
+use pumpkin_macros::packet;
+use serde::Deserialize;
+
+/// Sent when the player clicks a recipe in the recipe book, its ingredients are moved into the
+/// crafting grid
+#[derive(Deserialize)]
+#[packet(0x22)]
+pub struct SPlaceRecipe {
+    pub window_id: i8,
+    pub recipe: String,
+    /// Shift clicks place as many ingredients as possible
+    pub make_all: bool,
+}
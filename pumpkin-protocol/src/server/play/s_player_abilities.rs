@@ -0,0 +1,12 @@
+use pumpkin_macros::packet;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[packet(0x23)]
+pub struct SPlayerAbilities {
+    pub flags: i8,
+}
+
+impl SPlayerAbilities {
+    pub const FLYING: i8 = 0x02;
+}
@@ -0,0 +1,15 @@
+use pumpkin_macros::packet;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[packet(0x26)]
+pub struct SPlayerInput {
+    pub sideways: f32,
+    pub forward: f32,
+    pub flags: u8,
+}
+
+impl SPlayerInput {
+    pub const JUMP: u8 = 0x01;
+    pub const UNMOUNT: u8 = 0x02;
+}
@@ -0,0 +1,42 @@
+use bytes::Bytes;
+use pumpkin_macros::packet;
+
+use crate::{
+    bytebuf::{ByteBuffer, DeserializerError},
+    ServerPacket,
+};
+
+#[packet(0x07)]
+pub struct SPlayerSession {
+    pub session_id: uuid::Uuid,
+    /// When the public key expires, in epoch milliseconds
+    pub expires_at: i64,
+    /// The X.509 encoded public key
+    pub public_key: Bytes, // 512
+    /// The public key signed by Mojang
+    pub key_signature: Bytes, // 4096
+}
+
+impl ServerPacket for SPlayerSession {
+    fn read(bytebuf: &mut ByteBuffer) -> Result<Self, DeserializerError> {
+        Ok(Self {
+            session_id: bytebuf.get_uuid()?,
+            expires_at: bytebuf.get_i64()?,
+            public_key: get_prefixed_bytes(bytebuf, 512)?,
+            key_signature: get_prefixed_bytes(bytebuf, 4096)?,
+        })
+    }
+}
+
+fn get_prefixed_bytes(
+    bytebuf: &mut ByteBuffer,
+    max_len: usize,
+) -> Result<Bytes, DeserializerError> {
+    let len = bytebuf.get_var_int()?.0;
+    match usize::try_from(len) {
+        Ok(len) if len <= max_len => bytebuf.copy_to_bytes(len),
+        _ => Err(DeserializerError::Message(format!(
+            "Invalid byte array length {len} (max. {max_len})"
+        ))),
+    }
+}
@@ -0,0 +1,9 @@
+use pumpkin_macros::packet;
+use serde::Deserialize;
+
+/// Sent when the player types into the name field of an anvil, and when they put an item into it
+#[derive(Deserialize)]
+#[packet(0x2A)]
+pub struct SRenameItem {
+    pub item_name: String,
+}
@@ -1,10 +1,36 @@
 use pumpkin_macros::packet;
 
-use crate::slot::Slot;
+use crate::{
+    bytebuf::{ByteBuffer, DeserializerError},
+    slot::Slot,
+    ServerPacket,
+};
 
-#[derive(serde::Deserialize, Debug)]
+#[derive(Debug)]
 #[packet(0x32)]
 pub struct SSetCreativeSlot {
+    /// -1 if the item was dropped out of the creative menu
     pub slot: i16,
     pub clicked_item: Slot,
 }
+
+impl ServerPacket for SSetCreativeSlot {
+    fn read(bytebuf: &mut ByteBuffer) -> Result<Self, DeserializerError> {
+        let slot = bytebuf.get_i16()?;
+        let item_count = bytebuf.get_var_int()?;
+        if item_count.0 <= 0 {
+            return Ok(Self {
+                slot,
+                clicked_item: Slot::empty(),
+            });
+        }
+        let item_id = bytebuf.get_var_int()?;
+        // Components are not supported yet. The item is the last field, so the rest of the
+        // packet can be skipped and creative players still get the item without its components
+        bytebuf.get_slice();
+        Ok(Self {
+            slot,
+            clicked_item: Slot::new(item_count, item_id),
+        })
+    }
+}
@@ -0,0 +1,9 @@
+use pumpkin_macros::packet;
+use serde::Deserialize;
+
+/// Sent when the player looked at a recipe the recipe book showed as new
+#[derive(Deserialize)]
+#[packet(0x29)]
+pub struct SSetSeenRecipe {
+    pub recipe: String,
+}
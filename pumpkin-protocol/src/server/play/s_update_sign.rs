@@ -0,0 +1,14 @@
+use pumpkin_core::math::position::WorldPosition;
+use pumpkin_macros::packet;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[packet(0x35)]
+pub struct SUpdateSign {
+    pub location: WorldPosition,
+    pub is_front_text: bool,
+    pub line1: String,
+    pub line2: String,
+    pub line3: String,
+    pub line4: String,
+}
@@ -1,5 +1,5 @@
 use crate::VarInt;
-use pumpkin_world::item::ItemStack;
+use pumpkin_world::item::{Enchantment, ItemName, ItemStack};
 use serde::ser::SerializeSeq;
 use serde::{
     de::{self, SeqAccess},
@@ -12,10 +12,81 @@ pub struct Slot {
     item_id: Option<VarInt>,
     num_components_to_add: Option<VarInt>,
     num_components_to_remove: Option<VarInt>,
-    components_to_add: Option<Vec<(VarInt, ())>>, // The second type depends on the varint
+    components_to_add: Option<Vec<Component>>,
     components_to_remove: Option<Vec<VarInt>>,
 }
 
+/// The protocol ids of the data component types Pumpkin knows
+const DAMAGE: i32 = 3;
+const CUSTOM_NAME: i32 = 5;
+const ENCHANTMENTS: i32 = 9;
+const REPAIR_COST: i32 = 16;
+const STORED_ENCHANTMENTS: i32 = 23;
+
+/// The NBT tag type of strings, plain text components are sent as one
+const NBT_STRING: u8 = 8;
+
+/// A component the slot adds to its item, the data depends on its type
+#[derive(Debug, Clone)]
+enum Component {
+    /// `minecraft:enchantments`, or `minecraft:stored_enchantments` of enchanted books: the
+    /// registry ids of the enchantments with their levels
+    Enchantments {
+        stored: bool,
+        levels: Vec<(VarInt, VarInt)>,
+        show_in_tooltip: bool,
+    },
+    /// `minecraft:damage`: how much of its durability the item used up
+    Damage(VarInt),
+    /// `minecraft:custom_name`: a plain text component
+    CustomName(String),
+    /// `minecraft:repair_cost`: the levels anvils charge on top
+    RepairCost(VarInt),
+}
+
+impl Serialize for Component {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_seq(None)?;
+        match self {
+            Self::Enchantments {
+                stored,
+                levels,
+                show_in_tooltip,
+            } => {
+                s.serialize_element(&VarInt(if *stored {
+                    STORED_ENCHANTMENTS
+                } else {
+                    ENCHANTMENTS
+                }))?;
+                s.serialize_element(&VarInt(levels.len() as i32))?;
+                for (id, level) in levels {
+                    s.serialize_element(id)?;
+                    s.serialize_element(level)?;
+                }
+                s.serialize_element(show_in_tooltip)?;
+            }
+            Self::Damage(damage) => {
+                s.serialize_element(&VarInt(DAMAGE))?;
+                s.serialize_element(damage)?;
+            }
+            Self::CustomName(name) => {
+                s.serialize_element(&VarInt(CUSTOM_NAME))?;
+                s.serialize_element(&NBT_STRING)?;
+                s.serialize_element(&(name.len() as u16))?;
+                s.serialize_element(&RawBytes(name.as_bytes()))?;
+            }
+            Self::RepairCost(repair_cost) => {
+                s.serialize_element(&VarInt(REPAIR_COST))?;
+                s.serialize_element(repair_cost)?;
+            }
+        }
+        s.end()
+    }
+}
+
 impl<'de> Deserialize<'de> for Slot {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -55,10 +126,38 @@ impl<'de> Deserialize<'de> for Slot {
                 let num_components_to_remove = seq
                     .next_element::<VarInt>()?
                     .ok_or(de::Error::custom("Failed to decode VarInt"))?;
-                if num_components_to_add.0 != 0 || num_components_to_remove.0 != 0 {
-                    return Err(de::Error::custom(
-                        "Slot components are currently unsupported",
-                    ));
+                let mut components_to_add = Vec::new();
+                for _ in 0..num_components_to_add.0 {
+                    let component = match next_var_int(&mut seq)?.0 {
+                        DAMAGE => Component::Damage(next_var_int(&mut seq)?),
+                        CUSTOM_NAME => Component::CustomName(next_nbt_string(&mut seq)?),
+                        REPAIR_COST => Component::RepairCost(next_var_int(&mut seq)?),
+                        component_type @ (ENCHANTMENTS | STORED_ENCHANTMENTS) => {
+                            let count = next_var_int(&mut seq)?;
+                            let mut levels = Vec::new();
+                            for _ in 0..count.0 {
+                                levels.push((next_var_int(&mut seq)?, next_var_int(&mut seq)?));
+                            }
+                            let show_in_tooltip = seq
+                                .next_element::<bool>()?
+                                .ok_or(de::Error::custom("Failed to decode bool"))?;
+                            Component::Enchantments {
+                                stored: component_type == STORED_ENCHANTMENTS,
+                                levels,
+                                show_in_tooltip,
+                            }
+                        }
+                        other => {
+                            return Err(de::Error::custom(format!(
+                                "Slot component {other} is currently unsupported"
+                            )))
+                        }
+                    };
+                    components_to_add.push(component);
+                }
+                let mut components_to_remove = Vec::new();
+                for _ in 0..num_components_to_remove.0 {
+                    components_to_remove.push(next_var_int(&mut seq)?);
                 }
 
                 Ok(Slot {
@@ -66,8 +165,8 @@ impl<'de> Deserialize<'de> for Slot {
                     item_id: Some(item_id),
                     num_components_to_add: Some(num_components_to_add),
                     num_components_to_remove: Some(num_components_to_remove),
-                    components_to_add: None,
-                    components_to_remove: None,
+                    components_to_add: Some(components_to_add),
+                    components_to_remove: Some(components_to_remove),
                 })
             }
         }
@@ -76,6 +175,52 @@ impl<'de> Deserialize<'de> for Slot {
     }
 }
 
+fn next_var_int<'de, A>(seq: &mut A) -> Result<VarInt, A::Error>
+where
+    A: SeqAccess<'de>,
+{
+    seq.next_element::<VarInt>()?
+        .ok_or(de::Error::custom("Failed to decode VarInt"))
+}
+
+/// A text component the client sent, only plain text is supported
+fn next_nbt_string<'de, A>(seq: &mut A) -> Result<String, A::Error>
+where
+    A: SeqAccess<'de>,
+{
+    let tag = seq
+        .next_element::<u8>()?
+        .ok_or(de::Error::custom("Failed to decode NBT tag"))?;
+    if tag != NBT_STRING {
+        return Err(de::Error::custom(format!(
+            "Text components of NBT tag {tag} are currently unsupported"
+        )));
+    }
+    let len = seq
+        .next_element::<u16>()?
+        .ok_or(de::Error::custom("Failed to decode u16"))?;
+    let mut bytes = Vec::with_capacity(len.into());
+    for _ in 0..len {
+        bytes.push(
+            seq.next_element::<u8>()?
+                .ok_or(de::Error::custom("Failed to decode u8"))?,
+        );
+    }
+    String::from_utf8(bytes).map_err(|_| de::Error::custom("Text is no valid UTF-8"))
+}
+
+/// Bytes written as they are, without their length in front
+struct RawBytes<'a>(&'a [u8]);
+
+impl Serialize for RawBytes<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
 impl Serialize for Slot {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -129,12 +274,45 @@ impl Serialize for Slot {
 }
 
 impl Slot {
+    /// A slot holding `item_count` items of `item_id` without any components
+    pub fn new(item_count: VarInt, item_id: VarInt) -> Self {
+        Slot {
+            item_count,
+            item_id: Some(item_id),
+            num_components_to_add: None,
+            num_components_to_remove: None,
+            components_to_add: None,
+            components_to_remove: None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.item_count.0 == 0
+    }
+
+    /// The item in the slot, None if it is empty or the client sent an invalid item
     pub fn to_item(self) -> Option<ItemStack> {
-        let item_id = self.item_id?.0.try_into().unwrap();
-        Some(ItemStack {
-            item_id,
-            item_count: self.item_count.0.try_into().unwrap(),
-        })
+        let item_id = self.item_id?.0.try_into().ok()?;
+        let item_count = self.item_count.0.try_into().ok()?;
+        let mut item = ItemStack::new(item_count, item_id);
+        for component in self.components_to_add.into_iter().flatten() {
+            match component {
+                Component::Enchantments { levels, .. } => {
+                    for (id, level) in levels {
+                        if let Some(enchantment) = Enchantment::from_registry_id(id.0) {
+                            item.enchantments
+                                .set(enchantment, level.0.clamp(0, u8::MAX.into()) as u8);
+                        }
+                    }
+                }
+                Component::Damage(damage) => item.damage = damage.0.max(0) as u32,
+                Component::CustomName(name) => item.custom_name = ItemName::new(&name),
+                Component::RepairCost(repair_cost) => {
+                    item.repair_cost = repair_cost.0.max(0) as u32;
+                }
+            }
+        }
+        Some(item)
     }
 
     pub const fn empty() -> Self {
@@ -151,13 +329,16 @@ impl Slot {
 
 impl From<&ItemStack> for Slot {
     fn from(item: &ItemStack) -> Self {
+        let components = Some(components(item)).filter(|components| !components.is_empty());
         Slot {
             item_count: item.item_count.into(),
             item_id: Some(item.item_id.into()),
-            // TODO: add these
-            num_components_to_add: None,
+            // TODO: add the other components
+            num_components_to_add: components
+                .as_ref()
+                .map(|components| (components.len() as i32).into()),
             num_components_to_remove: None,
-            components_to_add: None,
+            components_to_add: components,
             components_to_remove: None,
         }
     }
@@ -168,3 +349,29 @@ impl From<Option<&ItemStack>> for Slot {
         item.map(Slot::from).unwrap_or(Slot::empty())
     }
 }
+
+/// The components of the item which differ from those of its kind
+fn components(item: &ItemStack) -> Vec<Component> {
+    let mut components = Vec::new();
+    if item.damage > 0 {
+        components.push(Component::Damage((item.damage as i32).into()));
+    }
+    if let Some(name) = &item.custom_name {
+        components.push(Component::CustomName(name.as_str().to_string()));
+    }
+    if !item.enchantments.is_empty() {
+        components.push(Component::Enchantments {
+            stored: item.stores_enchantments(),
+            levels: item
+                .enchantments
+                .iter()
+                .map(|(enchantment, level)| (enchantment.registry_id().into(), level.into()))
+                .collect(),
+            show_in_tooltip: true,
+        });
+    }
+    if item.repair_cost > 0 {
+        components.push(Component::RepairCost((item.repair_cost as i32).into()));
+    }
+    components
+}
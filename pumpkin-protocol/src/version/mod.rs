@@ -0,0 +1,82 @@
+//! Lets clients on older protocol versions talk to the Server.
+//!
+//! Every supported version has its own module which describes how packets differ from the current protocol.
+//! Packets are always written and read using the current protocol, the ids are translated right before
+//! sending and right after receiving.
+//!
+//! Only packet ids get translated, so a version can only be supported once all its packets have the
+//! same fields as in the current protocol. 1.20.5 and 1.20.6 are not supported: their clients expect
+//! the built-in enchantment ids in item components and don't know the registries added in 1.21.
+
+use crate::{ConnectionState, CURRENT_MC_PROTOCOL};
+
+mod v1_21;
+
+/// A Minecraft protocol version the Server can talk to
+pub struct ProtocolVersion {
+    /// The protocol number sent in the Handshake
+    pub protocol: u32,
+    /// The Minecraft versions using this protocol (e.g. 1.21.1)
+    pub name: &'static str,
+    /// Translates the id of a packet we send from the current protocol into this version.
+    /// None when the packet does not exist in this version
+    clientbound: fn(ConnectionState, i32) -> Option<i32>,
+    /// Translates the id of a packet we receive from this version into the current protocol.
+    /// None when the packet does not exist in the current protocol
+    serverbound: fn(ConnectionState, i32) -> Option<i32>,
+}
+
+/// Every version the Server accepts, newest first
+pub const SUPPORTED_VERSIONS: &[ProtocolVersion] = &[v1_21::VERSION];
+
+impl ProtocolVersion {
+    /// Finds a supported version by its protocol number
+    pub fn find(protocol: i32) -> Option<&'static Self> {
+        SUPPORTED_VERSIONS
+            .iter()
+            .find(|version| version.protocol as i32 == protocol)
+    }
+
+    /// The version the Server is written against
+    pub fn current() -> &'static Self {
+        Self::find(CURRENT_MC_PROTOCOL as i32).expect("The current protocol must be supported")
+    }
+
+    pub fn is_current(&self) -> bool {
+        self.protocol == CURRENT_MC_PROTOCOL
+    }
+
+    pub fn clientbound_id(&self, state: ConnectionState, id: i32) -> Option<i32> {
+        (self.clientbound)(state, id)
+    }
+
+    pub fn serverbound_id(&self, state: ConnectionState, id: i32) -> Option<i32> {
+        (self.serverbound)(state, id)
+    }
+}
+
+/// For versions where a packet did not change
+const fn identity(_state: ConnectionState, id: i32) -> Option<i32> {
+    Some(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn current_is_supported() {
+        assert!(ProtocolVersion::current().is_current());
+        assert_eq!(
+            ProtocolVersion::current().clientbound_id(ConnectionState::Play, 0x2B),
+            Some(0x2B)
+        );
+    }
+
+    #[test]
+    fn unsupported_version() {
+        assert!(ProtocolVersion::find(47).is_none());
+        // Item components and registries differ, see the module docs
+        assert!(ProtocolVersion::find(766).is_none());
+    }
+}
@@ -0,0 +1,9 @@
+use super::{identity, ProtocolVersion};
+
+/// 1.21 and 1.21.1, the current protocol
+pub const VERSION: ProtocolVersion = ProtocolVersion {
+    protocol: 767,
+    name: "1.21.1",
+    clientbound: identity,
+    serverbound: identity,
+};
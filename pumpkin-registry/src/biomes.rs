@@ -1,4 +1,6 @@
-use pumpkin_protocol::VarInt;
+use fastnbt::SerOpts;
+use pumpkin_protocol::{client::config::RegistryEntry, VarInt};
+use pumpkin_world::biome::Biome as WorldBiome;
 use serde::Serialize;
 
 #[derive(Debug, Clone, Serialize)]
@@ -116,3 +118,88 @@ impl Default for Biome {
         }
     }
 }
+
+impl Biome {
+    /// The vanilla climate and colors of a biome, the client uses them for grass, water and the sky
+    fn vanilla(biome: WorldBiome) -> Self {
+        // (temperature, downfall, sky color)
+        let (temperature, downfall, sky_color) = match biome {
+            WorldBiome::Plains | WorldBiome::Beach => (0.8, 0.4, 7907327),
+            WorldBiome::Forest => (0.7, 0.8, 7972607),
+            WorldBiome::BirchForest => (0.6, 0.6, 8037887),
+            WorldBiome::Taiga => (0.25, 0.8, 8233983),
+            WorldBiome::SnowyTaiga => (-0.5, 0.4, 8625919),
+            WorldBiome::SnowyPlains => (0.0, 0.5, 8364543),
+            WorldBiome::Desert
+            | WorldBiome::Savanna
+            | WorldBiome::Badlands
+            | WorldBiome::NetherWastes => (2.0, 0.0, 7254527),
+            WorldBiome::Jungle => (0.95, 0.9, 7842047),
+            WorldBiome::Swamp => (0.8, 0.9, 7907327),
+            WorldBiome::SnowyBeach => (0.05, 0.3, 8364543),
+            WorldBiome::River
+            | WorldBiome::Ocean
+            | WorldBiome::DeepOcean
+            | WorldBiome::WarmOcean
+            | WorldBiome::ColdOcean => (0.5, 0.5, 8103167),
+            WorldBiome::FrozenOcean => (0.0, 0.5, 8364543),
+            WorldBiome::StonyPeaks => (1.0, 0.3, 7776511),
+            WorldBiome::SnowySlopes => (-0.3, 0.9, 8560639),
+            WorldBiome::FrozenPeaks => (-0.7, 0.9, 8756735),
+            WorldBiome::TheEnd => (0.5, 0.5, 0),
+        };
+        let fog_color = match biome {
+            WorldBiome::NetherWastes => 3344392,
+            WorldBiome::TheEnd => 10518688,
+            _ => 12638463,
+        };
+        let (water_color, water_fog_color) = match biome {
+            WorldBiome::Swamp => (6388580, 2302743),
+            WorldBiome::WarmOcean => (4445678, 270131),
+            WorldBiome::SnowyTaiga | WorldBiome::SnowyBeach | WorldBiome::ColdOcean => {
+                (4020182, 329011)
+            }
+            WorldBiome::FrozenOcean => (3750089, 329011),
+            _ => (4159204, 329011),
+        };
+        Self {
+            has_precipitation: !matches!(
+                biome,
+                WorldBiome::Desert
+                    | WorldBiome::Savanna
+                    | WorldBiome::Badlands
+                    | WorldBiome::NetherWastes
+                    | WorldBiome::TheEnd
+            ) as i8,
+            temperature,
+            temperature_modifier: (biome == WorldBiome::FrozenOcean).then(|| "frozen".into()),
+            downfall,
+            effects: BiomeEffects {
+                fog_color,
+                water_color,
+                water_fog_color,
+                sky_color,
+                foliage_color: match biome {
+                    WorldBiome::Swamp => Some(6975545),
+                    WorldBiome::Badlands => Some(10387789),
+                    _ => None,
+                },
+                grass_color: (biome == WorldBiome::Badlands).then_some(9470285),
+                grass_color_modifier: (biome == WorldBiome::Swamp).then(|| "swamp".into()),
+                ..Self::default().effects
+            },
+        }
+    }
+}
+
+/// Every biome the world generator knows, in the order of their network ids
+pub(super) fn entries() -> Vec<RegistryEntry<'static>> {
+    WorldBiome::ALL
+        .iter()
+        .map(|biome| RegistryEntry {
+            entry_id: biome.registry_id(),
+            data: fastnbt::to_bytes_with_opts(&Biome::vanilla(*biome), SerOpts::network_nbt())
+                .unwrap(),
+        })
+        .collect()
+}
@@ -1,3 +1,6 @@
+use fastnbt::SerOpts;
+use pumpkin_protocol::client::config::RegistryEntry;
+use pumpkin_world::dimension::Dimension as WorldDimension;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,6 +62,56 @@ impl Default for Dimension {
     }
 }
 
+impl Dimension {
+    /// The vanilla settings of a dimension type.
+    /// Chunks always have the height of the overworld, so all dimensions keep its height
+    fn vanilla(dimension: WorldDimension) -> Self {
+        match dimension {
+            WorldDimension::OverWorld => Self::default(),
+            WorldDimension::Nether => Self {
+                ambient_light: 0.1,
+                bed_works: 0,
+                coordinate_scale: dimension.coordinate_scale(),
+                effects: DimensionEffects::TheNether,
+                fixed_time: Some(18000),
+                has_ceiling: 1,
+                has_raids: 0,
+                has_skylight: 0,
+                infiniburn: "#minecraft:infiniburn_nether".into(),
+                natural: 0,
+                piglin_safe: 1,
+                respawn_anchor_works: 1,
+                ultrawarm: 1,
+                ..Self::default()
+            },
+            WorldDimension::End => Self {
+                bed_works: 0,
+                effects: DimensionEffects::TheEnd,
+                fixed_time: Some(6000),
+                has_skylight: 0,
+                infiniburn: "#minecraft:infiniburn_end".into(),
+                natural: 0,
+                ..Self::default()
+            },
+        }
+    }
+}
+
+/// Every dimension type, in the order of their ids
+pub(super) fn entries() -> Vec<RegistryEntry<'static>> {
+    WorldDimension::ALL
+        .iter()
+        .map(|dimension| RegistryEntry {
+            entry_id: dimension.type_name(),
+            data: fastnbt::to_bytes_with_opts(
+                &Dimension::vanilla(*dimension),
+                SerOpts::network_nbt(),
+            )
+            .unwrap(),
+        })
+        .collect()
+}
+
 #[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum MonsterSpawnLightLevel {
@@ -0,0 +1,14 @@
+use pumpkin_protocol::client::config::RegistryEntry;
+use pumpkin_world::item::Enchantment;
+
+/// The enchantments of vanilla, in the order of their registry ids. The client knows their
+/// definitions from the `minecraft:core` pack, so no data is sent
+pub(super) fn entries() -> Vec<RegistryEntry<'static>> {
+    Enchantment::ALL
+        .into_iter()
+        .map(|enchantment| RegistryEntry {
+            entry_id: enchantment.name(),
+            data: Vec::new(),
+        })
+        .collect()
+}
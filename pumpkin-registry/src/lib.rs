@@ -1,6 +1,4 @@
-use biomes::Biome;
 use chat_type::ChatType;
-use dimensions::Dimension;
 use fastnbt::SerOpts;
 use paint::Painting;
 use pumpkin_protocol::client::config::RegistryEntry;
@@ -10,6 +8,7 @@ mod biomes;
 mod chat_type;
 mod damage_type;
 mod dimensions;
+mod enchantment;
 mod paint;
 mod wolf;
 
@@ -23,26 +22,11 @@ impl Registry {
     pub fn get_static() -> Vec<Self> {
         let dimensions = Registry {
             registry_id: "minecraft:dimension_type".to_string(),
-            registry_entries: vec![RegistryEntry {
-                entry_id: "minecraft:overworld",
-                data: fastnbt::to_bytes_with_opts(&Dimension::default(), SerOpts::network_nbt())
-                    .unwrap(),
-            }],
+            registry_entries: dimensions::entries(),
         };
         let biomes = Registry {
             registry_id: "minecraft:worldgen/biome".to_string(),
-            registry_entries: vec![
-                RegistryEntry {
-                    entry_id: "minecraft:plains",
-                    data: fastnbt::to_bytes_with_opts(&Biome::default(), SerOpts::network_nbt())
-                        .unwrap(),
-                },
-                RegistryEntry {
-                    entry_id: "minecraft:snowy_taiga",
-                    data: fastnbt::to_bytes_with_opts(&Biome::default(), SerOpts::network_nbt())
-                        .unwrap(),
-                },
-            ],
+            registry_entries: biomes::entries(),
         };
         let wolf_variants = Registry {
             registry_id: "minecraft:wolf_variant".to_string(),
@@ -74,6 +58,10 @@ impl Registry {
                     .unwrap(),
             }],
         };
+        let enchantments = Registry {
+            registry_id: "minecraft:enchantment".to_string(),
+            registry_entries: enchantment::entries(),
+        };
         vec![
             dimensions,
             damage_types,
@@ -81,6 +69,7 @@ impl Registry {
             wolf_variants,
             paintings,
             chat_types,
+            enchantments,
         ]
     }
 }
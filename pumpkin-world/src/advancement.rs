@@ -0,0 +1,345 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use pumpkin_core::text::TextComponent;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::{datapack, storage};
+
+/// The folder inside of the main world which holds the progress of every player, like in vanilla
+const ADVANCEMENTS_FOLDER: &str = "advancements";
+const DATA_VERSION: i32 = 3955;
+/// The vanilla advancements Pumpkin ships with, by their id
+const ADVANCEMENTS_JSON: &str = include_str!("../../assets/advancements.json");
+
+#[derive(Error, Debug)]
+pub enum AdvancementError {
+    #[error("Io error: {0}")]
+    IoError(std::io::ErrorKind),
+    #[error("Failed to parse the advancements: {0}")]
+    ParsingError(serde_json::Error),
+}
+
+/// An advancement as vanilla stores it in `data/<namespace>/advancement/<path>.json`
+#[derive(Deserialize, Clone, Debug)]
+pub struct Advancement {
+    /// The id of the advancement it follows in the tree, None for the root of a tab
+    #[serde(default)]
+    pub parent: Option<String>,
+    /// How the advancement is shown, None for advancements players never see
+    #[serde(default)]
+    pub display: Option<AdvancementDisplay>,
+    pub criteria: HashMap<String, Criterion>,
+    /// One criterion of every group has to be met. Empty if every criterion has to be met
+    #[serde(default)]
+    pub requirements: Vec<Vec<String>>,
+    #[serde(default)]
+    pub rewards: AdvancementRewards,
+    #[serde(default)]
+    pub sends_telemetry_event: bool,
+}
+
+impl Advancement {
+    /// The groups of criteria, one criterion of every group has to be met
+    pub fn requirements(&self) -> Vec<Vec<String>> {
+        if !self.requirements.is_empty() {
+            return self.requirements.clone();
+        }
+        let mut criteria: Vec<String> = self.criteria.keys().cloned().collect();
+        criteria.sort();
+        criteria
+            .into_iter()
+            .map(|criterion| vec![criterion])
+            .collect()
+    }
+
+    /// Whether the advancement is done once the criteria for which `met` returns true are met
+    pub fn is_done(&self, met: impl Fn(&str) -> bool) -> bool {
+        self.requirements()
+            .iter()
+            .all(|group| group.iter().any(|criterion| met(criterion)))
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct AdvancementDisplay {
+    pub icon: AdvancementIcon,
+    pub title: TextComponent<'static>,
+    pub description: TextComponent<'static>,
+    #[serde(default)]
+    pub frame: AdvancementFrame,
+    /// The texture behind the tab, only used by roots
+    #[serde(default)]
+    pub background: Option<String>,
+    #[serde(default = "default_true")]
+    pub show_toast: bool,
+    #[serde(default = "default_true")]
+    pub announce_to_chat: bool,
+    /// Hidden advancements are only shown once they are done
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct AdvancementIcon {
+    /// The item, e.g. `minecraft:grass_block`
+    pub id: String,
+    #[serde(default = "default_count")]
+    pub count: u8,
+}
+
+/// The frame around the icon, in the order clients know them
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AdvancementFrame {
+    #[default]
+    Task,
+    Challenge,
+    Goal,
+}
+
+/// Something a player has to do, `trigger` tells when it is checked and `conditions` what has
+/// to be true then
+#[derive(Deserialize, Clone, Debug)]
+pub struct Criterion {
+    /// e.g. `minecraft:inventory_changed`
+    pub trigger: String,
+    #[serde(default)]
+    pub conditions: Value,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct AdvancementRewards {
+    /// Experience points the player gets once the advancement is done
+    #[serde(default)]
+    pub experience: i32,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_count() -> u8 {
+    1
+}
+
+/// The built-in advancements and those of the datapacks of the main world in `root_folder`,
+/// by their id. Advancements of datapacks replace built-in ones with the same id
+pub fn load_advancements(root_folder: &Path) -> HashMap<String, Advancement> {
+    let mut advancements: HashMap<String, Advancement> =
+        serde_json::from_str(ADVANCEMENTS_JSON).expect("Could not parse advancements.json");
+    // The folder was renamed in 1.21, older datapacks still use the plural
+    advancements.extend(datapack::load_files(
+        root_folder,
+        &["advancement", "advancements"],
+        "advancement",
+    ));
+    advancements
+}
+
+/// What a player met of one advancement
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CriteriaProgress {
+    /// When the player met each criterion, in milliseconds since the unix epoch
+    pub criteria: HashMap<String, i64>,
+    pub done: bool,
+}
+
+/// The progress of a player for every advancement they met a criterion of, stored in
+/// `advancements/<uuid>.json` like in vanilla
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AdvancementProgress {
+    pub advancements: HashMap<String, CriteriaProgress>,
+}
+
+impl AdvancementProgress {
+    /// Reads the progress of a player from the main world in `root_folder`, empty if they never
+    /// played before
+    pub fn read(root_folder: &Path, uuid: &str) -> Result<Self, AdvancementError> {
+        let json = match fs::read_to_string(progress_file(root_folder, uuid)) {
+            Ok(json) => json,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(AdvancementError::IoError(err.kind())),
+        };
+        let data: Map<String, Value> =
+            serde_json::from_str(&json).map_err(AdvancementError::ParsingError)?;
+        let mut advancements = HashMap::new();
+        for (id, progress) in data {
+            let Value::Object(progress) = progress else {
+                // e.g. the DataVersion
+                continue;
+            };
+            let criteria = match progress.get("criteria") {
+                Some(Value::Object(criteria)) => criteria
+                    .iter()
+                    .filter_map(|(name, date)| Some((name.clone(), parse_date(date.as_str()?)?)))
+                    .collect(),
+                _ => HashMap::new(),
+            };
+            let done = progress
+                .get("done")
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            advancements.insert(id, CriteriaProgress { criteria, done });
+        }
+        Ok(Self { advancements })
+    }
+
+    /// Writes the progress of a player into the main world in `root_folder`, replacing the old one
+    pub fn write(&self, root_folder: &Path, uuid: &str) -> Result<(), AdvancementError> {
+        let mut data = Map::new();
+        for (id, progress) in &self.advancements {
+            let criteria = progress
+                .criteria
+                .iter()
+                .map(|(name, millis)| (name.clone(), Value::String(format_date(*millis))))
+                .collect();
+            let mut entry = Map::new();
+            entry.insert("criteria".to_string(), Value::Object(criteria));
+            entry.insert("done".to_string(), Value::Bool(progress.done));
+            data.insert(id.clone(), Value::Object(entry));
+        }
+        data.insert("DataVersion".to_string(), Value::from(DATA_VERSION));
+        let json = serde_json::to_string_pretty(&data).map_err(AdvancementError::ParsingError)?;
+
+        let io_error = |err: std::io::Error| AdvancementError::IoError(err.kind());
+        storage::write_atomically(&progress_file(root_folder, uuid), json.as_bytes())
+            .map_err(io_error)
+    }
+
+    /// Whether the player met `criterion` of the advancement `id`
+    pub fn is_met(&self, id: &str, criterion: &str) -> bool {
+        self.advancements
+            .get(id)
+            .is_some_and(|progress| progress.criteria.contains_key(criterion))
+    }
+}
+
+fn progress_file(root_folder: &Path, uuid: &str) -> PathBuf {
+    root_folder
+        .join(ADVANCEMENTS_FOLDER)
+        .join(format!("{uuid}.json"))
+}
+
+/// Formats milliseconds since the unix epoch like vanilla, e.g. `2024-06-13 09:30:00 +0000`
+fn format_date(millis: i64) -> String {
+    let seconds = millis.div_euclid(1000);
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86400));
+    let time = seconds.rem_euclid(86400);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} +0000",
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+/// Parses a date written by `format_date` or vanilla, which writes the local time zone
+fn parse_date(date: &str) -> Option<i64> {
+    let mut parts = date.split(' ');
+    let mut numbers = |separator: char| {
+        parts.next().map(|part: &str| {
+            part.split(separator)
+                .map(str::parse::<i64>)
+                .collect::<Result<Vec<_>, _>>()
+        })
+    };
+    let (Some(Ok(day)), Some(Ok(time))) = (numbers('-'), numbers(':')) else {
+        return None;
+    };
+    let (&[year, month, day], &[hours, minutes, seconds]) = (day.as_slice(), time.as_slice())
+    else {
+        return None;
+    };
+    let offset = parts.next().unwrap_or("+0000");
+    let (sign, offset) = match offset.split_at_checked(1)? {
+        ("-", offset) => (-1, offset),
+        (_, offset) => (1, offset),
+    };
+    let offset: i64 = offset.parse().ok()?;
+    let offset = sign * (offset / 100 * 3600 + offset % 100 * 60);
+    let seconds =
+        days_from_civil(year, month, day) * 86400 + hours * 3600 + minutes * 60 + seconds - offset;
+    Some(seconds * 1000)
+}
+
+/// The days since the unix epoch of a date, see <https://howardhinnant.github.io/date_algorithms.html>
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// The date of a day since the unix epoch, the inverse of `days_from_civil`
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{TestFolder, UUID};
+
+    #[test]
+    fn builtin_advancements() {
+        let advancements = load_advancements(Path::new("does-not-exist"));
+        let mine_stone = &advancements["minecraft:story/mine_stone"];
+        assert_eq!(mine_stone.parent.as_deref(), Some("minecraft:story/root"));
+        assert!(mine_stone.is_done(|criterion| criterion == "get_stone"));
+        let armor = &advancements["minecraft:story/obtain_armor"];
+        assert!(armor.is_done(|criterion| criterion == "iron_boots"));
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(format_date(0), "1970-01-01 00:00:00 +0000");
+        let millis = 1_718_271_000_000;
+        assert_eq!(format_date(millis), "2024-06-13 09:30:00 +0000");
+        assert_eq!(parse_date(&format_date(millis)), Some(millis));
+        assert_eq!(parse_date("2024-06-13 11:30:00 +0200"), Some(millis));
+    }
+
+    #[test]
+    fn round_trip() {
+        let folder = TestFolder::new("advancements");
+        let folder = folder.path();
+        let progress = AdvancementProgress {
+            advancements: HashMap::from([(
+                "minecraft:story/root".to_string(),
+                CriteriaProgress {
+                    criteria: HashMap::from([("crafting_table".to_string(), 1_718_271_000_000)]),
+                    done: true,
+                },
+            )]),
+        };
+        progress.write(folder, UUID).unwrap();
+
+        let read = AdvancementProgress::read(folder, UUID).unwrap();
+        let unknown = AdvancementProgress::read(folder, "unknown").unwrap();
+        assert_eq!(read, progress);
+        assert!(read.is_met("minecraft:story/root", "crafting_table"));
+        assert_eq!(unknown, AdvancementProgress::default());
+    }
+}
@@ -1,14 +1,108 @@
 use enum_dispatch::enum_dispatch;
 use serde::{Deserialize, Serialize};
 
-// TODO make this work with the protocol
-// Send by the registry
-#[derive(Serialize, Deserialize, Clone, Copy)]
+/// The biomes the Server knows, they are sent to clients in the biome registry.
+/// The variants have to stay in the same order as `Biome::ALL`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
 #[non_exhaustive]
 pub enum Biome {
+    #[default]
     Plains,
-    SnowyTiga,
-    // TODO list all Biomes
+    Forest,
+    BirchForest,
+    Taiga,
+    SnowyTaiga,
+    SnowyPlains,
+    Desert,
+    Savanna,
+    Jungle,
+    Swamp,
+    Badlands,
+    Beach,
+    SnowyBeach,
+    River,
+    Ocean,
+    DeepOcean,
+    WarmOcean,
+    ColdOcean,
+    FrozenOcean,
+    StonyPeaks,
+    SnowySlopes,
+    FrozenPeaks,
+    NetherWastes,
+    TheEnd,
+}
+
+impl Biome {
+    /// All biomes, in the order of the biome registry. The position of a biome is its network id
+    pub const ALL: [Biome; 24] = [
+        Biome::Plains,
+        Biome::Forest,
+        Biome::BirchForest,
+        Biome::Taiga,
+        Biome::SnowyTaiga,
+        Biome::SnowyPlains,
+        Biome::Desert,
+        Biome::Savanna,
+        Biome::Jungle,
+        Biome::Swamp,
+        Biome::Badlands,
+        Biome::Beach,
+        Biome::SnowyBeach,
+        Biome::River,
+        Biome::Ocean,
+        Biome::DeepOcean,
+        Biome::WarmOcean,
+        Biome::ColdOcean,
+        Biome::FrozenOcean,
+        Biome::StonyPeaks,
+        Biome::SnowySlopes,
+        Biome::FrozenPeaks,
+        Biome::NetherWastes,
+        Biome::TheEnd,
+    ];
+
+    pub const fn registry_id(&self) -> &'static str {
+        match self {
+            Biome::Plains => "minecraft:plains",
+            Biome::Forest => "minecraft:forest",
+            Biome::BirchForest => "minecraft:birch_forest",
+            Biome::Taiga => "minecraft:taiga",
+            Biome::SnowyTaiga => "minecraft:snowy_taiga",
+            Biome::SnowyPlains => "minecraft:snowy_plains",
+            Biome::Desert => "minecraft:desert",
+            Biome::Savanna => "minecraft:savanna",
+            Biome::Jungle => "minecraft:jungle",
+            Biome::Swamp => "minecraft:swamp",
+            Biome::Badlands => "minecraft:badlands",
+            Biome::Beach => "minecraft:beach",
+            Biome::SnowyBeach => "minecraft:snowy_beach",
+            Biome::River => "minecraft:river",
+            Biome::Ocean => "minecraft:ocean",
+            Biome::DeepOcean => "minecraft:deep_ocean",
+            Biome::WarmOcean => "minecraft:warm_ocean",
+            Biome::ColdOcean => "minecraft:cold_ocean",
+            Biome::FrozenOcean => "minecraft:frozen_ocean",
+            Biome::StonyPeaks => "minecraft:stony_peaks",
+            Biome::SnowySlopes => "minecraft:snowy_slopes",
+            Biome::FrozenPeaks => "minecraft:frozen_peaks",
+            Biome::NetherWastes => "minecraft:nether_wastes",
+            Biome::TheEnd => "minecraft:the_end",
+        }
+    }
+
+    pub fn from_registry_id(registry_id: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|biome| biome.registry_id() == registry_id)
+    }
+
+    /// The id of the biome in the biome registry
+    pub const fn network_id(&self) -> i32 {
+        // The variants are declared in registry order
+        *self as i32
+    }
 }
 
 #[derive(Clone)]
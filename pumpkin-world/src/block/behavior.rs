@@ -0,0 +1,321 @@
+use std::{collections::HashSet, sync::LazyLock};
+
+use super::{
+    block_registry::{BlockId, BLOCKS},
+    BlockState,
+};
+
+/// Blocks players can't break outside of creative mode
+const UNBREAKABLE_BLOCKS: [&str; 13] = [
+    "minecraft:bedrock",
+    "minecraft:barrier",
+    "minecraft:command_block",
+    "minecraft:chain_command_block",
+    "minecraft:repeating_command_block",
+    "minecraft:end_portal",
+    "minecraft:end_gateway",
+    "minecraft:end_portal_frame",
+    "minecraft:jigsaw",
+    "minecraft:light",
+    "minecraft:moving_piston",
+    "minecraft:nether_portal",
+    "minecraft:structure_block",
+];
+
+/// Block categories with a hardness of 0, clients break them as soon as they start digging
+const INSTANT_BREAK_CATEGORIES: [&str; 58] = [
+    "minecraft:attached_stem",
+    "minecraft:azalea",
+    "minecraft:bamboo_sapling",
+    "minecraft:base_coral_fan",
+    "minecraft:base_coral_plant",
+    "minecraft:base_coral_wall_fan",
+    "minecraft:beetroot",
+    "minecraft:carrot",
+    "minecraft:cave_vines",
+    "minecraft:cave_vines_plant",
+    "minecraft:comparator",
+    "minecraft:coral_fan",
+    "minecraft:coral_plant",
+    "minecraft:coral_wall_fan",
+    "minecraft:crop",
+    "minecraft:dead_bush",
+    "minecraft:decorated_pot",
+    "minecraft:double_plant",
+    "minecraft:end_rod",
+    "minecraft:fire",
+    "minecraft:flower",
+    "minecraft:flower_pot",
+    "minecraft:frogspawn",
+    "minecraft:fungus",
+    "minecraft:hanging_roots",
+    "minecraft:honey",
+    "minecraft:kelp",
+    "minecraft:kelp_plant",
+    "minecraft:mangrove_propagule",
+    "minecraft:mushroom",
+    "minecraft:nether_sprouts",
+    "minecraft:nether_wart",
+    "minecraft:pink_petals",
+    "minecraft:pitcher_crop",
+    "minecraft:potato",
+    "minecraft:redstone_torch",
+    "minecraft:redstone_wall_torch",
+    "minecraft:redstone_wire",
+    "minecraft:repeater",
+    "minecraft:roots",
+    "minecraft:sapling",
+    "minecraft:scaffolding",
+    "minecraft:sea_pickle",
+    "minecraft:seagrass",
+    "minecraft:slime",
+    "minecraft:small_dripleaf",
+    "minecraft:soul_fire",
+    "minecraft:spore_blossom",
+    "minecraft:stem",
+    "minecraft:structure_void",
+    "minecraft:sugar_cane",
+    "minecraft:tall_flower",
+    "minecraft:tall_grass",
+    "minecraft:tall_seagrass",
+    "minecraft:tnt",
+    "minecraft:torch",
+    "minecraft:torchflower_crop",
+    "minecraft:wall_torch",
+];
+
+/// Block categories a placed block replaces, instead of being placed next to them
+const REPLACEABLE_CATEGORIES: [&str; 16] = [
+    "minecraft:air",
+    "minecraft:bubble_column",
+    "minecraft:dead_bush",
+    "minecraft:double_plant",
+    "minecraft:fire",
+    "minecraft:glow_lichen",
+    "minecraft:hanging_roots",
+    "minecraft:light",
+    "minecraft:liquid",
+    "minecraft:nether_sprouts",
+    "minecraft:roots",
+    "minecraft:seagrass",
+    "minecraft:soul_fire",
+    "minecraft:structure_void",
+    "minecraft:tall_grass",
+    "minecraft:tall_seagrass",
+];
+
+/// Block categories entities walk through, they can be placed where a player stands
+const NO_COLLISION_CATEGORIES: [&str; 25] = [
+    "minecraft:attached_stem",
+    "minecraft:beetroot",
+    "minecraft:button",
+    "minecraft:carrot",
+    "minecraft:crop",
+    "minecraft:detector_rail",
+    "minecraft:flower",
+    "minecraft:fungus",
+    "minecraft:mushroom",
+    "minecraft:nether_wart",
+    "minecraft:potato",
+    "minecraft:powered_rail",
+    "minecraft:pressure_plate",
+    "minecraft:rail",
+    "minecraft:redstone_torch",
+    "minecraft:redstone_wall_torch",
+    "minecraft:redstone_wire",
+    "minecraft:sapling",
+    "minecraft:standing_sign",
+    "minecraft:stem",
+    "minecraft:sugar_cane",
+    "minecraft:tall_flower",
+    "minecraft:torch",
+    "minecraft:wall_sign",
+    "minecraft:wall_torch",
+];
+
+/// Block categories which fall down when there is nothing below them, e.g. sand, gravel and anvils
+const GRAVITY_CATEGORIES: [&str; 5] = [
+    "minecraft:anvil",
+    "minecraft:brushable",
+    "minecraft:colored_falling",
+    "minecraft:concrete_powder",
+    "minecraft:dragon_egg",
+];
+
+/// The blast resistance of blocks by the end of their id, the first match counts.
+/// The block data has no blast resistances, so these follow vanilla for common blocks
+const BLAST_RESISTANCES: [(&str, f32); 38] = [
+    ("obsidian", 1200.0),
+    ("anvil", 1200.0),
+    ("ancient_debris", 1200.0),
+    ("netherite_block", 1200.0),
+    ("reinforced_deepslate", 1200.0),
+    ("respawn_anchor", 1200.0),
+    ("enchanting_table", 1200.0),
+    ("ender_chest", 600.0),
+    ("water", 100.0),
+    ("lava", 100.0),
+    ("end_stone_bricks", 9.0),
+    ("end_stone", 9.0),
+    ("_ore", 3.0),
+    ("sandstone", 0.8),
+    ("glowstone", 0.3),
+    ("deepslate", 6.0),
+    ("bricks", 6.0),
+    ("stone", 6.0),
+    ("_planks", 3.0),
+    ("_log", 2.0),
+    ("_wood", 2.0),
+    ("_stem", 2.0),
+    ("_hyphae", 2.0),
+    ("_leaves", 0.2),
+    ("glass", 0.3),
+    ("glass_pane", 0.3),
+    ("_wool", 0.8),
+    ("_concrete", 1.8),
+    ("terracotta", 4.2),
+    ("netherrack", 0.4),
+    ("sand", 0.5),
+    ("dirt", 0.5),
+    ("grass_block", 0.6),
+    ("gravel", 0.6),
+    ("snow", 0.1),
+    ("ice", 0.5),
+    ("tnt", 0.0),
+    ("_block", 6.0),
+];
+
+/// Blocks matching none of `BLAST_RESISTANCES`
+const DEFAULT_BLAST_RESISTANCE: f32 = 3.0;
+
+/// Block categories which do something on random ticks, e.g. crops growing or leaves decaying
+const RANDOM_TICK_CATEGORIES: [&str; 9] = [
+    "minecraft:beetroot",
+    "minecraft:carrot",
+    "minecraft:crop",
+    "minecraft:farm",
+    "minecraft:grass",
+    "minecraft:leaves",
+    "minecraft:mycelium",
+    "minecraft:potato",
+    "minecraft:sapling",
+];
+
+/// The states of all blocks in `RANDOM_TICK_CATEGORIES`, random ticks check a lot of blocks
+static RANDOM_TICK_STATES: LazyLock<HashSet<u16>> = LazyLock::new(|| {
+    BLOCKS
+        .values()
+        .filter(|block| RANDOM_TICK_CATEGORIES.contains(&block.definition.category.as_str()))
+        .flat_map(|block| block.states.iter().map(|state| state.id.data))
+        .collect()
+});
+
+/// Whether entities walk through the blocks of a category, e.g. flowers or water
+pub(super) fn is_passable_category(category: &str) -> bool {
+    NO_COLLISION_CATEGORIES.contains(&category) || REPLACEABLE_CATEGORIES.contains(&category)
+}
+
+impl BlockId {
+    /// The category of the block, e.g. `minecraft:slab`
+    pub fn category(&self) -> Option<&'static str> {
+        let (registry_id, _) = self.registry_state()?;
+        BLOCKS
+            .get(registry_id)
+            .map(|block| block.definition.category.as_str())
+    }
+
+    /// Whether survival players can't break the block
+    pub fn is_unbreakable(&self) -> bool {
+        self.registry_state()
+            .is_some_and(|(registry_id, _)| UNBREAKABLE_BLOCKS.contains(&registry_id))
+    }
+
+    /// Whether the block breaks as soon as a player starts digging it
+    pub fn breaks_instantly(&self) -> bool {
+        self.category()
+            .is_some_and(|category| INSTANT_BREAK_CATEGORIES.contains(&category))
+    }
+
+    /// Whether placing a block into this block replaces it, e.g. air, water or grass
+    pub fn is_replaceable(&self) -> bool {
+        if self.is_air() {
+            return true;
+        }
+        let Some((registry_id, properties)) = self.registry_state() else {
+            return false;
+        };
+        // Only a single layer of snow gets replaced
+        if registry_id == "minecraft:snow" {
+            return properties.get("layers").is_some_and(|layers| layers == "1");
+        }
+        self.category()
+            .is_some_and(|category| REPLACEABLE_CATEGORIES.contains(&category))
+    }
+
+    /// The value of a property of the block state, e.g. the `age` of wheat
+    pub fn property(&self, name: &str) -> Option<&'static str> {
+        let (_, properties) = self.registry_state()?;
+        properties.get(name).map(String::as_str)
+    }
+
+    /// The state of the same block with one property changed, None if there is no such state
+    pub fn with_property(&self, name: &str, value: &str) -> Option<BlockId> {
+        let (registry_id, properties) = self.registry_state()?;
+        let mut properties = properties.clone();
+        *properties.get_mut(name)? = value.to_string();
+        BlockState::new(registry_id, Some(&properties))
+            .ok()
+            .map(Into::into)
+    }
+
+    /// Whether the block is a log or wood, which keeps leaves from decaying
+    pub fn is_log(&self) -> bool {
+        self.category() == Some("minecraft:rotated_pillar")
+            && self.registry_state().is_some_and(|(registry_id, _)| {
+                ["_log", "_wood", "_stem", "_hyphae"]
+                    .iter()
+                    .any(|suffix| registry_id.ends_with(suffix))
+            })
+    }
+
+    /// Whether the block does something on random ticks
+    pub fn ticks_randomly(&self) -> bool {
+        RANDOM_TICK_STATES.contains(&self.data)
+    }
+
+    /// Whether the block falls down when there is nothing below it
+    pub fn has_gravity(&self) -> bool {
+        self.category()
+            .is_some_and(|category| GRAVITY_CATEGORIES.contains(&category))
+    }
+
+    /// Whether falling blocks fall through the block, e.g. air, water or fire
+    pub fn can_fall_through(&self) -> bool {
+        self.is_replaceable()
+    }
+
+    /// How well the block withstands explosions, air and blocks which break instantly don't at all
+    pub fn blast_resistance(&self) -> f32 {
+        if self.is_unbreakable() {
+            return 3_600_000.0;
+        }
+        if self.is_air() || self.breaks_instantly() {
+            return 0.0;
+        }
+        let Some((registry_id, _)) = self.registry_state() else {
+            return DEFAULT_BLAST_RESISTANCE;
+        };
+        BLAST_RESISTANCES
+            .iter()
+            .find(|(suffix, _)| registry_id.ends_with(suffix))
+            .map_or(DEFAULT_BLAST_RESISTANCE, |(_, resistance)| *resistance)
+    }
+
+    /// Whether entities collide with the block, so it can't be placed where they stand
+    pub fn has_collision(&self) -> bool {
+        !self.is_replaceable()
+            && !self
+                .category()
+                .is_some_and(|category| NO_COLLISION_CATEGORIES.contains(&category))
+    }
+}
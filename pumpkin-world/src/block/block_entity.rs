@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+
+use fastnbt::Value;
+use pumpkin_core::math::vector3::Vector3;
+
+use super::BlockId;
+use crate::{
+    global_registry::{self, ITEM_REGISTRY},
+    item::ItemStack,
+};
+
+pub const BLOCK_ENTITY_TYPE_REGISTRY: &str = "minecraft:block_entity_type";
+
+/// Block categories which have a block entity, and the id of that block entity
+const BLOCK_ENTITY_CATEGORIES: [(&str, &str); 10] = [
+    ("minecraft:chest", "minecraft:chest"),
+    ("minecraft:trapped_chest", "minecraft:trapped_chest"),
+    ("minecraft:barrel", "minecraft:barrel"),
+    ("minecraft:furnace", "minecraft:furnace"),
+    ("minecraft:smoker", "minecraft:smoker"),
+    ("minecraft:blast_furnace", "minecraft:blast_furnace"),
+    ("minecraft:standing_sign", "minecraft:sign"),
+    ("minecraft:wall_sign", "minecraft:sign"),
+    ("minecraft:ceiling_hanging_sign", "minecraft:hanging_sign"),
+    ("minecraft:wall_hanging_sign", "minecraft:hanging_sign"),
+];
+
+/// Block entities whose contents clients don't need to render them
+const CONTAINERS: [&str; 12] = [
+    "minecraft:barrel",
+    "minecraft:blast_furnace",
+    "minecraft:brewing_stand",
+    "minecraft:chest",
+    "minecraft:crafter",
+    "minecraft:dispenser",
+    "minecraft:dropper",
+    "minecraft:furnace",
+    "minecraft:hopper",
+    "minecraft:shulker_box",
+    "minecraft:smoker",
+    "minecraft:trapped_chest",
+];
+
+/// Data of a block which doesn't fit into its block state, e.g. the text of a sign or the items in a chest
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockEntity {
+    /// The type of the block entity, e.g. `minecraft:sign`
+    pub id: String,
+    pub position: Vector3<i32>,
+    /// Everything else stored in the NBT of the block entity, kept as it is so nothing gets lost
+    pub data: HashMap<String, Value>,
+}
+
+impl BlockEntity {
+    /// The empty block entity of a block, None if the block doesn't have one
+    pub fn for_block(block: BlockId, position: Vector3<i32>) -> Option<Self> {
+        let category = block.category()?;
+        let (_, id) = BLOCK_ENTITY_CATEGORIES
+            .iter()
+            .find(|(block_category, _)| *block_category == category)?;
+        let mut entity = Self {
+            id: id.to_string(),
+            position,
+            data: HashMap::new(),
+        };
+        if entity.is_sign() {
+            entity.set_sign_text(true, Default::default());
+            entity.set_sign_text(false, Default::default());
+            entity.data.insert("is_waxed".to_string(), Value::Byte(0));
+        }
+        Some(entity)
+    }
+
+    /// Reads a block entity from the NBT compound it is saved as in chunks
+    pub fn from_nbt(nbt: Value) -> Option<Self> {
+        let Value::Compound(mut data) = nbt else {
+            return None;
+        };
+        let Some(Value::String(id)) = data.remove("id") else {
+            return None;
+        };
+        let mut coordinate = |name: &str| match data.remove(name) {
+            Some(Value::Int(value)) => Some(value),
+            _ => None,
+        };
+        let position = Vector3::new(coordinate("x")?, coordinate("y")?, coordinate("z")?);
+        data.remove("keepPacked");
+        Some(Self { id, position, data })
+    }
+
+    /// The NBT compound the block entity is saved as in chunks
+    pub fn to_nbt(&self) -> Value {
+        let mut nbt = self.data.clone();
+        nbt.insert("id".to_string(), Value::String(self.id.clone()));
+        nbt.insert("x".to_string(), Value::Int(self.position.x));
+        nbt.insert("y".to_string(), Value::Int(self.position.y));
+        nbt.insert("z".to_string(), Value::Int(self.position.z));
+        Value::Compound(nbt)
+    }
+
+    /// The protocol id of the block entity type, None for types clients don't know
+    pub fn type_id(&self) -> Option<u32> {
+        global_registry::find_protocol_id(BLOCK_ENTITY_TYPE_REGISTRY, &self.id)
+    }
+
+    /// The data clients need to render the block entity, the contents of containers stay on the server
+    pub fn client_data(&self) -> HashMap<String, Value> {
+        if CONTAINERS.contains(&self.id.as_str()) {
+            return HashMap::new();
+        }
+        self.data.clone()
+    }
+
+    pub fn is_sign(&self) -> bool {
+        self.id == "minecraft:sign" || self.id == "minecraft:hanging_sign"
+    }
+
+    /// Whether players can't edit the sign anymore
+    pub fn is_waxed(&self) -> bool {
+        matches!(self.data.get("is_waxed"), Some(Value::Byte(1)))
+    }
+
+    /// Sets the lines on the front or back of a sign, keeping its color and glow
+    pub fn set_sign_text(&mut self, front: bool, lines: [String; 4]) {
+        let key = if front { "front_text" } else { "back_text" };
+        let text = self
+            .data
+            .entry(key.to_string())
+            .or_insert_with(|| Value::Compound(HashMap::new()));
+        let Value::Compound(text) = text else {
+            return;
+        };
+        // Every line is a text component as JSON, a plain JSON string is the simplest one
+        let messages = lines
+            .iter()
+            .map(|line| Value::String(serde_json::to_string(line).unwrap_or_default()))
+            .collect();
+        text.insert("messages".to_string(), Value::List(messages));
+        text.entry("color".to_string())
+            .or_insert_with(|| Value::String("black".to_string()));
+        text.entry("has_glowing_text".to_string())
+            .or_insert(Value::Byte(0));
+    }
+
+    /// The items of a container by their slot, items clients don't know are left out
+    pub fn items(&self) -> Vec<(usize, ItemStack)> {
+        let Some(Value::List(items)) = self.data.get("Items") else {
+            return Vec::new();
+        };
+        items
+            .iter()
+            .filter_map(|item| {
+                let Value::Compound(item) = item else {
+                    return None;
+                };
+                let slot = match item.get("Slot")? {
+                    Value::Byte(slot) => *slot as usize,
+                    _ => return None,
+                };
+                let Value::String(id) = item.get("id")? else {
+                    return None;
+                };
+                // Chunks from before 1.20.5 store the count as a byte named `Count`
+                let item_count = match item.get("count").or_else(|| item.get("Count")) {
+                    Some(Value::Int(count)) => *count as u8,
+                    Some(Value::Byte(count)) => *count as u8,
+                    _ => 1,
+                };
+                let item_id = global_registry::find_protocol_id(ITEM_REGISTRY, id)?;
+                let mut stack = ItemStack::new(item_count, item_id);
+                stack.read_components(item);
+                Some((slot, stack))
+            })
+            .collect()
+    }
+
+    /// Replaces the items of a container
+    pub fn set_items(&mut self, items: &[Option<ItemStack>]) {
+        let items = items
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, item)| {
+                let item = item.as_ref()?;
+                let id = global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id)?;
+                let mut nbt = HashMap::from([
+                    ("Slot".to_string(), Value::Byte(slot as i8)),
+                    ("id".to_string(), Value::String(id.to_string())),
+                    ("count".to_string(), Value::Int(item.item_count.into())),
+                ]);
+                item.write_components(&mut nbt);
+                Some(Value::Compound(nbt))
+            })
+            .collect();
+        self.data.insert("Items".to_string(), Value::List(items));
+    }
+
+    /// A number stored as a short, e.g. the `BurnTime` of a furnace. 0 if it is missing
+    pub fn short(&self, key: &str) -> i16 {
+        match self.data.get(key) {
+            Some(Value::Short(value)) => *value,
+            _ => 0,
+        }
+    }
+
+    pub fn set_short(&mut self, key: &str, value: i16) {
+        self.data.insert(key.to_string(), Value::Short(value));
+    }
+
+    /// How many items a furnace cooked with each recipe since a player last took its
+    /// experience, by the id of the recipe
+    pub fn recipes_used(&self) -> HashMap<String, i32> {
+        let Some(Value::Compound(recipes)) = self.data.get("RecipesUsed") else {
+            return HashMap::new();
+        };
+        recipes
+            .iter()
+            .filter_map(|(id, count)| match count {
+                Value::Int(count) => Some((id.clone(), *count)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn set_recipes_used(&mut self, recipes: HashMap<String, i32>) {
+        let recipes = recipes
+            .into_iter()
+            .map(|(id, count)| (id, Value::Int(count)))
+            .collect();
+        self.data
+            .insert("RecipesUsed".to_string(), Value::Compound(recipes));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::BlockState;
+
+    #[test]
+    fn signs_have_block_entities() {
+        let sign: BlockId = BlockState::new("minecraft:oak_sign", None).unwrap().into();
+        let entity = BlockEntity::for_block(sign, Vector3::new(1, 2, 3)).unwrap();
+        assert_eq!(entity.id, "minecraft:sign");
+        assert!(!entity.is_waxed());
+        assert!(entity.client_data().contains_key("front_text"));
+
+        let stone: BlockId = BlockState::new("minecraft:stone", None).unwrap().into();
+        assert!(BlockEntity::for_block(stone, Vector3::new(1, 2, 3)).is_none());
+    }
+
+    #[test]
+    fn nbt_round_trip() {
+        let chest: BlockId = BlockState::new("minecraft:chest", None).unwrap().into();
+        let mut entity = BlockEntity::for_block(chest, Vector3::new(-5, 70, 12)).unwrap();
+        let stone = global_registry::get_protocol_id(ITEM_REGISTRY, "minecraft:stone");
+        let mut items = [None; 27];
+        items[4] = Some(ItemStack::new(12, stone));
+        entity.set_items(&items);
+
+        let read = BlockEntity::from_nbt(entity.to_nbt()).unwrap();
+        assert_eq!(read, entity);
+        assert_eq!(read.items(), vec![(4, items[4].unwrap())]);
+        assert!(read.client_data().is_empty());
+    }
+}
@@ -9,6 +9,21 @@ pub static BLOCKS: LazyLock<HashMap<String, RegistryBlockType>> = LazyLock::new(
         .expect("Could not parse block.json registry.")
 });
 
+/// The registry id and properties of every block state, used to save blocks
+static BLOCK_STATES_BY_ID: LazyLock<
+    HashMap<u16, (&'static str, &'static HashMap<String, String>)>,
+> = LazyLock::new(|| {
+    BLOCKS
+        .iter()
+        .flat_map(|(registry_id, block)| {
+            block
+                .states
+                .iter()
+                .map(move |state| (state.id.data, (registry_id.as_str(), &state.properties)))
+        })
+        .collect()
+});
+
 pumpkin_macros::blocks_enum!();
 pumpkin_macros::block_categories_enum!();
 
@@ -70,6 +85,11 @@ impl BlockId {
     pub fn get_id(&self) -> u16 {
         self.data
     }
+
+    /// The registry id and properties of this block state, e.g. `minecraft:oak_log` and `axis=y`
+    pub fn registry_state(&self) -> Option<(&'static str, &'static HashMap<String, String>)> {
+        BLOCK_STATES_BY_ID.get(&self.data).copied()
+    }
 }
 
 impl From<BlockState> for BlockId {
@@ -0,0 +1,329 @@
+use std::{collections::HashMap, sync::LazyLock};
+
+use super::{
+    behavior,
+    block_registry::{BlockId, BLOCKS},
+};
+
+/// The highest light level, of sky light and of the brightest light sources
+pub const MAX_LIGHT: u8 = 15;
+
+/// Blocks which always glow, with their light level
+const LIGHT_SOURCES: [(&str, u8); 38] = [
+    ("minecraft:beacon", 15),
+    ("minecraft:conduit", 15),
+    ("minecraft:end_gateway", 15),
+    ("minecraft:end_portal", 15),
+    ("minecraft:fire", 15),
+    ("minecraft:glowstone", 15),
+    ("minecraft:jack_o_lantern", 15),
+    ("minecraft:lantern", 15),
+    ("minecraft:lava", 15),
+    ("minecraft:lava_cauldron", 15),
+    ("minecraft:ochre_froglight", 15),
+    ("minecraft:pearlescent_froglight", 15),
+    ("minecraft:sea_lantern", 15),
+    ("minecraft:shroomlight", 15),
+    ("minecraft:verdant_froglight", 15),
+    ("minecraft:end_rod", 14),
+    ("minecraft:torch", 14),
+    ("minecraft:wall_torch", 14),
+    ("minecraft:nether_portal", 11),
+    ("minecraft:crying_obsidian", 10),
+    ("minecraft:soul_fire", 10),
+    ("minecraft:soul_lantern", 10),
+    ("minecraft:soul_torch", 10),
+    ("minecraft:soul_wall_torch", 10),
+    ("minecraft:enchanting_table", 7),
+    ("minecraft:ender_chest", 7),
+    ("minecraft:glow_lichen", 7),
+    ("minecraft:amethyst_cluster", 5),
+    ("minecraft:large_amethyst_bud", 4),
+    ("minecraft:magma_block", 3),
+    ("minecraft:medium_amethyst_bud", 2),
+    ("minecraft:brewing_stand", 1),
+    ("minecraft:brown_mushroom", 1),
+    ("minecraft:calibrated_sculk_sensor", 1),
+    ("minecraft:dragon_egg", 1),
+    ("minecraft:end_portal_frame", 1),
+    ("minecraft:sculk_sensor", 1),
+    ("minecraft:small_amethyst_bud", 1),
+];
+
+/// Blocks which only glow while their `lit` property is true, with their light level
+const LIT_LIGHT_SOURCES: [(&str, u8); 10] = [
+    ("minecraft:campfire", 15),
+    ("minecraft:redstone_lamp", 15),
+    ("minecraft:blast_furnace", 13),
+    ("minecraft:furnace", 13),
+    ("minecraft:smoker", 13),
+    ("minecraft:soul_campfire", 10),
+    ("minecraft:deepslate_redstone_ore", 9),
+    ("minecraft:redstone_ore", 9),
+    ("minecraft:redstone_torch", 7),
+    ("minecraft:redstone_wall_torch", 7),
+];
+
+/// Block categories light passes through without getting darker, besides the ones without collision
+const TRANSPARENT_CATEGORIES: [&str; 120] = [
+    "minecraft:air",
+    "minecraft:amethyst_cluster",
+    "minecraft:anvil",
+    "minecraft:azalea",
+    "minecraft:bamboo_sapling",
+    "minecraft:bamboo_stalk",
+    "minecraft:banner",
+    "minecraft:barrier",
+    "minecraft:base_coral_fan",
+    "minecraft:base_coral_plant",
+    "minecraft:base_coral_wall_fan",
+    "minecraft:beacon",
+    "minecraft:bed",
+    "minecraft:bell",
+    "minecraft:big_dripleaf",
+    "minecraft:big_dripleaf_stem",
+    "minecraft:brewing_stand",
+    "minecraft:cactus",
+    "minecraft:cake",
+    "minecraft:calibrated_sculk_sensor",
+    "minecraft:campfire",
+    "minecraft:candle",
+    "minecraft:candle_cake",
+    "minecraft:carpet",
+    "minecraft:cauldron",
+    "minecraft:cave_vines",
+    "minecraft:cave_vines_plant",
+    "minecraft:ceiling_hanging_sign",
+    "minecraft:chain",
+    "minecraft:chest",
+    "minecraft:chorus_flower",
+    "minecraft:chorus_plant",
+    "minecraft:cocoa",
+    "minecraft:comparator",
+    "minecraft:composter",
+    "minecraft:conduit",
+    "minecraft:coral_fan",
+    "minecraft:coral_plant",
+    "minecraft:coral_wall_fan",
+    "minecraft:daylight_detector",
+    "minecraft:decorated_pot",
+    "minecraft:door",
+    "minecraft:dragon_egg",
+    "minecraft:enchantment_table",
+    "minecraft:end_gateway",
+    "minecraft:end_portal",
+    "minecraft:end_portal_frame",
+    "minecraft:end_rod",
+    "minecraft:ender_chest",
+    "minecraft:fence",
+    "minecraft:fence_gate",
+    "minecraft:flower_pot",
+    "minecraft:frogspawn",
+    "minecraft:glow_lichen",
+    "minecraft:grindstone",
+    "minecraft:heavy_core",
+    "minecraft:hopper",
+    "minecraft:iron_bars",
+    "minecraft:ladder",
+    "minecraft:lantern",
+    "minecraft:lava_cauldron",
+    "minecraft:layered_cauldron",
+    "minecraft:lectern",
+    "minecraft:lever",
+    "minecraft:lightning_rod",
+    "minecraft:mangrove_propagule",
+    "minecraft:mangrove_roots",
+    "minecraft:moving_piston",
+    "minecraft:nether_portal",
+    "minecraft:piglinwallskull",
+    "minecraft:pink_petals",
+    "minecraft:piston_head",
+    "minecraft:pitcher_crop",
+    "minecraft:player_head",
+    "minecraft:player_wall_head",
+    "minecraft:pointed_dripstone",
+    "minecraft:repeater",
+    "minecraft:scaffolding",
+    "minecraft:sculk_sensor",
+    "minecraft:sculk_shrieker",
+    "minecraft:sculk_vein",
+    "minecraft:sea_pickle",
+    "minecraft:shulker_box",
+    "minecraft:skull",
+    "minecraft:slab",
+    "minecraft:small_dripleaf",
+    "minecraft:sniffer_egg",
+    "minecraft:snow_layer",
+    "minecraft:spawner",
+    "minecraft:spore_blossom",
+    "minecraft:stained_glass",
+    "minecraft:stained_glass_pane",
+    "minecraft:stair",
+    "minecraft:stonecutter",
+    "minecraft:sweet_berry_bush",
+    "minecraft:torchflower_crop",
+    "minecraft:transparent",
+    "minecraft:trapdoor",
+    "minecraft:trapped_chest",
+    "minecraft:trial_spawner",
+    "minecraft:trip_wire_hook",
+    "minecraft:tripwire",
+    "minecraft:turtle_egg",
+    "minecraft:twisting_vines",
+    "minecraft:twisting_vines_plant",
+    "minecraft:vault",
+    "minecraft:vine",
+    "minecraft:wall",
+    "minecraft:wall_banner",
+    "minecraft:wall_hanging_sign",
+    "minecraft:wall_skull",
+    "minecraft:waterlily",
+    "minecraft:waterlogged_transparent",
+    "minecraft:weeping_vines",
+    "minecraft:weeping_vines_plant",
+    "minecraft:weighted_pressure_plate",
+    "minecraft:wither_rose",
+    "minecraft:wither_skull",
+    "minecraft:wither_wall_skull",
+    "minecraft:wool_carpet",
+];
+
+/// Block categories which dim light passing through them by one level, like water
+const DIMMING_CATEGORIES: [&str; 16] = [
+    "minecraft:bubble_column",
+    "minecraft:cherry_leaves",
+    "minecraft:frosted_ice",
+    "minecraft:half_transparent",
+    "minecraft:honey",
+    "minecraft:ice",
+    "minecraft:kelp",
+    "minecraft:kelp_plant",
+    "minecraft:leaves",
+    "minecraft:liquid",
+    "minecraft:mangrove_leaves",
+    "minecraft:powder_snow",
+    "minecraft:seagrass",
+    "minecraft:slime",
+    "minecraft:tall_seagrass",
+    "minecraft:web",
+];
+
+/// The light emission and opacity of every block state, indexed by the state id
+static LIGHT_PROPERTIES: LazyLock<Vec<(u8, u8)>> = LazyLock::new(|| {
+    let mut properties = Vec::new();
+    for (registry_id, block) in BLOCKS.iter() {
+        let category = block.definition.category.as_str();
+        for state in &block.states {
+            let index = state.id.data as usize;
+            if properties.len() <= index {
+                properties.resize(index + 1, (0, MAX_LIGHT));
+            }
+            properties[index] = (
+                emission(registry_id, category, &state.properties),
+                opacity(registry_id, category, &state.properties),
+            );
+        }
+    }
+    properties
+});
+
+fn emission(registry_id: &str, category: &str, properties: &HashMap<String, String>) -> u8 {
+    let property = |name: &str| properties.get(name).map(String::as_str);
+    let number = |name: &str| property(name).and_then(|value| value.parse::<u8>().ok());
+    if let Some((_, level)) = LIGHT_SOURCES.iter().find(|(id, _)| *id == registry_id) {
+        return *level;
+    }
+    let lit = property("lit") == Some("true");
+    if let Some((_, level)) = LIT_LIGHT_SOURCES.iter().find(|(id, _)| *id == registry_id) {
+        return if lit { *level } else { 0 };
+    }
+    match category {
+        "minecraft:light" => number("level").unwrap_or(MAX_LIGHT),
+        "minecraft:candle" if lit => number("candles").unwrap_or(1) * 3,
+        "minecraft:candle_cake" if lit => 3,
+        // Sea pickles only glow under water
+        "minecraft:sea_pickle" if property("waterlogged") == Some("true") => {
+            number("pickles").unwrap_or(1) * 3 + 3
+        }
+        "minecraft:respawn_anchor" => match number("charges").unwrap_or(0) {
+            0 => 0,
+            charges => charges * 4 - 1,
+        },
+        "minecraft:cave_vines" | "minecraft:cave_vines_plant"
+            if property("berries") == Some("true") =>
+        {
+            14
+        }
+        _ => 0,
+    }
+}
+
+fn opacity(registry_id: &str, category: &str, properties: &HashMap<String, String>) -> u8 {
+    if registry_id == "minecraft:tinted_glass" {
+        return MAX_LIGHT;
+    }
+    if DIMMING_CATEGORIES.contains(&category) {
+        return 1;
+    }
+    // Copper doors, trapdoors, slabs, stairs and grates
+    let copper = category.starts_with("minecraft:weathering_copper_")
+        && category != "minecraft:weathering_copper_full";
+    let transparent = TRANSPARENT_CATEGORIES.contains(&category)
+        || copper
+        || behavior::is_passable_category(category);
+    if !transparent {
+        return MAX_LIGHT;
+    }
+    // Water in a block dims the light, like water itself
+    if properties
+        .get("waterlogged")
+        .is_some_and(|value| value == "true")
+    {
+        1
+    } else {
+        0
+    }
+}
+
+impl BlockId {
+    /// How bright the block glows, from 0 to 15
+    pub fn light_emission(&self) -> u8 {
+        LIGHT_PROPERTIES
+            .get(self.data as usize)
+            .map_or(0, |(emission, _)| *emission)
+    }
+
+    /// How many light levels light loses when passing through the block, 15 for opaque blocks.
+    /// Light loses at least one level per block anyway, except sky light going straight down
+    pub fn light_opacity(&self) -> u8 {
+        LIGHT_PROPERTIES
+            .get(self.data as usize)
+            .map_or(MAX_LIGHT, |(_, opacity)| *opacity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::block;
+
+    #[test]
+    fn light_sources() {
+        assert_eq!(block("minecraft:torch").light_emission(), 14);
+        assert_eq!(block("minecraft:glowstone").light_emission(), 15);
+        assert_eq!(block("minecraft:stone").light_emission(), 0);
+        // Furnaces are not lit by default
+        assert_eq!(block("minecraft:furnace").light_emission(), 0);
+    }
+
+    #[test]
+    fn light_opacity() {
+        assert_eq!(block("minecraft:air").light_opacity(), 0);
+        assert_eq!(block("minecraft:glass").light_opacity(), 0);
+        assert_eq!(block("minecraft:torch").light_opacity(), 0);
+        assert_eq!(block("minecraft:water").light_opacity(), 1);
+        assert_eq!(block("minecraft:oak_leaves").light_opacity(), 1);
+        assert_eq!(block("minecraft:stone").light_opacity(), 15);
+        assert_eq!(block("minecraft:glowstone").light_opacity(), 15);
+    }
+}
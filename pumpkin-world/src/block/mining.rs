@@ -0,0 +1,532 @@
+use rand::Rng;
+
+use super::block_registry::BlockId;
+use crate::{
+    global_registry::{find_protocol_id, ITEM_REGISTRY},
+    item::{Enchantment, ItemStack},
+};
+
+/// The hardness of blocks by the end of their id, the first match counts.
+/// The block data has no hardness, so these follow vanilla for common blocks
+const HARDNESSES: [(&str, f32); 63] = [
+    ("crying_obsidian", 50.0),
+    ("obsidian", 50.0),
+    ("netherite_block", 50.0),
+    ("respawn_anchor", 50.0),
+    ("ancient_debris", 30.0),
+    ("ender_chest", 22.5),
+    ("reinforced_deepslate", 55.0),
+    ("deepslate_coal_ore", 4.5),
+    ("deepslate_copper_ore", 4.5),
+    ("deepslate_diamond_ore", 4.5),
+    ("deepslate_emerald_ore", 4.5),
+    ("deepslate_gold_ore", 4.5),
+    ("deepslate_iron_ore", 4.5),
+    ("deepslate_lapis_ore", 4.5),
+    ("deepslate_redstone_ore", 4.5),
+    ("_ore", 3.0),
+    ("cobbled_deepslate", 3.5),
+    ("deepslate", 3.0),
+    ("anvil", 5.0),
+    ("enchanting_table", 5.0),
+    ("iron_bars", 5.0),
+    ("iron_door", 5.0),
+    ("iron_trapdoor", 5.0),
+    ("spawner", 5.0),
+    ("furnace", 3.5),
+    ("smoker", 3.5),
+    ("dispenser", 3.5),
+    ("dropper", 3.5),
+    ("hopper", 3.0),
+    ("end_stone", 3.0),
+    ("blue_ice", 2.8),
+    ("chest", 2.5),
+    ("barrel", 2.5),
+    ("crafting_table", 2.5),
+    ("cobblestone", 2.0),
+    ("bricks", 2.0),
+    ("_door", 3.0),
+    ("_trapdoor", 3.0),
+    ("_planks", 2.0),
+    ("_log", 2.0),
+    ("_wood", 2.0),
+    ("_stem", 2.0),
+    ("_hyphae", 2.0),
+    ("_fence", 2.0),
+    ("_fence_gate", 2.0),
+    ("glowstone", 0.3),
+    ("stone", 1.5),
+    ("bookshelf", 1.5),
+    ("terracotta", 1.25),
+    ("basalt", 1.25),
+    ("_concrete", 1.8),
+    ("pumpkin", 1.0),
+    ("melon", 1.0),
+    ("_wool", 0.8),
+    ("sandstone", 0.8),
+    ("grass_block", 0.6),
+    ("gravel", 0.6),
+    ("clay", 0.6),
+    ("netherrack", 0.4),
+    ("_leaves", 0.2),
+    ("glass", 0.3),
+    ("glass_pane", 0.3),
+    ("_block", 5.0),
+];
+
+/// Blocks matching none of `HARDNESSES`, most of them are dirt like
+const DEFAULT_HARDNESS: f32 = 0.5;
+
+/// Blocks which are broken fastest with a pickaxe, by the end of their id
+const PICKAXE_BLOCKS: [&str; 38] = [
+    "stone",
+    "cobblestone",
+    "deepslate",
+    "_ore",
+    "ancient_debris",
+    "obsidian",
+    "bricks",
+    "terracotta",
+    "_concrete",
+    "netherrack",
+    "basalt",
+    "sandstone",
+    "end_stone",
+    "andesite",
+    "diorite",
+    "granite",
+    "tuff",
+    "calcite",
+    "dripstone_block",
+    "iron_block",
+    "gold_block",
+    "diamond_block",
+    "emerald_block",
+    "lapis_block",
+    "redstone_block",
+    "copper_block",
+    "netherite_block",
+    "anvil",
+    "furnace",
+    "smoker",
+    "dispenser",
+    "dropper",
+    "hopper",
+    "iron_bars",
+    "iron_door",
+    "iron_trapdoor",
+    "ice",
+    "rail",
+];
+
+/// Blocks which are broken fastest with an axe, by the end of their id
+const AXE_BLOCKS: [&str; 17] = [
+    "_planks",
+    "_log",
+    "_wood",
+    "_stem",
+    "_hyphae",
+    "_fence",
+    "_fence_gate",
+    "_door",
+    "_trapdoor",
+    "_sign",
+    "chest",
+    "barrel",
+    "crafting_table",
+    "bookshelf",
+    "pumpkin",
+    "melon",
+    "ladder",
+];
+
+/// Blocks which are broken fastest with a shovel, by the end of their id
+const SHOVEL_BLOCKS: [&str; 14] = [
+    "dirt",
+    "grass_block",
+    "podzol",
+    "mycelium",
+    "farmland",
+    "dirt_path",
+    "sand",
+    "gravel",
+    "clay",
+    "soul_soil",
+    "snow",
+    "snow_block",
+    "concrete_powder",
+    "mud",
+];
+
+/// Blocks which are broken fastest with a hoe, by the end of their id
+const HOE_BLOCKS: [&str; 7] = [
+    "_leaves",
+    "sponge",
+    "hay_block",
+    "sculk",
+    "shroomlight",
+    "wart_block",
+    "moss_block",
+];
+
+/// Blocks which drop nothing without a pickaxe of at least this tier, by the end of their id.
+/// 1 is stone, 2 iron and 3 diamond
+const PICKAXE_TIERS: [(&str, u8); 13] = [
+    ("obsidian", 3),
+    ("ancient_debris", 3),
+    ("netherite_block", 3),
+    ("respawn_anchor", 3),
+    ("diamond_ore", 2),
+    ("diamond_block", 2),
+    ("emerald_ore", 2),
+    ("emerald_block", 2),
+    ("gold_ore", 2),
+    ("gold_block", 2),
+    ("redstone_ore", 2),
+    ("iron_ore", 1),
+    ("iron_block", 1),
+];
+
+/// Pickaxe blocks which drop without the right tool too
+const PICKAXE_ALWAYS_DROPS: [&str; 3] = ["rail", "ice", "hopper"];
+
+/// What breaking a block drops, if it isn't the block itself. The count is a range
+const DROPS: [(&str, &str, u8, u8); 31] = [
+    ("minecraft:stone", "minecraft:cobblestone", 1, 1),
+    ("minecraft:deepslate", "minecraft:cobbled_deepslate", 1, 1),
+    ("minecraft:grass_block", "minecraft:dirt", 1, 1),
+    ("minecraft:mycelium", "minecraft:dirt", 1, 1),
+    ("minecraft:podzol", "minecraft:dirt", 1, 1),
+    ("minecraft:dirt_path", "minecraft:dirt", 1, 1),
+    ("minecraft:farmland", "minecraft:dirt", 1, 1),
+    ("minecraft:coal_ore", "minecraft:coal", 1, 1),
+    ("minecraft:deepslate_coal_ore", "minecraft:coal", 1, 1),
+    ("minecraft:iron_ore", "minecraft:raw_iron", 1, 1),
+    ("minecraft:deepslate_iron_ore", "minecraft:raw_iron", 1, 1),
+    ("minecraft:gold_ore", "minecraft:raw_gold", 1, 1),
+    ("minecraft:deepslate_gold_ore", "minecraft:raw_gold", 1, 1),
+    ("minecraft:copper_ore", "minecraft:raw_copper", 2, 5),
+    (
+        "minecraft:deepslate_copper_ore",
+        "minecraft:raw_copper",
+        2,
+        5,
+    ),
+    ("minecraft:diamond_ore", "minecraft:diamond", 1, 1),
+    ("minecraft:deepslate_diamond_ore", "minecraft:diamond", 1, 1),
+    ("minecraft:emerald_ore", "minecraft:emerald", 1, 1),
+    ("minecraft:deepslate_emerald_ore", "minecraft:emerald", 1, 1),
+    ("minecraft:lapis_ore", "minecraft:lapis_lazuli", 4, 9),
+    (
+        "minecraft:deepslate_lapis_ore",
+        "minecraft:lapis_lazuli",
+        4,
+        9,
+    ),
+    ("minecraft:redstone_ore", "minecraft:redstone", 4, 5),
+    (
+        "minecraft:deepslate_redstone_ore",
+        "minecraft:redstone",
+        4,
+        5,
+    ),
+    ("minecraft:nether_quartz_ore", "minecraft:quartz", 1, 1),
+    ("minecraft:nether_gold_ore", "minecraft:gold_nugget", 2, 6),
+    ("minecraft:glowstone", "minecraft:glowstone_dust", 2, 4),
+    ("minecraft:clay", "minecraft:clay_ball", 4, 4),
+    ("minecraft:melon", "minecraft:melon_slice", 3, 7),
+    ("minecraft:snow_block", "minecraft:snowball", 4, 4),
+    ("minecraft:bookshelf", "minecraft:book", 3, 3),
+    ("minecraft:wheat", "minecraft:wheat_seeds", 1, 1),
+];
+
+/// Blocks which drop nothing unless they are broken with silk touch or shears, by the end of
+/// their id
+const NO_DROPS: [&str; 9] = [
+    "glass",
+    "glass_pane",
+    "ice",
+    "_leaves",
+    "short_grass",
+    "fern",
+    "budding_amethyst",
+    "spawner",
+    "cobweb",
+];
+
+/// The experience breaking a block with the right tool drops, as a range
+const EXPERIENCE: [(&str, i32, i32); 15] = [
+    ("minecraft:coal_ore", 0, 2),
+    ("minecraft:deepslate_coal_ore", 0, 2),
+    ("minecraft:diamond_ore", 3, 7),
+    ("minecraft:deepslate_diamond_ore", 3, 7),
+    ("minecraft:emerald_ore", 3, 7),
+    ("minecraft:deepslate_emerald_ore", 3, 7),
+    ("minecraft:lapis_ore", 2, 5),
+    ("minecraft:deepslate_lapis_ore", 2, 5),
+    ("minecraft:redstone_ore", 1, 5),
+    ("minecraft:deepslate_redstone_ore", 1, 5),
+    ("minecraft:nether_quartz_ore", 2, 5),
+    ("minecraft:nether_gold_ore", 0, 1),
+    ("minecraft:spawner", 15, 43),
+    ("minecraft:sculk", 1, 1),
+    ("minecraft:sculk_catalyst", 5, 5),
+];
+
+/// What slows down or speeds up breaking blocks
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MiningConditions {
+    /// The amplifier of the haste effect, 0 for haste I
+    pub haste: Option<u8>,
+    /// The amplifier of the mining fatigue effect, 0 for mining fatigue I
+    pub mining_fatigue: Option<u8>,
+    /// Whether the player has their head in water
+    pub in_water: bool,
+    pub on_ground: bool,
+}
+
+impl BlockId {
+    /// How long the block takes to break, -1 for blocks which can't be broken
+    pub fn hardness(&self) -> f32 {
+        if self.is_unbreakable() {
+            return -1.0;
+        }
+        if self.is_air() || self.breaks_instantly() {
+            return 0.0;
+        }
+        self.registry_state()
+            .and_then(|(registry_id, _)| {
+                HARDNESSES
+                    .iter()
+                    .find(|(suffix, _)| registry_id.ends_with(suffix))
+            })
+            .map_or(DEFAULT_HARDNESS, |(_, hardness)| *hardness)
+    }
+
+    /// Whether the block drops nothing unless it is broken with the right tool, e.g. stone
+    pub fn requires_correct_tool(&self) -> bool {
+        if self.is_in("#minecraft:mineable/pickaxe") {
+            return !self.ends_with_any(&PICKAXE_ALWAYS_DROPS);
+        }
+        self.is_in("minecraft:cobweb") || self.is_in("minecraft:snow")
+    }
+
+    /// Whether the block is in a block tag like `#minecraft:mineable/axe` or is the block with
+    /// the id, as tool rules refer to blocks. There are no block tags yet, so the tags tools use
+    /// are made up from the end of the block ids
+    pub fn is_in(&self, blocks: &str) -> bool {
+        let Some((registry_id, _)) = self.registry_state() else {
+            return false;
+        };
+        let Some(tag) = blocks.strip_prefix("#minecraft:") else {
+            return registry_id == blocks;
+        };
+        // The lowest tier of tools which drop the block
+        let tier = || {
+            PICKAXE_TIERS
+                .iter()
+                .find(|(suffix, _)| registry_id.ends_with(suffix))
+                .map_or(0, |(_, tier)| *tier)
+        };
+        match tag {
+            "mineable/pickaxe" => {
+                self.ends_with_any(&PICKAXE_BLOCKS) && !registry_id.ends_with("glowstone")
+            }
+            "mineable/axe" => self.ends_with_any(&AXE_BLOCKS),
+            "mineable/shovel" => self.ends_with_any(&SHOVEL_BLOCKS),
+            "mineable/hoe" => self.ends_with_any(&HOE_BLOCKS),
+            "leaves" => self.category() == Some("minecraft:leaves"),
+            "wool" => registry_id.ends_with("_wool"),
+            "sword_efficient" => {
+                self.is_in("#minecraft:leaves")
+                    || self.breaks_instantly()
+                    || ["pumpkin", "melon", "vine", "cocoa"]
+                        .iter()
+                        .any(|suffix| registry_id.ends_with(suffix))
+            }
+            "incorrect_for_wooden_tool" | "incorrect_for_gold_tool" => tier() >= 1,
+            "incorrect_for_stone_tool" => tier() >= 2,
+            "incorrect_for_iron_tool" => tier() >= 3,
+            _ => false,
+        }
+    }
+
+    fn ends_with_any(&self, suffixes: &[&str]) -> bool {
+        self.registry_state().is_some_and(|(registry_id, _)| {
+            suffixes.iter().any(|suffix| registry_id.ends_with(suffix))
+        })
+    }
+
+    /// How much of the block is broken every tick, it breaks once this adds up to 1.
+    /// 0 for blocks which can't be broken
+    pub fn destroy_progress(&self, tool: Option<&ItemStack>, conditions: &MiningConditions) -> f32 {
+        let hardness = self.hardness();
+        if hardness < 0.0 {
+            return 0.0;
+        }
+        if hardness == 0.0 {
+            return 1.0;
+        }
+        let mut speed = tool.map_or(1.0, |tool| tool.mining_speed(self));
+        if let Some(haste) = conditions.haste {
+            speed *= 1.0 + 0.2 * (f32::from(haste) + 1.0);
+        }
+        if let Some(mining_fatigue) = conditions.mining_fatigue {
+            speed *= match mining_fatigue {
+                0 => 0.3,
+                1 => 0.09,
+                2 => 0.0027,
+                _ => 0.000_81,
+            };
+        }
+        // TODO: Aqua affinity
+        if conditions.in_water {
+            speed /= 5.0;
+        }
+        if !conditions.on_ground {
+            speed /= 5.0;
+        }
+        let harvests = !self.requires_correct_tool()
+            || tool.is_some_and(|tool| tool.is_correct_for_drops(self));
+        speed / hardness / if harvests { 30.0 } else { 100.0 }
+    }
+
+    /// The items breaking the block with `tool` drops
+    // TODO: Silk touch and fortune
+    pub fn drops(&self, tool: Option<&ItemStack>) -> Vec<ItemStack> {
+        if self.requires_correct_tool() && !tool.is_some_and(|tool| tool.is_correct_for_drops(self))
+        {
+            return Vec::new();
+        }
+        self.drops_ignoring_tool()
+    }
+
+    /// The items the block drops when nobody broke it, e.g. in an explosion. Blocks which need
+    /// a tool drop their items as well
+    pub fn drops_ignoring_tool(&self) -> Vec<ItemStack> {
+        let Some((registry_id, properties)) = self.registry_state() else {
+            return Vec::new();
+        };
+        if self.ends_with_any(&NO_DROPS) {
+            return Vec::new();
+        }
+        // Blocks made of two, like doors and beds, drop once
+        if properties.get("half").is_some_and(|half| half == "upper")
+            || properties.get("part").is_some_and(|part| part == "head")
+        {
+            return Vec::new();
+        }
+        let (item, count) = match DROPS.iter().find(|(block, ..)| *block == registry_id) {
+            Some((_, item, min, max)) => (*item, rand::thread_rng().gen_range(*min..=*max)),
+            // Slabs in one block are two slabs
+            None if properties
+                .get("type")
+                .is_some_and(|value| value == "double") =>
+            {
+                (registry_id, 2)
+            }
+            None => (registry_id, 1),
+        };
+        // Blocks on walls are the same item as the standing ones, e.g. torches and signs
+        let item_id = find_protocol_id(ITEM_REGISTRY, item)
+            .or_else(|| find_protocol_id(ITEM_REGISTRY, &item.replacen("_wall", "", 1)))
+            .or_else(|| find_protocol_id(ITEM_REGISTRY, &item.replacen("wall_", "", 1)));
+        match item_id {
+            Some(item_id) => vec![ItemStack::new(count, item_id)],
+            None => Vec::new(),
+        }
+    }
+
+    /// The experience breaking the block with `tool` drops, mostly ores. Silk touch drops none
+    pub fn experience(&self, tool: Option<&ItemStack>) -> i32 {
+        let Some((registry_id, _)) = self.registry_state() else {
+            return 0;
+        };
+        if tool.is_some_and(|tool| tool.enchantments.level(Enchantment::SilkTouch) > 0) {
+            return 0;
+        }
+        if self.requires_correct_tool() && !tool.is_some_and(|tool| tool.is_correct_for_drops(self))
+        {
+            return 0;
+        }
+        EXPERIENCE
+            .iter()
+            .find(|(block, ..)| *block == registry_id)
+            .map_or(0, |(_, min, max)| rand::thread_rng().gen_range(*min..=*max))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_utils::{block, item};
+
+    use super::*;
+
+    fn ticks(block: &BlockId, tool: Option<&ItemStack>) -> f32 {
+        let conditions = MiningConditions {
+            on_ground: true,
+            ..Default::default()
+        };
+        (1.0 / block.destroy_progress(tool, &conditions)).ceil()
+    }
+
+    #[test]
+    fn break_times() {
+        let stone = block("minecraft:stone");
+        // Like in vanilla: 7.5 seconds by hand, 1.15 with a wooden pickaxe
+        assert_eq!(ticks(&stone, None), 150.0);
+        assert_eq!(ticks(&stone, Some(&item("minecraft:wooden_pickaxe"))), 23.0);
+        // The wrong tool is as fast as the hand
+        assert_eq!(ticks(&stone, Some(&item("minecraft:diamond_axe"))), 150.0);
+        assert_eq!(ticks(&block("minecraft:oak_log"), None), 60.0);
+        assert_eq!(
+            block("minecraft:short_grass").destroy_progress(None, &MiningConditions::default()),
+            1.0
+        );
+        assert_eq!(
+            block("minecraft:bedrock").destroy_progress(None, &MiningConditions::default()),
+            0.0
+        );
+    }
+
+    #[test]
+    fn tool_drops() {
+        let stone = block("minecraft:stone");
+        assert!(stone.drops(None).is_empty());
+        let drops = stone.drops(Some(&item("minecraft:wooden_pickaxe")));
+        assert_eq!(drops, vec![item("minecraft:cobblestone")]);
+
+        let diamond_ore = block("minecraft:diamond_ore");
+        assert!(diamond_ore
+            .drops(Some(&item("minecraft:stone_pickaxe")))
+            .is_empty());
+        assert_eq!(
+            diamond_ore.drops(Some(&item("minecraft:iron_pickaxe"))),
+            vec![item("minecraft:diamond")]
+        );
+
+        assert_eq!(
+            block("minecraft:oak_log").drops(None),
+            vec![item("minecraft:oak_log")]
+        );
+        assert_eq!(
+            block("minecraft:wall_torch").drops(None),
+            vec![item("minecraft:torch")]
+        );
+        assert!(block("minecraft:glass").drops(None).is_empty());
+    }
+
+    #[test]
+    fn experience_drops() {
+        let pickaxe = item("minecraft:iron_pickaxe");
+        let diamond_ore = block("minecraft:diamond_ore");
+        assert!((3..=7).contains(&diamond_ore.experience(Some(&pickaxe))));
+        assert_eq!(diamond_ore.experience(None), 0);
+        assert_eq!(block("minecraft:iron_ore").experience(Some(&pickaxe)), 0);
+        assert_eq!(block("minecraft:stone").experience(Some(&pickaxe)), 0);
+
+        let mut silk_touch = pickaxe;
+        silk_touch.enchantments.set(Enchantment::SilkTouch, 1);
+        assert_eq!(diamond_ore.experience(Some(&silk_touch)), 0);
+    }
+}
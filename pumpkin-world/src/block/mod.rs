@@ -1,12 +1,19 @@
 use num_derive::FromPrimitive;
 
+mod behavior;
+pub mod block_entity;
 mod block_registry;
 pub mod block_state;
+mod light;
+mod mining;
 
 use pumpkin_core::math::vector3::Vector3;
 
+pub use block_entity::BlockEntity;
 pub use block_registry::{Block, BlockCategory, BlockId};
 pub use block_state::BlockState;
+pub use light::MAX_LIGHT;
+pub use mining::MiningConditions;
 
 #[derive(FromPrimitive)]
 pub enum BlockFace {
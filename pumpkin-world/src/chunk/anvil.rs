@@ -1,26 +1,62 @@
 use std::{
+    collections::HashMap,
     fs::OpenOptions,
-    io::{Read, Seek},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-use flate2::bufread::{GzDecoder, ZlibDecoder};
+use flate2::{
+    bufread::{GzDecoder, GzEncoder, ZlibDecoder, ZlibEncoder},
+    Compression as CompressionLevel,
+};
 use itertools::Itertools;
+use parking_lot::Mutex;
+use pumpkin_core::math::vector2::Vector2;
 
 use crate::level::SaveFile;
 
-use super::{ChunkData, ChunkReader, ChunkReadingError, CompressionError};
+use super::{
+    ChunkData, ChunkReader, ChunkReadingError, ChunkWriter, ChunkWritingError, CompressionError,
+};
+
+/// Set in the compression byte of chunks which are stored in a separate `.mcc` file
+const EXTERNAL_CHUNK_FLAG: u8 = 0x80;
+/// Region files are split into sectors of this size
+const SECTOR_SIZE: usize = 4096;
+/// The location and timestamp tables take up the first two sectors
+const HEADER_SECTORS: usize = 2;
+/// The sector count of a chunk is stored in a single byte
+const MAX_CHUNK_SECTORS: usize = 255;
+/// How new chunks are compressed, the same as vanilla
+const WRITE_COMPRESSION: Compression = Compression::ZLib;
 
-pub struct AnvilChunkReader {}
+pub struct AnvilChunkFormat {
+    /// One lock per region file. It is held from reading the header until the header is
+    /// written again, so chunks of the same region saved at once don't get the same sectors
+    region_locks: Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>,
+}
 
-impl Default for AnvilChunkReader {
+impl Default for AnvilChunkFormat {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl AnvilChunkReader {
+impl AnvilChunkFormat {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            region_locks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn region_lock(&self, region_file: &Path) -> Arc<Mutex<()>> {
+        self.region_locks
+            .lock()
+            .entry(region_file.to_path_buf())
+            .or_default()
+            .clone()
     }
 }
 
@@ -51,6 +87,50 @@ impl Compression {
         }
     }
 
+    pub fn to_byte(self) -> u8 {
+        match self {
+            Self::GZip => 1,
+            Self::ZLib => 2,
+            Self::None => 3,
+            Self::LZ4 => 4,
+            Self::Custom => 127,
+        }
+    }
+
+    fn compress_data(&self, uncompressed_data: &[u8]) -> Result<Vec<u8>, CompressionError> {
+        match self {
+            Compression::GZip => {
+                let mut encoder = GzEncoder::new(uncompressed_data, CompressionLevel::default());
+                let mut chunk_data = Vec::new();
+                encoder
+                    .read_to_end(&mut chunk_data)
+                    .map_err(CompressionError::GZipError)?;
+                Ok(chunk_data)
+            }
+            Compression::ZLib => {
+                let mut encoder = ZlibEncoder::new(uncompressed_data, CompressionLevel::default());
+                let mut chunk_data = Vec::new();
+                encoder
+                    .read_to_end(&mut chunk_data)
+                    .map_err(CompressionError::ZlibError)?;
+                Ok(chunk_data)
+            }
+            Compression::None => Ok(uncompressed_data.to_vec()),
+            Compression::LZ4 => {
+                let mut encoder = lz4::EncoderBuilder::new()
+                    .build(Vec::new())
+                    .map_err(CompressionError::LZ4Error)?;
+                encoder
+                    .write_all(uncompressed_data)
+                    .map_err(CompressionError::LZ4Error)?;
+                let (compressed_data, result) = encoder.finish();
+                result.map_err(CompressionError::LZ4Error)?;
+                Ok(compressed_data)
+            }
+            Compression::Custom => Err(CompressionError::UnsupportedCustomCompression),
+        }
+    }
+
     fn decompress_data(&self, compressed_data: Vec<u8>) -> Result<Vec<u8>, CompressionError> {
         match self {
             Compression::GZip => {
@@ -79,29 +159,58 @@ impl Compression {
                     .map_err(CompressionError::LZ4Error)?;
                 Ok(decompressed_data)
             }
-            Compression::Custom => todo!(),
+            Compression::Custom => Err(CompressionError::UnsupportedCustomCompression),
         }
     }
 }
 
-impl ChunkReader for AnvilChunkReader {
+/// The name of the region file a chunk is stored in and the index of the chunk in its tables
+fn region_location(at: Vector2<i32>) -> (String, usize) {
+    let file_name = format!("r.{}.{}.mca", at.x >> 5, at.z >> 5);
+    let index = (at.x & 31) as usize + (at.z & 31) as usize * 32;
+    (file_name, index)
+}
+
+/// The first sector and the sector count of a chunk in the location table
+fn sector_location(location_table: &[u8], index: usize) -> (usize, usize) {
+    let entry = &location_table[index * 4..index * 4 + 4];
+    let offset = u32::from_be_bytes([0, entry[0], entry[1], entry[2]]) as usize;
+    (offset, entry[3] as usize)
+}
+
+/// Finds the first gap of `count` sectors which no chunk uses, or the end of the file
+fn allocate_sectors(location_table: &[u8], count: usize) -> usize {
+    let mut used = (0..1024)
+        .map(|index| sector_location(location_table, index))
+        .filter(|(_, sectors)| *sectors > 0)
+        .collect_vec();
+    used.sort_unstable();
+
+    let mut start = HEADER_SECTORS;
+    for (offset, sectors) in used {
+        if offset >= start + count {
+            break;
+        }
+        start = start.max(offset + sectors);
+    }
+    start
+}
+
+impl ChunkReader for AnvilChunkFormat {
     fn read_chunk(
         &self,
         save_file: &SaveFile,
-        at: pumpkin_core::math::vector2::Vector2<i32>,
+        at: Vector2<i32>,
     ) -> Result<super::ChunkData, ChunkReadingError> {
-        let region = (
-            ((at.x as f32) / 32.0).floor() as i32,
-            ((at.z as f32) / 32.0).floor() as i32,
-        );
+        let (file_name, index) = region_location(at);
+        let region_path = save_file.region_folder.join(file_name);
 
+        // A chunk being written may move, the header has to match the sectors read
+        let region_lock = self.region_lock(&region_path);
+        let _guard = region_lock.lock();
         let mut region_file = OpenOptions::new()
             .read(true)
-            .open(
-                save_file
-                    .region_folder
-                    .join(format!("r.{}.{}.mca", region.0, region.1)),
-            )
+            .open(region_path)
             .map_err(|err| match err.kind() {
                 std::io::ErrorKind::NotFound => ChunkReadingError::ChunkNotExist,
                 kind => ChunkReadingError::IoError(kind),
@@ -118,15 +227,9 @@ impl ChunkReader for AnvilChunkReader {
             .read_exact(&mut timestamp_table)
             .map_err(|err| ChunkReadingError::IoError(err.kind()))?;
 
-        let modulus = |a: i32, b: i32| ((a % b) + b) % b;
-        let chunk_x = modulus(at.x, 32) as u32;
-        let chunk_z = modulus(at.z, 32) as u32;
-        let table_entry = (chunk_x + chunk_z * 32) * 4;
-
-        let mut offset = vec![0u8];
-        offset.extend_from_slice(&location_table[table_entry as usize..table_entry as usize + 3]);
-        let offset = u32::from_be_bytes(offset.try_into().unwrap()) as u64 * 4096;
-        let size = location_table[table_entry as usize + 3] as usize * 4096;
+        let (offset, sectors) = sector_location(&location_table, index);
+        let offset = (offset * SECTOR_SIZE) as u64;
+        let size = sectors * SECTOR_SIZE;
 
         if offset == 0 && size == 0 {
             return Err(ChunkReadingError::ChunkNotExist);
@@ -145,15 +248,31 @@ impl ChunkReader for AnvilChunkReader {
         };
 
         // TODO: check checksum to make sure chunk is not corrupted
+        if file_buf.len() < 5 {
+            return Err(ChunkReadingError::RegionIsInvalid);
+        }
         let header = file_buf.drain(0..5).collect_vec();
 
-        let compression = Compression::from_byte(header[4])
+        // Chunks too large for the region file are stored in their own file
+        let external = header[4] & EXTERNAL_CHUNK_FLAG != 0;
+        let compression = Compression::from_byte(header[4] & !EXTERNAL_CHUNK_FLAG)
             .ok_or_else(|| ChunkReadingError::Compression(CompressionError::UnknownCompression))?;
 
-        let size = u32::from_be_bytes(header[..4].try_into().unwrap());
-
-        // size includes the compression scheme byte, so we need to subtract 1
-        let chunk_data = file_buf.drain(0..size as usize - 1).collect_vec();
+        let chunk_data = if external {
+            std::fs::read(
+                save_file
+                    .region_folder
+                    .join(format!("c.{}.{}.mcc", at.x, at.z)),
+            )
+            .map_err(|err| ChunkReadingError::IoError(err.kind()))?
+        } else {
+            let size = u32::from_be_bytes(header[..4].try_into().unwrap()) as usize;
+            // size includes the compression scheme byte, so we need to subtract 1
+            if size == 0 || size - 1 > file_buf.len() {
+                return Err(ChunkReadingError::RegionIsInvalid);
+            }
+            file_buf.drain(0..size - 1).collect_vec()
+        };
         let decompressed_chunk = compression
             .decompress_data(chunk_data)
             .map_err(ChunkReadingError::Compression)?;
@@ -161,3 +280,138 @@ impl ChunkReader for AnvilChunkReader {
         ChunkData::from_bytes(decompressed_chunk, at).map_err(ChunkReadingError::ParsingError)
     }
 }
+
+impl ChunkWriter for AnvilChunkFormat {
+    fn write_chunk(
+        &self,
+        chunk: &ChunkData,
+        save_file: &SaveFile,
+        at: Vector2<i32>,
+    ) -> Result<(), ChunkWritingError> {
+        let io_error = |err: std::io::Error| ChunkWritingError::IoError(err.kind());
+
+        let chunk_data = chunk
+            .to_bytes()
+            .map_err(ChunkWritingError::SerializingError)?;
+        let compressed_data = WRITE_COMPRESSION
+            .compress_data(&chunk_data)
+            .map_err(ChunkWritingError::Compression)?;
+
+        std::fs::create_dir_all(&save_file.region_folder).map_err(io_error)?;
+        let (file_name, index) = region_location(at);
+        let external_file = save_file
+            .region_folder
+            .join(format!("c.{}.{}.mcc", at.x, at.z));
+
+        // Length, compression byte and data, padded to whole sectors
+        let mut payload = Vec::with_capacity(compressed_data.len() + 5);
+        if compressed_data.len() + 5 > MAX_CHUNK_SECTORS * SECTOR_SIZE {
+            // Too large for the region file, only the header stays in there
+            std::fs::write(&external_file, &compressed_data).map_err(io_error)?;
+            payload.extend_from_slice(&1u32.to_be_bytes());
+            payload.push(WRITE_COMPRESSION.to_byte() | EXTERNAL_CHUNK_FLAG);
+        } else {
+            payload.extend_from_slice(&(compressed_data.len() as u32 + 1).to_be_bytes());
+            payload.push(WRITE_COMPRESSION.to_byte());
+            payload.extend_from_slice(&compressed_data);
+            if let Err(err) = std::fs::remove_file(&external_file) {
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    return Err(io_error(err));
+                }
+            }
+        }
+        let sectors = payload.len().div_ceil(SECTOR_SIZE);
+        payload.resize(sectors * SECTOR_SIZE, 0);
+
+        let region_path = save_file.region_folder.join(file_name);
+        let region_lock = self.region_lock(&region_path);
+        let _guard = region_lock.lock();
+        let mut region_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(region_path)
+            .map_err(io_error)?;
+
+        // A new region file gets an empty header
+        let mut header = [0; HEADER_SECTORS * SECTOR_SIZE];
+        if region_file.metadata().map_err(io_error)?.len() >= header.len() as u64 {
+            region_file.read_exact(&mut header).map_err(io_error)?;
+        }
+        let (location_table, timestamp_table) = header.split_at_mut(SECTOR_SIZE);
+
+        // Overwrite the chunk in place if it still fits, otherwise move it to free sectors.
+        // When moving, the old sectors stay in use until the header is written, so a crash
+        // in between leaves the old chunk intact
+        let (old_offset, old_sectors) = sector_location(location_table, index);
+        let offset = if old_offset >= HEADER_SECTORS && old_sectors >= sectors {
+            old_offset
+        } else {
+            allocate_sectors(location_table, sectors)
+        };
+
+        region_file
+            .seek(SeekFrom::Start((offset * SECTOR_SIZE) as u64))
+            .map_err(io_error)?;
+        region_file.write_all(&payload).map_err(io_error)?;
+
+        let location = ((offset as u32) << 8) | sectors as u32;
+        location_table[index * 4..index * 4 + 4].copy_from_slice(&location.to_be_bytes());
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs() as u32);
+        timestamp_table[index * 4..index * 4 + 4].copy_from_slice(&timestamp.to_be_bytes());
+
+        region_file.rewind().map_err(io_error)?;
+        region_file.write_all(&header).map_err(io_error)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pumpkin_core::math::vector2::Vector2;
+
+    use crate::{
+        chunk::{ChunkBiomes, ChunkBlocks, ChunkData, ChunkLight, ChunkReader, ChunkWriter},
+        level::SaveFile,
+        test_utils::TestFolder,
+    };
+
+    use super::AnvilChunkFormat;
+
+    #[test]
+    fn chunks_of_one_region_saved_at_once() {
+        let folder = TestFolder::new("anvil");
+        let save_file = SaveFile {
+            root_folder: folder.path().to_path_buf(),
+            region_folder: folder.path().join("region"),
+        };
+        let format = AnvilChunkFormat::new();
+        let positions = (0..32).map(|x| Vector2::new(x, 0)).collect::<Vec<_>>();
+
+        std::thread::scope(|scope| {
+            for &at in &positions {
+                let (format, save_file) = (&format, &save_file);
+                scope.spawn(move || {
+                    let chunk = ChunkData {
+                        blocks: ChunkBlocks::default(),
+                        biomes: ChunkBiomes::default(),
+                        position: at,
+                        block_entities: HashMap::new(),
+                        light: ChunkLight::default(),
+                    };
+                    format.write_chunk(&chunk, save_file, at).unwrap();
+                });
+            }
+        });
+
+        for at in positions {
+            let chunk = format.read_chunk(&save_file, at).unwrap();
+            assert_eq!(chunk.position, at);
+        }
+    }
+}
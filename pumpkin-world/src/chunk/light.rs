@@ -0,0 +1,82 @@
+use super::{CHUNK_VOLUME, SUBCHUNK_VOLUME};
+
+/// Bytes of light in a section, every byte holds the light of two blocks
+pub const SECTION_LIGHT_BYTES: usize = SUBCHUNK_VOLUME / 2;
+
+/// The light of a chunk, calculated when the chunk is loaded.
+/// Like the blocks, light is ordered yzx (y being the most significant)
+pub struct ChunkLight {
+    /// None in dimensions without sky light, e.g. the Nether
+    pub sky: Option<LightArray>,
+    pub block: LightArray,
+}
+
+impl Default for ChunkLight {
+    fn default() -> Self {
+        Self {
+            sky: Some(LightArray::default()),
+            block: LightArray::default(),
+        }
+    }
+}
+
+/// A light level from 0 to 15 for every block of a chunk, packed like in the packets
+pub struct LightArray {
+    data: Box<[u8]>,
+}
+
+impl Default for LightArray {
+    fn default() -> Self {
+        Self {
+            data: vec![0; CHUNK_VOLUME / 2].into_boxed_slice(),
+        }
+    }
+}
+
+impl LightArray {
+    /// The light at a block index, see `ChunkBlocks` for the ordering
+    pub fn get(&self, index: usize) -> u8 {
+        let byte = self.data[index / 2];
+        if index % 2 == 0 {
+            byte & 0xF
+        } else {
+            byte >> 4
+        }
+    }
+
+    pub fn set(&mut self, index: usize, level: u8) {
+        let byte = &mut self.data[index / 2];
+        if index % 2 == 0 {
+            *byte = (*byte & 0xF0) | (level & 0xF);
+        } else {
+            *byte = (*byte & 0x0F) | (level << 4);
+        }
+    }
+
+    /// The light of a section as it is sent to clients, the lowest section of the world has index 0
+    pub fn section(&self, section: usize) -> &[u8] {
+        let start = section * SECTION_LIGHT_BYTES;
+        &self.data[start..start + SECTION_LIGHT_BYTES]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packs_two_levels_per_byte() {
+        let mut light = LightArray::default();
+        light.set(0, 3);
+        light.set(1, 15);
+        light.set(2, 7);
+        assert_eq!(light.get(0), 3);
+        assert_eq!(light.get(1), 15);
+        assert_eq!(light.get(2), 7);
+        assert_eq!(light.section(0)[0], 0xF3);
+
+        light.set(1, 0);
+        assert_eq!(light.get(0), 3);
+        assert_eq!(light.get(1), 0);
+    }
+}
@@ -1,24 +1,38 @@
 use std::cmp::max;
 use std::collections::HashMap;
-use std::ops::Index;
 
 use fastnbt::LongArray;
-use pumpkin_core::math::vector2::Vector2;
+use pumpkin_core::math::{vector2::Vector2, vector3::Vector3};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::{
-    block::{block_state::BlockStateError, BlockId, BlockState},
-    coordinates::{ChunkRelativeBlockCoordinates, Height},
+    biome::Biome,
+    block::{block_state::BlockStateError, BlockEntity, BlockId, BlockState},
+    coordinates::ChunkRelativeBlockCoordinates,
     level::SaveFile,
-    WORLD_HEIGHT,
+    WORLD_HEIGHT, WORLD_LOWEST_Y,
 };
 
 pub mod anvil;
+pub mod light;
+pub mod palette;
+
+pub use light::ChunkLight;
+pub use palette::BlockSection;
 
 const CHUNK_AREA: usize = 16 * 16;
-const SUBCHUNK_VOLUME: usize = CHUNK_AREA * 16;
+pub(crate) const SUBCHUNK_VOLUME: usize = CHUNK_AREA * 16;
 const CHUNK_VOLUME: usize = CHUNK_AREA * WORLD_HEIGHT;
+const SECTIONS: usize = WORLD_HEIGHT / 16;
+/// Biomes are stored for cells of 4x4x4 blocks
+const BIOME_AREA: usize = 4 * 4;
+const SUBCHUNK_BIOME_VOLUME: usize = BIOME_AREA * 4;
+const CHUNK_BIOME_VOLUME: usize = BIOME_AREA * WORLD_HEIGHT / 4;
+/// The data version of 1.21.1, written into saved chunks
+const DATA_VERSION: usize = 3955;
+/// Heightmaps store one value per column, packed with this many bits
+const HEIGHTMAP_BITS: usize = 9;
 
 pub trait ChunkReader: Sync + Send {
     fn read_chunk(
@@ -28,6 +42,15 @@ pub trait ChunkReader: Sync + Send {
     ) -> Result<ChunkData, ChunkReadingError>;
 }
 
+pub trait ChunkWriter: Sync + Send {
+    fn write_chunk(
+        &self,
+        chunk: &ChunkData,
+        save_file: &SaveFile,
+        at: Vector2<i32>,
+    ) -> Result<(), ChunkWritingError>;
+}
+
 #[derive(Error, Debug)]
 pub enum ChunkReadingError {
     #[error("Io error: {0}")]
@@ -42,6 +65,16 @@ pub enum ChunkReadingError {
     ParsingError(ChunkParsingError),
 }
 
+#[derive(Error, Debug)]
+pub enum ChunkWritingError {
+    #[error("Io error: {0}")]
+    IoError(std::io::ErrorKind),
+    #[error("Compression error {0}")]
+    Compression(CompressionError),
+    #[error("Failed to serialize Chunk: {0}")]
+    SerializingError(ChunkSerializingError),
+}
+
 #[derive(Error, Debug)]
 pub enum CompressionError {
     #[error("Compression scheme not recognised")]
@@ -52,33 +85,45 @@ pub enum CompressionError {
     GZipError(std::io::Error),
     #[error("Error while working with LZ4 compression: {0}")]
     LZ4Error(std::io::Error),
+    #[error("Custom compression algorithms are not supported")]
+    UnsupportedCustomCompression,
 }
 
 pub struct ChunkData {
     pub blocks: ChunkBlocks,
+    pub biomes: ChunkBiomes,
     pub position: Vector2<i32>,
+    /// Keyed by their position in the world
+    pub block_entities: HashMap<Vector3<i32>, BlockEntity>,
+    /// Not saved, the light is calculated again when the chunk is loaded
+    pub light: ChunkLight,
 }
 
 pub struct ChunkBlocks {
-    // TODO make this a Vec that doesn't store the upper layers that only contain air
-
-    // The packet relies on this ordering -> leave it like this for performance
-    /// Ordering: yzx (y being the most significant)
-    blocks: Box<[BlockId; CHUNK_VOLUME]>,
+    /// The lowest section first, the blocks of a section are ordered yzx (y being the most
+    /// significant) like the block indices of the whole chunk
+    sections: Vec<BlockSection>,
 
     /// See `https://minecraft.fandom.com/wiki/Heightmap` for more info
     pub heightmap: ChunkHeightmaps,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+pub struct ChunkBiomes {
+    /// Ordering: yzx, like the blocks
+    biomes: Box<[Biome; CHUNK_BIOME_VOLUME]>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "PascalCase")]
 struct PaletteEntry {
     name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     properties: Option<HashMap<String, String>>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 struct ChunkSectionBlockStates {
+    #[serde(skip_serializing_if = "Option::is_none")]
     data: Option<LongArray>,
     palette: Vec<PaletteEntry>,
 }
@@ -90,23 +135,47 @@ pub struct ChunkHeightmaps {
     world_surface: LongArray,
 }
 
-#[derive(Deserialize, Debug)]
-#[expect(dead_code)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct ChunkSectionBiomes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<LongArray>,
+    palette: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
 struct ChunkSection {
     #[serde(rename = "Y")]
     y: i32,
     block_states: Option<ChunkSectionBlockStates>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    biomes: Option<ChunkSectionBiomes>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "PascalCase")]
 struct ChunkNbt {
-    #[expect(dead_code)]
     data_version: usize,
 
+    #[serde(rename = "xPos", default)]
+    x_pos: i32,
+    #[serde(rename = "zPos", default)]
+    z_pos: i32,
+    /// The lowest section of the chunk
+    #[serde(rename = "yPos", default)]
+    y_pos: i32,
+
+    /// Already checked with `ChunkStatus`, only written
+    #[serde(default)]
+    status: String,
+
     #[serde(rename = "sections")]
     sections: Vec<ChunkSection>,
 
+    #[serde(rename = "block_entities", default)]
+    block_entities: Vec<fastnbt::Value>,
+
+    /// Missing in chunks which were upgraded from old versions
+    #[serde(default)]
     heightmaps: ChunkHeightmaps,
 }
 
@@ -137,7 +206,7 @@ enum ChunkStatus {
     Spawn,
     #[serde(rename = "minecraft:heightmaps")]
     Heightmaps,
-    #[serde(rename = "minecraft:full")]
+    #[serde(rename = "minecraft:full", alias = "full")]
     Full,
 }
 
@@ -154,24 +223,21 @@ impl Default for ChunkHeightmaps {
 
 impl Default for ChunkBlocks {
     fn default() -> Self {
-        Self {
-            blocks: Box::new([BlockId::default(); CHUNK_VOLUME]),
-            heightmap: ChunkHeightmaps::default(),
-        }
+        Self::empty_with_heightmap(ChunkHeightmaps::default())
     }
 }
 
 impl ChunkBlocks {
     pub fn empty_with_heightmap(heightmap: ChunkHeightmaps) -> Self {
         Self {
-            blocks: Box::new([BlockId::default(); CHUNK_VOLUME]),
+            sections: vec![BlockSection::default(); SECTIONS],
             heightmap,
         }
     }
 
     /// Gets the given block in the chunk
     pub fn get_block(&self, position: ChunkRelativeBlockCoordinates) -> BlockId {
-        self.blocks[Self::convert_index(position)]
+        self.block_at(Self::convert_index(position))
     }
 
     /// Sets the given block in the chunk, returning the old block
@@ -194,55 +260,205 @@ impl ChunkBlocks {
         position: ChunkRelativeBlockCoordinates,
         block: BlockId,
     ) -> BlockId {
-        std::mem::replace(&mut self.blocks[Self::convert_index(position)], block)
+        let index = Self::convert_index(position);
+        self.sections[index / SUBCHUNK_VOLUME].set(index % SUBCHUNK_VOLUME, block)
     }
 
-    pub fn iter_subchunks(&self) -> impl Iterator<Item = &[BlockId; SUBCHUNK_VOLUME]> {
-        self.blocks
-            .chunks(SUBCHUNK_VOLUME)
-            .map(|subchunk| subchunk.try_into().unwrap())
+    /// The sections of the chunk, the lowest one first
+    pub fn sections(&self) -> &[BlockSection] {
+        &self.sections
     }
 
-    fn convert_index(index: ChunkRelativeBlockCoordinates) -> usize {
+    /// The block at an index into the yzx ordered blocks, the index of the lowest block is 0
+    pub(crate) fn block_at(&self, index: usize) -> BlockId {
+        self.sections[index / SUBCHUNK_VOLUME].get(index % SUBCHUNK_VOLUME)
+    }
+
+    pub(crate) fn convert_index(index: ChunkRelativeBlockCoordinates) -> usize {
         // % works for negative numbers as intended.
         index.y.get_absolute() as usize * CHUNK_AREA + *index.z as usize * 16 + *index.x as usize
     }
 
-    #[expect(dead_code)]
-    fn calculate_heightmap(&self) -> ChunkHeightmaps {
-        // figure out how LongArray is formatted
-        // figure out how to find out if block is motion blocking
-        todo!()
+    /// Calculates the heightmaps from the blocks.
+    ///
+    /// Every non-air block counts as motion blocking, which is close enough until we know
+    /// which blocks actually are.
+    pub(crate) fn calculate_heightmap(&self) -> ChunkHeightmaps {
+        let values_per_long = 64 / HEIGHTMAP_BITS;
+        let mut heightmap = vec![0i64; CHUNK_AREA.div_ceil(values_per_long)];
+        for column in 0..CHUNK_AREA {
+            // The height above the lowest y of the highest block, 0 if there is none
+            let height = self
+                .sections
+                .iter()
+                .enumerate()
+                .rev()
+                .filter(|(_, section)| section.non_air_blocks() > 0)
+                .find_map(|(index, section)| {
+                    (0..16)
+                        .rev()
+                        .find(|y| !section.get(y * CHUNK_AREA + column).is_air())
+                        .map(|y| index * 16 + y + 1)
+                })
+                .unwrap_or(0) as i64;
+            heightmap[column / values_per_long] |=
+                height << ((column % values_per_long) * HEIGHTMAP_BITS);
+        }
+        ChunkHeightmaps {
+            motion_blocking: LongArray::new(heightmap.clone()),
+            world_surface: LongArray::new(heightmap),
+        }
+    }
+}
+
+impl Default for ChunkBiomes {
+    fn default() -> Self {
+        Self {
+            biomes: Box::new([Biome::default(); CHUNK_BIOME_VOLUME]),
+        }
     }
 }
 
-impl Index<ChunkRelativeBlockCoordinates> for ChunkBlocks {
-    type Output = BlockId;
+impl ChunkBiomes {
+    /// Gets the biome of the given block
+    pub fn get_biome(&self, position: ChunkRelativeBlockCoordinates) -> Biome {
+        self.biomes[Self::convert_index(
+            *position.x as usize / 4,
+            position.y.get_absolute() as usize / 4,
+            *position.z as usize / 4,
+        )]
+    }
 
-    fn index(&self, index: ChunkRelativeBlockCoordinates) -> &Self::Output {
-        &self.blocks[Self::convert_index(index)]
+    /// Sets the biome of a whole column, `x` and `z` are the coordinates of the column's cell (`0..4`)
+    pub fn set_column(&mut self, x: usize, z: usize, biome: Biome) {
+        for y in 0..WORLD_HEIGHT / 4 {
+            self.biomes[Self::convert_index(x, y, z)] = biome;
+        }
+    }
+
+    pub fn iter_subchunks(&self) -> impl Iterator<Item = &[Biome; SUBCHUNK_BIOME_VOLUME]> {
+        self.biomes
+            .chunks(SUBCHUNK_BIOME_VOLUME)
+            .map(|subchunk| subchunk.try_into().unwrap())
     }
+
+    /// Reads the biomes of a section from a region file, biomes Pumpkin doesn't know become plains
+    fn read_section(
+        section: ChunkSectionBiomes,
+        biomes: &mut [Biome],
+    ) -> Result<(), ChunkParsingError> {
+        let palette: Vec<Biome> = section
+            .palette
+            .iter()
+            .map(|name| Biome::from_registry_id(name).unwrap_or_default())
+            .collect();
+        match section.data {
+            // A section with a single biome in its palette has no data
+            None => biomes.fill(*palette.first().ok_or(ChunkParsingError::InvalidPalette)?),
+            Some(data) => {
+                let indices = unpack_palette_indices(
+                    &data.into_inner(),
+                    palette_bits(palette.len(), 1),
+                    biomes.len(),
+                );
+                for (biome, index) in biomes.iter_mut().zip(indices) {
+                    *biome = *palette
+                        .get(index)
+                        .ok_or(ChunkParsingError::InvalidPalette)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `y` counts the cells from the bottom of the world
+    fn convert_index(x: usize, y: usize, z: usize) -> usize {
+        y * BIOME_AREA + z * 4 + x
+    }
+}
+
+/// How many bits a palette index takes in the packed data of a section, at least `min_bits`
+fn palette_bits(palette_len: usize, min_bits: u32) -> u32 {
+    max(
+        min_bits,
+        64 - (palette_len as u64).saturating_sub(1).leading_zeros(),
+    )
+}
+
+/// Unpacks the palette indices of a section, entries never span two longs
+fn unpack_palette_indices(data: &[i64], bits: u32, count: usize) -> Vec<usize> {
+    let per_long = (64 / bits) as usize;
+    let mask = (1 << bits) - 1;
+    data.iter()
+        .flat_map(|long| (0..per_long).map(move |i| ((long >> (i as u32 * bits)) & mask) as usize))
+        .take(count)
+        .collect()
+}
+
+/// Packs palette indices into longs, the opposite of `unpack_palette_indices`
+fn pack_palette_indices(indices: &[i64], bits: u32) -> LongArray {
+    let per_long = (64 / bits) as usize;
+    let packed = indices
+        .chunks(per_long)
+        .map(|indices| {
+            indices
+                .iter()
+                .enumerate()
+                .fold(0, |long, (i, index)| long | *index << (i as u32 * bits))
+        })
+        .collect();
+    LongArray::new(packed)
+}
+
+/// The palette of a section and the index into it of every entry
+fn build_palette<T: Copy + Eq + std::hash::Hash>(entries: &[T]) -> (Vec<T>, Vec<i64>) {
+    let mut palette = Vec::new();
+    let mut palette_indices = HashMap::new();
+    let indices = entries
+        .iter()
+        .map(|entry| {
+            *palette_indices.entry(*entry).or_insert_with(|| {
+                palette.push(*entry);
+                palette.len() as i64 - 1
+            })
+        })
+        .collect();
+    (palette, indices)
 }
 
 impl ChunkData {
     pub fn from_bytes(chunk_data: Vec<u8>, at: Vector2<i32>) -> Result<Self, ChunkParsingError> {
-        if fastnbt::from_bytes::<ChunkStatus>(&chunk_data).expect("Failed reading chunk status.")
-            != ChunkStatus::Full
-        {
+        let status = fastnbt::from_bytes::<ChunkStatus>(&chunk_data)
+            .map_err(|e| ChunkParsingError::ErrorDeserializingChunk(e.to_string()))?;
+        if status != ChunkStatus::Full {
             return Err(ChunkParsingError::ChunkNotGenerated);
         }
 
         let chunk_data = fastnbt::from_bytes::<ChunkNbt>(chunk_data.as_slice())
             .map_err(|e| ChunkParsingError::ErrorDeserializingChunk(e.to_string()))?;
 
-        // this needs to be boxed, otherwise it will cause a stack-overflow
         let mut blocks = ChunkBlocks::empty_with_heightmap(chunk_data.heightmaps);
-        let mut block_index = 0; // which block we're currently at
+        let mut biomes = ChunkBiomes::default();
 
         for section in chunk_data.sections.into_iter() {
+            // Sections are stored by their Y, the lowest one in the world is at index 0
+            let section_index = section.y - (WORLD_LOWEST_Y / 16) as i32;
+            if !(0..SECTIONS as i32).contains(&section_index) {
+                // Sections above and below the world only store light
+                continue;
+            }
+
+            if let Some(section_biomes) = section.biomes {
+                let start = section_index as usize * SUBCHUNK_BIOME_VOLUME;
+                ChunkBiomes::read_section(
+                    section_biomes,
+                    &mut biomes.biomes[start..start + SUBCHUNK_BIOME_VOLUME],
+                )?;
+            }
+
             let block_states = match section.block_states {
                 Some(states) => states,
-                None => continue, // TODO @lukas0008 this should instead fill all blocks with the only element of the palette
+                None => continue,
             };
 
             let palette = block_states
@@ -257,67 +473,126 @@ impl ChunkData {
                 .collect::<Result<Vec<_>, _>>()
                 .map_err(ChunkParsingError::BlockStateError)?;
 
-            let block_data = match block_states.data {
+            // A section with a single block in its palette has no data, it's filled with that block
+            let section = match block_states.data {
                 None => {
-                    // We skipped placing an empty subchunk.
-                    // We need to increase the y coordinate of the next subchunk being placed.
-                    block_index += SUBCHUNK_VOLUME;
-                    continue;
+                    BlockSection::filled(*palette.first().ok_or(ChunkParsingError::InvalidPalette)?)
                 }
-                Some(d) => d,
-            }
-            .into_inner();
-
-            // How many bits each block has in one of the pallete u64s
-            let block_bit_size = {
-                let size = 64 - (palette.len() as i64 - 1).leading_zeros();
-                max(4, size)
+                Some(data) => BlockSection::from_palette(palette, &data)
+                    .ok_or(ChunkParsingError::InvalidPalette)?,
             };
-            // How many blocks there are in one of the palletes u64s
-            let blocks_in_pallete = 64 / block_bit_size;
-
-            let mask = (1 << block_bit_size) - 1;
-            'block_loop: for block in block_data.iter() {
-                for i in 0..blocks_in_pallete {
-                    let index = (block >> (i * block_bit_size)) & mask;
-                    let block = palette[index as usize];
-
-                    // TODO allow indexing blocks directly so we can just use block_index and save some time?
-                    // this is fine because we initalized the heightmap of `blocks`
-                    // from the cached value in the world file
-                    blocks.set_block_no_heightmap_update(
-                        ChunkRelativeBlockCoordinates {
-                            z: ((block_index % CHUNK_AREA) / 16).into(),
-                            y: Height::from_absolute((block_index / CHUNK_AREA) as u16),
-                            x: (block_index % 16).into(),
-                        },
-                        block,
-                    );
-
-                    block_index += 1;
-
-                    // if `SUBCHUNK_VOLUME `is not divisible by `blocks_in_pallete` the block_data
-                    // can sometimes spill into other subchunks. We avoid that by aborting early
-                    if (block_index % SUBCHUNK_VOLUME) == 0 {
-                        break 'block_loop;
-                    }
-                }
-            }
+            // this is fine because we initalized the heightmap of `blocks`
+            // from the cached value in the world file
+            blocks.sections[section_index as usize] = section;
         }
 
+        let block_entities = chunk_data
+            .block_entities
+            .into_iter()
+            .filter_map(BlockEntity::from_nbt)
+            .map(|entity| (entity.position, entity))
+            .collect();
+
         Ok(ChunkData {
             blocks,
+            biomes,
             position: at,
+            block_entities,
+            light: ChunkLight::default(),
         })
     }
 }
 
+impl ChunkData {
+    /// Serializes the chunk into the NBT format vanilla uses in region files
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ChunkSerializingError> {
+        let mut sections = Vec::with_capacity(SECTIONS);
+        let subchunks = self
+            .blocks
+            .sections
+            .iter()
+            .zip(self.biomes.iter_subchunks());
+        for (index, (subchunk, subchunk_biomes)) in subchunks.enumerate() {
+            // The palettes of sections are saved like in packets, except for the direct palette
+            let (palette, data) = match subchunk.palette() {
+                Some(palette) => (
+                    palette.to_vec(),
+                    // A section with a single block in its palette needs no data
+                    (subchunk.bits() > 0).then(|| LongArray::new(subchunk.data().to_vec())),
+                ),
+                None => {
+                    let (palette, indices) = build_palette(&subchunk.to_vec());
+                    let data = pack_palette_indices(&indices, palette_bits(palette.len(), 4));
+                    (palette, Some(data))
+                }
+            };
+
+            let palette = palette
+                .iter()
+                .map(|block| {
+                    let (name, properties) = block
+                        .registry_state()
+                        .ok_or(ChunkSerializingError::UnknownBlockState(block.data))?;
+                    Ok(PaletteEntry {
+                        name: name.to_string(),
+                        properties: (!properties.is_empty()).then(|| properties.clone()),
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let (biome_palette, biome_indices) = build_palette(subchunk_biomes.as_slice());
+            let biomes = ChunkSectionBiomes {
+                data: (biome_palette.len() > 1).then(|| {
+                    pack_palette_indices(&biome_indices, palette_bits(biome_palette.len(), 1))
+                }),
+                palette: biome_palette
+                    .iter()
+                    .map(|biome| biome.registry_id().to_string())
+                    .collect(),
+            };
+
+            sections.push(ChunkSection {
+                y: index as i32 + (WORLD_LOWEST_Y / 16) as i32,
+                block_states: Some(ChunkSectionBlockStates { data, palette }),
+                biomes: Some(biomes),
+            });
+        }
+
+        let nbt = ChunkNbt {
+            data_version: DATA_VERSION,
+            x_pos: self.position.x,
+            z_pos: self.position.z,
+            y_pos: (WORLD_LOWEST_Y / 16) as i32,
+            status: "minecraft:full".to_string(),
+            sections,
+            block_entities: self
+                .block_entities
+                .values()
+                .map(BlockEntity::to_nbt)
+                .collect(),
+            heightmaps: self.blocks.calculate_heightmap(),
+        };
+        fastnbt::to_bytes(&nbt)
+            .map_err(|e| ChunkSerializingError::ErrorSerializingChunk(e.to_string()))
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ChunkSerializingError {
+    #[error("Block state {0} is not in the registry")]
+    UnknownBlockState(u16),
+    #[error("Error serializing chunk: {0}")]
+    ErrorSerializingChunk(String),
+}
+
 #[derive(Error, Debug)]
 pub enum ChunkParsingError {
     #[error("BlockState error: {0}")]
     BlockStateError(BlockStateError),
     #[error("The chunk isn't generated yet")]
     ChunkNotGenerated,
+    #[error("A block is not in the palette of its section")]
+    InvalidPalette,
     #[error("Error deserializing chunk: {0}")]
     ErrorDeserializingChunk(String),
 }
@@ -0,0 +1,286 @@
+use crate::{block::BlockId, DIRECT_PALETTE_BITS};
+
+use super::SUBCHUNK_VOLUME;
+
+/// Palettes with more entries than fit into this many bits are replaced by the direct palette
+const MAX_INDIRECT_BITS: u32 = 8;
+/// Indirect palettes use at least this many bits per block, like in vanilla
+const MIN_INDIRECT_BITS: u32 = 4;
+
+/// The blocks of a 16x16x16 section, stored like in the chunk packet.
+///
+/// Every block is an index into the palette of the section, packed into longs with as few bits
+/// as the palette needs. Entries never span two longs and the first one goes into the lowest bits.
+/// A section made of one block only stores that block, sections with too many different blocks
+/// store the block ids themselves (the direct palette). Ordering: yzx, like `ChunkBlocks`
+#[derive(Clone, Debug)]
+pub struct BlockSection {
+    /// Empty if the section uses the direct palette
+    palette: Vec<BlockId>,
+    /// 0 if the whole section is the only block in the palette
+    bits: u32,
+    data: Vec<i64>,
+    non_air_blocks: u16,
+}
+
+impl Default for BlockSection {
+    fn default() -> Self {
+        Self::filled(BlockId::default())
+    }
+}
+
+impl BlockSection {
+    /// A section made of one block
+    pub fn filled(block: BlockId) -> Self {
+        Self {
+            palette: vec![block],
+            bits: 0,
+            data: Vec::new(),
+            non_air_blocks: if block.is_air() {
+                0
+            } else {
+                SUBCHUNK_VOLUME as u16
+            },
+        }
+    }
+
+    /// A section with the smallest palette for its blocks, there have to be `SUBCHUNK_VOLUME`
+    pub fn from_blocks(blocks: &[BlockId]) -> Self {
+        debug_assert_eq!(blocks.len(), SUBCHUNK_VOLUME);
+        let mut palette = Vec::new();
+        for block in blocks {
+            if !palette.contains(block) {
+                palette.push(*block);
+            }
+        }
+        if palette.len() == 1 {
+            return Self::filled(palette[0]);
+        }
+        let non_air_blocks = blocks.iter().filter(|block| !block.is_air()).count() as u16;
+        let bits = palette_bits(palette.len());
+        if bits > MAX_INDIRECT_BITS {
+            return Self {
+                palette: Vec::new(),
+                bits: DIRECT_PALETTE_BITS,
+                data: pack(
+                    blocks.iter().map(|block| block.data.into()),
+                    DIRECT_PALETTE_BITS,
+                ),
+                non_air_blocks,
+            };
+        }
+        let data = pack(
+            blocks
+                .iter()
+                .map(|block| palette.iter().position(|entry| entry == block).unwrap() as u64),
+            bits,
+        );
+        Self {
+            palette,
+            bits,
+            data,
+            non_air_blocks,
+        }
+    }
+
+    /// A section from a palette and its packed indices as they are saved in region files.
+    /// Returns None if the data doesn't fit the palette
+    pub fn from_palette(palette: Vec<BlockId>, data: &[i64]) -> Option<Self> {
+        if palette.len() <= 1 {
+            return palette.first().copied().map(Self::filled);
+        }
+        let bits = palette_bits(palette.len());
+        let longs = SUBCHUNK_VOLUME.div_ceil((64 / bits) as usize);
+        let data = data.get(..longs)?;
+        let indices = unpack(data, bits);
+        if indices.iter().any(|index| *index as usize >= palette.len()) {
+            return None;
+        }
+        if bits > MAX_INDIRECT_BITS {
+            let blocks: Vec<_> = indices
+                .iter()
+                .map(|index| palette[*index as usize])
+                .collect();
+            return Some(Self::from_blocks(&blocks));
+        }
+        let non_air_blocks = indices
+            .iter()
+            .filter(|index| !palette[**index as usize].is_air())
+            .count() as u16;
+        Some(Self {
+            palette,
+            bits,
+            data: data.to_vec(),
+            non_air_blocks,
+        })
+    }
+
+    /// The block at an index into the section
+    pub fn get(&self, index: usize) -> BlockId {
+        if self.bits == 0 {
+            return self.palette[0];
+        }
+        let entry = self.entry(index);
+        if self.palette.is_empty() {
+            BlockId { data: entry as u16 }
+        } else {
+            self.palette[entry as usize]
+        }
+    }
+
+    /// Sets the block at an index into the section, returning the old block
+    pub fn set(&mut self, index: usize, block: BlockId) -> BlockId {
+        let old = self.get(index);
+        if old == block {
+            return old;
+        }
+        match self.palette_entry(block) {
+            Some(entry) => self.set_entry(index, entry),
+            // The palette is full, it gets rebuilt without the blocks which are not used anymore
+            None => {
+                let mut blocks = self.to_vec();
+                blocks[index] = block;
+                *self = Self::from_blocks(&blocks);
+                return old;
+            }
+        }
+        match (old.is_air(), block.is_air()) {
+            (true, false) => self.non_air_blocks += 1,
+            (false, true) => self.non_air_blocks -= 1,
+            _ => {}
+        }
+        old
+    }
+
+    /// All blocks of the section
+    pub fn to_vec(&self) -> Vec<BlockId> {
+        (0..SUBCHUNK_VOLUME).map(|index| self.get(index)).collect()
+    }
+
+    /// How many blocks are not air
+    pub fn non_air_blocks(&self) -> u16 {
+        self.non_air_blocks
+    }
+
+    /// How many bits every block takes in the data, 0 if the section is a single block
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// None if the data holds the block ids themselves
+    pub fn palette(&self) -> Option<&[BlockId]> {
+        (!self.palette.is_empty()).then_some(self.palette.as_slice())
+    }
+
+    /// The packed palette indices, empty for sections made of one block
+    pub fn data(&self) -> &[i64] {
+        &self.data
+    }
+
+    /// The value to store for a block, None if it doesn't fit into the palette
+    fn palette_entry(&mut self, block: BlockId) -> Option<u64> {
+        if self.palette.is_empty() {
+            return Some(block.data.into());
+        }
+        if let Some(entry) = self.palette.iter().position(|entry| *entry == block) {
+            return Some(entry as u64);
+        }
+        if self.bits == 0 || self.palette.len() >= 1 << self.bits {
+            return None;
+        }
+        self.palette.push(block);
+        Some(self.palette.len() as u64 - 1)
+    }
+
+    fn entry(&self, index: usize) -> u64 {
+        let per_long = (64 / self.bits) as usize;
+        let mask = (1 << self.bits) - 1;
+        (self.data[index / per_long] as u64 >> ((index % per_long) as u32 * self.bits)) & mask
+    }
+
+    fn set_entry(&mut self, index: usize, entry: u64) {
+        let per_long = (64 / self.bits) as usize;
+        let shift = (index % per_long) as u32 * self.bits;
+        let mask = ((1u64 << self.bits) - 1) << shift;
+        let long = &mut self.data[index / per_long];
+        *long = ((*long as u64 & !mask) | (entry << shift)) as i64;
+    }
+}
+
+/// How many bits an indirect palette with this many entries needs
+fn palette_bits(palette_len: usize) -> u32 {
+    (usize::BITS - (palette_len - 1).leading_zeros()).max(MIN_INDIRECT_BITS)
+}
+
+/// Packs values into longs, entries never span two longs
+fn pack(values: impl IntoIterator<Item = u64>, bits: u32) -> Vec<i64> {
+    let per_long = (64 / bits) as usize;
+    let mut data = vec![0i64; SUBCHUNK_VOLUME.div_ceil(per_long)];
+    for (index, value) in values.into_iter().enumerate() {
+        data[index / per_long] |= (value << ((index % per_long) as u32 * bits)) as i64;
+    }
+    data
+}
+
+fn unpack(data: &[i64], bits: u32) -> Vec<u64> {
+    let per_long = (64 / bits) as usize;
+    let mask = (1 << bits) - 1;
+    data.iter()
+        .flat_map(|long| (0..per_long).map(move |i| (*long as u64 >> (i as u32 * bits)) & mask))
+        .take(SUBCHUNK_VOLUME)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn block(data: u16) -> BlockId {
+        BlockId { data }
+    }
+
+    #[test]
+    fn grows_and_keeps_blocks() {
+        let mut section = BlockSection::default();
+        assert_eq!(section.bits(), 0);
+        for index in 0..SUBCHUNK_VOLUME {
+            section.set(index, block(index as u16 % 300 + 1));
+        }
+        assert_eq!(section.bits(), DIRECT_PALETTE_BITS);
+        assert!(section.palette().is_none());
+        assert_eq!(section.non_air_blocks(), SUBCHUNK_VOLUME as u16);
+        for index in 0..SUBCHUNK_VOLUME {
+            assert_eq!(section.get(index), block(index as u16 % 300 + 1));
+        }
+    }
+
+    #[test]
+    fn compacts_full_palette() {
+        let mut section = BlockSection::default();
+        // Air and 15 other blocks
+        for index in 0..15 {
+            section.set(index, block(index as u16 + 1));
+        }
+        assert_eq!(section.bits(), 4);
+        // Air is not used anymore, so the new block fits into the rebuilt palette
+        for index in 15..SUBCHUNK_VOLUME {
+            section.set(index, block(1));
+        }
+        section.set(0, block(100));
+        assert_eq!(section.bits(), 4);
+        assert_eq!(section.get(0), block(100));
+        assert_eq!(section.get(14), block(15));
+    }
+
+    #[test]
+    fn reads_saved_palette() {
+        let mut blocks = vec![block(0); SUBCHUNK_VOLUME];
+        blocks[5] = block(7);
+        let section = BlockSection::from_blocks(&blocks);
+        let read = BlockSection::from_palette(section.palette().unwrap().to_vec(), section.data())
+            .unwrap();
+        assert_eq!(read.to_vec(), blocks);
+        assert_eq!(read.non_air_blocks(), 1);
+        assert!(BlockSection::from_palette(vec![block(0), block(1)], &[]).is_none());
+    }
+}
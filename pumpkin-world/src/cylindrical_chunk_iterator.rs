@@ -1,6 +1,6 @@
 use pumpkin_core::math::vector2::Vector2;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub struct Cylindrical {
     pub center: Vector2<i32>,
     pub view_distance: i32,
@@ -14,14 +14,20 @@ impl Cylindrical {
         }
     }
 
-    #[allow(unused_variables)]
+    /// Calls `newly_included` for every chunk the new view contains but the old one didn't,
+    /// and `just_removed` for every chunk only the old view contains.
+    /// With `ignore` the old view is treated as empty
     pub fn for_each_changed_chunk(
         old_cylindrical: Cylindrical,
         new_cylindrical: Cylindrical,
         mut newly_included: impl FnMut(Vector2<i32>),
-        just_removed: impl FnMut(Vector2<i32>),
+        mut just_removed: impl FnMut(Vector2<i32>),
         ignore: bool,
     ) {
+        if ignore {
+            new_cylindrical.for_each_chunk(newly_included);
+            return;
+        }
         let min_x = old_cylindrical.left().min(new_cylindrical.left());
         let max_x = old_cylindrical.right().max(new_cylindrical.right());
         let min_z = old_cylindrical.bottom().min(new_cylindrical.bottom());
@@ -29,30 +35,36 @@ impl Cylindrical {
 
         for x in min_x..=max_x {
             for z in min_z..=max_z {
-                // TODO
-                // let old_is_within = if ignore {
-                //     false
-                // } else {
-                //     old_cylindrical.is_within_distance(x, z)
-                // };
-                // let new_is_within = if ignore {
-                //     true
-                // } else {
-                //     new_cylindrical.is_within_distance(x, z)
-                // };
+                let old_is_within = old_cylindrical.is_within_distance(x, z);
+                let new_is_within = new_cylindrical.is_within_distance(x, z);
 
-                // if old_is_within != new_is_within {
-                //     if new_is_within {
-                newly_included(Vector2::new(x, z));
-                //     } else {
-                //         dbg!("aa");
-                //         just_removed(Vector2::new(x, z));
-                //     }
-                // }
+                if old_is_within != new_is_within {
+                    if new_is_within {
+                        newly_included(Vector2::new(x, z));
+                    } else {
+                        just_removed(Vector2::new(x, z));
+                    }
+                }
             }
         }
     }
 
+    /// Calls `f` for every chunk within the view distance
+    pub fn for_each_chunk(&self, mut f: impl FnMut(Vector2<i32>)) {
+        for x in self.left()..=self.right() {
+            for z in self.bottom()..=self.top() {
+                if self.is_within_distance(x, z) {
+                    f(Vector2::new(x, z));
+                }
+            }
+        }
+    }
+
+    /// Whether the chunk is within the view distance
+    pub fn contains(&self, at: Vector2<i32>) -> bool {
+        self.is_within_distance(at.x, at.z)
+    }
+
     fn left(&self) -> i32 {
         self.center.x - self.view_distance - 1
     }
@@ -69,13 +81,10 @@ impl Cylindrical {
         self.center.z + self.view_distance + 1
     }
 
-    #[allow(dead_code)]
     fn is_within_distance(&self, x: i32, z: i32) -> bool {
-        let max_dist_squared = self.view_distance * self.view_distance;
-        let max_dist = self.view_distance as i64;
-        let dist_x = (x - self.center.x).abs().max(0) - (1);
-        let dist_z = (z - self.center.z).abs().max(0) - (1);
-        let dist_squared = dist_x.pow(2) + (max_dist.min(dist_z as i64) as i32).pow(2);
-        dist_squared < max_dist_squared
+        // Like vanilla, measured from the edge of the center chunk
+        let dist_x = ((x - self.center.x).abs() - 1).max(0);
+        let dist_z = ((z - self.center.z).abs() - 1).max(0);
+        dist_x * dist_x + dist_z * dist_z < self.view_distance * self.view_distance
     }
 }
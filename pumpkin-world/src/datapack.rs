@@ -0,0 +1,71 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use serde::de::DeserializeOwned;
+
+/// The datapacks of the main world may add to the built-in data or replace parts of it
+const DATAPACKS_FOLDER: &str = "datapacks";
+
+/// Reads the json files of every datapack of the main world in `root_folder`, by their id.
+/// `folders` are the folders inside of a namespace they are in, e.g. `recipe`, and `kind` names
+/// them in the warnings about broken files
+pub fn load_files<T: DeserializeOwned>(
+    root_folder: &Path,
+    folders: &[&str],
+    kind: &str,
+) -> HashMap<String, T> {
+    let mut files = HashMap::new();
+    let Ok(packs) = fs::read_dir(root_folder.join(DATAPACKS_FOLDER)) else {
+        return files;
+    };
+    for pack in packs.flatten() {
+        let Ok(namespaces) = fs::read_dir(pack.path().join("data")) else {
+            continue;
+        };
+        for namespace in namespaces.flatten() {
+            let name = namespace.file_name().to_string_lossy().into_owned();
+            for folder in folders {
+                load_folder(
+                    &namespace.path().join(folder),
+                    &format!("{name}:"),
+                    kind,
+                    &mut files,
+                );
+            }
+        }
+    }
+    files
+}
+
+/// Loads every json file below `folder`, the path without `.json` is appended to `prefix` for
+/// the id
+fn load_folder<T: DeserializeOwned>(
+    folder: &Path,
+    prefix: &str,
+    kind: &str,
+    files: &mut HashMap<String, T>,
+) {
+    let Ok(entries) = fs::read_dir(folder) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if path.is_dir() {
+            load_folder(&path, &format!("{prefix}{name}/"), kind, files);
+            continue;
+        }
+        let Some(name) = name.strip_suffix(".json") else {
+            continue;
+        };
+        let id = format!("{prefix}{name}");
+        let parsed = fs::read_to_string(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|json| serde_json::from_str(&json).map_err(|err| err.to_string()));
+        match parsed {
+            Ok(file) => {
+                files.insert(id, file);
+            }
+            Err(err) => log::warn!("Skipping {kind} {id}: {err}"),
+        }
+    }
+}
@@ -1,8 +1,11 @@
 use std::path::PathBuf;
 
-use crate::level::Level;
+use crate::{
+    level::Level,
+    world_gen::{GeneratorSettings, Seed},
+};
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum Dimension {
     OverWorld,
     Nether,
@@ -10,12 +13,58 @@ pub enum Dimension {
 }
 
 impl Dimension {
-    pub fn into_level(&self, mut base_directory: PathBuf) -> Level {
+    /// All dimensions, in the order of the dimension type registry
+    pub const ALL: [Dimension; 3] = [Dimension::OverWorld, Dimension::Nether, Dimension::End];
+
+    /// The name of the dimension type in the registry
+    pub const fn type_name(&self) -> &'static str {
+        match self {
+            Dimension::OverWorld => "minecraft:overworld",
+            Dimension::Nether => "minecraft:the_nether",
+            Dimension::End => "minecraft:the_end",
+        }
+    }
+
+    /// The id of the dimension type in the registry
+    pub const fn type_id(&self) -> i32 {
+        // The variants are declared in registry order
+        *self as i32
+    }
+
+    /// How many overworld blocks a block in this dimension is wide, used to link nether portals
+    pub const fn coordinate_scale(&self) -> f64 {
+        match self {
+            Dimension::Nether => 8.0,
+            Dimension::OverWorld | Dimension::End => 1.0,
+        }
+    }
+
+    /// Whether the sky lights the dimension
+    pub const fn has_skylight(&self) -> bool {
+        matches!(self, Dimension::OverWorld)
+    }
+
+    /// The folder the dimension is stored in, the Nether and the End are in the one of the overworld
+    pub fn level_folder(&self, mut base_directory: PathBuf) -> PathBuf {
         match self {
             Dimension::OverWorld => {}
             Dimension::Nether => base_directory.push("DIM-1"),
             Dimension::End => base_directory.push("DIM1"),
         }
-        Level::from_root_folder(base_directory)
+        base_directory
+    }
+
+    pub fn into_level(
+        &self,
+        base_directory: PathBuf,
+        seed: Seed,
+        generator: GeneratorSettings,
+    ) -> Level {
+        Level::from_root_folder(
+            self.level_folder(base_directory),
+            seed,
+            generator,
+            self.has_skylight(),
+        )
     }
 }
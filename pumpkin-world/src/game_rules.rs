@@ -0,0 +1,221 @@
+use std::{collections::HashMap, fmt};
+
+use thiserror::Error;
+
+pub const ANNOUNCE_ADVANCEMENTS: &str = "announceAdvancements";
+pub const DO_DAYLIGHT_CYCLE: &str = "doDaylightCycle";
+pub const DO_ENTITY_DROPS: &str = "doEntityDrops";
+pub const DO_FIRE_TICK: &str = "doFireTick";
+pub const DO_IMMEDIATE_RESPAWN: &str = "doImmediateRespawn";
+pub const DO_LIMITED_CRAFTING: &str = "doLimitedCrafting";
+pub const DO_MOB_SPAWNING: &str = "doMobSpawning";
+pub const DO_TILE_DROPS: &str = "doTileDrops";
+pub const DO_WEATHER_CYCLE: &str = "doWeatherCycle";
+pub const FALL_DAMAGE: &str = "fallDamage";
+pub const KEEP_INVENTORY: &str = "keepInventory";
+pub const MOB_GRIEFING: &str = "mobGriefing";
+pub const NATURAL_REGENERATION: &str = "naturalRegeneration";
+pub const PLAYERS_SLEEPING_PERCENTAGE: &str = "playersSleepingPercentage";
+pub const RANDOM_TICK_SPEED: &str = "randomTickSpeed";
+pub const REDUCED_DEBUG_INFO: &str = "reducedDebugInfo";
+pub const SHOW_DEATH_MESSAGES: &str = "showDeathMessages";
+
+/// The game rules of vanilla with their defaults. `spawnChunkRadius` is missing,
+/// the spawn chunks are configured per world instead
+const DEFAULTS: [(&str, GameRuleValue); 50] = [
+    (ANNOUNCE_ADVANCEMENTS, GameRuleValue::Bool(true)),
+    ("blockExplosionDropDecay", GameRuleValue::Bool(true)),
+    ("commandBlockOutput", GameRuleValue::Bool(true)),
+    ("commandModificationBlockLimit", GameRuleValue::Int(32768)),
+    ("disableElytraMovementCheck", GameRuleValue::Bool(false)),
+    ("disableRaids", GameRuleValue::Bool(false)),
+    (DO_DAYLIGHT_CYCLE, GameRuleValue::Bool(true)),
+    (DO_ENTITY_DROPS, GameRuleValue::Bool(true)),
+    (DO_FIRE_TICK, GameRuleValue::Bool(true)),
+    (DO_IMMEDIATE_RESPAWN, GameRuleValue::Bool(false)),
+    ("doInsomnia", GameRuleValue::Bool(true)),
+    (DO_LIMITED_CRAFTING, GameRuleValue::Bool(false)),
+    ("doMobLoot", GameRuleValue::Bool(true)),
+    (DO_MOB_SPAWNING, GameRuleValue::Bool(true)),
+    ("doPatrolSpawning", GameRuleValue::Bool(true)),
+    ("doTileDrops", GameRuleValue::Bool(true)),
+    ("doTraderSpawning", GameRuleValue::Bool(true)),
+    ("doVinesSpread", GameRuleValue::Bool(true)),
+    ("doWardenSpawning", GameRuleValue::Bool(true)),
+    (DO_WEATHER_CYCLE, GameRuleValue::Bool(true)),
+    ("drowningDamage", GameRuleValue::Bool(true)),
+    ("enderPearlsVanishOnDeath", GameRuleValue::Bool(true)),
+    (FALL_DAMAGE, GameRuleValue::Bool(true)),
+    ("fireDamage", GameRuleValue::Bool(true)),
+    ("forgiveDeadPlayers", GameRuleValue::Bool(true)),
+    ("freezeDamage", GameRuleValue::Bool(true)),
+    ("globalSoundEvents", GameRuleValue::Bool(true)),
+    (KEEP_INVENTORY, GameRuleValue::Bool(false)),
+    ("lavaSourceConversion", GameRuleValue::Bool(false)),
+    ("logAdminCommands", GameRuleValue::Bool(true)),
+    ("maxCommandChainLength", GameRuleValue::Int(65536)),
+    ("maxCommandForkCount", GameRuleValue::Int(65536)),
+    ("maxEntityCramming", GameRuleValue::Int(24)),
+    ("mobExplosionDropDecay", GameRuleValue::Bool(true)),
+    (MOB_GRIEFING, GameRuleValue::Bool(true)),
+    (NATURAL_REGENERATION, GameRuleValue::Bool(true)),
+    ("playersNetherPortalCreativeDelay", GameRuleValue::Int(1)),
+    ("playersNetherPortalDefaultDelay", GameRuleValue::Int(80)),
+    (PLAYERS_SLEEPING_PERCENTAGE, GameRuleValue::Int(100)),
+    ("projectilesCanBreakBlocks", GameRuleValue::Bool(true)),
+    (RANDOM_TICK_SPEED, GameRuleValue::Int(3)),
+    (REDUCED_DEBUG_INFO, GameRuleValue::Bool(false)),
+    ("sendCommandFeedback", GameRuleValue::Bool(true)),
+    (SHOW_DEATH_MESSAGES, GameRuleValue::Bool(true)),
+    ("snowAccumulationHeight", GameRuleValue::Int(1)),
+    ("spawnRadius", GameRuleValue::Int(10)),
+    ("spectatorsGenerateChunks", GameRuleValue::Bool(true)),
+    ("tntExplosionDropDecay", GameRuleValue::Bool(false)),
+    ("universalAnger", GameRuleValue::Bool(false)),
+    ("waterSourceConversion", GameRuleValue::Bool(true)),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameRuleValue {
+    Bool(bool),
+    Int(i32),
+}
+
+impl GameRuleValue {
+    /// Parses `value` as a value of the same type as this one
+    fn parse_like(self, value: &str) -> Option<Self> {
+        match self {
+            Self::Bool(_) => value.parse().ok().map(Self::Bool),
+            Self::Int(_) => value.parse().ok().map(Self::Int),
+        }
+    }
+}
+
+impl fmt::Display for GameRuleValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(value) => value.fmt(f),
+            Self::Int(value) => value.fmt(f),
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum GameRuleError {
+    #[error("Unknown game rule {0}")]
+    UnknownRule(String),
+    #[error("Invalid value {value} for game rule {rule}")]
+    InvalidValue { rule: String, value: String },
+}
+
+/// The game rules of a world. They are kept as the strings stored in the `level.dat`,
+/// so rules this server doesn't know survive a save
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GameRules {
+    values: HashMap<String, String>,
+}
+
+impl GameRules {
+    pub fn from_values(values: HashMap<String, String>) -> Self {
+        Self { values }
+    }
+
+    /// All rules as they are stored, the known ones which were never set with their default
+    pub fn values(&self) -> HashMap<String, String> {
+        let mut values: HashMap<_, _> = DEFAULTS
+            .iter()
+            .map(|(name, default)| (name.to_string(), default.to_string()))
+            .collect();
+        values.extend(self.values.clone());
+        values
+    }
+
+    /// The names of all known rules, sorted
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        let mut names: Vec<_> = DEFAULTS.iter().map(|(name, _)| *name).collect();
+        names.sort_unstable();
+        names.into_iter()
+    }
+
+    fn default_value(name: &str) -> Option<GameRuleValue> {
+        DEFAULTS
+            .iter()
+            .find(|(rule, _)| *rule == name)
+            .map(|(_, default)| *default)
+    }
+
+    /// The value of a rule, None if the rule is unknown. Invalid stored values count as the default
+    pub fn get(&self, name: &str) -> Option<GameRuleValue> {
+        let default = Self::default_value(name)?;
+        Some(
+            self.values
+                .get(name)
+                .and_then(|value| default.parse_like(value))
+                .unwrap_or(default),
+        )
+    }
+
+    /// The value of a boolean rule, false for unknown rules
+    pub fn get_bool(&self, name: &str) -> bool {
+        matches!(self.get(name), Some(GameRuleValue::Bool(true)))
+    }
+
+    /// The value of an integer rule, 0 for unknown rules
+    pub fn get_int(&self, name: &str) -> i32 {
+        match self.get(name) {
+            Some(GameRuleValue::Int(value)) => value,
+            _ => 0,
+        }
+    }
+
+    /// Changes a rule, `value` has to fit the type of the rule
+    pub fn set(&mut self, name: &str, value: &str) -> Result<GameRuleValue, GameRuleError> {
+        let default = Self::default_value(name)
+            .ok_or_else(|| GameRuleError::UnknownRule(name.to_string()))?;
+        let value = default
+            .parse_like(value)
+            .ok_or_else(|| GameRuleError::InvalidValue {
+                rule: name.to_string(),
+                value: value.to_string(),
+            })?;
+        self.values.insert(name.to_string(), value.to_string());
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_and_changes() {
+        let mut rules = GameRules::from_values(HashMap::from([
+            (KEEP_INVENTORY.to_string(), "true".to_string()),
+            (RANDOM_TICK_SPEED.to_string(), "fast".to_string()),
+            ("someModRule".to_string(), "1".to_string()),
+        ]));
+        assert!(rules.get_bool(KEEP_INVENTORY));
+        assert!(rules.get_bool(DO_DAYLIGHT_CYCLE));
+        // Broken values count as the default
+        assert_eq!(rules.get_int(RANDOM_TICK_SPEED), 3);
+        assert_eq!(rules.get("someModRule"), None);
+
+        assert_eq!(
+            rules.set(RANDOM_TICK_SPEED, "10"),
+            Ok(GameRuleValue::Int(10))
+        );
+        assert_eq!(rules.get_int(RANDOM_TICK_SPEED), 10);
+        assert!(matches!(
+            rules.set(MOB_GRIEFING, "10"),
+            Err(GameRuleError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            rules.set("someModRule", "2"),
+            Err(GameRuleError::UnknownRule(_))
+        ));
+
+        let values = rules.values();
+        assert_eq!(values["someModRule"], "1");
+        assert_eq!(values[MOB_GRIEFING], "true");
+    }
+}
@@ -24,6 +24,16 @@ pub fn get_protocol_id(category: &str, entry: &str) -> u32 {
         .expect("No Entry found")
 }
 
+/// Like `get_protocol_id`, but None for unknown categories and entries
+pub fn find_protocol_id(category: &str, entry: &str) -> Option<u32> {
+    REGISTRY
+        .get(category)?
+        .entries
+        .get(entry)?
+        .get("protocol_id")
+        .copied()
+}
+
 pub fn get_default<'a>(category: &str) -> Option<&'a str> {
     REGISTRY
         .get(category)
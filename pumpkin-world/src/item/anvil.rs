@@ -0,0 +1,287 @@
+use super::{ItemName, ItemStack};
+use crate::global_registry::{self, ITEM_REGISTRY};
+
+/// Anvils refuse to work on items for this many levels or more, unless the player is in creative
+/// mode. Only renaming stays possible, it costs one level less
+pub const TOO_EXPENSIVE: i32 = 40;
+
+/// What an anvil makes of the items put into it
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AnvilResult {
+    /// None if the anvil can't do anything with the items
+    pub item: Option<ItemStack>,
+    /// The levels taking the result costs, the window shows it even without a result
+    pub cost: i32,
+    /// How many of the second item repairing uses up, all of them if 0
+    pub material_count: u8,
+}
+
+/// Repairs, combines and renames items like the anvil of vanilla. `left` is the item which is
+/// worked on, `right` the material, the item whose enchantments it gets or an enchanted book.
+/// `name` is what the player typed into the name field, None if they didn't
+pub fn anvil_result(
+    left: &ItemStack,
+    right: Option<&ItemStack>,
+    name: Option<&str>,
+    creative: bool,
+) -> AnvilResult {
+    let mut result = *left;
+    let base_cost =
+        i64::from(left.repair_cost) + right.map_or(0, |right| i64::from(right.repair_cost));
+    let mut cost = 0;
+    let mut material_count = 0;
+
+    if let Some(right) = right {
+        let stored = right.stores_enchantments();
+        let max_damage = left.max_damage();
+        if let Some(max_damage) = max_damage.filter(|_| is_repair_material(left, right)) {
+            // Every material repairs a quarter of the durability
+            let mut repair = result.damage.min(max_damage / 4);
+            if repair == 0 {
+                return AnvilResult::default();
+            }
+            while repair > 0 && material_count < right.item_count {
+                result.damage -= repair;
+                cost += 1;
+                material_count += 1;
+                repair = result.damage.min(max_damage / 4);
+            }
+        } else {
+            if !stored && (left.item_id != right.item_id || max_damage.is_none()) {
+                return AnvilResult::default();
+            }
+            // Two of the same item add up their durability, with a bonus of 12%
+            if let (Some(max_damage), false) = (max_damage, stored) {
+                let durability = max_damage.saturating_sub(left.damage)
+                    + right.max_damage().unwrap_or(0).saturating_sub(right.damage)
+                    + max_damage * 12 / 100;
+                let damage = max_damage.saturating_sub(durability);
+                if damage < result.damage {
+                    result.damage = damage;
+                    cost += 2;
+                }
+            }
+            let mut any_compatible = false;
+            let mut any_incompatible = false;
+            for (enchantment, level) in right.enchantments.iter() {
+                let current = result.enchantments.level(enchantment);
+                let level = if current == level {
+                    level.saturating_add(1)
+                } else {
+                    level.max(current)
+                };
+                let mut applies =
+                    creative || left.stores_enchantments() || enchantment.supports(left);
+                for (other, _) in result.enchantments.iter() {
+                    if other != enchantment && !enchantment.is_compatible(other) {
+                        applies = false;
+                        cost += 1;
+                    }
+                }
+                if !applies {
+                    any_incompatible = true;
+                    continue;
+                }
+                any_compatible = true;
+                let level = level.min(enchantment.max_level());
+                result.enchantments.set(enchantment, level);
+                // Books are cheaper than items
+                let anvil_cost = if stored {
+                    (enchantment.anvil_cost() / 2).max(1)
+                } else {
+                    enchantment.anvil_cost()
+                };
+                cost += anvil_cost * i32::from(level);
+                if left.item_count > 1 {
+                    cost = TOO_EXPENSIVE;
+                }
+            }
+            if any_incompatible && !any_compatible {
+                return AnvilResult::default();
+            }
+        }
+    }
+
+    let mut rename_cost = 0;
+    if let Some(name) = name {
+        match ItemName::new(name) {
+            Some(name) if name.as_str() != display_name(left) => {
+                rename_cost = 1;
+                result.custom_name = Some(name);
+            }
+            Some(_) => {}
+            // A blank name takes the name away
+            None if left.custom_name.is_some() => {
+                rename_cost = 1;
+                result.custom_name = None;
+            }
+            None => {}
+        }
+    }
+    cost += rename_cost;
+
+    let mut total = (base_cost + i64::from(cost)).clamp(0, i64::from(i32::MAX)) as i32;
+    if rename_cost == cost && rename_cost > 0 && total >= TOO_EXPENSIVE {
+        total = TOO_EXPENSIVE - 1;
+    }
+    if cost <= 0 || (total >= TOO_EXPENSIVE && !creative) {
+        return AnvilResult {
+            item: None,
+            cost: total,
+            material_count: 0,
+        };
+    }
+    // Every time an anvil works on an item it gets more expensive, renaming is free of that
+    let mut repair_cost = right.map_or(result.repair_cost, |right| {
+        result.repair_cost.max(right.repair_cost)
+    });
+    if rename_cost != cost {
+        repair_cost = repair_cost.saturating_mul(2).saturating_add(1);
+    }
+    result.repair_cost = repair_cost;
+    AnvilResult {
+        item: Some(result),
+        cost: total,
+        material_count,
+    }
+}
+
+/// Whether `material` repairs `item`, e.g. diamonds repair diamond tools
+fn is_repair_material(item: &ItemStack, material: &ItemStack) -> bool {
+    let name = |item: &ItemStack| {
+        global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id)
+            .and_then(|id| id.strip_prefix("minecraft:"))
+    };
+    let (Some(item), Some(material)) = (name(item), name(material)) else {
+        return false;
+    };
+    match item {
+        "elytra" => material == "phantom_membrane",
+        "turtle_helmet" => material == "turtle_scute",
+        "wolf_armor" => material == "armadillo_scute",
+        "mace" => material == "breeze_rod",
+        "shield" => material.ends_with("_planks"),
+        _ => match item.split('_').next() {
+            Some("wooden") => material.ends_with("_planks"),
+            Some("stone") => matches!(material, "cobblestone" | "blackstone" | "cobbled_deepslate"),
+            Some("iron" | "chainmail") => material == "iron_ingot",
+            Some("golden") => material == "gold_ingot",
+            Some("diamond") => material == "diamond",
+            Some("netherite") => material == "netherite_ingot",
+            Some("leather") => material == "leather",
+            _ => false,
+        },
+    }
+}
+
+/// The name the item shows, the client puts it into the name field of the anvil. Pumpkin has no
+/// translations, so the names of items without a custom name are made from their id, e.g.
+/// Diamond Sword
+fn display_name(item: &ItemStack) -> String {
+    if let Some(name) = &item.custom_name {
+        return name.as_str().to_string();
+    }
+    let Some(id) = global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id) else {
+        return String::new();
+    };
+    id.strip_prefix("minecraft:")
+        .unwrap_or(id)
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect()
+            })
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{item::Enchantment, test_utils::item};
+
+    fn book(enchantment: Enchantment, level: u8) -> ItemStack {
+        let mut book = item("minecraft:enchanted_book");
+        book.enchantments.set(enchantment, level);
+        book
+    }
+
+    #[test]
+    fn rename() {
+        let sword = item("minecraft:diamond_sword");
+        assert_eq!(
+            anvil_result(&sword, None, Some("Diamond Sword"), false).item,
+            None
+        );
+        let result = anvil_result(&sword, None, Some("Excalibur"), false);
+        assert_eq!(result.cost, 1);
+        let renamed = result.item.unwrap();
+        assert_eq!(renamed.custom_name.unwrap().as_str(), "Excalibur");
+        // Renaming doesn't make the next use more expensive
+        assert_eq!(renamed.repair_cost, 0);
+
+        let result = anvil_result(&renamed, None, Some(" "), false);
+        assert_eq!(result.item.unwrap().custom_name, None);
+    }
+
+    #[test]
+    fn repair() {
+        let mut sword = item("minecraft:diamond_sword");
+        sword.damage = 1000;
+        let diamonds = ItemStack::new(2, item("minecraft:diamond").item_id);
+        let result = anvil_result(&sword, Some(&diamonds), None, false);
+        assert_eq!(result.cost, 2);
+        assert_eq!(result.material_count, 2);
+        let repaired = result.item.unwrap();
+        assert_eq!(repaired.damage, 1000 - 2 * (1561 / 4));
+        assert_eq!(repaired.repair_cost, 1);
+
+        let sticks = item("minecraft:stick");
+        assert_eq!(anvil_result(&sword, Some(&sticks), None, false).item, None);
+    }
+
+    #[test]
+    fn combine() {
+        let mut sword = item("minecraft:diamond_sword");
+        let result = anvil_result(&sword, Some(&book(Enchantment::Sharpness, 4)), None, false);
+        assert_eq!(result.cost, 4);
+        sword = result.item.unwrap();
+        assert_eq!(sword.enchantments.level(Enchantment::Sharpness), 4);
+
+        // The same level gets one higher, up to the highest level
+        let result = anvil_result(&sword, Some(&book(Enchantment::Sharpness, 4)), None, false);
+        assert_eq!(
+            result
+                .item
+                .unwrap()
+                .enchantments
+                .level(Enchantment::Sharpness),
+            5
+        );
+        // Smite can't be combined with sharpness
+        let result = anvil_result(&sword, Some(&book(Enchantment::Smite, 1)), None, false);
+        assert_eq!(result.item, None);
+        // Nor can a sword get efficiency
+        let result = anvil_result(&sword, Some(&book(Enchantment::Efficiency, 1)), None, false);
+        assert_eq!(result.item, None);
+    }
+
+    #[test]
+    fn too_expensive() {
+        let mut sword = item("minecraft:diamond_sword");
+        sword.repair_cost = 39;
+        let sharpness = book(Enchantment::Sharpness, 1);
+        let result = anvil_result(&sword, Some(&sharpness), None, false);
+        assert_eq!(result.item, None);
+        assert_eq!(result.cost, 40);
+        assert!(anvil_result(&sword, Some(&sharpness), None, true)
+            .item
+            .is_some());
+        // Renaming is still possible
+        let result = anvil_result(&sword, None, Some("Old Sword"), false);
+        assert_eq!(result.cost, TOO_EXPENSIVE - 1);
+        assert!(result.item.is_some());
+    }
+}
@@ -0,0 +1,31 @@
+use serde::Deserialize;
+
+pub const ATTACK_DAMAGE: &str = "minecraft:generic.attack_damage";
+pub const ATTACK_SPEED: &str = "minecraft:generic.attack_speed";
+
+/// The `minecraft:attribute_modifiers` component of an item, how it changes the attributes of
+/// the entity holding or wearing it
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct AttributeModifiers {
+    pub modifiers: Vec<AttributeModifier>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct AttributeModifier {
+    /// The attribute which is changed, e.g. `ATTACK_DAMAGE`
+    #[serde(rename = "type")]
+    pub attribute: String,
+    pub amount: f64,
+    pub id: String,
+    pub operation: AttributeOperation,
+    /// Where the item has to be for the modifier to apply, e.g. `mainhand`
+    pub slot: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeOperation {
+    AddValue,
+    AddMultipliedBase,
+    AddMultipliedTotal,
+}
@@ -0,0 +1,776 @@
+use std::collections::HashMap;
+
+use fastnbt::Value;
+use pumpkin_core::random::{legacy_rand::LegacyRand, RandomImpl};
+
+use super::ItemStack;
+use crate::global_registry::{self, ITEM_REGISTRY};
+
+/// Enchanting tables count up to this many bookshelves
+pub const MAX_BOOKSHELVES: i32 = 15;
+
+/// The vanilla enchantments in the order of their registry, their registry id is their index
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Enchantment {
+    Protection,
+    FireProtection,
+    FeatherFalling,
+    BlastProtection,
+    ProjectileProtection,
+    Respiration,
+    AquaAffinity,
+    Thorns,
+    DepthStrider,
+    FrostWalker,
+    BindingCurse,
+    SoulSpeed,
+    SwiftSneak,
+    Sharpness,
+    Smite,
+    BaneOfArthropods,
+    Knockback,
+    FireAspect,
+    Looting,
+    SweepingEdge,
+    Efficiency,
+    SilkTouch,
+    Unbreaking,
+    Fortune,
+    Power,
+    Punch,
+    Flame,
+    Infinity,
+    LuckOfTheSea,
+    Lure,
+    Loyalty,
+    Impaling,
+    Riptide,
+    Channeling,
+    Multishot,
+    QuickCharge,
+    Piercing,
+    Density,
+    Breach,
+    WindBurst,
+    Mending,
+    VanishingCurse,
+}
+
+/// Which items an enchantment can be put on, like the item tags of vanilla
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ItemSet {
+    Armor,
+    HeadArmor,
+    ChestArmor,
+    LegArmor,
+    FootArmor,
+    /// Everything which can be worn, e.g. elytras and carved pumpkins too
+    Equippable,
+    Sword,
+    /// Swords and axes
+    SharpWeapon,
+    /// Swords, axes and maces
+    Weapon,
+    /// Swords and maces
+    FireAspect,
+    /// Tools and shears
+    Mining,
+    /// Tools which break blocks
+    MiningLoot,
+    /// Everything which breaks after being used
+    Durability,
+    Bow,
+    Crossbow,
+    Trident,
+    FishingRod,
+    Mace,
+    /// Everything which can lose its items on death, like the curse of vanishing
+    Vanishable,
+}
+
+/// The levels of an enchantment cost `base` at level 1 and `per_level` more for every level above
+#[derive(Clone, Copy, Debug)]
+struct Cost {
+    base: i32,
+    per_level: i32,
+}
+
+impl Cost {
+    const fn at(self, level: u8) -> i32 {
+        self.base + self.per_level * (level as i32 - 1)
+    }
+}
+
+/// How an enchantment behaves, like its definition in vanilla
+struct Definition {
+    weight: i32,
+    max_level: u8,
+    min_cost: Cost,
+    max_cost: Cost,
+    supported: ItemSet,
+    /// The items an enchanting table puts it on, the supported ones if None
+    primary: Option<ItemSet>,
+    /// Treasure enchantments can't be found at the enchanting table
+    treasure: bool,
+}
+
+const fn cost(base: i32, per_level: i32) -> Cost {
+    Cost { base, per_level }
+}
+
+const fn definition(
+    weight: i32,
+    max_level: u8,
+    min_cost: Cost,
+    max_cost: Cost,
+    supported: ItemSet,
+) -> Definition {
+    Definition {
+        weight,
+        max_level,
+        min_cost,
+        max_cost,
+        supported,
+        primary: None,
+        treasure: false,
+    }
+}
+
+impl Definition {
+    const fn primary(self, primary: ItemSet) -> Self {
+        Self {
+            primary: Some(primary),
+            ..self
+        }
+    }
+
+    const fn treasure(self) -> Self {
+        Self {
+            treasure: true,
+            ..self
+        }
+    }
+}
+
+/// Enchantments of the same group can't be on one item
+const EXCLUSIVE: [&[Enchantment]; 7] = [
+    &[
+        Enchantment::Protection,
+        Enchantment::FireProtection,
+        Enchantment::BlastProtection,
+        Enchantment::ProjectileProtection,
+    ],
+    &[Enchantment::DepthStrider, Enchantment::FrostWalker],
+    &[
+        Enchantment::Sharpness,
+        Enchantment::Smite,
+        Enchantment::BaneOfArthropods,
+        Enchantment::Impaling,
+        Enchantment::Density,
+        Enchantment::Breach,
+    ],
+    &[Enchantment::Fortune, Enchantment::SilkTouch],
+    &[Enchantment::Infinity, Enchantment::Mending],
+    &[Enchantment::Multishot, Enchantment::Piercing],
+    &[
+        Enchantment::Riptide,
+        Enchantment::Loyalty,
+        Enchantment::Channeling,
+    ],
+];
+
+impl Enchantment {
+    pub const ALL: [Self; 42] = [
+        Self::Protection,
+        Self::FireProtection,
+        Self::FeatherFalling,
+        Self::BlastProtection,
+        Self::ProjectileProtection,
+        Self::Respiration,
+        Self::AquaAffinity,
+        Self::Thorns,
+        Self::DepthStrider,
+        Self::FrostWalker,
+        Self::BindingCurse,
+        Self::SoulSpeed,
+        Self::SwiftSneak,
+        Self::Sharpness,
+        Self::Smite,
+        Self::BaneOfArthropods,
+        Self::Knockback,
+        Self::FireAspect,
+        Self::Looting,
+        Self::SweepingEdge,
+        Self::Efficiency,
+        Self::SilkTouch,
+        Self::Unbreaking,
+        Self::Fortune,
+        Self::Power,
+        Self::Punch,
+        Self::Flame,
+        Self::Infinity,
+        Self::LuckOfTheSea,
+        Self::Lure,
+        Self::Loyalty,
+        Self::Impaling,
+        Self::Riptide,
+        Self::Channeling,
+        Self::Multishot,
+        Self::QuickCharge,
+        Self::Piercing,
+        Self::Density,
+        Self::Breach,
+        Self::WindBurst,
+        Self::Mending,
+        Self::VanishingCurse,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Protection => "minecraft:protection",
+            Self::FireProtection => "minecraft:fire_protection",
+            Self::FeatherFalling => "minecraft:feather_falling",
+            Self::BlastProtection => "minecraft:blast_protection",
+            Self::ProjectileProtection => "minecraft:projectile_protection",
+            Self::Respiration => "minecraft:respiration",
+            Self::AquaAffinity => "minecraft:aqua_affinity",
+            Self::Thorns => "minecraft:thorns",
+            Self::DepthStrider => "minecraft:depth_strider",
+            Self::FrostWalker => "minecraft:frost_walker",
+            Self::BindingCurse => "minecraft:binding_curse",
+            Self::SoulSpeed => "minecraft:soul_speed",
+            Self::SwiftSneak => "minecraft:swift_sneak",
+            Self::Sharpness => "minecraft:sharpness",
+            Self::Smite => "minecraft:smite",
+            Self::BaneOfArthropods => "minecraft:bane_of_arthropods",
+            Self::Knockback => "minecraft:knockback",
+            Self::FireAspect => "minecraft:fire_aspect",
+            Self::Looting => "minecraft:looting",
+            Self::SweepingEdge => "minecraft:sweeping_edge",
+            Self::Efficiency => "minecraft:efficiency",
+            Self::SilkTouch => "minecraft:silk_touch",
+            Self::Unbreaking => "minecraft:unbreaking",
+            Self::Fortune => "minecraft:fortune",
+            Self::Power => "minecraft:power",
+            Self::Punch => "minecraft:punch",
+            Self::Flame => "minecraft:flame",
+            Self::Infinity => "minecraft:infinity",
+            Self::LuckOfTheSea => "minecraft:luck_of_the_sea",
+            Self::Lure => "minecraft:lure",
+            Self::Loyalty => "minecraft:loyalty",
+            Self::Impaling => "minecraft:impaling",
+            Self::Riptide => "minecraft:riptide",
+            Self::Channeling => "minecraft:channeling",
+            Self::Multishot => "minecraft:multishot",
+            Self::QuickCharge => "minecraft:quick_charge",
+            Self::Piercing => "minecraft:piercing",
+            Self::Density => "minecraft:density",
+            Self::Breach => "minecraft:breach",
+            Self::WindBurst => "minecraft:wind_burst",
+            Self::Mending => "minecraft:mending",
+            Self::VanishingCurse => "minecraft:vanishing_curse",
+        }
+    }
+
+    /// The enchantment with the id, the namespace may be left out like in commands
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        Self::ALL
+            .into_iter()
+            .find(|enchantment| &enchantment.name()["minecraft:".len()..] == name)
+    }
+
+    pub const fn registry_id(self) -> i32 {
+        self as i32
+    }
+
+    pub fn from_registry_id(id: i32) -> Option<Self> {
+        Self::ALL.get(usize::try_from(id).ok()?).copied()
+    }
+
+    const fn definition(self) -> Definition {
+        use ItemSet::*;
+        match self {
+            Self::Protection => definition(10, 4, cost(1, 11), cost(12, 11), Armor),
+            Self::FireProtection => definition(5, 4, cost(10, 8), cost(18, 8), Armor),
+            Self::FeatherFalling => definition(5, 4, cost(5, 6), cost(11, 6), FootArmor),
+            Self::BlastProtection => definition(2, 4, cost(5, 8), cost(13, 8), Armor),
+            Self::ProjectileProtection => definition(5, 4, cost(3, 6), cost(9, 6), Armor),
+            Self::Respiration => definition(2, 3, cost(10, 10), cost(40, 10), HeadArmor),
+            Self::AquaAffinity => definition(2, 1, cost(1, 0), cost(41, 0), HeadArmor),
+            Self::Thorns => definition(1, 3, cost(10, 20), cost(60, 20), Armor).primary(ChestArmor),
+            Self::DepthStrider => definition(2, 3, cost(10, 10), cost(25, 10), FootArmor),
+            Self::FrostWalker => definition(2, 2, cost(10, 10), cost(25, 10), FootArmor).treasure(),
+            Self::BindingCurse => definition(1, 1, cost(25, 0), cost(50, 0), Equippable).treasure(),
+            Self::SoulSpeed => definition(1, 3, cost(10, 10), cost(25, 10), FootArmor).treasure(),
+            Self::SwiftSneak => definition(1, 3, cost(25, 25), cost(75, 25), LegArmor).treasure(),
+            Self::Sharpness => {
+                definition(10, 5, cost(1, 11), cost(21, 11), SharpWeapon).primary(Sword)
+            }
+            Self::Smite | Self::BaneOfArthropods => {
+                definition(5, 5, cost(5, 8), cost(25, 8), Weapon).primary(Sword)
+            }
+            Self::Knockback => definition(5, 2, cost(5, 20), cost(55, 20), Sword),
+            Self::FireAspect => definition(2, 2, cost(10, 20), cost(60, 20), FireAspect),
+            Self::Looting => definition(2, 3, cost(15, 9), cost(65, 9), Sword),
+            Self::SweepingEdge => definition(2, 3, cost(5, 9), cost(20, 9), Sword),
+            Self::Efficiency => definition(10, 5, cost(1, 10), cost(51, 10), Mining),
+            Self::SilkTouch => definition(1, 1, cost(15, 0), cost(65, 0), MiningLoot),
+            Self::Unbreaking => definition(5, 3, cost(5, 8), cost(55, 8), Durability),
+            Self::Fortune => definition(2, 3, cost(15, 9), cost(65, 9), MiningLoot),
+            Self::Power => definition(10, 5, cost(1, 10), cost(16, 10), Bow),
+            Self::Punch => definition(2, 2, cost(12, 20), cost(37, 20), Bow),
+            Self::Flame | Self::Infinity => {
+                let weight = if matches!(self, Self::Flame) { 2 } else { 1 };
+                definition(weight, 1, cost(20, 0), cost(50, 0), Bow)
+            }
+            Self::LuckOfTheSea | Self::Lure => {
+                definition(2, 3, cost(15, 9), cost(65, 9), FishingRod)
+            }
+            Self::Loyalty => definition(5, 3, cost(12, 7), cost(50, 0), Trident),
+            Self::Impaling => definition(2, 5, cost(1, 8), cost(21, 8), Trident),
+            Self::Riptide => definition(2, 3, cost(17, 7), cost(50, 0), Trident),
+            Self::Channeling => definition(1, 1, cost(25, 0), cost(50, 0), Trident),
+            Self::Multishot => definition(2, 1, cost(20, 0), cost(50, 0), Crossbow),
+            Self::QuickCharge => definition(5, 3, cost(12, 20), cost(50, 0), Crossbow),
+            Self::Piercing => definition(10, 4, cost(1, 10), cost(50, 0), Crossbow),
+            Self::Density => definition(5, 5, cost(5, 8), cost(25, 8), Mace),
+            Self::Breach => definition(2, 4, cost(15, 9), cost(65, 9), Mace),
+            Self::WindBurst => definition(2, 3, cost(15, 9), cost(65, 9), Mace).treasure(),
+            Self::Mending => definition(2, 1, cost(25, 25), cost(75, 25), Durability).treasure(),
+            Self::VanishingCurse => {
+                definition(1, 1, cost(25, 0), cost(50, 0), Vanishable).treasure()
+            }
+        }
+    }
+
+    pub const fn max_level(self) -> u8 {
+        self.definition().max_level
+    }
+
+    /// Treasure enchantments can't be found at the enchanting table
+    pub const fn is_treasure(self) -> bool {
+        self.definition().treasure
+    }
+
+    /// The levels an anvil charges for every level of the enchantment, rarer ones cost more
+    pub const fn anvil_cost(self) -> i32 {
+        match self.definition().weight {
+            10 => 1,
+            5 => 2,
+            2 => 4,
+            _ => 8,
+        }
+    }
+
+    /// Whether the enchantment works on the item, e.g. with an anvil
+    pub fn supports(self, item: &ItemStack) -> bool {
+        self.definition().supported.contains(item)
+    }
+
+    /// Whether an enchanting table may put the enchantment on the item
+    fn is_primary_item(self, item: &ItemStack) -> bool {
+        let definition = self.definition();
+        definition
+            .primary
+            .unwrap_or(definition.supported)
+            .contains(item)
+    }
+
+    /// Whether both enchantments can be on the same item, an enchantment isn't compatible with
+    /// itself
+    pub fn is_compatible(self, other: Self) -> bool {
+        self != other
+            && !EXCLUSIVE
+                .iter()
+                .any(|group| group.contains(&self) && group.contains(&other))
+    }
+}
+
+impl ItemSet {
+    fn contains(self, item: &ItemStack) -> bool {
+        let Some(name) = global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id)
+            .and_then(|id| id.strip_prefix("minecraft:"))
+        else {
+            return false;
+        };
+        match self {
+            Self::Armor => [
+                Self::HeadArmor,
+                Self::ChestArmor,
+                Self::LegArmor,
+                Self::FootArmor,
+            ]
+            .into_iter()
+            .any(|set| set.contains(item)),
+            Self::HeadArmor => name.ends_with("_helmet"),
+            Self::ChestArmor => name.ends_with("_chestplate"),
+            Self::LegArmor => name.ends_with("_leggings"),
+            Self::FootArmor => name.ends_with("_boots"),
+            Self::Equippable => {
+                Self::Armor.contains(item)
+                    || matches!(name, "elytra" | "carved_pumpkin")
+                    || name.ends_with("_head")
+                    || name.ends_with("_skull")
+            }
+            Self::Sword => name.ends_with("_sword"),
+            Self::SharpWeapon => name.ends_with("_sword") || name.ends_with("_axe"),
+            Self::Weapon => Self::SharpWeapon.contains(item) || name == "mace",
+            Self::FireAspect => name.ends_with("_sword") || name == "mace",
+            Self::Mining => Self::MiningLoot.contains(item) || name == "shears",
+            Self::MiningLoot => ["_pickaxe", "_axe", "_shovel", "_hoe"]
+                .into_iter()
+                .any(|tool| name.ends_with(tool)),
+            Self::Durability => item.max_damage().is_some(),
+            Self::Bow => name == "bow",
+            Self::Crossbow => name == "crossbow",
+            Self::Trident => name == "trident",
+            Self::FishingRod => name == "fishing_rod",
+            Self::Mace => name == "mace",
+            Self::Vanishable => {
+                Self::Durability.contains(item)
+                    || Self::Equippable.contains(item)
+                    || matches!(name, "compass" | "recovery_compass")
+            }
+        }
+    }
+}
+
+/// The enchantments of an item with their levels, like its `minecraft:enchantments` component.
+/// Enchanted books store theirs in `minecraft:stored_enchantments` instead
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Enchantments([u8; Enchantment::ALL.len()]);
+
+impl Default for Enchantments {
+    fn default() -> Self {
+        Self([0; Enchantment::ALL.len()])
+    }
+}
+
+impl Enchantments {
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|level| *level == 0)
+    }
+
+    /// The level of the enchantment, 0 if the item doesn't have it
+    pub fn level(&self, enchantment: Enchantment) -> u8 {
+        self.0[enchantment as usize]
+    }
+
+    /// Level 0 removes the enchantment
+    pub fn set(&mut self, enchantment: Enchantment, level: u8) {
+        self.0[enchantment as usize] = level;
+    }
+
+    /// Every enchantment with its level
+    pub fn iter(&self) -> impl Iterator<Item = (Enchantment, u8)> + '_ {
+        Enchantment::ALL
+            .into_iter()
+            .zip(self.0)
+            .filter(|(_, level)| *level > 0)
+    }
+
+    /// Reads the component, vanilla accepts the levels with or without the compound around them.
+    /// Enchantments Pumpkin doesn't know are dropped
+    pub fn from_nbt(value: &Value) -> Self {
+        let mut enchantments = Self::default();
+        let Value::Compound(component) = value else {
+            return enchantments;
+        };
+        let levels = match component.get("levels") {
+            Some(Value::Compound(levels)) => levels,
+            _ => component,
+        };
+        for (name, level) in levels {
+            let level = match level {
+                Value::Int(level) => *level,
+                Value::Short(level) => i32::from(*level),
+                Value::Byte(level) => i32::from(*level),
+                _ => continue,
+            };
+            if let Some(enchantment) = Enchantment::from_name(name) {
+                enchantments.set(enchantment, level.clamp(0, u8::MAX.into()) as u8);
+            }
+        }
+        enchantments
+    }
+
+    pub fn to_nbt(&self) -> Value {
+        let levels = self
+            .iter()
+            .map(|(enchantment, level)| (enchantment.name().to_string(), Value::Int(level.into())))
+            .collect();
+        Value::Compound(HashMap::from([(
+            "levels".to_string(),
+            Value::Compound(levels),
+        )]))
+    }
+}
+
+/// How well an item enchants at an enchanting table like in vanilla, the better the more and the
+/// stronger enchantments it gets. 0 if the table can't enchant it
+pub fn enchantability(item: &ItemStack) -> i32 {
+    let Some(name) = global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id)
+        .and_then(|id| id.strip_prefix("minecraft:"))
+    else {
+        return 0;
+    };
+    match name {
+        "book" | "bow" | "crossbow" | "trident" | "fishing_rod" => return 1,
+        "mace" => return 15,
+        "turtle_helmet" => return 9,
+        _ => {}
+    }
+    if !ItemSet::Armor.contains(item)
+        && !ItemSet::MiningLoot.contains(item)
+        && !ItemSet::Sword.contains(item)
+    {
+        return 0;
+    }
+    let is_armor = ItemSet::Armor.contains(item);
+    match name.split('_').next() {
+        Some("wooden") => 15,
+        Some("stone") => 5,
+        Some("iron") if is_armor => 9,
+        Some("iron") => 14,
+        Some("golden") if is_armor => 25,
+        Some("golden") => 22,
+        Some("diamond") => 10,
+        Some("netherite") => 15,
+        Some("leather") => 15,
+        Some("chainmail") => 12,
+        _ => 0,
+    }
+}
+
+/// Whether an enchanting table can enchant the item: one tool, weapon, armor piece or book
+/// without enchantments
+pub fn is_enchantable(item: &ItemStack) -> bool {
+    item.item_count == 1 && item.enchantments.is_empty() && enchantability(item) > 0
+}
+
+/// The levels the three options of an enchanting table cost like in vanilla, 0 for options which
+/// aren't available. `seed` is the enchantment seed of the player
+pub fn enchanting_costs(seed: i32, bookshelves: i32, item: &ItemStack) -> [i32; 3] {
+    let mut costs = [0; 3];
+    if !is_enchantable(item) {
+        return costs;
+    }
+    let bookshelves = bookshelves.clamp(0, MAX_BOOKSHELVES);
+    let mut random = LegacyRand::from_seed(seed as i64 as u64);
+    for (slot, cost) in costs.iter_mut().enumerate() {
+        let base = random.next_bounded_i32(8)
+            + 1
+            + (bookshelves >> 1)
+            + random.next_bounded_i32(bookshelves + 1);
+        *cost = match slot {
+            0 => (base / 3).max(1),
+            1 => base * 2 / 3 + 1,
+            _ => base.max(bookshelves * 2),
+        };
+        if *cost < slot as i32 + 1 {
+            *cost = 0;
+        }
+    }
+    costs
+}
+
+/// The enchantments an option of the enchanting table puts on the item. `slot` is the option and
+/// `cost` the levels it costs
+pub fn table_enchantments(
+    seed: i32,
+    slot: i32,
+    cost: i32,
+    item: &ItemStack,
+) -> Vec<(Enchantment, u8)> {
+    table_enchantments_with_random(seed, slot, cost, item).0
+}
+
+/// The enchantment and level the enchanting table shows as a hint for an option, one of those it
+/// puts on the item
+pub fn table_clue(seed: i32, slot: i32, cost: i32, item: &ItemStack) -> Option<(Enchantment, u8)> {
+    let (enchantments, mut random) = table_enchantments_with_random(seed, slot, cost, item);
+    if enchantments.is_empty() {
+        return None;
+    }
+    Some(enchantments[random.next_bounded_i32(enchantments.len() as i32) as usize])
+}
+
+/// Like in vanilla the clue is picked with the same random, so it is always one of the
+/// enchantments
+fn table_enchantments_with_random(
+    seed: i32,
+    slot: i32,
+    cost: i32,
+    item: &ItemStack,
+) -> (Vec<(Enchantment, u8)>, LegacyRand) {
+    let mut random = LegacyRand::from_seed(seed.wrapping_add(slot) as i64 as u64);
+    let mut enchantments = select_enchantments(&mut random, item, cost);
+    // Books only get some of them
+    if is_book(item) && enchantments.len() > 1 {
+        enchantments.remove(random.next_bounded_i32(enchantments.len() as i32) as usize);
+    }
+    (enchantments, random)
+}
+
+fn is_book(item: &ItemStack) -> bool {
+    global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id) == Some("minecraft:book")
+}
+
+/// Picks random enchantments for an item which are worth about `level`, like vanilla
+fn select_enchantments(
+    random: &mut LegacyRand,
+    item: &ItemStack,
+    level: i32,
+) -> Vec<(Enchantment, u8)> {
+    let mut selected = Vec::new();
+    let enchantability = enchantability(item);
+    if enchantability <= 0 {
+        return selected;
+    }
+    let mut level = level
+        + 1
+        + random.next_bounded_i32(enchantability / 4 + 1)
+        + random.next_bounded_i32(enchantability / 4 + 1);
+    let bonus = (random.next_f32() + random.next_f32() - 1.0) * 0.15;
+    level = ((level as f32 + level as f32 * bonus + 0.5).floor() as i32).max(1);
+
+    let mut available = available_enchantments(level, item);
+    if available.is_empty() {
+        return selected;
+    }
+    selected.extend(weighted_random(random, &available));
+    while random.next_bounded_i32(50) <= level {
+        if let Some((last, _)) = selected.last() {
+            available.retain(|(enchantment, _)| last.is_compatible(*enchantment));
+        }
+        if available.is_empty() {
+            break;
+        }
+        selected.extend(weighted_random(random, &available));
+        level /= 2;
+    }
+    selected
+}
+
+/// The highest level of every enchantment the table can put on the item for `level`
+fn available_enchantments(level: i32, item: &ItemStack) -> Vec<(Enchantment, u8)> {
+    let book = is_book(item);
+    Enchantment::ALL
+        .into_iter()
+        .filter(|enchantment| !enchantment.is_treasure())
+        .filter(|enchantment| book || enchantment.is_primary_item(item))
+        .filter_map(|enchantment| {
+            let definition = enchantment.definition();
+            (1..=definition.max_level)
+                .rev()
+                .find(|enchantment_level| {
+                    (definition.min_cost.at(*enchantment_level)
+                        ..=definition.max_cost.at(*enchantment_level))
+                        .contains(&level)
+                })
+                .map(|enchantment_level| (enchantment, enchantment_level))
+        })
+        .collect()
+}
+
+fn weighted_random(
+    random: &mut LegacyRand,
+    enchantments: &[(Enchantment, u8)],
+) -> Option<(Enchantment, u8)> {
+    let total: i32 = enchantments
+        .iter()
+        .map(|(enchantment, _)| enchantment.definition().weight)
+        .sum();
+    if total <= 0 {
+        return None;
+    }
+    let mut index = random.next_bounded_i32(total);
+    for (enchantment, level) in enchantments {
+        index -= enchantment.definition().weight;
+        if index < 0 {
+            return Some((*enchantment, *level));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::item;
+
+    #[test]
+    fn names() {
+        for (id, enchantment) in Enchantment::ALL.into_iter().enumerate() {
+            assert_eq!(Enchantment::from_registry_id(id as i32), Some(enchantment));
+            assert_eq!(
+                Enchantment::from_name(enchantment.name()),
+                Some(enchantment)
+            );
+        }
+        assert_eq!(
+            Enchantment::from_name("silk_touch"),
+            Some(Enchantment::SilkTouch)
+        );
+    }
+
+    #[test]
+    fn items() {
+        assert!(Enchantment::Sharpness.supports(&item("minecraft:diamond_axe")));
+        assert!(!Enchantment::Sharpness.is_primary_item(&item("minecraft:diamond_axe")));
+        assert!(Enchantment::Efficiency.supports(&item("minecraft:iron_pickaxe")));
+        assert!(!Enchantment::Efficiency.supports(&item("minecraft:iron_sword")));
+        assert!(Enchantment::Unbreaking.supports(&item("minecraft:bow")));
+        assert!(!Enchantment::Unbreaking.supports(&item("minecraft:stone")));
+        assert!(!Enchantment::Fortune.is_compatible(Enchantment::SilkTouch));
+        assert!(!Enchantment::Fortune.is_compatible(Enchantment::Fortune));
+        assert!(Enchantment::Fortune.is_compatible(Enchantment::Efficiency));
+        assert_eq!(enchantability(&item("minecraft:golden_helmet")), 25);
+        assert_eq!(enchantability(&item("minecraft:golden_hoe")), 22);
+        assert_eq!(enchantability(&item("minecraft:stone")), 0);
+    }
+
+    #[test]
+    fn enchanting() {
+        let sword = item("minecraft:diamond_sword");
+        for seed in 0..100 {
+            let costs = enchanting_costs(seed, 15, &sword);
+            assert!(costs.iter().all(|cost| *cost > 0));
+            assert_eq!(costs[2], 30);
+            for (slot, cost) in costs.into_iter().enumerate() {
+                let enchantments = table_enchantments(seed, slot as i32, cost, &sword);
+                assert!(!enchantments.is_empty());
+                for (enchantment, level) in &enchantments {
+                    assert!(enchantment.is_primary_item(&sword));
+                    assert!(*level >= 1 && *level <= enchantment.max_level());
+                }
+                let clue = table_clue(seed, slot as i32, cost, &sword).unwrap();
+                assert!(enchantments.contains(&clue));
+            }
+        }
+        // The same seed always gives the same options
+        assert_eq!(
+            table_enchantments(42, 2, 30, &sword),
+            table_enchantments(42, 2, 30, &sword)
+        );
+        assert_eq!(enchanting_costs(7, 0, &item("minecraft:stone")), [0; 3]);
+    }
+
+    #[test]
+    fn nbt() {
+        let mut enchantments = Enchantments::default();
+        assert!(enchantments.is_empty());
+        enchantments.set(Enchantment::Sharpness, 5);
+        enchantments.set(Enchantment::Unbreaking, 3);
+        assert_eq!(Enchantments::from_nbt(&enchantments.to_nbt()), enchantments);
+        let levels = Value::Compound(HashMap::from([(
+            "minecraft:unbreaking".to_string(),
+            Value::Int(2),
+        )]));
+        assert_eq!(
+            Enchantments::from_nbt(&levels).iter().collect::<Vec<_>>(),
+            vec![(Enchantment::Unbreaking, 2)]
+        );
+    }
+}
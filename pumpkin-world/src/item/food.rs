@@ -0,0 +1,101 @@
+use serde::Deserialize;
+
+/// The `minecraft:food` component of an item, what eating it gives
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Food {
+    /// The food points the item restores
+    pub nutrition: i32,
+    /// The saturation the item adds, never more than the food level
+    pub saturation: f32,
+    /// Whether the item can be eaten with a full food bar, e.g. golden apples
+    #[serde(default)]
+    pub can_always_eat: bool,
+    #[serde(default = "default_eat_seconds")]
+    pub eat_seconds: f32,
+    /// The item which is left after eating, e.g. a bowl
+    pub using_converts_to: Option<ConvertsTo>,
+    /// The status effects the eater may get
+    #[serde(default)]
+    pub effects: Vec<FoodEffect>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct ConvertsTo {
+    pub id: String,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct FoodEffect {
+    pub effect: EffectInstance,
+    /// The chance to get the effect, from 0 to 1
+    #[serde(default = "default_probability")]
+    pub probability: f32,
+}
+
+/// A status effect as items describe it
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct EffectInstance {
+    /// The registry id of the effect, e.g. `minecraft:regeneration`
+    pub id: String,
+    /// 0 for level I
+    #[serde(default)]
+    pub amplifier: u8,
+    /// In ticks
+    #[serde(default)]
+    pub duration: i32,
+    #[serde(default)]
+    pub ambient: bool,
+    #[serde(default = "default_true")]
+    pub show_particles: bool,
+    #[serde(default = "default_true")]
+    pub show_icon: bool,
+}
+
+fn default_eat_seconds() -> f32 {
+    1.6
+}
+
+fn default_probability() -> f32 {
+    1.0
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Food {
+    /// How many ticks it takes to eat the item
+    pub fn eat_ticks(&self) -> u32 {
+        (self.eat_seconds * 20.0).round() as u32
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        global_registry::{self, ITEM_REGISTRY},
+        item::get_food,
+    };
+
+    fn food(name: &str) -> Option<&'static super::Food> {
+        get_food(global_registry::get_protocol_id(ITEM_REGISTRY, name))
+    }
+
+    #[test]
+    fn food_components() {
+        let bread = food("minecraft:bread").unwrap();
+        assert_eq!(bread.nutrition, 5);
+        assert_eq!(bread.eat_ticks(), 32);
+        assert!(!bread.can_always_eat);
+
+        assert_eq!(food("minecraft:dried_kelp").unwrap().eat_ticks(), 16);
+        let golden_apple = food("minecraft:golden_apple").unwrap();
+        assert!(golden_apple.can_always_eat);
+        assert_eq!(golden_apple.effects.len(), 2);
+        assert_eq!(golden_apple.effects[0].effect.id, "minecraft:regeneration");
+        assert_eq!(golden_apple.effects[0].effect.amplifier, 1);
+        assert_eq!(golden_apple.effects[1].effect.duration, 2400);
+        assert_eq!(golden_apple.effects[1].probability, 1.0);
+        assert!(food("minecraft:stone").is_none());
+    }
+}
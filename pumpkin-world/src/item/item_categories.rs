@@ -1,6 +1,42 @@
-use crate::item::ItemStack;
+use crate::{
+    global_registry::{find_minecraft_id, ITEM_REGISTRY},
+    item::ItemStack,
+};
+
+/// The armor points of armor pieces by the start of their id, from the helmet to the boots,
+/// and their toughness
+const ARMOR: [(&str, [f32; 4], f32); 7] = [
+    ("minecraft:leather_", [1.0, 3.0, 2.0, 1.0], 0.0),
+    ("minecraft:chainmail_", [2.0, 5.0, 4.0, 1.0], 0.0),
+    ("minecraft:iron_", [2.0, 6.0, 5.0, 2.0], 0.0),
+    ("minecraft:golden_", [2.0, 5.0, 3.0, 1.0], 0.0),
+    ("minecraft:diamond_", [3.0, 8.0, 6.0, 3.0], 2.0),
+    ("minecraft:netherite_", [3.0, 8.0, 6.0, 3.0], 3.0),
+    ("minecraft:turtle_", [2.0, 0.0, 0.0, 0.0], 0.0),
+];
+
+const ARMOR_PIECES: [&str; 4] = ["_helmet", "_chestplate", "_leggings", "_boots"];
 
 impl ItemStack {
+    /// The armor points and toughness the item gives when it is worn, 0 for other items
+    pub fn armor(&self) -> (f32, f32) {
+        let Some(name) = find_minecraft_id(ITEM_REGISTRY, self.item_id) else {
+            return (0.0, 0.0);
+        };
+        let Some(piece) = ARMOR_PIECES
+            .iter()
+            .position(|suffix| name.ends_with(suffix))
+        else {
+            return (0.0, 0.0);
+        };
+        ARMOR
+            .iter()
+            .find(|(prefix, ..)| name.starts_with(prefix))
+            .map_or((0.0, 0.0), |(_, points, toughness)| {
+                (points[piece], *toughness)
+            })
+    }
+
     pub fn is_helmet(&self) -> bool {
         [
             // Leather
@@ -1,6 +1,6 @@
 use std::{collections::HashMap, sync::LazyLock};
 
-use super::Rarity;
+use super::{AttributeModifier, AttributeModifiers, Food, Rarity, Tool};
 use crate::global_registry::{self, ITEM_REGISTRY};
 
 const ITEMS_JSON: &str = include_str!("../../../assets/items.json");
@@ -9,25 +9,117 @@ pub static ITEMS: LazyLock<HashMap<String, ItemElement>> = LazyLock::new(|| {
     serde_json::from_str(ITEMS_JSON).expect("Could not parse items.json registry.")
 });
 
-#[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(serde::Deserialize, Debug, Clone, PartialEq)]
 pub struct ItemComponents {
-    // TODO: attribute_modifiers
-    // TODO: enchantments: HashMap<>
+    #[serde(rename = "minecraft:attribute_modifiers")]
+    attribute_modifiers: AttributeModifiers,
     #[serde(rename = "minecraft:lore")]
     lore: Vec<String>,
     #[serde(rename = "minecraft:max_stack_size")]
     max_stack_size: u32,
+    #[serde(rename = "minecraft:max_damage")]
+    max_damage: Option<u32>,
     #[serde(rename = "minecraft:rarity")]
     rarity: Rarity,
     #[serde(rename = "minecraft:repair_cost")]
     repair_cost: u32,
+    #[serde(rename = "minecraft:tool")]
+    tool: Option<Tool>,
+    #[serde(rename = "minecraft:food")]
+    food: Option<Food>,
 }
 
-#[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(serde::Deserialize, Debug, Clone, PartialEq)]
 pub struct ItemElement {
     components: ItemComponents,
 }
 
+/// How many items fit into a stack, by protocol id
+static MAX_STACK_SIZES: LazyLock<HashMap<u32, u8>> = LazyLock::new(|| {
+    ITEMS
+        .iter()
+        .filter_map(|(name, item)| {
+            let protocol_id = global_registry::find_protocol_id(ITEM_REGISTRY, name)?;
+            Some((protocol_id, item.components.max_stack_size as u8))
+        })
+        .collect()
+});
+
+/// How many items of a kind fit into one stack, 64 for unknown items
+pub fn get_max_stack_size(protocol_id: u32) -> u8 {
+    MAX_STACK_SIZES.get(&protocol_id).copied().unwrap_or(64)
+}
+
+/// How often items which break can be used, by protocol id
+static MAX_DAMAGES: LazyLock<HashMap<u32, u32>> = LazyLock::new(|| {
+    ITEMS
+        .iter()
+        .filter_map(|(name, item)| {
+            let protocol_id = global_registry::find_protocol_id(ITEM_REGISTRY, name)?;
+            Some((protocol_id, item.components.max_damage?))
+        })
+        .collect()
+});
+
+/// How often an item can be used before it breaks, None if it doesn't break
+pub fn get_max_damage(protocol_id: u32) -> Option<u32> {
+    MAX_DAMAGES.get(&protocol_id).copied()
+}
+
+/// The tools by protocol id
+static TOOLS: LazyLock<HashMap<u32, Tool>> = LazyLock::new(|| {
+    ITEMS
+        .iter()
+        .filter_map(|(name, item)| {
+            let protocol_id = global_registry::find_protocol_id(ITEM_REGISTRY, name)?;
+            Some((protocol_id, item.components.tool.clone()?))
+        })
+        .collect()
+});
+
+/// The tool component of an item, None if it is no tool
+pub fn get_tool(protocol_id: u32) -> Option<&'static Tool> {
+    TOOLS.get(&protocol_id)
+}
+
+/// The foods by protocol id
+static FOODS: LazyLock<HashMap<u32, Food>> = LazyLock::new(|| {
+    ITEMS
+        .iter()
+        .filter_map(|(name, item)| {
+            let protocol_id = global_registry::find_protocol_id(ITEM_REGISTRY, name)?;
+            Some((protocol_id, item.components.food.clone()?))
+        })
+        .collect()
+});
+
+/// The food component of an item, None if it can't be eaten
+pub fn get_food(protocol_id: u32) -> Option<&'static Food> {
+    FOODS.get(&protocol_id)
+}
+
+/// The attribute modifiers by protocol id, items without any are left out
+static ATTRIBUTE_MODIFIERS: LazyLock<HashMap<u32, Vec<AttributeModifier>>> = LazyLock::new(|| {
+    ITEMS
+        .iter()
+        .filter(|(_, item)| !item.components.attribute_modifiers.modifiers.is_empty())
+        .filter_map(|(name, item)| {
+            let protocol_id = global_registry::find_protocol_id(ITEM_REGISTRY, name)?;
+            Some((
+                protocol_id,
+                item.components.attribute_modifiers.modifiers.clone(),
+            ))
+        })
+        .collect()
+});
+
+/// The attribute modifiers of an item, empty if it has none
+pub fn get_attribute_modifiers(protocol_id: u32) -> &'static [AttributeModifier] {
+    ATTRIBUTE_MODIFIERS
+        .get(&protocol_id)
+        .map_or(&[], Vec::as_slice)
+}
+
 #[expect(dead_code)]
 pub fn get_item_element(item_id: &str) -> &ItemComponents {
     &ITEMS.get(item_id).expect("Item not found").components
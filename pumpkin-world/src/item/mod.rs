@@ -1,6 +1,28 @@
+pub mod anvil;
+pub mod attribute_modifiers;
+pub mod enchantment;
+mod food;
 mod item_categories;
 mod item_registry;
-pub use item_registry::ITEMS;
+mod name;
+mod tool;
+pub use attribute_modifiers::{AttributeModifier, AttributeModifiers, AttributeOperation};
+pub use enchantment::{Enchantment, Enchantments};
+pub use food::{EffectInstance, Food, FoodEffect};
+pub use item_registry::{
+    get_attribute_modifiers, get_food, get_max_damage, get_max_stack_size, get_tool, ITEMS,
+};
+pub use name::{ItemName, MAX_NAME_LENGTH};
+pub use tool::Tool;
+
+use std::collections::HashMap;
+
+use fastnbt::Value;
+
+use crate::{
+    block::BlockId,
+    global_registry::{self, ITEM_REGISTRY},
+};
 #[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 /// Item Rarity
@@ -16,11 +38,160 @@ pub struct ItemStack {
     pub item_count: u8,
     // This ID is the numerical protocol ID, not the usual minecraft::block ID.
     pub item_id: u32,
-    // TODO: Add Item Components
+    // TODO: Add the other Item Components
+    pub enchantments: Enchantments,
+    /// How much of its durability the item used up
+    pub damage: u32,
+    /// The levels anvils charge on top for working on the item, it grows with every use
+    pub repair_cost: u32,
+    pub custom_name: Option<ItemName>,
+}
+
+impl ItemStack {
+    pub fn new(item_count: u8, item_id: u32) -> Self {
+        Self {
+            item_count,
+            item_id,
+            enchantments: Enchantments::default(),
+            damage: 0,
+            repair_cost: 0,
+            custom_name: None,
+        }
+    }
+
+    /// The same item with its components, but `item_count` of it
+    pub fn with_count(&self, item_count: u8) -> Self {
+        Self {
+            item_count,
+            ..*self
+        }
+    }
+
+    /// How many items of this kind fit into one stack
+    pub fn max_stack_size(&self) -> u8 {
+        get_max_stack_size(self.item_id)
+    }
+
+    /// How often the item can be used before it breaks, None if it doesn't break
+    pub fn max_damage(&self) -> Option<u32> {
+        get_max_damage(self.item_id)
+    }
+
+    /// Enchanted books keep their enchantments in `minecraft:stored_enchantments`, they only
+    /// apply once an anvil puts them on another item
+    pub fn stores_enchantments(&self) -> bool {
+        global_registry::find_minecraft_id(ITEM_REGISTRY, self.item_id)
+            == Some("minecraft:enchanted_book")
+    }
+
+    /// Reads the components vanilla stores with the item, those Pumpkin doesn't know are dropped
+    pub fn read_components(&mut self, item: &HashMap<String, Value>) {
+        let Some(Value::Compound(components)) = item.get("components") else {
+            return;
+        };
+        let enchantments = components
+            .get("minecraft:stored_enchantments")
+            .or_else(|| components.get("minecraft:enchantments"));
+        if let Some(enchantments) = enchantments {
+            self.enchantments = Enchantments::from_nbt(enchantments);
+        }
+        if let Some(Value::Int(damage)) = components.get("minecraft:damage") {
+            self.damage = (*damage).max(0) as u32;
+        }
+        if let Some(Value::Int(repair_cost)) = components.get("minecraft:repair_cost") {
+            self.repair_cost = (*repair_cost).max(0) as u32;
+        }
+        if let Some(Value::String(name)) = components.get("minecraft:custom_name") {
+            self.custom_name = ItemName::from_json(name);
+        }
+    }
+
+    /// Stores the components of the item like vanilla, items without any get none
+    pub fn write_components(&self, item: &mut HashMap<String, Value>) {
+        let mut components = HashMap::new();
+        if !self.enchantments.is_empty() {
+            let key = if self.stores_enchantments() {
+                "minecraft:stored_enchantments"
+            } else {
+                "minecraft:enchantments"
+            };
+            components.insert(key.to_string(), self.enchantments.to_nbt());
+        }
+        if self.damage > 0 {
+            components.insert(
+                "minecraft:damage".to_string(),
+                Value::Int(self.damage as i32),
+            );
+        }
+        if self.repair_cost > 0 {
+            components.insert(
+                "minecraft:repair_cost".to_string(),
+                Value::Int(self.repair_cost as i32),
+            );
+        }
+        if let Some(name) = &self.custom_name {
+            components.insert(
+                "minecraft:custom_name".to_string(),
+                Value::String(name.to_json()),
+            );
+        }
+        if !components.is_empty() {
+            item.insert("components".to_string(), Value::Compound(components));
+        }
+    }
+
+    /// The tool component of the item, None if it is no tool
+    pub fn tool(&self) -> Option<&'static Tool> {
+        get_tool(self.item_id)
+    }
+
+    /// How the item changes the attributes of the entity holding or wearing it
+    pub fn attribute_modifiers(&self) -> &'static [AttributeModifier] {
+        get_attribute_modifiers(self.item_id)
+    }
+
+    /// The value the item adds to `attribute` while it is held in the main hand
+    pub fn main_hand_bonus(&self, attribute: &str) -> f64 {
+        self.attribute_modifiers()
+            .iter()
+            .filter(|modifier| {
+                modifier.attribute == attribute
+                    && modifier.slot == "mainhand"
+                    && modifier.operation == AttributeOperation::AddValue
+            })
+            .map(|modifier| modifier.amount)
+            .sum()
+    }
+
+    /// The food component of the item, None if it can't be eaten
+    pub fn food(&self) -> Option<&'static Food> {
+        get_food(self.item_id)
+    }
+
+    /// How much faster than by hand the item breaks the block
+    pub fn mining_speed(&self, block: &BlockId) -> f32 {
+        self.tool().map_or(1.0, |tool| tool.mining_speed(block))
+    }
+
+    /// Whether blocks which need the right tool drop their items when broken with this item
+    pub fn is_correct_for_drops(&self, block: &BlockId) -> bool {
+        self.tool()
+            .is_some_and(|tool| tool.is_correct_for_drops(block))
+    }
+
+    /// Whether this stack and `other` are the same item with the same amount
+    pub fn is_same_stack(&self, other: &Self) -> bool {
+        self == other && self.item_count == other.item_count
+    }
 }
 
+/// Items are equal if they can be stacked, no matter how many there are
 impl PartialEq for ItemStack {
     fn eq(&self, other: &Self) -> bool {
         self.item_id == other.item_id
+            && self.enchantments == other.enchantments
+            && self.damage == other.damage
+            && self.repair_cost == other.repair_cost
+            && self.custom_name == other.custom_name
     }
 }
@@ -0,0 +1,66 @@
+use std::fmt;
+
+/// Anvils don't give items longer names than this many characters, like in vanilla
+pub const MAX_NAME_LENGTH: usize = 50;
+/// Every character takes up to 4 bytes in UTF-8
+const MAX_NAME_BYTES: usize = MAX_NAME_LENGTH * 4;
+
+/// The name a player gave an item, its `minecraft:custom_name` component. It is kept inside of
+/// the item so items can still be copied
+#[derive(Clone, Copy)]
+pub struct ItemName {
+    len: u8,
+    bytes: [u8; MAX_NAME_BYTES],
+}
+
+impl ItemName {
+    /// The name cut off after `MAX_NAME_LENGTH` characters, None if it is blank
+    pub fn new(name: &str) -> Option<Self> {
+        if name.trim().is_empty() {
+            return None;
+        }
+        let end = name
+            .char_indices()
+            .nth(MAX_NAME_LENGTH)
+            .map_or(name.len(), |(end, _)| end);
+        let mut bytes = [0; MAX_NAME_BYTES];
+        bytes[..end].copy_from_slice(&name.as_bytes()[..end]);
+        Some(Self {
+            len: end as u8,
+            bytes,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Only ever cut at the boundary of a character
+        std::str::from_utf8(&self.bytes[..self.len as usize]).unwrap_or_default()
+    }
+
+    /// Reads the component, vanilla stores it as a text component in JSON. Only the text is kept
+    pub fn from_json(json: &str) -> Option<Self> {
+        match serde_json::from_str(json) {
+            Ok(serde_json::Value::String(text)) => Self::new(&text),
+            Ok(serde_json::Value::Object(component)) => Self::new(component.get("text")?.as_str()?),
+            // Older versions stored the plain name
+            _ => Self::new(json),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::Value::String(self.as_str().to_string()).to_string()
+    }
+}
+
+impl PartialEq for ItemName {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for ItemName {}
+
+impl fmt::Debug for ItemName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
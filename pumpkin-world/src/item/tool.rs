@@ -0,0 +1,61 @@
+use serde::Deserialize;
+
+use crate::block::BlockId;
+
+/// The `minecraft:tool` component of an item, how fast it breaks blocks and which blocks drop
+/// their items when it breaks them
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Tool {
+    rules: Vec<ToolRule>,
+    #[serde(default = "default_mining_speed")]
+    default_mining_speed: f32,
+}
+
+/// The first rule matching a block decides about it
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct ToolRule {
+    blocks: ToolBlocks,
+    speed: Option<f32>,
+    correct_for_drops: Option<bool>,
+}
+
+/// A block tag like `#minecraft:mineable/pickaxe`, a block or a list of blocks
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+enum ToolBlocks {
+    One(String),
+    List(Vec<String>),
+}
+
+fn default_mining_speed() -> f32 {
+    1.0
+}
+
+impl ToolRule {
+    fn matches(&self, block: &BlockId) -> bool {
+        match &self.blocks {
+            ToolBlocks::One(blocks) => block.is_in(blocks),
+            ToolBlocks::List(blocks) => blocks.iter().any(|blocks| block.is_in(blocks)),
+        }
+    }
+}
+
+impl Tool {
+    /// How much faster than by hand the tool breaks the block
+    pub fn mining_speed(&self, block: &BlockId) -> f32 {
+        self.rules
+            .iter()
+            .filter(|rule| rule.matches(block))
+            .find_map(|rule| rule.speed)
+            .unwrap_or(self.default_mining_speed)
+    }
+
+    /// Whether blocks which need the right tool drop their items when broken with this tool
+    pub fn is_correct_for_drops(&self, block: &BlockId) -> bool {
+        self.rules
+            .iter()
+            .filter(|rule| rule.matches(block))
+            .find_map(|rule| rule.correct_for_drops)
+            .unwrap_or(false)
+    }
+}
@@ -1,120 +1,635 @@
-use std::{collections::HashMap, path::PathBuf, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock},
+};
 
-use parking_lot::Mutex;
-use pumpkin_core::math::vector2::Vector2;
-use rayon::prelude::*;
-use tokio::sync::mpsc;
+use futures::{
+    future::{self, Shared},
+    FutureExt,
+};
+use parking_lot::{Mutex, RwLock};
+use pumpkin_core::math::{position::WorldPosition, vector2::Vector2, vector3::Vector3};
+use rand::Rng;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use tokio::sync::{mpsc, oneshot};
 
 use crate::{
-    chunk::{anvil::AnvilChunkReader, ChunkData, ChunkReader, ChunkReadingError},
-    world_gen::{get_world_gen, Seed, WorldGenerator},
+    biome::Biome,
+    block::{BlockEntity, BlockId},
+    chunk::{
+        anvil::AnvilChunkFormat, ChunkBlocks, ChunkData, ChunkParsingError, ChunkReader,
+        ChunkReadingError, ChunkWriter, SUBCHUNK_VOLUME,
+    },
+    coordinates::{ChunkRelativeBlockCoordinates, Height},
+    level_data::{LevelData, LevelDataError},
+    lighting::LightView,
+    ticket::{ChunkTickets, TicketType},
+    world_gen::{get_world_gen, ChunkGenerator, GeneratorSettings, Seed},
+    WORLD_HEIGHT, WORLD_LOWEST_Y, WORLD_MAX_Y,
 };
 
+/// Reads and generates chunks, so neither blocks the async runtime
+static CHUNK_WORKERS: LazyLock<ThreadPool> = LazyLock::new(|| {
+    ThreadPoolBuilder::new()
+        .thread_name(|index| format!("chunk-worker-{index}"))
+        .build()
+        .expect("Failed to start the chunk workers")
+});
+
+/// A chunk one of the chunk workers is reading or generating
+type PendingChunk = Shared<oneshot::Receiver<Arc<RwLock<ChunkData>>>>;
+
 /// The `Level` module provides functionality for working with chunks within or outside a Minecraft world.
 ///
 /// Key features include:
 ///
-/// - **Chunk Loading:** Efficiently loads chunks from disk.
-/// - **Chunk Caching:** Stores accessed chunks in memory for faster access.
-/// - **Chunk Generation:** Generates new chunks on-demand using a specified `WorldGenerator`.
+/// - **Chunk Loading:** Efficiently loads chunks from disk, in the background on the chunk workers.
+/// - **Chunk Caching:** Stores accessed chunks in memory for faster access, and their encoded packets until they change.
+/// - **Chunk Generation:** Generates new chunks on-demand using a specified `ChunkGenerator`.
+/// - **Chunk Saving:** Writes changed chunks back to disk.
+/// - **Chunk Unloading:** Chunks without tickets can be unloaded again, see `TicketType`.
+/// - **Lighting:** Calculates the light of loaded chunks and updates it when blocks change, see `LightView`.
 ///
-/// For more details on world generation, refer to the `WorldGenerator` module.
+/// For more details on world generation, refer to the `ChunkGenerator` module.
 pub struct Level {
-    save_file: Option<SaveFile>,
-    loaded_chunks: Arc<Mutex<HashMap<Vector2<i32>, Arc<ChunkData>>>>,
-    chunk_reader: Box<dyn ChunkReader>,
-    world_gen: Box<dyn WorldGenerator>,
+    save_file: SaveFile,
+    loaded_chunks: Arc<Mutex<HashMap<Vector2<i32>, Arc<RwLock<ChunkData>>>>>,
+    /// Chunks which were generated or changed since they were last saved
+    dirty_chunks: Mutex<HashSet<Vector2<i32>>>,
+    /// What keeps the loaded chunks loaded
+    tickets: Mutex<ChunkTickets>,
+    /// Encoded chunk data packets, shared by all clients the chunk is sent to
+    chunk_packets: Mutex<HashMap<Vector2<i32>, Vec<(u64, Arc<[u8]>)>>>,
+    /// Sections whose light changed by chunk, clients which already have the chunk need to be told
+    light_updates: Mutex<HashMap<Vector2<i32>, u32>>,
+    /// Whether the sky lights the world, false e.g. in the Nether
+    has_skylight: bool,
+    /// Chunks being loaded on the chunk workers, requesting them again waits for the same work
+    pending_chunks: Mutex<HashMap<Vector2<i32>, PendingChunk>>,
+    chunk_reader: Arc<dyn ChunkReader>,
+    chunk_writer: Arc<dyn ChunkWriter>,
+    world_gen: Box<dyn ChunkGenerator>,
 }
 
 pub struct SaveFile {
-    #[expect(dead_code)]
-    root_folder: PathBuf,
+    pub(crate) root_folder: PathBuf,
     pub region_folder: PathBuf,
 }
 
 impl Level {
-    pub fn from_root_folder(root_folder: PathBuf) -> Self {
-        let world_gen = get_world_gen(Seed(0)); // TODO Read Seed from config.
+    /// Loads the world in `root_folder`, new chunks get generated from `seed` with the `generator`
+    pub fn from_root_folder(
+        root_folder: PathBuf,
+        seed: Seed,
+        generator: GeneratorSettings,
+        has_skylight: bool,
+    ) -> Self {
+        let world_gen = get_world_gen(seed, generator);
 
         if root_folder.exists() {
-            let region_folder = root_folder.join("region");
-            assert!(
-                region_folder.exists(),
-                "World region folder does not exist, despite there being a root folder."
+            log::info!("Loading world from {}", root_folder.display());
+        } else {
+            log::info!(
+                "No world found in {}, generating a new one",
+                root_folder.display()
             );
+        }
+        let region_folder = root_folder.join("region");
+        // Reading and writing share the locks of the region files
+        let chunk_format = Arc::new(AnvilChunkFormat::new());
+
+        Self {
+            world_gen,
+            save_file: SaveFile {
+                root_folder,
+                region_folder,
+            },
+            chunk_reader: chunk_format.clone(),
+            chunk_writer: chunk_format,
+            loaded_chunks: Arc::new(Mutex::new(HashMap::new())),
+            dirty_chunks: Mutex::new(HashSet::new()),
+            tickets: Mutex::new(ChunkTickets::default()),
+            pending_chunks: Mutex::new(HashMap::new()),
+            chunk_packets: Mutex::new(HashMap::new()),
+            light_updates: Mutex::new(HashMap::new()),
+            has_skylight,
+        }
+    }
+
+    /// The chunk a block is in and its coordinates in there, None if it is outside the world
+    fn block_location(
+        position: &WorldPosition,
+    ) -> Option<(Vector2<i32>, ChunkRelativeBlockCoordinates)> {
+        let WorldPosition(position) = *position;
+        if !(WORLD_LOWEST_Y as i32..WORLD_MAX_Y as i32).contains(&position.y) {
+            return None;
+        }
+        let chunk = Vector2::new(position.x >> 4, position.z >> 4);
+        let relative = ChunkRelativeBlockCoordinates {
+            x: ((position.x & 15) as u8).into(),
+            y: Height::from(position.y),
+            z: ((position.z & 15) as u8).into(),
+        };
+        Some((chunk, relative))
+    }
+
+    /// Gets a block, None if its chunk is not loaded
+    pub fn get_block(&self, position: &WorldPosition) -> Option<BlockId> {
+        let (chunk, relative) = Self::block_location(position)?;
+        let chunk = self.loaded_chunks.lock().get(&chunk).cloned()?;
+        let block = chunk.read().blocks.get_block(relative);
+        Some(block)
+    }
+
+    /// The light at a block, the brighter one of its sky light and its block light.
+    /// None if its chunk is not loaded
+    pub fn get_light(&self, position: &WorldPosition) -> Option<u8> {
+        let (sky, block) = self.get_light_levels(position)?;
+        Some(sky.max(block))
+    }
+
+    /// The sky light and the block light at a block, None if its chunk is not loaded
+    pub fn get_light_levels(&self, position: &WorldPosition) -> Option<(u8, u8)> {
+        let (chunk, relative) = Self::block_location(position)?;
+        let chunk = self.loaded_chunks.lock().get(&chunk).cloned()?;
+        let chunk = chunk.read();
+        let index = ChunkBlocks::convert_index(relative);
+        let sky = chunk.light.sky.as_ref().map_or(0, |sky| sky.get(index));
+        Some((sky, chunk.light.block.get(index)))
+    }
+
+    /// Gets the biome at a block, None if its chunk is not loaded
+    pub fn get_biome(&self, position: &WorldPosition) -> Option<Biome> {
+        let (chunk, relative) = Self::block_location(position)?;
+        let chunk = self.loaded_chunks.lock().get(&chunk).cloned()?;
+        let biome = chunk.read().biomes.get_biome(relative);
+        Some(biome)
+    }
+
+    /// The y above the highest section of a loaded chunk which has blocks in it, no block of the
+    /// chunk is higher. None if the chunk is not loaded
+    pub fn top_y(&self, at: Vector2<i32>) -> Option<i32> {
+        let chunk = self.loaded_chunks.lock().get(&at).cloned()?;
+        let sections = chunk
+            .read()
+            .blocks
+            .sections()
+            .iter()
+            .rposition(|section| section.non_air_blocks() > 0)
+            .map_or(0, |index| index + 1);
+        Some(i32::from(WORLD_LOWEST_Y) + sections as i32 * 16)
+    }
 
-            Self {
-                world_gen,
-                save_file: Some(SaveFile {
-                    root_folder,
-                    region_folder,
-                }),
-                chunk_reader: Box::new(AnvilChunkReader::new()),
-                loaded_chunks: Arc::new(Mutex::new(HashMap::new())),
+    /// Picks `per_section` random blocks in every section of a loaded chunk, for random ticks.
+    /// Only the blocks which tick randomly are returned
+    pub fn random_tick_blocks(
+        &self,
+        at: Vector2<i32>,
+        per_section: u32,
+    ) -> Vec<(WorldPosition, BlockId)> {
+        let Some(chunk) = self.loaded_chunks.lock().get(&at).cloned() else {
+            return Vec::new();
+        };
+        let chunk = chunk.read();
+        let mut rng = rand::thread_rng();
+        let mut blocks = Vec::new();
+        for section in 0..WORLD_HEIGHT / 16 {
+            for _ in 0..per_section {
+                let index = section * SUBCHUNK_VOLUME + rng.gen_range(0..SUBCHUNK_VOLUME);
+                let block = chunk.blocks.block_at(index);
+                if block.ticks_randomly() {
+                    let position = Vector3::new(
+                        at.x * 16 + (index % 16) as i32,
+                        WORLD_LOWEST_Y as i32 + (index / 256) as i32,
+                        at.z * 16 + (index / 16 % 16) as i32,
+                    );
+                    blocks.push((WorldPosition(position), block));
+                }
             }
-        } else {
-            log::warn!(
-                "Pumpkin currently only supports Superflat World generation. Use a vanilla ./world folder to play in a normal world."
-            );
+        }
+        blocks
+    }
+
+    /// Sets a block and marks its chunk for saving, returning the old block.
+    /// Returns None if the chunk of the block is not loaded
+    pub fn set_block(&self, position: &WorldPosition, block: BlockId) -> Option<BlockId> {
+        let (at, relative) = Self::block_location(position)?;
+        let chunk = self.loaded_chunks.lock().get(&at).cloned()?;
+        let mut chunk = chunk.write();
+        let old_block = chunk.blocks.set_block(relative, block);
+        let WorldPosition(position) = *position;
+        let light_changed = changes_light(old_block, block);
+        update_block_entity(&mut chunk, position, block);
+        // Still holding the chunk, so nobody caches a packet of the old chunk afterwards
+        self.chunk_packets.lock().remove(&at);
+        drop(chunk);
+        if light_changed {
+            self.update_light(at, None, |view| view.update_block(position));
+        }
+        self.dirty_chunks.lock().insert(at);
+        Some(old_block)
+    }
+
+    /// Changes many blocks of a loaded chunk at once. `edit` gets the position and the old block
+    /// and returns the new block, or None to keep the old one. The light is updated once after all blocks changed.
+    /// Returns the blocks which changed with their new state, nothing if the chunk is not loaded
+    pub fn edit_blocks(
+        &self,
+        at: Vector2<i32>,
+        positions: impl IntoIterator<Item = Vector3<i32>>,
+        mut edit: impl FnMut(Vector3<i32>, BlockId) -> Option<BlockId>,
+    ) -> Vec<(Vector3<i32>, BlockId)> {
+        let Some(chunk) = self.loaded_chunks.lock().get(&at).cloned() else {
+            return Vec::new();
+        };
+        let mut chunk = chunk.write();
+        let mut changed = Vec::new();
+        let mut light_changed = Vec::new();
+        for position in positions {
+            let Some((chunk_at, relative)) = Self::block_location(&WorldPosition(position)) else {
+                continue;
+            };
+            if chunk_at != at {
+                continue;
+            }
+            let old_block = chunk.blocks.get_block(relative);
+            let Some(block) = edit(position, old_block).filter(|block| *block != old_block) else {
+                continue;
+            };
+            chunk.blocks.set_block(relative, block);
+            update_block_entity(&mut chunk, position, block);
+            if changes_light(old_block, block) {
+                light_changed.push(position);
+            }
+            changed.push((position, block));
+        }
+        if changed.is_empty() {
+            return changed;
+        }
+        self.chunk_packets.lock().remove(&at);
+        drop(chunk);
+        if !light_changed.is_empty() {
+            self.update_light(at, None, |view| {
+                for position in light_changed {
+                    view.update_block(position);
+                }
+            });
+        }
+        self.dirty_chunks.lock().insert(at);
+        changed
+    }
+
+    /// Gets the block entity of a block, None if the block has none or its chunk is not loaded
+    pub fn get_block_entity(&self, position: &WorldPosition) -> Option<BlockEntity> {
+        let (at, _) = Self::block_location(position)?;
+        let chunk = self.loaded_chunks.lock().get(&at).cloned()?;
+        let entity = chunk.read().block_entities.get(&position.0).cloned();
+        entity
+    }
+
+    /// The block entities of a loaded chunk which match `filter`
+    pub fn block_entities(
+        &self,
+        at: Vector2<i32>,
+        filter: impl Fn(&BlockEntity) -> bool,
+    ) -> Vec<BlockEntity> {
+        let Some(chunk) = self.loaded_chunks.lock().get(&at).cloned() else {
+            return Vec::new();
+        };
+        let chunk = chunk.read();
+        chunk
+            .block_entities
+            .values()
+            .filter(|entity| filter(entity))
+            .cloned()
+            .collect()
+    }
+
+    /// Replaces the block entity at its position and marks its chunk for saving.
+    /// Returns false if the chunk of the block entity is not loaded
+    pub fn set_block_entity(&self, entity: BlockEntity) -> bool {
+        let Some((at, _)) = Self::block_location(&WorldPosition(entity.position)) else {
+            return false;
+        };
+        let Some(chunk) = self.loaded_chunks.lock().get(&at).cloned() else {
+            return false;
+        };
+        let mut chunk = chunk.write();
+        chunk.block_entities.insert(entity.position, entity);
+        self.chunk_packets.lock().remove(&at);
+        drop(chunk);
+        self.dirty_chunks.lock().insert(at);
+        true
+    }
+
+    /// The encoded chunk data packet of a chunk, as it was sent to a client using `encoding`.
+    /// `encoding` stands for everything which changes the encoded packet, e.g. the packet id and compression
+    pub fn cached_chunk_packet(&self, at: Vector2<i32>, encoding: u64) -> Option<Arc<[u8]>> {
+        self.chunk_packets
+            .lock()
+            .get(&at)?
+            .iter()
+            .find(|(cached_encoding, _)| *cached_encoding == encoding)
+            .map(|(_, packet)| packet.clone())
+    }
+
+    /// Caches the encoded chunk data packet of a chunk until the chunk changes or gets unloaded.
+    /// Must be called while holding the read lock of the chunk the packet was encoded from
+    pub fn cache_chunk_packet(&self, at: Vector2<i32>, encoding: u64, packet: Arc<[u8]>) {
+        let mut chunk_packets = self.chunk_packets.lock();
+        let packets = chunk_packets.entry(at).or_default();
+        if !packets.iter().any(|(cached, _)| *cached == encoding) {
+            packets.push((encoding, packet));
+        }
+    }
+
+    /// Writes all chunks which changed since they were last saved to disk.
+    /// Returns how many chunks were saved, chunks which failed stay marked for the next save
+    pub fn save(&self) -> usize {
+        self.dirty_chunks()
+            .into_iter()
+            .filter(|at| self.save_chunk(*at))
+            .count()
+    }
+
+    /// The chunks which changed since they were last saved, `save_chunk` saves them
+    pub fn dirty_chunks(&self) -> Vec<Vector2<i32>> {
+        self.dirty_chunks.lock().iter().copied().collect()
+    }
+
+    /// Writes a single chunk to disk if it changed since it was last saved, returns whether it was written.
+    /// A chunk which failed gets marked for the next save again
+    pub fn save_chunk(&self, at: Vector2<i32>) -> bool {
+        // It may have been saved in the meantime, e.g. before it was unloaded
+        if !self.dirty_chunks.lock().remove(&at) {
+            return false;
+        }
+        let Some(chunk) = self.loaded_chunks.lock().get(&at).cloned() else {
+            return false;
+        };
+        let result = self
+            .chunk_writer
+            .write_chunk(&chunk.read(), &self.save_file, at);
+        if let Err(err) = result {
+            log::error!("Failed to save chunk {}, {}: {err}", at.x, at.z);
+            self.dirty_chunks.lock().insert(at);
+            return false;
+        }
+        true
+    }
+
+    /// Changes the light around a chunk, the loaded chunks next to it get locked for that.
+    /// Clients need the changed light unless they don't have the chunk yet, like the `loaded` chunk
+    fn update_light(
+        &self,
+        at: Vector2<i32>,
+        loaded: Option<Vector2<i32>>,
+        update: impl FnOnce(&mut LightView),
+    ) {
+        let mut around: Vec<_> = {
+            let loaded_chunks = self.loaded_chunks.lock();
+            (-1..=1)
+                .flat_map(|x| (-1..=1).map(move |z| Vector2::new(at.x + x, at.z + z)))
+                .filter_map(|pos| Some((pos, loaded_chunks.get(&pos)?.clone())))
+                .collect()
+        };
+        // Always locked in the same order, so updates next to each other don't deadlock
+        around.sort_by_key(|(pos, _)| (pos.x, pos.z));
+        let mut chunks: Vec<_> = around.iter().map(|(_, chunk)| chunk.write()).collect();
+        let mut view = LightView::new(chunks.iter_mut().map(|chunk| &mut **chunk));
+        update(&mut view);
 
-            Self {
-                world_gen,
-                save_file: None,
-                chunk_reader: Box::new(AnvilChunkReader::new()),
-                loaded_chunks: Arc::new(Mutex::new(HashMap::new())),
+        // Still holding the chunks, so nobody caches a packet with the old light afterwards
+        let mut chunk_packets = self.chunk_packets.lock();
+        let mut light_updates = self.light_updates.lock();
+        for (pos, sections) in view.into_changed() {
+            // A client may have gotten the chunk before it was lit, if its packet was cached already
+            let was_sent = chunk_packets.remove(&pos).is_some();
+            if Some(pos) != loaded || was_sent {
+                *light_updates.entry(pos).or_default() |= sections;
             }
         }
     }
 
-    pub fn get_block() {}
+    /// Takes the sections whose light changed since the last call by chunk,
+    /// bit i stands for section i of the world
+    pub fn take_light_updates(&self) -> Vec<(Vector2<i32>, u32)> {
+        self.light_updates.lock().drain().collect()
+    }
+
+    /// A chunk if it is loaded, without loading it
+    pub fn loaded_chunk(&self, at: Vector2<i32>) -> Option<Arc<RwLock<ChunkData>>> {
+        self.loaded_chunks.lock().get(&at).cloned()
+    }
+
+    /// The folder the world is stored in
+    pub fn root_folder(&self) -> &Path {
+        &self.save_file.root_folder
+    }
+
+    /// Reads the `level.dat` of the world, e.g. its time
+    pub fn read_level_data(&self) -> Result<LevelData, LevelDataError> {
+        LevelData::read(&self.save_file.root_folder)
+    }
+
+    pub fn write_level_data(&self, level_data: &LevelData) -> Result<(), LevelDataError> {
+        level_data.write(&self.save_file.root_folder)
+    }
+
+    /// The amount of chunks currently cached in memory
+    pub fn loaded_chunk_count(&self) -> usize {
+        self.loaded_chunks.lock().len()
+    }
+
+    /// Keeps a chunk loaded until the ticket is removed again.
+    /// Tickets are counted, so every added ticket has to be removed once
+    pub fn add_ticket(&self, at: Vector2<i32>, ticket: TicketType) {
+        self.tickets.lock().add(at, ticket);
+    }
+
+    pub fn remove_ticket(&self, at: Vector2<i32>, ticket: TicketType) {
+        self.tickets.lock().remove(at, ticket);
+    }
+
+    /// Force loads a chunk, returns false if it already was
+    pub fn force_chunk(&self, at: Vector2<i32>) -> bool {
+        let mut tickets = self.tickets.lock();
+        if tickets.count(at, TicketType::Forced) > 0 {
+            return false;
+        }
+        tickets.add(at, TicketType::Forced);
+        true
+    }
+
+    /// Stops force loading a chunk, returns false if it was not force loaded
+    pub fn unforce_chunk(&self, at: Vector2<i32>) -> bool {
+        let mut tickets = self.tickets.lock();
+        if tickets.count(at, TicketType::Forced) == 0 {
+            return false;
+        }
+        tickets.remove(at, TicketType::Forced);
+        true
+    }
+
+    /// The force loaded chunks
+    pub fn forced_chunks(&self) -> Vec<Vector2<i32>> {
+        self.tickets.lock().chunks_with(TicketType::Forced)
+    }
 
-    /// Reads/Generates many chunks in a world
+    /// Unloads all chunks without a ticket, returns how many were unloaded.
+    /// Changed chunks get saved first when `save` is set, otherwise they stay loaded until they were saved
+    pub fn unload_unused_chunks(&self, save: bool) -> usize {
+        let unused: Vec<_> = {
+            let tickets = self.tickets.lock();
+            self.loaded_chunks
+                .lock()
+                .keys()
+                .filter(|at| !tickets.is_needed(**at))
+                .copied()
+                .collect()
+        };
+        let mut unloaded = 0;
+        for at in unused {
+            let is_dirty = self.dirty_chunks.lock().contains(&at);
+            if is_dirty && !(save && self.save_chunk(at)) {
+                continue;
+            }
+            // It may have been changed or gotten a ticket while it was saved
+            let tickets = self.tickets.lock();
+            if tickets.is_needed(at) || self.dirty_chunks.lock().contains(&at) {
+                continue;
+            }
+            self.loaded_chunks.lock().remove(&at);
+            self.chunk_packets.lock().remove(&at);
+            unloaded += 1;
+        }
+        unloaded
+    }
+
+    fn generate_chunk(&self, at: Vector2<i32>) -> ChunkData {
+        let mut chunk = self.world_gen.generate_terrain(at);
+        self.world_gen.populate(&mut chunk);
+        chunk
+    }
+
+    /// Gets a loaded chunk, reading or generating it if it is not loaded yet.
+    /// Blocks until the chunk is ready, so it should not be called from async code directly
+    pub fn load_chunk(&self, at: Vector2<i32>) -> Arc<RwLock<ChunkData>> {
+        // Check if chunks is already loaded
+        let loaded = self.loaded_chunks.lock().get(&at).cloned();
+        if let Some(loaded) = loaded {
+            return loaded;
+        }
+        // Don't hold the lock while reading, so chunks can be read in parallel
+        let (data, generated) = match self.chunk_reader.read_chunk(&self.save_file, at) {
+            Ok(data) => (data, false),
+            Err(
+                ChunkReadingError::ChunkNotExist
+                | ChunkReadingError::ParsingError(ChunkParsingError::ChunkNotGenerated),
+            ) => {
+                // This chunk was not generated yet.
+                (self.generate_chunk(at), true)
+            }
+            Err(err) => {
+                log::error!(
+                    "Failed to read chunk {}, {}: {err}. Generating it instead",
+                    at.x,
+                    at.z
+                );
+                // Not saved unless it changes, so the broken chunk can still be recovered
+                (self.generate_chunk(at), false)
+            }
+        };
+        // Another thread may have loaded the same chunk in the meantime
+        let mut loaded_chunks = self.loaded_chunks.lock();
+        if let Some(loaded) = loaded_chunks.get(&at) {
+            return loaded.clone();
+        }
+        if generated {
+            // Saved as well, so the world stays the same if the generator changes
+            self.dirty_chunks.lock().insert(at);
+        }
+        let chunk = Arc::new(RwLock::new(data));
+        loaded_chunks.insert(at, chunk.clone());
+        drop(loaded_chunks);
+        let has_skylight = self.has_skylight;
+        self.update_light(at, Some(at), |view| view.light_chunk(at, has_skylight));
+        chunk
+    }
+
+    /// Gets a loaded chunk, reading or generating it on the chunk workers if it is not loaded yet.
+    /// The work starts right away, the returned future only waits for it. None if loading the chunk failed
+    pub fn get_chunk(
+        self: &Arc<Self>,
+        at: Vector2<i32>,
+    ) -> impl Future<Output = Option<Arc<RwLock<ChunkData>>>> + Send + 'static {
+        let mut pending_chunks = self.pending_chunks.lock();
+        // Checked while holding the pending chunks, a worker loads the chunk before it removes it from there
+        let loaded = self.loaded_chunks.lock().get(&at).cloned();
+        if let Some(loaded) = loaded {
+            return future::ready(Some(loaded)).left_future();
+        }
+        let pending = pending_chunks
+            .entry(at)
+            .or_insert_with(|| self.spawn_load(at))
+            .clone();
+        pending.map(Result::ok).right_future()
+    }
+
+    fn spawn_load(self: &Arc<Self>, at: Vector2<i32>) -> PendingChunk {
+        let (sender, receiver) = oneshot::channel();
+        let level = self.clone();
+        CHUNK_WORKERS.spawn(move || {
+            let chunk = level.load_chunk(at);
+            level.pending_chunks.lock().remove(&at);
+            // Nobody may be waiting anymore
+            let _ = sender.send(chunk);
+        });
+        receiver.shared()
+    }
+
+    /// Reads/Generates many chunks in a world, each chunk is sent as soon as it is ready.
     /// MUST be called from a tokio runtime thread
     ///
     /// Note: The order of the output chunks will almost never be in the same order as the order of input chunks
     pub fn fetch_chunks(
-        &self,
+        self: &Arc<Self>,
         chunks: &[Vector2<i32>],
-        channel: mpsc::Sender<Arc<ChunkData>>,
-        is_alive: bool,
+        channel: mpsc::Sender<Arc<RwLock<ChunkData>>>,
     ) {
-        chunks.into_par_iter().for_each(|at| {
-            if is_alive {
-                return;
-            }
-            let mut loaded_chunks = self.loaded_chunks.lock();
+        for &at in chunks {
+            let chunk = self.get_chunk(at);
             let channel = channel.clone();
-
-            // Check if chunks is already loaded
-            if loaded_chunks.contains_key(at) {
-                channel
-                    .blocking_send(loaded_chunks.get(at).unwrap().clone())
-                    .expect("Failed sending ChunkData.");
-                return;
-            }
-            let at = *at;
-            let data = match &self.save_file {
-                Some(save_file) => {
-                    match self.chunk_reader.read_chunk(save_file, at) {
-                        Err(ChunkReadingError::ChunkNotExist) => {
-                            // This chunk was not generated yet.
-                            Ok(self.world_gen.generate_chunk(at))
-                        }
-                        // TODO this doesn't warn the user about the error. fix.
-                        result => result,
+            tokio::spawn(async move {
+                match chunk.await {
+                    // The receiver may have stopped listening, e.g. because the player disconnected
+                    Some(chunk) => {
+                        let _ = channel.send(chunk).await;
                     }
+                    None => log::error!("Failed to load chunk {}, {}", at.x, at.z),
                 }
-                None => {
-                    // There is no savefile yet -> generate the chunks
-                    Ok(self.world_gen.generate_chunk(at))
-                }
+            });
+        }
+    }
+}
+
+fn changes_light(old: BlockId, new: BlockId) -> bool {
+    old.light_opacity() != new.light_opacity() || old.light_emission() != new.light_emission()
+}
+
+/// Gives a changed block its block entity or removes the one it had
+fn update_block_entity(chunk: &mut ChunkData, position: Vector3<i32>, block: BlockId) {
+    match BlockEntity::for_block(block, position) {
+        // Blocks keep their block entity when only their state changes, e.g. a chest turning
+        Some(entity) => {
+            if !matches!(chunk.block_entities.get(&position), Some(old) if old.id == entity.id) {
+                chunk.block_entities.insert(position, entity);
             }
-            .unwrap();
-            let data = Arc::new(data);
-            channel
-                .blocking_send(data.clone())
-                .expect("Failed sending ChunkData.");
-            loaded_chunks.insert(at, data);
-        })
+        }
+        None => {
+            chunk.block_entities.remove(&position);
+        }
     }
 }
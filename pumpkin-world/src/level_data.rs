@@ -0,0 +1,218 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+use fastnbt::Value;
+use flate2::read::GzDecoder;
+use thiserror::Error;
+
+use crate::{game_rules::GameRules, storage};
+
+const LEVEL_DATA_FILE: &str = "level.dat";
+const DATA_VERSION: i32 = 3955;
+/// The version of the `level.dat` format, the same for all versions since 1.13
+const LEVEL_DATA_VERSION: i32 = 19133;
+
+#[derive(Error, Debug)]
+pub enum LevelDataError {
+    #[error("Io error: {0}")]
+    IoError(std::io::ErrorKind),
+    #[error("Failed to parse the level data: {0}")]
+    ParsingError(fastnbt::error::Error),
+    #[error("The level data has no Data compound")]
+    MissingData,
+}
+
+/// The data of a world which doesn't belong to a chunk, stored in its `level.dat`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelData {
+    /// The total amount of ticks the world has been running
+    pub time: i64,
+    /// The time of day in ticks, not wrapped at 24000 so it also counts the days
+    pub day_time: i64,
+    pub raining: bool,
+    /// Ticks until it starts or stops raining
+    pub rain_time: i32,
+    pub thundering: bool,
+    /// Ticks until it starts or stops thundering
+    pub thunder_time: i32,
+    /// Ticks the weather stays clear, set by `/weather clear`
+    pub clear_weather_time: i32,
+    pub game_rules: GameRules,
+    /// The seed the world was generated with, None if it was never saved
+    pub seed: Option<i64>,
+    /// Everything else stored in the level data, kept as it is so worlds created by vanilla keep it
+    data: HashMap<String, Value>,
+}
+
+impl Default for LevelData {
+    fn default() -> Self {
+        Self {
+            time: 0,
+            day_time: 0,
+            raining: false,
+            rain_time: 0,
+            thundering: false,
+            thunder_time: 0,
+            clear_weather_time: 0,
+            game_rules: GameRules::default(),
+            seed: None,
+            data: HashMap::from([
+                ("DataVersion".to_string(), Value::Int(DATA_VERSION)),
+                ("version".to_string(), Value::Int(LEVEL_DATA_VERSION)),
+            ]),
+        }
+    }
+}
+
+impl LevelData {
+    /// Reads the level data of the world in `root_folder`, the default data if there is none yet
+    pub fn read(root_folder: &Path) -> Result<Self, LevelDataError> {
+        let file = match File::open(root_folder.join(LEVEL_DATA_FILE)) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(LevelDataError::IoError(err.kind())),
+        };
+        let mut bytes = Vec::new();
+        GzDecoder::new(BufReader::new(file))
+            .read_to_end(&mut bytes)
+            .map_err(|err| LevelDataError::IoError(err.kind()))?;
+        let root: HashMap<String, Value> =
+            fastnbt::from_bytes(&bytes).map_err(LevelDataError::ParsingError)?;
+        let Some(Value::Compound(mut data)) = root.get("Data").cloned() else {
+            return Err(LevelDataError::MissingData);
+        };
+
+        let mut long = |name: &str| match data.remove(name) {
+            Some(Value::Long(value)) => value,
+            _ => 0,
+        };
+        let time = long("Time");
+        let day_time = long("DayTime");
+        let mut int = |name: &str| match data.remove(name) {
+            Some(Value::Int(value)) => value,
+            _ => 0,
+        };
+        let rain_time = int("rainTime");
+        let thunder_time = int("thunderTime");
+        let clear_weather_time = int("clearWeatherTime");
+        let mut flag = |name: &str| matches!(data.remove(name), Some(Value::Byte(1)));
+        let raining = flag("raining");
+        let thundering = flag("thundering");
+        // Vanilla stores the values of all game rules as strings
+        let game_rules = match data.remove("GameRules") {
+            Some(Value::Compound(rules)) => GameRules::from_values(
+                rules
+                    .into_iter()
+                    .filter_map(|(name, value)| match value {
+                        Value::String(value) => Some((name, value)),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            _ => GameRules::default(),
+        };
+        // The rest of the generator settings stays as it is
+        let seed = match data.get_mut("WorldGenSettings") {
+            Some(Value::Compound(settings)) => match settings.remove("seed") {
+                Some(Value::Long(seed)) => Some(seed),
+                _ => None,
+            },
+            _ => None,
+        };
+        if matches!(data.get("WorldGenSettings"), Some(Value::Compound(settings)) if settings.is_empty())
+        {
+            data.remove("WorldGenSettings");
+        }
+        Ok(Self {
+            time,
+            day_time,
+            raining,
+            rain_time,
+            thundering,
+            thunder_time,
+            clear_weather_time,
+            game_rules,
+            seed,
+            data,
+        })
+    }
+
+    /// Writes the level data into `root_folder`, replacing the old one
+    pub fn write(&self, root_folder: &Path) -> Result<(), LevelDataError> {
+        let mut data = self.data.clone();
+        data.insert("Time".to_string(), Value::Long(self.time));
+        data.insert("DayTime".to_string(), Value::Long(self.day_time));
+        data.insert("raining".to_string(), Value::Byte(self.raining.into()));
+        data.insert("rainTime".to_string(), Value::Int(self.rain_time));
+        data.insert(
+            "thundering".to_string(),
+            Value::Byte(self.thundering.into()),
+        );
+        data.insert("thunderTime".to_string(), Value::Int(self.thunder_time));
+        data.insert(
+            "clearWeatherTime".to_string(),
+            Value::Int(self.clear_weather_time),
+        );
+        let game_rules = self
+            .game_rules
+            .values()
+            .into_iter()
+            .map(|(name, value)| (name, Value::String(value)))
+            .collect();
+        data.insert("GameRules".to_string(), Value::Compound(game_rules));
+        if let Some(seed) = self.seed {
+            let settings = data
+                .entry("WorldGenSettings".to_string())
+                .or_insert_with(|| Value::Compound(HashMap::new()));
+            if let Value::Compound(settings) = settings {
+                settings.insert("seed".to_string(), Value::Long(seed));
+            }
+        }
+        let root = HashMap::from([("Data".to_string(), Value::Compound(data))]);
+        let bytes = fastnbt::to_bytes(&root).map_err(LevelDataError::ParsingError)?;
+
+        let io_error = |err: std::io::Error| LevelDataError::IoError(err.kind());
+        let path = root_folder.join(LEVEL_DATA_FILE);
+        storage::write_atomically(&path, &storage::gzip(&bytes).map_err(io_error)?)
+            .map_err(io_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{game_rules::DO_DAYLIGHT_CYCLE, test_utils::TestFolder};
+
+    #[test]
+    fn round_trip() {
+        let folder = TestFolder::new("level-data");
+        let folder = folder.path();
+        let mut level_data = LevelData {
+            time: 123_456,
+            day_time: 30_000,
+            raining: true,
+            rain_time: 500,
+            seed: Some(-42),
+            ..Default::default()
+        };
+        level_data
+            .game_rules
+            .set(DO_DAYLIGHT_CYCLE, "false")
+            .unwrap();
+        level_data.write(folder).unwrap();
+
+        let read = LevelData::read(folder).unwrap();
+        assert!(!read.game_rules.get_bool(DO_DAYLIGHT_CYCLE));
+        // All game rules get written, not only the changed ones
+        assert_eq!(read.game_rules.values(), level_data.game_rules.values());
+        let read = LevelData {
+            game_rules: level_data.game_rules.clone(),
+            ..read
+        };
+        assert_eq!(read, level_data);
+    }
+}
@@ -1,13 +1,27 @@
+pub mod advancement;
 pub mod biome;
 pub mod block;
 pub mod chunk;
 pub mod coordinates;
 pub mod cylindrical_chunk_iterator;
+pub mod datapack;
 pub mod dimension;
+pub mod game_rules;
 pub mod global_registry;
 pub mod item;
 pub mod level;
-mod world_gen;
+pub mod level_data;
+pub mod lighting;
+pub mod player_data;
+pub mod recipe;
+pub mod schematic;
+pub mod scoreboard;
+pub mod stats;
+pub mod storage;
+#[cfg(test)]
+mod test_utils;
+pub mod ticket;
+pub mod world_gen;
 
 pub const WORLD_HEIGHT: usize = 384;
 pub const WORLD_LOWEST_Y: i16 = -64;
@@ -0,0 +1,340 @@
+use std::collections::{HashMap, VecDeque};
+
+use pumpkin_core::math::{vector2::Vector2, vector3::Vector3};
+
+use crate::{
+    block::{BlockId, MAX_LIGHT},
+    chunk::{light::LightArray, ChunkData},
+    WORLD_HEIGHT, WORLD_LOWEST_Y, WORLD_MAX_Y,
+};
+
+const SECTION_VOLUME: usize = 16 * 16 * 16;
+
+const DIRECTIONS: [Vector3<i32>; 6] = [
+    Vector3::new(0, -1, 0),
+    Vector3::new(0, 1, 0),
+    Vector3::new(-1, 0, 0),
+    Vector3::new(1, 0, 0),
+    Vector3::new(0, 0, -1),
+    Vector3::new(0, 0, 1),
+];
+
+/// Blocks queued for spreading light, with the light they had when they were queued
+type LightQueue = VecDeque<(Vector3<i32>, u8)>;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LightKind {
+    Sky,
+    Block,
+}
+
+/// The loaded chunks around a light change. Light spreads at most 15 blocks,
+/// so a change in one chunk only reaches the chunks right next to it
+pub struct LightView<'a> {
+    chunks: HashMap<Vector2<i32>, &'a mut ChunkData>,
+    /// The sections whose light changed by chunk, bit i stands for section i of the world
+    changed: HashMap<Vector2<i32>, u32>,
+}
+
+impl<'a> LightView<'a> {
+    pub fn new(chunks: impl IntoIterator<Item = &'a mut ChunkData>) -> Self {
+        Self {
+            chunks: chunks
+                .into_iter()
+                .map(|chunk| (chunk.position, chunk))
+                .collect(),
+            changed: HashMap::new(),
+        }
+    }
+
+    /// The sections whose light changed by chunk, bit i stands for section i of the world
+    pub fn into_changed(self) -> HashMap<Vector2<i32>, u32> {
+        self.changed
+    }
+
+    /// Calculates the light of a newly loaded chunk. The light of the loaded chunks around it
+    /// spreads into it, and its own light into them
+    pub fn light_chunk(&mut self, at: Vector2<i32>, has_skylight: bool) {
+        let Some(chunk) = self.chunks.get_mut(&at) else {
+            return;
+        };
+        if !has_skylight {
+            chunk.light.sky = None;
+        }
+        let mut kinds = vec![LightKind::Block];
+        if chunk.light.sky.is_some() {
+            kinds.push(LightKind::Sky);
+        }
+        let base = Vector3::new(at.x * 16, WORLD_LOWEST_Y as i32, at.z * 16);
+
+        for kind in kinds {
+            let mut queue = LightQueue::new();
+            match kind {
+                LightKind::Sky => {
+                    for (x, z) in (0..16).flat_map(|x| (0..16).map(move |z| (x, z))) {
+                        let position = Vector3::new(base.x + x, WORLD_MAX_Y as i32 - 1, base.z + z);
+                        self.add_source(kind, position, &mut queue);
+                    }
+                }
+                LightKind::Block => {
+                    let emitting: Vec<_> = {
+                        let chunk = &self.chunks[&at];
+                        (0..WORLD_HEIGHT * 256)
+                            .filter(|index| chunk.blocks.block_at(*index).light_emission() > 0)
+                            .collect()
+                    };
+                    for index in emitting {
+                        let position = Vector3::new(
+                            base.x + (index % 16) as i32,
+                            base.y + (index / 256) as i32,
+                            base.z + (index / 16 % 16) as i32,
+                        );
+                        self.add_source(kind, position, &mut queue);
+                    }
+                }
+            }
+            // The light at the borders of the neighbors spreads into the chunk
+            for (dx, dz) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                if !self
+                    .chunks
+                    .contains_key(&Vector2::new(at.x + dx, at.z + dz))
+                {
+                    continue;
+                }
+                for y in WORLD_LOWEST_Y as i32..WORLD_MAX_Y as i32 {
+                    for i in 0..16 {
+                        let position = match (dx, dz) {
+                            (-1, _) => Vector3::new(base.x - 1, y, base.z + i),
+                            (1, _) => Vector3::new(base.x + 16, y, base.z + i),
+                            (_, -1) => Vector3::new(base.x + i, y, base.z - 1),
+                            _ => Vector3::new(base.x + i, y, base.z + 16),
+                        };
+                        if let Some(level) = self.light(kind, position).filter(|level| *level > 1) {
+                            queue.push_back((position, level));
+                        }
+                    }
+                }
+            }
+            self.spread(kind, &mut queue);
+        }
+    }
+
+    /// Updates the light after the block at `position` changed
+    pub fn update_block(&mut self, position: Vector3<i32>) {
+        for kind in [LightKind::Sky, LightKind::Block] {
+            let Some(old) = self.light(kind, position) else {
+                continue;
+            };
+            let mut darken = LightQueue::new();
+            let mut relight = LightQueue::new();
+            if old > 0 {
+                self.set_light(kind, position, 0);
+                darken.push_back((position, old));
+            }
+            self.add_source(kind, position, &mut relight);
+            // The neighbors light the block again, as far as it lets light through
+            for direction in DIRECTIONS {
+                let neighbor = position.add(&direction);
+                if let Some(level) = self.light(kind, neighbor).filter(|level| *level > 0) {
+                    relight.push_back((neighbor, level));
+                }
+            }
+            self.darken(kind, &mut darken, &mut relight);
+            self.spread(kind, &mut relight);
+        }
+    }
+
+    /// The chunk of a block and the index of the block in there, None if it is outside the world
+    fn locate(position: Vector3<i32>) -> Option<(Vector2<i32>, usize)> {
+        if !(WORLD_LOWEST_Y as i32..WORLD_MAX_Y as i32).contains(&position.y) {
+            return None;
+        }
+        let at = Vector2::new(position.x >> 4, position.z >> 4);
+        let index = (position.y - WORLD_LOWEST_Y as i32) as usize * 256
+            + (position.z & 15) as usize * 16
+            + (position.x & 15) as usize;
+        Some((at, index))
+    }
+
+    fn array(chunk: &ChunkData, kind: LightKind) -> Option<&LightArray> {
+        match kind {
+            LightKind::Sky => chunk.light.sky.as_ref(),
+            LightKind::Block => Some(&chunk.light.block),
+        }
+    }
+
+    /// None if the block is outside the world or the loaded chunks
+    fn block(&self, position: Vector3<i32>) -> Option<BlockId> {
+        let (at, index) = Self::locate(position)?;
+        Some(self.chunks.get(&at)?.blocks.block_at(index))
+    }
+
+    /// None if the block is outside the world or the loaded chunks
+    fn light(&self, kind: LightKind, position: Vector3<i32>) -> Option<u8> {
+        let (at, index) = Self::locate(position)?;
+        Some(Self::array(self.chunks.get(&at)?, kind)?.get(index))
+    }
+
+    fn set_light(&mut self, kind: LightKind, position: Vector3<i32>, level: u8) {
+        let Some((at, index)) = Self::locate(position) else {
+            return;
+        };
+        let Some(chunk) = self.chunks.get_mut(&at) else {
+            return;
+        };
+        let array = match kind {
+            LightKind::Sky => chunk.light.sky.as_mut(),
+            LightKind::Block => Some(&mut chunk.light.block),
+        };
+        if let Some(array) = array {
+            array.set(index, level);
+            *self.changed.entry(at).or_default() |= 1 << (index / SECTION_VOLUME);
+        }
+    }
+
+    /// The light a block gets by itself, sky light comes in from above the world
+    fn source(kind: LightKind, position: Vector3<i32>, block: BlockId) -> u8 {
+        match kind {
+            LightKind::Block => block.light_emission(),
+            LightKind::Sky if position.y == WORLD_MAX_Y as i32 - 1 => {
+                MAX_LIGHT.saturating_sub(block.light_opacity())
+            }
+            LightKind::Sky => 0,
+        }
+    }
+
+    /// Lights a block with its own light and queues it for spreading, if it is brighter than before
+    fn add_source(&mut self, kind: LightKind, position: Vector3<i32>, queue: &mut LightQueue) {
+        let Some(block) = self.block(position) else {
+            return;
+        };
+        let source = Self::source(kind, position, block);
+        if source > self.light(kind, position).unwrap_or(MAX_LIGHT) {
+            self.set_light(kind, position, source);
+            queue.push_back((position, source));
+        }
+    }
+
+    /// Spreads the light of the queued blocks to their neighbors
+    fn spread(&mut self, kind: LightKind, queue: &mut LightQueue) {
+        while let Some((position, level)) = queue.pop_front() {
+            // The block got darker after it was queued, its light spreads from elsewhere
+            if self.light(kind, position) != Some(level) || level <= 1 {
+                continue;
+            }
+            for direction in DIRECTIONS {
+                let neighbor = position.add(&direction);
+                let Some(block) = self.block(neighbor) else {
+                    continue;
+                };
+                let opacity = block.light_opacity();
+                // Sky light goes straight down without getting darker
+                let new = if kind == LightKind::Sky
+                    && direction.y == -1
+                    && level == MAX_LIGHT
+                    && opacity == 0
+                {
+                    MAX_LIGHT
+                } else {
+                    level.saturating_sub(opacity.max(1))
+                };
+                if new > self.light(kind, neighbor).unwrap_or(MAX_LIGHT) {
+                    self.set_light(kind, neighbor, new);
+                    queue.push_back((neighbor, new));
+                }
+            }
+        }
+    }
+
+    /// Removes the light the queued blocks gave to the blocks around them. Blocks lit from elsewhere
+    /// and light sources end up in `relight`, so their light fills the darkened blocks again
+    fn darken(&mut self, kind: LightKind, queue: &mut LightQueue, relight: &mut LightQueue) {
+        while let Some((position, level)) = queue.pop_front() {
+            for direction in DIRECTIONS {
+                let neighbor = position.add(&direction);
+                let Some(neighbor_level) = self.light(kind, neighbor).filter(|level| *level > 0)
+                else {
+                    continue;
+                };
+                let lit_from_here =
+                    if kind == LightKind::Sky && direction.y == -1 && level == MAX_LIGHT {
+                        neighbor_level == MAX_LIGHT
+                    } else {
+                        neighbor_level < level
+                    };
+                if lit_from_here {
+                    self.set_light(kind, neighbor, 0);
+                    queue.push_back((neighbor, neighbor_level));
+                    self.add_source(kind, neighbor, relight);
+                } else {
+                    relight.push_back((neighbor, neighbor_level));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chunk::{ChunkBiomes, ChunkBlocks, ChunkLight},
+        coordinates::{ChunkRelativeBlockCoordinates, Height},
+        test_utils::block,
+    };
+
+    fn set_block(chunk: &mut ChunkData, x: u8, y: i32, z: u8, block: BlockId) {
+        chunk.blocks.set_block(
+            ChunkRelativeBlockCoordinates {
+                x: x.into(),
+                y: Height::from(y),
+                z: z.into(),
+            },
+            block,
+        );
+    }
+
+    fn chunk() -> ChunkData {
+        ChunkData {
+            blocks: ChunkBlocks::default(),
+            biomes: ChunkBiomes::default(),
+            position: Vector2::new(0, 0),
+            block_entities: HashMap::new(),
+            light: ChunkLight::default(),
+        }
+    }
+
+    #[test]
+    fn torch_light_fades() {
+        let mut chunk = chunk();
+        // A roof, so the sky doesn't light everything
+        for (x, z) in (0..16).flat_map(|x| (0..16).map(move |z| (x, z))) {
+            set_block(&mut chunk, x, 100, z, block("minecraft:stone"));
+        }
+        set_block(&mut chunk, 8, 64, 8, block("minecraft:torch"));
+        let mut view = LightView::new([&mut chunk]);
+        view.light_chunk(Vector2::new(0, 0), true);
+        assert_eq!(
+            view.light(LightKind::Block, Vector3::new(8, 64, 8)),
+            Some(14)
+        );
+        assert_eq!(
+            view.light(LightKind::Block, Vector3::new(8, 66, 8)),
+            Some(12)
+        );
+        assert_eq!(
+            view.light(LightKind::Sky, Vector3::new(8, 101, 8)),
+            Some(15)
+        );
+        assert_eq!(view.light(LightKind::Sky, Vector3::new(8, 99, 8)), Some(0));
+
+        // Removing the torch takes its light away again
+        set_block(&mut chunk, 8, 64, 8, BlockId::default());
+        let mut view = LightView::new([&mut chunk]);
+        view.update_block(Vector3::new(8, 64, 8));
+        assert_eq!(
+            view.light(LightKind::Block, Vector3::new(8, 66, 8)),
+            Some(0)
+        );
+    }
+}
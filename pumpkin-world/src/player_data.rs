@@ -0,0 +1,440 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+use fastnbt::Value;
+use flate2::read::GzDecoder;
+use pumpkin_core::{math::vector3::Vector3, GameMode};
+use thiserror::Error;
+
+use crate::{
+    global_registry::{find_minecraft_id, find_protocol_id, ITEM_REGISTRY},
+    item::ItemStack,
+    storage,
+};
+
+/// The folder inside of the main world which holds a file for every player, like in vanilla
+const PLAYER_DATA_FOLDER: &str = "playerdata";
+const DATA_VERSION: i32 = 3955;
+
+#[derive(Error, Debug)]
+pub enum PlayerDataError {
+    #[error("Io error: {0}")]
+    IoError(std::io::ErrorKind),
+    #[error("Failed to parse the player data: {0}")]
+    ParsingError(fastnbt::error::Error),
+}
+
+/// Where a player respawns after dying, set by sleeping in a bed or charging a respawn anchor
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpawnPoint {
+    /// The bed or respawn anchor, or the position itself if `forced`
+    pub position: Vector3<i32>,
+    /// The dimension it is in, e.g. `minecraft:overworld`
+    pub dimension: String,
+    /// The name of the world it is in, there may be several worlds of a dimension
+    pub world: Option<String>,
+    /// The yaw the player respawns with
+    pub angle: f32,
+    /// Whether the player respawns there even without a bed or respawn anchor
+    pub forced: bool,
+}
+
+/// What is kept of a player while they are offline, stored in `playerdata/<uuid>.dat`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerData {
+    pub position: Vector3<f64>,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub health: f32,
+    /// The extra health which is lost first
+    pub absorption: f32,
+    pub food: i32,
+    pub food_saturation: f32,
+    pub food_exhaustion: f32,
+    pub experience_level: i32,
+    /// The progress towards the next level, from 0 to 1
+    pub experience_progress: f32,
+    pub total_experience: i32,
+    /// Decides which enchantments the enchanting table offers, it changes after enchanting
+    pub enchantment_seed: i32,
+    pub game_mode: GameMode,
+    /// The dimension the player is in, e.g. `minecraft:the_nether`
+    pub dimension: String,
+    /// The name of the world the player is in, there may be several worlds of a dimension
+    pub world: Option<String>,
+    /// The selected hotbar slot, from 0 to 8
+    pub selected_slot: i32,
+    /// The items with their vanilla slot: 0-8 the hotbar, 9-35 the inventory, 100-103 the armor
+    /// from the boots to the helmet and -106 the offhand
+    pub inventory: Vec<(i8, ItemStack)>,
+    /// Where the player respawns, None for the spawn of the world
+    pub spawn_point: Option<SpawnPoint>,
+    /// The base values of the attributes by name, e.g. `minecraft:generic.max_health`
+    pub attribute_bases: Vec<(String, f64)>,
+    /// Everything else stored in the player data, kept as it is so players saved by vanilla keep it
+    data: HashMap<String, Value>,
+}
+
+impl Default for PlayerData {
+    fn default() -> Self {
+        Self {
+            position: Vector3::new(0.0, 0.0, 0.0),
+            yaw: 0.0,
+            pitch: 0.0,
+            health: 20.0,
+            absorption: 0.0,
+            food: 20,
+            food_saturation: 5.0,
+            food_exhaustion: 0.0,
+            experience_level: 0,
+            experience_progress: 0.0,
+            total_experience: 0,
+            enchantment_seed: 0,
+            game_mode: GameMode::Survival,
+            dimension: "minecraft:overworld".to_string(),
+            world: None,
+            selected_slot: 0,
+            inventory: Vec::new(),
+            spawn_point: None,
+            attribute_bases: Vec::new(),
+            data: HashMap::from([("DataVersion".to_string(), Value::Int(DATA_VERSION))]),
+        }
+    }
+}
+
+impl PlayerData {
+    /// Reads the data of a player from the main world in `root_folder`, None if they never
+    /// played before
+    pub fn read(root_folder: &Path, uuid: &str) -> Result<Option<Self>, PlayerDataError> {
+        let file = match File::open(player_file(root_folder, uuid)) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(PlayerDataError::IoError(err.kind())),
+        };
+        let mut bytes = Vec::new();
+        GzDecoder::new(BufReader::new(file))
+            .read_to_end(&mut bytes)
+            .map_err(|err| PlayerDataError::IoError(err.kind()))?;
+        let mut data: HashMap<String, Value> =
+            fastnbt::from_bytes(&bytes).map_err(PlayerDataError::ParsingError)?;
+
+        let default = Self::default();
+        let position = match data.remove("Pos") {
+            Some(Value::List(values)) => match values.as_slice() {
+                [Value::Double(x), Value::Double(y), Value::Double(z)] => Vector3::new(*x, *y, *z),
+                _ => default.position,
+            },
+            _ => default.position,
+        };
+        let (yaw, pitch) = match data.remove("Rotation") {
+            Some(Value::List(values)) => match values.as_slice() {
+                [Value::Float(yaw), Value::Float(pitch)] => (*yaw, *pitch),
+                _ => (default.yaw, default.pitch),
+            },
+            _ => (default.yaw, default.pitch),
+        };
+        let mut float = |name: &str, default: f32| match data.remove(name) {
+            Some(Value::Float(value)) => value,
+            _ => default,
+        };
+        let health = float("Health", default.health);
+        let absorption = float("AbsorptionAmount", default.absorption);
+        let food_saturation = float("foodSaturationLevel", default.food_saturation);
+        let food_exhaustion = float("foodExhaustionLevel", default.food_exhaustion);
+        let experience_progress = float("XpP", default.experience_progress);
+        let mut int = |name: &str, default: i32| match data.remove(name) {
+            Some(Value::Int(value)) => value,
+            _ => default,
+        };
+        let food = int("foodLevel", default.food);
+        let experience_level = int("XpLevel", default.experience_level);
+        let total_experience = int("XpTotal", default.total_experience);
+        let enchantment_seed = int("XpSeed", default.enchantment_seed);
+        let selected_slot = int("SelectedItemSlot", default.selected_slot).clamp(0, 8);
+        let game_mode = match int("playerGameType", default.game_mode as i32) {
+            1 => GameMode::Creative,
+            2 => GameMode::Adventure,
+            3 => GameMode::Spectator,
+            _ => GameMode::Survival,
+        };
+        let dimension = match data.remove("Dimension") {
+            Some(Value::String(dimension)) => dimension,
+            _ => default.dimension,
+        };
+        let world = match data.remove("PumpkinWorld") {
+            Some(Value::String(world)) => Some(world),
+            _ => None,
+        };
+        let inventory = match data.remove("Inventory") {
+            Some(Value::List(items)) => items.iter().filter_map(read_item).collect(),
+            _ => Vec::new(),
+        };
+        let spawn_point = read_spawn_point(&mut data);
+        let attribute_bases = match data.remove("attributes") {
+            Some(Value::List(attributes)) => attributes.iter().filter_map(read_attribute).collect(),
+            _ => Vec::new(),
+        };
+        Ok(Some(Self {
+            position,
+            yaw,
+            pitch,
+            health,
+            absorption,
+            food,
+            food_saturation,
+            food_exhaustion,
+            experience_level,
+            experience_progress,
+            total_experience,
+            enchantment_seed,
+            game_mode,
+            dimension,
+            world,
+            selected_slot,
+            inventory,
+            spawn_point,
+            attribute_bases,
+            data,
+        }))
+    }
+
+    /// Writes the data of a player into the main world in `root_folder`, replacing the old one
+    pub fn write(&self, root_folder: &Path, uuid: &str) -> Result<(), PlayerDataError> {
+        let mut data = self.data.clone();
+        data.insert(
+            "Pos".to_string(),
+            Value::List(vec![
+                Value::Double(self.position.x),
+                Value::Double(self.position.y),
+                Value::Double(self.position.z),
+            ]),
+        );
+        data.insert(
+            "Rotation".to_string(),
+            Value::List(vec![Value::Float(self.yaw), Value::Float(self.pitch)]),
+        );
+        data.insert("Health".to_string(), Value::Float(self.health));
+        data.insert(
+            "AbsorptionAmount".to_string(),
+            Value::Float(self.absorption),
+        );
+        data.insert("foodLevel".to_string(), Value::Int(self.food));
+        data.insert(
+            "foodSaturationLevel".to_string(),
+            Value::Float(self.food_saturation),
+        );
+        data.insert(
+            "foodExhaustionLevel".to_string(),
+            Value::Float(self.food_exhaustion),
+        );
+        data.insert("XpLevel".to_string(), Value::Int(self.experience_level));
+        data.insert("XpP".to_string(), Value::Float(self.experience_progress));
+        data.insert("XpTotal".to_string(), Value::Int(self.total_experience));
+        data.insert("XpSeed".to_string(), Value::Int(self.enchantment_seed));
+        data.insert(
+            "playerGameType".to_string(),
+            Value::Int(self.game_mode as i32),
+        );
+        data.insert(
+            "Dimension".to_string(),
+            Value::String(self.dimension.clone()),
+        );
+        if let Some(world) = &self.world {
+            data.insert("PumpkinWorld".to_string(), Value::String(world.clone()));
+        }
+        data.insert(
+            "SelectedItemSlot".to_string(),
+            Value::Int(self.selected_slot),
+        );
+        let items = self
+            .inventory
+            .iter()
+            .filter_map(|(slot, item)| write_item(*slot, item))
+            .collect();
+        data.insert("Inventory".to_string(), Value::List(items));
+        if let Some(spawn_point) = &self.spawn_point {
+            write_spawn_point(&mut data, spawn_point);
+        }
+        let attributes = self
+            .attribute_bases
+            .iter()
+            .map(|(id, base)| {
+                Value::Compound(HashMap::from([
+                    ("id".to_string(), Value::String(id.clone())),
+                    ("base".to_string(), Value::Double(*base)),
+                ]))
+            })
+            .collect();
+        data.insert("attributes".to_string(), Value::List(attributes));
+        let bytes = fastnbt::to_bytes(&data).map_err(PlayerDataError::ParsingError)?;
+
+        let io_error = |err: std::io::Error| PlayerDataError::IoError(err.kind());
+        storage::write_atomically(
+            &player_file(root_folder, uuid),
+            &storage::gzip(&bytes).map_err(io_error)?,
+        )
+        .map_err(io_error)
+    }
+}
+
+fn player_file(root_folder: &Path, uuid: &str) -> PathBuf {
+    root_folder
+        .join(PLAYER_DATA_FOLDER)
+        .join(format!("{uuid}.dat"))
+}
+
+/// The spawn point as vanilla stores it, None if the player has none
+fn read_spawn_point(data: &mut HashMap<String, Value>) -> Option<SpawnPoint> {
+    let mut int = |name: &str| match data.remove(name) {
+        Some(Value::Int(value)) => Some(value),
+        _ => None,
+    };
+    let (x, y, z) = (int("SpawnX"), int("SpawnY"), int("SpawnZ"));
+    let angle = match data.remove("SpawnAngle") {
+        Some(Value::Float(angle)) => angle,
+        _ => 0.0,
+    };
+    let forced = matches!(data.remove("SpawnForced"), Some(Value::Byte(1)));
+    let dimension = match data.remove("SpawnDimension") {
+        Some(Value::String(dimension)) => dimension,
+        _ => "minecraft:overworld".to_string(),
+    };
+    let world = match data.remove("PumpkinSpawnWorld") {
+        Some(Value::String(world)) => Some(world),
+        _ => None,
+    };
+    Some(SpawnPoint {
+        position: Vector3::new(x?, y?, z?),
+        dimension,
+        world,
+        angle,
+        forced,
+    })
+}
+
+fn write_spawn_point(data: &mut HashMap<String, Value>, spawn_point: &SpawnPoint) {
+    data.insert("SpawnX".to_string(), Value::Int(spawn_point.position.x));
+    data.insert("SpawnY".to_string(), Value::Int(spawn_point.position.y));
+    data.insert("SpawnZ".to_string(), Value::Int(spawn_point.position.z));
+    data.insert("SpawnAngle".to_string(), Value::Float(spawn_point.angle));
+    data.insert(
+        "SpawnForced".to_string(),
+        Value::Byte(spawn_point.forced.into()),
+    );
+    data.insert(
+        "SpawnDimension".to_string(),
+        Value::String(spawn_point.dimension.clone()),
+    );
+    if let Some(world) = &spawn_point.world {
+        data.insert(
+            "PumpkinSpawnWorld".to_string(),
+            Value::String(world.clone()),
+        );
+    }
+}
+
+/// The name and base value of an entry of the attribute list, the modifiers are not kept
+fn read_attribute(value: &Value) -> Option<(String, f64)> {
+    let Value::Compound(attribute) = value else {
+        return None;
+    };
+    match (attribute.get("id"), attribute.get("base")) {
+        (Some(Value::String(id)), Some(Value::Double(base))) => Some((id.clone(), *base)),
+        _ => None,
+    }
+}
+
+/// An item of the inventory list, None for unknown items
+fn read_item(value: &Value) -> Option<(i8, ItemStack)> {
+    let Value::Compound(item) = value else {
+        return None;
+    };
+    let Some(Value::Byte(slot)) = item.get("Slot") else {
+        return None;
+    };
+    let Some(Value::String(id)) = item.get("id") else {
+        return None;
+    };
+    let Some(item_id) = find_protocol_id(ITEM_REGISTRY, id) else {
+        log::warn!("Dropping unknown item {id} from the player data");
+        return None;
+    };
+    // Vanilla stores the count as a byte before 1.20.5
+    let count = match item.get("count").or_else(|| item.get("Count")) {
+        Some(Value::Int(count)) => *count,
+        Some(Value::Byte(count)) => i32::from(*count),
+        _ => 1,
+    };
+    let mut stack = ItemStack::new(count.clamp(1, u8::MAX.into()) as u8, item_id);
+    stack.read_components(item);
+    Some((*slot, stack))
+}
+
+fn write_item(slot: i8, item: &ItemStack) -> Option<Value> {
+    let id = find_minecraft_id(ITEM_REGISTRY, item.item_id)?;
+    let mut nbt = HashMap::from([
+        ("Slot".to_string(), Value::Byte(slot)),
+        ("id".to_string(), Value::String(id.to_string())),
+        ("count".to_string(), Value::Int(item.item_count.into())),
+    ]);
+    item.write_components(&mut nbt);
+    Some(Value::Compound(nbt))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        item::Enchantment,
+        test_utils::{TestFolder, UUID},
+    };
+
+    #[test]
+    fn round_trip() {
+        let folder = TestFolder::new("player-data");
+        let folder = folder.path();
+        let stone = find_protocol_id(ITEM_REGISTRY, "minecraft:stone").unwrap();
+        let mut sword = ItemStack::new(
+            1,
+            find_protocol_id(ITEM_REGISTRY, "minecraft:diamond_sword").unwrap(),
+        );
+        sword.enchantments.set(Enchantment::Sharpness, 5);
+        let player_data = PlayerData {
+            position: Vector3::new(12.5, 70.0, -3.25),
+            yaw: 90.0,
+            pitch: -15.0,
+            health: 7.5,
+            food: 12,
+            experience_level: 4,
+            experience_progress: 0.5,
+            total_experience: 30,
+            enchantment_seed: -1234,
+            game_mode: GameMode::Creative,
+            dimension: "minecraft:the_nether".to_string(),
+            world: Some("world_nether".to_string()),
+            selected_slot: 3,
+            inventory: vec![(-106, ItemStack::new(64, stone)), (0, sword)],
+            spawn_point: Some(SpawnPoint {
+                position: Vector3::new(-40, 64, 18),
+                dimension: "minecraft:the_nether".to_string(),
+                world: Some("world_nether".to_string()),
+                angle: 180.0,
+                forced: false,
+            }),
+            attribute_bases: vec![("minecraft:generic.max_health".to_string(), 30.0)],
+            ..Default::default()
+        };
+        player_data.write(folder, UUID).unwrap();
+
+        let read = PlayerData::read(folder, UUID).unwrap();
+        assert!(PlayerData::read(folder, "unknown").unwrap().is_none());
+        let read = read.unwrap();
+        // ItemStack only compares the item, not the count
+        assert_eq!(read.inventory[0].1.item_count, 64);
+        assert_eq!(read, player_data);
+    }
+}
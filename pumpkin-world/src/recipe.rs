@@ -0,0 +1,467 @@
+use std::{collections::HashMap, path::Path};
+
+use serde::Deserialize;
+
+use crate::datapack;
+
+/// The vanilla recipes Pumpkin ships with, by their id. Their item tags are replaced by the
+/// items in them
+const RECIPES_JSON: &str = include_str!("../../assets/recipes.json");
+
+/// A recipe as vanilla stores it in `data/<namespace>/recipe/<path>.json`
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "type")]
+pub enum Recipe {
+    #[serde(rename = "minecraft:crafting_shaped")]
+    Shaped(ShapedRecipe),
+    #[serde(rename = "minecraft:crafting_shapeless")]
+    Shapeless(ShapelessRecipe),
+    #[serde(rename = "minecraft:smelting")]
+    Smelting(CookingRecipe),
+    #[serde(rename = "minecraft:blasting")]
+    Blasting(CookingRecipe),
+    #[serde(rename = "minecraft:smoking")]
+    Smoking(CookingRecipe),
+    /// Recipes Pumpkin can't use yet, e.g. the special crafting recipes
+    #[serde(other)]
+    Unsupported,
+}
+
+/// The tab of the recipe book a crafting recipe is shown in, in the order of the protocol
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CraftingCategory {
+    Building,
+    Redstone,
+    Equipment,
+    #[default]
+    Misc,
+}
+
+/// The tab of the recipe book of furnaces a cooking recipe is shown in, in the order of the
+/// protocol
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CookingCategory {
+    Food,
+    Blocks,
+    #[default]
+    Misc,
+}
+
+/// A recipe whose ingredients have to be arranged like in its pattern
+#[derive(Deserialize, Clone, Debug)]
+pub struct ShapedRecipe {
+    /// Recipes of the same group are shown together in the recipe book
+    #[serde(default)]
+    pub group: String,
+    #[serde(default)]
+    pub category: CraftingCategory,
+    /// The ingredient of every character of the pattern
+    pub key: HashMap<char, Ingredient>,
+    /// The rows of the pattern, spaces are empty slots
+    pub pattern: Vec<String>,
+    pub result: RecipeResult,
+    /// Whether the client shows a toast once the recipe is unlocked
+    #[serde(default = "default_true")]
+    pub show_notification: bool,
+}
+
+/// A recipe whose ingredients may be anywhere in the grid
+#[derive(Deserialize, Clone, Debug)]
+pub struct ShapelessRecipe {
+    #[serde(default)]
+    pub group: String,
+    #[serde(default)]
+    pub category: CraftingCategory,
+    pub ingredients: Vec<Ingredient>,
+    pub result: RecipeResult,
+}
+
+/// A recipe of a furnace, blast furnace or smoker which turns one item into its result
+#[derive(Deserialize, Clone, Debug)]
+pub struct CookingRecipe {
+    #[serde(default)]
+    pub group: String,
+    #[serde(default)]
+    pub category: CookingCategory,
+    pub ingredient: Ingredient,
+    pub result: RecipeResult,
+    /// The experience the player gets for every cooked item
+    #[serde(default)]
+    pub experience: f32,
+    /// How many ticks cooking one item takes, None for the default of the recipe type
+    #[serde(rename = "cookingtime")]
+    pub cooking_time: Option<i32>,
+}
+
+/// What may go into one slot of a recipe
+#[derive(Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum Ingredient {
+    Item {
+        item: String,
+    },
+    Tag {
+        tag: String,
+    },
+    /// Any of several items or tags
+    AnyOf(Vec<Ingredient>),
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct RecipeResult {
+    pub id: String,
+    #[serde(default = "default_count")]
+    pub count: u8,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_count() -> u8 {
+    1
+}
+
+impl Recipe {
+    /// Whether the items in a crafting grid of `width` make this recipe, the grid goes row by
+    /// row and has the id of every item
+    pub fn matches(&self, grid: &[Option<&str>], width: usize) -> bool {
+        match self {
+            Self::Shaped(recipe) => recipe.matches(grid, width),
+            Self::Shapeless(recipe) => recipe.matches(grid),
+            _ => false,
+        }
+    }
+
+    /// Whether the recipe can be made in a crafting grid of `width`, the inventory has a 2x2 grid
+    pub fn fits(&self, width: usize) -> bool {
+        match self {
+            Self::Shaped(recipe) => {
+                let (_, _, recipe_width, height) = recipe.bounds();
+                recipe_width <= width && height <= width
+            }
+            Self::Shapeless(recipe) => recipe.ingredients.len() <= width * width,
+            _ => false,
+        }
+    }
+
+    pub fn result(&self) -> Option<&RecipeResult> {
+        match self {
+            Self::Shaped(recipe) => Some(&recipe.result),
+            Self::Shapeless(recipe) => Some(&recipe.result),
+            Self::Smelting(recipe) | Self::Blasting(recipe) | Self::Smoking(recipe) => {
+                Some(&recipe.result)
+            }
+            Self::Unsupported => None,
+        }
+    }
+
+    /// The cooking recipe if it is made in the block with the id, e.g. `minecraft:smoker`
+    pub fn cooking(&self, block: &str) -> Option<&CookingRecipe> {
+        match (self, block) {
+            (Self::Smelting(recipe), "minecraft:furnace")
+            | (Self::Blasting(recipe), "minecraft:blast_furnace")
+            | (Self::Smoking(recipe), "minecraft:smoker") => Some(recipe),
+            _ => None,
+        }
+    }
+
+    /// How many ticks cooking one item takes, None for crafting recipes. Blast furnaces and
+    /// smokers are twice as fast like in vanilla
+    pub fn cooking_time(&self) -> Option<i32> {
+        match self {
+            Self::Smelting(recipe) => Some(recipe.cooking_time.unwrap_or(200)),
+            Self::Blasting(recipe) | Self::Smoking(recipe) => {
+                Some(recipe.cooking_time.unwrap_or(100))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl ShapedRecipe {
+    /// The width and height of the pattern
+    pub fn size(&self) -> (usize, usize) {
+        let (_, _, width, height) = self.bounds();
+        (width, height)
+    }
+
+    /// The ingredient at a slot of the pattern, None for empty slots. Like in vanilla, empty
+    /// rows and columns at the edges of the pattern are left out
+    pub fn ingredient(&self, x: usize, y: usize) -> Option<&Ingredient> {
+        let (left, top, _, _) = self.bounds();
+        self.raw_ingredient(left + x, top + y)
+    }
+
+    fn raw_ingredient(&self, x: usize, y: usize) -> Option<&Ingredient> {
+        let symbol = self.pattern.get(y)?.chars().nth(x)?;
+        self.key.get(&symbol)
+    }
+
+    fn bounds(&self) -> (usize, usize, usize, usize) {
+        let width = self
+            .pattern
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        bounds(width, self.pattern.len(), |x, y| {
+            self.raw_ingredient(x, y).is_some()
+        })
+        .unwrap_or((0, 0, 0, 0))
+    }
+
+    fn matches(&self, grid: &[Option<&str>], width: usize) -> bool {
+        let height = grid.len() / width;
+        // The pattern may be anywhere in the grid
+        let Some((left, top, grid_width, grid_height)) =
+            bounds(width, height, |x, y| grid[y * width + x].is_some())
+        else {
+            return false;
+        };
+        let (recipe_width, recipe_height) = self.size();
+        if (grid_width, grid_height) != (recipe_width, recipe_height) {
+            return false;
+        }
+        // Like in vanilla, the mirrored pattern works too
+        [false, true].into_iter().any(|mirrored| {
+            (0..recipe_height).all(|y| {
+                (0..recipe_width).all(|x| {
+                    let pattern_x = if mirrored { recipe_width - 1 - x } else { x };
+                    match (
+                        self.ingredient(pattern_x, y),
+                        grid[(top + y) * width + left + x],
+                    ) {
+                        (None, None) => true,
+                        (Some(ingredient), Some(item)) => ingredient.test(item),
+                        _ => false,
+                    }
+                })
+            })
+        })
+    }
+}
+
+impl ShapelessRecipe {
+    fn matches(&self, grid: &[Option<&str>]) -> bool {
+        let items: Vec<&str> = grid.iter().flatten().copied().collect();
+        items.len() == self.ingredients.len()
+            && assign(&self.ingredients, &items, &mut vec![false; items.len()])
+    }
+}
+
+impl Ingredient {
+    /// Whether an item with the id can be used
+    pub fn test(&self, item: &str) -> bool {
+        match self {
+            Self::Item { item: id } => id == item,
+            // TODO: Support item tags, the built-in recipes list the items of their tags instead
+            Self::Tag { .. } => false,
+            Self::AnyOf(ingredients) => ingredients.iter().any(|ingredient| ingredient.test(item)),
+        }
+    }
+
+    /// The ids of every item which can be used, the recipe book shows them
+    pub fn items(&self) -> Vec<&str> {
+        match self {
+            Self::Item { item } => vec![item.as_str()],
+            Self::Tag { .. } => Vec::new(),
+            Self::AnyOf(ingredients) => ingredients.iter().flat_map(Self::items).collect(),
+        }
+    }
+}
+
+/// The left, top, width and height of the filled part of a grid, None if it is empty
+fn bounds(
+    width: usize,
+    height: usize,
+    filled: impl Fn(usize, usize) -> bool,
+) -> Option<(usize, usize, usize, usize)> {
+    let columns: Vec<usize> = (0..width)
+        .filter(|x| (0..height).any(|y| filled(*x, y)))
+        .collect();
+    let rows: Vec<usize> = (0..height)
+        .filter(|y| (0..width).any(|x| filled(x, *y)))
+        .collect();
+    let (left, right) = (*columns.first()?, *columns.last()?);
+    let (top, bottom) = (*rows.first()?, *rows.last()?);
+    Some((left, top, right - left + 1, bottom - top + 1))
+}
+
+/// Whether every ingredient can get one of the items which is not `used` yet
+fn assign(ingredients: &[Ingredient], items: &[&str], used: &mut [bool]) -> bool {
+    let Some((ingredient, rest)) = ingredients.split_first() else {
+        return true;
+    };
+    for index in 0..items.len() {
+        if !used[index] && ingredient.test(items[index]) {
+            used[index] = true;
+            if assign(rest, items, used) {
+                return true;
+            }
+            used[index] = false;
+        }
+    }
+    false
+}
+
+/// What is left in the crafting grid of an item after crafting with it, like the bucket of a
+/// milk bucket. Furnaces keep the bucket of lava they burnt too
+pub fn remainder(item: &str) -> Option<&'static str> {
+    match item {
+        "minecraft:water_bucket"
+        | "minecraft:lava_bucket"
+        | "minecraft:milk_bucket"
+        | "minecraft:powder_snow_bucket" => Some("minecraft:bucket"),
+        "minecraft:honey_bottle" | "minecraft:dragon_breath" => Some("minecraft:glass_bottle"),
+        _ => None,
+    }
+}
+
+/// How many ticks an item burns in a furnace, None if it is no fuel. Like in vanilla, wood of
+/// the nether doesn't burn
+pub fn fuel(item: &str) -> Option<i32> {
+    let name = item.strip_prefix("minecraft:")?;
+    let ticks = match name {
+        "lava_bucket" => 20000,
+        "coal_block" => 16000,
+        "dried_kelp_block" => 4001,
+        "blaze_rod" => 2400,
+        "coal" | "charcoal" => 1600,
+        "bow" | "crossbow" | "fishing_rod" | "ladder" | "crafting_table" | "cartography_table"
+        | "fletching_table" | "smithing_table" | "loom" | "bookshelf" | "chiseled_bookshelf"
+        | "lectern" | "composter" | "barrel" | "chest" | "trapped_chest" | "note_block"
+        | "jukebox" | "daylight_detector" | "beehive" | "bee_nest" | "mangrove_roots" => 300,
+        "wooden_sword" | "wooden_shovel" | "wooden_pickaxe" | "wooden_axe" | "wooden_hoe" => 200,
+        "stick" | "bowl" | "dead_bush" | "azalea" | "flowering_azalea" | "mangrove_propagule" => {
+            100
+        }
+        "bamboo" | "scaffolding" => 50,
+        _ if name.ends_with("_banner") => 300,
+        _ if name.ends_with("_wool") => 100,
+        _ if name.ends_with("_carpet") && !name.contains("moss") => 67,
+        _ => wood_fuel(name.strip_prefix("stripped_").unwrap_or(name))?,
+    };
+    Some(ticks)
+}
+
+/// How long a block or item made of the wood of a tree burns
+fn wood_fuel(name: &str) -> Option<i32> {
+    const WOODS: [&str; 9] = [
+        "oak", "spruce", "birch", "jungle", "acacia", "dark_oak", "mangrove", "cherry", "bamboo",
+    ];
+    let part = WOODS
+        .iter()
+        .find_map(|wood| name.strip_prefix(wood)?.strip_prefix('_'))?;
+    let ticks = match part {
+        "boat" | "chest_boat" | "raft" | "chest_raft" => 1200,
+        "hanging_sign" => 800,
+        "planks" | "log" | "wood" | "block" | "mosaic" | "stairs" | "mosaic_stairs" | "fence"
+        | "fence_gate" | "trapdoor" | "pressure_plate" => 300,
+        "door" | "sign" => 200,
+        "slab" | "mosaic_slab" => 150,
+        "button" | "sapling" => 100,
+        _ => return None,
+    };
+    Some(ticks)
+}
+
+/// The built-in recipes and those of the datapacks of the main world in `root_folder`, by their
+/// id. Recipes of datapacks replace built-in ones with the same id
+pub fn load_recipes(root_folder: &Path) -> HashMap<String, Recipe> {
+    let mut recipes: HashMap<String, Recipe> =
+        serde_json::from_str(RECIPES_JSON).expect("Could not parse recipes.json");
+    // The folder was renamed in 1.21, older datapacks still use the plural
+    recipes.extend(datapack::load_files(
+        root_folder,
+        &["recipe", "recipes"],
+        "recipe",
+    ));
+    recipes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shaped() {
+        let recipes = load_recipes(Path::new("does-not-exist"));
+        let sticks = &recipes["minecraft:stick"];
+        assert!(sticks.fits(2));
+        // Anywhere in the grid
+        let grid = [
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some("minecraft:oak_planks"),
+            None,
+            None,
+            Some("minecraft:birch_planks"),
+        ];
+        assert!(sticks.matches(&grid, 3));
+        assert!(!sticks.matches(&[Some("minecraft:oak_planks"), None, None, None], 2));
+
+        let axe = &recipes["minecraft:iron_axe"];
+        assert!(!axe.fits(2));
+        let iron = Some("minecraft:iron_ingot");
+        let stick = Some("minecraft:stick");
+        assert!(axe.matches(&[iron, iron, None, iron, stick, None, None, stick, None], 3));
+        // Mirrored
+        assert!(axe.matches(&[iron, iron, None, stick, iron, None, stick, None, None], 3));
+        assert!(!axe.matches(&[iron, iron, None, stick, iron, None, None, stick, None], 3));
+    }
+
+    #[test]
+    fn shapeless() {
+        let recipes = load_recipes(Path::new("does-not-exist"));
+        let flint_and_steel = &recipes["minecraft:flint_and_steel"];
+        let flint = Some("minecraft:flint");
+        let iron = Some("minecraft:iron_ingot");
+        assert!(flint_and_steel.matches(&[None, flint, iron, None], 2));
+        assert!(flint_and_steel.matches(&[iron, None, None, flint], 2));
+        assert!(!flint_and_steel.matches(&[iron, flint, flint, None], 2));
+        assert!(!flint_and_steel.matches(&[iron, iron, None, None], 2));
+        assert_eq!(
+            flint_and_steel.result().map(|result| result.id.as_str()),
+            Some("minecraft:flint_and_steel")
+        );
+    }
+
+    #[test]
+    fn cooking() {
+        let recipes = load_recipes(Path::new("does-not-exist"));
+        let (_, recipe) = recipes
+            .iter()
+            .find(|(_, recipe)| {
+                recipe
+                    .cooking("minecraft:furnace")
+                    .is_some_and(|recipe| recipe.ingredient.test("minecraft:raw_iron"))
+            })
+            .unwrap();
+        assert_eq!(recipe.cooking_time(), Some(200));
+        assert_eq!(
+            recipe.result().map(|result| result.id.as_str()),
+            Some("minecraft:iron_ingot")
+        );
+        // Only blast furnaces blast
+        let blasting = &recipes["minecraft:iron_ingot_from_blasting_raw_iron"];
+        assert!(blasting.cooking("minecraft:furnace").is_none());
+        assert_eq!(blasting.cooking_time(), Some(100));
+        assert!(!blasting.matches(&[Some("minecraft:raw_iron")], 1));
+    }
+
+    #[test]
+    fn fuel() {
+        assert_eq!(super::fuel("minecraft:coal"), Some(1600));
+        assert_eq!(super::fuel("minecraft:stripped_dark_oak_log"), Some(300));
+        assert_eq!(super::fuel("minecraft:oak_slab"), Some(150));
+        assert_eq!(super::fuel("minecraft:oak_hanging_sign"), Some(800));
+        assert_eq!(super::fuel("minecraft:crimson_planks"), None);
+        assert_eq!(super::fuel("minecraft:stone"), None);
+    }
+}
@@ -0,0 +1,471 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+use fastnbt::Value;
+use flate2::read::GzDecoder;
+use pumpkin_core::math::vector3::Vector3;
+use thiserror::Error;
+
+use crate::block::{block_state::BlockState, BlockEntity, BlockId};
+
+/// The first bytes of gzip compressed files, schematics are usually compressed
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+#[derive(Error, Debug)]
+pub enum SchematicError {
+    #[error("Io error: {0}")]
+    IoError(std::io::ErrorKind),
+    #[error("Failed to parse the schematic: {0}")]
+    ParsingError(fastnbt::error::Error),
+    #[error("Neither a Sponge schematic nor a structure file")]
+    UnknownFormat,
+    #[error("Invalid schematic: {0}")]
+    Invalid(&'static str),
+}
+
+/// How a schematic is turned around the vertical axis when it is pasted
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Rotation {
+    #[default]
+    None,
+    Clockwise90,
+    Clockwise180,
+    CounterClockwise90,
+}
+
+impl Rotation {
+    /// The rotation of clockwise degrees, None if they are no multiple of 90
+    pub fn from_degrees(degrees: i32) -> Option<Self> {
+        match degrees.rem_euclid(360) {
+            0 => Some(Self::None),
+            90 => Some(Self::Clockwise90),
+            180 => Some(Self::Clockwise180),
+            270 => Some(Self::CounterClockwise90),
+            _ => None,
+        }
+    }
+
+    /// How many times it turns clockwise by 90 degrees
+    fn quarter_turns(self) -> usize {
+        match self {
+            Self::None => 0,
+            Self::Clockwise90 => 1,
+            Self::Clockwise180 => 2,
+            Self::CounterClockwise90 => 3,
+        }
+    }
+
+    /// Turns a position in a box of `size`, so the rotated box starts at the same corner
+    pub fn rotate_position(self, position: Vector3<i32>, size: Vector3<i32>) -> Vector3<i32> {
+        let Vector3 { x, y, z } = position;
+        match self {
+            Self::None => position,
+            Self::Clockwise90 => Vector3::new(size.z - 1 - z, y, x),
+            Self::Clockwise180 => Vector3::new(size.x - 1 - x, y, size.z - 1 - z),
+            Self::CounterClockwise90 => Vector3::new(z, y, size.x - 1 - x),
+        }
+    }
+
+    /// The size of a box after turning it
+    pub fn rotate_size(self, size: Vector3<i32>) -> Vector3<i32> {
+        match self {
+            Self::None | Self::Clockwise180 => size,
+            Self::Clockwise90 | Self::CounterClockwise90 => Vector3::new(size.z, size.y, size.x),
+        }
+    }
+
+    /// Turns the properties of a block state which depend on the direction, e.g. where stairs face
+    pub fn rotate_block(self, block: BlockId) -> BlockId {
+        if self == Self::None {
+            return block;
+        }
+        let Some((registry_id, properties)) = block.registry_state() else {
+            return block;
+        };
+        let mut rotated = properties.clone();
+        for (name, value) in properties {
+            match name.as_str() {
+                "facing" | "horizontal_facing" => {
+                    if let Some(direction) = self.rotate_direction(value) {
+                        rotated.insert(name.clone(), direction.to_string());
+                    }
+                }
+                "axis" if self.quarter_turns() % 2 == 1 => {
+                    let axis = match value.as_str() {
+                        "x" => "z",
+                        "z" => "x",
+                        other => other,
+                    };
+                    rotated.insert(name.clone(), axis.to_string());
+                }
+                // Signs and banners can face 16 directions
+                "rotation" => {
+                    if let Ok(rotation) = value.parse::<usize>() {
+                        let rotation = (rotation + self.quarter_turns() * 4) % 16;
+                        rotated.insert(name.clone(), rotation.to_string());
+                    }
+                }
+                // Fences, walls, panes and redstone connect to their sides
+                "north" | "east" | "south" | "west" => {
+                    if let Some(direction) = self.rotate_direction(name) {
+                        rotated.insert(direction.to_string(), value.clone());
+                    }
+                }
+                "shape" => {
+                    if let Some(shape) = self.rotate_rail_shape(value) {
+                        rotated.insert(name.clone(), shape);
+                    }
+                }
+                _ => {}
+            }
+        }
+        BlockState::new(registry_id, Some(&rotated))
+            .ok()
+            .map_or(block, Into::into)
+    }
+
+    fn rotate_direction(self, direction: &str) -> Option<&'static str> {
+        const DIRECTIONS: [&str; 4] = ["north", "east", "south", "west"];
+        let index = DIRECTIONS.iter().position(|other| *other == direction)?;
+        Some(DIRECTIONS[(index + self.quarter_turns()) % 4])
+    }
+
+    /// Rails are shaped by the directions they lead to, e.g. `north_east` or `ascending_west`
+    fn rotate_rail_shape(self, shape: &str) -> Option<String> {
+        let mut rotated = Vec::new();
+        for part in shape.split('_') {
+            match part {
+                "ascending" => rotated.push(part),
+                direction => rotated.push(self.rotate_direction(direction)?),
+            }
+        }
+        let shape = match rotated.as_slice() {
+            ["north", "south"] | ["south", "north"] => "north_south".to_string(),
+            ["east", "west"] | ["west", "east"] => "east_west".to_string(),
+            // Curves are named with north or south first
+            [first @ ("east" | "west"), second @ ("north" | "south")] => {
+                format!("{second}_{first}")
+            }
+            _ => rotated.join("_"),
+        };
+        Some(shape)
+    }
+}
+
+/// Blocks saved in a file to be pasted into worlds, e.g. a hub or an arena
+pub struct Schematic {
+    pub size: Vector3<i32>,
+    /// Relative to the lowest corner, air included
+    pub blocks: Vec<(Vector3<i32>, BlockId)>,
+    /// Their positions are relative to the lowest corner as well
+    pub block_entities: Vec<BlockEntity>,
+}
+
+impl Schematic {
+    /// Reads a Sponge schematic (`.schem`) or a vanilla structure file (`.nbt`)
+    pub fn read(path: &Path) -> Result<Self, SchematicError> {
+        let mut bytes = Vec::new();
+        File::open(path)
+            .and_then(|file| BufReader::new(file).read_to_end(&mut bytes))
+            .map_err(|err| SchematicError::IoError(err.kind()))?;
+        if bytes.starts_with(&GZIP_MAGIC) {
+            let mut decompressed = Vec::new();
+            GzDecoder::new(bytes.as_slice())
+                .read_to_end(&mut decompressed)
+                .map_err(|err| SchematicError::IoError(err.kind()))?;
+            bytes = decompressed;
+        }
+        let root: HashMap<String, Value> =
+            fastnbt::from_bytes(&bytes).map_err(SchematicError::ParsingError)?;
+        Self::from_nbt(root)
+    }
+
+    /// Reads the schematic from the root compound of its file
+    pub fn from_nbt(mut root: HashMap<String, Value>) -> Result<Self, SchematicError> {
+        // Version 3 of the Sponge format wraps everything in a compound
+        if let Some(Value::Compound(schematic)) = root.remove("Schematic") {
+            return Self::from_sponge(schematic);
+        }
+        if root.contains_key("Width") {
+            Self::from_sponge(root)
+        } else if root.contains_key("palette") || root.contains_key("palettes") {
+            Self::from_structure(root)
+        } else {
+            Err(SchematicError::UnknownFormat)
+        }
+    }
+
+    /// Reads versions 2 and 3 of the Sponge schematic format
+    fn from_sponge(mut data: HashMap<String, Value>) -> Result<Self, SchematicError> {
+        let mut dimension = |name: &str| match data.remove(name) {
+            Some(Value::Short(value)) => Ok(i32::from(value as u16)),
+            _ => Err(SchematicError::Invalid("missing size")),
+        };
+        let size = Vector3::new(
+            dimension("Width")?,
+            dimension("Height")?,
+            dimension("Length")?,
+        );
+        // Version 3 keeps the blocks in their own compound
+        let mut blocks = match data.remove("Blocks") {
+            Some(Value::Compound(blocks)) => blocks,
+            _ => data,
+        };
+        let Some(Value::Compound(palette)) = blocks.remove("Palette") else {
+            return Err(SchematicError::Invalid("missing palette"));
+        };
+        let mut states = HashMap::new();
+        for (state, index) in palette {
+            if let Value::Int(index) = index {
+                states.insert(index, parse_block_state(&state).unwrap_or_default());
+            }
+        }
+        let data = match blocks.remove("Data").or_else(|| blocks.remove("BlockData")) {
+            Some(Value::ByteArray(data)) => data,
+            _ => return Err(SchematicError::Invalid("missing block data")),
+        };
+
+        let mut schematic = Self {
+            size,
+            blocks: Vec::new(),
+            block_entities: Vec::new(),
+        };
+        // The palette indices are var ints, ordered by y, then z, then x
+        let mut bytes = data.iter().map(|byte| *byte as u8);
+        let mut index = 0;
+        while let Some(state) = read_var_int(&mut bytes) {
+            let x = index % size.x;
+            let z = (index / size.x) % size.z;
+            let y = index / (size.x * size.z);
+            if y >= size.y {
+                break;
+            }
+            let block = states.get(&state).copied().unwrap_or_default();
+            schematic.blocks.push((Vector3::new(x, y, z), block));
+            index += 1;
+        }
+
+        if let Some(Value::List(entities)) = blocks.remove("BlockEntities") {
+            for entity in entities {
+                let Value::Compound(mut entity) = entity else {
+                    continue;
+                };
+                let Some(Value::IntArray(position)) = entity.remove("Pos") else {
+                    continue;
+                };
+                let Some(Value::String(id)) = entity.remove("Id") else {
+                    continue;
+                };
+                let &[x, y, z] = &position[..] else {
+                    continue;
+                };
+                // Version 3 keeps the data of the block entity in its own compound
+                let data = match entity.remove("Data") {
+                    Some(Value::Compound(data)) => data,
+                    _ => entity,
+                };
+                schematic.block_entities.push(BlockEntity {
+                    id,
+                    position: Vector3::new(x, y, z),
+                    data,
+                });
+            }
+        }
+        Ok(schematic)
+    }
+
+    /// Reads the structure files written by vanilla's structure blocks
+    fn from_structure(mut data: HashMap<String, Value>) -> Result<Self, SchematicError> {
+        let size = match data.remove("size") {
+            Some(Value::List(size)) => read_position(&size),
+            _ => None,
+        }
+        .ok_or(SchematicError::Invalid("missing size"))?;
+        // Structures with several palettes pick one at random, this always uses the first
+        let palette = match (data.remove("palette"), data.remove("palettes")) {
+            (Some(Value::List(palette)), _) => palette,
+            (None, Some(Value::List(mut palettes))) if !palettes.is_empty() => {
+                match palettes.swap_remove(0) {
+                    Value::List(palette) => palette,
+                    _ => return Err(SchematicError::Invalid("invalid palette")),
+                }
+            }
+            _ => return Err(SchematicError::Invalid("missing palette")),
+        };
+        let states: Vec<_> = palette
+            .into_iter()
+            .map(|state| {
+                let Value::Compound(mut state) = state else {
+                    return BlockId::default();
+                };
+                let Some(Value::String(name)) = state.remove("Name") else {
+                    return BlockId::default();
+                };
+                let properties = match state.remove("Properties") {
+                    Some(Value::Compound(properties)) => properties
+                        .into_iter()
+                        .filter_map(|(name, value)| match value {
+                            Value::String(value) => Some((name, value)),
+                            _ => None,
+                        })
+                        .collect(),
+                    _ => HashMap::new(),
+                };
+                block_state(&name, properties).unwrap_or_default()
+            })
+            .collect();
+
+        let Some(Value::List(blocks)) = data.remove("blocks") else {
+            return Err(SchematicError::Invalid("missing blocks"));
+        };
+        let mut schematic = Self {
+            size,
+            blocks: Vec::new(),
+            block_entities: Vec::new(),
+        };
+        for block in blocks {
+            let Value::Compound(mut block) = block else {
+                continue;
+            };
+            let (Some(Value::List(position)), Some(Value::Int(state))) =
+                (block.remove("pos"), block.remove("state"))
+            else {
+                continue;
+            };
+            let Some(position) = read_position(&position) else {
+                continue;
+            };
+            let state = states.get(state as usize).copied().unwrap_or_default();
+            schematic.blocks.push((position, state));
+            if let Some(Value::Compound(mut nbt)) = block.remove("nbt") {
+                if let Some(Value::String(id)) = nbt.remove("id") {
+                    schematic.block_entities.push(BlockEntity {
+                        id,
+                        position,
+                        data: nbt,
+                    });
+                }
+            }
+        }
+        Ok(schematic)
+    }
+}
+
+fn read_position(values: &[Value]) -> Option<Vector3<i32>> {
+    match values {
+        [Value::Int(x), Value::Int(y), Value::Int(z)] => Some(Vector3::new(*x, *y, *z)),
+        _ => None,
+    }
+}
+
+fn read_var_int(bytes: &mut impl Iterator<Item = u8>) -> Option<i32> {
+    let mut value = 0;
+    for i in 0..5 {
+        let byte = bytes.next()?;
+        value |= i32::from(byte & 0x7f) << (i * 7);
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// A block state, the properties which are not given keep their default values
+fn block_state(name: &str, properties: HashMap<String, String>) -> Option<BlockId> {
+    let default: BlockId = BlockState::new(name, None).ok()?.into();
+    if properties.is_empty() {
+        return Some(default);
+    }
+    let (_, default_properties) = default.registry_state()?;
+    let mut all = default_properties.clone();
+    all.extend(properties);
+    BlockState::new(name, Some(&all)).ok().map(Into::into)
+}
+
+/// Parses a block state written like `minecraft:oak_stairs[facing=north,half=bottom]`
+pub fn parse_block_state(state: &str) -> Option<BlockId> {
+    let (name, properties) = match state.split_once('[') {
+        Some((name, properties)) => (name, properties.strip_suffix(']')?),
+        None => (state, ""),
+    };
+    let properties = properties
+        .split(',')
+        .filter(|property| !property.is_empty())
+        .map(|property| {
+            let (key, value) = property.split_once('=')?;
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect::<Option<HashMap<_, _>>>()?;
+    block_state(name, properties)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use fastnbt::{ByteArray, Value};
+    use pumpkin_core::math::vector3::Vector3;
+
+    use super::{parse_block_state, Rotation, Schematic};
+
+    #[test]
+    fn rotating_keeps_positions_in_the_box() {
+        let size = Vector3::new(3, 1, 5);
+        for rotation in [
+            Rotation::None,
+            Rotation::Clockwise90,
+            Rotation::Clockwise180,
+            Rotation::CounterClockwise90,
+        ] {
+            let rotated_size = rotation.rotate_size(size);
+            for x in 0..size.x {
+                for z in 0..size.z {
+                    let rotated = rotation.rotate_position(Vector3::new(x, 0, z), size);
+                    assert!((0..rotated_size.x).contains(&rotated.x));
+                    assert!((0..rotated_size.z).contains(&rotated.z));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rotates_facing() {
+        let stairs = parse_block_state("minecraft:oak_stairs[facing=north]").unwrap();
+        let rotated = Rotation::Clockwise90.rotate_block(stairs);
+        assert_eq!(rotated.property("facing"), Some("east"));
+        assert_eq!(rotated.property("half"), stairs.property("half"));
+    }
+
+    #[test]
+    fn reads_sponge_schematic() {
+        let root = HashMap::from([
+            ("Width".to_string(), Value::Short(2)),
+            ("Height".to_string(), Value::Short(1)),
+            ("Length".to_string(), Value::Short(1)),
+            (
+                "Palette".to_string(),
+                Value::Compound(HashMap::from([
+                    ("minecraft:air".to_string(), Value::Int(0)),
+                    ("minecraft:stone".to_string(), Value::Int(1)),
+                ])),
+            ),
+            (
+                "BlockData".to_string(),
+                Value::ByteArray(ByteArray::new(vec![0, 1])),
+            ),
+        ]);
+        let schematic = Schematic::from_nbt(root).unwrap();
+        assert_eq!(schematic.size, Vector3::new(2, 1, 1));
+        assert_eq!(
+            schematic.blocks[1],
+            (
+                Vector3::new(1, 0, 0),
+                parse_block_state("minecraft:stone").unwrap()
+            )
+        );
+    }
+}
@@ -0,0 +1,868 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+use flate2::read::GzDecoder;
+use pumpkin_core::text::color::NamedColor;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{stats, storage};
+
+/// The folder inside of the main world which holds the scoreboard, like in vanilla
+const DATA_FOLDER: &str = "data";
+const SCOREBOARD_FILE: &str = "scoreboard.dat";
+const DATA_VERSION: i32 = 3955;
+
+#[derive(Error, Debug)]
+pub enum ScoreboardDataError {
+    #[error("Io error: {0}")]
+    IoError(std::io::ErrorKind),
+    #[error("Failed to parse the scoreboard: {0}")]
+    ParsingError(fastnbt::error::Error),
+}
+
+/// Why a change to the scoreboard was refused, the messages are the ones of vanilla
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ScoreboardError {
+    #[error("An objective already exists by that name")]
+    ObjectiveExists,
+    #[error("Unknown scoreboard objective '{0}'")]
+    UnknownObjective(String),
+    #[error("Invalid criterion '{0}'")]
+    UnknownCriterion(String),
+    #[error("That objective is read-only and cannot be set")]
+    ReadOnly,
+    #[error("A team already exists by that name")]
+    TeamExists,
+    #[error("Unknown team '{0}'")]
+    UnknownTeam(String),
+}
+
+/// What changes the scores of an objective
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Criterion {
+    /// Only commands change the scores
+    Dummy,
+    /// Counts how often players die, kill other players or kill anything
+    DeathCount,
+    PlayerKillCount,
+    TotalKillCount,
+    /// Always the current value of the players, commands can't change them
+    Health,
+    Food,
+    Level,
+    Xp,
+    /// Counts a statistic, written like `minecraft.mined:minecraft.stone`
+    Stat {
+        category: String,
+        stat: String,
+    },
+}
+
+impl Criterion {
+    pub fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "dummy" => Self::Dummy,
+            "deathCount" => Self::DeathCount,
+            "playerKillCount" => Self::PlayerKillCount,
+            "totalKillCount" => Self::TotalKillCount,
+            "health" => Self::Health,
+            "food" => Self::Food,
+            "level" => Self::Level,
+            "xp" => Self::Xp,
+            _ => {
+                let (category, stat) = stats::parse_criterion(name)?;
+                Self::Stat { category, stat }
+            }
+        })
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Dummy => "dummy".to_string(),
+            Self::DeathCount => "deathCount".to_string(),
+            Self::PlayerKillCount => "playerKillCount".to_string(),
+            Self::TotalKillCount => "totalKillCount".to_string(),
+            Self::Health => "health".to_string(),
+            Self::Food => "food".to_string(),
+            Self::Level => "level".to_string(),
+            Self::Xp => "xp".to_string(),
+            Self::Stat { category, stat } => format!(
+                "{}:{}",
+                category.replacen(':', ".", 1),
+                stat.replacen(':', ".", 1)
+            ),
+        }
+    }
+
+    /// Whether the scores always show the current value of the players, so commands can't set
+    /// them
+    pub const fn is_read_only(&self) -> bool {
+        matches!(self, Self::Health | Self::Food | Self::Level | Self::Xp)
+    }
+
+    /// The statistics the scores count, every increase of them adds to the score
+    pub fn counted_stats(&self) -> Vec<(&str, &str)> {
+        match self {
+            Self::DeathCount => vec![(stats::CUSTOM, stats::DEATHS)],
+            Self::PlayerKillCount => vec![(stats::CUSTOM, stats::PLAYER_KILLS)],
+            Self::TotalKillCount => vec![
+                (stats::CUSTOM, stats::PLAYER_KILLS),
+                (stats::CUSTOM, stats::MOB_KILLS),
+            ],
+            Self::Stat { category, stat } => vec![(category.as_str(), stat.as_str())],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Health shows hearts unless it is changed, everything else numbers
+    pub const fn default_render_type(&self) -> RenderType {
+        match self {
+            Self::Health => RenderType::Hearts,
+            _ => RenderType::Integer,
+        }
+    }
+}
+
+/// How clients show the scores of an objective
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderType {
+    Integer,
+    Hearts,
+}
+
+impl RenderType {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Integer => "integer",
+            Self::Hearts => "hearts",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Self::Integer, Self::Hearts]
+            .into_iter()
+            .find(|render_type| render_type.name() == name)
+    }
+}
+
+/// Something players get scores in
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Objective {
+    pub name: String,
+    pub criterion: Criterion,
+    /// Shown instead of the name, e.g. above the sidebar
+    pub display_name: String,
+    pub render_type: RenderType,
+}
+
+/// Where clients show an objective, the team sidebars only to members of teams with the color
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DisplaySlot {
+    List,
+    Sidebar,
+    BelowName,
+    TeamSidebar(NamedColor),
+}
+
+impl DisplaySlot {
+    /// All slots by their protocol id
+    pub fn all() -> impl Iterator<Item = Self> {
+        [Self::List, Self::Sidebar, Self::BelowName]
+            .into_iter()
+            .chain(NamedColor::ALL.into_iter().map(Self::TeamSidebar))
+    }
+
+    pub const fn id(self) -> u8 {
+        match self {
+            Self::List => 0,
+            Self::Sidebar => 1,
+            Self::BelowName => 2,
+            Self::TeamSidebar(color) => 3 + color as u8,
+        }
+    }
+
+    /// The name used in commands and saved data, e.g. `sidebar.team.red`
+    pub fn name(self) -> String {
+        match self {
+            Self::List => "list".to_string(),
+            Self::Sidebar => "sidebar".to_string(),
+            Self::BelowName => "below_name".to_string(),
+            Self::TeamSidebar(color) => format!("sidebar.team.{}", color.name()),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().find(|slot| slot.name() == name)
+    }
+}
+
+/// Whose name tags or death messages members of a team see
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Visibility {
+    Always,
+    Never,
+    HideForOtherTeams,
+    HideForOwnTeam,
+}
+
+impl Visibility {
+    pub const ALL: [Self; 4] = [
+        Self::Always,
+        Self::Never,
+        Self::HideForOtherTeams,
+        Self::HideForOwnTeam,
+    ];
+
+    /// The name used in commands, saved data and packets
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::Never => "never",
+            Self::HideForOtherTeams => "hideForOtherTeams",
+            Self::HideForOwnTeam => "hideForOwnTeam",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|visibility| visibility.name() == name)
+    }
+}
+
+/// Which entities members of a team push and get pushed by
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CollisionRule {
+    Always,
+    Never,
+    PushOtherTeams,
+    PushOwnTeam,
+}
+
+impl CollisionRule {
+    pub const ALL: [Self; 4] = [
+        Self::Always,
+        Self::Never,
+        Self::PushOtherTeams,
+        Self::PushOwnTeam,
+    ];
+
+    /// The name used in commands, saved data and packets
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::Never => "never",
+            Self::PushOtherTeams => "pushOtherTeams",
+            Self::PushOwnTeam => "pushOwnTeam",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|rule| rule.name() == name)
+    }
+
+    /// Whether a member pushes an entity, which may be on the same team
+    pub const fn pushes(self, same_team: bool) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::PushOtherTeams => !same_team,
+            Self::PushOwnTeam => same_team,
+        }
+    }
+}
+
+/// A group of players, or other score holders, which share the color and the rules of their
+/// names
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Team {
+    pub name: String,
+    pub display_name: String,
+    /// The color of the names of the members, None leaves them white
+    pub color: Option<NamedColor>,
+    /// Shown in front of and behind the names of the members
+    pub prefix: String,
+    pub suffix: String,
+    /// Whether members can hurt each other
+    pub friendly_fire: bool,
+    /// Whether members see each other while they are invisible
+    pub see_friendly_invisibles: bool,
+    pub name_tag_visibility: Visibility,
+    pub death_message_visibility: Visibility,
+    pub collision_rule: CollisionRule,
+    pub members: BTreeSet<String>,
+}
+
+impl Team {
+    fn new(name: &str, display_name: Option<&str>) -> Self {
+        Self {
+            name: name.to_string(),
+            display_name: display_name.unwrap_or(name).to_string(),
+            color: None,
+            prefix: String::new(),
+            suffix: String::new(),
+            friendly_fire: true,
+            see_friendly_invisibles: true,
+            name_tag_visibility: Visibility::Always,
+            death_message_visibility: Visibility::Always,
+            collision_rule: CollisionRule::Always,
+            members: BTreeSet::new(),
+        }
+    }
+}
+
+/// The objectives with the scores of players and the teams, stored in `data/scoreboard.dat` of
+/// the main world like in vanilla
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScoreboardData {
+    objectives: BTreeMap<String, Objective>,
+    /// The scores by holder and objective, holders are mostly player names
+    scores: BTreeMap<String, BTreeMap<String, i32>>,
+    teams: BTreeMap<String, Team>,
+    /// The objective shown in every slot which shows one
+    display_slots: BTreeMap<DisplaySlot, String>,
+}
+
+impl ScoreboardData {
+    pub fn objectives(&self) -> impl Iterator<Item = &Objective> {
+        self.objectives.values()
+    }
+
+    pub fn objective(&self, name: &str) -> Result<&Objective, ScoreboardError> {
+        self.objectives
+            .get(name)
+            .ok_or_else(|| ScoreboardError::UnknownObjective(name.to_string()))
+    }
+
+    /// An objective commands may change the scores of
+    pub fn writable_objective(&self, name: &str) -> Result<&Objective, ScoreboardError> {
+        let objective = self.objective(name)?;
+        if objective.criterion.is_read_only() {
+            return Err(ScoreboardError::ReadOnly);
+        }
+        Ok(objective)
+    }
+
+    pub fn add_objective(
+        &mut self,
+        name: &str,
+        criterion: &str,
+        display_name: Option<&str>,
+    ) -> Result<&Objective, ScoreboardError> {
+        if self.objectives.contains_key(name) {
+            return Err(ScoreboardError::ObjectiveExists);
+        }
+        let criterion = Criterion::parse(criterion)
+            .ok_or_else(|| ScoreboardError::UnknownCriterion(criterion.to_string()))?;
+        let objective = Objective {
+            name: name.to_string(),
+            display_name: display_name.unwrap_or(name).to_string(),
+            render_type: criterion.default_render_type(),
+            criterion,
+        };
+        Ok(self.objectives.entry(name.to_string()).or_insert(objective))
+    }
+
+    /// Removes an objective with all of its scores, the slots showing it get empty
+    pub fn remove_objective(&mut self, name: &str) -> Result<Objective, ScoreboardError> {
+        let objective = self
+            .objectives
+            .remove(name)
+            .ok_or_else(|| ScoreboardError::UnknownObjective(name.to_string()))?;
+        for scores in self.scores.values_mut() {
+            scores.remove(name);
+        }
+        self.scores.retain(|_, scores| !scores.is_empty());
+        self.display_slots.retain(|_, shown| shown != name);
+        Ok(objective)
+    }
+
+    pub fn objective_mut(&mut self, name: &str) -> Result<&mut Objective, ScoreboardError> {
+        self.objectives
+            .get_mut(name)
+            .ok_or_else(|| ScoreboardError::UnknownObjective(name.to_string()))
+    }
+
+    /// The objective shown in a slot
+    pub fn displayed(&self, slot: DisplaySlot) -> Option<&Objective> {
+        self.objectives.get(self.display_slots.get(&slot)?)
+    }
+
+    /// Shows an objective in a slot, None clears the slot
+    pub fn set_display_slot(
+        &mut self,
+        slot: DisplaySlot,
+        objective: Option<&str>,
+    ) -> Result<(), ScoreboardError> {
+        match objective {
+            Some(name) => {
+                self.objective(name)?;
+                self.display_slots.insert(slot, name.to_string());
+            }
+            None => {
+                self.display_slots.remove(&slot);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn display_slots(&self) -> impl Iterator<Item = (DisplaySlot, &str)> {
+        self.display_slots
+            .iter()
+            .map(|(slot, name)| (*slot, name.as_str()))
+    }
+
+    /// The score of a holder, None if they have none in the objective
+    pub fn score(&self, holder: &str, objective: &str) -> Option<i32> {
+        self.scores.get(holder)?.get(objective).copied()
+    }
+
+    /// All scores of a holder by objective
+    pub fn scores_of(&self, holder: &str) -> impl Iterator<Item = (&str, i32)> {
+        self.scores
+            .get(holder)
+            .into_iter()
+            .flatten()
+            .map(|(objective, score)| (objective.as_str(), *score))
+    }
+
+    /// Every holder with at least one score
+    pub fn holders(&self) -> impl Iterator<Item = &str> {
+        self.scores.keys().map(String::as_str)
+    }
+
+    /// Sets the score of a holder, returns false if it already was the value
+    pub fn set_score(
+        &mut self,
+        holder: &str,
+        objective: &str,
+        value: i32,
+    ) -> Result<bool, ScoreboardError> {
+        self.objective(objective)?;
+        let old = self
+            .scores
+            .entry(holder.to_string())
+            .or_default()
+            .insert(objective.to_string(), value);
+        Ok(old != Some(value))
+    }
+
+    /// Adds to the score of a holder, which starts at 0, and returns the new one
+    pub fn add_score(
+        &mut self,
+        holder: &str,
+        objective: &str,
+        amount: i32,
+    ) -> Result<i32, ScoreboardError> {
+        let value = self
+            .score(holder, objective)
+            .unwrap_or(0)
+            .saturating_add(amount);
+        self.set_score(holder, objective, value)?;
+        Ok(value)
+    }
+
+    /// Removes the score of a holder in an objective, or all their scores if there is none.
+    /// Returns the objectives they lost a score in
+    pub fn reset_scores(&mut self, holder: &str, objective: Option<&str>) -> Vec<String> {
+        let Some(scores) = self.scores.get_mut(holder) else {
+            return Vec::new();
+        };
+        let reset = match objective {
+            Some(objective) => scores
+                .remove(objective)
+                .map(|_| vec![objective.to_string()])
+                .unwrap_or_default(),
+            None => std::mem::take(scores).into_keys().collect(),
+        };
+        if scores.is_empty() {
+            self.scores.remove(holder);
+        }
+        reset
+    }
+
+    pub fn teams(&self) -> impl Iterator<Item = &Team> {
+        self.teams.values()
+    }
+
+    pub fn team(&self, name: &str) -> Result<&Team, ScoreboardError> {
+        self.teams
+            .get(name)
+            .ok_or_else(|| ScoreboardError::UnknownTeam(name.to_string()))
+    }
+
+    pub fn team_mut(&mut self, name: &str) -> Result<&mut Team, ScoreboardError> {
+        self.teams
+            .get_mut(name)
+            .ok_or_else(|| ScoreboardError::UnknownTeam(name.to_string()))
+    }
+
+    /// The team a holder is a member of
+    pub fn team_of(&self, member: &str) -> Option<&Team> {
+        self.teams
+            .values()
+            .find(|team| team.members.contains(member))
+    }
+
+    pub fn add_team(
+        &mut self,
+        name: &str,
+        display_name: Option<&str>,
+    ) -> Result<&Team, ScoreboardError> {
+        if self.teams.contains_key(name) {
+            return Err(ScoreboardError::TeamExists);
+        }
+        Ok(self
+            .teams
+            .entry(name.to_string())
+            .or_insert(Team::new(name, display_name)))
+    }
+
+    pub fn remove_team(&mut self, name: &str) -> Result<Team, ScoreboardError> {
+        self.teams
+            .remove(name)
+            .ok_or_else(|| ScoreboardError::UnknownTeam(name.to_string()))
+    }
+
+    /// Makes a holder a member of a team, they leave their old one. Returns the old team, the
+    /// same one if they were a member already
+    pub fn join_team(
+        &mut self,
+        team: &str,
+        member: &str,
+    ) -> Result<Option<String>, ScoreboardError> {
+        self.team(team)?;
+        let old = self.leave_team(member);
+        self.team_mut(team)?.members.insert(member.to_string());
+        Ok(old)
+    }
+
+    /// Takes a holder out of their team, returns the team they left
+    pub fn leave_team(&mut self, member: &str) -> Option<String> {
+        let team = self
+            .teams
+            .values_mut()
+            .find(|team| team.members.contains(member))?;
+        team.members.remove(member);
+        Some(team.name.clone())
+    }
+
+    /// Reads the scoreboard of the main world in `root_folder`, empty if there is none yet
+    pub fn read(root_folder: &Path) -> Result<Self, ScoreboardDataError> {
+        let path = root_folder.join(DATA_FOLDER).join(SCOREBOARD_FILE);
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(ScoreboardDataError::IoError(err.kind())),
+        };
+        let mut bytes = Vec::new();
+        GzDecoder::new(BufReader::new(file))
+            .read_to_end(&mut bytes)
+            .map_err(|err| ScoreboardDataError::IoError(err.kind()))?;
+        let file: ScoreboardFile =
+            fastnbt::from_bytes(&bytes).map_err(ScoreboardDataError::ParsingError)?;
+        Ok(file.data.into())
+    }
+
+    /// Writes the scoreboard into the main world in `root_folder`, replacing the old one
+    pub fn write(&self, root_folder: &Path) -> Result<(), ScoreboardDataError> {
+        let bytes = fastnbt::to_bytes(&ScoreboardFile {
+            data: self.into(),
+            data_version: DATA_VERSION,
+        })
+        .map_err(ScoreboardDataError::ParsingError)?;
+
+        let io_error = |err: std::io::Error| ScoreboardDataError::IoError(err.kind());
+        let path = root_folder.join(DATA_FOLDER).join(SCOREBOARD_FILE);
+        storage::write_atomically(&path, &storage::gzip(&bytes).map_err(io_error)?)
+            .map_err(io_error)
+    }
+}
+
+/// Vanilla stores names as JSON text components
+fn to_json_text(text: &str) -> String {
+    serde_json::json!({ "text": text }).to_string()
+}
+
+/// The plain text of a JSON text component, names which are no JSON stay as they are
+fn from_json_text(json: &str) -> String {
+    match serde_json::from_str(json) {
+        Ok(serde_json::Value::String(text)) => text,
+        Ok(serde_json::Value::Object(component)) => match component.get("text") {
+            Some(serde_json::Value::String(text)) => text.clone(),
+            _ => String::new(),
+        },
+        _ => json.to_string(),
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ScoreboardFile {
+    data: FileData,
+    #[serde(rename = "DataVersion")]
+    data_version: i32,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+struct FileData {
+    #[serde(default)]
+    objectives: Vec<FileObjective>,
+    #[serde(default)]
+    player_scores: Vec<FileScore>,
+    #[serde(default)]
+    teams: Vec<FileTeam>,
+    #[serde(default)]
+    display_slots: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct FileObjective {
+    name: String,
+    criteria_name: String,
+    display_name: String,
+    render_type: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct FileScore {
+    name: String,
+    objective: String,
+    score: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct FileTeam {
+    name: String,
+    display_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    team_color: Option<String>,
+    #[serde(default)]
+    member_name_prefix: String,
+    #[serde(default)]
+    member_name_suffix: String,
+    allow_friendly_fire: bool,
+    see_friendly_invisibles: bool,
+    name_tag_visibility: String,
+    death_message_visibility: String,
+    collision_rule: String,
+    #[serde(default)]
+    players: Vec<String>,
+}
+
+impl From<&ScoreboardData> for FileData {
+    fn from(data: &ScoreboardData) -> Self {
+        Self {
+            objectives: data
+                .objectives
+                .values()
+                .map(|objective| FileObjective {
+                    name: objective.name.clone(),
+                    criteria_name: objective.criterion.name(),
+                    display_name: to_json_text(&objective.display_name),
+                    render_type: objective.render_type.name().to_string(),
+                })
+                .collect(),
+            player_scores: data
+                .scores
+                .iter()
+                .flat_map(|(holder, scores)| {
+                    scores.iter().map(|(objective, score)| FileScore {
+                        name: holder.clone(),
+                        objective: objective.clone(),
+                        score: *score,
+                    })
+                })
+                .collect(),
+            teams: data
+                .teams
+                .values()
+                .map(|team| FileTeam {
+                    name: team.name.clone(),
+                    display_name: to_json_text(&team.display_name),
+                    team_color: team.color.map(|color| color.name().to_string()),
+                    member_name_prefix: to_json_text(&team.prefix),
+                    member_name_suffix: to_json_text(&team.suffix),
+                    allow_friendly_fire: team.friendly_fire,
+                    see_friendly_invisibles: team.see_friendly_invisibles,
+                    name_tag_visibility: team.name_tag_visibility.name().to_string(),
+                    death_message_visibility: team.death_message_visibility.name().to_string(),
+                    collision_rule: team.collision_rule.name().to_string(),
+                    players: team.members.iter().cloned().collect(),
+                })
+                .collect(),
+            display_slots: data
+                .display_slots
+                .iter()
+                .map(|(slot, objective)| (slot.name(), objective.clone()))
+                .collect(),
+        }
+    }
+}
+
+/// Objectives with criteria this server doesn't know get dropped, and everything pointing at them
+impl From<FileData> for ScoreboardData {
+    fn from(file: FileData) -> Self {
+        let mut data = Self::default();
+        for objective in file.objectives {
+            let Some(criterion) = Criterion::parse(&objective.criteria_name) else {
+                log::warn!(
+                    "Dropping objective {} with the unknown criterion {}",
+                    objective.name,
+                    objective.criteria_name
+                );
+                continue;
+            };
+            data.objectives.insert(
+                objective.name.clone(),
+                Objective {
+                    render_type: RenderType::from_name(&objective.render_type)
+                        .unwrap_or_else(|| criterion.default_render_type()),
+                    display_name: from_json_text(&objective.display_name),
+                    name: objective.name,
+                    criterion,
+                },
+            );
+        }
+        for score in file.player_scores {
+            if data.objectives.contains_key(&score.objective) {
+                data.scores
+                    .entry(score.name)
+                    .or_default()
+                    .insert(score.objective, score.score);
+            }
+        }
+        for team in file.teams {
+            let mut read = Team::new(
+                &team.name,
+                Some(from_json_text(&team.display_name).as_str()),
+            );
+            read.color = team.team_color.as_deref().and_then(NamedColor::from_name);
+            read.prefix = from_json_text(&team.member_name_prefix);
+            read.suffix = from_json_text(&team.member_name_suffix);
+            read.friendly_fire = team.allow_friendly_fire;
+            read.see_friendly_invisibles = team.see_friendly_invisibles;
+            read.name_tag_visibility =
+                Visibility::from_name(&team.name_tag_visibility).unwrap_or(Visibility::Always);
+            read.death_message_visibility =
+                Visibility::from_name(&team.death_message_visibility).unwrap_or(Visibility::Always);
+            read.collision_rule =
+                CollisionRule::from_name(&team.collision_rule).unwrap_or(CollisionRule::Always);
+            read.members = team.players.into_iter().collect();
+            data.teams.insert(team.name, read);
+        }
+        for (slot, objective) in file.display_slots {
+            if let Some(slot) = DisplaySlot::from_name(&slot) {
+                if data.objectives.contains_key(&objective) {
+                    data.display_slots.insert(slot, objective);
+                }
+            }
+        }
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestFolder;
+
+    #[test]
+    fn objectives_and_scores() {
+        let mut data = ScoreboardData::default();
+        data.add_objective("kills", "playerKillCount", Some("Kills"))
+            .unwrap();
+        assert_eq!(
+            data.add_objective("kills", "dummy", None),
+            Err(ScoreboardError::ObjectiveExists)
+        );
+        assert!(matches!(
+            data.add_objective("broken", "minecraft.unknown:stone", None),
+            Err(ScoreboardError::UnknownCriterion(_))
+        ));
+        data.add_objective("hp", "health", None).unwrap();
+        assert_eq!(
+            data.objective("hp").unwrap().render_type,
+            RenderType::Hearts
+        );
+        assert_eq!(
+            data.writable_objective("hp"),
+            Err(ScoreboardError::ReadOnly)
+        );
+
+        assert_eq!(data.set_score("Steve", "kills", 3), Ok(true));
+        assert_eq!(data.set_score("Steve", "kills", 3), Ok(false));
+        assert_eq!(data.add_score("Steve", "kills", 2), Ok(5));
+        assert_eq!(data.add_score("Alex", "kills", i32::MAX), Ok(i32::MAX));
+        assert_eq!(data.add_score("Alex", "kills", 1), Ok(i32::MAX));
+
+        data.set_display_slot(DisplaySlot::Sidebar, Some("kills"))
+            .unwrap();
+        data.remove_objective("kills").unwrap();
+        assert_eq!(data.score("Steve", "kills"), None);
+        assert_eq!(data.displayed(DisplaySlot::Sidebar), None);
+        assert_eq!(data.holders().count(), 0);
+    }
+
+    #[test]
+    fn teams() {
+        let mut data = ScoreboardData::default();
+        data.add_team("red", None).unwrap();
+        data.add_team("blue", Some("Blue Team")).unwrap();
+        assert_eq!(data.add_team("red", None), Err(ScoreboardError::TeamExists));
+        assert_eq!(data.join_team("red", "Steve"), Ok(None));
+        // A holder is in one team at most
+        assert_eq!(data.join_team("blue", "Steve"), Ok(Some("red".to_string())));
+        assert_eq!(data.team_of("Steve").unwrap().name, "blue");
+        assert!(data.team("red").unwrap().members.is_empty());
+        assert_eq!(data.leave_team("Steve"), Some("blue".to_string()));
+        assert_eq!(data.team_of("Steve"), None);
+
+        assert!(CollisionRule::PushOwnTeam.pushes(true));
+        assert!(!CollisionRule::PushOwnTeam.pushes(false));
+        assert_eq!(
+            DisplaySlot::from_name("sidebar.team.dark_red"),
+            Some(DisplaySlot::TeamSidebar(NamedColor::DarkRed))
+        );
+        assert_eq!(DisplaySlot::TeamSidebar(NamedColor::White).id(), 18);
+    }
+
+    #[test]
+    fn round_trip() {
+        let folder = TestFolder::new("scoreboard");
+        let folder = folder.path();
+        let mut data = ScoreboardData::default();
+        data.add_objective(
+            "mined",
+            "minecraft.mined:minecraft.stone",
+            Some("Stone \"mined\""),
+        )
+        .unwrap();
+        data.set_score("Steve", "mined", 42).unwrap();
+        data.set_display_slot(DisplaySlot::TeamSidebar(NamedColor::Gold), Some("mined"))
+            .unwrap();
+        data.add_team("gold", None).unwrap();
+        let team = data.team_mut("gold").unwrap();
+        team.color = Some(NamedColor::Gold);
+        team.prefix = "[Gold] ".to_string();
+        team.friendly_fire = false;
+        team.collision_rule = CollisionRule::PushOtherTeams;
+        data.join_team("gold", "Steve").unwrap();
+        data.write(folder).unwrap();
+
+        let read = ScoreboardData::read(folder).unwrap();
+        assert_eq!(read, data);
+        assert_eq!(
+            read.objective("mined").unwrap().criterion.name(),
+            "minecraft.mined:minecraft.stone"
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::storage;
+
+/// The folder inside of the main world which holds the statistics of every player, like in vanilla
+const STATS_FOLDER: &str = "stats";
+const DATA_VERSION: i32 = 3955;
+
+/// Blocks a player mined, by the id of the block
+pub const MINED: &str = "minecraft:mined";
+/// Items a player crafted, by the id of the item
+pub const CRAFTED: &str = "minecraft:crafted";
+/// Items a player used, e.g. placed blocks or eaten food
+pub const USED: &str = "minecraft:used";
+/// Tools and armor which broke while a player used them
+pub const BROKEN: &str = "minecraft:broken";
+pub const PICKED_UP: &str = "minecraft:picked_up";
+pub const DROPPED: &str = "minecraft:dropped";
+/// Entities a player killed, by the id of the entity type
+pub const KILLED: &str = "minecraft:killed";
+/// Entities which killed a player, by the id of the entity type
+pub const KILLED_BY: &str = "minecraft:killed_by";
+/// Everything else, by an id of the `minecraft:custom_stat` registry like `DEATHS`
+pub const CUSTOM: &str = "minecraft:custom";
+
+/// Ticks a player played, counted on every tick
+pub const PLAY_TIME: &str = "minecraft:play_time";
+pub const TOTAL_WORLD_TIME: &str = "minecraft:total_world_time";
+pub const TIME_SINCE_DEATH: &str = "minecraft:time_since_death";
+pub const TIME_SINCE_REST: &str = "minecraft:time_since_rest";
+pub const SNEAK_TIME: &str = "minecraft:sneak_time";
+pub const DEATHS: &str = "minecraft:deaths";
+pub const SLEEP_IN_BED: &str = "minecraft:sleep_in_bed";
+pub const LEAVE_GAME: &str = "minecraft:leave_game";
+pub const JUMP: &str = "minecraft:jump";
+pub const ENCHANT_ITEM: &str = "minecraft:enchant_item";
+/// Damage in tenths of a health point
+pub const DAMAGE_TAKEN: &str = "minecraft:damage_taken";
+pub const DAMAGE_DEALT: &str = "minecraft:damage_dealt";
+pub const PLAYER_KILLS: &str = "minecraft:player_kills";
+pub const MOB_KILLS: &str = "minecraft:mob_kills";
+/// Distances in centimeters
+pub const WALK_ONE_CM: &str = "minecraft:walk_one_cm";
+pub const SPRINT_ONE_CM: &str = "minecraft:sprint_one_cm";
+pub const CROUCH_ONE_CM: &str = "minecraft:crouch_one_cm";
+pub const FLY_ONE_CM: &str = "minecraft:fly_one_cm";
+pub const AVIATE_ONE_CM: &str = "minecraft:aviate_one_cm";
+
+#[derive(Error, Debug)]
+pub enum StatsError {
+    #[error("Io error: {0}")]
+    IoError(std::io::ErrorKind),
+    #[error("Failed to parse the statistics: {0}")]
+    ParsingError(serde_json::Error),
+}
+
+/// How often a player did something, stored in `stats/<uuid>.json` like in vanilla
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlayerStats {
+    /// The value of every statistic, by category and statistic, e.g. `MINED` and
+    /// `minecraft:stone`
+    #[serde(default)]
+    pub stats: HashMap<String, HashMap<String, i32>>,
+}
+
+#[derive(Serialize)]
+struct StatsFile<'a> {
+    stats: &'a HashMap<String, HashMap<String, i32>>,
+    #[serde(rename = "DataVersion")]
+    data_version: i32,
+}
+
+impl PlayerStats {
+    /// The value of a statistic, 0 if the player never did it
+    pub fn get(&self, category: &str, stat: &str) -> i32 {
+        self.stats
+            .get(category)
+            .and_then(|stats| stats.get(stat))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Replaces the value of a statistic
+    pub fn set(&mut self, category: &str, stat: &str, value: i32) {
+        self.stats
+            .entry(category.to_string())
+            .or_default()
+            .insert(stat.to_string(), value);
+    }
+
+    /// Adds `amount` to a statistic and returns the new value, it never goes past `i32::MAX`
+    pub fn increment(&mut self, category: &str, stat: &str, amount: i32) -> i32 {
+        let value = self
+            .stats
+            .entry(category.to_string())
+            .or_default()
+            .entry(stat.to_string())
+            .or_default();
+        *value = value.saturating_add(amount);
+        *value
+    }
+
+    /// Every statistic with its category and value
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str, i32)> {
+        self.stats.iter().flat_map(|(category, stats)| {
+            stats
+                .iter()
+                .map(|(stat, value)| (category.as_str(), stat.as_str(), *value))
+        })
+    }
+
+    /// Replaces every `{stat:<criterion>}` in `text` with the value of the statistic, the
+    /// criterion is written like in scoreboards, e.g. `{stat:minecraft.custom:minecraft.deaths}`
+    pub fn replace_placeholders(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find("{stat:") {
+            result.push_str(&rest[..start]);
+            let placeholder = &rest[start..];
+            let Some(end) = placeholder.find('}') else {
+                rest = placeholder;
+                break;
+            };
+            match parse_criterion(&placeholder["{stat:".len()..end]) {
+                Some((category, stat)) => result.push_str(&self.get(&category, &stat).to_string()),
+                None => result.push_str(&placeholder[..=end]),
+            }
+            rest = &placeholder[end + 1..];
+        }
+        result.push_str(rest);
+        result
+    }
+
+    /// Reads the statistics of a player from the main world in `root_folder`, empty if they
+    /// never played before
+    pub fn read(root_folder: &Path, uuid: &str) -> Result<Self, StatsError> {
+        match fs::read_to_string(stats_file(root_folder, uuid)) {
+            Ok(json) => serde_json::from_str(&json).map_err(StatsError::ParsingError),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(StatsError::IoError(err.kind())),
+        }
+    }
+
+    /// Writes the statistics of a player into the main world in `root_folder`, replacing the old
+    /// ones
+    pub fn write(&self, root_folder: &Path, uuid: &str) -> Result<(), StatsError> {
+        let json = serde_json::to_string(&StatsFile {
+            stats: &self.stats,
+            data_version: DATA_VERSION,
+        })
+        .map_err(StatsError::ParsingError)?;
+
+        let io_error = |err: std::io::Error| StatsError::IoError(err.kind());
+        storage::write_atomically(&stats_file(root_folder, uuid), json.as_bytes()).map_err(io_error)
+    }
+}
+
+/// The registry the statistics of a category are from
+pub fn category_registry(category: &str) -> Option<&'static str> {
+    match category {
+        MINED => Some("minecraft:block"),
+        CRAFTED | USED | BROKEN | PICKED_UP | DROPPED => Some("minecraft:item"),
+        KILLED | KILLED_BY => Some("minecraft:entity_type"),
+        CUSTOM => Some("minecraft:custom_stat"),
+        _ => None,
+    }
+}
+
+/// Parses a statistic written like a scoreboard criterion, e.g. `minecraft.mined:minecraft.stone`
+/// into its category and statistic
+pub fn parse_criterion(criterion: &str) -> Option<(String, String)> {
+    let (category, stat) = criterion.split_once(':')?;
+    let category = category.replacen('.', ":", 1);
+    category_registry(&category)?;
+    Some((category, stat.replacen('.', ":", 1)))
+}
+
+fn stats_file(root_folder: &Path, uuid: &str) -> PathBuf {
+    root_folder.join(STATS_FOLDER).join(format!("{uuid}.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{TestFolder, UUID};
+
+    #[test]
+    fn placeholders() {
+        let mut stats = PlayerStats::default();
+        stats.increment(CUSTOM, DEATHS, 3);
+        stats.increment(MINED, "minecraft:stone", 64);
+        assert_eq!(
+            stats.replace_placeholders(
+                "{stat:minecraft.custom:minecraft.deaths} deaths, \
+                 {stat:minecraft.mined:minecraft.stone} stone, {stat:unknown:x} {stat:"
+            ),
+            "3 deaths, 64 stone, {stat:unknown:x} {stat:"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let folder = TestFolder::new("stats");
+        let folder = folder.path();
+        let mut stats = PlayerStats::default();
+        stats.increment(CUSTOM, PLAY_TIME, 1200);
+        stats.set(KILLED, "minecraft:zombie", 2);
+        assert_eq!(stats.increment(CUSTOM, PLAY_TIME, i32::MAX), i32::MAX);
+        stats.write(folder, UUID).unwrap();
+
+        let read = PlayerStats::read(folder, UUID).unwrap();
+        let unknown = PlayerStats::read(folder, "unknown").unwrap();
+        assert_eq!(read, stats);
+        assert_eq!(read.get(KILLED, "minecraft:zombie"), 2);
+        assert_eq!(unknown, PlayerStats::default());
+    }
+}
@@ -0,0 +1,28 @@
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::Path,
+};
+
+use flate2::{write::GzEncoder, Compression};
+
+/// Writes the bytes next to `path` first and then renames them over it, so a crash while
+/// saving never leaves a broken file. The folders are created if they don't exist yet
+pub fn write_atomically(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if let Some(folder) = path.parent() {
+        fs::create_dir_all(folder)?;
+    }
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push("_tmp");
+    let mut file = File::create(&temp_path)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    fs::rename(temp_path, path)
+}
+
+/// Compresses the bytes like vanilla compresses its `.dat` files
+pub fn gzip(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes)?;
+    encoder.finish()
+}
@@ -0,0 +1,44 @@
+//! Helpers shared by the tests of several modules
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    block::{BlockId, BlockState},
+    global_registry::{find_protocol_id, ITEM_REGISTRY},
+    item::ItemStack,
+};
+
+/// The UUID the tests save player files under
+pub const UUID: &str = "069a79f4-44e9-4726-a5be-fca90e38aaf5";
+
+/// A folder of its own for one test, it is removed once the test is done
+pub struct TestFolder(PathBuf);
+
+impl TestFolder {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("pumpkin-{name}-{}", std::process::id()));
+        // Left over from a test which panicked
+        let _ = std::fs::remove_dir_all(&path);
+        Self(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TestFolder {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// The default state of a block, e.g. `minecraft:stone`
+pub fn block(registry_id: &str) -> BlockId {
+    BlockState::new(registry_id, None).unwrap().into()
+}
+
+/// A single item, e.g. `minecraft:diamond_sword`
+pub fn item(registry_id: &str) -> ItemStack {
+    ItemStack::new(1, find_protocol_id(ITEM_REGISTRY, registry_id).unwrap())
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use pumpkin_core::math::vector2::Vector2;
+
+/// Why a chunk is kept loaded
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TicketType {
+    /// A player sees the chunk
+    Player,
+    /// The chunk was force loaded with `/forceload`
+    Forced,
+    /// A portal is being searched or built in the chunk
+    Portal,
+    /// A plugin needs the chunk
+    Plugin,
+    /// The chunk is near the spawn of its world
+    Spawn,
+}
+
+/// Counts the tickets of every chunk. A chunk without tickets may be unloaded
+#[derive(Default)]
+pub struct ChunkTickets {
+    tickets: HashMap<Vector2<i32>, HashMap<TicketType, u32>>,
+}
+
+impl ChunkTickets {
+    pub fn add(&mut self, at: Vector2<i32>, ticket: TicketType) {
+        *self
+            .tickets
+            .entry(at)
+            .or_default()
+            .entry(ticket)
+            .or_default() += 1;
+    }
+
+    /// Removes a ticket which was added before, removing a ticket the chunk doesn't have does nothing
+    pub fn remove(&mut self, at: Vector2<i32>, ticket: TicketType) {
+        let Some(tickets) = self.tickets.get_mut(&at) else {
+            return;
+        };
+        if let Some(count) = tickets.get_mut(&ticket) {
+            *count -= 1;
+            if *count == 0 {
+                tickets.remove(&ticket);
+            }
+        }
+        if tickets.is_empty() {
+            self.tickets.remove(&at);
+        }
+    }
+
+    /// How many tickets of a type a chunk has
+    pub fn count(&self, at: Vector2<i32>, ticket: TicketType) -> u32 {
+        self.tickets
+            .get(&at)
+            .and_then(|tickets| tickets.get(&ticket))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Whether anything keeps a chunk loaded
+    pub fn is_needed(&self, at: Vector2<i32>) -> bool {
+        self.tickets.contains_key(&at)
+    }
+
+    /// All chunks with at least one ticket of a type
+    pub fn chunks_with(&self, ticket: TicketType) -> Vec<Vector2<i32>> {
+        self.tickets
+            .iter()
+            .filter(|(_, tickets)| tickets.contains_key(&ticket))
+            .map(|(at, _)| *at)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_tickets() {
+        let mut tickets = ChunkTickets::default();
+        let at = Vector2::new(1, -2);
+        tickets.add(at, TicketType::Player);
+        tickets.add(at, TicketType::Player);
+        tickets.add(at, TicketType::Forced);
+        assert_eq!(tickets.count(at, TicketType::Player), 2);
+
+        tickets.remove(at, TicketType::Player);
+        tickets.remove(at, TicketType::Forced);
+        assert!(tickets.is_needed(at));
+        tickets.remove(at, TicketType::Player);
+        assert!(!tickets.is_needed(at));
+    }
+
+    #[test]
+    fn ignores_missing_tickets() {
+        let mut tickets = ChunkTickets::default();
+        let at = Vector2::new(0, 0);
+        tickets.remove(at, TicketType::Plugin);
+        tickets.add(at, TicketType::Plugin);
+        tickets.remove(at, TicketType::Player);
+        assert_eq!(tickets.count(at, TicketType::Plugin), 1);
+        assert!(tickets.chunks_with(TicketType::Player).is_empty());
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::HashSet;
+
+use pumpkin_core::{
+    math::{vector2::Vector2, vector3::Vector3},
+    random::{xoroshiro128::Xoroshiro, RandomImpl},
+};
+use pumpkin_macros::block;
+
+use crate::{biome::Biome, block::BlockId, chunk::ChunkData};
+
+use super::{get_block, set_block, BlockBox, FeatureTerrain};
+
+/// One in this many chunks has a lake
+const RARITY: i32 = 24;
+
+/// A pond sunk into the surface
+pub struct Lake {
+    columns: HashSet<(i32, i32)>,
+    /// The blocks replaced by water or air
+    blocks: Vec<(Vector3<i32>, BlockId)>,
+    /// The blocks right below the water, grass there turns into dirt
+    floor: Vec<Vector3<i32>>,
+}
+
+impl Lake {
+    /// The lake of a chunk, if it has one
+    pub fn generate(
+        random: &mut Xoroshiro,
+        chunk: Vector2<i32>,
+        terrain: &impl FeatureTerrain,
+    ) -> Option<Self> {
+        if random.next_bounded_i32(RARITY) != 0 {
+            return None;
+        }
+        let center_x = chunk.x * 16 + random.next_bounded_i32(16);
+        let center_z = chunk.z * 16 + random.next_bounded_i32(16);
+        let radius_x = 3 + random.next_bounded_i32(4);
+        let radius_z = 3 + random.next_bounded_i32(4);
+        let depth = 2 + random.next_bounded_i32(3);
+        if matches!(
+            terrain.biome_at(center_x, center_z),
+            Biome::Desert | Biome::Badlands
+        ) {
+            return None;
+        }
+
+        // The water stays below the lowest ground around the lake, so it doesn't spill
+        let level = (-radius_x - 1..=radius_x + 1)
+            .flat_map(|x| (-radius_z - 1..=radius_z + 1).map(move |z| (x, z)))
+            .map(|(x, z)| terrain.surface_height(center_x + x, center_z + z))
+            .min()?;
+        if level <= terrain.sea_level() {
+            return None;
+        }
+
+        let water: BlockId = block!("minecraft:water").into();
+        let air: BlockId = block!("minecraft:air").into();
+        let mut lake = Self {
+            columns: HashSet::new(),
+            blocks: Vec::new(),
+            floor: Vec::new(),
+        };
+        for x in -radius_x..=radius_x {
+            for z in -radius_z..=radius_z {
+                let distance = (f64::from(x) / f64::from(radius_x)).powi(2)
+                    + (f64::from(z) / f64::from(radius_z)).powi(2);
+                if distance > 1.0 {
+                    continue;
+                }
+                let (x, z) = (center_x + x, center_z + z);
+                // Deepest in the middle
+                let column_depth = ((f64::from(depth) * (1.0 - distance)).ceil() as i32).max(1);
+                let bottom = level - column_depth + 1;
+                for y in bottom..=level {
+                    lake.blocks.push((Vector3::new(x, y, z), water));
+                }
+                // Including the snow on top
+                for y in level + 1..=terrain.surface_height(x, z) + 1 {
+                    lake.blocks.push((Vector3::new(x, y, z), air));
+                }
+                lake.floor.push(Vector3::new(x, bottom - 1, z));
+                lake.columns.insert((x, z));
+            }
+        }
+        Some(lake)
+    }
+
+    pub fn contains_column(&self, x: i32, z: i32) -> bool {
+        self.columns.contains(&(x, z))
+    }
+
+    /// Places the part of the lake inside of a chunk
+    pub fn place(&self, chunk: &mut ChunkData, chunk_box: &BlockBox) {
+        for (position, block) in &self.blocks {
+            if chunk_box.contains(*position) {
+                set_block(chunk, *position, *block);
+            }
+        }
+        let grass_block: BlockId = block!("minecraft:grass_block").into();
+        for position in &self.floor {
+            if chunk_box.contains(*position) && get_block(chunk, *position) == grass_block {
+                set_block(chunk, *position, block!("minecraft:dirt").into());
+            }
+        }
+    }
+}
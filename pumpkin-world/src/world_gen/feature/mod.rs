@@ -0,0 +1,192 @@
+//! Features decorate the terrain of a chunk after it was generated: lakes, ore veins, trees and
+//! plants.
+//!
+//! Lakes, ore veins and trees can reach into the neighbouring chunks. Every chunk places the parts
+//! of its own features and the features of its neighbours which are inside of it. Where a feature
+//! goes only depends on the seed and the shape of the terrain, so the order in which chunks are
+//! generated doesn't matter.
+
+mod lake;
+mod ore;
+mod tree;
+
+use std::collections::HashMap;
+
+use pumpkin_core::{
+    math::{vector2::Vector2, vector3::Vector3},
+    random::{xoroshiro128::Xoroshiro, RandomImpl},
+};
+use pumpkin_macros::block;
+
+use crate::{
+    biome::Biome, block::BlockId, chunk::ChunkData, coordinates::ChunkRelativeBlockCoordinates,
+};
+
+use super::structure::BlockBox;
+use lake::Lake;
+
+/// Different for every kind of feature, so they don't use the same random numbers
+const LAKE_SALT: u64 = 10_000;
+const ORE_SALT: u64 = 20_000;
+const TREE_SALT: u64 = 30_000;
+const VEGETATION_SALT: u64 = 40_000;
+
+/// What features need to know about the terrain, also of chunks which were not generated yet
+pub trait FeatureTerrain {
+    /// The height of the highest solid block in a column
+    fn surface_height(&self, x: i32, z: i32) -> i32;
+
+    fn biome_at(&self, x: i32, z: i32) -> Biome;
+
+    /// Water fills everything below this height
+    fn sea_level(&self) -> i32;
+}
+
+/// Decorates chunks with lakes, ore veins, trees and plants
+pub struct FeatureGenerator {
+    seed: u64,
+}
+
+impl FeatureGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    /// The random numbers for one kind of feature in a chunk, like vanilla's decoration seed
+    fn random(&self, chunk: Vector2<i32>, salt: u64) -> Xoroshiro {
+        Xoroshiro::from_seed(
+            (chunk.x as u64)
+                .wrapping_mul(341_873_128_712)
+                .wrapping_add((chunk.z as u64).wrapping_mul(132_897_987_541))
+                .wrapping_add(self.seed)
+                .wrapping_add(salt),
+        )
+    }
+
+    /// Places the features of a chunk and the parts of its neighbours' features reaching into it
+    pub fn populate(&self, chunk: &mut ChunkData, terrain: &impl FeatureTerrain) {
+        let at = chunk.position;
+        let chunk_box = BlockBox::of_chunk(at);
+        let neighbours: Vec<_> = (-1..=1)
+            .flat_map(|x| (-1..=1).map(move |z| Vector2::new(at.x + x, at.z + z)))
+            .collect();
+
+        // Trees of the neighbours must not stand in lakes of their neighbours
+        let mut lakes = HashMap::new();
+        for x in -2..=2 {
+            for z in -2..=2 {
+                let lake_chunk = Vector2::new(at.x + x, at.z + z);
+                let lake =
+                    Lake::generate(&mut self.random(lake_chunk, LAKE_SALT), lake_chunk, terrain);
+                lakes.insert(lake_chunk, lake);
+            }
+        }
+
+        for neighbour in &neighbours {
+            if let Some(Some(lake)) = lakes.get(neighbour) {
+                lake.place(chunk, &chunk_box);
+            }
+        }
+        for neighbour in &neighbours {
+            for vein in ore::veins(&mut self.random(*neighbour, ORE_SALT), *neighbour) {
+                vein.place(chunk, &chunk_box);
+            }
+        }
+        for neighbour in &neighbours {
+            for tree in tree::trees(&mut self.random(*neighbour, TREE_SALT), *neighbour, terrain) {
+                let in_lake = (-1..=1)
+                    .flat_map(|x| (-1..=1).map(move |z| (x, z)))
+                    .filter_map(|(x, z)| {
+                        lakes
+                            .get(&Vector2::new(neighbour.x + x, neighbour.z + z))?
+                            .as_ref()
+                    })
+                    .any(|lake| lake.contains_column(tree.trunk.x, tree.trunk.z));
+                if !in_lake {
+                    tree.place(chunk, &chunk_box);
+                }
+            }
+        }
+        self.place_vegetation(chunk, terrain);
+        chunk.blocks.heightmap = chunk.blocks.calculate_heightmap();
+    }
+
+    /// Grass, ferns, flowers and dead bushes. They only take one block, so every chunk places its
+    /// own after the bigger features
+    fn place_vegetation(&self, chunk: &mut ChunkData, terrain: &impl FeatureTerrain) {
+        let mut random = self.random(chunk.position, VEGETATION_SALT);
+        let grass_block: BlockId = block!("minecraft:grass_block").into();
+        let sand: BlockId = block!("minecraft:sand").into();
+        for x in 0..16 {
+            for z in 0..16 {
+                let world_x = chunk.position.x * 16 + x;
+                let world_z = chunk.position.z * 16 + z;
+                let surface = terrain.surface_height(world_x, world_z);
+                if surface < terrain.sea_level() {
+                    continue;
+                }
+                let ground = Vector3::new(world_x, surface, world_z);
+                let above = Vector3::new(world_x, surface + 1, world_z);
+                if !get_block(chunk, above).is_air() {
+                    continue;
+                }
+                let biome = terrain.biome_at(world_x, world_z);
+                let roll = random.next_bounded_i32(100);
+                let plant = match get_block(chunk, ground) {
+                    block if block == grass_block => grass_plant(biome, roll),
+                    block if block == sand && biome == Biome::Desert && roll == 0 => {
+                        Some(block!("minecraft:dead_bush").into())
+                    }
+                    _ => None,
+                };
+                if let Some(plant) = plant {
+                    set_block(chunk, above, plant);
+                }
+            }
+        }
+    }
+}
+
+/// The plant growing on a grass block, `roll` is a random number from 0 to 99
+fn grass_plant(biome: Biome, roll: i32) -> Option<BlockId> {
+    let grass_chance = match biome {
+        Biome::Plains | Biome::Savanna => 25,
+        Biome::Jungle | Biome::Swamp => 20,
+        Biome::Forest | Biome::BirchForest | Biome::Taiga | Biome::SnowyTaiga => 8,
+        _ => 4,
+    };
+    let plant = match roll {
+        0 => block!("minecraft:dandelion"),
+        1 => block!("minecraft:poppy"),
+        2 if matches!(biome, Biome::Forest | Biome::BirchForest) => {
+            block!("minecraft:lily_of_the_valley")
+        }
+        roll if roll < 3 + grass_chance => {
+            if matches!(biome, Biome::Taiga | Biome::SnowyTaiga | Biome::Jungle) && roll % 2 == 0 {
+                block!("minecraft:fern")
+            } else {
+                block!("minecraft:short_grass")
+            }
+        }
+        _ => return None,
+    };
+    Some(plant.into())
+}
+
+fn relative(position: Vector3<i32>) -> ChunkRelativeBlockCoordinates {
+    ChunkRelativeBlockCoordinates {
+        x: (position.x.rem_euclid(16) as u8).into(),
+        y: position.y.into(),
+        z: (position.z.rem_euclid(16) as u8).into(),
+    }
+}
+
+/// Gets a block of the chunk, the position has to be inside of it
+fn get_block(chunk: &ChunkData, position: Vector3<i32>) -> BlockId {
+    chunk.blocks.get_block(relative(position))
+}
+
+/// Sets a block of the chunk, the position has to be inside of it
+fn set_block(chunk: &mut ChunkData, position: Vector3<i32>, block: BlockId) {
+    chunk.blocks.set_block(relative(position), block);
+}
@@ -0,0 +1,144 @@
+use pumpkin_core::{
+    math::{vector2::Vector2, vector3::Vector3},
+    random::{xoroshiro128::Xoroshiro, RandomImpl},
+};
+use pumpkin_macros::block;
+
+use crate::{
+    block::{block_state::BlockState, BlockId},
+    chunk::ChunkData,
+};
+
+use super::{get_block, set_block, BlockBox};
+
+/// An ore which is spread over the world in veins
+struct OreType {
+    /// The ore replacing stone, the deepslate variant replaces deepslate
+    name: &'static str,
+    veins_per_chunk: i32,
+    min_y: i32,
+    max_y: i32,
+    /// How many blocks a vein has at most
+    size: i32,
+}
+
+/// Roughly the heights and amounts of vanilla
+const ORES: [OreType; 7] = [
+    OreType {
+        name: "coal_ore",
+        veins_per_chunk: 20,
+        min_y: 0,
+        max_y: 192,
+        size: 17,
+    },
+    OreType {
+        name: "iron_ore",
+        veins_per_chunk: 10,
+        min_y: -64,
+        max_y: 72,
+        size: 9,
+    },
+    OreType {
+        name: "copper_ore",
+        veins_per_chunk: 6,
+        min_y: -16,
+        max_y: 112,
+        size: 10,
+    },
+    OreType {
+        name: "gold_ore",
+        veins_per_chunk: 4,
+        min_y: -64,
+        max_y: 32,
+        size: 9,
+    },
+    OreType {
+        name: "redstone_ore",
+        veins_per_chunk: 4,
+        min_y: -64,
+        max_y: 16,
+        size: 8,
+    },
+    OreType {
+        name: "lapis_ore",
+        veins_per_chunk: 2,
+        min_y: -64,
+        max_y: 64,
+        size: 7,
+    },
+    OreType {
+        name: "diamond_ore",
+        veins_per_chunk: 1,
+        min_y: -64,
+        max_y: 16,
+        size: 8,
+    },
+];
+
+/// A cluster of ore replacing stone and deepslate
+pub struct OreVein {
+    ore: BlockId,
+    deepslate_ore: BlockId,
+    blocks: Vec<Vector3<i32>>,
+}
+
+impl OreVein {
+    /// Places the part of the vein inside of a chunk
+    pub fn place(&self, chunk: &mut ChunkData, chunk_box: &BlockBox) {
+        let stone: BlockId = block!("minecraft:stone").into();
+        let deepslate: BlockId = block!("minecraft:deepslate").into();
+        for position in &self.blocks {
+            if !chunk_box.contains(*position) {
+                continue;
+            }
+            let old = get_block(chunk, *position);
+            if old == stone {
+                set_block(chunk, *position, self.ore);
+            } else if old == deepslate {
+                set_block(chunk, *position, self.deepslate_ore);
+            }
+        }
+    }
+}
+
+fn ore_block(name: &str) -> Option<BlockId> {
+    BlockState::new(&format!("minecraft:{name}"), None)
+        .ok()
+        .map(Into::into)
+}
+
+/// The ore veins starting in a chunk
+pub fn veins(random: &mut Xoroshiro, chunk: Vector2<i32>) -> Vec<OreVein> {
+    let mut veins = Vec::new();
+    for ore_type in &ORES {
+        let (Some(ore), Some(deepslate_ore)) = (
+            ore_block(ore_type.name),
+            ore_block(&format!("deepslate_{}", ore_type.name)),
+        ) else {
+            continue;
+        };
+        for _ in 0..ore_type.veins_per_chunk {
+            let mut position = Vector3::new(
+                chunk.x * 16 + random.next_bounded_i32(16),
+                random.next_inbetween_i32(ore_type.min_y, ore_type.max_y),
+                chunk.z * 16 + random.next_bounded_i32(16),
+            );
+            // The vein wanders a little from where it starts
+            let mut blocks = Vec::with_capacity(ore_type.size as usize);
+            for _ in 0..=random.next_bounded_i32(ore_type.size) {
+                blocks.push(position);
+                position = position.add(&Vector3::new(
+                    random.next_inbetween_i32(-1, 1),
+                    random.next_inbetween_i32(-1, 1),
+                    random.next_inbetween_i32(-1, 1),
+                ));
+            }
+            veins.push(OreVein {
+                ore,
+                deepslate_ore,
+                blocks,
+            });
+        }
+    }
+    veins
+}
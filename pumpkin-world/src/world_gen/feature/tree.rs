@@ -0,0 +1,196 @@
+use pumpkin_core::{
+    math::{vector2::Vector2, vector3::Vector3},
+    random::{xoroshiro128::Xoroshiro, RandomImpl},
+};
+use pumpkin_macros::block;
+
+use crate::{
+    biome::Biome,
+    block::{block_state::BlockState, BlockId},
+    chunk::ChunkData,
+};
+
+use super::{get_block, set_block, BlockBox, FeatureTerrain};
+
+/// Leaves further away from a log than this decay, like in vanilla
+const MAX_LEAF_DISTANCE: i32 = 7;
+
+#[derive(Clone, Copy)]
+enum TreeShape {
+    /// A round crown on a short trunk
+    Blob,
+    /// Layers of leaves getting smaller towards the top
+    Cone,
+}
+
+/// The kind of trees growing in a biome
+struct TreeKind {
+    wood: &'static str,
+    shape: TreeShape,
+    min_height: i32,
+    max_height: i32,
+}
+
+impl TreeKind {
+    const fn new(wood: &'static str, shape: TreeShape, min_height: i32, max_height: i32) -> Self {
+        Self {
+            wood,
+            shape,
+            min_height,
+            max_height,
+        }
+    }
+}
+
+/// The trees of a biome and how many grow in a chunk, in tenths
+fn biome_trees(biome: Biome) -> Option<(TreeKind, i32)> {
+    Some(match biome {
+        Biome::Forest => (TreeKind::new("oak", TreeShape::Blob, 4, 6), 60),
+        Biome::BirchForest => (TreeKind::new("birch", TreeShape::Blob, 5, 7), 60),
+        Biome::Taiga => (TreeKind::new("spruce", TreeShape::Cone, 6, 9), 50),
+        Biome::SnowyTaiga => (TreeKind::new("spruce", TreeShape::Cone, 6, 9), 30),
+        Biome::Jungle => (TreeKind::new("jungle", TreeShape::Blob, 5, 9), 80),
+        Biome::Swamp => (TreeKind::new("oak", TreeShape::Blob, 4, 6), 20),
+        Biome::Savanna => (TreeKind::new("acacia", TreeShape::Blob, 4, 6), 5),
+        Biome::Plains => (TreeKind::new("oak", TreeShape::Blob, 4, 6), 1),
+        Biome::SnowyPlains => (TreeKind::new("spruce", TreeShape::Cone, 6, 9), 1),
+        _ => return None,
+    })
+}
+
+/// A tree growing on the surface
+pub struct Tree {
+    /// The lowest log
+    pub trunk: Vector3<i32>,
+    log: BlockId,
+    height: i32,
+    leaves: Vec<(Vector3<i32>, BlockId)>,
+}
+
+impl Tree {
+    fn grow(random: &mut Xoroshiro, trunk: Vector3<i32>, kind: &TreeKind) -> Option<Self> {
+        let log: BlockId = BlockState::new(&format!("minecraft:{}_log", kind.wood), None)
+            .ok()?
+            .into();
+        let leaves: BlockId = BlockState::new(&format!("minecraft:{}_leaves", kind.wood), None)
+            .ok()?
+            .into();
+        let height = random.next_inbetween_i32(kind.min_height, kind.max_height);
+        let mut placed_leaves = Vec::new();
+        let mut add_leaf = |x: i32, y: i32, z: i32| {
+            if x == 0 && z == 0 && y < height {
+                return;
+            }
+            // The closest log is always the one of the trunk at the same height, or the top one
+            let distance = x.abs() + z.abs() + (y - (height - 1)).max(0);
+            if let Some(leaf) = leaves.with_property(
+                "distance",
+                &distance.clamp(1, MAX_LEAF_DISTANCE).to_string(),
+            ) {
+                placed_leaves.push((trunk.add(&Vector3::new(x, y, z)), leaf));
+            }
+        };
+        match kind.shape {
+            TreeShape::Blob => {
+                for y in height - 3..=height {
+                    let top = y > height - 2;
+                    let radius = if top { 1 } else { 2 };
+                    for x in -radius..=radius {
+                        for z in -radius..=radius {
+                            let corner = x.abs() == radius && z.abs() == radius;
+                            if corner && (top || random.next_bool()) {
+                                continue;
+                            }
+                            add_leaf(x, y, z);
+                        }
+                    }
+                }
+            }
+            TreeShape::Cone => {
+                for y in 2..=height {
+                    let from_top = height - y;
+                    let radius = if from_top == 0 {
+                        0
+                    } else if from_top % 2 == 1 && from_top >= 3 {
+                        2
+                    } else {
+                        1
+                    };
+                    for x in -radius..=radius {
+                        for z in -radius..=radius {
+                            if radius > 0 && x.abs() == radius && z.abs() == radius {
+                                continue;
+                            }
+                            add_leaf(x, y, z);
+                        }
+                    }
+                }
+            }
+        }
+        Some(Self {
+            trunk,
+            log,
+            height,
+            leaves: placed_leaves,
+        })
+    }
+
+    /// Places the part of the tree inside of a chunk. Leaves only replace air and plants, the
+    /// trunk also replaces the leaves of other trees
+    pub fn place(&self, chunk: &mut ChunkData, chunk_box: &BlockBox) {
+        for (position, leaf) in &self.leaves {
+            if chunk_box.contains(*position) && get_block(chunk, *position).is_replaceable() {
+                set_block(chunk, *position, *leaf);
+            }
+        }
+        if !chunk_box.contains(self.trunk) {
+            return;
+        }
+        for y in 0..self.height {
+            let position = self.trunk.add(&Vector3::new(0, y, 0));
+            let old = get_block(chunk, position);
+            if old.is_replaceable() || old.category() == Some("minecraft:leaves") {
+                set_block(chunk, position, self.log);
+            }
+        }
+        // Trees turn the grass below them into dirt, like in vanilla
+        let below = self.trunk.add(&Vector3::new(0, -1, 0));
+        if get_block(chunk, below) == BlockId::from(block!("minecraft:grass_block")) {
+            set_block(chunk, below, block!("minecraft:dirt").into());
+        }
+    }
+}
+
+/// The trees growing in a chunk
+pub fn trees(
+    random: &mut Xoroshiro,
+    chunk: Vector2<i32>,
+    terrain: &impl FeatureTerrain,
+) -> Vec<Tree> {
+    let Some((_, per_chunk)) = biome_trees(terrain.biome_at(chunk.x * 16 + 8, chunk.z * 16 + 8))
+    else {
+        return Vec::new();
+    };
+    // Whole trees for every ten tenths, the rest is a chance for one more
+    let mut count = per_chunk / 10;
+    if random.next_bounded_i32(10) < per_chunk % 10 {
+        count += 1;
+    }
+    let mut trees = Vec::new();
+    for _ in 0..count {
+        let x = chunk.x * 16 + random.next_bounded_i32(16);
+        let z = chunk.z * 16 + random.next_bounded_i32(16);
+        let surface = terrain.surface_height(x, z);
+        if surface < terrain.sea_level() {
+            continue;
+        }
+        // The biome may change within the chunk, there are no trees on beaches or in deserts
+        let Some((kind, _)) = biome_trees(terrain.biome_at(x, z)) else {
+            continue;
+        };
+        if let Some(tree) = Tree::grow(random, Vector3::new(x, surface + 1, z), &kind) {
+            trees.push(tree);
+        }
+    }
+    trees
+}
@@ -11,15 +11,20 @@ pub trait GeneratorInit {
     fn new(seed: Seed) -> Self;
 }
 
-pub trait WorldGenerator: Sync + Send {
-    fn generate_chunk(&self, at: Vector2<i32>) -> ChunkData;
+/// Generates new chunks. Plugins can implement this to provide their own generators,
+/// e.g. for void or skyblock worlds
+pub trait ChunkGenerator: Sync + Send {
+    /// Generates the blocks and biomes of a chunk
+    fn generate_terrain(&self, at: Vector2<i32>) -> ChunkData;
+
+    /// Adds features like trees and ores to a chunk after its terrain was generated
+    fn populate(&self, _chunk: &mut ChunkData) {}
 }
 
 pub(crate) trait BiomeGenerator: Sync + Send {
     fn generate_biome(&self, at: XZBlockCoordinates) -> Biome;
 }
 
-#[expect(dead_code)]
 pub(crate) trait TerrainGenerator: Sync + Send {
     fn prepare_chunk(&self, at: &Vector2<i32>);
 
@@ -1,14 +1,18 @@
+use std::collections::HashMap;
+
 use noise::{NoiseFn, Perlin};
 use pumpkin_core::math::vector2::Vector2;
 
 use crate::{
-    chunk::{ChunkBlocks, ChunkData},
+    chunk::{ChunkBiomes, ChunkBlocks, ChunkData, ChunkLight},
     coordinates::{ChunkRelativeBlockCoordinates, ChunkRelativeXZBlockCoordinates},
-    WORLD_LOWEST_Y,
+    WORLD_LOWEST_Y, WORLD_MAX_Y,
 };
 
 use super::{
-    generator::{BiomeGenerator, GeneratorInit, PerlinTerrainGenerator, WorldGenerator},
+    generator::{
+        BiomeGenerator, ChunkGenerator, GeneratorInit, PerlinTerrainGenerator, TerrainGenerator,
+    },
     Seed,
 };
 
@@ -32,9 +36,10 @@ impl<B: BiomeGenerator + GeneratorInit, T: PerlinTerrainGenerator + GeneratorIni
     }
 }
 
-impl<B: BiomeGenerator, T: PerlinTerrainGenerator> WorldGenerator for GenericGenerator<B, T> {
-    fn generate_chunk(&self, at: Vector2<i32>) -> ChunkData {
+impl<B: BiomeGenerator, T: PerlinTerrainGenerator> ChunkGenerator for GenericGenerator<B, T> {
+    fn generate_terrain(&self, at: Vector2<i32>) -> ChunkData {
         let mut blocks = ChunkBlocks::default();
+        let mut biomes = ChunkBiomes::default();
         self.terrain_generator.prepare_chunk(&at, &self.perlin);
         let noise_value = self.perlin.get([at.x as f64 / 16.0, at.z as f64 / 16.0]);
 
@@ -51,6 +56,9 @@ impl<B: BiomeGenerator, T: PerlinTerrainGenerator> WorldGenerator for GenericGen
                     }
                     .with_chunk_coordinates(at),
                 );
+                if x % 4 == 0 && z % 4 == 0 {
+                    biomes.set_column(x as usize / 4, z as usize / 4, biome);
+                }
 
                 // Iterate from the highest block to the lowest, in order to minimize the heightmap updates
                 for y in (WORLD_LOWEST_Y..chunk_height as i16).rev() {
@@ -76,46 +84,73 @@ impl<B: BiomeGenerator, T: PerlinTerrainGenerator> WorldGenerator for GenericGen
 
         ChunkData {
             blocks,
+            biomes,
             position: at,
+            block_entities: HashMap::new(),
+            light: ChunkLight::default(),
         }
     }
 }
 
-// TODO: implement static terrain generator
-/*
-fn generate_chunk(&mut self, at: Vector2<i32>) -> ChunkData {
-    let mut blocks = ChunkBlocks::default();
-    self.terrain_generator.prepare_chunk(&at, &self.perlin);
-    for x in 0..16u8 {
-        for z in 0..16u8 {
-            let biome = self.biome_generator.generate_biome(
-                ChunkRelativeXZBlockCoordinates {
-                    x: x.into(),
-                    z: z.into(),
-                }
-                .with_chunk_coordinates(at),
-            );
-
-            // Iterate from the highest block to the lowest, in order to minimize the heightmap updates
-            for y in (WORLD_LOWEST_Y..WORLD_MAX_Y).rev() {
-                let coordinates = ChunkRelativeBlockCoordinates {
-                    x: x.into(),
-                    y: y.into(),
-                    z: z.into(),
-                };
-
-                blocks.set_block(
-                    coordinates,
-                    self.terrain_generator
-                        .generate_block(coordinates.with_chunk_coordinates(at), biome),
+/// Generates chunks whose terrain doesn't depend on any noise, e.g. superflat worlds
+pub struct StaticGenerator<B: BiomeGenerator, T: TerrainGenerator> {
+    pub(crate) biome_generator: B,
+    pub(crate) terrain_generator: T,
+}
+
+impl<B: BiomeGenerator + GeneratorInit, T: TerrainGenerator + GeneratorInit> GeneratorInit
+    for StaticGenerator<B, T>
+{
+    fn new(seed: Seed) -> Self {
+        Self {
+            biome_generator: B::new(seed),
+            terrain_generator: T::new(seed),
+        }
+    }
+}
+
+impl<B: BiomeGenerator, T: TerrainGenerator> ChunkGenerator for StaticGenerator<B, T> {
+    fn generate_terrain(&self, at: Vector2<i32>) -> ChunkData {
+        let mut blocks = ChunkBlocks::default();
+        let mut biomes = ChunkBiomes::default();
+        self.terrain_generator.prepare_chunk(&at);
+        for x in 0..16u8 {
+            for z in 0..16u8 {
+                let biome = self.biome_generator.generate_biome(
+                    ChunkRelativeXZBlockCoordinates {
+                        x: x.into(),
+                        z: z.into(),
+                    }
+                    .with_chunk_coordinates(at),
                 );
+                if x % 4 == 0 && z % 4 == 0 {
+                    biomes.set_column(x as usize / 4, z as usize / 4, biome);
+                }
+
+                for y in WORLD_LOWEST_Y..WORLD_MAX_Y {
+                    let coordinates = ChunkRelativeBlockCoordinates {
+                        x: x.into(),
+                        y: y.into(),
+                        z: z.into(),
+                    };
+
+                    blocks.set_block_no_heightmap_update(
+                        coordinates,
+                        self.terrain_generator
+                            .generate_block(coordinates.with_chunk_coordinates(at), biome)
+                            .into(),
+                    );
+                }
             }
         }
-    }
+        blocks.heightmap = blocks.calculate_heightmap();
 
-    ChunkData {
-        blocks,
-        position: at,
+        ChunkData {
+            blocks,
+            biomes,
+            position: at,
+            block_entities: HashMap::new(),
+            light: ChunkLight::default(),
+        }
     }
 }
-*/
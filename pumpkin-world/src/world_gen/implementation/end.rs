@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use noise::{Fbm, MultiFractal, NoiseFn, Perlin};
+use pumpkin_core::math::vector2::Vector2;
+use pumpkin_macros::block;
+
+use crate::{
+    biome::Biome,
+    chunk::{ChunkBiomes, ChunkBlocks, ChunkData, ChunkLight},
+    coordinates::ChunkRelativeBlockCoordinates,
+    world_gen::{
+        generator::{ChunkGenerator, GeneratorInit},
+        Seed,
+    },
+};
+
+/// The top of the main island, before the noise is added
+const ISLAND_TOP: f64 = 60.0;
+/// How far the main island reaches from the center of the End, before the noise is added
+const ISLAND_RADIUS: f64 = 100.0;
+/// How deep the main island reaches below its top at the center
+const ISLAND_DEPTH: f64 = 40.0;
+
+/// Generates the main island of the End, a floating island of end stone around the center of the world
+pub struct EndGenerator {
+    shape: Fbm<Perlin>,
+}
+
+impl GeneratorInit for EndGenerator {
+    fn new(seed: Seed) -> Self {
+        let noise_seed = (seed.0 ^ (seed.0 >> 32)) as u32;
+        Self {
+            shape: Fbm::<Perlin>::new(noise_seed.wrapping_add(32))
+                .set_octaves(3)
+                .set_frequency(1.0 / 64.0),
+        }
+    }
+}
+
+impl ChunkGenerator for EndGenerator {
+    fn generate_terrain(&self, at: Vector2<i32>) -> ChunkData {
+        let mut blocks = ChunkBlocks::default();
+        let mut biomes = ChunkBiomes::default();
+        for x in 0..4 {
+            for z in 0..4 {
+                biomes.set_column(x, z, Biome::TheEnd);
+            }
+        }
+
+        for x in 0..16usize {
+            for z in 0..16usize {
+                let world_x = at.x * 16 + x as i32;
+                let world_z = at.z * 16 + z as i32;
+                let Some((bottom, top)) = self.island_column(world_x, world_z) else {
+                    continue;
+                };
+                for y in bottom..=top {
+                    blocks.set_block_no_heightmap_update(
+                        ChunkRelativeBlockCoordinates {
+                            x: (x as u8).into(),
+                            y: y.into(),
+                            z: (z as u8).into(),
+                        },
+                        block!("minecraft:end_stone").into(),
+                    );
+                }
+            }
+        }
+        blocks.heightmap = blocks.calculate_heightmap();
+
+        ChunkData {
+            blocks,
+            biomes,
+            position: at,
+            block_entities: HashMap::new(),
+            light: ChunkLight::default(),
+        }
+    }
+}
+
+impl EndGenerator {
+    /// The lowest and highest block of the island in a column, None if the column is empty
+    fn island_column(&self, x: i32, z: i32) -> Option<(i32, i32)> {
+        let distance = f64::from(x).hypot(f64::from(z));
+        // Don't bother sampling the noise far away from the island
+        if distance > ISLAND_RADIUS * 1.5 {
+            return None;
+        }
+        let noise = self.shape.get([x as f64, z as f64]);
+        let radius = ISLAND_RADIUS + noise * 30.0;
+        if distance >= radius {
+            return None;
+        }
+        let top = ISLAND_TOP + noise * 4.0;
+        // Round at the edges and deepest in the center
+        let depth = (1.0 - (distance / radius).powi(2)).sqrt() * ISLAND_DEPTH;
+        Some(((top - depth) as i32, top as i32))
+    }
+}
@@ -1,2 +1,15 @@
+pub mod end;
+pub mod nether;
 pub mod overworld;
 pub mod superflat;
+
+/// A deterministic random number for every block of a world, used for random block patterns
+pub(crate) fn block_hash(seed: u64, x: i32, y: i32, z: i32) -> u64 {
+    let mut hash = seed
+        ^ (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ (z as u64).wrapping_mul(0x1656_67B1_9E37_79F9);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+    hash ^ (hash >> 33)
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use noise::{Fbm, MultiFractal, NoiseFn, Perlin};
+use pumpkin_core::math::vector2::Vector2;
+use pumpkin_macros::block;
+
+use crate::{
+    biome::Biome,
+    block::block_state::BlockState,
+    chunk::{ChunkBiomes, ChunkBlocks, ChunkData, ChunkLight},
+    coordinates::ChunkRelativeBlockCoordinates,
+    world_gen::{
+        generator::{ChunkGenerator, GeneratorInit},
+        implementation::block_hash,
+        noise::clamped_map,
+        Seed,
+    },
+};
+
+/// The lowest block of the Nether, the bedrock floor starts here
+pub const NETHER_FLOOR: i32 = 0;
+/// The highest block of the Nether, the bedrock ceiling ends here
+pub const NETHER_CEILING: i32 = 127;
+/// Caverns below this height are filled with lava, like in vanilla
+const LAVA_LEVEL: i32 = 32;
+/// The terrain gets more solid over this many blocks above the floor and below the ceiling,
+/// so the caverns are closed off
+const EDGE_FALLOFF: i32 = 24;
+
+/// Generates Nether-like caverns of netherrack between a bedrock floor and ceiling, with a lava sea below
+pub struct NetherGenerator {
+    terrain: Fbm<Perlin>,
+    /// Used for the random bedrock patterns
+    seed: u64,
+}
+
+impl GeneratorInit for NetherGenerator {
+    fn new(seed: Seed) -> Self {
+        let noise_seed = (seed.0 ^ (seed.0 >> 32)) as u32;
+        Self {
+            terrain: Fbm::<Perlin>::new(noise_seed.wrapping_add(16))
+                .set_octaves(3)
+                .set_frequency(1.0 / 48.0),
+            seed: seed.0 as u64,
+        }
+    }
+}
+
+impl ChunkGenerator for NetherGenerator {
+    fn generate_terrain(&self, at: Vector2<i32>) -> ChunkData {
+        let mut blocks = ChunkBlocks::default();
+        let mut biomes = ChunkBiomes::default();
+        for x in 0..4 {
+            for z in 0..4 {
+                biomes.set_column(x, z, Biome::NetherWastes);
+            }
+        }
+
+        for x in 0..16usize {
+            for z in 0..16usize {
+                let world_x = at.x * 16 + x as i32;
+                let world_z = at.z * 16 + z as i32;
+                for y in NETHER_FLOOR..=NETHER_CEILING {
+                    let block = self.generate_block(world_x, y, world_z);
+                    if block.is_air() {
+                        continue;
+                    }
+                    blocks.set_block_no_heightmap_update(
+                        ChunkRelativeBlockCoordinates {
+                            x: (x as u8).into(),
+                            y: y.into(),
+                            z: (z as u8).into(),
+                        },
+                        block.into(),
+                    );
+                }
+            }
+        }
+        blocks.heightmap = blocks.calculate_heightmap();
+
+        ChunkData {
+            blocks,
+            biomes,
+            position: at,
+            block_entities: HashMap::new(),
+            light: ChunkLight::default(),
+        }
+    }
+}
+
+impl NetherGenerator {
+    fn generate_block(&self, x: i32, y: i32, z: i32) -> BlockState {
+        // Bedrock gets rarer over the 5 layers of the floor and the ceiling
+        let hash = (block_hash(self.seed, x, y, z) % 5) as i32;
+        if (y - NETHER_FLOOR < 5 && hash >= y - NETHER_FLOOR)
+            || (NETHER_CEILING - y < 5 && hash >= NETHER_CEILING - y)
+        {
+            return block!("minecraft:bedrock");
+        }
+
+        let edge = clamped_map(
+            y as f64,
+            NETHER_FLOOR as f64,
+            (NETHER_FLOOR + EDGE_FALLOFF) as f64,
+            1.0,
+            0.0,
+        ) + clamped_map(
+            y as f64,
+            (NETHER_CEILING - EDGE_FALLOFF) as f64,
+            NETHER_CEILING as f64,
+            0.0,
+            1.0,
+        );
+        // Stretched vertically, so the caverns are higher than they are wide
+        let density = self.terrain.get([x as f64, y as f64 * 1.5, z as f64]) + edge;
+        if density > 0.0 {
+            block!("minecraft:netherrack")
+        } else if y < LAVA_LEVEL {
+            block!("minecraft:lava")
+        } else {
+            BlockState::AIR
+        }
+    }
+}
@@ -1 +1,2 @@
 pub mod biome;
+pub mod noise_generator;
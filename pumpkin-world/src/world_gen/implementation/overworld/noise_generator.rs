@@ -0,0 +1,441 @@
+use std::collections::HashMap;
+
+use noise::{Fbm, MultiFractal, NoiseFn, Perlin, RidgedMulti};
+use pumpkin_core::math::vector2::Vector2;
+use pumpkin_macros::block;
+
+use crate::{
+    biome::Biome,
+    block::block_state::BlockState,
+    chunk::{ChunkBiomes, ChunkBlocks, ChunkData, ChunkLight},
+    coordinates::ChunkRelativeBlockCoordinates,
+    world_gen::{
+        feature::{FeatureGenerator, FeatureTerrain},
+        generator::{ChunkGenerator, GeneratorInit},
+        implementation::block_hash,
+        noise::{clamped_map, lerp3},
+        structure::{StructureGenerator, StructureType},
+        Seed,
+    },
+    WORLD_HEIGHT, WORLD_LOWEST_Y, WORLD_MAX_Y,
+};
+
+/// Water fills everything below this height
+const SEA_LEVEL: i32 = 63;
+/// Columns higher than this have no grass, only bare stone
+const STONE_PEAK_HEIGHT: i32 = 140;
+/// Columns higher than this are covered in snow
+const SNOW_PEAK_HEIGHT: i32 = 170;
+/// Caves below this height are filled with lava, like in vanilla
+const LAVA_LEVEL: i32 = -55;
+
+/// Cave noise is only sampled at the corners of cells this large and interpolated in between
+const CELL_WIDTH: usize = 4;
+const CELL_HEIGHT: usize = 8;
+/// Caves with a noise value above this get carved out
+const CAVE_THRESHOLD: f64 = 0.3;
+/// Caves stay this many blocks below the surface, so oceans and rivers don't drain into them
+const CAVE_SURFACE_DISTANCE: i32 = 8;
+
+/// Maps continentalness to the base height of the terrain, from deep oceans to far inland
+const CONTINENTALNESS_SPLINE: [(f64, f64); 7] = [
+    (-1.0, 24.0),
+    (-0.45, 34.0),
+    (-0.2, 50.0),
+    (-0.1, 62.0),
+    (0.05, 67.0),
+    (0.3, 80.0),
+    (1.0, 100.0),
+];
+
+/// Generates vanilla-like terrain: oceans, coasts, rolling hills and mountains, with caves below.
+///
+/// The shape of the terrain follows vanilla's idea: continentalness decides between ocean and land,
+/// erosion decides how flat the land is and ridges form the mountains where erosion is low.
+/// Biomes are picked from the height of the terrain, the temperature and the humidity.
+pub struct NoiseGenerator {
+    continentalness: Fbm<Perlin>,
+    erosion: Fbm<Perlin>,
+    temperature: Fbm<Perlin>,
+    humidity: Fbm<Perlin>,
+    ridges: RidgedMulti<Perlin>,
+    detail: Fbm<Perlin>,
+    caves: Fbm<Perlin>,
+    /// Used for the random bedrock and deepslate patterns
+    seed: u64,
+    features: FeatureGenerator,
+    structures: StructureGenerator,
+}
+
+impl GeneratorInit for NoiseGenerator {
+    fn new(seed: Seed) -> Self {
+        let noise_seed = (seed.0 ^ (seed.0 >> 32)) as u32;
+        Self {
+            continentalness: Fbm::<Perlin>::new(noise_seed)
+                .set_octaves(5)
+                .set_frequency(1.0 / 1024.0),
+            erosion: Fbm::<Perlin>::new(noise_seed.wrapping_add(1))
+                .set_octaves(4)
+                .set_frequency(1.0 / 512.0),
+            ridges: RidgedMulti::<Perlin>::new(noise_seed.wrapping_add(2))
+                .set_octaves(4)
+                .set_frequency(1.0 / 256.0),
+            detail: Fbm::<Perlin>::new(noise_seed.wrapping_add(3))
+                .set_octaves(3)
+                .set_frequency(1.0 / 64.0),
+            caves: Fbm::<Perlin>::new(noise_seed.wrapping_add(4))
+                .set_octaves(2)
+                .set_frequency(1.0 / 64.0),
+            temperature: Fbm::<Perlin>::new(noise_seed.wrapping_add(5))
+                .set_octaves(4)
+                .set_frequency(1.0 / 1024.0),
+            humidity: Fbm::<Perlin>::new(noise_seed.wrapping_add(6))
+                .set_octaves(4)
+                .set_frequency(1.0 / 768.0),
+            seed: seed.0 as u64,
+            features: FeatureGenerator::new(seed.0 as u64),
+            structures: StructureGenerator::new(seed.0 as u64, StructureType::ALL.to_vec()),
+        }
+    }
+}
+
+impl ChunkGenerator for NoiseGenerator {
+    fn generate_terrain(&self, at: Vector2<i32>) -> ChunkData {
+        let mut blocks = ChunkBlocks::default();
+        let mut biomes = ChunkBiomes::default();
+        let caves = self.sample_caves(at);
+
+        // Every biome cell gets the biome of the column in its center
+        let mut cell_biomes = [[Biome::default(); 4]; 4];
+        for (cell_x, cells) in cell_biomes.iter_mut().enumerate() {
+            for (cell_z, biome) in cells.iter_mut().enumerate() {
+                let world_x = at.x * 16 + cell_x as i32 * 4 + 2;
+                let world_z = at.z * 16 + cell_z as i32 * 4 + 2;
+                *biome = self.biome(world_x, world_z, self.surface_height(world_x, world_z));
+                biomes.set_column(cell_x, cell_z, *biome);
+            }
+        }
+
+        for x in 0..16usize {
+            for z in 0..16usize {
+                let world_x = at.x * 16 + x as i32;
+                let world_z = at.z * 16 + z as i32;
+                let surface = self.surface_height(world_x, world_z);
+                let biome = cell_biomes[x / 4][z / 4];
+
+                // One above the surface, for snow
+                for y in WORLD_LOWEST_Y as i32..=(surface + 1).max(SEA_LEVEL - 1) {
+                    let cave = caves.get(x, (y - WORLD_LOWEST_Y as i32) as usize, z);
+                    let block = self.generate_block(world_x, y, world_z, surface, cave, biome);
+                    if block.is_air() {
+                        continue;
+                    }
+                    blocks.set_block_no_heightmap_update(
+                        ChunkRelativeBlockCoordinates {
+                            x: (x as u8).into(),
+                            y: y.into(),
+                            z: (z as u8).into(),
+                        },
+                        block.into(),
+                    );
+                }
+            }
+        }
+        blocks.heightmap = blocks.calculate_heightmap();
+
+        ChunkData {
+            blocks,
+            biomes,
+            position: at,
+            block_entities: HashMap::new(),
+            light: ChunkLight::default(),
+        }
+    }
+
+    fn populate(&self, chunk: &mut ChunkData) {
+        self.features.populate(chunk, self);
+        self.structures
+            .populate(chunk, SEA_LEVEL, &|x, z| self.surface_height(x, z));
+    }
+}
+
+impl NoiseGenerator {
+    /// Only generates the given structures instead of all of them
+    pub fn with_structures(mut self, structures: Vec<StructureType>) -> Self {
+        self.structures = StructureGenerator::new(self.seed, structures);
+        self
+    }
+
+    /// The height of the highest solid block in a column
+    fn surface_height(&self, x: i32, z: i32) -> i32 {
+        let point = [x as f64, z as f64];
+        let continentalness = (self.continentalness.get(point) * 1.5).clamp(-1.0, 1.0);
+        let erosion = self.erosion.get(point) * 1.5;
+
+        let mut height = CONTINENTALNESS_SPLINE
+            .windows(2)
+            .find(|points| continentalness <= points[1].0)
+            .map_or(CONTINENTALNESS_SPLINE[6].1, |points| {
+                clamped_map(
+                    continentalness,
+                    points[0].0,
+                    points[1].0,
+                    points[0].1,
+                    points[1].1,
+                )
+            });
+
+        // Mountains only rise inland, where there is little erosion
+        let mountains = clamped_map(continentalness, -0.1, 0.3, 0.0, 1.0)
+            * clamped_map(erosion, 0.2, -0.5, 0.0, 1.0);
+        height += mountains * (self.ridges.get(point) + 1.0) * 0.5 * 110.0;
+        // Eroded land is flatter
+        height += self.detail.get(point) * clamped_map(erosion, -0.5, 0.5, 10.0, 3.0);
+
+        (height as i32).clamp(WORLD_LOWEST_Y as i32 + 8, WORLD_MAX_Y as i32 - 16)
+    }
+
+    /// Picks the biome of a column from its height, temperature and humidity
+    fn biome(&self, x: i32, z: i32, surface: i32) -> Biome {
+        let point = [x as f64, z as f64];
+        let temperature = (self.temperature.get(point) * 1.5).clamp(-1.0, 1.0);
+        let humidity = (self.humidity.get(point) * 1.5).clamp(-1.0, 1.0);
+
+        if surface < SEA_LEVEL - 1 {
+            return if temperature < -0.45 {
+                Biome::FrozenOcean
+            } else if surface < SEA_LEVEL - 20 {
+                Biome::DeepOcean
+            } else if temperature < -0.15 {
+                Biome::ColdOcean
+            } else if temperature > 0.45 {
+                Biome::WarmOcean
+            } else {
+                Biome::Ocean
+            };
+        }
+        if surface >= SNOW_PEAK_HEIGHT {
+            return if temperature < 0.2 {
+                Biome::FrozenPeaks
+            } else {
+                Biome::StonyPeaks
+            };
+        }
+        if surface >= STONE_PEAK_HEIGHT {
+            return if temperature < 0.0 {
+                Biome::SnowySlopes
+            } else {
+                Biome::StonyPeaks
+            };
+        }
+        if surface <= SEA_LEVEL + 1 {
+            return if temperature < -0.45 {
+                Biome::SnowyBeach
+            } else {
+                Biome::Beach
+            };
+        }
+
+        match temperature {
+            t if t < -0.45 => {
+                if humidity < 0.0 {
+                    Biome::SnowyPlains
+                } else {
+                    Biome::SnowyTaiga
+                }
+            }
+            t if t < -0.15 => {
+                if humidity < -0.2 {
+                    Biome::Plains
+                } else {
+                    Biome::Taiga
+                }
+            }
+            t if t < 0.2 => {
+                if humidity < -0.2 {
+                    Biome::Plains
+                } else if humidity < 0.2 {
+                    Biome::Forest
+                } else if humidity < 0.35 || surface > SEA_LEVEL + 6 {
+                    Biome::BirchForest
+                } else {
+                    Biome::Swamp
+                }
+            }
+            t if t < 0.5 => {
+                if humidity < -0.1 {
+                    Biome::Savanna
+                } else if humidity < 0.25 {
+                    Biome::Plains
+                } else {
+                    Biome::Jungle
+                }
+            }
+            _ => {
+                if humidity < 0.1 {
+                    Biome::Desert
+                } else {
+                    Biome::Badlands
+                }
+            }
+        }
+    }
+
+    fn generate_block(
+        &self,
+        x: i32,
+        y: i32,
+        z: i32,
+        surface: i32,
+        cave: f64,
+        biome: Biome,
+    ) -> BlockState {
+        let lowest_y = WORLD_LOWEST_Y as i32;
+        let snowy = matches!(
+            biome,
+            Biome::SnowyPlains | Biome::SnowyTaiga | Biome::SnowyBeach | Biome::FrozenOcean
+        );
+        // Bedrock gets rarer over the lowest 5 layers
+        if y < lowest_y + 5 && (self.hash(x, y, z) % 5) as i32 >= y - lowest_y {
+            return block!("minecraft:bedrock");
+        }
+        if y > surface {
+            return if y == SEA_LEVEL - 1 && snowy {
+                block!("minecraft:ice")
+            } else if y < SEA_LEVEL {
+                block!("minecraft:water")
+            } else if y == surface + 1 && snowy {
+                block!("minecraft:snow")
+            } else {
+                BlockState::AIR
+            };
+        }
+        if y < surface - CAVE_SURFACE_DISTANCE && cave > CAVE_THRESHOLD {
+            return if y < LAVA_LEVEL {
+                block!("minecraft:lava")
+            } else {
+                BlockState::AIR
+            };
+        }
+
+        let depth = surface - y;
+        if surface >= SNOW_PEAK_HEIGHT && depth == 0 {
+            return block!("minecraft:snow_block");
+        }
+        if surface < STONE_PEAK_HEIGHT {
+            if surface < SEA_LEVEL - 1 {
+                // Ocean floor
+                if depth < 3 {
+                    return if surface < SEA_LEVEL - 8 {
+                        block!("minecraft:gravel")
+                    } else {
+                        block!("minecraft:sand")
+                    };
+                }
+            } else if surface <= SEA_LEVEL + 1 || biome == Biome::Desert {
+                // Beaches and deserts
+                if depth < 3 {
+                    return block!("minecraft:sand");
+                }
+                if depth < 6 {
+                    return block!("minecraft:sandstone");
+                }
+            } else if biome == Biome::Badlands {
+                if depth == 0 {
+                    return block!("minecraft:red_sand");
+                }
+                if depth < 8 {
+                    return block!("minecraft:terracotta");
+                }
+            } else if depth == 0 {
+                return if snowy {
+                    block!("minecraft:grass_block", "snowy" = "true")
+                } else {
+                    block!("minecraft:grass_block")
+                };
+            } else if depth < 4 {
+                return block!("minecraft:dirt");
+            }
+        }
+
+        // Deepslate replaces stone below 0, mixed over the 8 blocks above
+        if y < 0 || (y < 8 && (self.hash(x, y, z) % 8) as i32 >= y) {
+            block!("minecraft:deepslate")
+        } else {
+            block!("minecraft:stone")
+        }
+    }
+
+    /// Samples the cave noise at the corners of all cells in the chunk
+    fn sample_caves(&self, at: Vector2<i32>) -> CaveSamples {
+        let samples_xz = 16 / CELL_WIDTH + 1;
+        let samples_y = WORLD_HEIGHT / CELL_HEIGHT + 1;
+        let mut samples = Vec::with_capacity(samples_xz * samples_xz * samples_y);
+        for x in 0..samples_xz {
+            for z in 0..samples_xz {
+                for y in 0..samples_y {
+                    let world_x = (at.x * 16) as f64 + (x * CELL_WIDTH) as f64;
+                    let world_y = WORLD_LOWEST_Y as f64 + (y * CELL_HEIGHT) as f64;
+                    let world_z = (at.z * 16) as f64 + (z * CELL_WIDTH) as f64;
+                    // Stretched vertically, so caves are wider than they are high
+                    samples.push(self.caves.get([world_x, world_y * 2.0, world_z]));
+                }
+            }
+        }
+        CaveSamples {
+            samples,
+            samples_xz,
+            samples_y,
+        }
+    }
+
+    /// A deterministic random number for every block
+    fn hash(&self, x: i32, y: i32, z: i32) -> u64 {
+        block_hash(self.seed, x, y, z)
+    }
+}
+
+impl FeatureTerrain for NoiseGenerator {
+    fn surface_height(&self, x: i32, z: i32) -> i32 {
+        NoiseGenerator::surface_height(self, x, z)
+    }
+
+    fn biome_at(&self, x: i32, z: i32) -> Biome {
+        self.biome(x, z, NoiseGenerator::surface_height(self, x, z))
+    }
+
+    fn sea_level(&self) -> i32 {
+        SEA_LEVEL
+    }
+}
+
+/// The cave noise at the corners of the cells of a chunk
+struct CaveSamples {
+    samples: Vec<f64>,
+    samples_xz: usize,
+    samples_y: usize,
+}
+
+impl CaveSamples {
+    fn sample(&self, x: usize, y: usize, z: usize) -> f64 {
+        self.samples[(x * self.samples_xz + z) * self.samples_y + y]
+    }
+
+    /// The interpolated cave noise at a block, `y` counts from the lowest block of the world
+    fn get(&self, x: usize, y: usize, z: usize) -> f64 {
+        let (cell_x, cell_y, cell_z) = (x / CELL_WIDTH, y / CELL_HEIGHT, z / CELL_WIDTH);
+        lerp3(
+            (x % CELL_WIDTH) as f64 / CELL_WIDTH as f64,
+            (y % CELL_HEIGHT) as f64 / CELL_HEIGHT as f64,
+            (z % CELL_WIDTH) as f64 / CELL_WIDTH as f64,
+            self.sample(cell_x, cell_y, cell_z),
+            self.sample(cell_x + 1, cell_y, cell_z),
+            self.sample(cell_x, cell_y + 1, cell_z),
+            self.sample(cell_x + 1, cell_y + 1, cell_z),
+            self.sample(cell_x, cell_y, cell_z + 1),
+            self.sample(cell_x + 1, cell_y, cell_z + 1),
+            self.sample(cell_x, cell_y + 1, cell_z + 1),
+            self.sample(cell_x + 1, cell_y + 1, cell_z + 1),
+        )
+    }
+}
@@ -1,52 +1,130 @@
 use pumpkin_core::math::vector2::Vector2;
-use pumpkin_macros::block;
+use thiserror::Error;
 
 use crate::{
     biome::Biome,
     block::block_state::BlockState,
     coordinates::{BlockCoordinates, XZBlockCoordinates},
     world_gen::{
-        generator::{BiomeGenerator, GeneratorInit, TerrainGenerator},
-        generic_generator::GenericGenerator,
-        Seed,
+        generator::{BiomeGenerator, TerrainGenerator},
+        generic_generator::StaticGenerator,
     },
+    WORLD_HEIGHT, WORLD_LOWEST_Y,
 };
 
-#[expect(dead_code)]
-pub type SuperflatGenerator = GenericGenerator<SuperflatBiomeGenerator, SuperflatTerrainGenerator>;
+pub type SuperflatGenerator = StaticGenerator<SuperflatBiomeGenerator, SuperflatTerrainGenerator>;
 
-pub(crate) struct SuperflatBiomeGenerator {}
+impl SuperflatGenerator {
+    pub fn from_preset(preset: FlatPreset) -> Self {
+        Self {
+            biome_generator: SuperflatBiomeGenerator {
+                biome: preset.biome,
+            },
+            terrain_generator: SuperflatTerrainGenerator {
+                layers: preset.layers,
+            },
+        }
+    }
+}
 
-impl GeneratorInit for SuperflatBiomeGenerator {
-    fn new(_: Seed) -> Self {
-        Self {}
+#[derive(Error, Debug)]
+pub enum FlatPresetError {
+    #[error("Invalid layer \"{0}\", expected a block or `count*block`")]
+    InvalidLayer(String),
+    #[error("Unknown block {0}")]
+    UnknownBlock(String),
+    #[error("The layers are {0} blocks high, but the world is only {WORLD_HEIGHT} blocks high")]
+    TooHigh(usize),
+    #[error("Unknown biome {0}")]
+    UnknownBiome(String),
+}
+
+/// The layers and biome of a superflat world
+#[derive(Clone)]
+pub struct FlatPreset {
+    /// One block per height, starting at the bottom of the world
+    layers: Vec<BlockState>,
+    biome: Biome,
+}
+
+impl FlatPreset {
+    /// Parses vanilla style layers like `minecraft:bedrock,2*minecraft:dirt,minecraft:grass_block`,
+    /// from the bottom to the top
+    pub fn parse(layers: &str, biome: &str) -> Result<Self, FlatPresetError> {
+        let mut blocks = Vec::new();
+        for layer in layers
+            .split(',')
+            .map(str::trim)
+            .filter(|layer| !layer.is_empty())
+        {
+            let (count, block) = match layer.split_once('*') {
+                Some((count, block)) => (
+                    count
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|_| FlatPresetError::InvalidLayer(layer.to_string()))?,
+                    block.trim(),
+                ),
+                None => (1, layer),
+            };
+            let block = with_namespace(block);
+            let state = BlockState::new(&block, None)
+                .map_err(|_| FlatPresetError::UnknownBlock(block.clone()))?;
+            if blocks.len() + count > WORLD_HEIGHT {
+                return Err(FlatPresetError::TooHigh(blocks.len() + count));
+            }
+            blocks.extend(std::iter::repeat_n(state, count));
+        }
+
+        let biome = with_namespace(biome.trim());
+        Ok(Self {
+            layers: blocks,
+            biome: Biome::from_registry_id(&biome).ok_or(FlatPresetError::UnknownBiome(biome))?,
+        })
     }
 }
 
-impl BiomeGenerator for SuperflatBiomeGenerator {
-    // TODO make generic over Biome and allow changing the Biome in the config.
-    fn generate_biome(&self, _: XZBlockCoordinates) -> Biome {
-        Biome::Plains
+impl Default for FlatPreset {
+    fn default() -> Self {
+        Self::parse(
+            "minecraft:bedrock,2*minecraft:dirt,minecraft:grass_block",
+            "minecraft:plains",
+        )
+        .expect("The default preset is valid")
     }
 }
 
-pub(crate) struct SuperflatTerrainGenerator {}
+/// Blocks and biomes may be written without the `minecraft:` namespace, like in vanilla
+fn with_namespace(id: &str) -> String {
+    if id.contains(':') {
+        id.to_string()
+    } else {
+        format!("minecraft:{id}")
+    }
+}
 
-impl GeneratorInit for SuperflatTerrainGenerator {
-    fn new(_: Seed) -> Self {
-        Self {}
+pub(crate) struct SuperflatBiomeGenerator {
+    biome: Biome,
+}
+
+impl BiomeGenerator for SuperflatBiomeGenerator {
+    fn generate_biome(&self, _: XZBlockCoordinates) -> Biome {
+        self.biome
     }
 }
 
+pub(crate) struct SuperflatTerrainGenerator {
+    layers: Vec<BlockState>,
+}
+
 impl TerrainGenerator for SuperflatTerrainGenerator {
     fn prepare_chunk(&self, _at: &Vector2<i32>) {}
-    // TODO allow specifying which blocks should be at which height in the config.
+
     fn generate_block(&self, at: BlockCoordinates, _: Biome) -> BlockState {
-        match *at.y {
-            -64 => block!("minecraft:bedrock"),
-            -63..=-62 => block!("minecraft:dirt"),
-            -61 => block!("minecraft:grass_block"),
-            _ => BlockState::AIR,
-        }
+        usize::try_from(*at.y - WORLD_LOWEST_Y)
+            .ok()
+            .and_then(|height| self.layers.get(height))
+            .cloned()
+            .unwrap_or(BlockState::AIR)
     }
 }
@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
 mod blender;
+mod feature;
 mod generator;
 mod generic_generator;
 pub mod height_limit;
@@ -10,16 +11,45 @@ mod positions;
 mod proto_chunk;
 mod sampler;
 mod seed;
+mod structure;
 
-pub use generator::WorldGenerator;
-use implementation::overworld::biome::plains::PlainsGenerator;
+pub use generator::ChunkGenerator;
+pub use implementation::nether::{NETHER_CEILING, NETHER_FLOOR};
+pub use implementation::superflat::{FlatPreset, FlatPresetError};
+use implementation::{
+    end::EndGenerator, nether::NetherGenerator, overworld::noise_generator::NoiseGenerator,
+    superflat::SuperflatGenerator,
+};
 pub use seed::Seed;
+pub use structure::StructureType;
 
 use generator::GeneratorInit;
 
-pub fn get_world_gen(seed: Seed) -> Box<dyn WorldGenerator> {
-    // TODO decide which WorldGenerator to pick based on config.
-    Box::new(PlainsGenerator::new(seed))
+/// Which generator new chunks come from
+pub enum GeneratorSettings {
+    Normal {
+        /// The structures generated into new chunks
+        structures: Vec<StructureType>,
+    },
+    Flat(FlatPreset),
+    /// Netherrack caverns above a lava sea
+    Nether,
+    /// The main island of the End
+    End,
+    /// A generator provided by a plugin
+    Custom(Box<dyn ChunkGenerator>),
+}
+
+pub fn get_world_gen(seed: Seed, settings: GeneratorSettings) -> Box<dyn ChunkGenerator> {
+    match settings {
+        GeneratorSettings::Normal { structures } => {
+            Box::new(NoiseGenerator::new(seed).with_structures(structures))
+        }
+        GeneratorSettings::Flat(preset) => Box::new(SuperflatGenerator::from_preset(preset)),
+        GeneratorSettings::Nether => Box::new(NetherGenerator::new(seed)),
+        GeneratorSettings::End => Box::new(EndGenerator::new(seed)),
+        GeneratorSettings::Custom(generator) => generator,
+    }
 }
 
 pub mod biome_coords {
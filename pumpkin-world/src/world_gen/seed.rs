@@ -1,15 +1,38 @@
-use std::hash::{DefaultHasher, Hash, Hasher};
-
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Seed(pub i64);
 
+impl Seed {
+    /// A random seed, for worlds without a configured one
+    pub fn random() -> Self {
+        Self(rand::random())
+    }
+}
+
 impl From<&str> for Seed {
+    /// Numbers are used as they are, any other text is hashed the same way as vanilla does
     fn from(value: &str) -> Self {
-        // TODO replace with a deterministic hasher (the same as vanilla?)
-        let mut hasher = DefaultHasher::new();
-        value.hash(&mut hasher);
+        if let Ok(seed) = value.trim().parse() {
+            return Self(seed);
+        }
+        // Java's String.hashCode
+        let hash = value
+            .encode_utf16()
+            .fold(0i32, |hash, c| hash.wrapping_mul(31).wrapping_add(c as i32));
+        Self(hash as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        // TODO use cast_signed once the feature is stabilized.
-        Self(hasher.finish() as i64)
+    #[test]
+    fn hashes_like_vanilla() {
+        assert_eq!(
+            Seed::from("-4172144997902289642"),
+            Seed(-4172144997902289642)
+        );
+        // "Pumpkin".hashCode() in Java
+        assert_eq!(Seed::from("Pumpkin"), Seed(1_439_567_688));
     }
 }
@@ -0,0 +1,346 @@
+//! Structures are generated after the terrain, in the population stage of a chunk.
+//!
+//! Where a structure starts only depends on the seed, so every chunk can find the structures
+//! reaching into it without its neighbours being generated. Each chunk then places only the
+//! part of a structure inside its own bounding box, which keeps structures crossing chunk
+//! borders whole.
+
+mod template;
+
+use pumpkin_core::{
+    math::{vector2::Vector2, vector3::Vector3},
+    random::{xoroshiro128::Xoroshiro, RandomImpl},
+};
+
+use crate::{
+    block::block_entity::BlockEntity, chunk::ChunkData, coordinates::ChunkRelativeBlockCoordinates,
+    WORLD_LOWEST_Y, WORLD_MAX_Y,
+};
+
+pub use template::StructureTemplate;
+
+/// Structures are at most this many chunks away from the chunk they start in
+const MAX_CHUNK_REACH: i32 = 1;
+/// Dungeons are generated between this height and a bit below the surface
+const DUNGEON_MIN_Y: i32 = WORLD_LOWEST_Y as i32 + 8;
+/// How far below the surface dungeons stay, so they don't break through it
+const DUNGEON_SURFACE_DISTANCE: i32 = 12;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StructureType {
+    /// A small underground room with a spawner and a chest
+    Dungeon,
+    /// The crumbled walls of a small house
+    SurfaceRuins,
+}
+
+impl StructureType {
+    pub const ALL: [Self; 2] = [Self::Dungeon, Self::SurfaceRuins];
+
+    fn placement(self) -> StructurePlacement {
+        match self {
+            Self::Dungeon => StructurePlacement {
+                spacing: 4,
+                separation: 1,
+                salt: 14_357_617,
+            },
+            Self::SurfaceRuins => StructurePlacement {
+                spacing: 16,
+                separation: 6,
+                salt: 34_222_645,
+            },
+        }
+    }
+}
+
+/// Spreads structures over the world like vanilla's random spread placement. The world is split
+/// into regions of `spacing` by `spacing` chunks and each region gets one structure, in a random
+/// chunk at least `separation` chunks away from the structures of the neighbouring regions
+struct StructurePlacement {
+    spacing: i32,
+    separation: i32,
+    /// Different for every structure type, so they don't all start in the same chunks
+    salt: u64,
+}
+
+impl StructurePlacement {
+    /// The random numbers of a region, like vanilla's large feature seed
+    fn region_random(&self, seed: u64, region: Vector2<i32>) -> Xoroshiro {
+        Xoroshiro::from_seed(
+            (region.x as u64)
+                .wrapping_mul(341_873_128_712)
+                .wrapping_add((region.z as u64).wrapping_mul(132_897_987_541))
+                .wrapping_add(seed)
+                .wrapping_add(self.salt),
+        )
+    }
+
+    /// The chunk the structure of the region containing `chunk` starts in, and the random
+    /// numbers to build it with
+    fn start_chunk(&self, seed: u64, chunk: Vector2<i32>) -> (Vector2<i32>, Xoroshiro) {
+        let region = Vector2::new(
+            chunk.x.div_euclid(self.spacing),
+            chunk.z.div_euclid(self.spacing),
+        );
+        let mut random = self.region_random(seed, region);
+        let range = self.spacing - self.separation;
+        let start = Vector2::new(
+            region.x * self.spacing + random.next_bounded_i32(range),
+            region.z * self.spacing + random.next_bounded_i32(range),
+        );
+        (start, random)
+    }
+}
+
+/// A box of blocks, both corners are inside of it
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BlockBox {
+    pub min: Vector3<i32>,
+    pub max: Vector3<i32>,
+}
+
+impl BlockBox {
+    pub fn new(min: Vector3<i32>, max: Vector3<i32>) -> Self {
+        Self { min, max }
+    }
+
+    /// All blocks of a chunk
+    pub fn of_chunk(chunk: Vector2<i32>) -> Self {
+        Self::new(
+            Vector3::new(chunk.x * 16, WORLD_LOWEST_Y as i32, chunk.z * 16),
+            Vector3::new(chunk.x * 16 + 15, WORLD_MAX_Y as i32 - 1, chunk.z * 16 + 15),
+        )
+    }
+
+    pub fn contains(&self, position: Vector3<i32>) -> bool {
+        (self.min.x..=self.max.x).contains(&position.x)
+            && (self.min.y..=self.max.y).contains(&position.y)
+            && (self.min.z..=self.max.z).contains(&position.z)
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min.x <= other.max.x
+            && self.max.x >= other.min.x
+            && self.min.y <= other.max.y
+            && self.max.y >= other.min.y
+            && self.min.z <= other.max.z
+            && self.max.z >= other.min.z
+    }
+}
+
+/// A structure placed in the world
+pub struct StructureStart {
+    pub structure: StructureType,
+    pub template: StructureTemplate,
+    /// Where the lowest corner of the template is
+    pub origin: Vector3<i32>,
+}
+
+impl StructureStart {
+    pub fn bounding_box(&self) -> BlockBox {
+        self.template.bounding_box(self.origin)
+    }
+}
+
+/// Generates the enabled structures into chunks of a world
+pub struct StructureGenerator {
+    seed: u64,
+    enabled: Vec<StructureType>,
+}
+
+impl StructureGenerator {
+    pub fn new(seed: u64, enabled: Vec<StructureType>) -> Self {
+        Self { seed, enabled }
+    }
+
+    /// The structure starting in a chunk, if there is one. `surface_height` gives the height of
+    /// the terrain in any column, even in chunks which were not generated yet
+    pub fn start_in(
+        &self,
+        structure: StructureType,
+        chunk: Vector2<i32>,
+        sea_level: i32,
+        surface_height: &impl Fn(i32, i32) -> i32,
+    ) -> Option<StructureStart> {
+        let (start, mut random) = structure.placement().start_chunk(self.seed, chunk);
+        if start != chunk {
+            return None;
+        }
+        let template = match structure {
+            StructureType::Dungeon => StructureTemplate::dungeon(&mut random),
+            StructureType::SurfaceRuins => StructureTemplate::surface_ruins(&mut random),
+        };
+        let x = chunk.x * 16 + random.next_bounded_i32(16);
+        let z = chunk.z * 16 + random.next_bounded_i32(16);
+        // The lowest surface under the structure, so it never floats
+        let surface = [
+            (x, z),
+            (x + template.size.x - 1, z),
+            (x, z + template.size.z - 1),
+            (x + template.size.x - 1, z + template.size.z - 1),
+        ]
+        .into_iter()
+        .map(|(x, z)| surface_height(x, z))
+        .min()
+        .unwrap_or(sea_level);
+
+        let y = match structure {
+            StructureType::Dungeon => {
+                let max_y = surface - DUNGEON_SURFACE_DISTANCE - template.size.y;
+                if max_y <= DUNGEON_MIN_Y {
+                    return None;
+                }
+                DUNGEON_MIN_Y + random.next_bounded_i32(max_y - DUNGEON_MIN_Y)
+            }
+            StructureType::SurfaceRuins => {
+                // Ruins are not generated under water
+                if surface < sea_level {
+                    return None;
+                }
+                surface
+            }
+        };
+        Some(StructureStart {
+            structure,
+            template,
+            origin: Vector3::new(x, y, z),
+        })
+    }
+
+    /// All structures which reach into a chunk
+    pub fn starts_reaching(
+        &self,
+        chunk: Vector2<i32>,
+        sea_level: i32,
+        surface_height: &impl Fn(i32, i32) -> i32,
+    ) -> Vec<StructureStart> {
+        let chunk_box = BlockBox::of_chunk(chunk);
+        let mut starts = Vec::new();
+        for structure in &self.enabled {
+            for x in -MAX_CHUNK_REACH..=MAX_CHUNK_REACH {
+                for z in -MAX_CHUNK_REACH..=MAX_CHUNK_REACH {
+                    let start_chunk = Vector2::new(chunk.x + x, chunk.z + z);
+                    if let Some(start) =
+                        self.start_in(*structure, start_chunk, sea_level, surface_height)
+                    {
+                        if start.bounding_box().intersects(&chunk_box) {
+                            starts.push(start);
+                        }
+                    }
+                }
+            }
+        }
+        starts
+    }
+
+    /// Places the parts of all structures inside of a chunk
+    pub fn populate(
+        &self,
+        chunk: &mut ChunkData,
+        sea_level: i32,
+        surface_height: &impl Fn(i32, i32) -> i32,
+    ) {
+        let chunk_box = BlockBox::of_chunk(chunk.position);
+        let starts = self.starts_reaching(chunk.position, sea_level, surface_height);
+        if starts.is_empty() {
+            return;
+        }
+        for start in starts {
+            for (offset, block) in &start.template.blocks {
+                let position = start.origin.add(offset);
+                if !chunk_box.contains(position) {
+                    continue;
+                }
+                chunk.blocks.set_block(
+                    ChunkRelativeBlockCoordinates {
+                        x: (position.x.rem_euclid(16) as u8).into(),
+                        y: position.y.into(),
+                        z: (position.z.rem_euclid(16) as u8).into(),
+                    },
+                    *block,
+                );
+                chunk.block_entities.remove(&position);
+                if let Some(entity) = BlockEntity::for_block(*block, position) {
+                    chunk.block_entities.insert(position, entity);
+                }
+            }
+        }
+        chunk.blocks.heightmap = chunk.blocks.calculate_heightmap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pumpkin_core::math::vector2::Vector2;
+
+    use super::{BlockBox, StructureGenerator, StructureType};
+
+    #[test]
+    fn one_start_per_region() {
+        let generator = StructureGenerator::new(1234, StructureType::ALL.to_vec());
+        let placement = StructureType::SurfaceRuins.placement();
+        for region_x in -3..3 {
+            for region_z in -3..3 {
+                let starts = (0..placement.spacing)
+                    .flat_map(|x| (0..placement.spacing).map(move |z| (x, z)))
+                    .filter(|(x, z)| {
+                        let chunk = Vector2::new(
+                            region_x * placement.spacing + x,
+                            region_z * placement.spacing + z,
+                        );
+                        generator
+                            .start_in(StructureType::SurfaceRuins, chunk, 63, &|_, _| 70)
+                            .is_some()
+                    })
+                    .count();
+                assert_eq!(starts, 1);
+            }
+        }
+    }
+
+    #[test]
+    fn starts_are_deterministic() {
+        let generator = StructureGenerator::new(42, vec![StructureType::Dungeon]);
+        for x in -8..8 {
+            for z in -8..8 {
+                let chunk = Vector2::new(x, z);
+                let first = generator.start_in(StructureType::Dungeon, chunk, 63, &|_, _| 70);
+                let second = generator.start_in(StructureType::Dungeon, chunk, 63, &|_, _| 70);
+                assert_eq!(
+                    first.map(|start| start.bounding_box()),
+                    second.map(|start| start.bounding_box())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn structures_reach_into_neighbouring_chunks() {
+        let generator = StructureGenerator::new(7, StructureType::ALL.to_vec());
+        let surface = |_, _| 70;
+        for x in -8..8 {
+            for z in -8..8 {
+                for structure in StructureType::ALL {
+                    let Some(start) =
+                        generator.start_in(structure, Vector2::new(x, z), 63, &surface)
+                    else {
+                        continue;
+                    };
+                    // Every chunk the structure overlaps has to place its part of it
+                    for chunk_x in x - 1..=x + 1 {
+                        for chunk_z in z - 1..=z + 1 {
+                            let chunk = Vector2::new(chunk_x, chunk_z);
+                            if !start.bounding_box().intersects(&BlockBox::of_chunk(chunk)) {
+                                continue;
+                            }
+                            assert!(generator
+                                .starts_reaching(chunk, 63, &surface)
+                                .iter()
+                                .any(|other| other.origin == start.origin));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
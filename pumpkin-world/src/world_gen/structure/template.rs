@@ -0,0 +1,117 @@
+use pumpkin_core::{
+    math::vector3::Vector3,
+    random::{xoroshiro128::Xoroshiro, RandomImpl},
+};
+use pumpkin_macros::block;
+
+use crate::block::BlockId;
+
+use super::BlockBox;
+
+/// The blocks of a structure, relative to its lowest corner
+pub struct StructureTemplate {
+    pub size: Vector3<i32>,
+    pub blocks: Vec<(Vector3<i32>, BlockId)>,
+}
+
+impl StructureTemplate {
+    fn new(size: Vector3<i32>) -> Self {
+        Self {
+            size,
+            blocks: Vec::new(),
+        }
+    }
+
+    fn set(&mut self, x: i32, y: i32, z: i32, block: BlockId) {
+        self.blocks.push((Vector3::new(x, y, z), block));
+    }
+
+    /// The box the template covers when its lowest corner is at `origin`
+    pub fn bounding_box(&self, origin: Vector3<i32>) -> BlockBox {
+        BlockBox::new(
+            origin,
+            origin.add(&Vector3::new(
+                self.size.x - 1,
+                self.size.y - 1,
+                self.size.z - 1,
+            )),
+        )
+    }
+
+    /// A small cobblestone room with a monster spawner and a chest, like vanilla's dungeons.
+    /// The spawner has no block entity yet, so it stays empty
+    pub fn dungeon(random: &mut Xoroshiro) -> Self {
+        let width = if random.next_bool() { 7 } else { 9 };
+        let depth = if random.next_bool() { 7 } else { 9 };
+        let mut template = Self::new(Vector3::new(width, 5, depth));
+        for x in 0..width {
+            for z in 0..depth {
+                for y in 0..5 {
+                    let wall = x == 0 || z == 0 || x == width - 1 || z == depth - 1;
+                    let block = if y == 0 {
+                        // The floor is mostly mossy, like in vanilla
+                        if random.next_bounded_i32(4) == 0 {
+                            block!("minecraft:cobblestone")
+                        } else {
+                            block!("minecraft:mossy_cobblestone")
+                        }
+                    } else if y == 4 || wall {
+                        block!("minecraft:cobblestone")
+                    } else {
+                        block!("minecraft:air")
+                    };
+                    template.set(x, y, z, block.into());
+                }
+            }
+        }
+        template.set(width / 2, 1, depth / 2, block!("minecraft:spawner").into());
+        // The chest stands against one of the walls
+        let chest_x = 1 + random.next_bounded_i32(width - 2);
+        template.set(
+            chest_x,
+            1,
+            1,
+            block!("minecraft:chest", "facing" = "south").into(),
+        );
+        template
+    }
+
+    /// The crumbled walls of a small stone brick house
+    pub fn surface_ruins(random: &mut Xoroshiro) -> Self {
+        let width = 5 + random.next_bounded_i32(3);
+        let depth = 5 + random.next_bounded_i32(3);
+        let height = 4;
+        let mut template = Self::new(Vector3::new(width, height + 1, depth));
+        for x in 0..width {
+            for z in 0..depth {
+                template.set(x, 0, z, Self::ruin_brick(random));
+                let wall = x == 0 || z == 0 || x == width - 1 || z == depth - 1;
+                // The walls are broken down to a random height
+                let wall_height = if wall {
+                    random.next_bounded_i32(height + 1)
+                } else {
+                    0
+                };
+                for y in 1..=height {
+                    let block = if y <= wall_height {
+                        Self::ruin_brick(random)
+                    } else {
+                        block!("minecraft:air").into()
+                    };
+                    template.set(x, y, z, block);
+                }
+            }
+        }
+        template
+    }
+
+    fn ruin_brick(random: &mut Xoroshiro) -> BlockId {
+        match random.next_bounded_i32(5) {
+            0 => block!("minecraft:cracked_stone_bricks"),
+            1 => block!("minecraft:mossy_stone_bricks"),
+            2 => block!("minecraft:cobblestone"),
+            _ => block!("minecraft:stone_bricks"),
+        }
+        .into()
+    }
+}
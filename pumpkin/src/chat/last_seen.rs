@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+
+use thiserror::Error;
+
+/// How many messages a client can acknowledge at once
+pub const LAST_SEEN_COUNT: usize = 20;
+/// How many messages may wait for an acknowledgement before the client gets kicked
+pub const MAX_PENDING: usize = 4096;
+
+#[derive(Error, Debug)]
+pub enum LastSeenError {
+    #[error("Invalid message acknowledgement count")]
+    InvalidOffset,
+    #[error("Too many acknowledged messages")]
+    TooManyAcknowledged,
+    #[error("Acknowledged unknown chat message")]
+    UnknownMessage,
+    #[error("Chat message was acknowledged as lost")]
+    LostMessage,
+}
+
+struct TrackedMessage {
+    signature: Box<[u8]>,
+    /// Whether the client did not acknowledge this message yet
+    pending: bool,
+}
+
+/// Tracks the signed messages we sent to a client, so we know which messages it claims to have seen.
+///
+/// The signatures of seen messages are part of every signed message, so we need them to validate it
+pub struct LastSeenMessages {
+    /// The first `LAST_SEEN_COUNT` entries are the window the client acknowledges, newer ones are pending
+    tracked: VecDeque<Option<TrackedMessage>>,
+    last_pending: Option<Box<[u8]>>,
+}
+
+impl Default for LastSeenMessages {
+    fn default() -> Self {
+        Self {
+            tracked: (0..LAST_SEEN_COUNT).map(|_| None).collect(),
+            last_pending: None,
+        }
+    }
+}
+
+impl LastSeenMessages {
+    /// Remembers a signed message we sent to the client
+    pub fn add_pending(&mut self, signature: &[u8]) {
+        if self.last_pending.as_deref() == Some(signature) {
+            return;
+        }
+        let signature: Box<[u8]> = signature.into();
+        self.tracked.push_back(Some(TrackedMessage {
+            signature: signature.clone(),
+            pending: true,
+        }));
+        self.last_pending = Some(signature);
+    }
+
+    /// How many messages the client did not acknowledge yet
+    pub fn pending_count(&self) -> usize {
+        self.tracked.len() - LAST_SEEN_COUNT
+    }
+
+    /// Moves the window forward by `offset` messages
+    pub fn apply_offset(&mut self, offset: i32) -> Result<(), LastSeenError> {
+        match usize::try_from(offset) {
+            Ok(offset) if offset <= self.pending_count() => {
+                self.tracked.drain(..offset);
+                Ok(())
+            }
+            _ => Err(LastSeenError::InvalidOffset),
+        }
+    }
+
+    /// Applies the acknowledgement sent with a chat message and returns the signatures of all seen messages
+    pub fn apply_update(
+        &mut self,
+        offset: i32,
+        acknowledged: &[u8],
+    ) -> Result<Vec<Box<[u8]>>, LastSeenError> {
+        self.apply_offset(offset)?;
+
+        let is_set = |bit: usize| {
+            acknowledged
+                .get(bit / 8)
+                .is_some_and(|byte| (byte >> (bit % 8)) & 1 == 1)
+        };
+        if (LAST_SEEN_COUNT..acknowledged.len() * 8).any(is_set) {
+            return Err(LastSeenError::TooManyAcknowledged);
+        }
+
+        let mut seen = Vec::new();
+        for (bit, entry) in self.tracked.iter_mut().take(LAST_SEEN_COUNT).enumerate() {
+            if is_set(bit) {
+                let Some(message) = entry else {
+                    return Err(LastSeenError::UnknownMessage);
+                };
+                message.pending = false;
+                seen.push(message.signature.clone());
+            } else {
+                if entry.as_ref().is_some_and(|message| !message.pending) {
+                    return Err(LastSeenError::LostMessage);
+                }
+                *entry = None;
+            }
+        }
+        Ok(seen)
+    }
+}
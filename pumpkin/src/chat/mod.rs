@@ -0,0 +1,142 @@
+//! Secure chat: player chat sessions and the validation of signed messages.
+//!
+//! Every signed message references the messages its sender has seen, so for each player we also
+//! track the signed messages we sent to them.
+
+use std::sync::Arc;
+
+use pumpkin_config::{ADVANCED_CONFIG, BASIC_CONFIG};
+use pumpkin_protocol::server::play::SChatMessage;
+use rsa::Pkcs1v15Sign;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use uuid::Uuid;
+
+pub mod last_seen;
+pub mod session;
+
+use last_seen::{LastSeenError, LastSeenMessages};
+use session::ChatSession;
+
+/// Whether players must sign their chat messages
+pub fn secure_chat_enforced() -> bool {
+    BASIC_CONFIG.get().online_mode
+        && BASIC_CONFIG.get().enforce_secure_chat
+        && !ADVANCED_CONFIG.get().chat.no_chat_reports
+}
+
+#[derive(Error, Debug)]
+pub enum ChatError {
+    #[error("Chat message validation failure: {0}")]
+    LastSeen(#[from] LastSeenError),
+    #[error("Received chat packet with missing or invalid signature")]
+    InvalidSignature,
+    #[error("Expired profile public key")]
+    ExpiredKey,
+    #[error("Out-of-order chat packet received")]
+    OutOfOrder,
+}
+
+/// A signed message which passed validation
+pub struct ValidatedMessage {
+    /// The index of the message in the sender's chat session
+    pub index: i32,
+    /// The signatures of the messages the sender has seen
+    pub last_seen: Vec<Box<[u8]>>,
+}
+
+/// The secure chat state of a single player
+#[derive(Default)]
+pub struct PlayerChat {
+    session: Option<Arc<ChatSession>>,
+    /// The index the next message of the session must have
+    next_index: i32,
+    /// The timestamp of the last message, messages must not be older
+    last_timestamp: i64,
+    /// The signed messages we sent to this player
+    pub last_seen: LastSeenMessages,
+}
+
+impl PlayerChat {
+    pub fn session(&self) -> Option<Arc<ChatSession>> {
+        self.session.clone()
+    }
+
+    /// Starts a new chat session, the message chain begins from the start
+    pub fn set_session(&mut self, session: Arc<ChatSession>) {
+        self.session = Some(session);
+        self.next_index = 0;
+        self.last_timestamp = i64::MIN;
+    }
+
+    /// Validates a chat message of `sender`. Returns None if the player has no chat session,
+    /// it's up to the caller to decide if unsigned messages are allowed
+    pub fn validate(
+        &mut self,
+        sender: Uuid,
+        message: &SChatMessage,
+    ) -> Result<Option<ValidatedMessage>, ChatError> {
+        let last_seen = self
+            .last_seen
+            .apply_update(message.message_count.0, &message.acknowledged)?;
+        let Some(session) = &self.session else {
+            return Ok(None);
+        };
+        let Some(signature) = &message.signature else {
+            return Err(ChatError::InvalidSignature);
+        };
+        if session.is_expired() {
+            return Err(ChatError::ExpiredKey);
+        }
+        if message.timestamp < self.last_timestamp {
+            return Err(ChatError::OutOfOrder);
+        }
+
+        let index = self.next_index;
+        let hashed = Sha256::digest(signed_payload(
+            sender,
+            session.session_id,
+            index,
+            message,
+            &last_seen,
+        ));
+        session
+            .public_key
+            .verify(Pkcs1v15Sign::new::<Sha256>(), &hashed, signature)
+            .map_err(|_| ChatError::InvalidSignature)?;
+
+        self.next_index += 1;
+        self.last_timestamp = message.timestamp;
+        Ok(Some(ValidatedMessage { index, last_seen }))
+    }
+}
+
+/// Builds the data the client signed, this must match vanilla byte for byte
+fn signed_payload(
+    sender: Uuid,
+    session_id: Uuid,
+    index: i32,
+    message: &SChatMessage,
+    last_seen: &[Box<[u8]>],
+) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(64 + message.message.len() + last_seen.len() * 256);
+    // Signature version
+    payload.extend_from_slice(&1i32.to_be_bytes());
+    // Message link
+    for uuid in [sender, session_id] {
+        let (most, least) = uuid.as_u64_pair();
+        payload.extend_from_slice(&most.to_be_bytes());
+        payload.extend_from_slice(&least.to_be_bytes());
+    }
+    payload.extend_from_slice(&index.to_be_bytes());
+    // Message body, the timestamp is signed in seconds
+    payload.extend_from_slice(&message.salt.to_be_bytes());
+    payload.extend_from_slice(&(message.timestamp / 1000).to_be_bytes());
+    payload.extend_from_slice(&(message.message.len() as i32).to_be_bytes());
+    payload.extend_from_slice(message.message.as_bytes());
+    payload.extend_from_slice(&(last_seen.len() as i32).to_be_bytes());
+    for signature in last_seen {
+        payload.extend_from_slice(signature);
+    }
+    payload
+}
@@ -0,0 +1,124 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::{engine::general_purpose, Engine};
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_protocol::{client::play::InitChat, server::play::SPlayerSession};
+use rsa::{pkcs8::DecodePublicKey, Pkcs1v15Sign, RsaPublicKey};
+use serde::Deserialize;
+use sha1::{Digest, Sha1};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum SessionError {
+    #[error("Expired profile public key")]
+    Expired,
+    #[error("Invalid profile public key")]
+    InvalidKey,
+    #[error("Invalid signature for profile public key")]
+    InvalidSignature,
+}
+
+/// The chat session of a player, used to verify the signatures of their messages
+pub struct ChatSession {
+    pub session_id: Uuid,
+    /// When the public key expires, in epoch milliseconds
+    pub expires_at: i64,
+    pub public_key: RsaPublicKey,
+    /// The X.509 encoded public key, as sent by the client
+    pub public_key_der: Box<[u8]>,
+    /// The public key signed by Mojang
+    pub key_signature: Box<[u8]>,
+}
+
+impl ChatSession {
+    /// Checks that Mojang issued the public key to `owner`
+    pub fn validate(
+        owner: Uuid,
+        session: SPlayerSession,
+        mojang_keys: &[RsaPublicKey],
+    ) -> Result<Self, SessionError> {
+        if session.expires_at < now_millis() {
+            return Err(SessionError::Expired);
+        }
+
+        let (most, least) = owner.as_u64_pair();
+        let mut payload = Vec::with_capacity(24 + session.public_key.len());
+        payload.extend_from_slice(&most.to_be_bytes());
+        payload.extend_from_slice(&least.to_be_bytes());
+        payload.extend_from_slice(&session.expires_at.to_be_bytes());
+        payload.extend_from_slice(&session.public_key);
+        let hashed = Sha1::digest(&payload);
+        let signed_by_mojang = mojang_keys.iter().any(|key| {
+            key.verify(Pkcs1v15Sign::new::<Sha1>(), &hashed, &session.key_signature)
+                .is_ok()
+        });
+        if !signed_by_mojang {
+            return Err(SessionError::InvalidSignature);
+        }
+
+        let public_key = RsaPublicKey::from_public_key_der(&session.public_key)
+            .map_err(|_| SessionError::InvalidKey)?;
+        Ok(Self {
+            session_id: session.session_id,
+            expires_at: session.expires_at,
+            public_key,
+            public_key_der: session.public_key.to_vec().into_boxed_slice(),
+            key_signature: session.key_signature.to_vec().into_boxed_slice(),
+        })
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at < now_millis()
+    }
+
+    /// The session as sent to other players, so they can verify messages themselves
+    pub fn init_chat(&self) -> InitChat<'_> {
+        InitChat {
+            session_id: self.session_id,
+            expires_at: self.expires_at,
+            public_key: &self.public_key_der,
+            key_signature: &self.key_signature,
+        }
+    }
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_millis() as i64)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicKeys {
+    player_certificate_keys: Vec<PublicKey>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicKey {
+    /// Base64 encoded X.509 public key
+    public_key: String,
+}
+
+/// Fetches the keys Mojang signs player public keys with
+pub async fn fetch_mojang_keys(
+    auth_client: &reqwest::Client,
+) -> Result<Vec<RsaPublicKey>, reqwest::Error> {
+    let keys: PublicKeys = auth_client
+        .get(&ADVANCED_CONFIG.get().authentication.public_keys_url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(keys
+        .player_certificate_keys
+        .iter()
+        .filter_map(|key| {
+            let der = general_purpose::STANDARD.decode(&key.public_key).ok()?;
+            RsaPublicKey::from_public_key_der(&der).ok()
+        })
+        .collect())
+}
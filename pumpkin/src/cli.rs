@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use pumpkin_config::overrides::ConfigOverrides;
+
+/// Command line flags, they take precedence over the configuration files
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Args {
+    /// The directory the configuration files are in
+    #[arg(long, value_name = "DIR")]
+    pub config: Option<PathBuf>,
+    /// The port to listen on, the address is still taken from `server_address`
+    #[arg(long)]
+    pub port: Option<u16>,
+    /// The directory the world is stored in
+    #[arg(long, value_name = "DIR")]
+    pub world_dir: Option<PathBuf>,
+    /// Whether players must have a valid Minecraft account
+    #[arg(long, value_name = "BOOL")]
+    pub online_mode: Option<bool>,
+    /// Disables online mode, same as `--online-mode false`
+    #[arg(long, conflicts_with = "online_mode")]
+    pub offline: bool,
+    /// The maximum number of players allowed on the server
+    #[arg(long)]
+    pub max_players: Option<u32>,
+    /// The server's description displayed on the status screen
+    #[arg(long)]
+    pub motd: Option<String>,
+    /// Don't read commands from the console, e.g. when running without a terminal
+    #[arg(long)]
+    pub no_console: bool,
+    /// Don't load any plugins
+    #[arg(long)]
+    pub no_plugins: bool,
+}
+
+impl Args {
+    pub fn config_overrides(&self) -> ConfigOverrides {
+        ConfigOverrides {
+            config_dir: self.config.clone(),
+            port: self.port,
+            level_name: self.world_dir.clone(),
+            online_mode: if self.offline {
+                Some(false)
+            } else {
+                self.online_mode
+            },
+            max_players: self.max_players,
+            motd: self.motd.clone(),
+            use_console: self.no_console.then_some(false),
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use std::sync::atomic::Ordering;
+
+use pumpkin_core::math::position::WorldPosition;
+use pumpkin_core::GameMode;
+use pumpkin_inventory::container_click::{ClickType, KeyClick};
+use pumpkin_inventory::window_property::{self, WindowProperty};
+use pumpkin_inventory::{
+    move_into_slots, Anvil, Container, InventoryError, OpenContainer, OptionallyCombinedContainer,
+    WindowType,
+};
+use pumpkin_protocol::client::play::CWorldEvent;
+use pumpkin_protocol::server::play::SRenameItem;
+use pumpkin_world::block::block_registry::BlockId;
+use pumpkin_world::block::BlockState;
+use pumpkin_world::item::anvil::{self, AnvilResult};
+use pumpkin_world::item::{ItemStack, MAX_NAME_LENGTH};
+
+use crate::entity::player::Player;
+use crate::server::Server;
+use crate::world::block_entity::NEXT_CONTAINER_ID;
+
+/// Anvils get damaged by this many of their uses, like in vanilla
+const DAMAGE_CHANCE: f32 = 0.12;
+/// The world events which play the sounds of anvils
+const ANVIL_DESTROYED: i32 = 1029;
+const ANVIL_USED: i32 = 1030;
+
+/// The anvil a damaged anvil becomes, None once it breaks
+fn damaged_anvil(registry_id: &str) -> Option<&'static str> {
+    match registry_id {
+        "minecraft:anvil" => Some("minecraft:chipped_anvil"),
+        "minecraft:chipped_anvil" => Some("minecraft:damaged_anvil"),
+        _ => None,
+    }
+}
+
+impl Player {
+    /// Opens the anvil at `position`, every player works in their own window
+    pub fn open_anvil(&self, server: &Server, position: &WorldPosition) {
+        self.close_container(server);
+        let container_id = NEXT_CONTAINER_ID.fetch_add(1, Ordering::Relaxed);
+        server.open_containers.write().insert(
+            container_id,
+            OpenContainer::new(self.entity_id(), Box::new(Anvil::new())),
+        );
+        self.open_container.store(Some(container_id));
+        self.anvil.store(Some(*position));
+        self.open_container(server, "minecraft:anvil");
+    }
+
+    /// Called after the player closed a container. If it was an anvil the items in it go back
+    /// into the inventory, what doesn't fit is dropped like in vanilla
+    pub(super) fn close_anvil(&self, server: &Server, container_id: u64) {
+        let items: Vec<ItemStack> = {
+            let mut open_containers = server.open_containers.write();
+            let is_anvil = open_containers
+                .get(&container_id)
+                .is_some_and(|open| *open.container().lock().window_type() == WindowType::Anvil);
+            if !is_anvil {
+                return;
+            }
+            let Some(open_container) = open_containers.remove(&container_id) else {
+                return;
+            };
+            let mut container = open_container.container().lock();
+            // The result is only made of the other items
+            let mut slots = container.all_slots();
+            [Anvil::LEFT_SLOT, Anvil::RIGHT_SLOT]
+                .into_iter()
+                .filter_map(|slot| slots[slot].take())
+                .collect()
+        };
+        self.anvil.store(None);
+        *self.anvil_name.lock() = None;
+        self.give_or_drop(items);
+    }
+
+    /// What the anvil makes of the items in the window for the player
+    fn anvil_result(&self, container: &dyn Container) -> AnvilResult {
+        let slots = container.all_slots_ref();
+        let Some(left) = slots[Anvil::LEFT_SLOT] else {
+            return AnvilResult::default();
+        };
+        anvil::anvil_result(
+            left,
+            slots[Anvil::RIGHT_SLOT],
+            self.anvil_name.lock().as_deref(),
+            self.gamemode.load() == GameMode::Creative,
+        )
+    }
+
+    /// Puts what the anvil makes into its result slot after its items or the name changed, and
+    /// shows the player what it costs
+    pub(super) fn update_anvil_result(&self, opened_container: Option<&mut Box<dyn Container>>) {
+        let Some(container) =
+            opened_container.filter(|container| *container.window_type() == WindowType::Anvil)
+        else {
+            return;
+        };
+        let result = self.anvil_result(&**container);
+        let mut slots = container.all_slots();
+        if *slots[Anvil::RESULT_SLOT] != result.item {
+            *slots[Anvil::RESULT_SLOT] = result.item;
+            drop(slots);
+            self.set_container_content(Some(container));
+        }
+        self.set_container_property(WindowProperty::new(
+            window_property::Anvil::RepairCost,
+            result.cost.clamp(0, i16::MAX.into()) as i16,
+        ));
+    }
+
+    /// The player typed a new name for the item in the anvil
+    pub fn handle_rename_item(&self, server: &Server, packet: SRenameItem) {
+        self.update_last_action();
+        let Some(container) = self.get_open_container(server) else {
+            return;
+        };
+        let mut container = container.lock();
+        if *container.window_type() != WindowType::Anvil {
+            return;
+        }
+        // Like in vanilla, formatting codes are taken out and names which are too long ignored
+        let name: String = packet
+            .item_name
+            .chars()
+            .filter(|char| *char != '§' && !char.is_control())
+            .collect();
+        if name.chars().count() > MAX_NAME_LENGTH {
+            return;
+        }
+        *self.anvil_name.lock() = Some(name);
+        self.update_anvil_result(Some(&mut *container));
+    }
+
+    /// Clicks on the result slot of an anvil take the result if the player has the levels it
+    /// costs: into the carried stack, into the inventory with shift clicks, into the hotbar with
+    /// number keys or out of the window with the drop key. Nothing can be put into it. Returns
+    /// the levels the player has to pay
+    pub(super) fn click_anvil_result(
+        &self,
+        opened_container: Option<&mut Box<dyn Container>>,
+        click_type: ClickType,
+    ) -> Result<Option<i32>, InventoryError> {
+        let mut thrown = None;
+        let cost = {
+            let Some(anvil) = opened_container else {
+                return Err(InventoryError::InvalidSlot);
+            };
+            let result = self.anvil_result(&**anvil);
+            let Some(item) = result.item else {
+                return Ok(None);
+            };
+            let creative = self.gamemode.load() == GameMode::Creative;
+            if result.cost <= 0 || (!creative && self.experience.load().level < result.cost) {
+                return Ok(None);
+            }
+            let mut inventory = self.inventory.lock();
+            let mut container = OptionallyCombinedContainer::new(&mut inventory, Some(anvil));
+            let container_slots = container.container_slots();
+            let mut slots = container.all_slots();
+            match click_type {
+                ClickType::MouseClick(_) => {
+                    if self.carried_item.load().is_some() {
+                        return Ok(None);
+                    }
+                    self.carried_item.store(Some(item));
+                }
+                ClickType::ShiftClick => {
+                    // Into the player inventory, starting at the end of the hotbar like vanilla
+                    let mut moving = Some(item);
+                    let mut targets: Vec<Option<ItemStack>> =
+                        slots[container_slots..].iter().map(|slot| **slot).collect();
+                    move_into_slots(&mut moving, targets.iter_mut().rev());
+                    if moving.is_some() {
+                        return Ok(None);
+                    }
+                    for (slot, target) in slots[container_slots..].iter_mut().zip(targets) {
+                        **slot = target;
+                    }
+                }
+                ClickType::KeyClick(KeyClick::Slot(key)) => {
+                    let hotbar_slot = slots
+                        .get_mut(container_slots + 27 + key as usize)
+                        .ok_or(InventoryError::InvalidSlot)?;
+                    if hotbar_slot.is_some() {
+                        return Ok(None);
+                    }
+                    **hotbar_slot = Some(item);
+                }
+                ClickType::DropType(_) => thrown = Some(item),
+                _ => return Ok(None),
+            }
+            *slots[Anvil::LEFT_SLOT] = None;
+            *slots[Anvil::RESULT_SLOT] = None;
+            // Repairing only uses up as much material as it needs
+            let material = &mut *slots[Anvil::RIGHT_SLOT];
+            *material = material
+                .filter(|_| result.material_count > 0)
+                .map(|stack| {
+                    stack.with_count(stack.item_count.saturating_sub(result.material_count))
+                })
+                .filter(|stack| stack.item_count > 0);
+            result.cost
+        };
+        if let Some(item) = thrown {
+            self.drop_item(item, false);
+        }
+        Ok(Some(cost))
+    }
+
+    /// Charges the levels for what the player took out of the anvil. Like in vanilla the anvil
+    /// gets damaged now and then, until it breaks
+    pub(super) fn use_anvil(&self, server: &Server, cost: i32) {
+        let creative = self.gamemode.load() == GameMode::Creative;
+        if !creative {
+            self.add_experience_levels(-cost);
+        }
+        let Some(position) = self.anvil.load() else {
+            return;
+        };
+        let world = self.living_entity.entity.world();
+        let Some((registry_id, properties)) = world
+            .level
+            .get_block(&position)
+            .and_then(|block| block.registry_state())
+        else {
+            return;
+        };
+        if creative || rand::random::<f32>() >= DAMAGE_CHANCE {
+            world.broadcast_packet_all(&CWorldEvent::new(ANVIL_USED, &position, 0, false));
+            return;
+        }
+        match damaged_anvil(registry_id)
+            .and_then(|next| BlockState::new(next, Some(properties)).ok())
+        {
+            Some(damaged) => {
+                world.set_block(&position, damaged.into());
+                world.broadcast_packet_all(&CWorldEvent::new(ANVIL_USED, &position, 0, false));
+            }
+            None => {
+                world.set_block(&position, BlockId::default());
+                world.broadcast_packet_all(&CWorldEvent::new(ANVIL_DESTROYED, &position, 0, false));
+                self.close_container(server);
+                self.send_close_container();
+            }
+        }
+    }
+}
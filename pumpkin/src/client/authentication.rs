@@ -57,16 +57,22 @@ pub async fn authenticate(
     ip: &IpAddr,
     auth_client: &reqwest::Client,
 ) -> Result<GameProfile, AuthError> {
-    assert!(ADVANCED_CONFIG.authentication.enabled);
-    let address = if ADVANCED_CONFIG.authentication.prevent_proxy_connections {
+    assert!(ADVANCED_CONFIG.get().authentication.enabled);
+    let address = if ADVANCED_CONFIG
+        .get()
+        .authentication
+        .prevent_proxy_connections
+    {
         ADVANCED_CONFIG
+            .get()
             .authentication
-            .auth_url
+            .prevent_proxy_connection_auth_url
             .replace("{username}", username)
             .replace("{server_hash}", server_hash)
-            .replace("{}", &ip.to_string())
+            .replace("{ip}", &ip.to_string())
     } else {
         ADVANCED_CONFIG
+            .get()
             .authentication
             .auth_url
             .replace("{username}", username)
@@ -145,6 +151,17 @@ pub enum AuthError {
     UnknownStatusCode(StatusCode),
 }
 
+impl AuthError {
+    /// Whether the authentication server could not be reached, rather than rejecting the player
+    pub fn is_unreachable(&self) -> bool {
+        match self {
+            Self::FailedResponse => true,
+            Self::UnknownStatusCode(status) => status.is_server_error(),
+            _ => false,
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum TextureError {
     #[error("Invalid URL")]
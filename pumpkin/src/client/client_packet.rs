@@ -1,26 +1,31 @@
 use num_traits::FromPrimitive;
-use pumpkin_config::{ADVANCED_CONFIG, BASIC_CONFIG};
+use pumpkin_config::{messages::format_message, ADVANCED_CONFIG, BASIC_CONFIG, MESSAGES};
 use pumpkin_core::text::TextComponent;
+use pumpkin_plugins::events::{ResourcePackStatus, ResourcePackStatusEvent};
 use pumpkin_protocol::{
     client::{
         config::{CConfigAddResourcePack, CFinishConfig, CKnownPacks, CRegistryData},
         login::{CLoginSuccess, CSetCompression},
-        status::CPingResponse,
+        status::{CPingResponse, CStatusResponse},
     },
     server::{
-        config::{SAcknowledgeFinishConfig, SClientInformationConfig, SKnownPacks, SPluginMessage},
+        config::{
+            SAcknowledgeFinishConfig, SClientInformationConfig, SConfigResourcePackResponse,
+            SKnownPacks, SPluginMessage,
+        },
         handshake::SHandShake,
         login::{SEncryptionResponse, SLoginAcknowledged, SLoginPluginResponse, SLoginStart},
         status::{SStatusPingRequest, SStatusRequest},
     },
+    version::ProtocolVersion,
     ConnectionState, KnownPack, CURRENT_MC_PROTOCOL,
 };
 use uuid::Uuid;
 
 use crate::{
-    client::authentication::{self, validate_textures, GameProfile},
-    entity::player::{ChatMode, Hand},
-    proxy::{bungeecord::bungeecord_login, velocity::velocity_login},
+    client::authentication::{self, offline_uuid, validate_textures, GameProfile},
+    entity::player::{Arm, ChatMode},
+    proxy::{bungeecord::bungeecord_login, floodgate, velocity::velocity_login},
     server::{Server, CURRENT_MC_VERSION},
 };
 
@@ -36,25 +41,72 @@ impl Client {
         let version = handshake.protocol_version.0;
         self.protocol_version
             .store(version, std::sync::atomic::Ordering::Relaxed);
-        *self.server_address.lock() = handshake.server_address;
+        if ADVANCED_CONFIG.get().floodgate.enabled {
+            let (server_address, data) = floodgate::split_server_address(&handshake.server_address);
+            if let Some(data) = data {
+                match floodgate::decode(data) {
+                    Ok(bedrock) => {
+                        if let Some(ip) = bedrock.ip {
+                            self.address.lock().set_ip(ip);
+                        }
+                        *self.bedrock.lock() = Some(bedrock);
+                    }
+                    Err(err) => {
+                        self.kick(&err.to_string());
+                        return;
+                    }
+                }
+            }
+            *self.server_address.lock() = server_address;
+        } else {
+            *self.server_address.lock() = handshake.server_address;
+        }
 
-        self.connection_state.store(handshake.next_state);
+        if handshake.next_state == ConnectionState::Transfer {
+            // A transfer is a normal login, the Client just came from another server
+            self.transferred
+                .store(true, std::sync::atomic::Ordering::Relaxed);
+            self.connection_state.store(ConnectionState::Login);
+            if !BASIC_CONFIG.get().accept_transfers {
+                self.kick(&format_message(&MESSAGES.get().transfers_disabled, &[]));
+                return;
+            }
+        } else {
+            self.connection_state.store(handshake.next_state);
+        }
         if self.connection_state.load() != ConnectionState::Status {
             let protocol = version;
+            if let Some(version) = ProtocolVersion::find(protocol) {
+                self.version.store(version);
+                return;
+            }
+            let client_protocol = protocol.to_string();
+            let server_protocol = CURRENT_MC_PROTOCOL.to_string();
+            let placeholders = [
+                ("client_protocol", client_protocol.as_str()),
+                ("version", CURRENT_MC_VERSION),
+                ("protocol", server_protocol.as_str()),
+            ];
             match protocol.cmp(&(CURRENT_MC_PROTOCOL as i32)) {
                 std::cmp::Ordering::Less => {
-                    self.kick(&format!("Client outdated ({protocol}), Server uses Minecraft {CURRENT_MC_VERSION}, Protocol {CURRENT_MC_PROTOCOL}"));
+                    self.kick(&format_message(
+                        &MESSAGES.get().outdated_client,
+                        &placeholders,
+                    ));
                 }
                 std::cmp::Ordering::Equal => {}
                 std::cmp::Ordering::Greater => {
-                    self.kick(&format!("Server outdated, Server uses Minecraft {CURRENT_MC_VERSION}, Protocol {CURRENT_MC_PROTOCOL}"));
+                    self.kick(&format_message(
+                        &MESSAGES.get().outdated_server,
+                        &placeholders,
+                    ));
                 }
             }
         }
     }
 
     pub fn handle_status_request(&self, server: &Server, _status_request: SStatusRequest) {
-        self.send_packet(&server.get_status());
+        self.send_packet(&CStatusResponse::new(&server.get_status_json()));
     }
 
     pub fn handle_ping_request(&self, ping_request: SStatusPingRequest) {
@@ -73,14 +125,31 @@ impl Client {
     pub fn handle_login_start(&self, server: &Server, login_start: SLoginStart) {
         log::debug!("login start, State {:?}", self.connection_state);
 
+        if server.get_player_count() >= BASIC_CONFIG.get().max_players as usize {
+            self.kick(&format_message(
+                &MESSAGES.get().server_full,
+                &[("max_players", &BASIC_CONFIG.get().max_players.to_string())],
+            ));
+            return;
+        }
+
+        // Geyser already authenticated Bedrock players, their name may not be valid on Java
+        let bedrock = self.bedrock.lock().clone();
+        if let Some(bedrock) = bedrock {
+            let profile = bedrock.game_profile(&ADVANCED_CONFIG.get().floodgate);
+            self.finish_login(&profile);
+            *self.gameprofile.lock() = Some(profile);
+            return;
+        }
+
         if !Self::is_valid_player_name(&login_start.name) {
-            self.kick("Invalid characters in username");
+            self.kick(&format_message(&MESSAGES.get().invalid_username, &[]));
             return;
         }
         // default game profile, when no online mode
         // TODO: make offline uuid
         let mut gameprofile = self.gameprofile.lock();
-        let proxy = &ADVANCED_CONFIG.proxy;
+        let proxy = &ADVANCED_CONFIG.get().proxy;
         if proxy.enabled {
             if proxy.velocity.enabled {
                 velocity_login(self);
@@ -104,7 +173,9 @@ impl Client {
 
             // TODO: check config for encryption
             let verify_token: [u8; 4] = rand::random();
-            self.send_packet(&server.encryption_request(&verify_token, BASIC_CONFIG.online_mode));
+            self.send_packet(
+                &server.encryption_request(&verify_token, BASIC_CONFIG.get().online_mode),
+            );
         }
     }
 
@@ -118,20 +189,67 @@ impl Client {
         self.set_encryption(Some(&shared_secret))
             .unwrap_or_else(|e| self.kick(&e.to_string()));
 
-        let mut gameprofile = self.gameprofile.lock();
-
-        if BASIC_CONFIG.online_mode {
-            match self
-                .autenticate(server, &shared_secret, &gameprofile.as_ref().unwrap().name)
-                .await
-            {
-                Ok(profile) => *gameprofile = Some(profile),
+        if BASIC_CONFIG.get().online_mode {
+            // Don't hold the lock while we wait for the authentication server
+            let Some(name) = self
+                .gameprofile
+                .lock()
+                .as_ref()
+                .map(|profile| profile.name.clone())
+            else {
+                self.kick("No Game profile");
+                return;
+            };
+            match self.autenticate(server, &shared_secret, &name).await {
+                Ok(profile) => {
+                    server.skin_cache.store(&name, &profile.properties).await;
+                    *self.gameprofile.lock() = Some(profile);
+                }
+                Err(e)
+                    if e.is_unreachable()
+                        && ADVANCED_CONFIG.get().authentication.offline_fallback =>
+                {
+                    log::warn!(
+                        "Authentication server unreachable ({e}), {name} joins in offline mode"
+                    );
+                    let Ok(id) = offline_uuid(&name) else {
+                        self.kick("Failed to make offline UUID");
+                        return;
+                    };
+                    // The session server is down, but we may still know their skin
+                    let properties = server
+                        .skin_cache
+                        .cached(&name, true)
+                        .await
+                        .unwrap_or_default();
+                    *self.gameprofile.lock() = Some(GameProfile {
+                        id,
+                        name,
+                        properties,
+                        profile_actions: None,
+                    });
+                }
                 Err(e) => {
                     self.kick(&e.to_string());
+                    return;
+                }
+            }
+        } else if ADVANCED_CONFIG.get().skins.offline_skins {
+            let name = self
+                .gameprofile
+                .lock()
+                .as_ref()
+                .map(|profile| profile.name.clone());
+            if let Some(name) = name {
+                if let Some(properties) = server.skin_cache.fetch(&name).await {
+                    if let Some(profile) = self.gameprofile.lock().as_mut() {
+                        profile.properties = properties;
+                    }
                 }
             }
         }
 
+        let gameprofile = self.gameprofile.lock().clone();
         if let Some(profile) = gameprofile.as_ref() {
             self.finish_login(profile);
         } else {
@@ -141,8 +259,12 @@ impl Client {
 
     fn finish_login(&self, profile: &GameProfile) {
         // enable compression
-        if ADVANCED_CONFIG.packet_compression.enabled {
-            let compression = ADVANCED_CONFIG.packet_compression.compression_info.clone();
+        if ADVANCED_CONFIG.get().packet_compression.enabled {
+            let compression = ADVANCED_CONFIG
+                .get()
+                .packet_compression
+                .compression_info
+                .clone();
             self.send_packet(&CSetCompression::new(compression.threshold.into()));
             self.set_compression(Some(compression));
         }
@@ -165,28 +287,30 @@ impl Client {
             // Check if player should join
             if let Some(actions) = &profile.profile_actions {
                 if !ADVANCED_CONFIG
+                    .get()
                     .authentication
                     .player_profile
                     .allow_banned_players
                 {
                     if !actions.is_empty() {
-                        self.kick("Your account can't join");
+                        self.kick(&format_message(&MESSAGES.get().account_not_allowed, &[]));
                     }
                 } else {
                     for allowed in &ADVANCED_CONFIG
+                        .get()
                         .authentication
                         .player_profile
                         .allowed_actions
                     {
                         if !actions.contains(allowed) {
-                            self.kick("Your account can't join");
+                            self.kick(&format_message(&MESSAGES.get().account_not_allowed, &[]));
                         }
                     }
                 }
             }
             // validate textures
             for property in &profile.properties {
-                validate_textures(property, &ADVANCED_CONFIG.authentication.textures)
+                validate_textures(property, &ADVANCED_CONFIG.get().authentication.textures)
                     .map_err(AuthError::TextureError)?;
             }
             return Ok(profile);
@@ -202,9 +326,9 @@ impl Client {
         _login_acknowledged: SLoginAcknowledged,
     ) {
         self.connection_state.store(ConnectionState::Config);
-        self.send_packet(&server.get_branding());
+        server.send_branding(self);
 
-        let resource_config = &ADVANCED_CONFIG.resource_pack;
+        let resource_config = &ADVANCED_CONFIG.get().resource_pack;
         if resource_config.enabled {
             let resource_pack = CConfigAddResourcePack::new(
                 Uuid::new_v3(
@@ -224,6 +348,8 @@ impl Client {
             self.send_packet(&resource_pack);
         }
 
+        self.fire_configuration_event(server);
+
         // known data packs
         self.send_packet(&CKnownPacks::new(&[KnownPack {
             namespace: "minecraft",
@@ -235,7 +361,7 @@ impl Client {
     pub fn handle_client_information_config(&self, client_information: SClientInformationConfig) {
         dbg!("got client settings");
         if let (Some(main_hand), Some(chat_mode)) = (
-            Hand::from_i32(client_information.main_hand.into()),
+            Arm::from_i32(client_information.main_hand.into()),
             ChatMode::from_i32(client_information.chat_mode.into()),
         ) {
             *self.config.lock() = Some(PlayerConfig {
@@ -265,6 +391,33 @@ impl Client {
         }
     }
 
+    pub fn handle_resource_pack_response(
+        &self,
+        server: &Server,
+        resource_pack_response: SConfigResourcePackResponse,
+    ) {
+        let Some(status) = ResourcePackStatus::from_id(resource_pack_response.result.0) else {
+            self.kick("Invalid resource pack status");
+            return;
+        };
+        log::debug!("Resource pack status: {status:?}");
+        if let Some(profile) = self.gameprofile.lock().as_ref() {
+            let event = ResourcePackStatusEvent {
+                player_name: &profile.name,
+                player_uuid: profile.id,
+                pack_uuid: resource_pack_response.uuid,
+                status,
+            };
+            server
+                .plugins
+                .read()
+                .for_each(|plugin| plugin.on_resource_pack_status(&event));
+        }
+        if status == ResourcePackStatus::Declined && ADVANCED_CONFIG.get().resource_pack.force {
+            self.kick(&format_message(&MESSAGES.get().resource_pack_declined, &[]));
+        }
+    }
+
     pub fn handle_known_packs(&self, server: &Server, _config_acknowledged: SKnownPacks) {
         for registry in &server.cached_registry {
             self.send_packet(&CRegistryData::new(
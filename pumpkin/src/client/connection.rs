@@ -0,0 +1,34 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use bytes::BytesMut;
+use tokio::{
+    io::{AsyncRead, AsyncWrite, AsyncWriteExt},
+    sync::mpsc,
+};
+
+/// The reading half of a Client's connection, either TCP or a Unix socket
+pub type ConnectionReader = Box<dyn AsyncRead + Unpin + Send>;
+/// The writing half of a Client's connection, either TCP or a Unix socket
+pub type ConnectionWriter = Box<dyn AsyncWrite + Unpin + Send>;
+
+/// Writes everything the Client flushes to the connection.
+///
+/// Ends once the Client closed the connection and everything queued before was written.
+/// `queued_bytes` counts down as the bytes get written
+pub(super) async fn write_loop(
+    mut writer: ConnectionWriter,
+    mut receiver: mpsc::UnboundedReceiver<BytesMut>,
+    queued_bytes: Arc<AtomicUsize>,
+) {
+    while let Some(buf) = receiver.recv().await {
+        if let Err(err) = writer.write_all(&buf).await {
+            log::debug!("Failed to write to connection: {err}");
+            return;
+        }
+        queued_bytes.fetch_sub(buf.len(), Ordering::Relaxed);
+    }
+    let _ = writer.shutdown().await;
+}
@@ -1,16 +1,18 @@
 use crate::entity::player::Player;
 use crate::server::Server;
+use crate::world::block_entity;
 use itertools::Itertools;
 use parking_lot::Mutex;
 use pumpkin_core::text::TextComponent;
 use pumpkin_core::GameMode;
 use pumpkin_inventory::container_click::{
-    Click, ClickType, KeyClick, MouseClick, MouseDragState, MouseDragType,
+    Click, ClickType, DropType, KeyClick, MouseClick, MouseDragState, MouseDragType,
 };
 use pumpkin_inventory::drag_handler::DragHandler;
+use pumpkin_inventory::player::PlayerInventory;
 use pumpkin_inventory::window_property::{WindowProperty, WindowPropertyTrait};
-use pumpkin_inventory::Container;
 use pumpkin_inventory::{container_click, InventoryError, OptionallyCombinedContainer};
+use pumpkin_inventory::{move_into_slots, Anvil, Container, EnchantingTable, Furnace, WindowType};
 use pumpkin_protocol::client::play::{
     CCloseContainer, COpenScreen, CSetContainerContent, CSetContainerProperty, CSetContainerSlot,
 };
@@ -19,6 +21,8 @@ use pumpkin_protocol::slot::Slot;
 use pumpkin_world::item::ItemStack;
 use std::sync::Arc;
 
+use super::furnace::furnace_slot;
+
 impl Player {
     pub fn open_container(&self, server: &Server, minecraft_menu_id: &str) {
         let inventory = self.inventory.lock();
@@ -52,10 +56,29 @@ impl Player {
         self.set_container_content(container.as_deref_mut());
     }
 
+    /// Closes the container the player has open, if any
+    pub fn close_container(&self, server: &Server) {
+        let Some(id) = self.open_container.take() else {
+            return;
+        };
+        if let Some(container) = server.open_containers.write().get_mut(&id) {
+            container.remove_player(self.entity_id())
+        }
+        block_entity::close_block_container(server, &self.living_entity.entity.world(), id);
+        self.close_crafting_table(server, id);
+        self.close_enchanting_table(server, id);
+        self.close_anvil(server, id);
+    }
+
     pub fn set_container_content(&self, container: Option<&mut Box<dyn Container>>) {
         let mut inventory = self.inventory.lock();
 
-        let total_opened_containers = inventory.total_opened_containers;
+        // The player inventory is always window 0
+        let window_id = if container.is_some() {
+            inventory.total_opened_containers
+        } else {
+            0
+        };
         let container = OptionallyCombinedContainer::new(&mut inventory, container);
 
         let slots = container
@@ -74,17 +97,13 @@ impl Player {
         let i = inventory
             .state_id
             .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-        let packet = CSetContainerContent::new(
-            total_opened_containers,
-            ((i + 1) as i32).into(),
-            &slots,
-            &carried_item,
-        );
+        let packet =
+            CSetContainerContent::new(window_id, ((i + 1) as i32).into(), &slots, &carried_item);
         self.client.send_packet(&packet);
     }
 
     /// The official Minecraft client is weird, and will always just close *any* window that is opened when this gets sent
-    pub fn close_container(&self) {
+    pub fn send_close_container(&self) {
         let mut inventory = self.inventory.lock();
         inventory.total_opened_containers += 1;
         self.client
@@ -92,7 +111,7 @@ impl Player {
     }
 
     pub fn set_container_property<T: WindowPropertyTrait>(
-        &mut self,
+        &self,
         window_property: WindowProperty<T>,
     ) {
         let (id, value) = window_property.into_tuple();
@@ -108,6 +127,7 @@ impl Player {
         server: &Arc<Server>,
         packet: SClickContainer,
     ) -> Result<(), InventoryError> {
+        self.update_last_action();
         let opened_container = self.get_open_container(server);
         let mut opened_container = opened_container.as_ref().map(|container| container.lock());
         let drag_handler = &server.drag_handler;
@@ -141,13 +161,95 @@ impl Player {
             packet.slot,
         )?;
         let mut update_whole_container = false;
+        // The inventory has a crafting grid too
+        let has_crafting_grid = opened_container
+            .as_ref()
+            .map_or(true, |container| container.crafting_grid_width().is_some());
+        let on_crafting_result =
+            has_crafting_grid && matches!(click.slot, container_click::Slot::Normal(0));
+        let is_furnace = opened_container
+            .as_ref()
+            .is_some_and(|container| Furnace::is_furnace(container.window_type()));
+        let on_furnace_result = is_furnace
+            && matches!(
+                click.slot,
+                container_click::Slot::Normal(Furnace::RESULT_SLOT)
+            );
+        let mut took_furnace_result = false;
+        let is_anvil = opened_container
+            .as_ref()
+            .is_some_and(|container| *container.window_type() == WindowType::Anvil);
+        let on_anvil_result = is_anvil
+            && matches!(
+                click.slot,
+                container_click::Slot::Normal(Anvil::RESULT_SLOT)
+            );
+        let mut anvil_cost = None;
+        // Items are only dragged into slots which accept them, e.g. lapis lazuli into an
+        // enchanting table
+        let refused_drag = match (
+            &click.click_type,
+            opened_container.as_ref(),
+            self.carried_item.load(),
+        ) {
+            (
+                ClickType::MouseDrag {
+                    drag_state: MouseDragState::AddSlot(slot),
+                },
+                Some(container),
+                Some(carried_item),
+            ) => {
+                *slot < container.all_slots_ref().len() && !container.accepts(*slot, &carried_item)
+            }
+            _ => false,
+        };
 
         match click.click_type {
+            ClickType::MouseClick(_)
+            | ClickType::ShiftClick
+            | ClickType::KeyClick(_)
+            | ClickType::DropType(_)
+                if on_crafting_result =>
+            {
+                self.click_crafting_result(
+                    server,
+                    opened_container.as_deref_mut(),
+                    click.click_type,
+                )
+            }
+            // Nothing can be put into the result slot
+            ClickType::MouseDrag {
+                drag_state: MouseDragState::AddSlot(0),
+            } if has_crafting_grid => Ok(()),
+            ClickType::MouseClick(_)
+            | ClickType::ShiftClick
+            | ClickType::KeyClick(_)
+            | ClickType::DropType(_)
+                if on_furnace_result =>
+            {
+                took_furnace_result =
+                    self.click_furnace_result(opened_container.as_deref_mut(), click.click_type)?;
+                Ok(())
+            }
+            ClickType::MouseDrag {
+                drag_state: MouseDragState::AddSlot(Furnace::RESULT_SLOT),
+            } if is_furnace => Ok(()),
+            ClickType::MouseClick(_)
+            | ClickType::ShiftClick
+            | ClickType::KeyClick(_)
+            | ClickType::DropType(_)
+                if on_anvil_result =>
+            {
+                anvil_cost =
+                    self.click_anvil_result(opened_container.as_deref_mut(), click.click_type)?;
+                Ok(())
+            }
+            ClickType::MouseDrag { .. } if refused_drag => Ok(()),
             ClickType::MouseClick(mouse_click) => {
                 self.mouse_click(opened_container.as_deref_mut(), mouse_click, click.slot)
             }
             ClickType::ShiftClick => {
-                self.shift_mouse_click(opened_container.as_deref_mut(), click.slot)
+                self.shift_mouse_click(server, opened_container.as_deref_mut(), click.slot)
             }
             ClickType::KeyClick(key_click) => match click.slot {
                 container_click::Slot::Normal(slot) => {
@@ -176,27 +278,60 @@ impl Player {
                 }
                 self.mouse_drag(drag_handler, opened_container.as_deref_mut(), drag_state)
             }
-            ClickType::DropType(_drop_type) => {
-                dbg!("todo");
-                Ok(())
-            }
+            ClickType::DropType(drop_type) => match click.slot {
+                container_click::Slot::Normal(slot) => {
+                    self.drop_from_slot(opened_container.as_deref_mut(), drop_type, slot)
+                }
+                container_click::Slot::OutsideInventory => Ok(()),
+            },
         }?;
+        let result_changed = has_crafting_grid
+            && self.update_crafting_result(server, opened_container.as_deref_mut());
+        // The client predicts what a click does, it gets the actual contents when it guessed wrong
+        if !self.matches_prediction(opened_container.as_deref_mut(), &packet) {
+            self.set_container_content(opened_container.as_deref_mut());
+        } else if result_changed {
+            self.send_crafting_result(opened_container.as_deref_mut());
+        }
+        self.update_enchanting_options(opened_container.as_deref_mut());
+        self.update_anvil_result(opened_container.as_deref_mut());
         if let Some(mut opened_container) = opened_container {
             if update_whole_container {
                 drop(opened_container);
                 self.send_whole_container_change(server).await?;
             } else if let container_click::Slot::Normal(slot_index) = click.slot {
-                let mut inventory = self.inventory.lock();
-                let combined_container =
-                    OptionallyCombinedContainer::new(&mut inventory, Some(&mut opened_container));
-                if let Some(slot) = combined_container.get_slot_excluding_inventory(slot_index) {
-                    let slot = Slot::from(slot);
-                    drop(opened_container);
+                // Don't hold any locks while sending the changes
+                let slot = {
+                    let mut inventory = self.inventory.lock();
+                    let combined_container = OptionallyCombinedContainer::new(
+                        &mut inventory,
+                        Some(&mut opened_container),
+                    );
+                    combined_container
+                        .get_slot_excluding_inventory(slot_index)
+                        .map(Slot::from)
+                };
+                drop(opened_container);
+                if let Some(slot) = slot {
                     self.send_container_changes(server, slot_index, slot)
                         .await?;
                 }
             }
         }
+        // Only once the furnace isn't locked anymore
+        if took_furnace_result {
+            if let Some(container_id) = self.open_container.load() {
+                self.living_entity.entity.world().take_furnace_experience(
+                    server,
+                    container_id,
+                    self,
+                );
+            }
+        }
+        // Only once the anvil isn't locked anymore, it may break
+        if let Some(cost) = anvil_cost {
+            self.use_anvil(server, cost);
+        }
         Ok(())
     }
 
@@ -216,76 +351,224 @@ impl Player {
                 self.carried_item.store(carried_item);
                 res
             }
-            container_click::Slot::OutsideInventory => Ok(()),
+            // Clicking outside of the window throws the carried stack, or one of its items
+            container_click::Slot::OutsideInventory => {
+                let Some(mut carried_item) = self.carried_item.take() else {
+                    return Ok(());
+                };
+                let count = match mouse_click {
+                    MouseClick::Left => carried_item.item_count,
+                    MouseClick::Right => 1,
+                };
+                carried_item.item_count -= count;
+                self.drop_item(carried_item.with_count(count), false);
+                if carried_item.item_count > 0 {
+                    self.carried_item.store(Some(carried_item));
+                }
+                Ok(())
+            }
         }
     }
 
+    /// Throws one item out of a slot, or the whole stack
+    fn drop_from_slot(
+        &self,
+        opened_container: Option<&mut Box<dyn Container>>,
+        drop_type: DropType,
+        slot: usize,
+    ) -> Result<(), InventoryError> {
+        let dropped = {
+            let mut inventory = self.inventory.lock();
+            let mut container = OptionallyCombinedContainer::new(&mut inventory, opened_container);
+            let mut slots = container.all_slots();
+            let slot = slots.get_mut(slot).ok_or(InventoryError::InvalidSlot)?;
+            let Some(item) = slot.as_mut() else {
+                return Ok(());
+            };
+            let count = match drop_type {
+                DropType::SingleItem => 1,
+                DropType::FullStack => item.item_count,
+            };
+            item.item_count -= count;
+            let dropped = item.with_count(count);
+            if item.item_count == 0 {
+                **slot = None;
+            }
+            dropped
+        };
+        self.drop_item(dropped, false);
+        Ok(())
+    }
+
+    /// Whether the slots the client changed with a click and its carried item are the same as on
+    /// the Server
+    fn matches_prediction(
+        &self,
+        opened_container: Option<&mut Box<dyn Container>>,
+        packet: &SClickContainer,
+    ) -> bool {
+        let same = |predicted: &Slot, item: Option<&ItemStack>| match (
+            predicted.clone().to_item(),
+            item,
+        ) {
+            (None, None) => true,
+            (Some(predicted), Some(item)) => predicted.is_same_stack(item),
+            _ => false,
+        };
+        if !same(&packet.carried_item, self.carried_item.load().as_ref()) {
+            return false;
+        }
+        let mut inventory = self.inventory.lock();
+        let container = OptionallyCombinedContainer::new(&mut inventory, opened_container);
+        let slots = container.all_slots_ref();
+        packet
+            .array_of_changed_slots
+            .iter()
+            .all(|(slot, predicted)| {
+                usize::try_from(*slot)
+                    .ok()
+                    .and_then(|slot| slots.get(slot))
+                    .is_some_and(|item| same(predicted, *item))
+            })
+    }
+
+    /// Moves the stack in a slot into the other part of the window: between a container and the
+    /// player inventory, or between the hotbar and the rest of the inventory
     fn shift_mouse_click(
         &self,
+        server: &Server,
         opened_container: Option<&mut Box<dyn Container>>,
         slot: container_click::Slot,
     ) -> Result<(), InventoryError> {
+        let container_click::Slot::Normal(slot) = slot else {
+            return Ok(());
+        };
         let mut inventory = self.inventory.lock();
         let mut container = OptionallyCombinedContainer::new(&mut inventory, opened_container);
+        let container_slots = container.container_slots();
+        let window_type = container.window_type();
+        let mut slots: Vec<_> = container.all_slots().into_iter().map(Some).collect();
+        let slot_count = slots.len();
+        let clicked = slots
+            .get_mut(slot)
+            .and_then(Option::take)
+            .ok_or(InventoryError::InvalidSlot)?;
+        let Some(item) = *clicked else {
+            return Ok(());
+        };
 
-        match slot {
-            container_click::Slot::Normal(slot) => {
-                let all_slots = container.all_slots();
-                if let Some(item_in_pressed_slot) = all_slots[slot].to_owned() {
-                    let slots = all_slots.into_iter().enumerate();
-                    // Hotbar
-                    let find_condition = |(slot_number, slot): (usize, &mut Option<ItemStack>)| {
-                        // TODO: Check for max item count here
-                        match slot {
-                            Some(item) => {
-                                if item.item_id == item_in_pressed_slot.item_id
-                                    && item.item_count != 64
-                                {
-                                    Some(slot_number)
-                                } else {
-                                    None
-                                }
-                            }
-                            None => Some(slot_number),
-                        }
-                    };
-
-                    let slots = if slot > 35 {
-                        slots.skip(9).find_map(find_condition)
-                    } else {
-                        slots.skip(36).rev().find_map(find_condition)
-                    };
-                    if let Some(slot) = slots {
-                        let mut item_slot = container.all_slots()[slot].map(|i| i.to_owned());
-                        container.handle_item_change(&mut item_slot, slot, MouseClick::Left)?;
-                        *container.all_slots()[slot] = item_slot;
+        let targets: Vec<usize> = if container_slots > 0 {
+            if slot < container_slots {
+                // Into the player inventory, starting at the end of the hotbar like vanilla
+                (container_slots..slot_count).rev().collect()
+            } else if Furnace::is_furnace(window_type) {
+                let hotbar = container_slots + 27;
+                match furnace_slot(server, window_type, &item) {
+                    Some(target) => vec![target],
+                    // Like in vanilla, other items move between the hotbar and the rest
+                    None if slot < hotbar => (hotbar..slot_count).collect(),
+                    None => (container_slots..hotbar).collect(),
+                }
+            } else if *window_type == WindowType::EnchantmentTable {
+                if EnchantingTable::is_lapis(&item) {
+                    vec![EnchantingTable::LAPIS_SLOT]
+                } else {
+                    // Only one item is enchanted at a time
+                    if let Some(target) = slots[EnchantingTable::ITEM_SLOT]
+                        .take()
+                        .filter(|target| target.is_none())
+                    {
+                        *target = Some(item.with_count(1));
+                        *clicked =
+                            (item.item_count > 1).then(|| item.with_count(item.item_count - 1));
                     }
+                    return Ok(());
+                }
+            } else if *window_type == WindowType::Anvil {
+                // Nothing can be put into the result slot
+                vec![Anvil::LEFT_SLOT, Anvil::RIGHT_SLOT]
+            } else {
+                (0..container_slots).collect()
+            }
+        } else {
+            let inventory = match slot {
+                // Crafting results are handled by `click_crafting_result`
+                0 => return Ok(()),
+                9..=35 => (36..45).collect(),
+                36..=44 => (9..36).collect(),
+                _ => (9..45).collect(),
+            };
+            // Armor goes into its slot first
+            match PlayerInventory::armor_slot(&item) {
+                Some(armor_slot) if slot >= 9 => {
+                    [armor_slot].into_iter().chain(inventory).collect()
                 }
+                _ => inventory,
             }
-            container_click::Slot::OutsideInventory => (),
         };
+        let mut moving = clicked.take();
+        move_into_slots(
+            &mut moving,
+            targets.iter().filter_map(|target| slots[*target].take()),
+        );
+        *clicked = moving;
         Ok(())
     }
 
+    /// Swaps a slot with a hotbar slot or the offhand
     fn number_button_pressed(
         &self,
         opened_container: Option<&mut Box<dyn Container>>,
         key_click: KeyClick,
         slot: usize,
     ) -> Result<(), InventoryError> {
-        let changing_slot = match key_click {
-            KeyClick::Slot(slot) => slot,
+        let container_slots = opened_container
+            .as_ref()
+            .map_or(0, |container| container.all_slots_ref().len());
+        let inventory_slot = match key_click {
+            KeyClick::Slot(key) => 36 + key as usize,
             KeyClick::Offhand => 45,
         };
-        let mut inventory = self.inventory.lock();
-        let mut changing_item_slot = inventory.get_slot(changing_slot as usize)?.to_owned();
-        let mut container = OptionallyCombinedContainer::new(&mut inventory, opened_container);
+        // Containers only show the inventory and the hotbar of the player inventory
+        let (window_slot, slot_count) = if container_slots == 0 {
+            (Some(inventory_slot), 46)
+        } else {
+            (
+                (inventory_slot < 45).then(|| inventory_slot - 9 + container_slots),
+                container_slots + 36,
+            )
+        };
+        if slot >= slot_count {
+            return Err(InventoryError::InvalidSlot);
+        }
+        if window_slot == Some(slot) {
+            return Ok(());
+        }
 
-        container.handle_item_change(&mut changing_item_slot, slot, MouseClick::Left)?;
-        *inventory.get_slot(changing_slot as usize)? = changing_item_slot;
+        let mut inventory = self.inventory.lock();
+        let mut key_item = *inventory.get_slot(inventory_slot)?;
+        if let (Some(container), Some(item)) = (opened_container.as_ref(), key_item.as_ref()) {
+            if slot < container_slots && !container.accepts(slot, item) {
+                return Ok(());
+            }
+        }
+        if container_slots == 0 {
+            // Only armor goes into the armor slots
+            let slot_condition = inventory.slot_condition(slot)?;
+            if key_item.as_ref().is_some_and(|item| !slot_condition(item)) {
+                return Ok(());
+            }
+        }
+        inventory.get_slot(inventory_slot)?.take();
+        {
+            let mut container = OptionallyCombinedContainer::new(&mut inventory, opened_container);
+            std::mem::swap(&mut *container.all_slots()[slot], &mut key_item);
+        }
+        *inventory.get_slot(inventory_slot)? = key_item;
         Ok(())
     }
 
+    /// Gives a creative player a full stack of the item in a slot
     fn creative_pick_item(
         &self,
         opened_container: Option<&mut Box<dyn Container>>,
@@ -294,46 +577,61 @@ impl Player {
         if self.gamemode.load() != GameMode::Creative {
             return Err(InventoryError::PermissionError);
         }
+        if self.carried_item.load().is_some() {
+            return Ok(());
+        }
         let mut inventory = self.inventory.lock();
-        let mut container = OptionallyCombinedContainer::new(&mut inventory, opened_container);
-        if let Some(Some(item)) = container.all_slots().get_mut(slot) {
-            self.carried_item.store(Some(item.to_owned()));
+        let container = OptionallyCombinedContainer::new(&mut inventory, opened_container);
+        if let Some(Some(item)) = container.all_slots_ref().get(slot) {
+            self.carried_item
+                .store(Some(item.with_count(item.max_stack_size())));
         }
         Ok(())
     }
 
+    /// Collects items of the carried kind into the carried stack, the first click of the double
+    /// click already picked it up
     fn double_click(
         &self,
         opened_container: Option<&mut Box<dyn Container>>,
-        slot: usize,
+        _slot: usize,
     ) -> Result<(), InventoryError> {
+        let Some(mut carried_item) = self.carried_item.load() else {
+            return Ok(());
+        };
+        let max_stack_size = carried_item.max_stack_size();
         let mut inventory = self.inventory.lock();
         let mut container = OptionallyCombinedContainer::new(&mut inventory, opened_container);
+        // Crafting results are not collected, they would be crafted without using up the
+        // ingredients. Neither are the results of anvils
+        let skipped = usize::from(container.crafting_grid_width().is_some());
+        let anvil_result =
+            (*container.window_type() == WindowType::Anvil).then_some(Anvil::RESULT_SLOT);
         let mut slots = container.all_slots();
 
-        let Some(item) = slots.get_mut(slot) else {
-            return Ok(());
-        };
-        let Some(mut carried_item) = **item else {
-            return Ok(());
-        };
-        **item = None;
-
-        for slot in slots.iter_mut().filter_map(|slot| slot.as_mut()) {
-            if slot.item_id == carried_item.item_id {
-                // TODO: Check for max stack size
-                if slot.item_count + carried_item.item_count <= 64 {
-                    slot.item_count = 0;
-                    carried_item.item_count = 64;
-                } else {
-                    let to_remove = slot.item_count - (64 - carried_item.item_count);
-                    slot.item_count -= to_remove;
-                    carried_item.item_count += to_remove;
+        // Like vanilla, stacks which are not full are taken first
+        for take_full in [false, true] {
+            for (index, slot) in slots.iter_mut().enumerate().skip(skipped) {
+                if Some(index) == anvil_result {
+                    continue;
                 }
-
-                if carried_item.item_count == 64 {
+                if carried_item.item_count >= max_stack_size {
                     break;
                 }
+                let Some(stack) = slot.as_mut() else {
+                    continue;
+                };
+                if *stack != carried_item || (stack.item_count >= max_stack_size) != take_full {
+                    continue;
+                }
+                let taken = stack
+                    .item_count
+                    .min(max_stack_size - carried_item.item_count);
+                stack.item_count -= taken;
+                carried_item.item_count += taken;
+                if stack.item_count == 0 {
+                    **slot = None;
+                }
             }
         }
         self.carried_item.store(Some(carried_item));
@@ -396,7 +694,7 @@ impl Player {
         let players = self
             .living_entity
             .entity
-            .world
+            .world()
             .current_players
             .lock()
             .iter()
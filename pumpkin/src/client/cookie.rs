@@ -0,0 +1,104 @@
+use bytes::Bytes;
+use pumpkin_plugins::events::{CookieResponseEvent, PlayerConfigurationEvent};
+use pumpkin_protocol::{
+    client::{
+        config::{CCookieRequest, CStoreCookie},
+        login::CLoginCookieRequest,
+    },
+    server::login::MAX_COOKIE_SIZE,
+    ConnectionState,
+};
+
+use crate::server::Server;
+
+use super::Client;
+
+impl Client {
+    /// Asks the client for a cookie, the answer fires a `CookieResponseEvent`.
+    /// Only possible in the Login and Config state
+    pub fn request_cookie(&self, key: &str) {
+        match self.connection_state.load() {
+            ConnectionState::Login => self.send_packet(&CLoginCookieRequest::new(key.to_string())),
+            ConnectionState::Config => self.send_packet(&CCookieRequest::new(key.to_string())),
+            state => {
+                log::warn!("Can't request cookie {key} in {state:?} State");
+                return;
+            }
+        }
+        self.cookie_requests.lock().push(key.to_string());
+    }
+
+    /// Stores a cookie on the client, it is kept across transfers.
+    /// Only possible in the Config state
+    pub fn store_cookie(&self, key: &str, payload: &[u8]) {
+        let state = self.connection_state.load();
+        if state != ConnectionState::Config {
+            log::warn!("Can't store cookie {key} in {state:?} State");
+            return;
+        }
+        if payload.len() > MAX_COOKIE_SIZE {
+            log::warn!(
+                "Cookie {key} is too big ({} > {MAX_COOKIE_SIZE})",
+                payload.len()
+            );
+            return;
+        }
+        self.send_packet(&CStoreCookie::new(key, payload));
+    }
+
+    /// Lets plugins request and store cookies, called once the Client enters the Config state
+    pub(super) fn fire_configuration_event(&self, server: &Server) {
+        let Some(profile) = self.gameprofile.lock().clone() else {
+            return;
+        };
+        let mut event = PlayerConfigurationEvent {
+            player_name: &profile.name,
+            player_uuid: profile.id,
+            transferred: self.transferred.load(std::sync::atomic::Ordering::Relaxed),
+            bedrock: self.bedrock.lock().is_some(),
+            request_cookies: Vec::new(),
+            store_cookies: Vec::new(),
+        };
+        server
+            .plugins
+            .read()
+            .for_each(|plugin| plugin.on_player_configuration(&mut event));
+
+        for (key, payload) in &event.store_cookies {
+            self.store_cookie(key, payload);
+        }
+        for key in &event.request_cookies {
+            self.request_cookie(key);
+        }
+    }
+
+    pub(super) fn handle_cookie_response(
+        &self,
+        server: &Server,
+        key: String,
+        payload: Option<Bytes>,
+    ) {
+        {
+            let mut requests = self.cookie_requests.lock();
+            let Some(index) = requests.iter().position(|request| *request == key) else {
+                drop(requests);
+                self.kick("Unexpected cookie response");
+                return;
+            };
+            requests.swap_remove(index);
+        }
+        let Some(profile) = self.gameprofile.lock().clone() else {
+            return;
+        };
+        let event = CookieResponseEvent {
+            player_name: &profile.name,
+            player_uuid: profile.id,
+            key: &key,
+            payload: payload.as_deref(),
+        };
+        server
+            .plugins
+            .read()
+            .for_each(|plugin| plugin.on_cookie_response(&event));
+    }
+}
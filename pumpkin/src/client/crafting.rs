@@ -0,0 +1,437 @@
+use std::sync::atomic::Ordering;
+
+use pumpkin_inventory::container_click::{ClickType, KeyClick};
+use pumpkin_inventory::{
+    move_into_slots, Container, CraftingTable, InventoryError, OpenContainer,
+    OptionallyCombinedContainer, WindowType,
+};
+use pumpkin_protocol::client::play::{
+    CPlaceGhostRecipe, CSetContainerSlot, CUpdateRecipeBook, CUpdateRecipes, RecipeBookAction,
+};
+use pumpkin_protocol::server::play::{SChangeRecipeBookSettings, SPlaceRecipe};
+use pumpkin_protocol::slot::Slot;
+use pumpkin_world::global_registry::{self, ITEM_REGISTRY};
+use pumpkin_world::item::ItemStack;
+use pumpkin_world::recipe::{self, Ingredient, Recipe};
+use pumpkin_world::stats;
+use serde_json::Value;
+
+use crate::entity::player::Player;
+use crate::server::{recipes, Server};
+use crate::world::block_entity::NEXT_CONTAINER_ID;
+
+/// Shift clicks on a crafting result craft at most this often, like in vanilla a full stack of
+/// most items
+const MAX_CRAFTS: usize = 64;
+
+impl Player {
+    /// Sends every recipe and unlocks them in the recipe book. Pumpkin does not keep track of
+    /// which recipes a player found, every recipe is unlocked from the start
+    pub fn send_recipe_book(&self, server: &Server) {
+        let entries = server.recipes.entries();
+        self.client.send_packet(&CUpdateRecipes::new(&entries));
+        let ids = server.recipes.ids();
+        let settings = self.recipe_book.load();
+        self.client.send_packet(&CUpdateRecipeBook::new(
+            RecipeBookAction::Init,
+            &settings,
+            &ids,
+            &[],
+        ));
+    }
+
+    pub fn handle_change_recipe_book_settings(&self, packet: SChangeRecipeBookSettings) {
+        let Ok(book) = usize::try_from(packet.book_id.0) else {
+            return;
+        };
+        let mut settings = self.recipe_book.load();
+        if book < settings.open.len() {
+            settings.open[book] = packet.book_open;
+            settings.filtering[book] = packet.filter_active;
+            self.recipe_book.store(settings);
+        }
+    }
+
+    /// Opens a crafting table, every player crafts in their own grid
+    pub fn open_crafting_table(&self, server: &Server) {
+        self.close_container(server);
+        let container_id = NEXT_CONTAINER_ID.fetch_add(1, Ordering::Relaxed);
+        server.open_containers.write().insert(
+            container_id,
+            OpenContainer::new(self.entity_id(), Box::new(CraftingTable::new())),
+        );
+        self.open_container.store(Some(container_id));
+        self.open_container(server, "minecraft:crafting");
+    }
+
+    /// Called after the player closed a container. If it was a crafting table the items in its
+    /// grid go back into the inventory, what doesn't fit is dropped like in vanilla
+    pub(super) fn close_crafting_table(&self, server: &Server, container_id: u64) {
+        let items: Vec<ItemStack> = {
+            let mut open_containers = server.open_containers.write();
+            let is_crafting_table = open_containers.get(&container_id).is_some_and(|open| {
+                *open.container().lock().window_type() == WindowType::CraftingTable
+            });
+            if !is_crafting_table {
+                return;
+            }
+            let Some(open_container) = open_containers.remove(&container_id) else {
+                return;
+            };
+            let mut container = open_container.container().lock();
+            // The result is only made of the items in the grid
+            container
+                .all_slots()
+                .into_iter()
+                .skip(1)
+                .filter_map(Option::take)
+                .collect()
+        };
+        self.give_or_drop(items);
+    }
+
+    /// Puts what the crafting grid of the open window makes into its result slot, true if it
+    /// changed
+    pub(super) fn update_crafting_result(
+        &self,
+        server: &Server,
+        opened_container: Option<&mut Box<dyn Container>>,
+    ) -> bool {
+        let mut inventory = self.inventory.lock();
+        let mut container = OptionallyCombinedContainer::new(&mut inventory, opened_container);
+        let Some(width) = container.crafting_grid_width() else {
+            return false;
+        };
+        let mut slots = container.all_slots();
+        let result = crafting_result(server, &slots, width).map(|(_, item)| item);
+        let changed = match (&*slots[0], &result) {
+            (None, None) => false,
+            (Some(old), Some(new)) => {
+                old.item_id != new.item_id || old.item_count != new.item_count
+            }
+            _ => true,
+        };
+        *slots[0] = result;
+        changed
+    }
+
+    /// Shows the player the result slot of the crafting grid of the open window
+    pub(super) fn send_crafting_result(&self, opened_container: Option<&mut Box<dyn Container>>) {
+        let mut inventory = self.inventory.lock();
+        // The player inventory is always window 0
+        let window_id = if opened_container.is_some() {
+            inventory.total_opened_containers
+        } else {
+            0
+        };
+        let result = OptionallyCombinedContainer::new(&mut inventory, opened_container)
+            .all_slots_ref()
+            .first()
+            .copied()
+            .flatten()
+            .copied();
+        // Gets the previous value
+        let state_id = inventory.state_id.fetch_add(1, Ordering::Relaxed);
+        drop(inventory);
+        self.client.send_packet(&CSetContainerSlot::new(
+            window_id as i8,
+            (state_id + 1) as i32,
+            0,
+            &Slot::from(result.as_ref()),
+        ));
+    }
+
+    /// Clicks on the result slot of a crafting grid craft its result: into the carried stack,
+    /// into the inventory with shift clicks, into the hotbar with number keys or out of the
+    /// window with the drop key
+    pub(super) fn click_crafting_result(
+        &self,
+        server: &Server,
+        opened_container: Option<&mut Box<dyn Container>>,
+        click_type: ClickType,
+    ) -> Result<(), InventoryError> {
+        let mut crafted: Vec<(&str, ItemStack)> = Vec::new();
+        let mut thrown = Vec::new();
+        let mut remainders = Vec::new();
+        {
+            let mut inventory = self.inventory.lock();
+            let mut container = OptionallyCombinedContainer::new(&mut inventory, opened_container);
+            let width = container
+                .crafting_grid_width()
+                .ok_or(InventoryError::InvalidSlot)?;
+            let inventory_slots = inventory_slots(container.container_slots());
+            let mut slots = container.all_slots();
+            while crafted.len() < MAX_CRAFTS {
+                let Some((id, result)) = crafting_result(server, &slots, width) else {
+                    break;
+                };
+                match &click_type {
+                    ClickType::MouseClick(_) => {
+                        let carried_item = match self.carried_item.load() {
+                            None => result,
+                            Some(mut carried)
+                                if carried.item_id == result.item_id
+                                    && carried.item_count + result.item_count
+                                        <= carried.max_stack_size() =>
+                            {
+                                carried.item_count += result.item_count;
+                                carried
+                            }
+                            Some(_) => break,
+                        };
+                        self.carried_item.store(Some(carried_item));
+                    }
+                    ClickType::ShiftClick => {
+                        // Like vanilla, shift clicks stop once the result changes
+                        if crafted
+                            .first()
+                            .is_some_and(|(_, first)| first.item_id != result.item_id)
+                        {
+                            break;
+                        }
+                        // Into the player inventory, starting at the end of the hotbar like
+                        // vanilla. Only whole results are crafted
+                        let targets: Vec<usize> = inventory_slots.clone().rev().collect();
+                        let mut items: Vec<Option<ItemStack>> =
+                            targets.iter().map(|target| *slots[*target]).collect();
+                        let mut moving = Some(result);
+                        move_into_slots(&mut moving, items.iter_mut());
+                        if moving.is_some() {
+                            break;
+                        }
+                        for (target, item) in targets.into_iter().zip(items) {
+                            *slots[target] = item;
+                        }
+                    }
+                    ClickType::KeyClick(KeyClick::Slot(key)) => {
+                        let hotbar_slot = inventory_slots.start + 27 + *key as usize;
+                        let hotbar_slot = slots
+                            .get_mut(hotbar_slot)
+                            .ok_or(InventoryError::InvalidSlot)?;
+                        if hotbar_slot.is_some() {
+                            break;
+                        }
+                        **hotbar_slot = Some(result);
+                    }
+                    ClickType::DropType(_) => thrown.push(result),
+                    _ => break,
+                }
+                remainders.extend(take_ingredients(&mut slots[1..=width * width]));
+                crafted.push((id, result));
+                *slots[0] = crafting_result(server, &slots, width).map(|(_, item)| item);
+                if !matches!(click_type, ClickType::ShiftClick) {
+                    break;
+                }
+            }
+        }
+        self.give_or_drop(remainders);
+        for item in thrown {
+            self.drop_item(item, false);
+        }
+        for (id, item) in crafted {
+            if let Some(item_name) = global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id)
+            {
+                self.increment_stat(stats::CRAFTED, item_name, item.item_count.into());
+            }
+            self.trigger(server, "minecraft:recipe_crafted", |conditions| {
+                conditions.get("recipe_id").and_then(Value::as_str) == Some(id)
+            });
+        }
+        Ok(())
+    }
+
+    /// Moves the ingredients of a recipe the player clicked in the recipe book from their
+    /// inventory into the crafting grid. If they don't have them, the client shows where they
+    /// would go
+    pub fn handle_place_recipe(&self, server: &Server, packet: SPlaceRecipe) {
+        self.update_last_action();
+        let opened_container = self.get_open_container(server);
+        let mut opened_container = opened_container.as_ref().map(|container| container.lock());
+        let window_id = if opened_container.is_some() {
+            self.inventory.lock().total_opened_containers
+        } else {
+            0
+        };
+        if packet.window_id as u8 != window_id {
+            return;
+        }
+        let Some(recipe) = server.recipes.get(&packet.recipe) else {
+            return;
+        };
+        let placed = {
+            let mut inventory = self.inventory.lock();
+            let mut container =
+                OptionallyCombinedContainer::new(&mut inventory, opened_container.as_deref_mut());
+            let Some(width) = container.crafting_grid_width() else {
+                return;
+            };
+            if !recipe.fits(width) {
+                return;
+            }
+            let inventory_slots = inventory_slots(container.container_slots());
+            let mut slots = container.all_slots();
+            place_recipe(recipe, width, &mut slots, inventory_slots, packet.make_all)
+        };
+        if !placed {
+            self.client
+                .send_packet(&CPlaceGhostRecipe::new(packet.window_id, &packet.recipe));
+        }
+        self.update_crafting_result(server, opened_container.as_deref_mut());
+        self.set_container_content(opened_container.as_deref_mut());
+    }
+
+    /// Puts items into the inventory, what doesn't fit is dropped
+    pub(super) fn give_or_drop(&self, items: impl IntoIterator<Item = ItemStack>) {
+        let mut dropped = Vec::new();
+        {
+            let mut inventory = self.inventory.lock();
+            for item in items {
+                let mut item = Some(item);
+                inventory.insert(&mut item);
+                dropped.extend(item);
+            }
+        }
+        for item in dropped {
+            self.drop_item(item, false);
+        }
+    }
+}
+
+/// The window slots of the inventory and the hotbar, they follow the slots of the container
+fn inventory_slots(container_slots: usize) -> std::ops::Range<usize> {
+    // Without a container the window is the player inventory, with its crafting grid and armor
+    // first
+    let first = if container_slots == 0 {
+        9
+    } else {
+        container_slots
+    };
+    first..first + 36
+}
+
+/// The id of the recipe the crafting grid of `width` in `slots` makes and its result
+fn crafting_result<'a>(
+    server: &'a Server,
+    slots: &[&mut Option<ItemStack>],
+    width: usize,
+) -> Option<(&'a str, ItemStack)> {
+    let grid: Vec<Option<ItemStack>> = slots[1..=width * width].iter().map(|slot| **slot).collect();
+    let (id, recipe) = server.recipes.find_crafting(&grid, width)?;
+    Some((id, recipes::result_stack(recipe.result()?)?))
+}
+
+/// Takes one item out of every slot of a crafting grid. Remainders like empty buckets stay in
+/// the grid, those which don't fit are returned
+fn take_ingredients(grid: &mut [&mut Option<ItemStack>]) -> Vec<ItemStack> {
+    let mut remainders = Vec::new();
+    for slot in grid {
+        let Some(item) = slot.as_mut() else {
+            continue;
+        };
+        let remainder = global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id)
+            .and_then(recipe::remainder)
+            .and_then(|id| global_registry::find_protocol_id(ITEM_REGISTRY, id))
+            .map(|item_id| ItemStack::new(1, item_id));
+        item.item_count -= 1;
+        if item.item_count == 0 {
+            **slot = None;
+        }
+        match remainder {
+            Some(remainder) if slot.is_none() => **slot = Some(remainder),
+            Some(remainder) => remainders.push(remainder),
+            None => {}
+        }
+    }
+    remainders
+}
+
+/// Moves the ingredients of a recipe out of `inventory_slots` into the crafting grid, as often
+/// as possible with `make_all`. The items in the grid go back into the inventory first, nothing
+/// happens if they don't fit. False if the inventory doesn't have the ingredients
+fn place_recipe(
+    recipe: &Recipe,
+    width: usize,
+    slots: &mut [&mut Option<ItemStack>],
+    inventory_slots: std::ops::Range<usize>,
+    make_all: bool,
+) -> bool {
+    // Which ingredient goes into which slot of the grid
+    let layout: Vec<(usize, &Ingredient)> = match recipe {
+        Recipe::Shaped(recipe) => {
+            let (recipe_width, height) = recipe.size();
+            (0..height)
+                .flat_map(|y| (0..recipe_width).map(move |x| (x, y)))
+                .filter_map(|(x, y)| Some((y * width + x, recipe.ingredient(x, y)?)))
+                .collect()
+        }
+        Recipe::Shapeless(recipe) => recipe.ingredients.iter().enumerate().collect(),
+        _ => return false,
+    };
+
+    let mut grid: Vec<Option<ItemStack>> = vec![None; width * width];
+    let mut items: Vec<Option<ItemStack>> =
+        inventory_slots.clone().map(|slot| *slots[slot]).collect();
+    for slot in 1..=width * width {
+        let mut item = *slots[slot];
+        move_into_slots(&mut item, items.iter_mut());
+        if item.is_some() {
+            // The grid can't be cleared
+            return true;
+        }
+    }
+
+    let times = if make_all { MAX_CRAFTS } else { 1 };
+    let mut placed = 0;
+    while placed < times {
+        let (mut next_grid, mut next_items) = (grid.clone(), items.clone());
+        if !layout.iter().all(|(index, ingredient)| {
+            take_ingredient(ingredient, &mut next_grid[*index], &mut next_items)
+        }) {
+            break;
+        }
+        (grid, items) = (next_grid, next_items);
+        placed += 1;
+    }
+    if placed == 0 {
+        return false;
+    }
+    for (slot, item) in (1..=width * width).zip(grid) {
+        *slots[slot] = item;
+    }
+    for (slot, item) in inventory_slots.zip(items) {
+        *slots[slot] = item;
+    }
+    true
+}
+
+/// Moves one item matching `ingredient` from `items` into a slot of the crafting grid
+fn take_ingredient(
+    ingredient: &Ingredient,
+    grid_slot: &mut Option<ItemStack>,
+    items: &mut [Option<ItemStack>],
+) -> bool {
+    for slot in items {
+        let Some(item) = slot.as_mut() else {
+            continue;
+        };
+        let fits = grid_slot.as_ref().map_or(true, |placed| {
+            placed.item_id == item.item_id && placed.item_count < placed.max_stack_size()
+        });
+        if !fits
+            || !global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id)
+                .is_some_and(|id| ingredient.test(id))
+        {
+            continue;
+        }
+        match grid_slot {
+            Some(placed) => placed.item_count += 1,
+            None => *grid_slot = Some(ItemStack::new(1, item.item_id)),
+        }
+        item.item_count -= 1;
+        if item.item_count == 0 {
+            *slot = None;
+        }
+        return true;
+    }
+    false
+}
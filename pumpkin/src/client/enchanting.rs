@@ -0,0 +1,217 @@
+use std::sync::atomic::Ordering;
+
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_core::GameMode;
+use pumpkin_inventory::window_property::{EnchantmentTable as Property, WindowProperty};
+use pumpkin_inventory::{Container, EnchantingTable, OpenContainer, WindowType};
+use pumpkin_protocol::server::play::SClickContainerButton;
+use pumpkin_world::global_registry::{self, ITEM_REGISTRY};
+use pumpkin_world::item::enchantment::{self, Enchantment};
+use pumpkin_world::item::ItemStack;
+use pumpkin_world::stats;
+
+use crate::entity::player::Player;
+use crate::server::Server;
+use crate::world::block_entity::NEXT_CONTAINER_ID;
+use crate::world::World;
+
+/// The bookshelves around an enchanting table like in vanilla: two blocks away from it, at its
+/// height or one above, with only air or plants between them and the table
+fn count_bookshelves(world: &World, table: &WorldPosition) -> i32 {
+    let mut count = 0;
+    for x in -2..=2 {
+        for z in -2..=2 {
+            if x != -2 && x != 2 && z != -2 && z != 2 {
+                continue;
+            }
+            for y in 0..=1 {
+                let is_bookshelf = world
+                    .level
+                    .get_block(&WorldPosition(table.0.add(&Vector3::new(x, y, z))))
+                    .and_then(|block| block.registry_state())
+                    .is_some_and(|(registry_id, _)| registry_id == "minecraft:bookshelf");
+                let is_free = world
+                    .level
+                    .get_block(&WorldPosition(table.0.add(&Vector3::new(x / 2, y, z / 2))))
+                    .is_some_and(|block| block.is_replaceable());
+                if is_bookshelf && is_free {
+                    count += 1;
+                }
+            }
+        }
+    }
+    count.min(enchantment::MAX_BOOKSHELVES)
+}
+
+impl Player {
+    /// Opens the enchanting table at `position`, every player enchants in their own window
+    pub fn open_enchanting_table(&self, server: &Server, position: &WorldPosition) {
+        self.close_container(server);
+        let container_id = NEXT_CONTAINER_ID.fetch_add(1, Ordering::Relaxed);
+        server.open_containers.write().insert(
+            container_id,
+            OpenContainer::new(self.entity_id(), Box::new(EnchantingTable::new())),
+        );
+        self.open_container.store(Some(container_id));
+        self.enchanting_table.store(Some(*position));
+        self.open_container(server, "minecraft:enchantment");
+        self.send_enchanting_options(None);
+    }
+
+    /// Called after the player closed a container. If it was an enchanting table its item and
+    /// lapis go back into the inventory, what doesn't fit is dropped like in vanilla
+    pub(super) fn close_enchanting_table(&self, server: &Server, container_id: u64) {
+        let items: Vec<ItemStack> = {
+            let mut open_containers = server.open_containers.write();
+            let is_enchanting_table = open_containers.get(&container_id).is_some_and(|open| {
+                *open.container().lock().window_type() == WindowType::EnchantmentTable
+            });
+            if !is_enchanting_table {
+                return;
+            }
+            let Some(open_container) = open_containers.remove(&container_id) else {
+                return;
+            };
+            let mut container = open_container.container().lock();
+            container
+                .all_slots()
+                .into_iter()
+                .filter_map(Option::take)
+                .collect()
+        };
+        self.enchanting_table.store(None);
+        self.give_or_drop(items);
+    }
+
+    /// The levels the three options of the open enchanting table cost for the item, and the
+    /// enchantment each of them shows as a clue
+    fn enchanting_options(
+        &self,
+        item: Option<&ItemStack>,
+    ) -> [(i32, Option<(Enchantment, u8)>); 3] {
+        let mut options = [(0, None); 3];
+        let (Some(item), Some(table)) = (item, self.enchanting_table.load()) else {
+            return options;
+        };
+        let seed = self.enchantment_seed.load(Ordering::Relaxed);
+        let bookshelves = count_bookshelves(&self.living_entity.entity.world(), &table);
+        let costs = enchantment::enchanting_costs(seed, bookshelves, item);
+        for (slot, (option, cost)) in options.iter_mut().zip(costs).enumerate() {
+            let clue = (cost > 0)
+                .then(|| enchantment::table_clue(seed, slot as i32, cost, item))
+                .flatten();
+            *option = (cost, clue);
+        }
+        options
+    }
+
+    /// Shows the player the options of the enchanting table for the item in it
+    fn send_enchanting_options(&self, item: Option<&ItemStack>) {
+        let seed = self.enchantment_seed.load(Ordering::Relaxed);
+        self.set_container_property(WindowProperty::new(Property::EnchantmentSeed, seed as i16));
+        for (slot, (cost, clue)) in self.enchanting_options(item).into_iter().enumerate() {
+            let slot = slot as u8;
+            let (id, level) = clue.map_or((-1, -1), |(enchantment, level)| {
+                (enchantment.registry_id() as i16, i16::from(level))
+            });
+            self.set_container_property(WindowProperty::new(
+                Property::LevelRequirement { slot },
+                cost as i16,
+            ));
+            self.set_container_property(WindowProperty::new(Property::EnchantmentId { slot }, id));
+            self.set_container_property(WindowProperty::new(
+                Property::EnchantmentLevel { slot },
+                level,
+            ));
+        }
+    }
+
+    /// Shows the options of the enchanting table again after a click, the item in it may have
+    /// changed
+    pub(super) fn update_enchanting_options(
+        &self,
+        opened_container: Option<&mut Box<dyn Container>>,
+    ) {
+        let Some(container) = opened_container
+            .filter(|container| *container.window_type() == WindowType::EnchantmentTable)
+        else {
+            return;
+        };
+        let item = container.all_slots_ref()[EnchantingTable::ITEM_SLOT].copied();
+        self.send_enchanting_options(item.as_ref());
+    }
+
+    /// Enchants the item in the enchanting table with the option the player clicked. Like in
+    /// vanilla the option costs as many lapis lazuli and levels as its number, but the player
+    /// needs to have at least the levels it shows. Creative players enchant for free
+    pub fn handle_click_container_button(&self, server: &Server, packet: SClickContainerButton) {
+        self.update_last_action();
+        let Some(container) = self.get_open_container(server) else {
+            return;
+        };
+        let Ok(slot) = usize::try_from(packet.button_id) else {
+            return;
+        };
+        if slot >= 3 {
+            return;
+        }
+        let mut container = container.lock();
+        if *container.window_type() != WindowType::EnchantmentTable
+            || packet.window_id as u8 != self.inventory.lock().total_opened_containers
+        {
+            return;
+        }
+        let levels = slot as i32 + 1;
+        let creative = self.gamemode.load() == GameMode::Creative;
+        {
+            let mut slots = container.all_slots();
+            let Some(item) = *slots[EnchantingTable::ITEM_SLOT] else {
+                return;
+            };
+            let (cost, _) = self.enchanting_options(Some(&item))[slot];
+            let lapis = slots[EnchantingTable::LAPIS_SLOT].map_or(0, |lapis| lapis.item_count);
+            if cost <= 0
+                || (!creative && (i32::from(lapis) < levels || self.experience.load().level < cost))
+            {
+                return;
+            }
+            let seed = self.enchantment_seed.load(Ordering::Relaxed);
+            let enchantments = enchantment::table_enchantments(seed, slot as i32, cost, &item);
+            if enchantments.is_empty() {
+                return;
+            }
+            let mut enchanted = item;
+            if global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id)
+                == Some("minecraft:book")
+            {
+                let Some(book) =
+                    global_registry::find_protocol_id(ITEM_REGISTRY, "minecraft:enchanted_book")
+                else {
+                    return;
+                };
+                enchanted.item_id = book;
+            }
+            for (enchantment, level) in enchantments {
+                enchanted.enchantments.set(enchantment, level);
+            }
+            *slots[EnchantingTable::ITEM_SLOT] = Some(enchanted);
+            if !creative {
+                if let Some(lapis) = slots[EnchantingTable::LAPIS_SLOT].as_mut() {
+                    lapis.item_count -= levels as u8;
+                    if lapis.item_count == 0 {
+                        *slots[EnchantingTable::LAPIS_SLOT] = None;
+                    }
+                }
+            }
+        }
+        self.add_experience_levels(-levels);
+        // Every enchanting changes the options of every item
+        self.enchantment_seed
+            .store(rand::random(), Ordering::Relaxed);
+        self.increment_custom_stat(stats::ENCHANT_ITEM, 1);
+        self.set_container_content(Some(&mut *container));
+        self.update_enchanting_options(Some(&mut *container));
+        drop(container);
+        self.trigger(server, "minecraft:enchanted_item", |_| true);
+    }
+}
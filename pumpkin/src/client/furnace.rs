@@ -0,0 +1,115 @@
+use pumpkin_inventory::container_click::{ClickType, DropType, KeyClick, MouseClick};
+use pumpkin_inventory::{
+    move_into_slots, Container, Furnace, InventoryError, OptionallyCombinedContainer, WindowType,
+};
+use pumpkin_world::global_registry::{self, ITEM_REGISTRY};
+use pumpkin_world::item::ItemStack;
+use pumpkin_world::{recipe, stats};
+
+use crate::entity::player::Player;
+use crate::server::Server;
+use crate::world::furnace;
+
+impl Player {
+    /// Clicks on the result slot of a furnace take the cooked items: into the carried stack,
+    /// into the inventory with shift clicks, into the hotbar with number keys or out of the
+    /// window with the drop key. Nothing can be put into it. Returns whether items were taken
+    pub(super) fn click_furnace_result(
+        &self,
+        opened_container: Option<&mut Box<dyn Container>>,
+        click_type: ClickType,
+    ) -> Result<bool, InventoryError> {
+        let mut thrown = None;
+        let taken = {
+            let mut inventory = self.inventory.lock();
+            let mut container = OptionallyCombinedContainer::new(&mut inventory, opened_container);
+            let container_slots = container.container_slots();
+            let mut slots = container.all_slots();
+            let Some(result) = *slots[Furnace::RESULT_SLOT] else {
+                return Ok(false);
+            };
+            let count = match click_type {
+                ClickType::MouseClick(mouse_click) => {
+                    let wanted = match mouse_click {
+                        MouseClick::Left => result.item_count,
+                        MouseClick::Right => result.item_count.div_ceil(2),
+                    };
+                    let (carried, count) = match self.carried_item.load() {
+                        None => (ItemStack::new(wanted, result.item_id), wanted),
+                        Some(mut carried) if carried.item_id == result.item_id => {
+                            let count = wanted
+                                .min(carried.max_stack_size().saturating_sub(carried.item_count));
+                            carried.item_count += count;
+                            (carried, count)
+                        }
+                        Some(_) => return Ok(false),
+                    };
+                    self.carried_item.store(Some(carried));
+                    count
+                }
+                ClickType::ShiftClick => {
+                    // Into the player inventory, starting at the end of the hotbar like vanilla
+                    let mut moving = Some(result);
+                    move_into_slots(
+                        &mut moving,
+                        slots[container_slots..]
+                            .iter_mut()
+                            .rev()
+                            .map(|slot| &mut **slot),
+                    );
+                    result.item_count - moving.map_or(0, |left| left.item_count)
+                }
+                ClickType::KeyClick(KeyClick::Slot(key)) => {
+                    let hotbar_slot = slots
+                        .get_mut(container_slots + 27 + key as usize)
+                        .ok_or(InventoryError::InvalidSlot)?;
+                    if hotbar_slot.is_some() {
+                        return Ok(false);
+                    }
+                    **hotbar_slot = Some(result);
+                    result.item_count
+                }
+                ClickType::DropType(drop_type) => {
+                    let count = match drop_type {
+                        DropType::SingleItem => 1,
+                        DropType::FullStack => result.item_count,
+                    };
+                    thrown = Some(ItemStack::new(count, result.item_id));
+                    count
+                }
+                _ => return Ok(false),
+            };
+            if count == 0 {
+                return Ok(false);
+            }
+            let left = result.item_count - count;
+            *slots[Furnace::RESULT_SLOT] = (left > 0).then(|| ItemStack::new(left, result.item_id));
+            ItemStack::new(count, result.item_id)
+        };
+        if let Some(item) = thrown {
+            self.drop_item(item, false);
+        }
+        if let Some(item_name) = global_registry::find_minecraft_id(ITEM_REGISTRY, taken.item_id) {
+            self.increment_stat(stats::CRAFTED, item_name, taken.item_count.into());
+        }
+        Ok(true)
+    }
+}
+
+/// The slot of a furnace a shift click from the inventory moves an item into, like in vanilla
+/// what it cooks goes into the input slot and fuel into the fuel slot. None for other items
+pub(super) fn furnace_slot(
+    server: &Server,
+    window_type: &WindowType,
+    item: &ItemStack,
+) -> Option<usize> {
+    if server
+        .recipes
+        .find_cooking(furnace::block(window_type), item)
+        .is_some()
+    {
+        return Some(Furnace::INPUT_SLOT);
+    }
+    let item_name = global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id)?;
+    recipe::fuel(item_name).map(|_| Furnace::FUEL_SLOT)
+}
@@ -0,0 +1,49 @@
+use pumpkin_config::BASIC_CONFIG;
+use pumpkin_protocol::ConnectionState;
+
+use crate::server::{Server, CURRENT_MC_VERSION};
+
+use super::Client;
+
+/// The first byte of a legacy (pre 1.7) server list ping
+pub(super) const LEGACY_PING: u8 = 0xFE;
+/// Follows `LEGACY_PING` since 1.4, the client then expects the extended response format
+const LEGACY_PING_PAYLOAD: u8 = 0x01;
+/// The id of the legacy kick packet, which carries the response
+const LEGACY_KICK: u8 = 0xFF;
+/// The protocol version we report to legacy clients, this makes them show the Server as incompatible
+const LEGACY_PROTOCOL: u8 = 127;
+
+impl Client {
+    /// Checks if the first bytes on a new connection are a legacy ping and answers it.
+    /// Returns true if the data was a legacy ping
+    pub(super) fn try_handle_legacy_ping(&self, server: &Server, data: &[u8]) -> bool {
+        if self.connection_state.load() != ConnectionState::HandShake
+            || data.first() != Some(&LEGACY_PING)
+        {
+            return false;
+        }
+        log::debug!("Received legacy server list ping");
+
+        let motd = &BASIC_CONFIG.get().motd;
+        let online = server.get_player_count();
+        let max = BASIC_CONFIG.get().max_players;
+        let response = if data.get(1) == Some(&LEGACY_PING_PAYLOAD) {
+            format!("§1\0{LEGACY_PROTOCOL}\0{CURRENT_MC_VERSION}\0{motd}\0{online}\0{max}")
+        } else {
+            // Beta 1.8 to 1.3 use § as separator, so it can't be part of the motd
+            format!("{}§{online}§{max}", motd.replace('§', ""))
+        };
+
+        let chars: Vec<u16> = response.encode_utf16().collect();
+        let mut buf = Vec::with_capacity(3 + chars.len() * 2);
+        buf.push(LEGACY_KICK);
+        buf.extend_from_slice(&(chars.len() as u16).to_be_bytes());
+        for unit in chars {
+            buf.extend_from_slice(&unit.to_be_bytes());
+        }
+        self.write_raw(&buf);
+        self.close();
+        true
+    }
+}
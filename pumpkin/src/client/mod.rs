@@ -1,44 +1,80 @@
 use std::{
-    io::{self, Write},
+    collections::VecDeque,
     net::SocketAddr,
     sync::{
-        atomic::{AtomicBool, AtomicI32},
+        atomic::{AtomicBool, AtomicI32, AtomicUsize},
         Arc,
     },
 };
 
 use crate::{
-    entity::player::{ChatMode, Hand},
+    entity::player::{Arm, ChatMode},
+    metrics::METRICS,
+    proxy::floodgate::BedrockData,
     server::Server,
 };
 
 use authentication::GameProfile;
+use bytes::BytesMut;
+use connection::{ConnectionReader, ConnectionWriter};
 use crossbeam::atomic::AtomicCell;
-use mio::{event::Event, net::TcpStream};
 use parking_lot::Mutex;
-use pumpkin_config::compression::CompressionInfo;
+use pumpkin_config::{
+    compression::CompressionInfo, messages::format_message, rate_limit::RateLimitAction,
+    ADVANCED_CONFIG, MESSAGES,
+};
 use pumpkin_core::text::TextComponent;
 use pumpkin_protocol::{
-    bytebuf::{packet_id::Packet, DeserializerError},
-    client::{config::CConfigDisconnect, login::CLoginDisconnect, play::CPlayDisconnect},
+    bytebuf::{packet_id::Packet, ByteBuffer, DeserializerError},
+    client::{
+        config::CConfigDisconnect,
+        login::CLoginDisconnect,
+        play::{CChunkData, CPlayDisconnect},
+    },
     packet_decoder::PacketDecoder,
     packet_encoder::PacketEncoder,
     server::{
-        config::{SAcknowledgeFinishConfig, SClientInformationConfig, SKnownPacks, SPluginMessage},
+        config::{
+            SAcknowledgeFinishConfig, SClientInformationConfig, SConfigCookieResponse,
+            SConfigResourcePackResponse, SKnownPacks, SPluginMessage,
+        },
         handshake::SHandShake,
-        login::{SEncryptionResponse, SLoginAcknowledged, SLoginPluginResponse, SLoginStart},
+        login::{
+            SEncryptionResponse, SLoginAcknowledged, SLoginCookieResponse, SLoginPluginResponse,
+            SLoginStart,
+        },
         status::{SStatusPingRequest, SStatusRequest},
     },
-    ClientPacket, ConnectionState, PacketError, RawPacket, ServerPacket,
+    version::ProtocolVersion,
+    ClientPacket, ConnectionState, PacketError, RawPacket, ServerPacket, VarInt,
 };
+use pumpkin_world::{chunk::ChunkData, level::Level};
 
-use std::io::Read;
+use net_stats::NetStats;
+use packet_dump::Direction;
+use rate_limiter::RateLimiter;
 use thiserror::Error;
+use tokio::{
+    io::AsyncReadExt,
+    sync::{mpsc, Notify},
+    task::JoinHandle,
+};
 
+mod anvil;
 pub mod authentication;
 mod client_packet;
+pub mod connection;
 mod container;
+mod cookie;
+mod crafting;
+mod enchanting;
+mod furnace;
+mod legacy_ping;
+pub mod net_stats;
+mod packet_dump;
 pub mod player_packet;
+mod rate_limiter;
+pub mod status;
 
 /// Represents a player's configuration settings.
 ///
@@ -60,7 +96,7 @@ pub struct PlayerConfig {
     /// The player's skin configuration options.
     pub skin_parts: u8,
     /// The player's dominant hand (left or right).
-    pub main_hand: Hand,
+    pub main_hand: Arm,
     /// Whether text filtering is enabled.
     pub text_filtering: bool,
     /// Whether the player wants to appear in the server list.
@@ -75,7 +111,7 @@ impl Default for PlayerConfig {
             chat_mode: ChatMode::Enabled,
             chat_colors: true,
             skin_parts: 0,
-            main_hand: Hand::Main,
+            main_hand: Arm::Right,
             text_filtering: false,
             server_listing: false,
         }
@@ -93,8 +129,16 @@ pub struct Client {
     pub brand: Mutex<Option<String>>,
     /// The minecraft protocol version used by the client.
     pub protocol_version: AtomicI32,
+    /// Whether the client was transferred here from another server.
+    pub transferred: AtomicBool,
+    /// The keys of cookies we requested but the client did not answer yet.
+    cookie_requests: Mutex<Vec<String>>,
+    /// The supported protocol version matching `protocol_version`, packets get translated into it.
+    pub version: AtomicCell<&'static ProtocolVersion>,
     /// The Address used to connect to the Server, Send in the Handshake
     pub server_address: Mutex<String>,
+    /// Set when the client is a Bedrock player joining through Geyser with Floodgate
+    pub bedrock: Mutex<Option<BedrockData>>,
     /// The current connection state of the client (e.g., Handshaking, Status, Play).
     pub connection_state: AtomicCell<ConnectionState>,
     /// Whether encryption is enabled for the connection.
@@ -103,57 +147,76 @@ pub struct Client {
     pub closed: AtomicBool,
     /// A unique id identifying the client.
     pub id: usize,
-    /// The underlying TCP connection to the client.
-    pub connection: Arc<Mutex<TcpStream>>,
     /// The client's IP address.
     pub address: Mutex<SocketAddr>,
     /// The packet encoder for outgoing packets.
     enc: Arc<Mutex<PacketEncoder>>,
+    /// Hands encoded packets to the connection's writer task, None once the connection is closed.
+    outgoing: Mutex<Option<mpsc::UnboundedSender<BytesMut>>>,
+    /// How many flushed bytes the writer task still has to write
+    queued_bytes: Arc<AtomicUsize>,
+    /// The task writing to the connection.
+    writer_task: Mutex<Option<JoinHandle<()>>>,
+    /// Wakes up the reading side when the connection gets closed.
+    close_notify: Notify,
+    /// The total traffic of this connection.
+    pub net_stats: NetStats,
+    /// Tracks how many packets and bytes the client sent recently.
+    rate_limiter: Mutex<RateLimiter>,
+    /// When set, we don't read from the connection until then, because the client sent too much.
+    throttled_until: AtomicCell<Option<std::time::Instant>>,
     /// The packet decoder for incoming packets.
     dec: Arc<Mutex<PacketDecoder>>,
     /// A queue of raw packets received from the client, waiting to be processed.
-    pub client_packets_queue: Arc<Mutex<Vec<RawPacket>>>,
+    pub client_packets_queue: Arc<Mutex<VecDeque<RawPacket>>>,
 
     /// Indicates whether the client should be converted into a player.
     pub make_player: AtomicBool,
-    /// Sends each keep alive packet that the server receives for a player to here, which gets picked up in a tokio task
-    pub keep_alive_sender: Arc<tokio::sync::mpsc::Sender<i64>>,
-    /// Stores the last time it was confirmed that the client is alive
-    pub last_alive_received: AtomicCell<std::time::Instant>,
 }
 
 impl Client {
-    pub fn new(
-        id: usize,
-        connection: TcpStream,
-        address: SocketAddr,
-        keep_alive_sender: Arc<tokio::sync::mpsc::Sender<i64>>,
-    ) -> Self {
+    /// Creates a new Client and spawns the task writing to its connection
+    pub fn new(id: usize, writer: ConnectionWriter, address: SocketAddr) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let queued_bytes = Arc::new(AtomicUsize::new(0));
+        let writer_task = tokio::spawn(connection::write_loop(
+            writer,
+            receiver,
+            queued_bytes.clone(),
+        ));
         Self {
             protocol_version: AtomicI32::new(0),
+            transferred: AtomicBool::new(false),
+            cookie_requests: Mutex::new(Vec::new()),
+            version: AtomicCell::new(ProtocolVersion::current()),
             gameprofile: Mutex::new(None),
             config: Mutex::new(None),
             brand: Mutex::new(None),
             server_address: Mutex::new("".to_string()),
+            bedrock: Mutex::new(None),
             id,
             address: Mutex::new(address),
             connection_state: AtomicCell::new(ConnectionState::HandShake),
-            connection: Arc::new(Mutex::new(connection)),
             enc: Arc::new(Mutex::new(PacketEncoder::default())),
+            outgoing: Mutex::new(Some(sender)),
+            queued_bytes,
+            writer_task: Mutex::new(Some(writer_task)),
+            close_notify: Notify::new(),
+            net_stats: NetStats::default(),
+            rate_limiter: Mutex::new(RateLimiter::default()),
+            throttled_until: AtomicCell::new(None),
             dec: Arc::new(Mutex::new(PacketDecoder::default())),
             encryption: AtomicBool::new(false),
             closed: AtomicBool::new(false),
-            client_packets_queue: Arc::new(Mutex::new(Vec::new())),
+            client_packets_queue: Arc::new(Mutex::new(VecDeque::new())),
             make_player: AtomicBool::new(false),
-            keep_alive_sender,
-            last_alive_received: AtomicCell::new(std::time::Instant::now()),
         }
     }
 
     /// Adds a Incoming packet to the queue
     pub fn add_packet(&self, packet: RawPacket) {
         let mut client_packets_queue = self.client_packets_queue.lock();
-        client_packets_queue.push(packet);
+        client_packets_queue.push_back(packet);
     }
 
     /// Sets the Packet encryption
@@ -161,6 +224,8 @@ impl Client {
         &self,
         shared_secret: Option<&[u8]>, // decrypted
     ) -> Result<(), EncryptionError> {
+        // Everything queued so far was meant to be sent unencrypted
+        self.flush();
         if let Some(shared_secret) = shared_secret {
             self.encryption
                 .store(true, std::sync::atomic::Ordering::Relaxed);
@@ -182,34 +247,155 @@ impl Client {
         self.enc.lock().set_compression(compression);
     }
 
-    /// Send a Clientbound Packet to the Client
+    /// Queues a Clientbound Packet, it gets written to the connection on the next `flush`
     pub fn send_packet<P: ClientPacket>(&self, packet: &P) {
         // assert!(!self.closed);
-        let mut enc = self.enc.lock();
-        enc.append_packet(packet)
-            .unwrap_or_else(|e| self.kick(&e.to_string()));
-        self.connection
-            .lock()
-            .write_all(&enc.take())
-            .map_err(|_| PacketError::ConnectionWrite)
+        self.try_send_packet(packet)
             .unwrap_or_else(|e| self.kick(&e.to_string()));
     }
 
     pub fn try_send_packet<P: ClientPacket>(&self, packet: &P) -> Result<(), PacketError> {
         // assert!(!self.closed);
+        let Some(id) = self.clientbound_id::<P>() else {
+            // The packet does not exist in the client's version
+            return Ok(());
+        };
+        self.dump_packet(id, packet);
+        self.enc.lock().append_packet_with_id(id, packet)?;
+        METRICS.record_packet_sent();
+        self.net_stats.record_packet_sent();
+        Ok(())
+    }
+
+    /// Queues the chunk data packet of a chunk. The encoded packet is shared with all clients using
+    /// the same protocol and compression, so it is only encoded again once the chunk changes
+    pub fn send_chunk(&self, level: &Level, chunk: &ChunkData) {
+        self.try_send_chunk(level, chunk)
+            .unwrap_or_else(|e| self.kick(&e.to_string()));
+    }
 
+    fn try_send_chunk(&self, level: &Level, chunk: &ChunkData) -> Result<(), PacketError> {
+        let Some(id) = self.clientbound_id::<CChunkData>() else {
+            return Ok(());
+        };
+        let packet = CChunkData(chunk);
+        self.dump_packet(id, &packet);
         let mut enc = self.enc.lock();
-        enc.append_packet(packet)?;
-        self.connection
-            .lock()
-            .write_all(&enc.take())
-            .map_err(|_| PacketError::ConnectionWrite)?;
+        let threshold = enc.compression().map_or(u32::MAX, |info| info.threshold);
+        let encoding = (u64::from(id as u32) << 32) | u64::from(threshold);
+        let encoded = match level.cached_chunk_packet(chunk.position, encoding) {
+            Some(encoded) => encoded,
+            None => {
+                let encoded: Arc<[u8]> = enc.encode_packet_with_id(id, &packet)?[..].into();
+                level.cache_chunk_packet(chunk.position, encoding, encoded.clone());
+                encoded
+            }
+        };
+        enc.append_encoded(&encoded);
+        METRICS.record_packet_sent();
+        self.net_stats.record_packet_sent();
         Ok(())
     }
 
-    /// Processes all packets send by the client
+    /// The id of a packet in the client's version, None if it does not exist there
+    fn clientbound_id<P: ClientPacket>(&self) -> Option<i32> {
+        let version = self.version.load();
+        if version.is_current() {
+            return Some(P::PACKET_ID);
+        }
+        version.clientbound_id(self.connection_state.load(), P::PACKET_ID)
+    }
+
+    fn dump_packet<P: ClientPacket>(&self, id: i32, packet: &P) {
+        if packet_dump::should_dump(id) {
+            let mut data = ByteBuffer::empty();
+            packet.write(&mut data);
+            let state = self.connection_state.load();
+            packet_dump::dump(self.id, Direction::Clientbound, state, id, data.buf());
+        }
+    }
+
+    /// Translates the id of a received packet into the current protocol.
+    /// Returns false if the packet has no equivalent in the current protocol
+    pub fn translate_packet(&self, packet: &mut RawPacket) -> bool {
+        let version = self.version.load();
+        if version.is_current() {
+            return true;
+        }
+        match version.serverbound_id(self.connection_state.load(), packet.id.0) {
+            Some(id) => {
+                packet.id = VarInt(id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Writes all queued packets to the connection
+    pub fn flush(&self) {
+        if self.closed.load(std::sync::atomic::Ordering::Relaxed) {
+            return;
+        }
+        self.try_flush()
+            .unwrap_or_else(|e| self.kick(&e.to_string()));
+    }
+
+    /// Hands all queued packets to the connection's writer task in one go. Fails if the client
+    /// stopped reading and too much is waiting to be written already
+    pub fn try_flush(&self) -> Result<(), PacketError> {
+        let buf = self.enc.lock().take();
+        if buf.is_empty() {
+            return Ok(());
+        }
+        let queued = self.queued_bytes.load(std::sync::atomic::Ordering::Relaxed) + buf.len();
+        let max_bytes = ADVANCED_CONFIG.get().send_queue.max_bytes;
+        if max_bytes > 0 && queued as u64 > max_bytes {
+            if !self.closed.load(std::sync::atomic::Ordering::Relaxed) {
+                log::warn!(
+                    "Closing the connection of {}, it has {queued} bytes waiting to be written",
+                    self.address.lock()
+                );
+            }
+            return Err(PacketError::ConnectionWrite);
+        }
+        self.queued_bytes
+            .fetch_add(buf.len(), std::sync::atomic::Ordering::Relaxed);
+        self.net_stats.record_bytes_sent(buf.len() as u64);
+        match self.outgoing.lock().as_ref() {
+            Some(sender) => sender.send(buf).map_err(|_| PacketError::ConnectionWrite),
+            None => Err(PacketError::ConnectionWrite),
+        }
+    }
+
+    /// Writes bytes to the connection as they are, bypassing the packet encoder
+    pub fn write_raw(&self, data: &[u8]) {
+        if let Some(sender) = self.outgoing.lock().as_ref() {
+            self.net_stats.record_bytes_sent(data.len() as u64);
+            self.queued_bytes
+                .fetch_add(data.len(), std::sync::atomic::Ordering::Relaxed);
+            let _ = sender.send(BytesMut::from(data));
+        }
+    }
+
+    /// Waits until everything flushed before the connection was closed got written
+    pub async fn wait_for_writer(&self) {
+        let writer_task = self.writer_task.lock().take();
+        if let Some(writer_task) = writer_task {
+            let _ = writer_task.await;
+        }
+    }
+
+    /// Processes all packets send by the client.
+    /// Stops once the client should become a Player, the Player then handles the remaining packets
     pub async fn process_packets(&self, server: &Arc<Server>) {
-        while let Some(mut packet) = self.client_packets_queue.lock().pop() {
+        while !self.make_player.load(std::sync::atomic::Ordering::Relaxed) {
+            let packet = self.client_packets_queue.lock().pop_front();
+            let Some(mut packet) = packet else {
+                break;
+            };
+            if !self.translate_packet(&mut packet) {
+                continue;
+            }
             let _ = self.handle_packet(server, &mut packet).await.map_err(|e| {
                 let text = format!("Error while reading incoming packet {}", e);
                 log::error!("{}", text);
@@ -308,6 +494,11 @@ impl Client {
                 self.handle_login_acknowledged(server, SLoginAcknowledged::read(bytebuf)?);
                 Ok(())
             }
+            SLoginCookieResponse::PACKET_ID => {
+                let response = SLoginCookieResponse::read(bytebuf)?;
+                self.handle_cookie_response(server, response.key, response.payload);
+                Ok(())
+            }
             _ => {
                 log::error!(
                     "Failed to handle packet id {} while in Login state",
@@ -342,6 +533,18 @@ impl Client {
                 self.handle_known_packs(server, SKnownPacks::read(bytebuf)?);
                 Ok(())
             }
+            SConfigCookieResponse::PACKET_ID => {
+                let response = SConfigCookieResponse::read(bytebuf)?;
+                self.handle_cookie_response(server, response.key, response.payload);
+                Ok(())
+            }
+            SConfigResourcePackResponse::PACKET_ID => {
+                self.handle_resource_pack_response(
+                    server,
+                    SConfigResourcePackResponse::read(bytebuf)?,
+                );
+                Ok(())
+            }
             _ => {
                 log::error!(
                     "Failed to handle packet id {} while in Config state",
@@ -352,44 +555,90 @@ impl Client {
         }
     }
 
-    /// Reads the connection until our buffer of len 4096 is full, then decode
+    /// Reads once from the connection and queues every complete packet.
     /// Close connection when an error occurs or when the Client closed the connection
-    pub async fn poll(&self, event: &Event) {
-        if event.is_readable() {
-            let mut received_data = vec![];
-            let mut buf = [0; 4096];
-            loop {
-                let connection = self.connection.clone();
-                let mut connection = connection.lock();
-                match connection.read(&mut buf) {
-                    Ok(0) => {
-                        // Reading 0 bytes means the other side has closed the
-                        // connection or is done writing, then so are we.
-                        self.close();
-                        break;
-                    }
-                    Ok(n) => received_data.extend(&buf[..n]),
-                    // Would block "errors" are the OS's way of saying that the
-                    // connection is not actually ready to perform this I/O operation.
-                    Err(ref err) if would_block(err) => break,
-                    Err(ref err) if interrupted(err) => continue,
-                    // Other errors we'll consider fatal.
-                    Err(_) => self.close(),
-                }
+    pub async fn poll(&self, server: &Server, reader: &mut ConnectionReader) {
+        if let Some(throttled_until) = self.throttled_until.take() {
+            tokio::select! {
+                () = tokio::time::sleep_until(throttled_until.into()) => {},
+                () = self.close_notify.notified() => return,
+            };
+        }
+
+        let mut buf = [0; 4096];
+        let result = tokio::select! {
+            result = reader.read(&mut buf) => result,
+            () = self.close_notify.notified() => return,
+        };
+        let n = match result {
+            // Reading 0 bytes means the other side has closed the
+            // connection or is done writing, then so are we.
+            Ok(0) | Err(_) => {
+                self.close();
+                return;
             }
+            Ok(n) => n,
+        };
+        self.receive(server, &buf[..n]);
+    }
 
-            if !received_data.is_empty() {
-                let mut dec = self.dec.lock();
-                dec.queue_slice(&received_data);
+    /// Queues every complete packet in `data`, which was read from the connection
+    pub fn receive(&self, server: &Server, data: &[u8]) {
+        if self.try_handle_legacy_ping(server, data) {
+            return;
+        }
+
+        let n = data.len();
+        let mut packets = 0;
+        {
+            let mut dec = self.dec.lock();
+            dec.queue_slice(data);
+            loop {
                 match dec.decode() {
-                    Ok(packet) => {
-                        if let Some(packet) = packet {
-                            self.add_packet(packet);
+                    Ok(Some(mut packet)) => {
+                        packets += 1;
+                        if packet_dump::should_dump(packet.id.0) {
+                            packet_dump::dump(
+                                self.id,
+                                Direction::Serverbound,
+                                self.connection_state.load(),
+                                packet.id.0,
+                                packet.bytebuf.buf(),
+                            );
                         }
+                        self.add_packet(packet);
+                    }
+                    Ok(None) => break,
+                    Err(err) => {
+                        self.kick(&err.to_string());
+                        break;
                     }
-                    Err(err) => self.kick(&err.to_string()),
                 }
-                dec.clear();
+            }
+        }
+
+        METRICS.record_packets_received(packets.into());
+        self.net_stats.record_received(packets.into(), n as u64);
+        self.check_rate_limit(packets, n);
+    }
+
+    fn check_rate_limit(&self, packets: u32, bytes: usize) {
+        let config = &ADVANCED_CONFIG.get().packet_rate_limit;
+        if !config.enabled {
+            return;
+        }
+        let mut rate_limiter = self.rate_limiter.lock();
+        if rate_limiter.record(config, self.connection_state.load(), packets, bytes) {
+            return;
+        }
+        match config.action {
+            RateLimitAction::Kick => {
+                drop(rate_limiter);
+                log::warn!("Client {} exceeded the packet rate limit", self.id);
+                self.kick(&format_message(&MESSAGES.get().too_many_packets, &[]));
+            }
+            RateLimitAction::Throttle => {
+                self.throttled_until.store(Some(rate_limiter.window_end()));
             }
         }
     }
@@ -417,13 +666,20 @@ impl Client {
                 log::warn!("Can't kick in {:?} State", self.connection_state)
             }
         }
+        // The disconnect packet should arrive before we close the connection
+        self.try_flush().unwrap_or_else(|_| self.close());
         self.close()
     }
 
     /// You should prefer to use `kick` when you can
     pub fn close(&self) {
+        // Packets queued before closing should still arrive
+        let _ = self.try_flush();
         self.closed
             .store(true, std::sync::atomic::Ordering::Relaxed);
+        // Dropping the sender lets the writer task finish everything flushed so far
+        self.outgoing.lock().take();
+        self.close_notify.notify_one();
     }
 }
 
@@ -434,11 +690,3 @@ pub enum EncryptionError {
     #[error("shared secret has the wrong length")]
     SharedWrongLength,
 }
-
-fn would_block(err: &io::Error) -> bool {
-    err.kind() == io::ErrorKind::WouldBlock
-}
-
-pub fn interrupted(err: &io::Error) -> bool {
-    err.kind() == io::ErrorKind::Interrupted
-}
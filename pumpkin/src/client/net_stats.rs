@@ -0,0 +1,59 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// How much traffic a connection caused, in bytes as sent over the wire
+#[derive(Default)]
+pub struct NetStats {
+    bytes_received: AtomicU64,
+    bytes_sent: AtomicU64,
+    packets_received: AtomicU64,
+    packets_sent: AtomicU64,
+}
+
+/// A point in time copy of `NetStats`
+#[derive(Clone, Copy)]
+pub struct NetStatsSnapshot {
+    pub bytes_received: u64,
+    pub bytes_sent: u64,
+    pub packets_received: u64,
+    pub packets_sent: u64,
+}
+
+impl NetStats {
+    pub fn record_received(&self, packets: u64, bytes: u64) {
+        self.packets_received.fetch_add(packets, Ordering::Relaxed);
+        self.bytes_received.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn record_packet_sent(&self) {
+        self.packets_sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_bytes_sent(&self, bytes: u64) {
+        self.bytes_sent.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> NetStatsSnapshot {
+        NetStatsSnapshot {
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            packets_received: self.packets_received.load(Ordering::Relaxed),
+            packets_sent: self.packets_sent.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Formats a byte count with a binary unit, e.g. `1.5 MiB`
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
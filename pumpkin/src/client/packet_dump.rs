@@ -0,0 +1,108 @@
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{BufWriter, Write},
+    sync::LazyLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use parking_lot::Mutex;
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_protocol::ConnectionState;
+
+#[derive(Clone, Copy)]
+pub enum Direction {
+    Serverbound,
+    Clientbound,
+}
+
+/// The capture file packets get written to, None if packets get logged
+static CAPTURE_FILE: LazyLock<Option<Mutex<BufWriter<File>>>> = LazyLock::new(|| {
+    let config = ADVANCED_CONFIG.get();
+    let path = config.packet_dump.capture_file.as_ref()?;
+    match File::create(path) {
+        Ok(file) => {
+            log::info!("Writing packet capture to {path}");
+            Some(Mutex::new(BufWriter::new(file)))
+        }
+        Err(err) => {
+            log::error!(
+                "Failed to create packet capture file {path}, logging packets instead: {err}"
+            );
+            None
+        }
+    }
+});
+
+/// Whether packets with `id` should be dumped
+pub fn should_dump(id: i32) -> bool {
+    let config = &ADVANCED_CONFIG.get().packet_dump;
+    config.enabled && (config.packet_ids.is_empty() || config.packet_ids.contains(&id))
+}
+
+/// Logs or captures a single packet, `data` is the packet without its id
+pub fn dump(client: usize, direction: Direction, state: ConnectionState, id: i32, data: &[u8]) {
+    if let Some(capture) = CAPTURE_FILE.as_ref() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_millis() as i64);
+        let mut capture = capture.lock();
+        let result = capture
+            .write_all(&timestamp.to_be_bytes())
+            .and_then(|()| capture.write_all(&(client as u64).to_be_bytes()))
+            .and_then(|()| capture.write_all(&[direction as u8, state as u8]))
+            .and_then(|()| capture.write_all(&id.to_be_bytes()))
+            .and_then(|()| capture.write_all(&(data.len() as u32).to_be_bytes()))
+            .and_then(|()| capture.write_all(data))
+            // Flush every packet, so nothing is lost when the Server crashes
+            .and_then(|()| capture.flush());
+        if let Err(err) = result {
+            log::error!("Failed to write packet capture: {err}");
+        }
+        return;
+    }
+
+    let arrow = match direction {
+        Direction::Serverbound => "C -> S",
+        Direction::Clientbound => "S -> C",
+    };
+    if ADVANCED_CONFIG.get().packet_dump.hex_dump {
+        log::info!(
+            "[{client}] {arrow} {state:?} 0x{id:02X} ({} bytes)\n{}",
+            data.len(),
+            hex_dump(data)
+        );
+    } else {
+        log::info!(
+            "[{client}] {arrow} {state:?} 0x{id:02X} ({} bytes)",
+            data.len()
+        );
+    }
+}
+
+/// Formats `data` in rows of 16 bytes, with their offset and ASCII representation
+fn hex_dump(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len() * 4 + data.len() / 16 * 12);
+    for (row, chunk) in data.chunks(16).enumerate() {
+        let _ = write!(out, "{:08x}  ", row * 16);
+        for i in 0..16 {
+            match chunk.get(i) {
+                Some(byte) => {
+                    let _ = write!(out, "{byte:02x} ");
+                }
+                None => out.push_str("   "),
+            }
+        }
+        out.push(' ');
+        out.extend(chunk.iter().map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            }
+        }));
+        out.push('\n');
+    }
+    out.pop();
+    out
+}
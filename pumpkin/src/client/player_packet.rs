@@ -1,42 +1,54 @@
-use std::f32::consts::PI;
+use std::{sync::Arc, time::Instant};
 
 use crate::{
+    chat::{last_seen::MAX_PENDING, secure_chat_enforced, session::ChatSession},
     commands::CommandSender,
-    entity::player::{ChatMode, Hand, Player},
-    server::Server,
-    world::player_chunker,
+    entity::{
+        food::{hand_item, MINING_EXHAUSTION},
+        player::{Arm, ChatMode, Hand, Player},
+    },
+    server::{ticker::TICKS_PER_SECOND, Server},
+    world::{bed, block_entity, furnace, player_chunker, portal, tnt, tracking::angle_byte, World},
 };
 use num_traits::FromPrimitive;
-use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_config::{messages::format_message, ADVANCED_CONFIG, BASIC_CONFIG, MESSAGES};
 use pumpkin_core::{
     math::{position::WorldPosition, vector3::Vector3, wrap_degrees},
     text::TextComponent,
     GameMode,
 };
-use pumpkin_entity::EntityId;
+use pumpkin_entity::{effect::StatusEffect, EntityId};
 use pumpkin_inventory::{InventoryError, WindowType};
-use pumpkin_protocol::server::play::{SCloseContainer, SSetPlayerGround, SUseItem};
+use pumpkin_plugins::events::PlayerSettingsChangeEvent;
+use pumpkin_protocol::server::play::{
+    SCloseContainer, SKeepAlive, SMoveVehicle, SPaddleBoat, SPlayerAbilities, SPlayerInput,
+    SSetPlayerGround, SUseItem,
+};
 use pumpkin_protocol::{
     client::play::{
-        Animation, CAcknowledgeBlockChange, CBlockUpdate, CEntityAnimation, CEntityVelocity,
-        CHeadRot, CHurtAnimation, CPingResponse, CPlayerChatMessage, CUpdateEntityPos,
-        CUpdateEntityPosRot, CUpdateEntityRot, CWorldEvent, FilterType,
+        Animation, CBlockEntityData, CBlockUpdate, CEntityAnimation, CHeadRot, CMoveVehicle,
+        COpenSignEditor, CPingResponse, CPlayerChatMessage, CPlayerInfoUpdate, CSystemChatMessage,
+        CUpdateEntityRot, CWorldEvent, FilterType, PlayerAction, PreviousMessage,
     },
     server::play::{
-        Action, ActionType, SChatCommand, SChatMessage, SClientInformationPlay, SConfirmTeleport,
-        SInteract, SPlayPingRequest, SPlayerAction, SPlayerCommand, SPlayerPosition,
-        SPlayerPositionRotation, SPlayerRotation, SSetCreativeSlot, SSetHeldItem, SSwingArm,
+        Action, ActionType, ClientCommandAction, SChatCommand, SChatMessage, SClientCommand,
+        SClientInformationPlay, SConfirmTeleport, SInteract, SMessageAcknowledgement,
+        SPlayPingRequest, SPlayerAction, SPlayerCommand, SPlayerPosition, SPlayerPositionRotation,
+        SPlayerRotation, SPlayerSession, SSetCreativeSlot, SSetHeldItem, SSwingArm, SUpdateSign,
         SUseItemOn, Status,
     },
 };
-use pumpkin_world::block::{BlockFace, BlockState};
-use pumpkin_world::global_registry;
+use pumpkin_world::block::{BlockEntity, BlockFace, BlockId, BlockState, MiningConditions};
+use pumpkin_world::{game_rules, global_registry, stats};
 
 use super::PlayerConfig;
 
-fn modulus(a: f32, b: f32) -> f32 {
-    ((a % b) + b) % b
-}
+/// How much of a block has to be broken when a player finishes digging it, like in vanilla.
+/// Packets arrive unevenly, so clients may finish a bit before the Server thinks they are done
+const MIN_FINISHED_PROGRESS: f32 = 0.7;
+
+/// Longer lines are cut off, vanilla clients don't send more either
+const MAX_SIGN_LINE_LENGTH: usize = 384;
 
 /// Handles all Play Packets send by a real Player
 /// NEVER TRUST THE CLIENT. HANDLE EVERY ERROR, UNWRAP/EXPECT ARE FORBIDDEN
@@ -74,47 +86,36 @@ impl Player {
             self.kick(TextComponent::text("Invalid movement"));
             return;
         }
-        let entity = &self.living_entity.entity;
-        entity.set_pos(
+        // Passengers move with their vehicle
+        if self.vehicle().is_some() {
+            return;
+        }
+        let to = Vector3::new(
             Self::clamp_horizontal(position.x),
             Self::clamp_vertical(position.feet_y),
             Self::clamp_horizontal(position.z),
         );
+        if !self.check_move(to) {
+            return;
+        }
+        let entity = &self.living_entity.entity;
+        let previous_pos = entity.pos.load();
+        let was_on_ground = entity.on_ground.load(std::sync::atomic::Ordering::Relaxed);
+        entity.set_pos(to.x, to.y, to.z);
         let pos = entity.pos.load();
-        self.last_position.store(pos);
-        let last_position = self.last_position.load();
+        // The client also sends its position when standing still
+        if pos != previous_pos {
+            self.update_last_action();
+        }
         entity
             .on_ground
             .store(position.ground, std::sync::atomic::Ordering::Relaxed);
-        let entity_id = entity.entity_id;
-        let Vector3 { x, y, z } = pos;
-        let (lastx, lasty, lastz) = (last_position.x, last_position.y, last_position.z);
-        let world = &entity.world;
-
-        // let delta = Vector3::new(x - lastx, y - lasty, z - lastz);
-        // let velocity = self.velocity;
-
-        // // Player is falling down fast, we should account for that
-        // let max_speed = if self.fall_flying { 300.0 } else { 100.0 };
-
-        // teleport when more than 8 blocks (i guess 8 blocks)
-        // TODO: REPLACE * 2.0 by movement packets. see vanilla for details
-        // if delta.length_squared() - velocity.length_squared() > max_speed * 2.0 {
-        //     self.teleport(x, y, z, self.entity.yaw, self.entity.pitch);
-        //     return;
-        // }
-        // send new position to all other players
-        world.broadcast_packet_expect(
-            &[self.client.id],
-            &CUpdateEntityPos::new(
-                entity_id.into(),
-                x.mul_add(4096.0, -(lastx * 4096.0)) as i16,
-                y.mul_add(4096.0, -(lasty * 4096.0)) as i16,
-                z.mul_add(4096.0, -(lastz * 4096.0)) as i16,
-                position.ground,
-            ),
-        );
-        player_chunker::update_position(entity, self).await;
+        self.add_movement_exhaustion(previous_pos, pos, was_on_ground);
+        self.add_movement_stats(previous_pos, pos, was_on_ground);
+        self.update_fall(previous_pos, pos);
+        self.update_pose();
+        self.broadcast_movement(false);
+        player_chunker::update_position(entity, self);
     }
 
     pub async fn handle_position_rotation(&self, position_rotation: SPlayerPositionRotation) {
@@ -129,67 +130,48 @@ impl Player {
             self.kick(TextComponent::text("Invalid rotation"));
             return;
         }
-        let entity = &self.living_entity.entity;
-
-        entity.set_pos(
+        if self.vehicle().is_some() {
+            self.handle_rotation(SPlayerRotation {
+                yaw: position_rotation.yaw,
+                pitch: position_rotation.pitch,
+                ground: position_rotation.ground,
+            })
+            .await;
+            return;
+        }
+        self.update_last_action();
+        let to = Vector3::new(
             Self::clamp_horizontal(position_rotation.x),
             Self::clamp_vertical(position_rotation.feet_y),
             Self::clamp_horizontal(position_rotation.z),
         );
+        if !self.check_move(to) {
+            return;
+        }
+        let entity = &self.living_entity.entity;
+        let previous_pos = entity.pos.load();
+        let was_on_ground = entity.on_ground.load(std::sync::atomic::Ordering::Relaxed);
+
+        entity.set_pos(to.x, to.y, to.z);
         let pos = entity.pos.load();
-        self.last_position.store(pos);
-        let last_position = self.last_position.load();
         entity.on_ground.store(
             position_rotation.ground,
             std::sync::atomic::Ordering::Relaxed,
         );
+        self.add_movement_exhaustion(previous_pos, pos, was_on_ground);
+        self.add_movement_stats(previous_pos, pos, was_on_ground);
+        self.update_fall(previous_pos, pos);
+        self.update_pose();
         entity.set_rotation(
             wrap_degrees(position_rotation.yaw) % 360.0,
             wrap_degrees(position_rotation.pitch).clamp(-90.0, 90.0) % 360.0,
         );
-
-        let entity_id = entity.entity_id;
-        let Vector3 { x, y, z } = pos;
-        let (lastx, lasty, lastz) = (last_position.x, last_position.y, last_position.z);
-        let yaw = modulus(entity.yaw.load() * 256.0 / 360.0, 256.0);
-        let pitch = modulus(entity.pitch.load() * 256.0 / 360.0, 256.0);
-        // let head_yaw = (entity.head_yaw * 256.0 / 360.0).floor();
-        let world = &entity.world;
-
-        // let delta = Vector3::new(x - lastx, y - lasty, z - lastz);
-        // let velocity = self.velocity;
-
-        // // Player is falling down fast, we should account for that
-        // let max_speed = if self.fall_flying { 300.0 } else { 100.0 };
-
-        // // teleport when more than 8 blocks (i guess 8 blocks)
-        // // TODO: REPLACE * 2.0 by movement packets. see vanilla for details
-        // if delta.length_squared() - velocity.length_squared() > max_speed * 2.0 {
-        //     self.teleport(x, y, z, yaw, pitch);
-        //     return;
-        // }
-        // send new position to all other players
-
-        world.broadcast_packet_expect(
-            &[self.client.id],
-            &CUpdateEntityPosRot::new(
-                entity_id.into(),
-                x.mul_add(4096.0, -(lastx * 4096.0)) as i16,
-                y.mul_add(4096.0, -(lasty * 4096.0)) as i16,
-                z.mul_add(4096.0, -(lastz * 4096.0)) as i16,
-                yaw as u8,
-                pitch as u8,
-                position_rotation.ground,
-            ),
-        );
-        world.broadcast_packet_expect(
-            &[self.client.id],
-            &CHeadRot::new(entity_id.into(), yaw as u8),
-        );
-        player_chunker::update_position(entity, self).await;
+        self.broadcast_movement(true);
+        player_chunker::update_position(entity, self);
     }
 
     pub async fn handle_rotation(&self, rotation: SPlayerRotation) {
+        self.update_last_action();
         if !rotation.yaw.is_finite() || !rotation.pitch.is_finite() {
             self.kick(TextComponent::text("Invalid rotation"));
             return;
@@ -202,24 +184,73 @@ impl Player {
             wrap_degrees(rotation.yaw) % 360.0,
             wrap_degrees(rotation.pitch).clamp(-90.0, 90.0) % 360.0,
         );
-        // send new position to all other players
+        // send the new rotation to the players who see this one
         let entity_id = entity.entity_id;
-        let yaw = modulus(entity.yaw.load() * 256.0 / 360.0, 256.0);
-        let pitch = modulus(entity.pitch.load() * 256.0 / 360.0, 256.0);
-        // let head_yaw = modulus(entity.head_yaw * 256.0 / 360.0, 256.0);
+        let yaw = angle_byte(entity.yaw.load());
+        let pitch = angle_byte(entity.pitch.load());
+        let world = entity.world();
+        world.broadcast_tracked(
+            entity_id,
+            &CUpdateEntityRot::new(entity_id.into(), yaw, pitch, rotation.ground),
+        );
+        world.broadcast_tracked(entity_id, &CHeadRot::new(entity_id.into(), yaw));
+    }
+
+    /// The driver of a boat moved it, their client decides where it goes
+    pub fn handle_move_vehicle(&self, move_vehicle: SMoveVehicle) {
+        let coordinates = [move_vehicle.x, move_vehicle.y, move_vehicle.z];
+        if coordinates.iter().any(|coordinate| !coordinate.is_finite())
+            || !move_vehicle.yaw.is_finite()
+            || !move_vehicle.pitch.is_finite()
+        {
+            self.kick(TextComponent::text("Invalid move vehicle packet received"));
+            return;
+        }
+        self.update_last_action();
+        let position = Vector3::new(
+            Self::clamp_horizontal(move_vehicle.x),
+            Self::clamp_vertical(move_vehicle.y),
+            Self::clamp_horizontal(move_vehicle.z),
+        );
+        let world = self.living_entity.entity.world();
+        // The client has to put the boat back if it went somewhere it can't
+        if let Some((position, yaw, pitch)) =
+            world.steer_boat(self, position, move_vehicle.yaw, move_vehicle.pitch)
+        {
+            self.client.send_packet(&CMoveVehicle::new(
+                position.x, position.y, position.z, yaw, pitch,
+            ));
+        }
+    }
+
+    pub fn handle_paddle_boat(&self, paddle: SPaddleBoat) {
+        self.living_entity.entity.world().paddle_boat(
+            self,
+            paddle.left_paddle,
+            paddle.right_paddle,
+        );
+    }
 
-        let world = &entity.world;
-        let packet =
-            CUpdateEntityRot::new(entity_id.into(), yaw as u8, pitch as u8, rotation.ground);
-        world.broadcast_packet_expect(&[self.client.id], &packet);
-        let packet = CHeadRot::new(entity_id.into(), yaw as u8);
-        world.broadcast_packet_expect(&[self.client.id], &packet);
+    pub fn handle_player_input(&self, input: SPlayerInput) {
+        if !input.forward.is_finite() || !input.sideways.is_finite() {
+            self.kick(TextComponent::text("Invalid player input"));
+            return;
+        }
+        if input.flags & SPlayerInput::UNMOUNT != 0 {
+            self.dismount();
+            return;
+        }
+        self.living_entity
+            .entity
+            .world()
+            .push_vehicle(self, input.forward > 0.0);
     }
 
     pub fn handle_chat_command(&self, server: &Server, command: SChatCommand) {
+        self.update_last_action();
         let dispatcher = server.command_dispatcher.clone();
         dispatcher.handle_command(&mut CommandSender::Player(self), server, &command.command);
-        if ADVANCED_CONFIG.commands.log_console {
+        if ADVANCED_CONFIG.get().commands.log_console {
             log::info!(
                 "Player ({}): executed command /{}",
                 self.gameprofile.name,
@@ -228,6 +259,20 @@ impl Player {
         }
     }
 
+    /// The client starts or stops flying, players who may not fly are put back on the ground
+    pub fn handle_player_abilities(&self, abilities: SPlayerAbilities) {
+        self.update_last_action();
+        let flying = abilities.flags & SPlayerAbilities::FLYING != 0;
+        let allowed = {
+            let mut abilities = self.abilities.lock();
+            abilities.flying = flying && abilities.allow_flying;
+            abilities.flying == flying
+        };
+        if !allowed {
+            self.send_abilities_update();
+        }
+    }
+
     pub fn handle_player_ground(&self, ground: SSetPlayerGround) {
         self.living_entity
             .entity
@@ -235,7 +280,8 @@ impl Player {
             .store(ground.on_ground, std::sync::atomic::Ordering::Relaxed);
     }
 
-    pub async fn handle_player_command(&self, command: SPlayerCommand) {
+    pub fn handle_player_command(&self, command: SPlayerCommand) {
+        self.update_last_action();
         if command.entity_id != self.entity_id().into() {
             return;
         }
@@ -243,39 +289,21 @@ impl Player {
         if let Some(action) = Action::from_i32(command.action.0) {
             let entity = &self.living_entity.entity;
             match action {
-                pumpkin_protocol::server::play::Action::StartSneaking => {
-                    if !entity.sneaking.load(std::sync::atomic::Ordering::Relaxed) {
-                        entity.set_sneaking(true).await
-                    }
-                }
-                pumpkin_protocol::server::play::Action::StopSneaking => {
-                    if entity.sneaking.load(std::sync::atomic::Ordering::Relaxed) {
-                        entity.set_sneaking(false).await
-                    }
+                Action::StartSneaking => self.set_sneaking(true),
+                Action::StopSneaking => self.set_sneaking(false),
+                Action::LeaveBed => self.wake_up(true),
+                Action::StartSprinting => self.set_sprinting(true),
+                Action::StopSprinting => self.set_sprinting(false),
+                // None of the vehicles can jump or has an inventory yet
+                Action::StartHorseJump | Action::StopHorseJump | Action::OpenVehicleInventory => {
+                    log::debug!(
+                        "{} sent a vehicle action no vehicle supports",
+                        self.gameprofile.name
+                    );
                 }
-                pumpkin_protocol::server::play::Action::LeaveBed => todo!(),
-                pumpkin_protocol::server::play::Action::StartSprinting => {
-                    if !entity.sprinting.load(std::sync::atomic::Ordering::Relaxed) {
-                        entity.set_sprinting(true).await
-                    }
-                }
-                pumpkin_protocol::server::play::Action::StopSprinting => {
-                    if entity.sprinting.load(std::sync::atomic::Ordering::Relaxed) {
-                        entity.set_sprinting(false).await
-                    }
-                }
-                pumpkin_protocol::server::play::Action::StartHorseJump => todo!(),
-                pumpkin_protocol::server::play::Action::StopHorseJump => todo!(),
-                pumpkin_protocol::server::play::Action::OpenVehicleInventory => todo!(),
-                pumpkin_protocol::server::play::Action::StartFlyingElytra => {
-                    let fall_flying = entity.check_fall_flying();
-                    if entity
-                        .fall_flying
-                        .load(std::sync::atomic::Ordering::Relaxed)
-                        != fall_flying
-                    {
-                        entity.set_fall_flying(fall_flying).await;
-                    }
+                Action::StartFlyingElytra => {
+                    entity.set_fall_flying(entity.check_fall_flying());
+                    self.update_pose();
                 } // TODO
             }
         } else {
@@ -284,18 +312,17 @@ impl Player {
     }
 
     pub async fn handle_swing_arm(&self, swing_arm: SSwingArm) {
+        self.update_last_action();
         match Hand::from_i32(swing_arm.hand.0) {
             Some(hand) => {
                 let animation = match hand {
                     Hand::Main => Animation::SwingMainArm,
                     Hand::Off => Animation::SwingOffhand,
                 };
+                self.reset_attack_strength();
                 let id = self.entity_id();
-                let world = &self.living_entity.entity.world;
-                world.broadcast_packet_expect(
-                    &[self.client.id],
-                    &CEntityAnimation::new(id.into(), animation as u8),
-                )
+                let world = self.living_entity.entity.world();
+                world.broadcast_tracked(id, &CEntityAnimation::new(id.into(), animation as u8))
             }
             None => {
                 self.kick(TextComponent::text("Invalid hand"));
@@ -303,34 +330,95 @@ impl Player {
         };
     }
 
-    pub async fn handle_chat_message(&self, chat_message: SChatMessage) {
+    pub async fn handle_chat_message(&self, server: &Server, chat_message: SChatMessage) {
+        self.update_last_action();
         dbg!("got message");
 
-        let message = chat_message.message;
+        let message = &chat_message.message;
         if message.len() > 256 {
             self.kick(TextComponent::text("Oversized message"));
             return;
         }
 
-        // TODO: filter message & validation
-        let gameprofile = &self.gameprofile;
+        // TODO: filter message
+        let validated = self
+            .chat
+            .lock()
+            .validate(self.gameprofile.id, &chat_message);
+        let validated = match validated {
+            Ok(validated) => validated,
+            Err(err) => {
+                self.kick(TextComponent::text(&err.to_string()));
+                return;
+            }
+        };
+        server
+            .cluster
+            .send_chat(&format!("<{}> {message}", self.gameprofile.name));
+        if ADVANCED_CONFIG.get().chat.no_chat_reports {
+            // Nothing is signed, so there is nothing to report
+            let gameprofile = &self.gameprofile;
+            self.living_entity
+                .entity
+                .world()
+                .broadcast_packet_all(&CSystemChatMessage::new(
+                    TextComponent::text(&format!("<{}> {message}", gameprofile.name)),
+                    false,
+                ));
+            return;
+        }
+        if validated.is_none() && secure_chat_enforced() {
+            self.kick(TextComponent::text(
+                "Received chat packet with missing or invalid signature",
+            ));
+            return;
+        }
 
-        let entity = &self.living_entity.entity;
-        let world = &entity.world;
-        world.broadcast_packet_all(&CPlayerChatMessage::new(
+        let gameprofile = &self.gameprofile;
+        let (index, previous_messages, signature) = match &validated {
+            Some(validated) => (
+                validated.index,
+                validated
+                    .last_seen
+                    .iter()
+                    .map(|signature| PreviousMessage::full(signature))
+                    .collect(),
+                chat_message.signature.as_deref(),
+            ),
+            None => (0, Vec::new(), None),
+        };
+        let packet = CPlayerChatMessage::new(
             gameprofile.id,
-            1.into(),
-            chat_message.signature.as_deref(),
-            &message,
+            index.into(),
+            signature,
+            message,
             chat_message.timestamp,
             chat_message.salt,
-            &[],
-            Some(TextComponent::text(&message)),
+            &previous_messages,
+            None,
             FilterType::PassThrough,
             1.into(),
             TextComponent::text(&gameprofile.name),
             None,
-        ))
+        );
+
+        let world = self.living_entity.entity.world();
+        let players: Vec<_> = world.current_players.lock().values().cloned().collect();
+        for player in players {
+            player.client.send_packet(&packet);
+            let Some(signature) = signature else {
+                continue;
+            };
+            // The recipient has to acknowledge the message later
+            let pending = {
+                let mut chat = player.chat.lock();
+                chat.last_seen.add_pending(signature);
+                chat.last_seen.pending_count()
+            };
+            if pending > MAX_PENDING {
+                player.kick(TextComponent::text("Too many unacknowledged chat messages"));
+            }
+        }
 
         /* server.broadcast_packet(
             self,
@@ -343,11 +431,73 @@ impl Player {
         ) */
     }
 
-    pub fn handle_client_information_play(&self, client_information: SClientInformationPlay) {
+    pub fn handle_player_session(&self, server: &Server, player_session: SPlayerSession) {
+        if !BASIC_CONFIG.get().online_mode || ADVANCED_CONFIG.get().chat.no_chat_reports {
+            // Without Online mode we can't know if the key belongs to the player
+            return;
+        }
+        let session = ChatSession::validate(
+            self.gameprofile.id,
+            player_session,
+            &server.mojang_public_keys.read(),
+        );
+        let session = match session {
+            Ok(session) => Arc::new(session),
+            Err(err) => {
+                self.kick(TextComponent::text(&err.to_string()));
+                return;
+            }
+        };
+        self.chat.lock().set_session(session.clone());
+
+        // Let everyone verify the player's messages
+        let world = self.living_entity.entity.world();
+        world.broadcast_packet_all(&CPlayerInfoUpdate::new(
+            0x02,
+            &[pumpkin_protocol::client::play::Player {
+                uuid: self.gameprofile.id,
+                actions: vec![PlayerAction::InitializeChat(Some(session.init_chat()))],
+            }],
+        ));
+    }
+
+    pub fn handle_message_acknowledgement(&self, acknowledgement: SMessageAcknowledgement) {
+        let result = self
+            .chat
+            .lock()
+            .last_seen
+            .apply_offset(acknowledgement.message_count.0);
+        if let Err(err) = result {
+            self.kick(TextComponent::text(&format!(
+                "Chat message validation failure: {err}"
+            )));
+        }
+    }
+
+    pub async fn handle_client_command(self: &Arc<Self>, server: &Server, command: SClientCommand) {
+        match ClientCommandAction::from_i32(command.action_id.0) {
+            Some(ClientCommandAction::PerformRespawn) => self.respawn(server).await,
+            Some(ClientCommandAction::RequestStats) => self.send_stats(),
+            None => self.kick(TextComponent::text("Invalid client command")),
+        }
+    }
+
+    /// The player changed their settings, the other players see the changed skin layers and main
+    /// arm and chunks are sent as far as the new view distance
+    pub fn handle_client_information_play(
+        &self,
+        server: &Server,
+        client_information: SClientInformationPlay,
+    ) {
         if let (Some(main_hand), Some(chat_mode)) = (
-            Hand::from_i32(client_information.main_hand.into()),
+            Arm::from_i32(client_information.main_hand.into()),
             ChatMode::from_i32(client_information.chat_mode.into()),
         ) {
+            let old_view_distance = self.view_distance();
+            let model_changed = {
+                let config = self.config.lock();
+                config.skin_parts != client_information.skin_parts || config.main_hand != main_hand
+            };
             *self.config.lock() = PlayerConfig {
                 locale: client_information.locale,
                 view_distance: client_information.view_distance,
@@ -358,71 +508,63 @@ impl Player {
                 text_filtering: client_information.text_filtering,
                 server_listing: client_information.server_listing,
             };
+            let world = self.living_entity.entity.world();
+            if self.view_distance() != old_view_distance {
+                player_chunker::update_distances(&world, self);
+            }
+            if model_changed {
+                for packet in self.model_packets() {
+                    self.client.send_packet(&packet);
+                    world.broadcast_tracked(self.entity_id(), &packet);
+                }
+            }
+            let config = self.config.lock().clone();
+            let event = PlayerSettingsChangeEvent {
+                player_name: &self.gameprofile.name,
+                player_uuid: self.gameprofile.id,
+                locale: &config.locale,
+                view_distance: self.view_distance(),
+                skin_parts: config.skin_parts,
+            };
+            server
+                .plugins
+                .read()
+                .for_each(|plugin| plugin.on_player_settings_change(&event));
         } else {
             self.kick(TextComponent::text("Invalid hand or chat type"))
         }
     }
 
-    pub async fn handle_interact(&self, _: &Server, interact: SInteract) {
+    pub async fn handle_interact(&self, server: &Server, interact: SInteract) {
+        self.update_last_action();
         let sneaking = interact.sneaking;
+        self.set_sneaking(sneaking);
         let entity = &self.living_entity.entity;
-        if entity.sneaking.load(std::sync::atomic::Ordering::Relaxed) != sneaking {
-            entity.set_sneaking(sneaking).await;
-        }
         match ActionType::from_i32(interact.typ.0) {
             Some(action) => match action {
                 ActionType::Attack => {
-                    let entity_id = interact.entity_id;
-                    // TODO: do validation and stuff
-                    let config = &ADVANCED_CONFIG.pvp;
-                    if config.enabled {
-                        let world = &entity.world;
-                        let attacked_player = world.get_player_by_entityid(entity_id.0 as EntityId);
-                        if let Some(player) = attacked_player {
-                            let victem_entity = &player.living_entity.entity;
-                            if config.protect_creative
-                                && player.gamemode.load() == GameMode::Creative
-                            {
-                                return;
-                            }
-                            if config.knockback {
-                                let yaw = entity.yaw.load();
-                                let strength = 1.0;
-                                let victem_velocity = victem_entity.velocity.load();
-                                let saved_velo = victem_velocity;
-                                victem_entity.knockback(
-                                    strength * 0.5,
-                                    (yaw * (PI / 180.0)).sin() as f64,
-                                    -(yaw * (PI / 180.0)).cos() as f64,
-                                );
-                                let packet = &CEntityVelocity::new(
-                                    &entity_id,
-                                    victem_velocity.x as f32,
-                                    victem_velocity.y as f32,
-                                    victem_velocity.z as f32,
-                                );
-                                let velocity = entity.velocity.load();
-                                victem_entity
-                                    .velocity
-                                    .store(velocity.multiply(0.6, 1.0, 0.6));
-
-                                victem_entity.velocity.store(saved_velo);
-                                player.client.send_packet(packet);
-                            }
-                            if config.hurt_animation {
-                                world.broadcast_packet_all(&CHurtAnimation::new(
-                                    &entity_id,
-                                    entity.yaw.load(),
-                                ))
-                            }
-                            if config.swing {}
-                        } else {
-                            self.kick(TextComponent::text("Interacted with invalid entity id"))
-                        }
+                    let world = entity.world();
+                    let entity_id = interact.entity_id.0 as EntityId;
+                    if let Some(target) = world.get_player_by_entityid(entity_id) {
+                        self.attack_player(server, &target);
+                    } else if !self.attack_vehicle(entity_id) {
+                        // The entity may be gone already, the client did not know yet
+                        log::debug!(
+                            "{} attacked unknown entity {entity_id}",
+                            self.gameprofile.name
+                        );
                     }
                 }
                 ActionType::Interact => {
-                    dbg!("todo");
+                    let entity_id = interact.entity_id.0 as EntityId;
+                    let world = entity.world();
+                    // Name tags are used from the main hand only
+                    let main_hand = interact.hand.map_or(0, |hand| hand.0) == 0;
+                    let named = main_hand && world.name_mob(self, entity_id);
+                    // Sneaking players don't get in, like in vanilla
+                    if !named && !sneaking {
+                        world.enter_vehicle(self, entity_id);
+                    }
                 }
                 ActionType::InteractAt => {
                     dbg!("todo");
@@ -431,130 +573,491 @@ impl Player {
             None => self.kick(TextComponent::text("Invalid action type")),
         }
     }
-    pub async fn handle_player_action(&self, player_action: SPlayerAction) {
+    pub async fn handle_player_action(&self, server: &Server, player_action: SPlayerAction) {
+        self.update_last_action();
         match Status::from_i32(player_action.status.0) {
             Some(status) => match status {
                 Status::StartedDigging => {
-                    if !self.can_interact_with_block_at(&player_action.location, 1.0) {
-                        // TODO: maybe log?
-                        return;
-                    }
-                    // TODO: do validation
-                    // TODO: Config
-                    if self.gamemode.load() == GameMode::Creative {
-                        let location = player_action.location;
-                        // Block break & block break sound
-                        // TODO: currently this is always dirt replace it
-                        let entity = &self.living_entity.entity;
-                        let world = &entity.world;
-                        world.broadcast_packet_all(&CWorldEvent::new(2001, &location, 11, false));
-                        // AIR
-                        world.broadcast_packet_all(&CBlockUpdate::new(&location, 0.into()));
+                    let location = player_action.location;
+                    let world = self.living_entity.entity.world();
+                    if !self.can_interact_with_block_at(&location, 1.0)
+                        || !self.can_edit_block(server, &world, &location)
+                    {
+                        self.resync_block(&world, &location);
+                    } else if self.abilities.lock().instant_build {
+                        self.break_block(&world, &location);
+                    } else if let Some(block) = world.level.get_block(&location) {
+                        let progress = self.destroy_progress(&world, &block);
+                        if progress >= 1.0 {
+                            self.break_block(&world, &location);
+                        } else {
+                            // Clients which broke the block right away get it back
+                            self.resync_block(&world, &location);
+                            if progress > 0.0 {
+                                self.digging.store(Some((location, Instant::now())));
+                            }
+                        }
                     }
+                    self.acknowledge_block_change(player_action.sequence);
                 }
                 Status::CancelledDigging => {
-                    if !self.can_interact_with_block_at(&player_action.location, 1.0) {
-                        // TODO: maybe log?
-                        return;
-                    }
+                    self.digging.store(None);
                     self.current_block_destroy_stage
                         .store(0, std::sync::atomic::Ordering::Relaxed);
+                    self.acknowledge_block_change(player_action.sequence);
                 }
                 Status::FinishedDigging => {
-                    // TODO: do validation
                     let location = player_action.location;
-                    if !self.can_interact_with_block_at(&location, 1.0) {
-                        // TODO: maybe log?
-                        return;
+                    let world = self.living_entity.entity.world();
+                    // Only the block the player started digging may be broken, once they dug
+                    // long enough for their tool and effects
+                    let started = self
+                        .digging
+                        .take()
+                        .filter(|(digging, _)| digging.0 == location.0);
+                    let dug = match (started, world.level.get_block(&location)) {
+                        (Some((_, started)), Some(block)) => {
+                            let ticks = started.elapsed().as_secs_f32() * TICKS_PER_SECOND as f32;
+                            self.destroy_progress(&world, &block) * (ticks + 1.0)
+                                >= MIN_FINISHED_PROGRESS
+                        }
+                        _ => false,
+                    };
+                    if dug
+                        && self.can_interact_with_block_at(&location, 1.0)
+                        && self.gamemode.load() == GameMode::Survival
+                        && !world.is_spawn_protected(server, self, &location)
+                    {
+                        self.break_block(&world, &location);
+                    } else {
+                        self.resync_block(&world, &location);
                     }
-                    // Block break & block break sound
-                    // TODO: currently this is always dirt replace it
-                    let entity = &self.living_entity.entity;
-                    let world = &entity.world;
-                    world.broadcast_packet_all(&CWorldEvent::new(2001, &location, 11, false));
-                    // AIR
-                    world.broadcast_packet_all(&CBlockUpdate::new(&location, 0.into()));
-                    // TODO: Send this every tick
-                    self.client
-                        .send_packet(&CAcknowledgeBlockChange::new(player_action.sequence));
-                }
-                Status::DropItemStack => {
-                    dbg!("todo");
-                }
-                Status::DropItem => {
-                    dbg!("todo");
+                    self.acknowledge_block_change(player_action.sequence);
                 }
+                Status::DropItemStack => self.drop_held_item(true),
+                Status::DropItem => self.drop_held_item(false),
                 Status::ShootArrowOrFinishEating => {
-                    dbg!("todo");
-                }
-                Status::SwapItem => {
-                    dbg!("todo");
+                    // The player let go of the use button, bows shoot and eating stops
+                    self.release_bow();
+                    self.stop_eating();
                 }
+                Status::SwapItem => self.swap_hands(),
             },
             None => self.kick(TextComponent::text("Invalid status")),
         }
     }
 
+    /// Whether the player may break and place blocks, players in adventure and spectator mode may not
+    fn can_edit_world(&self) -> bool {
+        matches!(
+            self.gamemode.load(),
+            GameMode::Survival | GameMode::Creative
+        )
+    }
+
+    /// Whether the player may change the block, the game mode and spawn protection may prevent it
+    fn can_edit_block(&self, server: &Server, world: &World, location: &WorldPosition) -> bool {
+        self.can_edit_world() && !world.is_spawn_protected(server, self, location)
+    }
+
+    /// How much of the block the player breaks every tick with the item they hold
+    fn destroy_progress(&self, world: &World, block: &BlockId) -> f32 {
+        let entity = &self.living_entity.entity;
+        let pos = entity.pos.load();
+        let eyes = WorldPosition(Vector3::new(
+            pos.x.floor() as i32,
+            (pos.y + f64::from(entity.standing_eye_height)).floor() as i32,
+            pos.z.floor() as i32,
+        ));
+        let conditions = MiningConditions {
+            // Conduit power helps like haste
+            haste: self
+                .living_entity
+                .effect_amplifier(StatusEffect::Haste)
+                .max(
+                    self.living_entity
+                        .effect_amplifier(StatusEffect::ConduitPower),
+                ),
+            mining_fatigue: self
+                .living_entity
+                .effect_amplifier(StatusEffect::MiningFatigue),
+            in_water: world.level.get_block(&eyes).is_some_and(|block| {
+                block
+                    .registry_state()
+                    .is_some_and(|(registry_id, _)| registry_id == "minecraft:water")
+            }),
+            on_ground: entity.on_ground.load(std::sync::atomic::Ordering::Relaxed),
+        };
+        let inventory = self.inventory.lock();
+        block.destroy_progress(inventory.held_item(), &conditions)
+    }
+
+    /// Breaks a block, the other players see it break. The block drops its items unless the
+    /// player is in creative
+    fn break_block(&self, world: &World, location: &WorldPosition) {
+        let Some(block) = world.level.get_block(location) else {
+            return;
+        };
+        if block.is_air() {
+            return;
+        }
+        // Block break particles & sound, the player breaking it plays them on their own
+        world.broadcast_packet_expect(
+            &[self.client.id],
+            &CWorldEvent::new(2001, location, block.get_id_mojang_repr(), false),
+        );
+        world.set_block(location, BlockId::default());
+        // The other half of a bed breaks with it, only the foot drops the bed
+        let other_half = bed::other_half(world, location, &block);
+        if let Some((other, _)) = &other_half {
+            world.set_block(other, BlockId::default());
+        }
+        self.add_exhaustion(MINING_EXHAUSTION);
+        if let Some((registry_id, _)) = block.registry_state() {
+            self.increment_stat(stats::MINED, registry_id, 1);
+        }
+
+        if self.gamemode.load() == GameMode::Creative
+            || !world.game_rule_bool(game_rules::DO_TILE_DROPS)
+        {
+            return;
+        }
+        let experience = block.experience(self.inventory.lock().held_item());
+        world.drop_experience(
+            Vector3::new(
+                f64::from(location.0.x) + 0.5,
+                f64::from(location.0.y) + 0.5,
+                f64::from(location.0.z) + 0.5,
+            ),
+            experience,
+        );
+        for item in block.drops(self.inventory.lock().held_item()) {
+            world.drop_block_item(location, item);
+        }
+        if let Some((other, other_block)) = other_half {
+            for item in other_block.drops(self.inventory.lock().held_item()) {
+                world.drop_block_item(&other, item);
+            }
+        }
+    }
+
+    /// Sends the actual block to the player, undoing a change their client predicted
+    fn resync_block(&self, world: &World, location: &WorldPosition) {
+        if let Some(block) = world.level.get_block(location) {
+            self.client.send_packet(&CBlockUpdate::new(
+                location,
+                block.get_id_mojang_repr().into(),
+            ));
+        }
+    }
+
+    pub fn handle_keep_alive(&self, keep_alive: SKeepAlive) {
+        if self
+            .wait_for_keep_alive
+            .load(std::sync::atomic::Ordering::Relaxed)
+            && keep_alive.keep_alive_id
+                == self
+                    .keep_alive_id
+                    .load(std::sync::atomic::Ordering::Relaxed)
+        {
+            self.wait_for_keep_alive
+                .store(false, std::sync::atomic::Ordering::Relaxed);
+        } else {
+            self.kick(TextComponent::text(&format_message(
+                &MESSAGES.get().timed_out,
+                &[],
+            )));
+        }
+    }
+
     pub fn handle_play_ping_request(&self, request: SPlayPingRequest) {
         self.client
             .send_packet(&CPingResponse::new(request.payload));
     }
 
-    pub async fn handle_use_item_on(&self, use_item_on: SUseItemOn) {
+    pub async fn handle_use_item_on(&self, server: &Server, use_item_on: SUseItemOn) {
+        self.update_last_action();
         let location = use_item_on.location;
 
-        if !self.can_interact_with_block_at(&location, 1.0) {
-            // TODO: maybe log?
+        let Some(face) = BlockFace::from_i32(use_item_on.face.0) else {
+            self.kick(TextComponent::text("Invalid block face"));
             return;
+        };
+        let Some(hand) = Hand::from_i32(use_item_on.hand.0) else {
+            self.kick(TextComponent::text("Invalid hand"));
+            return;
+        };
+        let off_hand = matches!(hand, Hand::Off);
+        let world = self.living_entity.entity.world();
+        let held_item = hand_item(&mut self.inventory.lock(), off_hand).map(|item| item.item_id);
+        if !self.can_interact_with_block_at(&location, 1.0)
+            || !self.can_edit_block(server, &world, &location)
+        {
+            // TODO: maybe log?
+            self.resync_block(&world, &WorldPosition(location.0 + face.to_offset()));
+        } else if !off_hand
+            && !self
+                .living_entity
+                .entity
+                .sneaking
+                .load(std::sync::atomic::Ordering::Relaxed)
+            && self.use_block(server, &world, &location)
+        {
+            // Opening the block is all the click does, sneaking players place their block against it instead.
+            // The client only clicks with the off hand if the main hand did nothing
+        } else if let Some(item_id) = held_item {
+            let minecraft_id =
+                global_registry::find_minecraft_id(global_registry::ITEM_REGISTRY, item_id)
+                    .expect("All item ids are in the global registry");
+            let target = WorldPosition(location.0 + face.to_offset());
+            match minecraft_id {
+                "minecraft:flint_and_steel"
+                    if world
+                        .level
+                        .get_block(&location)
+                        .is_some_and(|block| block.category() == Some("minecraft:tnt")) =>
+                {
+                    world.prime_tnt(server, &location, tnt::DEFAULT_FUSE);
+                }
+                "minecraft:flint_and_steel" => {
+                    let is_air = world.level.get_block(&target).is_some_and(|b| b.is_air());
+                    if is_air && !portal::light_nether_portal(&world, target) {
+                        if let Ok(fire) = BlockState::new("minecraft:fire", None) {
+                            world.set_block(&target, fire.into());
+                        }
+                    }
+                }
+                "minecraft:ender_eye" => {
+                    portal::insert_eye(&world, location);
+                }
+                "minecraft:minecart" => self.place_minecart(&world, &location, off_hand),
+                _ => self.place_block(&world, minecraft_id, location, &face, off_hand),
+            }
         }
+        self.acknowledge_block_change(use_item_on.sequence);
+    }
 
-        if let Some(face) = BlockFace::from_i32(use_item_on.face.0) {
-            if let Some(item) = self.inventory.lock().held_item() {
-                let minecraft_id = global_registry::find_minecraft_id(
-                    global_registry::ITEM_REGISTRY,
-                    item.item_id,
+    /// Opens or flips the block the player clicked, e.g. a chest, a sign or a lever.
+    /// Returns false if the block can't be opened
+    fn use_block(&self, server: &Server, world: &World, location: &WorldPosition) -> bool {
+        if world.use_redstone_block(location)
+            || bed::use_bed(server, world, self, location)
+            || bed::use_respawn_anchor(server, world, self, location)
+        {
+            return true;
+        }
+        if world
+            .level
+            .get_block(location)
+            .is_some_and(|block| block.category() == Some("minecraft:crafting_table"))
+        {
+            self.open_crafting_table(server);
+            return true;
+        }
+        if world
+            .level
+            .get_block(location)
+            .and_then(|block| block.registry_state())
+            .is_some_and(|(registry_id, _)| registry_id == "minecraft:enchanting_table")
+        {
+            self.open_enchanting_table(server, location);
+            return true;
+        }
+        if world
+            .level
+            .get_block(location)
+            .and_then(|block| block.registry_state())
+            .is_some_and(|(registry_id, _)| {
+                matches!(
+                    registry_id,
+                    "minecraft:anvil" | "minecraft:chipped_anvil" | "minecraft:damaged_anvil"
                 )
-                .expect("All item ids are in the global registry");
-                if let Ok(block_state_id) = BlockState::new(minecraft_id, None) {
-                    let entity = &self.living_entity.entity;
-                    let world = &entity.world;
-                    world.broadcast_packet_all(&CBlockUpdate::new(
-                        &location,
-                        block_state_id.get_id_mojang_repr().into(),
-                    ));
-                    world.broadcast_packet_all(&CBlockUpdate::new(
-                        &WorldPosition(location.0 + face.to_offset()),
-                        block_state_id.get_id_mojang_repr().into(),
-                    ));
-                }
+            })
+        {
+            self.open_anvil(server, location);
+            return true;
+        }
+        let Some(entity) = world.level.get_block_entity(location) else {
+            return false;
+        };
+        if entity.is_sign() {
+            // Waxed signs can't be edited, clicking them does nothing
+            if !entity.is_waxed() {
+                self.client
+                    .send_packet(&COpenSignEditor::new(location, true));
+            }
+            return true;
+        }
+        block_entity::open_chest(server, world, self, location)
+            || furnace::open_furnace(server, world, self, location)
+    }
+
+    pub fn handle_update_sign(&self, server: &Server, update_sign: SUpdateSign) {
+        self.update_last_action();
+        let location = update_sign.location;
+        let world = self.living_entity.entity.world();
+        let Some(mut entity) = world
+            .level
+            .get_block_entity(&location)
+            .filter(BlockEntity::is_sign)
+        else {
+            return;
+        };
+        if entity.is_waxed()
+            || !self.can_edit_block(server, &world, &location)
+            || !self.can_interact_with_block_at(&location, 1.0)
+        {
+            // Undo the text the client already shows
+            if let Some(packet) = CBlockEntityData::new(&entity) {
+                self.client.send_packet(&packet);
+            }
+            return;
+        }
+        let lines: [String; 4] = [
+            update_sign.line1,
+            update_sign.line2,
+            update_sign.line3,
+            update_sign.line4,
+        ]
+        .map(|line| {
+            // Players can't write formatting codes on signs
+            line.chars()
+                .filter(|c| *c != '§' && !c.is_control())
+                .take(MAX_SIGN_LINE_LENGTH)
+                .collect()
+        });
+        entity.set_sign_text(update_sign.is_front_text, lines);
+        world.set_block_entity(entity);
+    }
+
+    /// Places the block of the held item against the clicked block.
+    /// The block is not placed if something is in the way, e.g. a solid block or a player
+    fn place_block(
+        &self,
+        world: &World,
+        item: &str,
+        clicked: WorldPosition,
+        face: &BlockFace,
+        off_hand: bool,
+    ) {
+        // Not every item is a block
+        let Ok(block) = BlockState::new(item, None) else {
+            return;
+        };
+        let block = BlockId::from(block);
+        // Leaves placed by players never decay, like in vanilla
+        let block = block.with_property("persistent", "true").unwrap_or(block);
+        // Clicking e.g. grass or water replaces it, like in vanilla
+        let target = if world
+            .level
+            .get_block(&clicked)
+            .is_some_and(|block| block.is_replaceable())
+        {
+            clicked
+        } else {
+            WorldPosition(clicked.0 + face.to_offset())
+        };
+        let placeable = world
+            .level
+            .get_block(&target)
+            .is_some_and(|block| block.is_replaceable())
+            && self.can_interact_with_block_at(&target, 1.0)
+            && !(block.has_collision() && world.is_occupied(&target));
+        if !placeable {
+            self.resync_block(world, &target);
+            return;
+        }
+        if bed::is_bed(&block) {
+            if !bed::place_bed(world, self, &target, block) {
+                self.resync_block(world, &target);
+                return;
             }
-            self.client
-                .send_packet(&CAcknowledgeBlockChange::new(use_item_on.sequence));
         } else {
-            self.kick(TextComponent::text("Invalid block face"))
+            world.set_block(&target, block);
+        }
+        if world
+            .level
+            .get_block_entity(&target)
+            .is_some_and(|entity| entity.is_sign())
+        {
+            self.client
+                .send_packet(&COpenSignEditor::new(&target, true));
+        }
+        if self.gamemode.load() != GameMode::Creative {
+            let mut inventory = self.inventory.lock();
+            let held = hand_item(&mut inventory, off_hand);
+            if let Some(item) = held {
+                item.item_count = item.item_count.saturating_sub(1);
+                if item.item_count == 0 {
+                    *held = None;
+                }
+            }
         }
     }
 
-    pub fn handle_use_item(&self, _use_item: SUseItem) {
-        // TODO: handle packet correctly
-        log::error!("An item was used(SUseItem), but the packet is not implemented yet");
+    pub fn handle_use_item(&self, use_item: SUseItem) {
+        self.update_last_action();
+        let Some(hand) = Hand::from_i32(use_item.hand.0) else {
+            self.kick(TextComponent::text("Invalid hand"));
+            return;
+        };
+        if !self.use_projectile_item(&hand) && !self.place_boat(&hand) {
+            self.start_eating(&hand);
+        }
+        self.acknowledge_block_change(use_item.sequence);
     }
 
     pub fn handle_set_held_item(&self, held: SSetHeldItem) {
+        self.update_last_action();
         let slot = held.slot;
         if !(0..=8).contains(&slot) {
-            self.kick(TextComponent::text("Invalid held slot"))
+            self.kick(TextComponent::text("Invalid held slot"));
+            return;
         }
         self.inventory.lock().set_selected(slot as usize);
+        self.reset_attack_strength();
     }
 
     pub fn handle_set_creative_slot(&self, packet: SSetCreativeSlot) -> Result<(), InventoryError> {
         if self.gamemode.load() != GameMode::Creative {
             return Err(InventoryError::PermissionError);
         }
-        self.inventory
-            .lock()
-            .set_slot(packet.slot as usize, packet.clicked_item.to_item(), false)
+        let slot = usize::try_from(packet.slot)
+            .ok()
+            .filter(|slot| (1..=45).contains(slot));
+        let item = if packet.clicked_item.is_empty() {
+            Some(None)
+        } else {
+            // Only registered items which fit into one stack, like in vanilla
+            packet
+                .clicked_item
+                .to_item()
+                .filter(|item| {
+                    global_registry::find_minecraft_id(global_registry::ITEM_REGISTRY, item.item_id)
+                        .is_some()
+                        && (1..=item.max_stack_size()).contains(&item.item_count)
+                })
+                .map(Some)
+        };
+        // Items dropped out of the creative menu
+        if packet.slot == -1 {
+            if let Some(Some(item)) = item {
+                self.drop_item(item, false);
+            }
+            return Ok(());
+        }
+        let (Some(slot), Some(item)) = (slot, item) else {
+            // The client shows the item anyways, so it gets the inventory as it is on the Server
+            log::debug!(
+                "{} tried to put an invalid item into creative slot {}",
+                self.gameprofile.name,
+                packet.slot
+            );
+            self.set_container_content(None);
+            return Ok(());
+        };
+        // Creative players can put any item into any slot, even the armor slots
+        self.inventory.lock().set_slot(slot, item, true)
     }
 
     // TODO:
@@ -566,17 +1069,33 @@ impl Player {
             .lock()
             .state_id
             .store(0, std::sync::atomic::Ordering::Relaxed);
-        let open_container = self.open_container.load();
-        if let Some(id) = open_container {
-            let mut open_containers = server.open_containers.write();
-            if let Some(container) = open_containers.get_mut(&id) {
-                container.remove_player(self.entity_id())
-            }
-            self.open_container.store(None);
-        }
+        self.close_container(server);
         let Some(_window_type) = WindowType::from_u8(packet.window_id) else {
             self.kick(TextComponent::text("Invalid window ID"));
             return;
         };
+        // The carried item and those in the crafting grid go back into the inventory, what
+        // doesn't fit is dropped like in vanilla
+        let mut dropped = Vec::new();
+        {
+            let mut inventory = self.inventory.lock();
+            let mut carried_item = self.carried_item.take();
+            inventory.insert(&mut carried_item);
+            dropped.extend(carried_item);
+            inventory.clear_crafting_grid();
+            // The result is only made of the items in the grid
+            if let Ok(result) = inventory.get_slot(0) {
+                *result = None;
+            }
+            for slot in 1..=4 {
+                if let Ok(item) = inventory.get_slot(slot) {
+                    dropped.extend(item.take());
+                }
+            }
+        }
+        for item in dropped {
+            self.drop_item(item, false);
+        }
+        self.set_container_content(None);
     }
 }
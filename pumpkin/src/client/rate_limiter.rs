@@ -0,0 +1,65 @@
+use std::time::{Duration, Instant};
+
+use pumpkin_config::rate_limit::{PacketRateLimitConfig, RateLimit};
+use pumpkin_protocol::ConnectionState;
+
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// Counts the packets and bytes a connection sent in the current second
+pub struct RateLimiter {
+    window_start: Instant,
+    packets: u32,
+    bytes: u32,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self {
+            window_start: Instant::now(),
+            packets: 0,
+            bytes: 0,
+        }
+    }
+}
+
+impl RateLimiter {
+    /// Records received data, returns false when the limits of the current connection state were exceeded
+    pub fn record(
+        &mut self,
+        config: &PacketRateLimitConfig,
+        state: ConnectionState,
+        packets: u32,
+        bytes: usize,
+    ) -> bool {
+        let now = Instant::now();
+        if now.duration_since(self.window_start) >= WINDOW {
+            self.window_start = now;
+            self.packets = 0;
+            self.bytes = 0;
+        }
+        self.packets = self.packets.saturating_add(packets);
+        self.bytes = self
+            .bytes
+            .saturating_add(u32::try_from(bytes).unwrap_or(u32::MAX));
+
+        let limit = Self::limit_for(config, state);
+        let exceeds = |value: u32, limit: u32| limit != 0 && value > limit;
+        !exceeds(self.packets, limit.packets_per_second)
+            && !exceeds(self.bytes, limit.bytes_per_second)
+    }
+
+    /// The time the current window ends, used to throttle a connection
+    pub fn window_end(&self) -> Instant {
+        self.window_start + WINDOW
+    }
+
+    const fn limit_for(config: &PacketRateLimitConfig, state: ConnectionState) -> &RateLimit {
+        match state {
+            ConnectionState::HandShake => &config.handshake,
+            ConnectionState::Status => &config.status,
+            ConnectionState::Login | ConnectionState::Transfer => &config.login,
+            ConnectionState::Config => &config.config,
+            ConnectionState::Play => &config.play,
+        }
+    }
+}
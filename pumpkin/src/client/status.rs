@@ -0,0 +1,152 @@
+//! Answers server list pings without creating a full `Client`.
+//!
+//! Server list scanners and ping floods only ever reach the Status state, so we keep their
+//! connections as cheap as possible: no writer task, no packet queue and a hard limit on how
+//! long and how much they may send.
+
+use std::time::Duration;
+
+use pumpkin_config::ADVANCED_CONFIG;
+
+use pumpkin_protocol::{
+    bytebuf::packet_id::Packet,
+    client::status::{CPingResponse, CStatusResponse},
+    packet_decoder::PacketDecoder,
+    packet_encoder::PacketEncoder,
+    server::{
+        handshake::SHandShake,
+        status::{SStatusPingRequest, SStatusRequest},
+    },
+    ConnectionState, ServerPacket,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    time::Instant,
+};
+
+use crate::{metrics::METRICS, server::Server};
+
+use super::{
+    connection::{ConnectionReader, ConnectionWriter},
+    legacy_ping::LEGACY_PING,
+};
+
+/// A handshake is at most around 270 bytes, more means the client is sending something else
+const MAX_HANDSHAKE_SIZE: usize = 1024;
+/// Status connections may not send more than this in total
+const MAX_STATUS_SIZE: usize = 1024;
+/// Status connections which don't finish their ping in time get closed
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What a new connection sent first
+pub enum Handshake {
+    /// The connection wants the server list status, the decoder holds everything after the handshake
+    Status(PacketDecoder),
+    /// Anything else, these bytes have to be handed to a `Client`
+    Other(Vec<u8>),
+}
+
+/// Reads from a new connection until its handshake is complete.
+/// Returns None if the connection was closed or did not finish its handshake in time
+pub async fn read_handshake(reader: &mut ConnectionReader) -> Option<Handshake> {
+    let mut received = Vec::new();
+    let mut decoder = PacketDecoder::default();
+    let mut buf = [0; 512];
+    let deadline =
+        Instant::now() + Duration::from_secs(ADVANCED_CONFIG.get().connection_timeout.handshake);
+    loop {
+        let Ok(result) = tokio::time::timeout_at(deadline, reader.read(&mut buf)).await else {
+            METRICS.record_connection_reaped(ConnectionState::HandShake);
+            return None;
+        };
+        let n = result.ok()?;
+        if n == 0 {
+            return None;
+        }
+        received.extend_from_slice(&buf[..n]);
+        // The `Client` answers legacy pings
+        if received[0] == LEGACY_PING {
+            return Some(Handshake::Other(received));
+        }
+
+        decoder.queue_slice(&buf[..n]);
+        match decoder.decode() {
+            Ok(Some(mut packet)) => {
+                let is_status = packet.id.0 == SHandShake::PACKET_ID
+                    && SHandShake::read(&mut packet.bytebuf)
+                        .is_ok_and(|handshake| handshake.next_state == ConnectionState::Status);
+                return Some(if is_status {
+                    METRICS.record_packets_received(1);
+                    Handshake::Status(decoder)
+                } else {
+                    Handshake::Other(received)
+                });
+            }
+            Ok(None) if received.len() < MAX_HANDSHAKE_SIZE => {}
+            // Let the Client deal with everything that is not a valid handshake
+            _ => return Some(Handshake::Other(received)),
+        }
+    }
+}
+
+/// Answers the status request and ping of a connection in the Status state, then closes it
+pub async fn handle_status(
+    server: &Server,
+    mut decoder: PacketDecoder,
+    mut reader: ConnectionReader,
+    mut writer: ConnectionWriter,
+) {
+    let mut encoder = PacketEncoder::default();
+    let mut received = 0;
+    let mut buf = [0; 256];
+    loop {
+        let mut done = false;
+        loop {
+            let mut packet = match decoder.decode() {
+                Ok(Some(packet)) => packet,
+                Ok(None) => break,
+                Err(_) => return,
+            };
+            METRICS.record_packets_received(1);
+            let result = match packet.id.0 {
+                SStatusRequest::PACKET_ID => {
+                    encoder.append_packet(&CStatusResponse::new(&server.get_status_json()))
+                }
+                SStatusPingRequest::PACKET_ID => {
+                    let Ok(ping) = SStatusPingRequest::read(&mut packet.bytebuf) else {
+                        return;
+                    };
+                    done = true;
+                    encoder.append_packet(&CPingResponse::new(ping.payload))
+                }
+                _ => return,
+            };
+            if result.is_err() {
+                return;
+            }
+            METRICS.record_packet_sent();
+            if done {
+                break;
+            }
+        }
+
+        let response = encoder.take();
+        if !response.is_empty() && writer.write_all(&response).await.is_err() {
+            return;
+        }
+        if done {
+            let _ = writer.shutdown().await;
+            return;
+        }
+
+        let n = match tokio::time::timeout(TIMEOUT, reader.read(&mut buf)).await {
+            Ok(Ok(n)) if n > 0 => n,
+            _ => return,
+        };
+        received += n;
+        if received > MAX_STATUS_SIZE {
+            return;
+        }
+        decoder.queue_slice(&buf[..n]);
+    }
+}
@@ -0,0 +1,317 @@
+//! Lets multiple Pumpkin servers share chat, player counts and transfer targets.
+//!
+//! Every node listens for other nodes and connects to its configured peers. Messages are sent
+//! over the outgoing connections and received over the incoming ones, as newline delimited JSON.
+
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use parking_lot::RwLock;
+use pumpkin_config::{cluster::ClusterConfig, ADVANCED_CONFIG, BASIC_CONFIG};
+use pumpkin_core::text::TextComponent;
+use pumpkin_plugins::events::ClusterMessageEvent;
+use pumpkin_protocol::client::play::CSystemChatMessage;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::broadcast::{self, error::RecvError},
+};
+
+use crate::server::Server;
+
+/// How often every node sends its status
+const STATUS_INTERVAL: Duration = Duration::from_secs(5);
+/// Nodes which did not send their status for this long are considered offline
+const NODE_TIMEOUT: Duration = Duration::from_secs(15);
+/// How long to wait before connecting to a peer again
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+/// Lines longer than this are not valid messages
+const MAX_MESSAGE_SIZE: u64 = 64 * 1024;
+/// How many messages may be queued for a slow peer before old ones get dropped
+const QUEUE_SIZE: usize = 256;
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClusterMessage {
+    /// The first message on every connection
+    Hello {
+        node: String,
+        secret: String,
+    },
+    Status {
+        online: u32,
+        max: u32,
+        address: Option<String>,
+    },
+    Chat {
+        message: String,
+    },
+    /// Sent and received by plugins
+    Plugin {
+        channel: String,
+        payload: Vec<u8>,
+    },
+}
+
+/// What we know about another node
+#[derive(Clone)]
+pub struct NodeInfo {
+    pub online: u32,
+    pub max: u32,
+    /// The address players get transferred to, as `host:port`
+    pub address: Option<String>,
+    last_seen: Instant,
+}
+
+pub struct Cluster {
+    /// Serialized messages, every outgoing connection forwards them
+    outgoing: broadcast::Sender<Arc<str>>,
+    nodes: RwLock<HashMap<String, NodeInfo>>,
+}
+
+impl Default for Cluster {
+    fn default() -> Self {
+        Self {
+            outgoing: broadcast::channel(QUEUE_SIZE).0,
+            nodes: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl Cluster {
+    /// The other nodes which are currently online, sorted by name
+    pub fn nodes(&self) -> Vec<(String, NodeInfo)> {
+        let mut nodes: Vec<_> = self
+            .nodes
+            .read()
+            .iter()
+            .filter(|(_, node)| node.last_seen.elapsed() < NODE_TIMEOUT)
+            .map(|(name, node)| (name.clone(), node.clone()))
+            .collect();
+        nodes.sort_by(|(a, _), (b, _)| a.cmp(b));
+        nodes
+    }
+
+    /// The players on all other nodes
+    pub fn remote_player_count(&self) -> u32 {
+        self.nodes().iter().map(|(_, node)| node.online).sum()
+    }
+
+    /// The address of a node players can be transferred to
+    pub fn transfer_target(&self, node: &str) -> Option<(String, u16)> {
+        let (_, info) = self.nodes().into_iter().find(|(name, _)| name == node)?;
+        let address = info.address?;
+        let (host, port) = address.rsplit_once(':')?;
+        Some((host.to_string(), port.parse().ok()?))
+    }
+
+    /// Shows a chat message on all other nodes
+    pub fn send_chat(&self, message: &str) {
+        if ADVANCED_CONFIG.get().cluster.relay_chat {
+            self.send(&ClusterMessage::Chat {
+                message: message.to_string(),
+            });
+        }
+    }
+
+    /// Sends a plugin message to all other nodes, they fire a `ClusterMessageEvent`
+    pub fn send_plugin_message(&self, channel: &str, payload: &[u8]) {
+        self.send(&ClusterMessage::Plugin {
+            channel: channel.to_string(),
+            payload: payload.to_vec(),
+        });
+    }
+
+    fn send(&self, message: &ClusterMessage) {
+        if !ADVANCED_CONFIG.get().cluster.enabled {
+            return;
+        }
+        match serde_json::to_string(message) {
+            // Fails when no peer is connected, then there is no one to tell
+            Ok(json) => {
+                let _ = self.outgoing.send(json.into());
+            }
+            Err(err) => log::error!("Failed to serialize cluster message: {err}"),
+        }
+    }
+}
+
+/// Connects to the cluster and keeps the node status up to date until the Server shuts down
+pub async fn run(config: &ClusterConfig, server: &Arc<Server>) {
+    let listener = match TcpListener::bind(config.address).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            log::error!("Failed to start cluster on {}: {err}", config.address);
+            return;
+        }
+    };
+    log::info!(
+        "Joined cluster as {}, listening on {}",
+        config.node_name,
+        config.address
+    );
+
+    let mut tasks = Vec::new();
+    for peer in &config.peers {
+        let peer = peer.clone();
+        let server = server.clone();
+        tasks.push(tokio::spawn(async move {
+            connect_to_peer(&peer, &server).await;
+        }));
+    }
+
+    let mut status_interval = tokio::time::interval(STATUS_INTERVAL);
+    while server.is_running() {
+        tokio::select! {
+            connection = listener.accept() => {
+                let Ok((stream, address)) = connection else {
+                    continue;
+                };
+                let server = server.clone();
+                tasks.push(tokio::spawn(async move {
+                    if let Err(err) = handle_node(stream, &server).await {
+                        log::debug!("Cluster connection from {address} closed: {err}");
+                    }
+                }));
+                tasks.retain(|task| !task.is_finished());
+            }
+            _ = status_interval.tick() => {
+                server.cluster.send(&ClusterMessage::Status {
+                    online: server.get_player_count() as u32,
+                    max: BASIC_CONFIG.get().max_players,
+                    address: config.public_address.clone(),
+                });
+            }
+            () = server.wait_for_shutdown() => break,
+        }
+    }
+    for task in tasks {
+        task.abort();
+    }
+}
+
+/// Forwards all outgoing messages to a peer, reconnecting whenever the connection is lost
+async fn connect_to_peer(peer: &str, server: &Server) {
+    let config = &ADVANCED_CONFIG.get().cluster;
+    let hello = ClusterMessage::Hello {
+        node: config.node_name.clone(),
+        secret: config.secret.clone(),
+    };
+    let Ok(hello) = serde_json::to_string(&hello) else {
+        return;
+    };
+    loop {
+        // Subscribe before connecting, so nothing sent in between is lost
+        let mut outgoing = server.cluster.outgoing.subscribe();
+        match TcpStream::connect(peer).await {
+            Ok(mut stream) => {
+                log::info!("Connected to cluster node {peer}");
+                let result: std::io::Result<()> = async {
+                    write_line(&mut stream, &hello).await?;
+                    loop {
+                        match outgoing.recv().await {
+                            Ok(message) => write_line(&mut stream, &message).await?,
+                            Err(RecvError::Lagged(skipped)) => {
+                                log::warn!("Dropped {skipped} messages for cluster node {peer}");
+                            }
+                            Err(RecvError::Closed) => return Ok(()),
+                        }
+                    }
+                }
+                .await;
+                if let Err(err) = result {
+                    log::warn!("Lost connection to cluster node {peer}: {err}");
+                }
+            }
+            Err(err) => log::debug!("Failed to connect to cluster node {peer}: {err}"),
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn write_line(stream: &mut TcpStream, line: &str) -> std::io::Result<()> {
+    stream.write_all(line.as_bytes()).await?;
+    stream.write_all(b"\n").await
+}
+
+/// Receives the messages of another node
+async fn handle_node(stream: TcpStream, server: &Server) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    let mut node = None;
+    loop {
+        line.clear();
+        let n = (&mut reader)
+            .take(MAX_MESSAGE_SIZE)
+            .read_line(&mut line)
+            .await?;
+        if n == 0 {
+            return Ok(());
+        }
+        if !line.ends_with('\n') {
+            return Err(std::io::Error::other("message too long"));
+        }
+        let message: ClusterMessage = serde_json::from_str(&line).map_err(std::io::Error::other)?;
+
+        let Some(node) = &node else {
+            let ClusterMessage::Hello { node: name, secret } = message else {
+                return Err(std::io::Error::other("expected hello"));
+            };
+            if secret != ADVANCED_CONFIG.get().cluster.secret {
+                log::warn!("Rejected cluster node {name}, its secret is wrong");
+                return Ok(());
+            }
+            log::info!("Cluster node {name} connected");
+            node = Some(name);
+            continue;
+        };
+        handle_message(server, node, message);
+    }
+}
+
+fn handle_message(server: &Server, node: &str, message: ClusterMessage) {
+    match message {
+        ClusterMessage::Hello { .. } => {}
+        ClusterMessage::Status {
+            online,
+            max,
+            address,
+        } => {
+            let previous = server.cluster.nodes.write().insert(
+                node.to_string(),
+                NodeInfo {
+                    online,
+                    max,
+                    address,
+                    last_seen: Instant::now(),
+                },
+            );
+            if previous.map_or(true, |previous| previous.online != online) {
+                server.refresh_status();
+            }
+        }
+        ClusterMessage::Chat { message } => {
+            if ADVANCED_CONFIG.get().cluster.relay_chat {
+                server.broadcast_packet_all(&CSystemChatMessage::new(
+                    TextComponent::text(&format!("[{node}] {message}")),
+                    false,
+                ));
+            }
+        }
+        ClusterMessage::Plugin { channel, payload } => {
+            let event = ClusterMessageEvent {
+                node,
+                channel: &channel,
+                payload: &payload,
+            };
+            server
+                .plugins
+                .read()
+                .for_each(|plugin| plugin.on_cluster_message(&event));
+        }
+    }
+}
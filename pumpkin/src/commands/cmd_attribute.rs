@@ -0,0 +1,108 @@
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::commands::cmd_effect::{consume_arg_target, parse_target, ARG_TARGET};
+use crate::commands::cmd_scoreboard::get_arg;
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, literal, require};
+use crate::commands::CommandSender;
+use crate::entity::attributes::EntityAttribute;
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["attribute"];
+const DESCRIPTION: &str = "Queries or changes the attributes of a player.";
+
+const ARG_ATTRIBUTE: &str = "attribute";
+const ARG_VALUE: &str = "value";
+
+pub fn consume_arg_attribute(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    EntityAttribute::from_name(s).map(|_| s.into())
+}
+
+pub fn consume_arg_value(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    s.parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .map(|_| s.into())
+}
+
+fn parse_attribute(args: &ConsumedArgs) -> Result<EntityAttribute, InvalidTreeError> {
+    let arg = get_arg(args, ARG_ATTRIBUTE)?;
+    EntityAttribute::from_name(arg).ok_or(InvalidConsumptionError(Some(arg.into())))
+}
+
+fn get_value(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let target = parse_target(sender, server, args)?;
+    let attribute = parse_attribute(args)?;
+    let value = target.living_entity.attribute(attribute);
+    sender.send_message(TextComponent::text(&format!(
+        "Value of attribute {} for {} is {value}",
+        attribute.name(),
+        target.gameprofile.name
+    )));
+    Ok(())
+}
+
+fn get_base(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let target = parse_target(sender, server, args)?;
+    let attribute = parse_attribute(args)?;
+    let base = target.living_entity.attributes.lock().base(attribute);
+    sender.send_message(TextComponent::text(&format!(
+        "Base value of attribute {} for {} is {base}",
+        attribute.name(),
+        target.gameprofile.name
+    )));
+    Ok(())
+}
+
+fn set_base(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let target = parse_target(sender, server, args)?;
+    let attribute = parse_attribute(args)?;
+    let arg = get_arg(args, ARG_VALUE)?;
+    let base = arg
+        .parse()
+        .map_err(|_| InvalidConsumptionError(Some(arg.into())))?;
+    target.set_attribute_base(attribute, base);
+    sender.send_message(
+        TextComponent::text(&format!(
+            "Base value for attribute {} for {} set to {base}",
+            attribute.name(),
+            target.gameprofile.name
+        ))
+        .color_named(NamedColor::Green),
+    );
+    Ok(())
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2).with_child(
+            argument(ARG_TARGET, consume_arg_target).with_child(
+                argument(ARG_ATTRIBUTE, consume_arg_attribute)
+                    .with_child(literal("get").execute(&get_value))
+                    .with_child(
+                        literal("base")
+                            .with_child(literal("get").execute(&get_base))
+                            .with_child(literal("set").with_child(
+                                argument(ARG_VALUE, consume_arg_value).execute(&set_base),
+                            )),
+                    ),
+            ),
+        ),
+    )
+}
@@ -0,0 +1,117 @@
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::server::backup;
+
+const NAMES: [&str; 1] = ["backup"];
+const DESCRIPTION: &str = "Writes, lists and restores backups of the worlds.";
+
+const ARG_NOW: &str = "now";
+const ARG_LIST: &str = "list";
+const ARG_RESTORE: &str = "restore";
+const ARG_NAME: &str = "name";
+
+pub fn consume_arg_now(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().filter(|arg| *arg == ARG_NOW).map(Into::into)
+}
+
+pub fn consume_arg_list(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().filter(|arg| *arg == ARG_LIST).map(Into::into)
+}
+
+pub fn consume_arg_restore(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().filter(|arg| *arg == ARG_RESTORE).map(Into::into)
+}
+
+pub fn consume_arg_name(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().map(Into::into)
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 4)
+            .with_child(
+                argument(ARG_NOW, consume_arg_now).execute(&|sender, server, _args| {
+                    // The archive is written in the background, the player is told once it is done
+                    let player = match sender {
+                        CommandSender::Player(player) => {
+                            server.get_player_by_name(&player.gameprofile.name)
+                        }
+                        _ => None,
+                    };
+                    let started = server.backups.start(server, move |result| {
+                        let Some(player) = player else {
+                            return;
+                        };
+                        let (message, color) = match result {
+                            Ok(backup) => {
+                                (format!("Wrote backup {}", backup.name), NamedColor::Green)
+                            }
+                            Err(err) => {
+                                (format!("Failed to write a backup: {err}"), NamedColor::Red)
+                            }
+                        };
+                        player
+                            .send_system_message(TextComponent::text(&message).color_named(color));
+                    });
+                    match started {
+                        Ok(()) => sender.send_message(TextComponent::text("Writing a backup")),
+                        Err(err) => sender.send_message(
+                            TextComponent::text(&err.to_string()).color_named(NamedColor::Red),
+                        ),
+                    }
+                    Ok(())
+                }),
+            )
+            .with_child(
+                argument(ARG_LIST, consume_arg_list).execute(&|sender, _server, _args| {
+                    let backups = match backup::list() {
+                        Ok(backups) => backups,
+                        Err(err) => {
+                            sender.send_message(
+                                TextComponent::text(&format!("Failed to list the backups: {err}"))
+                                    .color_named(NamedColor::Red),
+                            );
+                            return Ok(());
+                        }
+                    };
+                    if backups.is_empty() {
+                        sender.send_message(TextComponent::text("There are no backups"));
+                        return Ok(());
+                    }
+                    sender.send_message(TextComponent::text(&format!(
+                        "There are {} backups:",
+                        backups.len()
+                    )));
+                    for backup in backups {
+                        sender.send_message(TextComponent::text(&format!(
+                            "{} ({}MB)",
+                            backup.name,
+                            backup.size / (1024 * 1024)
+                        )));
+                    }
+                    Ok(())
+                }),
+            )
+            .with_child(argument(ARG_RESTORE, consume_arg_restore).with_child(
+                argument(ARG_NAME, consume_arg_name).execute(&|sender, server, args| {
+                    let name = args.get(ARG_NAME).ok_or(InvalidConsumptionError(None))?;
+                    match server.backups.restore(server, name) {
+                        Ok(()) => sender.send_message(
+                            TextComponent::text(&format!(
+                                "Stopping the server to restore backup {name}"
+                            ))
+                            .color_named(NamedColor::Green),
+                        ),
+                        Err(err) => sender.send_message(
+                            TextComponent::text(&err.to_string()).color_named(NamedColor::Red),
+                        ),
+                    }
+                    Ok(())
+                }),
+            )),
+    )
+}
@@ -0,0 +1,218 @@
+use std::sync::Arc;
+
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+use pumpkin_entity::effect::StatusEffect;
+
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::entity::effect::ActiveEffect;
+use crate::entity::player::Player;
+use crate::server::ticker::TICKS_PER_SECOND;
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["effect"];
+const DESCRIPTION: &str = "Gives or clears status effects.";
+
+const ARG_GIVE: &str = "give";
+const ARG_CLEAR: &str = "clear";
+pub(super) const ARG_TARGET: &str = "target";
+const ARG_EFFECT: &str = "effect";
+const ARG_SECONDS: &str = "seconds";
+const ARG_AMPLIFIER: &str = "amplifier";
+const ARG_HIDE_PARTICLES: &str = "hideParticles";
+
+/// How long effects last if no duration is given, like in vanilla
+const DEFAULT_SECONDS: i32 = 30;
+/// Effects can't last longer than this many seconds, unless they are infinite
+const MAX_SECONDS: i32 = 1_000_000;
+
+pub fn consume_arg_give(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().filter(|arg| *arg == ARG_GIVE).map(Into::into)
+}
+
+pub fn consume_arg_clear(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().filter(|arg| *arg == ARG_CLEAR).map(Into::into)
+}
+
+pub fn consume_arg_target(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().map(Into::into)
+}
+
+pub fn consume_arg_effect(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    StatusEffect::from_name(s).map(|_| s.into())
+}
+
+/// A duration in seconds or `infinite`
+pub fn consume_arg_seconds(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    parse_duration(s).map(|_| s.into())
+}
+
+pub fn consume_arg_amplifier(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    s.parse::<u8>().ok().map(|_| s.into())
+}
+
+pub fn consume_arg_bool(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    s.parse::<bool>().ok().map(|_| s.into())
+}
+
+/// The duration in ticks, -1 for `infinite`
+fn parse_duration(arg: &str) -> Option<i32> {
+    if arg == "infinite" {
+        return Some(-1);
+    }
+    let seconds = arg.parse::<i32>().ok()?;
+    (1..=MAX_SECONDS)
+        .contains(&seconds)
+        .then_some(seconds * TICKS_PER_SECOND as i32)
+}
+
+/// The player named by the target argument, `@s` is the player running the command
+pub(super) fn parse_target(
+    sender: &CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<Arc<Player>, InvalidTreeError> {
+    let Some(arg) = args.get(ARG_TARGET) else {
+        // Players clear their own effects if no target is given
+        return match sender {
+            CommandSender::Player(player) => server
+                .get_player_by_name(&player.gameprofile.name)
+                .ok_or(InvalidConsumptionError(None)),
+            _ => Err(InvalidConsumptionError(None)),
+        };
+    };
+    let name = match (arg.as_str(), sender) {
+        ("@s" | "@p", CommandSender::Player(player)) => player.gameprofile.name.as_str(),
+        _ => arg.as_str(),
+    };
+    server
+        .get_player_by_name(name)
+        .ok_or(InvalidConsumptionError(Some(arg.clone())))
+}
+
+fn parse_effect(args: &ConsumedArgs) -> Result<Option<StatusEffect>, InvalidTreeError> {
+    match args.get(ARG_EFFECT) {
+        Some(arg) => StatusEffect::from_name(arg)
+            .map(Some)
+            .ok_or(InvalidConsumptionError(Some(arg.clone()))),
+        None => Ok(None),
+    }
+}
+
+fn give_effect(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let target = parse_target(sender, server, args)?;
+    let effect = parse_effect(args)?.ok_or(InvalidConsumptionError(None))?;
+    let duration = match args.get(ARG_SECONDS) {
+        Some(arg) => parse_duration(arg).ok_or(InvalidConsumptionError(Some(arg.clone())))?,
+        None => DEFAULT_SECONDS * TICKS_PER_SECOND as i32,
+    };
+    let amplifier = match args.get(ARG_AMPLIFIER) {
+        Some(arg) => arg
+            .parse()
+            .map_err(|_| InvalidConsumptionError(Some(arg.clone())))?,
+        None => 0,
+    };
+    let hide_particles = match args.get(ARG_HIDE_PARTICLES) {
+        Some(arg) => arg
+            .parse()
+            .map_err(|_| InvalidConsumptionError(Some(arg.clone())))?,
+        None => false,
+    };
+    let mut active = ActiveEffect::new(effect, amplifier, duration);
+    active.show_particles = !hide_particles;
+    if !target.add_effect(active) {
+        sender.send_message(
+            TextComponent::text(&format!(
+                "{} already has a stronger {} effect",
+                target.gameprofile.name,
+                effect.name()
+            ))
+            .color_named(NamedColor::Red),
+        );
+        return Ok(());
+    }
+    sender.send_message(
+        TextComponent::text(&format!(
+            "Applied effect {} to {}",
+            effect.name(),
+            target.gameprofile.name
+        ))
+        .color_named(NamedColor::Green),
+    );
+    Ok(())
+}
+
+fn clear_effects(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let target = parse_target(sender, server, args)?;
+    let message = match parse_effect(args)? {
+        Some(effect) if target.remove_effect(effect) => format!(
+            "Removed effect {} from {}",
+            effect.name(),
+            target.gameprofile.name
+        ),
+        Some(effect) => format!(
+            "{} doesn't have the {} effect",
+            target.gameprofile.name,
+            effect.name()
+        ),
+        None => format!(
+            "Removed {} effects from {}",
+            target.clear_effects(),
+            target.gameprofile.name
+        ),
+    };
+    sender.send_message(TextComponent::text(&message).color_named(NamedColor::Gold));
+    Ok(())
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2)
+            .with_child(
+                argument(ARG_GIVE, consume_arg_give).with_child(
+                    argument(ARG_TARGET, consume_arg_target).with_child(
+                        argument(ARG_EFFECT, consume_arg_effect)
+                            .execute(&give_effect)
+                            .with_child(
+                                argument(ARG_SECONDS, consume_arg_seconds)
+                                    .execute(&give_effect)
+                                    .with_child(
+                                        argument(ARG_AMPLIFIER, consume_arg_amplifier)
+                                            .execute(&give_effect)
+                                            .with_child(
+                                                argument(ARG_HIDE_PARTICLES, consume_arg_bool)
+                                                    .execute(&give_effect),
+                                            ),
+                                    ),
+                            ),
+                    ),
+                ),
+            )
+            .with_child(
+                argument(ARG_CLEAR, consume_arg_clear)
+                    .with_child(require(&|sender| sender.is_player()).execute(&clear_effects))
+                    .with_child(
+                        argument(ARG_TARGET, consume_arg_target)
+                            .execute(&clear_effects)
+                            .with_child(
+                                argument(ARG_EFFECT, consume_arg_effect).execute(&clear_effects),
+                            ),
+                    ),
+            ),
+    )
+}
@@ -0,0 +1,218 @@
+use std::sync::Arc;
+
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::entity::experience::experience_to_next_level;
+use crate::entity::player::Player;
+use crate::server::Server;
+
+const NAMES: [&str; 2] = ["experience", "xp"];
+const DESCRIPTION: &str = "Adds, sets or shows the experience of a player.";
+
+const ARG_ADD: &str = "add";
+const ARG_SET: &str = "set";
+const ARG_QUERY: &str = "query";
+const ARG_TARGET: &str = "target";
+const ARG_AMOUNT: &str = "amount";
+const ARG_UNIT: &str = "unit";
+
+/// Whether an amount counts experience points or whole levels
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Points,
+    Levels,
+}
+
+fn parse_unit(arg: &str) -> Option<Unit> {
+    match arg {
+        "points" => Some(Unit::Points),
+        "levels" => Some(Unit::Levels),
+        _ => None,
+    }
+}
+
+pub fn consume_arg_add(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().filter(|arg| *arg == ARG_ADD).map(Into::into)
+}
+
+pub fn consume_arg_set(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().filter(|arg| *arg == ARG_SET).map(Into::into)
+}
+
+pub fn consume_arg_query(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().filter(|arg| *arg == ARG_QUERY).map(Into::into)
+}
+
+pub fn consume_arg_target(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().map(Into::into)
+}
+
+pub fn consume_arg_amount(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    s.parse::<i32>().ok().map(|_| s.into())
+}
+
+pub fn consume_arg_unit(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    parse_unit(s).map(|_| s.into())
+}
+
+/// The player named by the target argument, `@s` is the player running the command
+fn parse_target(
+    sender: &CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<Arc<Player>, InvalidTreeError> {
+    let arg = args.get(ARG_TARGET).ok_or(InvalidConsumptionError(None))?;
+    let name = match (arg.as_str(), sender) {
+        ("@s" | "@p", CommandSender::Player(player)) => player.gameprofile.name.as_str(),
+        _ => arg.as_str(),
+    };
+    server
+        .get_player_by_name(name)
+        .ok_or(InvalidConsumptionError(Some(arg.clone())))
+}
+
+fn parse_amount(args: &ConsumedArgs) -> Result<i32, InvalidTreeError> {
+    let arg = args.get(ARG_AMOUNT).ok_or(InvalidConsumptionError(None))?;
+    arg.parse()
+        .map_err(|_| InvalidConsumptionError(Some(arg.clone())))
+}
+
+/// Points if no unit is given, like in vanilla
+fn parse_unit_arg(args: &ConsumedArgs) -> Result<Unit, InvalidTreeError> {
+    match args.get(ARG_UNIT) {
+        Some(arg) => parse_unit(arg).ok_or(InvalidConsumptionError(Some(arg.clone()))),
+        None => Ok(Unit::Points),
+    }
+}
+
+fn add_experience(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let target = parse_target(sender, server, args)?;
+    let amount = parse_amount(args)?;
+    let unit = parse_unit_arg(args)?;
+    match unit {
+        Unit::Points => target.add_experience_points(amount),
+        Unit::Levels => target.add_experience_levels(amount),
+    }
+    let unit = if unit == Unit::Points {
+        "experience points"
+    } else {
+        "experience levels"
+    };
+    sender.send_message(
+        TextComponent::text(&format!(
+            "Gave {amount} {unit} to {}",
+            target.gameprofile.name
+        ))
+        .color_named(NamedColor::Green),
+    );
+    Ok(())
+}
+
+fn set_experience(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let target = parse_target(sender, server, args)?;
+    let amount = parse_amount(args)?;
+    if amount < 0 {
+        return Err(InvalidConsumptionError(Some(amount.to_string())));
+    }
+    let experience = target.experience.load();
+    let unit = match parse_unit_arg(args)? {
+        Unit::Points => {
+            // Like in vanilla, only the progress in the current level can be set
+            if amount >= experience_to_next_level(experience.level) {
+                sender.send_message(
+                    TextComponent::text(
+                        "Can't set experience points above the maximum points for the player's current level",
+                    )
+                    .color_named(NamedColor::Red),
+                );
+                return Ok(());
+            }
+            target.set_experience(experience.add_points(amount - experience.points()));
+            "experience points"
+        }
+        Unit::Levels => {
+            target.set_experience(experience.add_levels(amount - experience.level));
+            "experience levels"
+        }
+    };
+    sender.send_message(
+        TextComponent::text(&format!(
+            "Set {amount} {unit} on {}",
+            target.gameprofile.name
+        ))
+        .color_named(NamedColor::Green),
+    );
+    Ok(())
+}
+
+fn query_experience(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let target = parse_target(sender, server, args)?;
+    let experience = target.experience.load();
+    let message = match parse_unit_arg(args)? {
+        Unit::Points => format!(
+            "{} has {} experience points",
+            target.gameprofile.name,
+            experience.points()
+        ),
+        Unit::Levels => format!(
+            "{} has {} experience levels",
+            target.gameprofile.name, experience.level
+        ),
+    };
+    sender.send_message(TextComponent::text(&message).color_named(NamedColor::Gold));
+    Ok(())
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2)
+            .with_child(
+                argument(ARG_ADD, consume_arg_add).with_child(
+                    argument(ARG_TARGET, consume_arg_target).with_child(
+                        argument(ARG_AMOUNT, consume_arg_amount)
+                            .execute(&add_experience)
+                            .with_child(
+                                argument(ARG_UNIT, consume_arg_unit).execute(&add_experience),
+                            ),
+                    ),
+                ),
+            )
+            .with_child(
+                argument(ARG_SET, consume_arg_set).with_child(
+                    argument(ARG_TARGET, consume_arg_target).with_child(
+                        argument(ARG_AMOUNT, consume_arg_amount)
+                            .execute(&set_experience)
+                            .with_child(
+                                argument(ARG_UNIT, consume_arg_unit).execute(&set_experience),
+                            ),
+                    ),
+                ),
+            )
+            .with_child(
+                argument(ARG_QUERY, consume_arg_query).with_child(
+                    argument(ARG_TARGET, consume_arg_target).with_child(
+                        argument(ARG_UNIT, consume_arg_unit).execute(&query_experience),
+                    ),
+                ),
+            ),
+    )
+}
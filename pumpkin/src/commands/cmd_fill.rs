@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+use pumpkin_world::block::BlockId;
+use pumpkin_world::schematic::parse_block_state;
+
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::server::Server;
+use crate::world::bulk_edit::Region;
+use crate::world::World;
+
+const NAMES: [&str; 1] = ["fill"];
+const DESCRIPTION: &str = "Fills a region with a block.";
+
+const ARG_FROM: [&str; 3] = ["x1", "y1", "z1"];
+const ARG_TO: [&str; 3] = ["x2", "y2", "z2"];
+const ARG_BLOCK: &str = "block";
+const ARG_REPLACE: &str = "replace";
+const ARG_FILTER: &str = "filter";
+
+/// At most this many blocks can be filled at once, like in vanilla
+const MAX_VOLUME: u64 = 32768;
+
+pub fn consume_arg_coordinate(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    s.parse::<i32>().ok().map(|_| s.into())
+}
+
+pub fn consume_arg_block(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    parse_block(s).map(|_| s.into())
+}
+
+pub fn consume_arg_replace(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().filter(|arg| *arg == ARG_REPLACE).map(Into::into)
+}
+
+/// A block state like `stone` or `minecraft:oak_stairs[facing=east]`
+fn parse_block(block: &str) -> Option<BlockId> {
+    if block.contains(':') {
+        parse_block_state(block)
+    } else {
+        parse_block_state(&format!("minecraft:{block}"))
+    }
+}
+
+fn parse_position(
+    args: &ConsumedArgs,
+    names: [&str; 3],
+) -> Result<WorldPosition, InvalidTreeError> {
+    let mut coordinates = [0; 3];
+    for (coordinate, name) in coordinates.iter_mut().zip(names) {
+        let arg = args.get(name).ok_or(InvalidConsumptionError(None))?;
+        *coordinate = arg
+            .parse::<i32>()
+            .map_err(|_| InvalidConsumptionError(Some(arg.clone())))?;
+    }
+    let [x, y, z] = coordinates;
+    Ok(WorldPosition(Vector3::new(x, y, z)))
+}
+
+fn parse_block_arg(args: &ConsumedArgs, name: &str) -> Result<BlockId, InvalidTreeError> {
+    let arg = args.get(name).ok_or(InvalidConsumptionError(None))?;
+    parse_block(arg).ok_or_else(|| InvalidConsumptionError(Some(arg.clone())))
+}
+
+/// Players change the world they are in, everyone else the main world
+fn target_world(sender: &CommandSender, server: &Server) -> Arc<World> {
+    match sender {
+        CommandSender::Player(player) => player.living_entity.entity.world(),
+        _ => server.default_world().clone(),
+    }
+}
+
+fn fill(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let region = Region::new(
+        &parse_position(args, ARG_FROM)?,
+        &parse_position(args, ARG_TO)?,
+    );
+    let block = parse_block_arg(args, ARG_BLOCK)?;
+    let filter = match args.get(ARG_FILTER) {
+        Some(_) => Some(parse_block_arg(args, ARG_FILTER)?),
+        None => None,
+    };
+    if region.volume() > MAX_VOLUME {
+        sender.send_message(
+            TextComponent::text(&format!(
+                "Too many blocks in the specified area (maximum {MAX_VOLUME}, specified {})",
+                region.volume()
+            ))
+            .color_named(NamedColor::Red),
+        );
+        return Ok(());
+    }
+    let world = target_world(sender, server);
+    // Like vanilla, chunks are not loaded just for the command
+    if !region
+        .chunks()
+        .all(|chunk| world.level.loaded_chunk(chunk).is_some())
+    {
+        sender.send_message(
+            TextComponent::text("That position is not loaded").color_named(NamedColor::Red),
+        );
+        return Ok(());
+    }
+    let changed = match filter {
+        Some(filter) => world.replace(&region, filter, block),
+        None => world.fill(&region, block),
+    };
+    if changed == 0 {
+        sender.send_message(
+            TextComponent::text("No blocks were filled").color_named(NamedColor::Red),
+        );
+    } else {
+        sender.send_message(TextComponent::text(&format!(
+            "Successfully filled {changed} block(s)"
+        )));
+    }
+    Ok(())
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2).with_child(
+            argument(ARG_FROM[0], consume_arg_coordinate).with_child(
+                argument(ARG_FROM[1], consume_arg_coordinate).with_child(
+                    argument(ARG_FROM[2], consume_arg_coordinate).with_child(
+                        argument(ARG_TO[0], consume_arg_coordinate).with_child(
+                            argument(ARG_TO[1], consume_arg_coordinate).with_child(
+                                argument(ARG_TO[2], consume_arg_coordinate).with_child(
+                                    argument(ARG_BLOCK, consume_arg_block)
+                                        .execute(&fill)
+                                        .with_child(
+                                            argument(ARG_REPLACE, consume_arg_replace).with_child(
+                                                argument(ARG_FILTER, consume_arg_block)
+                                                    .execute(&fill),
+                                            ),
+                                        ),
+                                ),
+                            ),
+                        ),
+                    ),
+                ),
+            ),
+        ),
+    )
+}
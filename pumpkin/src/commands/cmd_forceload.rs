@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use pumpkin_core::math::vector2::Vector2;
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::server::Server;
+use crate::world::World;
+
+const NAMES: [&str; 1] = ["forceload"];
+const DESCRIPTION: &str = "Keeps chunks loaded even when no player is near them.";
+
+const ARG_ADD: &str = "add";
+const ARG_REMOVE: &str = "remove";
+const ARG_QUERY: &str = "query";
+const ARG_X: &str = "x";
+const ARG_Z: &str = "z";
+
+pub fn consume_arg_add(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().filter(|arg| *arg == ARG_ADD).map(Into::into)
+}
+
+pub fn consume_arg_remove(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().filter(|arg| *arg == ARG_REMOVE).map(Into::into)
+}
+
+pub fn consume_arg_query(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().filter(|arg| *arg == ARG_QUERY).map(Into::into)
+}
+
+pub fn consume_arg_coordinate(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    s.parse::<i32>().ok().map(|_| s.into())
+}
+
+/// The chunk containing the block at the given x and z coordinates
+fn parse_chunk(args: &ConsumedArgs) -> Result<Vector2<i32>, InvalidTreeError> {
+    let parse = |name| {
+        let arg = args.get(name).ok_or(InvalidConsumptionError(None))?;
+        arg.parse::<i32>()
+            .map_err(|_| InvalidConsumptionError(Some(arg.clone())))
+    };
+    Ok(Vector2::new(parse(ARG_X)? >> 4, parse(ARG_Z)? >> 4))
+}
+
+/// Players change the chunks of the world they are in, everyone else those of the main world
+fn target_world(sender: &CommandSender, server: &Server) -> Arc<World> {
+    match sender {
+        CommandSender::Player(player) => player.living_entity.entity.world(),
+        _ => server.default_world().clone(),
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2)
+            .with_child(argument(ARG_ADD, consume_arg_add).with_child(
+                argument(ARG_X, consume_arg_coordinate).with_child(
+                    argument(ARG_Z, consume_arg_coordinate).execute(&|sender, server, args| {
+                        let at = parse_chunk(args)?;
+                        let world = target_world(sender, server);
+                        if !world.level.force_chunk(at) {
+                            sender.send_message(
+                                TextComponent::text(&format!(
+                                    "Chunk {}, {} is already force loaded",
+                                    at.x, at.z
+                                ))
+                                .color_named(NamedColor::Red),
+                            );
+                            return Ok(());
+                        }
+                        // The chunk may have to be generated, which blocks
+                        let level = world.level.clone();
+                        tokio::task::spawn_blocking(move || level.load_chunk(at));
+                        sender.send_message(
+                            TextComponent::text(&format!(
+                                "Chunk {}, {} in {} is now force loaded",
+                                at.x, at.z, world.name
+                            ))
+                            .color_named(NamedColor::Green),
+                        );
+                        Ok(())
+                    }),
+                ),
+            ))
+            .with_child(argument(ARG_REMOVE, consume_arg_remove).with_child(
+                argument(ARG_X, consume_arg_coordinate).with_child(
+                    argument(ARG_Z, consume_arg_coordinate).execute(&|sender, server, args| {
+                        let at = parse_chunk(args)?;
+                        let world = target_world(sender, server);
+                        if !world.level.unforce_chunk(at) {
+                            sender.send_message(
+                                TextComponent::text(&format!(
+                                    "Chunk {}, {} is not force loaded",
+                                    at.x, at.z
+                                ))
+                                .color_named(NamedColor::Red),
+                            );
+                            return Ok(());
+                        }
+                        sender.send_message(
+                            TextComponent::text(&format!(
+                                "Chunk {}, {} in {} is no longer force loaded",
+                                at.x, at.z, world.name
+                            ))
+                            .color_named(NamedColor::Green),
+                        );
+                        Ok(())
+                    }),
+                ),
+            ))
+            .with_child(argument(ARG_QUERY, consume_arg_query).execute(
+                &|sender, server, _args| {
+                    let world = target_world(sender, server);
+                    let chunks = world.level.forced_chunks();
+                    if chunks.is_empty() {
+                        sender.send_message(TextComponent::text(&format!(
+                            "No chunks are force loaded in {}",
+                            world.name
+                        )));
+                        return Ok(());
+                    }
+                    let list = chunks
+                        .iter()
+                        .map(|at| format!("[{}, {}]", at.x, at.z))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    sender.send_message(
+                        TextComponent::text(&format!(
+                            "{} chunks are force loaded in {}: {list}",
+                            chunks.len(),
+                            world.name
+                        ))
+                        .color_named(NamedColor::Gold),
+                    );
+                    Ok(())
+                },
+            )),
+    )
+}
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+use pumpkin_world::game_rules::GameRules;
+
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::server::Server;
+use crate::world::World;
+
+const NAMES: [&str; 1] = ["gamerule"];
+const DESCRIPTION: &str = "Sets or queries a game rule value.";
+
+const ARG_RULE: &str = "rule";
+const ARG_VALUE: &str = "value";
+
+pub fn consume_arg_rule(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    GameRules::names().any(|name| name == s).then(|| s.into())
+}
+
+/// The value is checked against the type of the rule once the command runs
+pub fn consume_arg_value(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().map(Into::into)
+}
+
+/// Players use the game rules of the world they are in, everyone else those of the main world
+fn target_world(sender: &CommandSender, server: &Server) -> Arc<World> {
+    match sender {
+        CommandSender::Player(player) => player.living_entity.entity.world(),
+        _ => server.default_world().clone(),
+    }
+}
+
+fn query_rule(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let rule = args.get(ARG_RULE).ok_or(InvalidConsumptionError(None))?;
+    let world = target_world(sender, server);
+    let value = world
+        .game_rule(rule)
+        .ok_or(InvalidConsumptionError(Some(rule.clone())))?;
+    sender.send_message(TextComponent::text(&format!(
+        "Gamerule {rule} is currently set to: {value}"
+    )));
+    Ok(())
+}
+
+fn set_rule(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let rule = args.get(ARG_RULE).ok_or(InvalidConsumptionError(None))?;
+    let value = args.get(ARG_VALUE).ok_or(InvalidConsumptionError(None))?;
+    let world = target_world(sender, server);
+    match world.set_game_rule(server, rule, value) {
+        Ok(value) => sender.send_message(
+            TextComponent::text(&format!("Gamerule {rule} is now set to: {value}"))
+                .color_named(NamedColor::Gold),
+        ),
+        Err(err) => {
+            sender.send_message(TextComponent::text(&err.to_string()).color_named(NamedColor::Red))
+        }
+    }
+    Ok(())
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2).with_child(
+            argument(ARG_RULE, consume_arg_rule)
+                .execute(&query_rule)
+                .with_child(argument(ARG_VALUE, consume_arg_value).execute(&set_rule)),
+        ),
+    )
+}
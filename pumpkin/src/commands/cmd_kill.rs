@@ -4,6 +4,7 @@ use crate::commands::tree::RawArgs;
 use crate::commands::tree_builder::argument;
 use crate::commands::CommandSender;
 use pumpkin_core::text::{color::NamedColor, TextComponent};
+use std::sync::atomic::Ordering;
 
 const NAMES: [&str; 1] = ["kill"];
 const DESCRIPTION: &str = "Kills a target player.";
@@ -18,6 +19,12 @@ pub fn init_command_tree<'a>() -> CommandTree<'a> {
     CommandTree::new(NAMES, DESCRIPTION).with_child(
         argument(ARG_TARGET, consume_arg_target).execute(&|sender, server, args| {
             let target = parse_arg_player(sender, server, ARG_TARGET, args)?;
+            // The client shows the death screen once its health reaches 0
+            target.set_health(
+                0.0,
+                target.food.load(Ordering::Relaxed),
+                target.food_saturation.load(),
+            );
             target.living_entity.kill();
 
             sender.send_message(
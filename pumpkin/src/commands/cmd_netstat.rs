@@ -0,0 +1,74 @@
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::client::net_stats::{format_bytes, NetStatsSnapshot};
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+
+const NAMES: [&str; 1] = ["netstat"];
+const DESCRIPTION: &str = "Shows the network traffic of players.";
+
+const ARG_NAME: &str = "name";
+
+/// How many players are listed when no name is given
+const MAX_LISTED: usize = 10;
+
+pub fn consume_arg_name(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().map(Into::into)
+}
+
+fn format_stats(name: &str, stats: &NetStatsSnapshot) -> String {
+    format!(
+        "{name}: in {} ({} packets), out {} ({} packets)",
+        format_bytes(stats.bytes_received),
+        stats.packets_received,
+        format_bytes(stats.bytes_sent),
+        stats.packets_sent
+    )
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 3)
+            .execute(&|sender, server, _args| {
+                let mut stats: Vec<_> = server
+                    .get_all_players()
+                    .iter()
+                    .map(|player| {
+                        (
+                            player.gameprofile.name.clone(),
+                            player.client.net_stats.snapshot(),
+                        )
+                    })
+                    .collect();
+                // Heaviest traffic first
+                stats.sort_by_key(|(_, stats)| {
+                    std::cmp::Reverse(stats.bytes_received + stats.bytes_sent)
+                });
+
+                sender.send_message(
+                    TextComponent::text(&format!("Network traffic of {} players:", stats.len()))
+                        .color_named(NamedColor::Gold),
+                );
+                for (name, stats) in stats.iter().take(MAX_LISTED) {
+                    sender.send_message(TextComponent::text(&format_stats(name, stats)));
+                }
+                Ok(())
+            })
+            .with_child(
+                argument(ARG_NAME, consume_arg_name).execute(&|sender, server, args| {
+                    let name = args.get(ARG_NAME).ok_or(InvalidConsumptionError(None))?;
+                    let Some(player) = server.get_player_by_name(name) else {
+                        return Err(InvalidConsumptionError(Some(name.clone())));
+                    };
+                    let stats = player.client.net_stats.snapshot();
+                    sender.send_message(TextComponent::text(&format_stats(
+                        &player.gameprofile.name,
+                        &stats,
+                    )));
+                    Ok(())
+                }),
+            ),
+    )
+}
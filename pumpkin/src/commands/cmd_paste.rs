@@ -0,0 +1,133 @@
+use std::path::PathBuf;
+
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+use pumpkin_world::schematic::{Rotation, Schematic};
+
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::{
+    InvalidConsumptionError, InvalidRequirementError,
+};
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::commands::CommandSender::Player;
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["paste"];
+const DESCRIPTION: &str = "Pastes a schematic from the schematics folder where you stand.";
+
+const ARG_NAME: &str = "schematic";
+const ARG_ROTATION: &str = "rotation";
+
+/// Schematics are read from this folder next to the server
+const SCHEMATICS_FOLDER: &str = "schematics";
+/// The file extensions tried when a schematic is named without one
+const EXTENSIONS: [&str; 2] = ["schem", "nbt"];
+
+pub fn consume_arg_name(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    // Only plain file names, so nothing outside of the schematics folder can be read
+    args.pop()
+        .filter(|arg| {
+            !arg.starts_with('.')
+                && arg
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        })
+        .map(Into::into)
+}
+
+pub fn consume_arg_rotation(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    s.parse::<i32>()
+        .ok()
+        .and_then(Rotation::from_degrees)
+        .map(|_| s.into())
+}
+
+/// The file of a schematic, None if there is none with the name
+fn schematic_path(name: &str) -> Option<PathBuf> {
+    let folder = PathBuf::from(SCHEMATICS_FOLDER);
+    std::iter::once(folder.join(name))
+        .chain(
+            EXTENSIONS
+                .iter()
+                .map(|extension| folder.join(format!("{name}.{extension}"))),
+        )
+        .find(|path| path.is_file())
+}
+
+fn paste(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let name = args.get(ARG_NAME).ok_or(InvalidConsumptionError(None))?;
+    let rotation = match args.get(ARG_ROTATION) {
+        Some(rotation) => rotation
+            .parse::<i32>()
+            .ok()
+            .and_then(Rotation::from_degrees)
+            .ok_or_else(|| InvalidConsumptionError(Some(rotation.clone())))?,
+        None => Rotation::None,
+    };
+    let Player(player) = sender else {
+        return Err(InvalidRequirementError);
+    };
+    let Some(path) = schematic_path(name) else {
+        player.send_system_message(
+            TextComponent::text(&format!(
+                "There is no schematic {name} in the {SCHEMATICS_FOLDER} folder"
+            ))
+            .color_named(NamedColor::Red),
+        );
+        return Ok(());
+    };
+    let schematic = match Schematic::read(&path) {
+        Ok(schematic) => schematic,
+        Err(err) => {
+            player.send_system_message(
+                TextComponent::text(&format!("Failed to read the schematic {name}: {err}"))
+                    .color_named(NamedColor::Red),
+            );
+            return Ok(());
+        }
+    };
+    let position = player.living_entity.entity.pos.load();
+    let origin = WorldPosition(Vector3::new(
+        position.x.floor() as i32,
+        position.y.floor() as i32,
+        position.z.floor() as i32,
+    ));
+    let world = player.living_entity.entity.world();
+    let player = server.get_player_by_name(&player.gameprofile.name);
+    let name = name.clone();
+    // The chunks may have to be loaded or generated, which blocks
+    tokio::task::spawn_blocking(move || {
+        let pasted = world.paste(&schematic, &origin, rotation);
+        log::info!(
+            "Pasted the schematic {name} at {}, {}, {} in {}",
+            origin.0.x,
+            origin.0.y,
+            origin.0.z,
+            world.name
+        );
+        if let Some(player) = player {
+            player.send_system_message(
+                TextComponent::text(&format!("Pasted {name}, {pasted} blocks changed"))
+                    .color_named(NamedColor::Green),
+            );
+        }
+    });
+    Ok(())
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 4 && sender.is_player()).with_child(
+            argument(ARG_NAME, consume_arg_name)
+                .execute(&paste)
+                .with_child(argument(ARG_ROTATION, consume_arg_rotation).execute(&paste)),
+        ),
+    )
+}
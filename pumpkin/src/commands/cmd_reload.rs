@@ -0,0 +1,44 @@
+use pumpkin_core::text::color::NamedColor;
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::tree::CommandTree;
+use crate::commands::tree_builder::require;
+
+const NAMES: [&str; 1] = ["reload"];
+
+const DESCRIPTION: &str = "Reload the configuration.";
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 4).execute(&|sender, server, _args| {
+            match server.reload_config() {
+                Ok(summary) => {
+                    sender.send_message(
+                        TextComponent::text(&format!(
+                            "Reloaded configuration, {} settings changed",
+                            summary.changed.len()
+                        ))
+                        .color_named(NamedColor::Green),
+                    );
+                    if !summary.restart_required.is_empty() {
+                        sender.send_message(
+                            TextComponent::text(&format!(
+                                "These settings only apply after a restart: {}",
+                                summary.restart_required.join(", ")
+                            ))
+                            .color_named(NamedColor::Yellow),
+                        );
+                    }
+                }
+                Err(err) => {
+                    log::error!("Failed to reload configuration: {err}");
+                    sender.send_message(
+                        TextComponent::text(&format!("Failed to reload configuration: {err}"))
+                            .color_named(NamedColor::Red),
+                    );
+                }
+            }
+            Ok(())
+        }),
+    )
+}
@@ -0,0 +1,30 @@
+use pumpkin_core::text::color::NamedColor;
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::tree::CommandTree;
+use crate::commands::tree_builder::require;
+
+const NAMES: [&str; 1] = ["save-all"];
+
+const DESCRIPTION: &str = "Save all changed chunks to disk.";
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 4).execute(&|sender, server, _args| {
+            if server.backups.is_running() {
+                sender.send_message(
+                    TextComponent::text("Saving is paused while a backup is written")
+                        .color_named(NamedColor::Red),
+                );
+                return Ok(());
+            }
+            sender.send_message(TextComponent::text("Saving the game"));
+            let saved = server.save_worlds();
+            sender.send_message(
+                TextComponent::text(&format!("Saved the game, {saved} chunks were written"))
+                    .color_named(NamedColor::Green),
+            );
+            Ok(())
+        }),
+    )
+}
@@ -0,0 +1,463 @@
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+use pumpkin_world::scoreboard::{DisplaySlot, RenderType, ScoreboardError};
+
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, literal, require};
+use crate::commands::CommandSender;
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["scoreboard"];
+const DESCRIPTION: &str = "Manages scoreboard objectives and the scores of players.";
+
+const ARG_OBJECTIVE: &str = "objective";
+const ARG_CRITERION: &str = "criterion";
+const ARG_DISPLAY_NAME: &str = "displayName";
+const ARG_SLOT: &str = "slot";
+const ARG_RENDER_TYPE: &str = "renderType";
+const ARG_TARGETS: &str = "targets";
+const ARG_SCORE: &str = "score";
+
+pub fn consume_arg_name(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().map(Into::into)
+}
+
+/// Takes all remaining words, e.g. a display name with spaces
+pub fn consume_arg_text(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    if args.is_empty() {
+        return None;
+    }
+    let words: Vec<&str> = args.drain(..).rev().collect();
+    Some(words.join(" "))
+}
+
+pub fn consume_arg_slot(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    DisplaySlot::from_name(s).map(|_| s.into())
+}
+
+pub fn consume_arg_render_type(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    RenderType::from_name(s).map(|_| s.into())
+}
+
+pub fn consume_arg_score(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    s.parse::<i32>().ok().map(|_| s.into())
+}
+
+pub(super) fn get_arg<'a>(args: &'a ConsumedArgs, name: &str) -> Result<&'a str, InvalidTreeError> {
+    args.get(name)
+        .map(String::as_str)
+        .ok_or(InvalidConsumptionError(None))
+}
+
+/// The score holders named by an argument. `@s` and `@p` are the player running the command,
+/// `@a` are all players and every other name is taken as it is, holders don't have to be online
+pub(super) fn parse_holders(
+    sender: &CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+    name: &str,
+) -> Result<Vec<String>, InvalidTreeError> {
+    let arg = get_arg(args, name)?;
+    Ok(match (arg, sender) {
+        ("@s" | "@p", CommandSender::Player(player)) => vec![player.gameprofile.name.clone()],
+        ("@s" | "@p", _) => Vec::new(),
+        ("@a", _) => server
+            .get_all_players()
+            .iter()
+            .map(|player| player.gameprofile.name.clone())
+            .collect(),
+        _ => vec![arg.to_string()],
+    })
+}
+
+/// Tells the sender how a change went, refused changes in red
+pub(super) fn reply(sender: &mut CommandSender, result: Result<String, ScoreboardError>) {
+    match result {
+        Ok(message) => {
+            sender.send_message(TextComponent::text(&message).color_named(NamedColor::Green));
+        }
+        Err(err) => {
+            sender.send_message(TextComponent::text(&err.to_string()).color_named(NamedColor::Red));
+        }
+    }
+}
+
+fn list_objectives(
+    sender: &mut CommandSender,
+    server: &Server,
+    _args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let names: Vec<String> = server
+        .scoreboard
+        .data()
+        .objectives()
+        .map(|objective| format!("[{}]", objective.display_name))
+        .collect();
+    let message = if names.is_empty() {
+        "There are no objectives".to_string()
+    } else {
+        format!(
+            "There are {} objective(s): {}",
+            names.len(),
+            names.join(", ")
+        )
+    };
+    sender.send_message(TextComponent::text(&message).color_named(NamedColor::Gold));
+    Ok(())
+}
+
+fn add_objective(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let name = get_arg(args, ARG_OBJECTIVE)?;
+    let criterion = get_arg(args, ARG_CRITERION)?;
+    let display_name = args.get(ARG_DISPLAY_NAME).map(String::as_str);
+    let result = server
+        .scoreboard
+        .add_objective(server, name, criterion, display_name)
+        .map(|()| format!("Created new objective [{}]", display_name.unwrap_or(name)));
+    reply(sender, result);
+    Ok(())
+}
+
+fn remove_objective(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let name = get_arg(args, ARG_OBJECTIVE)?;
+    let result = server
+        .scoreboard
+        .remove_objective(server, name)
+        .map(|()| format!("Removed objective [{name}]"));
+    reply(sender, result);
+    Ok(())
+}
+
+fn set_display(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let slot_name = get_arg(args, ARG_SLOT)?;
+    let slot =
+        DisplaySlot::from_name(slot_name).ok_or(InvalidConsumptionError(Some(slot_name.into())))?;
+    let objective = args.get(ARG_OBJECTIVE).map(String::as_str);
+    let result = server
+        .scoreboard
+        .set_display_slot(server, slot, objective)
+        .map(|()| match objective {
+            Some(objective) => {
+                format!("Set display slot {slot_name} to show objective {objective}")
+            }
+            None => format!("Cleared any objectives in display slot {slot_name}"),
+        });
+    reply(sender, result);
+    Ok(())
+}
+
+fn modify_display_name(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let name = get_arg(args, ARG_OBJECTIVE)?;
+    let display_name = get_arg(args, ARG_DISPLAY_NAME)?;
+    let result = server
+        .scoreboard
+        .modify_objective(server, name, |objective| {
+            objective.display_name = display_name.to_string();
+        })
+        .map(|()| format!("Changed the display name of {name} to {display_name}"));
+    reply(sender, result);
+    Ok(())
+}
+
+fn modify_render_type(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let name = get_arg(args, ARG_OBJECTIVE)?;
+    let arg = get_arg(args, ARG_RENDER_TYPE)?;
+    let render_type =
+        RenderType::from_name(arg).ok_or(InvalidConsumptionError(Some(arg.into())))?;
+    let result = server
+        .scoreboard
+        .modify_objective(server, name, |objective| {
+            objective.render_type = render_type
+        })
+        .map(|()| format!("Changed the render type of objective [{name}]"));
+    reply(sender, result);
+    Ok(())
+}
+
+fn list_holders(
+    sender: &mut CommandSender,
+    server: &Server,
+    _args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let holders: Vec<String> = server.scoreboard.data().holders().map(Into::into).collect();
+    let message = if holders.is_empty() {
+        "There are no tracked entities".to_string()
+    } else {
+        format!(
+            "There are {} tracked entity/entities: {}",
+            holders.len(),
+            holders.join(", ")
+        )
+    };
+    sender.send_message(TextComponent::text(&message).color_named(NamedColor::Gold));
+    Ok(())
+}
+
+fn list_scores(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    for holder in parse_holders(sender, server, args, ARG_TARGETS)? {
+        let scores: Vec<String> = {
+            let data = server.scoreboard.data();
+            data.scores_of(&holder)
+                .map(|(objective, score)| {
+                    let display_name = data
+                        .objective(objective)
+                        .map_or(objective, |objective| objective.display_name.as_str());
+                    format!("[{display_name}]: {score}")
+                })
+                .collect()
+        };
+        if scores.is_empty() {
+            sender.send_message(
+                TextComponent::text(&format!("{holder} has no scores"))
+                    .color_named(NamedColor::Gold),
+            );
+            continue;
+        }
+        sender.send_message(
+            TextComponent::text(&format!("{holder} has {} score(s):", scores.len()))
+                .color_named(NamedColor::Gold),
+        );
+        for score in scores {
+            sender.send_message(TextComponent::text(&score).color_named(NamedColor::Gold));
+        }
+    }
+    Ok(())
+}
+
+fn get_score(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let objective = get_arg(args, ARG_OBJECTIVE)?;
+    for holder in parse_holders(sender, server, args, ARG_TARGETS)? {
+        let result = {
+            let data = server.scoreboard.data();
+            data.objective(objective)
+                .map(|found| (data.score(&holder, objective), found.display_name.clone()))
+        };
+        match result {
+            Ok((Some(score), display_name)) => sender.send_message(
+                TextComponent::text(&format!("{holder} has {score} [{display_name}]"))
+                    .color_named(NamedColor::Gold),
+            ),
+            Ok((None, _)) => sender.send_message(
+                TextComponent::text(&format!(
+                    "Can't get value of {objective} for {holder}; none is set"
+                ))
+                .color_named(NamedColor::Red),
+            ),
+            Err(err) => reply(sender, Err(err)),
+        }
+    }
+    Ok(())
+}
+
+fn set_score(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let objective = get_arg(args, ARG_OBJECTIVE)?;
+    let arg = get_arg(args, ARG_SCORE)?;
+    let score: i32 = arg
+        .parse()
+        .map_err(|_| InvalidConsumptionError(Some(arg.into())))?;
+    for holder in parse_holders(sender, server, args, ARG_TARGETS)? {
+        let result = server
+            .scoreboard
+            .set_score(server, &holder, objective, score)
+            .map(|()| format!("Set [{objective}] for {holder} to {score}"));
+        reply(sender, result);
+    }
+    Ok(())
+}
+
+/// Adds to scores, or takes from them with `remove`
+fn change_score(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+    remove: bool,
+) -> Result<(), InvalidTreeError> {
+    let objective = get_arg(args, ARG_OBJECTIVE)?;
+    let arg = get_arg(args, ARG_SCORE)?;
+    let amount: i32 = arg
+        .parse()
+        .ok()
+        .filter(|amount| *amount >= 0)
+        .ok_or(InvalidConsumptionError(Some(arg.into())))?;
+    for holder in parse_holders(sender, server, args, ARG_TARGETS)? {
+        let change = if remove { -amount } else { amount };
+        let result = server
+            .scoreboard
+            .add_score(server, &holder, objective, change)
+            .map(|score| {
+                if remove {
+                    format!("Removed {amount} from [{objective}] for {holder} (now {score})")
+                } else {
+                    format!("Added {amount} to [{objective}] for {holder} (now {score})")
+                }
+            });
+        reply(sender, result);
+    }
+    Ok(())
+}
+
+fn add_score(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    change_score(sender, server, args, false)
+}
+
+fn remove_score(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    change_score(sender, server, args, true)
+}
+
+fn reset_scores(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let objective = args.get(ARG_OBJECTIVE).map(String::as_str);
+    for holder in parse_holders(sender, server, args, ARG_TARGETS)? {
+        let result = server
+            .scoreboard
+            .reset_scores(server, &holder, objective)
+            .map(|_| match objective {
+                Some(objective) => format!("Reset [{objective}] for {holder}"),
+                None => format!("Reset scores for {holder}"),
+            });
+        reply(sender, result);
+    }
+    Ok(())
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2)
+            .with_child(
+                literal("objectives")
+                    .with_child(literal("list").execute(&list_objectives))
+                    .with_child(
+                        literal("add").with_child(
+                            argument(ARG_OBJECTIVE, consume_arg_name).with_child(
+                                argument(ARG_CRITERION, consume_arg_name)
+                                    .execute(&add_objective)
+                                    .with_child(
+                                        argument(ARG_DISPLAY_NAME, consume_arg_text)
+                                            .execute(&add_objective),
+                                    ),
+                            ),
+                        ),
+                    )
+                    .with_child(literal("remove").with_child(
+                        argument(ARG_OBJECTIVE, consume_arg_name).execute(&remove_objective),
+                    ))
+                    .with_child(
+                        literal("setdisplay").with_child(
+                            argument(ARG_SLOT, consume_arg_slot)
+                                .execute(&set_display)
+                                .with_child(
+                                    argument(ARG_OBJECTIVE, consume_arg_name).execute(&set_display),
+                                ),
+                        ),
+                    )
+                    .with_child(
+                        literal("modify").with_child(
+                            argument(ARG_OBJECTIVE, consume_arg_name)
+                                .with_child(
+                                    literal("displayname").with_child(
+                                        argument(ARG_DISPLAY_NAME, consume_arg_text)
+                                            .execute(&modify_display_name),
+                                    ),
+                                )
+                                .with_child(
+                                    literal("rendertype").with_child(
+                                        argument(ARG_RENDER_TYPE, consume_arg_render_type)
+                                            .execute(&modify_render_type),
+                                    ),
+                                ),
+                        ),
+                    ),
+            )
+            .with_child(
+                literal("players")
+                    .with_child(
+                        literal("list").execute(&list_holders).with_child(
+                            argument(ARG_TARGETS, consume_arg_name).execute(&list_scores),
+                        ),
+                    )
+                    .with_child(literal("get").with_child(
+                        argument(ARG_TARGETS, consume_arg_name).with_child(
+                            argument(ARG_OBJECTIVE, consume_arg_name).execute(&get_score),
+                        ),
+                    ))
+                    .with_child(literal("set").with_child(
+                        argument(ARG_TARGETS, consume_arg_name).with_child(
+                            argument(ARG_OBJECTIVE, consume_arg_name).with_child(
+                                argument(ARG_SCORE, consume_arg_score).execute(&set_score),
+                            ),
+                        ),
+                    ))
+                    .with_child(literal("add").with_child(
+                        argument(ARG_TARGETS, consume_arg_name).with_child(
+                            argument(ARG_OBJECTIVE, consume_arg_name).with_child(
+                                argument(ARG_SCORE, consume_arg_score).execute(&add_score),
+                            ),
+                        ),
+                    ))
+                    .with_child(literal("remove").with_child(
+                        argument(ARG_TARGETS, consume_arg_name).with_child(
+                            argument(ARG_OBJECTIVE, consume_arg_name).with_child(
+                                argument(ARG_SCORE, consume_arg_score).execute(&remove_score),
+                            ),
+                        ),
+                    ))
+                    .with_child(
+                        literal("reset").with_child(
+                            argument(ARG_TARGETS, consume_arg_name)
+                                .execute(&reset_scores)
+                                .with_child(
+                                    argument(ARG_OBJECTIVE, consume_arg_name)
+                                        .execute(&reset_scores),
+                                ),
+                        ),
+                    ),
+            ),
+    )
+}
@@ -0,0 +1,28 @@
+use pumpkin_core::text::{click::ClickEvent, color::NamedColor, TextComponent};
+
+use crate::commands::tree::CommandTree;
+use crate::commands::tree_builder::require;
+use crate::commands::CommandSender;
+
+const NAMES: [&str; 1] = ["seed"];
+const DESCRIPTION: &str = "Displays the world seed.";
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2).execute(&|sender, server, _| {
+            // Players get the seed of the world they are in, everyone else the one of the main world
+            let seed = match sender {
+                CommandSender::Player(player) => player.living_entity.entity.world().seed,
+                _ => server.default_world().seed,
+            };
+            let seed = seed.0.to_string();
+            let message = format!("Seed: [{seed}]");
+            sender.send_message(
+                TextComponent::text(&message)
+                    .color_named(NamedColor::Green)
+                    .click_event(ClickEvent::CopyToClipboard(seed.into())),
+            );
+            Ok(())
+        }),
+    )
+}
@@ -0,0 +1,56 @@
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::commands::dispatcher::InvalidTreeError::{
+    InvalidConsumptionError, InvalidRequirementError,
+};
+use crate::commands::tree::{CommandTree, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::commands::CommandSender::Player;
+
+const NAMES: [&str; 1] = ["server"];
+const DESCRIPTION: &str = "Lists the servers in the cluster or switches to one of them.";
+
+const ARG_NODE: &str = "node";
+
+pub fn consume_arg_node(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().map(Into::into)
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION)
+        .execute(&|sender, server, _args| {
+            let nodes = server.cluster.nodes();
+            if nodes.is_empty() {
+                sender.send_message(
+                    TextComponent::text("No other servers are online").color_named(NamedColor::Red),
+                );
+                return Ok(());
+            }
+            let list = nodes
+                .iter()
+                .map(|(name, node)| format!("{name} ({}/{})", node.online, node.max))
+                .collect::<Vec<_>>()
+                .join(", ");
+            sender.send_message(
+                TextComponent::text(&format!("Servers: {list}")).color_named(NamedColor::Gold),
+            );
+            Ok(())
+        })
+        .with_child(require(&|sender| sender.is_player()).with_child(
+            argument(ARG_NODE, consume_arg_node).execute(&|sender, server, args| {
+                let node = args.get(ARG_NODE).ok_or(InvalidConsumptionError(None))?;
+                let Player(player) = sender else {
+                    return Err(InvalidRequirementError);
+                };
+                match server.cluster.transfer_target(node) {
+                    Some((host, port)) => player.transfer(&host, port),
+                    None => player.send_system_message(
+                        TextComponent::text(&format!("Server {node} is not online"))
+                            .color_named(NamedColor::Red),
+                    ),
+                }
+                Ok(())
+            }),
+        ))
+}
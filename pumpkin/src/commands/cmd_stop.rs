@@ -10,10 +10,11 @@ const DESCRIPTION: &str = "Stop the server.";
 
 pub fn init_command_tree<'a>() -> CommandTree<'a> {
     CommandTree::new(NAMES, DESCRIPTION).with_child(
-        require(&|sender| sender.permission_lvl() >= 4).execute(&|sender, _, _args| {
+        require(&|sender| sender.permission_lvl() >= 4).execute(&|sender, server, _args| {
             sender
                 .send_message(TextComponent::text("Stopping Server").color_named(NamedColor::Red));
-            std::process::exit(0)
+            server.request_shutdown();
+            Ok(())
         }),
     )
 }
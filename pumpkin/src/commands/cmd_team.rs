@@ -0,0 +1,428 @@
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+use pumpkin_world::scoreboard::{CollisionRule, Team, Visibility};
+
+use crate::commands::cmd_scoreboard::{
+    consume_arg_name, consume_arg_text, get_arg, parse_holders, reply,
+};
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, literal, require};
+use crate::commands::CommandSender;
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["team"];
+const DESCRIPTION: &str = "Manages teams and their members.";
+
+const ARG_TEAM: &str = "team";
+const ARG_DISPLAY_NAME: &str = "displayName";
+const ARG_MEMBERS: &str = "members";
+const ARG_VALUE: &str = "value";
+
+/// `reset` takes the color away, like in vanilla
+fn parse_color(arg: &str) -> Option<Option<NamedColor>> {
+    match arg {
+        "reset" => Some(None),
+        _ => NamedColor::from_name(arg).map(Some),
+    }
+}
+
+fn parse_bool(arg: &str) -> Option<bool> {
+    match arg {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+pub fn consume_arg_color(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    parse_color(s).map(|_| s.into())
+}
+
+pub fn consume_arg_bool(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    parse_bool(s).map(|_| s.into())
+}
+
+pub fn consume_arg_visibility(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    Visibility::from_name(s).map(|_| s.into())
+}
+
+pub fn consume_arg_collision_rule(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    CollisionRule::from_name(s).map(|_| s.into())
+}
+
+/// The members of a team with the sender taking part if they are not named
+fn parse_members(
+    sender: &CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<Vec<String>, InvalidTreeError> {
+    if args.contains_key(ARG_MEMBERS) {
+        return parse_holders(sender, server, args, ARG_MEMBERS);
+    }
+    Ok(match sender {
+        CommandSender::Player(player) => vec![player.gameprofile.name.clone()],
+        _ => Vec::new(),
+    })
+}
+
+fn list_teams(
+    sender: &mut CommandSender,
+    server: &Server,
+    _args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let names: Vec<String> = server
+        .scoreboard
+        .data()
+        .teams()
+        .map(|team| format!("[{}]", team.display_name))
+        .collect();
+    let message = if names.is_empty() {
+        "There are no teams".to_string()
+    } else {
+        format!("There are {} team(s): {}", names.len(), names.join(", "))
+    };
+    sender.send_message(TextComponent::text(&message).color_named(NamedColor::Gold));
+    Ok(())
+}
+
+fn list_members(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let name = get_arg(args, ARG_TEAM)?;
+    let result = server.scoreboard.data().team(name).map(|team| {
+        if team.members.is_empty() {
+            format!("There are no members on team [{}]", team.display_name)
+        } else {
+            let members: Vec<&str> = team.members.iter().map(String::as_str).collect();
+            format!(
+                "Team [{}] has {} member(s): {}",
+                team.display_name,
+                members.len(),
+                members.join(", ")
+            )
+        }
+    });
+    match result {
+        Ok(message) => {
+            sender.send_message(TextComponent::text(&message).color_named(NamedColor::Gold));
+        }
+        Err(err) => reply(sender, Err(err)),
+    }
+    Ok(())
+}
+
+fn add_team(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let name = get_arg(args, ARG_TEAM)?;
+    let display_name = args.get(ARG_DISPLAY_NAME).map(String::as_str);
+    let result = server
+        .scoreboard
+        .add_team(server, name, display_name)
+        .map(|()| format!("Created team [{}]", display_name.unwrap_or(name)));
+    reply(sender, result);
+    Ok(())
+}
+
+fn remove_team(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let name = get_arg(args, ARG_TEAM)?;
+    let result = server
+        .scoreboard
+        .remove_team(server, name)
+        .map(|()| format!("Removed team [{name}]"));
+    reply(sender, result);
+    Ok(())
+}
+
+fn empty_team(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let name = get_arg(args, ARG_TEAM)?;
+    match server.scoreboard.empty_team(server, name) {
+        Ok(0) => sender.send_message(
+            TextComponent::text("Nothing changed. That team is already empty")
+                .color_named(NamedColor::Red),
+        ),
+        result => reply(
+            sender,
+            result.map(|count| format!("Removed {count} member(s) from team [{name}]")),
+        ),
+    }
+    Ok(())
+}
+
+fn join_team(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let name = get_arg(args, ARG_TEAM)?;
+    let members = parse_members(sender, server, args)?;
+    let result = server
+        .scoreboard
+        .join_team(server, name, &members)
+        .map(|()| match members.as_slice() {
+            [member] => format!("Added {member} to team [{name}]"),
+            _ => format!("Added {} members to team [{name}]", members.len()),
+        });
+    reply(sender, result);
+    Ok(())
+}
+
+fn leave_team(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let members = parse_members(sender, server, args)?;
+    server.scoreboard.leave_team(server, &members);
+    let message = match members.as_slice() {
+        [member] => format!("Removed {member} from any team"),
+        _ => format!("Removed {} members from any team", members.len()),
+    };
+    reply(sender, Ok(message));
+    Ok(())
+}
+
+/// Changes one option of a team, `modify` returns what to tell the sender
+fn modify_team(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+    modify: impl FnOnce(&mut Team, &str) -> Option<String>,
+) -> Result<(), InvalidTreeError> {
+    let name = get_arg(args, ARG_TEAM)?;
+    let value = get_arg(args, ARG_VALUE)?;
+    let mut message = None;
+    let result = server.scoreboard.modify_team(server, name, |team| {
+        message = modify(team, value);
+    });
+    let message = match result {
+        Ok(()) => Ok(message.ok_or(InvalidConsumptionError(Some(value.into())))?),
+        Err(err) => Err(err),
+    };
+    reply(sender, message);
+    Ok(())
+}
+
+fn modify_color(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    modify_team(sender, server, args, |team, value| {
+        team.color = parse_color(value)?;
+        Some(format!(
+            "Updated the color for team [{}] to {value}",
+            team.display_name
+        ))
+    })
+}
+
+fn modify_display_name(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    modify_team(sender, server, args, |team, value| {
+        team.display_name = value.to_string();
+        Some(format!("Team [{}] is now called {value}", team.name))
+    })
+}
+
+fn modify_prefix(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    modify_team(sender, server, args, |team, value| {
+        team.prefix = value.to_string();
+        Some(format!("Team prefix set to {value}"))
+    })
+}
+
+fn modify_suffix(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    modify_team(sender, server, args, |team, value| {
+        team.suffix = value.to_string();
+        Some(format!("Team suffix set to {value}"))
+    })
+}
+
+fn modify_friendly_fire(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    modify_team(sender, server, args, |team, value| {
+        team.friendly_fire = parse_bool(value)?;
+        let state = if team.friendly_fire {
+            "enabled"
+        } else {
+            "disabled"
+        };
+        Some(format!(
+            "Friendly fire is now {state} for team [{}]",
+            team.display_name
+        ))
+    })
+}
+
+fn modify_see_friendly_invisibles(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    modify_team(sender, server, args, |team, value| {
+        team.see_friendly_invisibles = parse_bool(value)?;
+        Some(if team.see_friendly_invisibles {
+            "Team members can now see invisible team members".to_string()
+        } else {
+            "Team members can no longer see invisible team members".to_string()
+        })
+    })
+}
+
+fn modify_name_tag_visibility(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    modify_team(sender, server, args, |team, value| {
+        team.name_tag_visibility = Visibility::from_name(value)?;
+        Some(format!(
+            "Name tag visibility is now \"{value}\" for team [{}]",
+            team.display_name
+        ))
+    })
+}
+
+fn modify_death_message_visibility(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    modify_team(sender, server, args, |team, value| {
+        team.death_message_visibility = Visibility::from_name(value)?;
+        Some(format!(
+            "Death message visibility is now \"{value}\" for team [{}]",
+            team.display_name
+        ))
+    })
+}
+
+fn modify_collision_rule(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    modify_team(sender, server, args, |team, value| {
+        team.collision_rule = CollisionRule::from_name(value)?;
+        Some(format!(
+            "Collision rule for team [{}] is now \"{value}\"",
+            team.display_name
+        ))
+    })
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2)
+            .with_child(
+                literal("list")
+                    .execute(&list_teams)
+                    .with_child(argument(ARG_TEAM, consume_arg_name).execute(&list_members)),
+            )
+            .with_child(
+                literal("add").with_child(
+                    argument(ARG_TEAM, consume_arg_name)
+                        .execute(&add_team)
+                        .with_child(
+                            argument(ARG_DISPLAY_NAME, consume_arg_text).execute(&add_team),
+                        ),
+                ),
+            )
+            .with_child(
+                literal("remove")
+                    .with_child(argument(ARG_TEAM, consume_arg_name).execute(&remove_team)),
+            )
+            .with_child(
+                literal("empty")
+                    .with_child(argument(ARG_TEAM, consume_arg_name).execute(&empty_team)),
+            )
+            .with_child(
+                literal("join").with_child(
+                    argument(ARG_TEAM, consume_arg_name)
+                        .execute(&join_team)
+                        .with_child(argument(ARG_MEMBERS, consume_arg_name).execute(&join_team)),
+                ),
+            )
+            .with_child(
+                literal("leave")
+                    .execute(&leave_team)
+                    .with_child(argument(ARG_MEMBERS, consume_arg_name).execute(&leave_team)),
+            )
+            .with_child(
+                literal("modify").with_child(
+                    argument(ARG_TEAM, consume_arg_name)
+                        .with_child(literal("color").with_child(
+                            argument(ARG_VALUE, consume_arg_color).execute(&modify_color),
+                        ))
+                        .with_child(literal("displayName").with_child(
+                            argument(ARG_VALUE, consume_arg_text).execute(&modify_display_name),
+                        ))
+                        .with_child(literal("prefix").with_child(
+                            argument(ARG_VALUE, consume_arg_text).execute(&modify_prefix),
+                        ))
+                        .with_child(literal("suffix").with_child(
+                            argument(ARG_VALUE, consume_arg_text).execute(&modify_suffix),
+                        ))
+                        .with_child(literal("friendlyFire").with_child(
+                            argument(ARG_VALUE, consume_arg_bool).execute(&modify_friendly_fire),
+                        ))
+                        .with_child(
+                            literal("seeFriendlyInvisibles").with_child(
+                                argument(ARG_VALUE, consume_arg_bool)
+                                    .execute(&modify_see_friendly_invisibles),
+                            ),
+                        )
+                        .with_child(
+                            literal("nametagVisibility").with_child(
+                                argument(ARG_VALUE, consume_arg_visibility)
+                                    .execute(&modify_name_tag_visibility),
+                            ),
+                        )
+                        .with_child(
+                            literal("deathMessageVisibility").with_child(
+                                argument(ARG_VALUE, consume_arg_visibility)
+                                    .execute(&modify_death_message_visibility),
+                            ),
+                        )
+                        .with_child(
+                            literal("collisionRule").with_child(
+                                argument(ARG_VALUE, consume_arg_collision_rule)
+                                    .execute(&modify_collision_rule),
+                            ),
+                        ),
+                ),
+            ),
+    )
+}
@@ -0,0 +1,87 @@
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::commands::arg_player::{consume_arg_player, parse_arg_player};
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::{
+    InvalidConsumptionError, InvalidRequirementError,
+};
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::commands::CommandSender::Player;
+
+const NAMES: [&str; 1] = ["transfer"];
+const DESCRIPTION: &str = "Transfers a player to another server.";
+
+const ARG_HOSTNAME: &str = "hostname";
+const ARG_PORT: &str = "port";
+const ARG_TARGET: &str = "target";
+
+const DEFAULT_PORT: u16 = 25565;
+
+pub fn consume_arg_hostname(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().map(Into::into)
+}
+
+pub fn consume_arg_port(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    s.parse::<u16>().ok().map(|_| s.into())
+}
+
+fn parse_args(consumed_args: &ConsumedArgs) -> Result<(&str, u16), InvalidTreeError> {
+    let hostname = consumed_args
+        .get(ARG_HOSTNAME)
+        .ok_or(InvalidConsumptionError(None))?;
+    let port = match consumed_args.get(ARG_PORT) {
+        Some(port) => port
+            .parse()
+            .map_err(|_| InvalidConsumptionError(Some(port.clone())))?,
+        None => DEFAULT_PORT,
+    };
+    Ok((hostname, port))
+}
+
+fn transfer_self(sender: &mut CommandSender, args: &ConsumedArgs) -> Result<(), InvalidTreeError> {
+    let (hostname, port) = parse_args(args)?;
+    if let Player(target) = sender {
+        target.transfer(hostname, port);
+        Ok(())
+    } else {
+        Err(InvalidRequirementError)
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 3).with_child(
+            argument(ARG_HOSTNAME, consume_arg_hostname)
+                .with_child(
+                    require(&|sender| sender.is_player())
+                        .execute(&|sender, _, args| transfer_self(sender, args)),
+                )
+                .with_child(
+                    argument(ARG_PORT, consume_arg_port)
+                        .with_child(
+                            require(&|sender| sender.is_player())
+                                .execute(&|sender, _, args| transfer_self(sender, args)),
+                        )
+                        .with_child(argument(ARG_TARGET, consume_arg_player).execute(
+                            &|sender, server, args| {
+                                let (hostname, port) = parse_args(args)?;
+                                let target = parse_arg_player(sender, server, ARG_TARGET, args)?;
+                                target.transfer(hostname, port);
+                                let name = target.gameprofile.name.clone();
+
+                                sender.send_message(
+                                    TextComponent::text(&format!(
+                                        "Transferred {name} to {hostname}:{port}"
+                                    ))
+                                    .color_named(NamedColor::Blue),
+                                );
+                                Ok(())
+                            },
+                        )),
+                ),
+        ),
+    )
+}
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::server::ticker::TICKS_PER_SECOND;
+use crate::server::Server;
+use crate::world::weather::WeatherType;
+use crate::world::World;
+
+const NAMES: [&str; 1] = ["weather"];
+const DESCRIPTION: &str = "Sets the weather.";
+
+const ARG_WEATHER: &str = "weather";
+const ARG_DURATION: &str = "duration";
+
+/// Ticks in a Minecraft day, the `d` unit of durations
+const TICKS_PER_DAY: i64 = 24000;
+
+fn parse_weather(arg: &str) -> Option<WeatherType> {
+    match arg {
+        "clear" => Some(WeatherType::Clear),
+        "rain" => Some(WeatherType::Rain),
+        "thunder" => Some(WeatherType::Thunder),
+        _ => None,
+    }
+}
+
+/// Parses a duration like vanilla, in ticks or with the unit `t`, `s` or `d`
+fn parse_duration(arg: &str) -> Option<i32> {
+    let (amount, unit) = match arg.char_indices().last()? {
+        (i, 't') => (&arg[..i], 1),
+        (i, 's') => (&arg[..i], TICKS_PER_SECOND as i64),
+        (i, 'd') => (&arg[..i], TICKS_PER_DAY),
+        _ => (arg, 1),
+    };
+    let ticks = amount.parse::<i64>().ok()?.checked_mul(unit)?;
+    (1..=i32::MAX as i64)
+        .contains(&ticks)
+        .then_some(ticks as i32)
+}
+
+pub fn consume_arg_weather(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    parse_weather(s).map(|_| s.into())
+}
+
+pub fn consume_arg_duration(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    parse_duration(s).map(|_| s.into())
+}
+
+/// Players change the weather of the world they are in, everyone else the one of the main world
+fn target_world(sender: &CommandSender, server: &Server) -> Arc<World> {
+    match sender {
+        CommandSender::Player(player) => player.living_entity.entity.world(),
+        _ => server.default_world().clone(),
+    }
+}
+
+fn set_weather(
+    sender: &mut CommandSender,
+    server: &Server,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let arg = args.get(ARG_WEATHER).ok_or(InvalidConsumptionError(None))?;
+    let weather = parse_weather(arg).ok_or(InvalidConsumptionError(Some(arg.clone())))?;
+    let duration = match args.get(ARG_DURATION) {
+        Some(arg) => Some(parse_duration(arg).ok_or(InvalidConsumptionError(Some(arg.clone())))?),
+        None => None,
+    };
+    let world = target_world(sender, server);
+    world.set_weather(server, weather, duration);
+    let message = match weather {
+        WeatherType::Clear => "Set the weather to clear",
+        WeatherType::Rain => "Set the weather to rain",
+        WeatherType::Thunder => "Set the weather to rain & thunder",
+    };
+    sender.send_message(
+        TextComponent::text(&format!("{message} in {}", world.name)).color_named(NamedColor::Gold),
+    );
+    Ok(())
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2).with_child(
+            argument(ARG_WEATHER, consume_arg_weather)
+                .execute(&set_weather)
+                .with_child(argument(ARG_DURATION, consume_arg_duration).execute(&set_weather)),
+        ),
+    )
+}
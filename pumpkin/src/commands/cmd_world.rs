@@ -0,0 +1,66 @@
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::commands::dispatcher::InvalidTreeError::{
+    InvalidConsumptionError, InvalidRequirementError,
+};
+use crate::commands::tree::{CommandTree, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::commands::CommandSender::Player;
+
+const NAMES: [&str; 1] = ["world"];
+const DESCRIPTION: &str = "Lists the worlds or moves you to another one.";
+
+const ARG_TP: &str = "tp";
+const ARG_WORLD: &str = "world";
+
+pub fn consume_arg_tp(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().filter(|arg| *arg == ARG_TP).map(Into::into)
+}
+
+pub fn consume_arg_world(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().map(Into::into)
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2)
+            .execute(&|sender, server, _args| {
+                let list = server
+                    .worlds
+                    .iter()
+                    .map(|world| format!("{} ({})", world.name, world.current_players.lock().len()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                sender.send_message(
+                    TextComponent::text(&format!("Worlds: {list}")).color_named(NamedColor::Gold),
+                );
+                Ok(())
+            })
+            .with_child(require(&|sender| sender.is_player()).with_child(
+                argument(ARG_TP, consume_arg_tp).with_child(
+                    argument(ARG_WORLD, consume_arg_world).execute(&|sender, server, args| {
+                        let name = args.get(ARG_WORLD).ok_or(InvalidConsumptionError(None))?;
+                        let Player(player) = sender else {
+                            return Err(InvalidRequirementError);
+                        };
+                        let Some(world) = server.get_world(name) else {
+                            player.send_system_message(
+                                TextComponent::text(&format!("World {name} does not exist"))
+                                    .color_named(NamedColor::Red),
+                            );
+                            return Ok(());
+                        };
+                        // The chunks of the new world are sent in the background
+                        if let Some(player) = server.get_player_by_name(&player.gameprofile.name) {
+                            tokio::spawn(async move {
+                                let spawn = world.spawn_position.load();
+                                player.change_world(world, spawn).await;
+                            });
+                        }
+                        Ok(())
+                    }),
+                ),
+            )),
+    )
+}
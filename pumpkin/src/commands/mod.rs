@@ -6,12 +6,30 @@ use crate::commands::dispatcher::CommandDispatcher;
 use crate::entity::player::Player;
 use crate::server::Server;
 mod arg_player;
+mod cmd_attribute;
+mod cmd_backup;
 mod cmd_echest;
+mod cmd_effect;
+mod cmd_experience;
+mod cmd_fill;
+mod cmd_forceload;
 mod cmd_gamemode;
+mod cmd_gamerule;
 mod cmd_help;
 mod cmd_kill;
+mod cmd_netstat;
+mod cmd_paste;
 mod cmd_pumpkin;
+mod cmd_reload;
+mod cmd_save_all;
+mod cmd_scoreboard;
+mod cmd_seed;
+mod cmd_server;
 mod cmd_stop;
+mod cmd_team;
+mod cmd_transfer;
+mod cmd_weather;
+mod cmd_world;
 pub mod dispatcher;
 mod tree;
 mod tree_builder;
@@ -29,7 +47,8 @@ impl<'a> CommandSender<'a> {
             // TODO: add color and stuff to console
             CommandSender::Console => log::info!("{}", text.to_pretty_console()),
             CommandSender::Player(c) => c.send_system_message(text),
-            CommandSender::Rcon(s) => s.push(text.to_pretty_console()),
+            // RCON clients don't show the colors of the console
+            CommandSender::Rcon(s) => s.push(text.to_plain()),
         }
     }
 
@@ -75,6 +94,24 @@ pub fn default_dispatcher<'a>() -> CommandDispatcher<'a> {
     dispatcher.register(cmd_help::init_command_tree());
     dispatcher.register(cmd_echest::init_command_tree());
     dispatcher.register(cmd_kill::init_command_tree());
+    dispatcher.register(cmd_transfer::init_command_tree());
+    dispatcher.register(cmd_netstat::init_command_tree());
+    dispatcher.register(cmd_reload::init_command_tree());
+    dispatcher.register(cmd_server::init_command_tree());
+    dispatcher.register(cmd_save_all::init_command_tree());
+    dispatcher.register(cmd_world::init_command_tree());
+    dispatcher.register(cmd_forceload::init_command_tree());
+    dispatcher.register(cmd_weather::init_command_tree());
+    dispatcher.register(cmd_gamerule::init_command_tree());
+    dispatcher.register(cmd_seed::init_command_tree());
+    dispatcher.register(cmd_paste::init_command_tree());
+    dispatcher.register(cmd_fill::init_command_tree());
+    dispatcher.register(cmd_backup::init_command_tree());
+    dispatcher.register(cmd_experience::init_command_tree());
+    dispatcher.register(cmd_effect::init_command_tree());
+    dispatcher.register(cmd_scoreboard::init_command_tree());
+    dispatcher.register(cmd_team::init_command_tree());
+    dispatcher.register(cmd_attribute::init_command_tree());
 
     dispatcher
 }
@@ -0,0 +1,331 @@
+use std::{
+    collections::HashSet,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use pumpkin_core::text::{color::NamedColor, hover::HoverEvent, Text, TextComponent};
+use pumpkin_protocol::client::play::{
+    AdvancementProgressEntry, CSystemChatMessage, CUpdateAdvancements,
+};
+use pumpkin_world::{
+    advancement::{Advancement, AdvancementFrame, AdvancementProgress, CriteriaProgress},
+    dimension::Dimension,
+    game_rules,
+    global_registry::{self, ITEM_REGISTRY},
+};
+use serde_json::Value;
+
+use crate::server::{advancements::Advancements, Server};
+
+use super::player::Player;
+
+/// What a player achieved and what their client knows about it
+#[derive(Default)]
+pub struct PlayerAdvancements {
+    pub progress: AdvancementProgress,
+    /// Whether the client got the advancements yet, they are sent on the first tick
+    sent: bool,
+    /// The dimension the player was in on the last tick
+    dimension: Option<Dimension>,
+}
+
+impl PlayerAdvancements {
+    pub fn new(progress: AdvancementProgress) -> Self {
+        Self {
+            progress,
+            ..Default::default()
+        }
+    }
+}
+
+impl Player {
+    /// Checks the criteria which are not triggered by anything the player does, `check_inventory`
+    /// also checks the items they carry
+    pub fn tick_advancements(&self, server: &Server, check_inventory: bool) {
+        let dimension = self.living_entity.entity.world().dimension;
+        let (first, previous) = {
+            let mut advancements = self.advancements.lock();
+            let first = !std::mem::replace(&mut advancements.sent, true);
+            (first, advancements.dimension.replace(dimension))
+        };
+        if first {
+            let advancements = self.advancements.lock();
+            let progress = &advancements.progress;
+            let shown = visible(&server.advancements, progress);
+            let shown: Vec<_> = server
+                .advancements
+                .iter()
+                .filter(|(id, _)| shown.contains(id.as_str()))
+                .collect();
+            self.send_advancements(&server.advancements, progress, true, &shown, &[], &shown);
+        }
+
+        if let Some(from) = previous.filter(|from| *from != dimension) {
+            self.trigger(server, "minecraft:changed_dimension", |conditions| {
+                dimension_matches(conditions, "from", from)
+                    && dimension_matches(conditions, "to", dimension)
+            });
+        }
+        self.trigger(server, "minecraft:tick", |_| true);
+        if check_inventory {
+            let items: Vec<(&str, u8)> = self
+                .inventory
+                .lock()
+                .slots()
+                .into_iter()
+                .flatten()
+                .filter_map(|item| {
+                    global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id)
+                        .map(|id| (id, item.item_count))
+                })
+                .collect();
+            self.trigger(server, "minecraft:inventory_changed", |conditions| {
+                inventory_matches(conditions, &items)
+            });
+        }
+    }
+
+    /// Awards every criterion with the `trigger` the player did not meet yet whose conditions
+    /// hold
+    pub fn trigger(&self, server: &Server, trigger: &str, matches: impl Fn(&Value) -> bool) {
+        let mut met = Vec::new();
+        {
+            let advancements = self.advancements.lock();
+            let progress = &advancements.progress;
+            for (id, advancement) in server.advancements.iter() {
+                if progress.advancements.get(id).is_some_and(|p| p.done) {
+                    continue;
+                }
+                for (name, criterion) in &advancement.criteria {
+                    if criterion.trigger == trigger
+                        && !progress.is_met(id, name)
+                        && matches(&criterion.conditions)
+                    {
+                        met.push((id.clone(), name.clone()));
+                    }
+                }
+            }
+        }
+        for (id, criterion) in met {
+            self.award_criterion(server, &id, &criterion);
+        }
+    }
+
+    /// Marks `criterion` of the advancement `id` as met, false if the player already met it or
+    /// it does not exist
+    pub fn award_criterion(&self, server: &Server, id: &str, criterion: &str) -> bool {
+        self.change_progress(server, id, criterion, |progress| {
+            if progress.criteria.contains_key(criterion) {
+                return false;
+            }
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as i64;
+            progress.criteria.insert(criterion.to_string(), now);
+            true
+        })
+    }
+
+    /// Marks `criterion` of the advancement `id` as not met, false if the player did not meet it
+    pub fn revoke_criterion(&self, server: &Server, id: &str, criterion: &str) -> bool {
+        self.change_progress(server, id, criterion, |progress| {
+            progress.criteria.remove(criterion).is_some()
+        })
+    }
+
+    fn change_progress(
+        &self,
+        server: &Server,
+        id: &str,
+        criterion: &str,
+        change: impl FnOnce(&mut CriteriaProgress) -> bool,
+    ) -> bool {
+        let Some(advancement) = server.advancements.get(id) else {
+            return false;
+        };
+        if !advancement.criteria.contains_key(criterion) {
+            return false;
+        }
+        let mut advancements = self.advancements.lock();
+        let shown_before = visible(&server.advancements, &advancements.progress);
+        let progress = advancements
+            .progress
+            .advancements
+            .entry(id.to_string())
+            .or_default();
+        if !change(progress) {
+            return false;
+        }
+        let was_done = progress.done;
+        progress.done = advancement.is_done(|name| progress.criteria.contains_key(name));
+        let got_done = progress.done && !was_done;
+        if progress.criteria.is_empty() {
+            advancements.progress.advancements.remove(id);
+        }
+
+        // Otherwise the client gets everything on the first tick
+        if advancements.sent {
+            let shown = visible(&server.advancements, &advancements.progress);
+            let added: Vec<_> = server
+                .advancements
+                .iter()
+                .filter(|(other, _)| {
+                    shown.contains(other.as_str()) && !shown_before.contains(other.as_str())
+                })
+                .collect();
+            let removed: Vec<&str> = shown_before.difference(&shown).copied().collect();
+            let mut changed = added.clone();
+            if shown_before.contains(id) && shown.contains(id) {
+                changed.extend(server.advancements.iter().find(|(other, _)| other == id));
+            }
+            // The client shows a toast for advancements which got done
+            self.send_advancements(
+                &server.advancements,
+                &advancements.progress,
+                false,
+                &added,
+                &removed,
+                &changed,
+            );
+        }
+        drop(advancements);
+
+        if got_done {
+            self.advancement_done(server, advancement);
+        }
+        true
+    }
+
+    fn send_advancements(
+        &self,
+        advancements: &Advancements,
+        progress: &AdvancementProgress,
+        reset: bool,
+        added: &[&(String, Advancement)],
+        removed: &[&str],
+        changed: &[&(String, Advancement)],
+    ) {
+        let requirements: Vec<_> = added
+            .iter()
+            .map(|(_, advancement)| advancement.requirements())
+            .collect();
+        let added: Vec<_> = added
+            .iter()
+            .zip(&requirements)
+            .map(|((id, advancement), requirements)| {
+                advancements.entry(id, advancement, requirements)
+            })
+            .collect();
+        let changed: Vec<_> = changed
+            .iter()
+            .map(|(id, advancement)| {
+                let met = progress.advancements.get(id);
+                AdvancementProgressEntry {
+                    id,
+                    criteria: advancement
+                        .criteria
+                        .keys()
+                        .map(|name| {
+                            let date = met.and_then(|met| met.criteria.get(name).copied());
+                            (name.as_str(), date)
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+        self.client
+            .send_packet(&CUpdateAdvancements::new(reset, &added, removed, &changed));
+    }
+
+    /// Gives the rewards and announces the advancement in chat
+    fn advancement_done(&self, server: &Server, advancement: &Advancement) {
+        if advancement.rewards.experience != 0 {
+            self.add_experience_points(advancement.rewards.experience);
+        }
+        let Some(display) = &advancement.display else {
+            return;
+        };
+        let world = self.living_entity.entity.world();
+        if !display.announce_to_chat || !world.game_rule_bool(game_rules::ANNOUNCE_ADVANCEMENTS) {
+            return;
+        }
+        let (kind, color) = match display.frame {
+            AdvancementFrame::Task => ("task", NamedColor::Green),
+            AdvancementFrame::Goal => ("goal", NamedColor::Green),
+            AdvancementFrame::Challenge => ("challenge", NamedColor::DarkPurple),
+        };
+        let title = TextComponent::translate("chat.square_brackets", vec![display.title.clone()])
+            .color_named(color)
+            .hover_event(HoverEvent::ShowText(Text(Box::new(
+                display.description.clone(),
+            ))));
+        let message = TextComponent::translate(
+            format!("chat.type.advancement.{kind}"),
+            vec![TextComponent::text(&self.gameprofile.name), title],
+        );
+        server.broadcast_packet_all(&CSystemChatMessage::new(message, false));
+    }
+}
+
+/// The advancements the client shows. Hidden ones only show up once they are done and like in
+/// vanilla, the children of advancements which are not shown are not shown either
+fn visible<'a>(advancements: &'a Advancements, progress: &AdvancementProgress) -> HashSet<&'a str> {
+    let mut shown = HashSet::new();
+    // Parents come before their children
+    for (id, advancement) in advancements.iter() {
+        let Some(display) = &advancement.display else {
+            continue;
+        };
+        let done = progress.advancements.get(id).is_some_and(|p| p.done);
+        let parent_shown = advancement
+            .parent
+            .as_ref()
+            .map_or(true, |parent| shown.contains(parent.as_str()));
+        if parent_shown && (!display.hidden || done) {
+            shown.insert(id.as_str());
+        }
+    }
+    shown
+}
+
+/// Whether the `key` condition of a changed_dimension trigger allows `dimension`, missing ones
+/// allow every dimension
+fn dimension_matches(conditions: &Value, key: &str, dimension: Dimension) -> bool {
+    conditions
+        .get(key)
+        .and_then(Value::as_str)
+        .map_or(true, |name| name == dimension.type_name())
+}
+
+/// Whether every item predicate of an inventory_changed trigger matches one of `items`
+fn inventory_matches(conditions: &Value, items: &[(&str, u8)]) -> bool {
+    let Some(predicates) = conditions.get("items").and_then(Value::as_array) else {
+        return !items.is_empty();
+    };
+    predicates.iter().all(|predicate| {
+        items
+            .iter()
+            .any(|(id, count)| item_matches(predicate, id, *count))
+    })
+}
+
+fn item_matches(predicate: &Value, id: &str, count: u8) -> bool {
+    // TODO: Support item tags
+    let id_matches = match predicate.get("items") {
+        Some(Value::String(item)) => item == id,
+        Some(Value::Array(items)) => items.iter().any(|item| item.as_str() == Some(id)),
+        _ => true,
+    };
+    let count = i64::from(count);
+    let count_matches = match predicate.get("count") {
+        Some(Value::Number(exact)) => exact.as_i64() == Some(count),
+        Some(Value::Object(range)) => {
+            let min = range.get("min").and_then(Value::as_i64).unwrap_or(i64::MIN);
+            let max = range.get("max").and_then(Value::as_i64).unwrap_or(i64::MAX);
+            (min..=max).contains(&count)
+        }
+        _ => true,
+    };
+    id_matches && count_matches
+}
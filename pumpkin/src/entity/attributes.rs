@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+
+use pumpkin_protocol::client::play::{Attribute, AttributeModifier, CUpdateAttributes};
+use pumpkin_world::item::{
+    attribute_modifiers::{AttributeOperation, ATTACK_DAMAGE, ATTACK_SPEED},
+    ItemStack,
+};
+
+use super::{
+    living::{LivingEntity, MAX_HEALTH},
+    player::Player,
+};
+
+/// The attributes of living entities the Server knows
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EntityAttribute {
+    AttackDamage,
+    AttackSpeed,
+    MaxHealth,
+    MovementSpeed,
+}
+
+impl EntityAttribute {
+    pub const ALL: [Self; 4] = [
+        Self::AttackDamage,
+        Self::AttackSpeed,
+        Self::MaxHealth,
+        Self::MovementSpeed,
+    ];
+
+    /// The protocol id of the `minecraft:attribute` registry
+    const fn id(self) -> i32 {
+        match self {
+            Self::AttackDamage => 2,
+            Self::AttackSpeed => 4,
+            Self::MaxHealth => 18,
+            Self::MovementSpeed => 21,
+        }
+    }
+
+    /// The name items and commands use
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::AttackDamage => ATTACK_DAMAGE,
+            Self::AttackSpeed => ATTACK_SPEED,
+            Self::MaxHealth => "minecraft:generic.max_health",
+            Self::MovementSpeed => "minecraft:generic.movement_speed",
+        }
+    }
+
+    /// The attribute by its name, the namespace may be left out
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        Self::ALL
+            .into_iter()
+            .find(|attribute| attribute.name().strip_prefix("minecraft:") == Some(name))
+    }
+
+    /// The base value of players, like in vanilla
+    pub const fn default_value(self) -> f64 {
+        match self {
+            Self::AttackDamage => 1.0,
+            Self::AttackSpeed => 4.0,
+            Self::MaxHealth => MAX_HEALTH as f64,
+            Self::MovementSpeed => 0.1,
+        }
+    }
+
+    /// The lowest and the highest value, like in vanilla
+    const fn range(self) -> (f64, f64) {
+        match self {
+            Self::AttackDamage => (0.0, 2048.0),
+            Self::AttackSpeed | Self::MovementSpeed => (0.0, 1024.0),
+            Self::MaxHealth => (1.0, 1024.0),
+        }
+    }
+}
+
+/// Changes the value of an attribute, e.g. while an effect lasts
+#[derive(Clone, Debug, PartialEq)]
+pub struct Modifier {
+    /// A modifier with the same id replaces this one, e.g. `minecraft:effect.speed`
+    pub id: String,
+    pub amount: f64,
+    pub operation: AttributeOperation,
+}
+
+impl Modifier {
+    pub fn new(id: &str, amount: f64, operation: AttributeOperation) -> Self {
+        Self {
+            id: id.to_string(),
+            amount,
+            operation,
+        }
+    }
+
+    /// The modifiers an item gives while it is held in the main hand
+    pub fn main_hand(item: &ItemStack, attribute: EntityAttribute) -> Vec<Self> {
+        item.attribute_modifiers()
+            .iter()
+            .filter(|modifier| {
+                modifier.attribute == attribute.name() && modifier.slot == "mainhand"
+            })
+            .map(|modifier| Self::new(&modifier.id, modifier.amount, modifier.operation))
+            .collect()
+    }
+}
+
+/// The protocol id of the operation
+const fn operation_id(operation: AttributeOperation) -> i8 {
+    match operation {
+        AttributeOperation::AddValue => 0,
+        AttributeOperation::AddMultipliedBase => 1,
+        AttributeOperation::AddMultipliedTotal => 2,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Instance {
+    base: f64,
+    modifiers: Vec<Modifier>,
+}
+
+/// The attributes of a living entity, the ones which are not stored have their default base
+/// value and no modifiers
+#[derive(Clone, Debug, Default)]
+pub struct Attributes {
+    instances: HashMap<EntityAttribute, Instance>,
+}
+
+impl Attributes {
+    pub fn base(&self, attribute: EntityAttribute) -> f64 {
+        self.instances
+            .get(&attribute)
+            .map_or(attribute.default_value(), |instance| instance.base)
+    }
+
+    pub fn set_base(&mut self, attribute: EntityAttribute, base: f64) {
+        self.instance(attribute).base = base;
+    }
+
+    pub fn value(&self, attribute: EntityAttribute) -> f64 {
+        self.value_with(attribute, &[])
+    }
+
+    /// The value with some more modifiers, e.g. the ones of the held item. Like in vanilla the
+    /// added values come first, then the multiplied base and then the multiplied total
+    pub fn value_with(&self, attribute: EntityAttribute, extra: &[Modifier]) -> f64 {
+        let modifiers = || self.modifiers(attribute).iter().chain(extra);
+        let sum = |operation| -> f64 {
+            modifiers()
+                .filter(|modifier| modifier.operation == operation)
+                .map(|modifier| modifier.amount)
+                .sum()
+        };
+        let base = self.base(attribute) + sum(AttributeOperation::AddValue);
+        let value = base + base * sum(AttributeOperation::AddMultipliedBase);
+        let value = modifiers()
+            .filter(|modifier| modifier.operation == AttributeOperation::AddMultipliedTotal)
+            .fold(value, |value, modifier| value * (1.0 + modifier.amount));
+        let (min, max) = attribute.range();
+        value.clamp(min, max)
+    }
+
+    pub fn modifiers(&self, attribute: EntityAttribute) -> &[Modifier] {
+        self.instances
+            .get(&attribute)
+            .map_or(&[], |instance| instance.modifiers.as_slice())
+    }
+
+    /// Adds a modifier, it replaces the one with the same id
+    pub fn add_modifier(&mut self, attribute: EntityAttribute, modifier: Modifier) {
+        let modifiers = &mut self.instance(attribute).modifiers;
+        modifiers.retain(|other| other.id != modifier.id);
+        modifiers.push(modifier);
+    }
+
+    /// Takes a modifier away, returns false if there was none with the id
+    pub fn remove_modifier(&mut self, attribute: EntityAttribute, id: &str) -> bool {
+        let Some(instance) = self.instances.get_mut(&attribute) else {
+            return false;
+        };
+        let count = instance.modifiers.len();
+        instance.modifiers.retain(|modifier| modifier.id != id);
+        instance.modifiers.len() != count
+    }
+
+    /// Takes all modifiers away, the base values stay
+    pub fn clear_modifiers(&mut self) {
+        for instance in self.instances.values_mut() {
+            instance.modifiers.clear();
+        }
+    }
+
+    fn instance(&mut self, attribute: EntityAttribute) -> &mut Instance {
+        self.instances.entry(attribute).or_insert_with(|| Instance {
+            base: attribute.default_value(),
+            modifiers: Vec::new(),
+        })
+    }
+}
+
+impl LivingEntity {
+    pub fn attribute(&self, attribute: EntityAttribute) -> f64 {
+        self.attributes.lock().value(attribute)
+    }
+
+    pub fn max_health(&self) -> f32 {
+        self.attribute(EntityAttribute::MaxHealth) as f32
+    }
+}
+
+impl Player {
+    /// The value of an attribute with the modifiers of the held item
+    pub fn attribute_with_held_item(&self, attribute: EntityAttribute) -> f64 {
+        let held = self
+            .inventory
+            .lock()
+            .held_item()
+            .map(|item| Modifier::main_hand(item, attribute))
+            .unwrap_or_default();
+        self.living_entity
+            .attributes
+            .lock()
+            .value_with(attribute, &held)
+    }
+
+    /// Changes the base value of an attribute, the client is told
+    pub fn set_attribute_base(&self, attribute: EntityAttribute, base: f64) {
+        self.living_entity
+            .attributes
+            .lock()
+            .set_base(attribute, base);
+        self.on_attribute_changed(attribute);
+    }
+
+    /// Adds a modifier or replaces the one with the same id, the client is told
+    pub fn add_attribute_modifier(&self, attribute: EntityAttribute, modifier: Modifier) {
+        self.living_entity
+            .attributes
+            .lock()
+            .add_modifier(attribute, modifier);
+        self.on_attribute_changed(attribute);
+    }
+
+    /// Takes a modifier away, returns false if there was none with the id
+    pub fn remove_attribute_modifier(&self, attribute: EntityAttribute, id: &str) -> bool {
+        let removed = self
+            .living_entity
+            .attributes
+            .lock()
+            .remove_modifier(attribute, id);
+        if removed {
+            self.on_attribute_changed(attribute);
+        }
+        removed
+    }
+
+    /// Players can't have more health than their max health, e.g. once health boost ends
+    fn on_attribute_changed(&self, attribute: EntityAttribute) {
+        self.send_attributes(&[attribute]);
+        if attribute == EntityAttribute::MaxHealth {
+            let max_health = self.living_entity.max_health();
+            let health = self.living_entity.health.load();
+            if health > max_health {
+                self.set_health(
+                    max_health,
+                    self.food.load(std::sync::atomic::Ordering::Relaxed),
+                    self.food_saturation.load(),
+                );
+            }
+        }
+    }
+
+    /// Tells the client the base values and modifiers of attributes. The client drops the
+    /// modifiers it had, so all of them are sent
+    pub fn send_attributes(&self, attributes: &[EntityAttribute]) {
+        let entries: Vec<(EntityAttribute, f64, Vec<Modifier>)> = {
+            let all = self.living_entity.attributes.lock();
+            attributes
+                .iter()
+                .map(|attribute| {
+                    (
+                        *attribute,
+                        all.base(*attribute),
+                        all.modifiers(*attribute).to_vec(),
+                    )
+                })
+                .collect()
+        };
+        let modifiers: Vec<Vec<AttributeModifier>> = entries
+            .iter()
+            .map(|(_, _, modifiers)| {
+                modifiers
+                    .iter()
+                    .map(|modifier| AttributeModifier {
+                        id: &modifier.id,
+                        amount: modifier.amount,
+                        operation: operation_id(modifier.operation),
+                    })
+                    .collect()
+            })
+            .collect();
+        let attributes: Vec<Attribute> = entries
+            .iter()
+            .zip(&modifiers)
+            .map(|((attribute, base, _), modifiers)| Attribute {
+                id: attribute.id().into(),
+                base_value: *base,
+                modifiers,
+            })
+            .collect();
+        self.client.send_packet(&CUpdateAttributes::new(
+            self.entity_id().into(),
+            &attributes,
+        ));
+    }
+}
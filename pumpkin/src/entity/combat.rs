@@ -0,0 +1,201 @@
+use std::{
+    f32::consts::PI,
+    sync::{atomic::Ordering, Arc},
+};
+
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_core::{
+    math::{boundingbox::BoundingBox, vector3::Vector3},
+    GameMode,
+};
+use pumpkin_plugins::events::PlayerAttackEvent;
+use pumpkin_protocol::client::play::{Animation, CEntityAnimation, CEntityVelocity};
+use pumpkin_world::stats;
+
+use crate::server::Server;
+
+use super::{
+    attributes::EntityAttribute, damage::DamageSource, food::ATTACK_EXHAUSTION, player::Player,
+};
+
+/// How far survival players reach entities, creative players reach further
+const ENTITY_INTERACTION_RANGE: f64 = 3.0;
+const CREATIVE_ENTITY_INTERACTION_RANGE: f64 = 5.0;
+/// Extra reach for the position the Server knows being a bit behind the client
+const REACH_TOLERANCE: f64 = 1.0;
+/// Every hurt entity is pushed away this much
+const BASE_KNOCKBACK: f64 = 0.4;
+/// Extra knockback of a full strength attack while sprinting
+const SPRINT_KNOCKBACK: f64 = 0.5;
+/// Attacks with more strength than this are full strength, they can crit and knock back more
+const FULL_STRENGTH: f32 = 0.9;
+const CRITICAL_MULTIPLIER: f32 = 1.5;
+
+impl Player {
+    /// How many ticks it takes until the held item attacks at full strength again
+    pub fn attack_cooldown(&self) -> f32 {
+        let speed = self
+            .attribute_with_held_item(EntityAttribute::AttackSpeed)
+            .max(0.1);
+        (20.0 / speed) as f32
+    }
+
+    /// How strong an attack would be right now, from 0 right after the last one to 1 once the
+    /// cooldown passed
+    pub fn attack_strength(&self) -> f32 {
+        let ticks = self.attack_strength_ticks.load(Ordering::Relaxed) as f32;
+        ((ticks + 0.5) / self.attack_cooldown()).clamp(0.0, 1.0)
+    }
+
+    /// Starts the attack cooldown over, e.g. after swinging or switching items
+    pub fn reset_attack_strength(&self) {
+        self.attack_strength_ticks.store(0, Ordering::Relaxed);
+    }
+
+    /// The damage of the held item with strength and weakness, before the attack cooldown
+    pub(super) fn attack_damage(&self) -> f32 {
+        self.attribute_with_held_item(EntityAttribute::AttackDamage) as f32
+    }
+
+    /// Whether the player is close enough to hit something in `bounding_box`
+    pub fn can_interact_with_entity(&self, bounding_box: &BoundingBox) -> bool {
+        let range = if self.gamemode.load() == GameMode::Creative {
+            CREATIVE_ENTITY_INTERACTION_RANGE
+        } else {
+            ENTITY_INTERACTION_RANGE
+        } + REACH_TOLERANCE;
+        let entity = &self.living_entity.entity;
+        let pos = entity.pos.load();
+        let eyes = Vector3::new(pos.x, pos.y + f64::from(entity.standing_eye_height), pos.z);
+        bounding_box.squared_magnitude(eyes) < range * range
+    }
+
+    /// Whether an attack right now would be a critical hit, falling players which don't sprint
+    /// crit with full strength attacks
+    fn is_critical_attack(&self, strength: f32) -> bool {
+        let entity = &self.living_entity.entity;
+        strength > FULL_STRENGTH
+            && self.living_entity.fall_distance.load() > 0.0
+            && !entity.on_ground.load(Ordering::Relaxed)
+            && !entity.sprinting.load(Ordering::Relaxed)
+    }
+
+    /// Hits another player with the held item. The attack cooldown scales the damage, falling
+    /// players crit and sprinting ones knock back further. Plugins can change the damage or
+    /// cancel the attack
+    pub fn attack_player(&self, server: &Server, target: &Arc<Player>) {
+        if Arc::ptr_eq(&target.client, &self.client)
+            || target.living_entity.health.load() <= 0.0
+            || target.gamemode.load() == GameMode::Spectator
+        {
+            return;
+        }
+        let world = self.living_entity.entity.world();
+        if !world.pvp_enabled() {
+            return;
+        }
+        let config = &ADVANCED_CONFIG.get().pvp;
+        if config.protect_creative && target.gamemode.load() == GameMode::Creative {
+            return;
+        }
+        if !server.scoreboard.can_hurt(self, target) {
+            return;
+        }
+        if !self.can_interact_with_entity(&target.bounding_box()) {
+            log::debug!(
+                "{} tried to attack {} out of reach",
+                self.gameprofile.name,
+                target.gameprofile.name
+            );
+            return;
+        }
+
+        let strength = self.attack_strength();
+        self.reset_attack_strength();
+        let critical = self.is_critical_attack(strength);
+        let mut damage = self.attack_damage() * (0.2 + strength * strength * 0.8);
+        if critical {
+            damage *= CRITICAL_MULTIPLIER;
+        }
+        let mut event = PlayerAttackEvent {
+            world: &world.name,
+            attacker_name: &self.gameprofile.name,
+            attacker_uuid: self.gameprofile.id,
+            target_entity_id: target.entity_id(),
+            target_uuid: Some(target.gameprofile.id),
+            damage,
+            critical,
+            cancelled: false,
+        };
+        server
+            .plugins
+            .read()
+            .for_each(|plugin| plugin.on_player_attack(&mut event));
+        if event.cancelled {
+            return;
+        }
+        let (damage, critical) = (event.damage, event.critical);
+
+        let attacker = &self.living_entity.entity;
+        let sprinting = attacker.sprinting.load(Ordering::Relaxed);
+        if !target.damage(damage, DamageSource::Attack(self.entity_id())) {
+            return;
+        }
+        self.add_exhaustion(ATTACK_EXHAUSTION);
+        self.increment_custom_stat(stats::DAMAGE_DEALT, (damage * 10.0).round() as i32);
+        if target.living_entity.health.load() <= 0.0 {
+            self.increment_custom_stat(stats::PLAYER_KILLS, 1);
+            self.increment_stat(stats::KILLED, "minecraft:player", 1);
+            target.increment_stat(stats::KILLED_BY, "minecraft:player", 1);
+        }
+        if critical {
+            world.broadcast_packet_all(&CEntityAnimation::new(
+                target.entity_id().into(),
+                Animation::CriticalEffect as u8,
+            ));
+        }
+        if !config.knockback {
+            return;
+        }
+        let victim = &target.living_entity.entity;
+        let from = attacker.pos.load();
+        let to = victim.pos.load();
+        victim.knockback(BASE_KNOCKBACK, from.x - to.x, from.z - to.z);
+        if sprinting && strength > FULL_STRENGTH {
+            let yaw = attacker.yaw.load() * (PI / 180.0);
+            victim.knockback(
+                SPRINT_KNOCKBACK,
+                f64::from(yaw.sin()),
+                -f64::from(yaw.cos()),
+            );
+            // The attacker slows down and stops sprinting, like in vanilla
+            attacker
+                .velocity
+                .store(attacker.velocity.load().multiply(0.6, 1.0, 0.6));
+            self.set_sprinting(false);
+        }
+        target.send_knockback();
+    }
+
+    /// Pushes the player away from `from`, e.g. when a mob hits them
+    pub fn knockback_from(&self, strength: f64, from: Vector3<f64>) {
+        let entity = &self.living_entity.entity;
+        let pos = entity.pos.load();
+        entity.knockback(strength, from.x - pos.x, from.z - pos.z);
+        self.send_knockback();
+    }
+
+    /// Players move themselves, so only their client can apply the knockback
+    fn send_knockback(&self) {
+        let entity = &self.living_entity.entity;
+        let velocity = entity.velocity.load();
+        self.client.send_packet(&CEntityVelocity::new(
+            &self.entity_id().into(),
+            velocity.x as f32,
+            velocity.y as f32,
+            velocity.z as f32,
+        ));
+        // Their next movement starts from the knockback
+        entity.velocity.store(Vector3::new(0.0, 0.0, 0.0));
+    }
+}
@@ -0,0 +1,236 @@
+use std::sync::atomic::Ordering;
+
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_entity::{effect::StatusEffect, EntityId};
+use pumpkin_world::{game_rules, item::Enchantment, WORLD_LOWEST_Y};
+
+use super::{living::LivingEntity, player::Player};
+
+/// Falls up to this many blocks don't hurt
+const SAFE_FALL_DISTANCE: f64 = 3.0;
+/// Entities this far below the world are hurt by the void
+const VOID_DEPTH: f64 = 64.0;
+const VOID_DAMAGE: f32 = 4.0;
+const FIRE_DAMAGE: f32 = 1.0;
+const LAVA_DAMAGE: f32 = 4.0;
+/// The exhaustion most damage costs, see `DamageSource::exhaustion`
+const DAMAGE_EXHAUSTION: f32 = 0.1;
+/// Protection enchantments don't reduce the damage by more than this many points, each takes 4%
+const MAX_PROTECTION: i32 = 20;
+
+/// What hurt an entity, decides which armor and enchantments protect against it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageSource {
+    /// Landing after falling further than `SAFE_FALL_DISTANCE`
+    Fall,
+    /// Standing in fire
+    Fire,
+    /// Standing in lava
+    Lava,
+    /// Being far below the world, even players in creative mode die from it
+    Void,
+    /// Being hit by another entity
+    Attack(EntityId),
+    /// Being hit by an arrow or something thrown, by the entity which shot it
+    Projectile(EntityId),
+    Explosion,
+    /// Having no food left
+    Starve,
+    /// Poison and instant damage
+    Magic,
+    /// The wither effect
+    Wither,
+}
+
+/// The enchantments which only protect against some damage
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProtectionKind {
+    FeatherFalling,
+    FireProtection,
+    BlastProtection,
+    ProjectileProtection,
+}
+
+impl DamageSource {
+    /// Whether worn armor doesn't reduce the damage
+    pub const fn bypasses_armor(&self) -> bool {
+        matches!(
+            self,
+            Self::Fall | Self::Void | Self::Starve | Self::Magic | Self::Wither
+        )
+    }
+
+    /// Whether entities are hurt even if they can't be hurt otherwise, e.g. players in creative
+    /// mode or entities which just got hurt
+    pub const fn bypasses_invulnerability(&self) -> bool {
+        matches!(self, Self::Void)
+    }
+
+    /// Whether the resistance effect doesn't reduce the damage
+    pub const fn bypasses_resistance(&self) -> bool {
+        matches!(self, Self::Void)
+    }
+
+    /// Whether fire resistance protects against the damage
+    pub const fn is_fire(&self) -> bool {
+        matches!(self, Self::Fire | Self::Lava)
+    }
+
+    /// Whether protection enchantments don't reduce the damage
+    pub const fn bypasses_enchantments(&self) -> bool {
+        matches!(self, Self::Void | Self::Starve)
+    }
+
+    /// The enchantment which protects especially well against the damage, like in vanilla it
+    /// counts twice, feather falling even three times
+    pub const fn protection_kind(&self) -> Option<ProtectionKind> {
+        match self {
+            Self::Fall => Some(ProtectionKind::FeatherFalling),
+            Self::Fire | Self::Lava => Some(ProtectionKind::FireProtection),
+            Self::Explosion => Some(ProtectionKind::BlastProtection),
+            Self::Projectile(_) => Some(ProtectionKind::ProjectileProtection),
+            Self::Void | Self::Attack(_) | Self::Starve | Self::Magic | Self::Wither => None,
+        }
+    }
+
+    /// How hungry getting hurt makes a player
+    pub const fn exhaustion(&self) -> f32 {
+        match self {
+            Self::Void | Self::Starve => 0.0,
+            _ => DAMAGE_EXHAUSTION,
+        }
+    }
+
+    /// The entity which caused the damage, if there is one
+    pub const fn attacker(&self) -> Option<EntityId> {
+        match self {
+            Self::Attack(attacker) | Self::Projectile(attacker) => Some(*attacker),
+            _ => None,
+        }
+    }
+}
+
+/// The damage left after protection enchantments, `protection` are the points of all worn
+/// protection enchantments which apply to the damage
+pub fn damage_after_protection(amount: f32, protection: i32) -> f32 {
+    let protection = protection.clamp(0, MAX_PROTECTION);
+    amount * (1.0 - protection as f32 / 25.0)
+}
+
+/// The damage a fall does once the entity lands
+pub fn fall_damage(fall_distance: f64) -> f32 {
+    (fall_distance - SAFE_FALL_DISTANCE).ceil().max(0.0) as f32
+}
+
+impl LivingEntity {
+    /// Adds up how far the entity fell since it last stood on the ground, returns the damage it
+    /// takes when it lands. Water and climbing stop the fall
+    pub fn update_fall_distance(&self, from: Vector3<f64>, to: Vector3<f64>) -> f32 {
+        let entity = &self.entity;
+        let world = entity.world();
+        let block = world.level.get_block(&entity.block_pos.load());
+        let breaks_fall = block.is_some_and(|block| {
+            block.registry_state().is_some_and(|(registry_id, _)| {
+                matches!(
+                    registry_id,
+                    "minecraft:water"
+                        | "minecraft:ladder"
+                        | "minecraft:vine"
+                        | "minecraft:cobweb"
+                        | "minecraft:scaffolding"
+                )
+            })
+        });
+        if breaks_fall {
+            self.fall_distance.store(0.0);
+            return 0.0;
+        }
+        if to.y < from.y {
+            self.fall_distance
+                .store(self.fall_distance.load() + (from.y - to.y));
+        }
+        if !entity.on_ground.load(Ordering::Relaxed) {
+            return 0.0;
+        }
+        let fall_distance = self.fall_distance.swap(0.0);
+        if world.game_rule_bool(game_rules::FALL_DAMAGE) {
+            fall_damage(fall_distance)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Player {
+    /// The points of the worn protection enchantments which apply to `source`
+    pub fn protection(&self, source: &DamageSource) -> i32 {
+        let special = source.protection_kind().map(|kind| match kind {
+            ProtectionKind::FeatherFalling => (Enchantment::FeatherFalling, 3),
+            ProtectionKind::FireProtection => (Enchantment::FireProtection, 2),
+            ProtectionKind::BlastProtection => (Enchantment::BlastProtection, 2),
+            ProtectionKind::ProjectileProtection => (Enchantment::ProjectileProtection, 2),
+        });
+        self.inventory
+            .lock()
+            .worn_armor()
+            .map(|item| {
+                let protection = i32::from(item.enchantments.level(Enchantment::Protection));
+                let special = special.map_or(0, |(enchantment, points)| {
+                    i32::from(item.enchantments.level(enchantment)) * points
+                });
+                protection + special
+            })
+            .sum()
+    }
+
+    /// Hurts the player when they fall far enough, the client tells where the player is but the
+    /// Server decides how far they fell
+    pub(crate) fn update_fall(&self, from: Vector3<f64>, to: Vector3<f64>) {
+        if self.awaiting_teleport.lock().is_some() || self.abilities.lock().flying {
+            self.living_entity.fall_distance.store(0.0);
+            return;
+        }
+        if self
+            .living_entity
+            .effect(StatusEffect::SlowFalling)
+            .is_some()
+        {
+            self.living_entity.fall_distance.store(0.0);
+            return;
+        }
+        let mut damage = self.living_entity.update_fall_distance(from, to);
+        // Jump boost lets the player fall a block further per level
+        if let Some(amplifier) = self.living_entity.effect_amplifier(StatusEffect::JumpBoost) {
+            damage -= f32::from(amplifier) + 1.0;
+        }
+        if damage > 0.0 {
+            self.damage(damage, DamageSource::Fall);
+        }
+    }
+
+    /// Hurts the player from where they are, in fire, lava or the void
+    pub(super) fn tick_environment_damage(&self) {
+        let entity = &self.living_entity.entity;
+        let pos = entity.pos.load();
+        if pos.y < f64::from(WORLD_LOWEST_Y) - VOID_DEPTH {
+            self.damage(VOID_DAMAGE, DamageSource::Void);
+            return;
+        }
+        let world = entity.world();
+        let feet = WorldPosition(Vector3::new(
+            pos.x.floor() as i32,
+            pos.y.floor() as i32,
+            pos.z.floor() as i32,
+        ));
+        let Some(block) = world.level.get_block(&feet) else {
+            return;
+        };
+        match block.registry_state().map(|(registry_id, _)| registry_id) {
+            Some("minecraft:fire" | "minecraft:soul_fire") => {
+                self.damage(FIRE_DAMAGE, DamageSource::Fire);
+            }
+            Some("minecraft:lava") => self.damage(LAVA_DAMAGE, DamageSource::Lava),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,346 @@
+use std::sync::atomic::Ordering;
+
+use pumpkin_entity::effect::StatusEffect;
+use pumpkin_protocol::client::play::{CEntityEffect, CRemoveEntityEffect};
+use pumpkin_world::item::{AttributeOperation, EffectInstance};
+
+use super::{
+    attributes::{EntityAttribute, Modifier},
+    damage::DamageSource,
+    living::LivingEntity,
+    player::Player,
+};
+
+/// Speed makes 20% faster per level, slowness 15% slower
+const SPEED_PER_LEVEL: f64 = 0.2;
+const SLOWNESS_PER_LEVEL: f64 = -0.15;
+/// Strength adds attack damage per level, weakness takes it away
+const STRENGTH_PER_LEVEL: f64 = 3.0;
+const WEAKNESS_PER_LEVEL: f64 = -4.0;
+/// Health boost adds two hearts per level
+const HEALTH_BOOST_PER_LEVEL: f64 = 4.0;
+/// Resistance takes 20% of the damage per level
+const RESISTANCE_PER_LEVEL: f32 = 0.2;
+/// Ticks between the effects of regeneration, poison and wither at level I, every level
+/// halves them
+const REGENERATION_INTERVAL: i32 = 50;
+const POISON_INTERVAL: i32 = 25;
+const WITHER_INTERVAL: i32 = 40;
+/// Exhaustion per tick and level of hunger
+const HUNGER_EXHAUSTION: f32 = 0.005;
+
+/// A status effect an entity has right now
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActiveEffect {
+    pub effect: StatusEffect,
+    /// 0 for level I
+    pub amplifier: u8,
+    /// Ticks left, -1 for an effect which lasts forever
+    pub duration: i32,
+    /// Effects from beacons and conduits are ambient, their particles are less visible
+    pub ambient: bool,
+    pub show_particles: bool,
+    pub show_icon: bool,
+}
+
+impl ActiveEffect {
+    pub const fn new(effect: StatusEffect, amplifier: u8, duration: i32) -> Self {
+        Self {
+            effect,
+            amplifier,
+            duration,
+            ambient: false,
+            show_particles: true,
+            show_icon: true,
+        }
+    }
+
+    /// The effect an item gives, None if the Server doesn't know it
+    pub fn from_item(instance: &EffectInstance) -> Option<Self> {
+        Some(Self {
+            effect: StatusEffect::from_name(&instance.id)?,
+            amplifier: instance.amplifier,
+            duration: instance.duration,
+            ambient: instance.ambient,
+            show_particles: instance.show_particles,
+            show_icon: instance.show_icon,
+        })
+    }
+
+    pub const fn is_infinite(&self) -> bool {
+        self.duration == -1
+    }
+
+    /// Whether the effect replaces `other` of the same kind, stronger effects replace weaker
+    /// ones and longer effects shorter ones of the same level
+    fn replaces(&self, other: &Self) -> bool {
+        self.amplifier > other.amplifier
+            || (self.amplifier == other.amplifier
+                && (self.is_infinite() || (!other.is_infinite() && self.duration > other.duration)))
+    }
+
+    /// Whether an effect which acts every `interval` ticks at level I acts this tick, effects
+    /// which last forever count with the `world_age`
+    fn acts_now(&self, interval: i32, world_age: i64) -> bool {
+        let interval = interval >> self.amplifier.min(31);
+        let ticks = if self.is_infinite() {
+            world_age
+        } else {
+            i64::from(self.duration)
+        };
+        interval <= 0 || ticks % i64::from(interval) == 0
+    }
+
+    fn packet(&self, entity_id: i32) -> CEntityEffect {
+        let mut flags = 0;
+        if self.ambient {
+            flags |= 0x01;
+        }
+        if self.show_particles {
+            flags |= 0x02;
+        }
+        if self.show_icon {
+            flags |= 0x04;
+        }
+        CEntityEffect::new(
+            entity_id.into(),
+            (self.effect as i32).into(),
+            i32::from(self.amplifier).into(),
+            self.duration.into(),
+            flags,
+        )
+    }
+}
+
+impl LivingEntity {
+    /// The effect of the kind the entity has right now
+    pub fn effect(&self, effect: StatusEffect) -> Option<ActiveEffect> {
+        self.effects
+            .lock()
+            .iter()
+            .find(|active| active.effect == effect)
+            .copied()
+    }
+
+    /// The amplifier of the effect, None if the entity doesn't have it
+    pub fn effect_amplifier(&self, effect: StatusEffect) -> Option<u8> {
+        self.effect(effect).map(|active| active.amplifier)
+    }
+
+    /// The damage left after the resistance effect
+    pub fn damage_after_resistance(&self, amount: f32, source: &DamageSource) -> f32 {
+        if source.bypasses_resistance() {
+            return amount;
+        }
+        match self.effect_amplifier(StatusEffect::Resistance) {
+            Some(amplifier) => {
+                let reduction = RESISTANCE_PER_LEVEL * (f32::from(amplifier) + 1.0);
+                (amount * (1.0 - reduction)).max(0.0)
+            }
+            None => amount,
+        }
+    }
+}
+
+impl Player {
+    /// Gives the player an effect, instant effects act right away. Returns false if the player
+    /// already has a stronger or longer effect of the kind
+    pub fn add_effect(&self, effect: ActiveEffect) -> bool {
+        if effect.effect.is_instant() {
+            self.apply_instant_effect(effect.effect, effect.amplifier);
+            return true;
+        }
+        {
+            let mut effects = self.living_entity.effects.lock();
+            match effects
+                .iter_mut()
+                .find(|active| active.effect == effect.effect)
+            {
+                Some(active) if !effect.replaces(active) => return false,
+                Some(active) => *active = effect,
+                None => effects.push(effect),
+            }
+        }
+        self.client.send_packet(&effect.packet(self.entity_id()));
+        self.on_effect_changed(effect.effect, Some(effect.amplifier));
+        true
+    }
+
+    /// Takes an effect away from the player, returns false if they didn't have it
+    pub fn remove_effect(&self, effect: StatusEffect) -> bool {
+        {
+            let mut effects = self.living_entity.effects.lock();
+            let Some(index) = effects.iter().position(|active| active.effect == effect) else {
+                return false;
+            };
+            effects.swap_remove(index);
+        }
+        self.client.send_packet(&CRemoveEntityEffect::new(
+            self.entity_id().into(),
+            (effect as i32).into(),
+        ));
+        self.on_effect_changed(effect, None);
+        true
+    }
+
+    /// Takes all effects away from the player, returns how many they had
+    pub fn clear_effects(&self) -> usize {
+        let effects: Vec<_> = self
+            .living_entity
+            .effects
+            .lock()
+            .iter()
+            .map(|active| active.effect)
+            .collect();
+        for effect in &effects {
+            self.remove_effect(*effect);
+        }
+        effects.len()
+    }
+
+    /// Shows the client all effects of the player again, e.g. after it switched worlds
+    pub fn send_effects(&self) {
+        let effects = self.living_entity.effects.lock().clone();
+        for effect in &effects {
+            self.client.send_packet(&effect.packet(self.entity_id()));
+        }
+        self.send_attributes(&EntityAttribute::ALL);
+    }
+
+    /// Lets the effects act and counts them down, expired effects are removed
+    pub(super) fn tick_effects(&self) {
+        if self.living_entity.health.load() <= 0.0 {
+            return;
+        }
+        // The effects can't be locked while they act, hurting the player needs them
+        let effects = self.living_entity.effects.lock().clone();
+        for effect in &effects {
+            self.apply_effect(effect);
+        }
+        let mut expired = Vec::new();
+        for active in self.living_entity.effects.lock().iter_mut() {
+            if active.is_infinite() {
+                continue;
+            }
+            active.duration -= 1;
+            if active.duration <= 0 {
+                expired.push(active.effect);
+            }
+        }
+        for effect in expired {
+            self.remove_effect(effect);
+        }
+    }
+
+    /// What an effect does every tick
+    fn apply_effect(&self, effect: &ActiveEffect) {
+        let health = self.living_entity.health.load();
+        let world_age = self
+            .living_entity
+            .entity
+            .world()
+            .world_age
+            .load(Ordering::Relaxed);
+        match effect.effect {
+            StatusEffect::Regeneration if effect.acts_now(REGENERATION_INTERVAL, world_age) => {
+                self.heal(1.0);
+            }
+            // Poison never kills
+            StatusEffect::Poison if effect.acts_now(POISON_INTERVAL, world_age) && health > 1.0 => {
+                self.damage(1.0, DamageSource::Magic);
+            }
+            StatusEffect::Wither if effect.acts_now(WITHER_INTERVAL, world_age) => {
+                self.damage(1.0, DamageSource::Wither);
+            }
+            StatusEffect::Hunger => {
+                self.add_exhaustion(HUNGER_EXHAUSTION * (f32::from(effect.amplifier) + 1.0));
+            }
+            _ => {}
+        }
+    }
+
+    /// What an instant effect does, all at once
+    fn apply_instant_effect(&self, effect: StatusEffect, amplifier: u8) {
+        let level = i32::from(amplifier) + 1;
+        // Instant health and damage double with every level
+        let doubled = 2f32.powi(i32::from(amplifier));
+        match effect {
+            StatusEffect::InstantHealth => self.heal(4.0 * doubled),
+            StatusEffect::InstantDamage => {
+                self.damage(6.0 * doubled, DamageSource::Magic);
+            }
+            StatusEffect::Saturation => {
+                let food = (self.food.load(Ordering::Relaxed) + level).min(20);
+                let saturation =
+                    (self.food_saturation.load() + (level * 2) as f32).min(food as f32);
+                self.set_health(self.living_entity.health.load(), food, saturation);
+            }
+            _ => {}
+        }
+    }
+
+    /// Updates what depends on an effect once it started, changed or ended, `amplifier` is
+    /// None once it ended
+    fn on_effect_changed(&self, effect: StatusEffect, amplifier: Option<u8>) {
+        if let Some((attribute, per_level, operation)) = effect_modifier(effect) {
+            let id = format!("minecraft:effect.{}", effect.name());
+            match amplifier {
+                Some(amplifier) => {
+                    let amount = per_level * (f64::from(amplifier) + 1.0);
+                    self.add_attribute_modifier(attribute, Modifier::new(&id, amount, operation));
+                }
+                None => {
+                    self.remove_attribute_modifier(attribute, &id);
+                }
+            }
+        }
+        match effect {
+            // Absorption gives extra health once, it is gone with the effect
+            StatusEffect::Absorption => {
+                let absorption = amplifier.map_or(0.0, |amplifier| {
+                    (4.0 * (f32::from(amplifier) + 1.0)).max(self.living_entity.absorption.load())
+                });
+                self.set_absorption(absorption);
+            }
+            StatusEffect::Glowing => self.living_entity.entity.set_glowing(amplifier.is_some()),
+            StatusEffect::Invisibility => {
+                self.living_entity.entity.set_invisible(amplifier.is_some());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The attribute an effect changes with the amount per level, like in vanilla
+const fn effect_modifier(
+    effect: StatusEffect,
+) -> Option<(EntityAttribute, f64, AttributeOperation)> {
+    match effect {
+        StatusEffect::Speed => Some((
+            EntityAttribute::MovementSpeed,
+            SPEED_PER_LEVEL,
+            AttributeOperation::AddMultipliedTotal,
+        )),
+        StatusEffect::Slowness => Some((
+            EntityAttribute::MovementSpeed,
+            SLOWNESS_PER_LEVEL,
+            AttributeOperation::AddMultipliedTotal,
+        )),
+        StatusEffect::Strength => Some((
+            EntityAttribute::AttackDamage,
+            STRENGTH_PER_LEVEL,
+            AttributeOperation::AddValue,
+        )),
+        StatusEffect::Weakness => Some((
+            EntityAttribute::AttackDamage,
+            WEAKNESS_PER_LEVEL,
+            AttributeOperation::AddValue,
+        )),
+        StatusEffect::HealthBoost => Some((
+            EntityAttribute::MaxHealth,
+            HEALTH_BOOST_PER_LEVEL,
+            AttributeOperation::AddValue,
+        )),
+        _ => None,
+    }
+}
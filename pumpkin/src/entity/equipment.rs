@@ -0,0 +1,68 @@
+use pumpkin_core::GameMode;
+use pumpkin_protocol::{
+    client::play::{CSetEquipment, EquipmentSlot},
+    slot::Slot,
+};
+use pumpkin_world::item::ItemStack;
+
+use super::{food::hand_item, player::Player};
+
+impl Player {
+    /// The items in the main and in the off hand
+    fn hand_items(&self) -> [Option<ItemStack>; 2] {
+        let mut inventory = self.inventory.lock();
+        [
+            *hand_item(&mut inventory, false),
+            *hand_item(&mut inventory, true),
+        ]
+    }
+
+    /// Shows the items in the player's hands, players who start to see the player get them.
+    /// Empty hands are left out
+    pub fn equipment_packets(&self) -> Vec<CSetEquipment> {
+        let entity_id = self.entity_id();
+        [EquipmentSlot::MainHand, EquipmentSlot::OffHand]
+            .into_iter()
+            .zip(self.hand_items())
+            .filter_map(|(slot, item)| {
+                item.map(|item| CSetEquipment::new(entity_id.into(), slot, Slot::from(&item)))
+            })
+            .collect()
+    }
+
+    /// Shows the other players the items in the player's hands once they changed, e.g. after
+    /// switching the held slot or picking something up
+    pub(super) fn tick_equipment(&self) {
+        let items = self.hand_items();
+        let shown = std::mem::replace(&mut *self.shown_equipment.lock(), items);
+        let entity_id = self.entity_id();
+        let world = self.living_entity.entity.world();
+        for ((slot, item), shown) in [EquipmentSlot::MainHand, EquipmentSlot::OffHand]
+            .into_iter()
+            .zip(items)
+            .zip(shown)
+        {
+            if item != shown {
+                world.broadcast_tracked(
+                    entity_id,
+                    &CSetEquipment::new(entity_id.into(), slot, Slot::from(item.as_ref())),
+                );
+            }
+        }
+    }
+
+    /// Swaps the items in the main and the off hand, like in vanilla spectators can't
+    pub fn swap_hands(&self) {
+        if self.gamemode.load() == GameMode::Spectator {
+            return;
+        }
+        {
+            let mut inventory = self.inventory.lock();
+            let main_hand = hand_item(&mut inventory, false).take();
+            let off_hand = std::mem::replace(hand_item(&mut inventory, true), main_hand);
+            *hand_item(&mut inventory, false) = off_hand;
+        }
+        // Eating and drawing a bow stop on the next tick, the item is in the other hand now
+        self.set_container_content(None);
+    }
+}
@@ -0,0 +1,112 @@
+use std::sync::atomic::Ordering;
+
+use pumpkin_world::game_rules;
+
+use super::player::{Experience, Player};
+
+/// Players drop this much experience per level when they die
+const DEATH_EXPERIENCE_PER_LEVEL: i32 = 7;
+/// Players never drop more experience than this when they die
+const MAX_DEATH_EXPERIENCE: i32 = 100;
+/// Players collect at most one experience orb every this many ticks, like in vanilla
+const PICKUP_DELAY: i32 = 2;
+
+/// The experience it takes to get from `level` to the next one
+pub const fn experience_to_next_level(level: i32) -> i32 {
+    if level >= 30 {
+        112 + (level - 30) * 9
+    } else if level >= 15 {
+        37 + (level - 15) * 5
+    } else {
+        7 + level * 2
+    }
+}
+
+/// The experience it takes to get from level 0 to `level`
+pub fn experience_for_level(level: i32) -> i32 {
+    (0..level.max(0)).map(experience_to_next_level).sum()
+}
+
+impl Experience {
+    /// The level and progress someone with `total` experience has
+    pub fn from_total(total: i32) -> Self {
+        let total = total.max(0);
+        let mut level = 0;
+        let mut left = total;
+        while left >= experience_to_next_level(level) {
+            left -= experience_to_next_level(level);
+            level += 1;
+        }
+        Self {
+            level,
+            progress: left as f32 / experience_to_next_level(level) as f32,
+            total,
+        }
+    }
+
+    /// The experience points collected towards the next level
+    pub fn points(&self) -> i32 {
+        (self.progress * experience_to_next_level(self.level) as f32).round() as i32
+    }
+
+    /// Adds or removes experience points, levels go up and down with them
+    pub fn add_points(self, points: i32) -> Self {
+        Self::from_total(self.total.saturating_add(points))
+    }
+
+    /// Adds or removes whole levels, the progress towards the next level stays
+    pub fn add_levels(self, levels: i32) -> Self {
+        let level = self.level.saturating_add(levels).max(0);
+        let points = self.points().min(experience_to_next_level(level) - 1);
+        Self {
+            level,
+            progress: points as f32 / experience_to_next_level(level) as f32,
+            total: experience_for_level(level) + points,
+        }
+    }
+}
+
+impl Player {
+    /// Gives the player experience points, negative points take them away
+    pub fn add_experience_points(&self, points: i32) {
+        self.set_experience(self.experience.load().add_points(points));
+    }
+
+    /// Gives the player whole levels, negative levels take them away
+    pub fn add_experience_levels(&self, levels: i32) {
+        self.set_experience(self.experience.load().add_levels(levels));
+    }
+
+    /// Whether the player can collect an experience orb right now, if so the next one has to
+    /// wait a bit
+    pub fn try_collect_experience(&self) -> bool {
+        if self.experience_pickup_delay.load(Ordering::Relaxed) > 0 {
+            return false;
+        }
+        self.experience_pickup_delay
+            .store(PICKUP_DELAY, Ordering::Relaxed);
+        true
+    }
+
+    pub(super) fn tick_experience_pickup(&self) {
+        let delay = self.experience_pickup_delay.load(Ordering::Relaxed);
+        if delay > 0 {
+            self.experience_pickup_delay
+                .store(delay - 1, Ordering::Relaxed);
+        }
+    }
+
+    /// Drops some of the player's experience where they died, the rest is lost once they
+    /// respawn. Nothing is dropped if they keep their inventory
+    pub(super) fn drop_death_experience(&self) {
+        let world = self.living_entity.entity.world();
+        if world.game_rule_bool(game_rules::KEEP_INVENTORY) {
+            return;
+        }
+        let experience = self.experience.load();
+        let amount = (experience.level * DEATH_EXPERIENCE_PER_LEVEL).min(MAX_DEATH_EXPERIENCE);
+        if amount > 0 {
+            world.drop_experience(self.living_entity.entity.pos.load(), amount);
+        }
+    }
+}
@@ -0,0 +1,327 @@
+use std::sync::atomic::Ordering;
+
+use pumpkin_config::BASIC_CONFIG;
+use pumpkin_core::{math::vector3::Vector3, Difficulty, GameMode};
+use pumpkin_inventory::player::PlayerInventory;
+use pumpkin_protocol::client::play::{CEntityStatus, CSetEntityMetadata, CSetHealth, Metadata};
+use pumpkin_world::{
+    game_rules,
+    global_registry::{self, ITEM_REGISTRY},
+    item::ItemStack,
+};
+
+use super::{
+    damage::DamageSource,
+    effect::ActiveEffect,
+    metadata,
+    player::{Hand, Player},
+};
+
+/// Every this much exhaustion costs a saturation or food point
+const EXHAUSTION_PER_POINT: f32 = 4.0;
+/// More exhaustion than this is not kept, like in vanilla
+const MAX_EXHAUSTION: f32 = 40.0;
+/// Ticks between healing while the food bar is full and there is saturation left
+const SATURATED_REGENERATION_TICKS: i32 = 10;
+/// Ticks between healing with at least `REGENERATION_FOOD` food
+const REGENERATION_TICKS: i32 = 80;
+/// Players with less food don't heal
+const REGENERATION_FOOD: i32 = 18;
+const MAX_FOOD: i32 = 20;
+/// Ticks between starvation damage while the food bar is empty
+const STARVATION_TICKS: i32 = 80;
+
+/// Exhaustion per block sprinted
+pub const SPRINT_EXHAUSTION: f32 = 0.1;
+/// Exhaustion per block swum
+pub const SWIM_EXHAUSTION: f32 = 0.01;
+pub const JUMP_EXHAUSTION: f32 = 0.05;
+pub const SPRINT_JUMP_EXHAUSTION: f32 = 0.2;
+/// Exhaustion per broken block
+pub const MINING_EXHAUSTION: f32 = 0.005;
+pub const ATTACK_EXHAUSTION: f32 = 0.1;
+
+/// Moves further than this between two packets are teleports, they don't make hungry
+const MAX_EXHAUSTING_MOVE: f64 = 10.0;
+
+/// The entity event which tells the client it finished eating
+const FINISH_USING_ITEM: i8 = 9;
+
+/// The food the player is eating and how long it still takes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Eating {
+    pub item_id: u32,
+    pub off_hand: bool,
+    pub ticks_left: u32,
+}
+
+/// The item in the player's main or off hand
+pub(crate) fn hand_item(inventory: &mut PlayerInventory, off_hand: bool) -> &mut Option<ItemStack> {
+    if off_hand {
+        inventory
+            .get_slot(45)
+            .expect("The off hand slot always exists")
+    } else {
+        inventory.held_item_mut()
+    }
+}
+
+impl Player {
+    /// Makes the player hungry, players in creative and spectator mode don't get hungry
+    pub fn add_exhaustion(&self, exhaustion: f32) {
+        if matches!(
+            self.gamemode.load(),
+            GameMode::Creative | GameMode::Spectator
+        ) {
+            return;
+        }
+        self.food_exhaustion
+            .store((self.food_exhaustion.load() + exhaustion).min(MAX_EXHAUSTION));
+    }
+
+    /// Turns exhaustion into hunger and heals the player while they are fed, like in vanilla
+    pub(super) fn tick_food(&self) {
+        let health = self.living_entity.health.load();
+        if health <= 0.0 {
+            return;
+        }
+        let mut food = self.food.load(Ordering::Relaxed);
+        let mut saturation = self.food_saturation.load();
+        let exhaustion = self.food_exhaustion.load();
+        let hungrier = exhaustion > EXHAUSTION_PER_POINT;
+        if hungrier {
+            self.food_exhaustion
+                .store(exhaustion - EXHAUSTION_PER_POINT);
+            if saturation > 0.0 {
+                saturation = (saturation - 1.0).max(0.0);
+            } else if BASIC_CONFIG.get().default_difficulty != Difficulty::Peaceful {
+                food = (food - 1).max(0);
+            }
+            self.food.store(food, Ordering::Relaxed);
+            self.food_saturation.store(saturation);
+        }
+
+        let world = self.living_entity.entity.world();
+        let regenerates = world.game_rule_bool(game_rules::NATURAL_REGENERATION)
+            && health < self.living_entity.max_health();
+        let healed = if regenerates && saturation > 0.0 && food >= MAX_FOOD {
+            // Well fed players heal fast and use up their saturation for it
+            self.tick_regeneration(SATURATED_REGENERATION_TICKS, || {
+                let amount = saturation.min(6.0);
+                (amount / 6.0, amount)
+            })
+        } else if regenerates && food >= REGENERATION_FOOD {
+            self.tick_regeneration(REGENERATION_TICKS, || (1.0, 6.0))
+        } else if food <= 0 {
+            self.tick_starvation(health);
+            false
+        } else {
+            self.food_tick_timer.store(0, Ordering::Relaxed);
+            false
+        };
+        // Healing sends the health already
+        if hungrier && !healed {
+            self.client
+                .send_packet(&CSetHealth::new(health, food.into(), saturation));
+        }
+    }
+
+    /// Heals the player once the timer reached `ticks`, `heal` returns the health and the
+    /// exhaustion it costs. Returns whether the player healed
+    fn tick_regeneration(&self, ticks: i32, heal: impl FnOnce() -> (f32, f32)) -> bool {
+        if self.food_tick_timer.fetch_add(1, Ordering::Relaxed) + 1 < ticks {
+            return false;
+        }
+        self.food_tick_timer.store(0, Ordering::Relaxed);
+        let (health, exhaustion) = heal();
+        self.heal(health);
+        self.add_exhaustion(exhaustion);
+        true
+    }
+
+    /// Hurts the player every few seconds while their food bar is empty. On easy they keep at
+    /// least half of their health, on normal half a heart and on hard they starve to death
+    fn tick_starvation(&self, health: f32) {
+        if self.food_tick_timer.fetch_add(1, Ordering::Relaxed) + 1 < STARVATION_TICKS {
+            return;
+        }
+        self.food_tick_timer.store(0, Ordering::Relaxed);
+        let starves = match BASIC_CONFIG.get().default_difficulty {
+            Difficulty::Peaceful => false,
+            Difficulty::Easy => health > 10.0,
+            Difficulty::Normal => health > 1.0,
+            Difficulty::Hard => true,
+        };
+        if starves {
+            self.damage(1.0, DamageSource::Starve);
+        }
+    }
+
+    /// Makes the player hungry for moving from `from` to `to`, sprinting, swimming and
+    /// jumping cost food while walking is free
+    pub(crate) fn add_movement_exhaustion(
+        &self,
+        from: Vector3<f64>,
+        to: Vector3<f64>,
+        was_on_ground: bool,
+    ) {
+        if self.awaiting_teleport.lock().is_some() {
+            return;
+        }
+        let entity = &self.living_entity.entity;
+        let sprinting = entity.sprinting.load(Ordering::Relaxed);
+        let on_ground = entity.on_ground.load(Ordering::Relaxed);
+        if was_on_ground && !on_ground && to.y > from.y {
+            self.add_exhaustion(if sprinting {
+                SPRINT_JUMP_EXHAUSTION
+            } else {
+                JUMP_EXHAUSTION
+            });
+        }
+        let delta = to.sub(&from);
+        let distance = delta.length();
+        if distance > MAX_EXHAUSTING_MOVE {
+            return;
+        }
+        let in_water = entity
+            .world()
+            .level
+            .get_block(&entity.block_pos.load())
+            .is_some_and(|block| {
+                block
+                    .registry_state()
+                    .is_some_and(|(registry_id, _)| registry_id == "minecraft:water")
+            });
+        if in_water {
+            self.add_exhaustion(SWIM_EXHAUSTION * distance as f32);
+        } else if sprinting {
+            let horizontal = delta.x.hypot(delta.z);
+            self.add_exhaustion(SPRINT_EXHAUSTION * horizontal as f32);
+        }
+    }
+
+    /// Whether the player may start eating `item`, full players only eat some food. Players
+    /// which can't be hurt may always eat
+    fn can_eat(&self, item: &ItemStack) -> bool {
+        item.food().is_some_and(|food| {
+            food.can_always_eat
+                || self.food.load(Ordering::Relaxed) < MAX_FOOD
+                || matches!(
+                    self.gamemode.load(),
+                    GameMode::Creative | GameMode::Spectator
+                )
+        })
+    }
+
+    /// Starts eating the food in `hand`, returns false if the item is no food or the player is
+    /// not hungry
+    pub fn start_eating(&self, hand: &Hand) -> bool {
+        let off_hand = matches!(hand, Hand::Off);
+        let item = *hand_item(&mut self.inventory.lock(), off_hand);
+        let Some(item) = item.filter(|item| self.can_eat(item)) else {
+            return false;
+        };
+        let Some(food) = item.food() else {
+            return false;
+        };
+        self.eating.store(Some(Eating {
+            item_id: item.item_id,
+            off_hand,
+            ticks_left: food.eat_ticks(),
+        }));
+        self.set_hand_active(Some(off_hand));
+        true
+    }
+
+    /// Stops eating before the food is eaten, e.g. when the player lets go of the use button
+    pub fn stop_eating(&self) {
+        if self.eating.take().is_some() {
+            self.set_hand_active(None);
+        }
+    }
+
+    /// Shows the other players which hand the player uses, they see them eat with it
+    pub(super) fn set_hand_active(&self, off_hand: Option<bool>) {
+        let flags: i8 = match off_hand {
+            None => 0,
+            Some(false) => 0x01,
+            Some(true) => 0x03,
+        };
+        let packet = CSetEntityMetadata::new(
+            self.entity_id().into(),
+            Metadata::new(metadata::LIVING_FLAGS, 0.into(), flags),
+        );
+        self.living_entity
+            .entity
+            .world()
+            .broadcast_packet_expect(&[self.client.id], &packet);
+    }
+
+    /// Counts down the food the player is eating, they stop if they switch to another item
+    pub(super) fn tick_eating(&self) {
+        let Some(mut eating) = self.eating.load() else {
+            return;
+        };
+        let still_held = hand_item(&mut self.inventory.lock(), eating.off_hand)
+            .is_some_and(|item| item.item_id == eating.item_id);
+        if !still_held || self.living_entity.health.load() <= 0.0 {
+            self.stop_eating();
+            return;
+        }
+        eating.ticks_left = eating.ticks_left.saturating_sub(1);
+        if eating.ticks_left > 0 {
+            self.eating.store(Some(eating));
+            return;
+        }
+        self.finish_eating(eating);
+    }
+
+    /// Eats one item of the stack, the player gets its food points and what is left of it,
+    /// e.g. a bowl
+    fn finish_eating(&self, eating: Eating) {
+        self.eating.store(None);
+        self.set_hand_active(None);
+        let Some(food) = ItemStack::new(1, eating.item_id).food() else {
+            return;
+        };
+        if self.gamemode.load() != GameMode::Creative {
+            let mut inventory = self.inventory.lock();
+            let slot = hand_item(&mut inventory, eating.off_hand);
+            if let Some(item) = slot {
+                item.item_count = item.item_count.saturating_sub(1);
+                if item.item_count == 0 {
+                    *slot = None;
+                }
+            }
+            let left = food.using_converts_to.as_ref().and_then(|converts_to| {
+                global_registry::find_protocol_id(ITEM_REGISTRY, &converts_to.id)
+            });
+            if let Some(left) = left {
+                let mut left = Some(ItemStack::new(1, left));
+                if slot.is_none() {
+                    *slot = left;
+                } else {
+                    // What doesn't fit is dropped
+                    inventory.insert(&mut left);
+                    if let Some(left) = left {
+                        self.drop_item(left, false);
+                    }
+                }
+            }
+        }
+        for effect in &food.effects {
+            if rand::random::<f32>() >= effect.probability {
+                continue;
+            }
+            if let Some(effect) = ActiveEffect::from_item(&effect.effect) {
+                self.add_effect(effect);
+            }
+        }
+        let food_level = (self.food.load(Ordering::Relaxed) + food.nutrition).min(MAX_FOOD);
+        let saturation = (self.food_saturation.load() + food.saturation).min(food_level as f32);
+        self.client
+            .send_packet(&CEntityStatus::new(self.entity_id(), FINISH_USING_ITEM));
+        self.set_health(self.living_entity.health.load(), food_level, saturation);
+        self.set_container_content(None);
+    }
+}
@@ -0,0 +1,85 @@
+use std::f64::consts::TAU;
+
+use pumpkin_core::{math::vector3::Vector3, GameMode};
+use pumpkin_world::{game_rules, item::ItemStack};
+
+use super::player::Player;
+
+/// Items are thrown this far below the eyes, like in vanilla
+const THROW_HEIGHT: f64 = 0.3;
+
+impl Player {
+    /// Throws an item away from the player. Thrown items fly where the player looks, scattered
+    /// ones fall in a random direction, like the inventory of a dead player
+    pub fn drop_item(&self, item: ItemStack, scatter: bool) {
+        let entity = &self.living_entity.entity;
+        let position = entity.pos.load().add(&Vector3::new(
+            0.0,
+            f64::from(entity.standing_eye_height) - THROW_HEIGHT,
+            0.0,
+        ));
+        let velocity = if scatter {
+            let speed = rand::random::<f64>() * 0.5;
+            let angle = rand::random::<f64>() * TAU;
+            Vector3::new(-angle.sin() * speed, 0.2, angle.cos() * speed)
+        } else {
+            let yaw = f64::from(entity.yaw.load()).to_radians();
+            let pitch = f64::from(entity.pitch.load()).to_radians();
+            // A little randomness, so items thrown at once don't land on top of each other
+            let angle = rand::random::<f64>() * TAU;
+            let spread = rand::random::<f64>() * 0.02;
+            Vector3::new(
+                -yaw.sin() * pitch.cos() * 0.3 + angle.cos() * spread,
+                -pitch.sin() * 0.3 + 0.1 + (rand::random::<f64>() - rand::random::<f64>()) * 0.1,
+                yaw.cos() * pitch.cos() * 0.3 + angle.sin() * spread,
+            )
+        };
+        entity.world().throw_item(position, velocity, item);
+    }
+
+    /// Throws one of the held items away, or the whole stack
+    pub fn drop_held_item(&self, whole_stack: bool) {
+        if self.gamemode.load() == GameMode::Spectator {
+            return;
+        }
+        let dropped = {
+            let mut inventory = self.inventory.lock();
+            let held = inventory.held_item_mut();
+            let Some(item) = held.as_mut() else {
+                return;
+            };
+            let count = if whole_stack { item.item_count } else { 1 };
+            item.item_count -= count;
+            let dropped = item.with_count(count);
+            if item.item_count == 0 {
+                *held = None;
+            }
+            dropped
+        };
+        self.drop_item(dropped, false);
+        self.set_container_content(None);
+    }
+
+    /// Scatters the whole inventory and the carried item around the player, unless players
+    /// keep their inventory
+    pub(super) fn drop_inventory(&self) {
+        let world = self.living_entity.entity.world();
+        if world.game_rule_bool(game_rules::KEEP_INVENTORY) {
+            return;
+        }
+        let mut items: Vec<ItemStack> = self
+            .inventory
+            .lock()
+            .slots_mut()
+            .into_iter()
+            // The crafting output is made of the grid, it is not an item of its own
+            .skip(1)
+            .filter_map(Option::take)
+            .collect();
+        items.extend(self.carried_item.take());
+        for item in items {
+            self.drop_item(item, true);
+        }
+        self.set_container_content(None);
+    }
+}
@@ -1,20 +1,60 @@
+use std::sync::atomic::{AtomicI32, Ordering};
+
 use crossbeam::atomic::AtomicCell;
+use parking_lot::Mutex;
 use pumpkin_protocol::client::play::{CEntityStatus, CSetEntityMetadata, Metadata};
 
-use super::Entity;
+use pumpkin_entity::effect::StatusEffect;
+
+use super::{
+    attributes::Attributes,
+    damage::{damage_after_protection, DamageSource},
+    effect::ActiveEffect,
+    metadata, Entity,
+};
+
+/// The health of players and most mobs when they spawn
+pub const MAX_HEALTH: f32 = 20.0;
+/// Ticks in which an entity which just got hurt only takes damage higher than the last one
+const INVULNERABLE_TICKS: i32 = 20;
 
 /// Represents a Living Entity (e.g. Player, Zombie, Enderman...)
 pub struct LivingEntity {
     pub entity: Entity,
     /// The entity's current health level.
     pub health: AtomicCell<f32>,
+    /// Extra health which is lost before the actual health, the golden hearts
+    pub absorption: AtomicCell<f32>,
+    /// Counts down after the entity got hurt, see `INVULNERABLE_TICKS`
+    pub invulnerable_ticks: AtomicI32,
+    /// The damage which hurt the entity last, before armor
+    pub last_damage: AtomicCell<f32>,
+    /// How far the entity fell since it last stood on the ground
+    pub fall_distance: AtomicCell<f64>,
+    /// The status effects the entity has right now, at most one of each kind
+    pub effects: Mutex<Vec<ActiveEffect>>,
+    /// The base values and modifiers of the attributes, e.g. the max health
+    pub attributes: Mutex<Attributes>,
 }
 
 impl LivingEntity {
-    pub const fn new(entity: Entity) -> Self {
+    pub fn new(entity: Entity) -> Self {
         Self {
             entity,
-            health: AtomicCell::new(20.0),
+            health: AtomicCell::new(MAX_HEALTH),
+            absorption: AtomicCell::new(0.0),
+            invulnerable_ticks: AtomicI32::new(0),
+            last_damage: AtomicCell::new(0.0),
+            fall_distance: AtomicCell::new(0.0),
+            effects: Mutex::new(Vec::new()),
+            attributes: Mutex::new(Attributes::default()),
+        }
+    }
+
+    /// Runs every tick the entity is in a world
+    pub fn tick(&self) {
+        if self.invulnerable_ticks.load(Ordering::Relaxed) > 0 {
+            self.invulnerable_ticks.fetch_sub(1, Ordering::Relaxed);
         }
     }
 
@@ -22,25 +62,95 @@ impl LivingEntity {
         self.health.store(health);
         // tell everyone entities health changed
         self.entity
-            .world
+            .world()
             .broadcast_packet_all(&CSetEntityMetadata::new(
                 self.entity.entity_id.into(),
-                Metadata::new(9, 3.into(), health),
+                Metadata::new(metadata::HEALTH, 3.into(), health),
             ));
     }
 
+    /// Heals the entity up to its max health, dead entities stay dead
+    pub fn heal(&self, amount: f32) {
+        let health = self.health.load();
+        let max_health = self.max_health();
+        if health <= 0.0 || health >= max_health {
+            return;
+        }
+        self.set_health((health + amount).min(max_health));
+    }
+
+    /// Hurts the entity, `armor` and `toughness` reduce the damage like in vanilla, then the
+    /// resistance effect and the `protection` points of the enchantments, unless `source`
+    /// bypasses them. Absorption is lost first. Returns false if the entity was not hurt, because
+    /// it still is invulnerable from the last damage, resists fire or is already dead
+    pub fn damage(
+        &self,
+        amount: f32,
+        source: &DamageSource,
+        armor: f32,
+        toughness: f32,
+        protection: i32,
+    ) -> bool {
+        if self.health.load() <= 0.0 || amount <= 0.0 {
+            return false;
+        }
+        if source.is_fire() && self.effect(StatusEffect::FireResistance).is_some() {
+            return false;
+        }
+        // Only the part higher than the last damage hurts while the entity is invulnerable
+        let amount = if self.invulnerable_ticks.load(Ordering::Relaxed) > INVULNERABLE_TICKS / 2
+            && !source.bypasses_invulnerability()
+        {
+            let last_damage = self.last_damage.load();
+            if amount <= last_damage {
+                return false;
+            }
+            self.last_damage.store(amount);
+            amount - last_damage
+        } else {
+            self.last_damage.store(amount);
+            self.invulnerable_ticks
+                .store(INVULNERABLE_TICKS, Ordering::Relaxed);
+            amount
+        };
+        let amount = if source.bypasses_armor() {
+            amount
+        } else {
+            damage_after_armor(amount, armor, toughness)
+        };
+        let amount = self.damage_after_resistance(amount, source);
+        let amount = if source.bypasses_enchantments() {
+            amount
+        } else {
+            damage_after_protection(amount, protection)
+        };
+        let absorption = self.absorption.load();
+        let absorbed = amount.min(absorption);
+        self.absorption.store(absorption - absorbed);
+        self.set_health((self.health.load() - (amount - absorbed)).max(0.0));
+        true
+    }
+
     /// Kills the Entity
     ///
     /// This is similar to `kill` but Spawn Particles, Animation and plays death sound
     pub fn kill(&self) {
         // Spawns death smoke particles
         self.entity
-            .world
+            .world()
             .broadcast_packet_all(&CEntityStatus::new(self.entity.entity_id, 60));
         // Plays the death sound and death animation
         self.entity
-            .world
+            .world()
             .broadcast_packet_all(&CEntityStatus::new(self.entity.entity_id, 3));
         self.entity.remove();
     }
 }
+
+/// The damage left after armor, every armor point takes 4% of it. Toughness keeps high damage
+/// from going through the armor
+pub fn damage_after_armor(amount: f32, armor: f32, toughness: f32) -> f32 {
+    let breaking = 2.0 + toughness / 4.0;
+    let armor = (armor - amount / breaking).clamp(armor * 0.2, 20.0);
+    amount * (1.0 - armor / 25.0)
+}
@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+
+use pumpkin_core::text::TextComponent;
+use pumpkin_protocol::{client::play::MetadataValue, VarInt};
+
+/// The index of the entity flags, see `Flag`
+pub const FLAGS: u8 = 0;
+pub const POSE: u8 = 6;
+/// The indices of living entities and players
+pub const LIVING_FLAGS: u8 = 8;
+pub const HEALTH: u8 = 9;
+pub const SLEEPING_POSITION: u8 = 14;
+pub const ABSORPTION: u8 = 15;
+pub const SKIN_PARTS: u8 = 17;
+pub const MAIN_HAND: u8 = 18;
+
+/// The name shown above the entity, e.g. from a name tag
+pub const CUSTOM_NAME: DataKey<Option<String>> = DataKey::new(2, None);
+/// Whether a creeper swells up, 1 while it does and -1 otherwise
+pub const CREEPER_STATE: DataKey<i32> = DataKey::new(16, -1);
+
+/// An entry of the entity metadata with the type of its value and the value entities start
+/// with, like in vanilla
+pub struct DataKey<T> {
+    pub index: u8,
+    default: T,
+}
+
+impl<T> DataKey<T> {
+    const fn new(index: u8, default: T) -> Self {
+        Self { index, default }
+    }
+}
+
+/// A value of the entity metadata as the Server keeps it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DataValue {
+    VarInt(i32),
+    OptionalText(Option<String>),
+}
+
+impl DataValue {
+    /// The value as it is sent, it borrows the text
+    pub fn to_metadata(&self) -> MetadataValue<'_> {
+        match self {
+            Self::VarInt(value) => MetadataValue::VarInt(VarInt(*value)),
+            Self::OptionalText(text) => {
+                MetadataValue::OptionalText(text.as_deref().map(TextComponent::text))
+            }
+        }
+    }
+}
+
+/// The types values of the entity metadata can have
+pub trait Tracked: Clone + PartialEq {
+    fn into_value(self) -> DataValue;
+    fn from_value(value: &DataValue) -> Option<Self>;
+}
+
+impl Tracked for i32 {
+    fn into_value(self) -> DataValue {
+        DataValue::VarInt(self)
+    }
+
+    fn from_value(value: &DataValue) -> Option<Self> {
+        match value {
+            DataValue::VarInt(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl Tracked for Option<String> {
+    fn into_value(self) -> DataValue {
+        DataValue::OptionalText(self)
+    }
+
+    fn from_value(value: &DataValue) -> Option<Self> {
+        match value {
+            DataValue::OptionalText(text) => Some(text.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// The entries of the entity metadata which differ from their defaults, by index. Players who
+/// start to see the entity get all of them, changes are sent right away
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EntityData {
+    values: BTreeMap<u8, DataValue>,
+}
+
+impl EntityData {
+    pub fn get<T: Tracked>(&self, key: &DataKey<T>) -> T {
+        self.values
+            .get(&key.index)
+            .and_then(T::from_value)
+            .unwrap_or_else(|| key.default.clone())
+    }
+
+    /// Changes an entry, returns false if it had the value already
+    pub fn set<T: Tracked>(&mut self, key: &DataKey<T>, value: T) -> bool {
+        if self.get(key) == value {
+            return false;
+        }
+        if value == key.default {
+            self.values.remove(&key.index);
+        } else {
+            self.values.insert(key.index, value.into_value());
+        }
+        true
+    }
+
+    /// An entry with its index, to send it
+    pub fn entry<T: Tracked>(&self, key: &DataKey<T>) -> (u8, DataValue) {
+        (key.index, self.get(key).into_value())
+    }
+
+    pub fn entries(&self) -> Vec<(u8, DataValue)> {
+        self.values
+            .iter()
+            .map(|(index, value)| (*index, value.clone()))
+            .collect()
+    }
+}
+
+/// The entries as they are sent, they borrow the texts
+pub fn metadata_values(entries: &[(u8, DataValue)]) -> Vec<(u8, MetadataValue<'_>)> {
+    entries
+        .iter()
+        .map(|(index, value)| (*index, value.to_metadata()))
+        .collect()
+}
@@ -1,8 +1,12 @@
-use std::sync::{atomic::AtomicBool, Arc};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 
 use crossbeam::atomic::AtomicCell;
 use num_derive::{FromPrimitive, ToPrimitive};
 use num_traits::ToPrimitive;
+use parking_lot::RwLock;
 use pumpkin_core::math::{
     get_section_cord, position::WorldPosition, vector2::Vector2, vector3::Vector3,
 };
@@ -14,8 +18,24 @@ use pumpkin_protocol::{
 
 use crate::world::World;
 
+pub mod advancement;
+pub mod attributes;
+pub mod combat;
+pub mod damage;
+pub mod effect;
+pub mod equipment;
+pub mod experience;
+pub mod food;
+pub mod item_drop;
 pub mod living;
+pub mod metadata;
+pub mod movement;
 pub mod player;
+pub mod player_data;
+pub mod projectile;
+pub mod sleep;
+pub mod stats;
+pub mod vehicle;
 
 /// Represents a not living Entity (e.g. Item, Egg, Snowball...)
 pub struct Entity {
@@ -23,8 +43,8 @@ pub struct Entity {
     pub entity_id: EntityId,
     /// The type of entity (e.g., player, zombie, item)
     pub entity_type: EntityType,
-    /// The world in which the entity exists.
-    pub world: Arc<World>,
+    /// The world in which the entity exists, changes when a player switches worlds
+    world: RwLock<Arc<World>>,
     /// The entity's current health level.
 
     /// The entity's current position in the world
@@ -40,6 +60,10 @@ pub struct Entity {
     pub sprinting: AtomicBool,
     /// Indicates whether the entity is flying due to a fall
     pub fall_flying: AtomicBool,
+    /// Indicates whether the entity glows through walls, e.g. from the glowing effect
+    pub glowing: AtomicBool,
+    /// Indicates whether the entity is invisible, e.g. from the invisibility effect
+    pub invisible: AtomicBool,
 
     /// The entity's current velocity vector, aka Knockback
     pub velocity: AtomicCell<Vector3<f64>>,
@@ -74,10 +98,12 @@ impl Entity {
             block_pos: AtomicCell::new(WorldPosition(Vector3::new(0, 0, 0))),
             chunk_pos: AtomicCell::new(Vector2::new(0, 0)),
             sneaking: AtomicBool::new(false),
-            world,
+            world: RwLock::new(world),
             // TODO: Load this from previous instance
             sprinting: AtomicBool::new(false),
             fall_flying: AtomicBool::new(false),
+            glowing: AtomicBool::new(false),
+            invisible: AtomicBool::new(false),
             yaw: AtomicCell::new(0.0),
             head_yaw: AtomicCell::new(0.0),
             pitch: AtomicCell::new(0.0),
@@ -87,6 +113,16 @@ impl Entity {
         }
     }
 
+    /// The world in which the entity exists
+    pub fn world(&self) -> Arc<World> {
+        self.world.read().clone()
+    }
+
+    /// Moves the entity into another world, without telling anyone
+    pub fn set_world(&self, world: Arc<World>) {
+        *self.world.write() = world;
+    }
+
     /// Updates the entity's position, block position, and chunk position.
     ///
     /// This function calculates the new position, block position, and chunk position based on the provided coordinates. If any of these values change, the corresponding fields are updated.
@@ -123,7 +159,7 @@ impl Entity {
 
     /// Removes the Entity from their current World
     pub fn remove(&self) {
-        self.world.remove_entity(self);
+        self.world().remove_entity(self);
     }
 
     /// Applies knockback to the entity, following vanilla Minecraft's mechanics.
@@ -151,56 +187,85 @@ impl Entity {
         ));
     }
 
-    pub async fn set_sneaking(&self, sneaking: bool) {
-        assert!(self.sneaking.load(std::sync::atomic::Ordering::Relaxed) != sneaking);
-        self.sneaking
-            .store(sneaking, std::sync::atomic::Ordering::Relaxed);
-        self.set_flag(Flag::Sneaking, sneaking).await;
-        // if sneaking {
-        //     self.set_pose(EntityPose::Crouching).await;
-        // } else {
-        //     self.set_pose(EntityPose::Standing).await;
-        // }
+    /// Starts or stops sneaking, the players who see the entity are told
+    pub fn set_sneaking(&self, sneaking: bool) {
+        if self.sneaking.swap(sneaking, Ordering::Relaxed) != sneaking {
+            self.send_flags();
+        }
     }
 
-    pub async fn set_sprinting(&self, sprinting: bool) {
-        assert!(self.sprinting.load(std::sync::atomic::Ordering::Relaxed) != sprinting);
-        self.sprinting
-            .store(sprinting, std::sync::atomic::Ordering::Relaxed);
-        self.set_flag(Flag::Sprinting, sprinting).await;
+    /// Starts or stops sprinting, the players who see the entity are told
+    pub fn set_sprinting(&self, sprinting: bool) {
+        if self.sprinting.swap(sprinting, Ordering::Relaxed) != sprinting {
+            self.send_flags();
+        }
     }
 
     pub fn check_fall_flying(&self) -> bool {
-        !self.on_ground.load(std::sync::atomic::Ordering::Relaxed)
+        !self.on_ground.load(Ordering::Relaxed)
+    }
+
+    /// Starts or stops gliding, the players who see the entity are told
+    pub fn set_fall_flying(&self, fall_flying: bool) {
+        if self.fall_flying.swap(fall_flying, Ordering::Relaxed) != fall_flying {
+            self.send_flags();
+        }
+    }
+
+    /// Starts or stops glowing, the players who see the entity are told
+    pub fn set_glowing(&self, glowing: bool) {
+        if self.glowing.swap(glowing, Ordering::Relaxed) != glowing {
+            self.send_flags();
+        }
+    }
+
+    /// Makes the entity invisible or visible again, the players who see the entity are told
+    pub fn set_invisible(&self, invisible: bool) {
+        if self.invisible.swap(invisible, Ordering::Relaxed) != invisible {
+            self.send_flags();
+        }
+    }
+
+    /// The flags of the entity metadata, one bit for every `Flag`
+    pub fn flags(&self) -> i8 {
+        [
+            (Flag::Sneaking, &self.sneaking),
+            (Flag::Sprinting, &self.sprinting),
+            (Flag::Invisible, &self.invisible),
+            (Flag::Glowing, &self.glowing),
+            (Flag::FallFlying, &self.fall_flying),
+        ]
+        .into_iter()
+        .filter(|(_, value)| value.load(Ordering::Relaxed))
+        .fold(0, |flags, (flag, _)| flags | 1 << flag.to_u32().unwrap())
+    }
+
+    pub fn flags_packet(&self) -> CSetEntityMetadata<i8> {
+        CSetEntityMetadata::new(
+            self.entity_id.into(),
+            Metadata::new(metadata::FLAGS, 0.into(), self.flags()),
+        )
     }
 
-    pub async fn set_fall_flying(&self, fall_flying: bool) {
-        assert!(self.fall_flying.load(std::sync::atomic::Ordering::Relaxed) != fall_flying);
-        self.fall_flying
-            .store(fall_flying, std::sync::atomic::Ordering::Relaxed);
-        self.set_flag(Flag::FallFlying, fall_flying).await;
+    /// All flags are sent at once, so a change of one keeps the others
+    fn send_flags(&self) {
+        self.world()
+            .broadcast_tracked(self.entity_id, &self.flags_packet());
     }
 
-    async fn set_flag(&self, flag: Flag, value: bool) {
-        let index = flag.to_u32().unwrap();
-        let mut b = 0i8;
-        if value {
-            b |= 1 << index;
-        } else {
-            b &= !(1 << index);
+    /// Changes the pose, the players who see the entity are told
+    pub fn set_pose(&self, pose: EntityPose) {
+        if self.pose.swap(pose) != pose {
+            self.world()
+                .broadcast_tracked(self.entity_id, &self.pose_packet());
         }
-        let packet = CSetEntityMetadata::new(self.entity_id.into(), Metadata::new(0, 0.into(), b));
-        self.world.broadcast_packet_all(&packet);
     }
 
-    pub async fn set_pose(&self, pose: EntityPose) {
-        self.pose.store(pose);
-        let pose = pose as i32;
-        let packet = CSetEntityMetadata::<VarInt>::new(
+    pub fn pose_packet(&self) -> CSetEntityMetadata<VarInt> {
+        CSetEntityMetadata::new(
             self.entity_id.into(),
-            Metadata::new(6, 20.into(), (pose).into()),
-        );
-        self.world.broadcast_packet_all(&packet)
+            Metadata::new(metadata::POSE, 20.into(), VarInt(self.pose.load() as i32)),
+        )
     }
 }
 
@@ -214,17 +279,17 @@ impl Entity {
 /// This enum provides a more type-safe and readable way to represent entity flags compared to using raw integer values.
 pub enum Flag {
     /// Indicates if the entity is on fire.
-    OnFire,
+    OnFire = 0,
     /// Indicates if the entity is sneaking.
-    Sneaking,
-    /// Indicates if the entity is sprinting.
-    Sprinting,
+    Sneaking = 1,
+    /// Indicates if the entity is sprinting, the bit before is unused.
+    Sprinting = 3,
     /// Indicates if the entity is swimming.
-    Swimming,
+    Swimming = 4,
     /// Indicates if the entity is invisible.
-    Invisible,
+    Invisible = 5,
     /// Indicates if the entity is glowing.
-    Glowing,
+    Glowing = 6,
     /// Indicates if the entity is flying due to a fall.
-    FallFlying,
+    FallFlying = 7,
 }
@@ -0,0 +1,178 @@
+use std::{sync::atomic::Ordering, time::Instant};
+
+use pumpkin_config::{messages::format_message, ADVANCED_CONFIG, MESSAGES};
+use pumpkin_core::{math::vector3::Vector3, text::TextComponent, GameMode};
+use pumpkin_entity::effect::StatusEffect;
+
+use super::player::Player;
+
+/// Milliseconds per tick, lagging players may move further with one move
+const MILLIS_PER_TICK: u128 = 50;
+/// Players who don't fall at least this much per move float, like in vanilla
+const MIN_FALL: f64 = -0.03125;
+/// Ticks until one violation is forgiven
+const VIOLATION_DECAY_TICKS: u32 = 20;
+
+/// What the Server knows about the moves of a player to check the next one
+pub struct MovementCheck {
+    /// When the last move arrived
+    last_move: Instant,
+    /// Where the player last stood on something, floating players are put back there
+    last_ground: Option<Vector3<f64>>,
+    /// Whether the player floated in the air with their last move
+    floating: bool,
+    floating_ticks: u32,
+    /// How often the player was put back lately
+    violations: u32,
+    /// Ticks since the last violation was forgiven
+    decay_ticks: u32,
+}
+
+impl Default for MovementCheck {
+    fn default() -> Self {
+        Self {
+            last_move: Instant::now(),
+            last_ground: None,
+            floating: false,
+            floating_ticks: 0,
+            violations: 0,
+            decay_ticks: 0,
+        }
+    }
+}
+
+impl Player {
+    /// Checks a move the client sent, moves which are too fast or go through blocks put the
+    /// player back. Returns false if the Server keeps the position it has, moves sent before
+    /// the client got teleported are ignored too
+    pub(crate) fn check_move(&self, to: Vector3<f64>) -> bool {
+        if self.awaiting_teleport.lock().is_some() {
+            return false;
+        }
+        let config = &ADVANCED_CONFIG.get().movement;
+        let entity = &self.living_entity.entity;
+        let from = entity.pos.load();
+        // Lagging players send several ticks of movement at once
+        let ticks = {
+            let mut check = self.movement_check.lock();
+            let now = Instant::now();
+            let elapsed = now.duration_since(check.last_move).as_millis() / MILLIS_PER_TICK;
+            check.last_move = now;
+            elapsed.clamp(1, u128::from(config.max_lag_ticks.max(1))) as f64
+        };
+        if !config.validate {
+            return true;
+        }
+        let fall_flying = entity.fall_flying.load(Ordering::Relaxed);
+        let max_speed = if fall_flying {
+            config.max_elytra_speed
+        } else {
+            config.max_speed
+        };
+        // Knockback may push players further
+        let max_distance = (max_speed + entity.velocity.load().length()) * ticks;
+        let delta = to.sub(&from);
+        if delta.length_squared() > max_distance * max_distance {
+            self.reject_move(from, "moved too quickly");
+            return false;
+        }
+        let gamemode = self.gamemode.load();
+        if gamemode != GameMode::Spectator && self.moves_into_blocks(&delta) {
+            self.reject_move(from, "moved into a block");
+            return false;
+        }
+        // Like in vanilla, anything right around or below the player holds them up
+        let mut around = self
+            .bounding_box()
+            .offset(&delta)
+            .expand(0.0625, 0.0625, 0.0625);
+        around.min_y -= 0.55;
+        let floating = delta.y >= MIN_FALL
+            && gamemode != GameMode::Spectator
+            && !self.abilities.lock().allow_flying
+            && !fall_flying
+            && self
+                .living_entity
+                .effect(StatusEffect::Levitation)
+                .is_none()
+            && entity.world().only_air(&around);
+        let mut check = self.movement_check.lock();
+        check.floating = floating;
+        if !floating {
+            check.floating_ticks = 0;
+            check.last_ground = Some(to);
+        }
+        true
+    }
+
+    /// Whether the player's box runs into blocks it didn't touch before, players who are stuck
+    /// may still move out
+    fn moves_into_blocks(&self, delta: &Vector3<f64>) -> bool {
+        let world = self.living_entity.entity.world();
+        let bounding_box = self.bounding_box();
+        world.collides(&bounding_box.offset(delta)) && !world.collides(&bounding_box)
+    }
+
+    /// Counts how long the player floats in the air and forgives violations over time
+    pub(super) fn tick_movement_check(&self) {
+        let alive = self.living_entity.health.load() > 0.0;
+        let riding = self.vehicle().is_some();
+        let sleeping = self.sleeping.load().is_some();
+        let last_ground = {
+            let mut check = self.movement_check.lock();
+            if check.violations > 0 {
+                check.decay_ticks += 1;
+                if check.decay_ticks >= VIOLATION_DECAY_TICKS {
+                    check.decay_ticks = 0;
+                    check.violations -= 1;
+                }
+            }
+            if !check.floating || !alive || riding || sleeping {
+                check.floating_ticks = 0;
+                return;
+            }
+            check.floating_ticks += 1;
+            if check.floating_ticks <= ADVANCED_CONFIG.get().movement.max_floating_ticks {
+                return;
+            }
+            check.floating = false;
+            check.floating_ticks = 0;
+            check.last_ground
+        };
+        let position = last_ground.unwrap_or_else(|| self.living_entity.entity.pos.load());
+        self.reject_move(position, "flew");
+    }
+
+    /// Puts the player back to `position`, they are kicked once they got put back too often
+    fn reject_move(&self, position: Vector3<f64>, reason: &str) {
+        if self.client.closed.load(Ordering::Relaxed) {
+            return;
+        }
+        let violations = {
+            let mut check = self.movement_check.lock();
+            check.violations += 1;
+            check.violations
+        };
+        let max_violations = ADVANCED_CONFIG.get().movement.max_violations;
+        if max_violations > 0 && violations > max_violations {
+            log::warn!(
+                "Kicking {} for illegal movement, they {reason}",
+                self.gameprofile.name
+            );
+            self.kick(TextComponent::text(&format_message(
+                &MESSAGES.get().illegal_movement,
+                &[],
+            )));
+            return;
+        }
+        log::debug!("{} {reason}, putting them back", self.gameprofile.name);
+        let entity = &self.living_entity.entity;
+        self.teleport(
+            position.x,
+            position.y,
+            position.z,
+            entity.yaw.load(),
+            entity.pitch.load(),
+        );
+    }
+}
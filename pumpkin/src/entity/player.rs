@@ -1,46 +1,91 @@
-use std::sync::{
-    atomic::{AtomicI32, AtomicU8},
-    Arc,
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicI32, AtomicI64, AtomicU8, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 
 use crossbeam::atomic::AtomicCell;
 use num_derive::FromPrimitive;
 use num_traits::ToPrimitive;
 use parking_lot::Mutex;
+use pumpkin_config::{messages::format_message, ADVANCED_CONFIG, BASIC_CONFIG, MESSAGES};
 use pumpkin_core::{
     math::{boundingbox::BoundingBox, position::WorldPosition, vector3::Vector3},
     text::TextComponent,
     GameMode,
 };
-use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_entity::{entity_type::EntityType, pose::EntityPose, EntityId};
 use pumpkin_inventory::player::PlayerInventory;
 use pumpkin_protocol::{
     bytebuf::packet_id::Packet,
     client::play::{
-        CGameEvent, CPlayDisconnect, CPlayerAbilities, CPlayerInfoUpdate, CSetHealth,
-        CSyncPlayerPosition, CSystemChatMessage, GameEvent, PlayerAction,
+        CAcknowledgeBlockChange, CGameEvent, CHeadRot, CHurtAnimation, CKeepAlive, CPlayDisconnect,
+        CPlayerAbilities, CPlayerInfoUpdate, CRemovePlayerInfo, CRespawn, CSetEntityMetadata,
+        CSetExperience, CSetHealth, CSyncPlayerPosition, CSystemChatMessage, CTransfer, GameEvent,
+        Metadata, PlayerAction, RecipeBookSettings,
     },
     server::play::{
-        SChatCommand, SChatMessage, SClickContainer, SClientInformationPlay, SConfirmTeleport,
-        SInteract, SPlayPingRequest, SPlayerAction, SPlayerCommand, SPlayerPosition,
-        SPlayerPositionRotation, SPlayerRotation, SSetCreativeSlot, SSetHeldItem, SSetPlayerGround,
-        SSwingArm, SUseItem, SUseItemOn,
+        SChangeRecipeBookSettings, SChatCommand, SChatMessage, SClickContainer,
+        SClickContainerButton, SClientCommand, SClientInformationPlay, SConfirmTeleport, SInteract,
+        SMessageAcknowledgement, SMoveVehicle, SPaddleBoat, SPlaceRecipe, SPlayPingRequest,
+        SPlayerAbilities, SPlayerAction, SPlayerCommand, SPlayerInput, SPlayerPosition,
+        SPlayerPositionRotation, SPlayerRotation, SPlayerSession, SRenameItem, SSetCreativeSlot,
+        SSetHeldItem, SSetPlayerGround, SSetSeenRecipe, SSwingArm, SUpdateSign, SUseItem,
+        SUseItemOn,
     },
     RawPacket, ServerPacket, VarInt,
 };
 
 use pumpkin_protocol::server::play::{SCloseContainer, SKeepAlive};
-use pumpkin_world::item::ItemStack;
+use pumpkin_world::{
+    cylindrical_chunk_iterator::Cylindrical,
+    dimension::Dimension,
+    game_rules,
+    item::{AttributeOperation, ItemStack},
+    player_data::SpawnPoint,
+    stats::{self, PlayerStats},
+};
 
 use super::Entity;
+use crate::chat::{session::ChatSession, PlayerChat};
 use crate::error::PumpkinError;
 use crate::{
     client::{authentication::GameProfile, Client, PlayerConfig},
     server::Server,
-    world::World,
+    world::{
+        bed,
+        player_chunker::{self, ChunkSendQueue},
+        portal::PortalState,
+        tracking::angle_byte,
+        World,
+    },
+};
+
+use super::{
+    advancement::PlayerAdvancements,
+    attributes::{EntityAttribute, Modifier},
+    damage::DamageSource,
+    food::Eating,
+    living::LivingEntity,
+    metadata,
+    movement::MovementCheck,
+    projectile::DrawingBow,
 };
 
-use super::living::LivingEntity;
+/// Sprinting players are 30% faster
+const SPRINT_MODIFIER: &str = "minecraft:sprinting";
+const SPRINT_SPEED: f64 = 0.3;
+
+/// The experience of a player, as it is shown in the experience bar
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Experience {
+    pub level: i32,
+    /// The progress towards the next level, from 0 to 1
+    pub progress: f32,
+    pub total: i32,
+}
 
 /// Represents a Minecraft player entity.
 ///
@@ -60,6 +105,20 @@ pub struct Player {
     pub food: AtomicI32,
     /// The player's food saturation level.
     pub food_saturation: AtomicCell<f32>,
+    /// Adds up with everything the player does, every 4 cost a saturation or food point
+    pub food_exhaustion: AtomicCell<f32>,
+    /// Ticks since the player last healed from being fed or got hurt from starving
+    pub food_tick_timer: AtomicI32,
+    /// The food the player is eating right now
+    pub eating: AtomicCell<Option<Eating>>,
+    /// The bow the player is drawing right now
+    pub drawing_bow: AtomicCell<Option<DrawingBow>>,
+    /// Ticks since the player last attacked or swung their arm, see `attack_strength`
+    pub attack_strength_ticks: AtomicI32,
+    /// The player's experience level and the progress towards the next one.
+    pub experience: AtomicCell<Experience>,
+    /// Ticks until the player can collect the next experience orb
+    pub experience_pickup_delay: AtomicI32,
     /// The player's inventory, containing items and equipment.
     pub inventory: Mutex<PlayerInventory>,
     /// The ID of the currently open container (if any).
@@ -67,27 +126,75 @@ pub struct Player {
     /// The item currently being held by the player.
     pub carried_item: AtomicCell<Option<ItemStack>>,
 
-    /// send `send_abilties_update` when changed
-    /// The player's abilities and special powers.
-    ///
-    /// This field represents the various abilities that the player possesses, such as flight, invulnerability, and other special effects.
+    /// The player's abilities and special powers, like flying and invulnerability.
     ///
-    /// **Note:** When the `abilities` field is updated, the server should send a `send_abilities_update` packet to the client to notify them of the changes.
-    pub abilities: PlayerAbilities,
-    /// The player's last known position.
+    /// **Note:** Change them with `update_abilities`, which tells the client
+    pub abilities: Mutex<PlayerAbilities>,
+    /// Where the other players were told this player is.
     ///
-    /// This field is used to calculate the player's movement delta for network synchronization and other purposes.
+    /// Their movement packets carry the difference to this position, see `broadcast_movement`
     pub last_position: AtomicCell<Vector3<f64>>,
 
     /// The current stage of the block the player is breaking.
     pub current_block_destroy_stage: AtomicU8,
+    /// The block the player started digging and when, only this block may be broken when they
+    /// finish digging and not before it took long enough
+    pub digging: AtomicCell<Option<(WorldPosition, Instant)>>,
+    /// The highest block change sequence which still has to be acknowledged, -1 for none.
+    pub block_change_sequence: AtomicI32,
     /// A counter for teleport IDs used to track pending teleports.
     pub teleport_id_count: AtomicI32,
     /// The pending teleport information, including the teleport ID and target location.
     pub awaiting_teleport: Mutex<Option<(VarInt, Vector3<f64>)>>,
+    /// What the Server knows about the last moves, to check the next one
+    pub movement_check: Mutex<MovementCheck>,
+    /// The items the other players last saw in the player's main and off hand
+    pub shown_equipment: Mutex<[Option<ItemStack>; 2]>,
 
     /// The coordinates of the chunk section the player is currently watching.
     pub watched_section: AtomicCell<Vector3<i32>>,
+    /// The chunks the player sees, each of them has a ticket keeping it loaded.
+    pub chunk_view: Mutex<Option<Cylindrical>>,
+    /// Replaces the configured view distance for this player.
+    pub view_distance_override: AtomicCell<Option<u8>>,
+    /// Replaces the configured simulation distance for this player.
+    pub simulation_distance_override: AtomicCell<Option<u8>>,
+    /// The chunks in the view which were not sent yet.
+    pub chunk_queue: Mutex<ChunkSendQueue>,
+
+    /// Whether we are waiting for the client to answer our last keep alive.
+    pub wait_for_keep_alive: AtomicBool,
+    /// The id of the last keep alive we sent.
+    pub keep_alive_id: AtomicI64,
+    /// The time we sent the last keep alive.
+    pub last_keep_alive_time: AtomicCell<Instant>,
+    /// The last time the player moved, chatted or interacted, used to kick idle players.
+    pub last_action_time: AtomicCell<Instant>,
+
+    /// The secure chat session and message chain of the player.
+    pub chat: Mutex<PlayerChat>,
+    /// Whether the player stands in a portal and for how long.
+    pub portal: AtomicCell<PortalState>,
+    /// The advancements the player got and their progress towards the others.
+    pub advancements: Mutex<PlayerAdvancements>,
+    /// How often the player did something, like mining blocks or dying.
+    pub stats: Mutex<PlayerStats>,
+    /// The head of the bed the player sleeps in.
+    pub sleeping: AtomicCell<Option<WorldPosition>>,
+    /// How long the player has been sleeping, up to when they slept long enough.
+    pub sleep_timer: AtomicI32,
+    /// The bed or respawn anchor the player respawns at, None for the spawn of the world.
+    pub spawn_point: Mutex<Option<SpawnPoint>>,
+    /// Which recipe books the player has open and whether they only show what can be crafted.
+    pub recipe_book: AtomicCell<RecipeBookSettings>,
+    /// Decides the enchantments the enchanting table offers, changes after every enchanting.
+    pub enchantment_seed: AtomicI32,
+    /// The enchanting table the player has open, its bookshelves decide the costs.
+    pub enchanting_table: AtomicCell<Option<WorldPosition>>,
+    /// The anvil the player has open, it gets damaged when they use it.
+    pub anvil: AtomicCell<Option<WorldPosition>>,
+    /// The name the player typed into the anvil, None if they didn't.
+    pub anvil_name: Mutex<Option<String>>,
 }
 
 impl Player {
@@ -121,34 +228,301 @@ impl Player {
             gameprofile,
             client,
             awaiting_teleport: Mutex::new(None),
-            // TODO: Load this from previous instance
+            movement_check: Mutex::new(MovementCheck::default()),
+            shown_equipment: Mutex::new([None, None]),
             food: AtomicI32::new(20),
             food_saturation: AtomicCell::new(20.0),
+            food_exhaustion: AtomicCell::new(0.0),
+            food_tick_timer: AtomicI32::new(0),
+            eating: AtomicCell::new(None),
+            drawing_bow: AtomicCell::new(None),
+            attack_strength_ticks: AtomicI32::new(0),
+            experience: AtomicCell::new(Experience::default()),
+            experience_pickup_delay: AtomicI32::new(0),
             current_block_destroy_stage: AtomicU8::new(0),
+            digging: AtomicCell::new(None),
+            block_change_sequence: AtomicI32::new(-1),
             inventory: Mutex::new(PlayerInventory::new()),
             open_container: AtomicCell::new(None),
             carried_item: AtomicCell::new(None),
             teleport_id_count: AtomicI32::new(0),
-            abilities: PlayerAbilities::default(),
+            abilities: Mutex::new(PlayerAbilities::for_gamemode(gamemode)),
             gamemode: AtomicCell::new(gamemode),
             watched_section: AtomicCell::new(Vector3::new(0, 0, 0)),
+            chunk_view: Mutex::new(None),
+            view_distance_override: AtomicCell::new(None),
+            simulation_distance_override: AtomicCell::new(None),
+            chunk_queue: Mutex::new(ChunkSendQueue::default()),
             last_position: AtomicCell::new(Vector3::new(0.0, 0.0, 0.0)),
+            wait_for_keep_alive: AtomicBool::new(false),
+            keep_alive_id: AtomicI64::new(0),
+            last_keep_alive_time: AtomicCell::new(Instant::now()),
+            last_action_time: AtomicCell::new(Instant::now()),
+            chat: Mutex::new(PlayerChat::default()),
+            portal: AtomicCell::new(PortalState::Outside),
+            advancements: Mutex::new(PlayerAdvancements::default()),
+            stats: Mutex::new(PlayerStats::default()),
+            sleeping: AtomicCell::new(None),
+            sleep_timer: AtomicI32::new(0),
+            spawn_point: Mutex::new(None),
+            recipe_book: AtomicCell::new(RecipeBookSettings::default()),
+            enchantment_seed: AtomicI32::new(rand::random()),
+            enchanting_table: AtomicCell::new(None),
+            anvil: AtomicCell::new(None),
+            anvil_name: Mutex::new(None),
         }
     }
 
+    /// Runs every Server tick
+    pub fn tick(&self) {
+        if self.client.closed.load(Ordering::Relaxed) {
+            return;
+        }
+        self.living_entity.tick();
+        self.attack_strength_ticks.fetch_add(1, Ordering::Relaxed);
+        self.tick_experience_pickup();
+        self.tick_effects();
+        self.tick_environment_damage();
+        self.tick_food();
+        self.tick_eating();
+        self.tick_bow();
+        self.tick_stats();
+        self.tick_sleep();
+        self.tick_movement_check();
+        self.tick_equipment();
+        // Like vanilla, block changes are acknowledged once per tick
+        let sequence = self.block_change_sequence.swap(-1, Ordering::Relaxed);
+        if sequence >= 0 {
+            self.client
+                .send_packet(&CAcknowledgeBlockChange::new(sequence.into()));
+        }
+        let config = &ADVANCED_CONFIG.get().keep_alive;
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_keep_alive_time.load());
+        if self.wait_for_keep_alive.load(Ordering::Relaxed) {
+            if elapsed >= Duration::from_secs(config.timeout) {
+                self.kick(TextComponent::text(&format_message(
+                    &MESSAGES.get().timed_out,
+                    &[],
+                )));
+            }
+        } else if elapsed >= Duration::from_secs(config.interval) {
+            let id = rand::random::<i64>();
+            self.keep_alive_id.store(id, Ordering::Relaxed);
+            self.wait_for_keep_alive.store(true, Ordering::Relaxed);
+            self.last_keep_alive_time.store(now);
+            self.client.send_packet(&CKeepAlive { keep_alive_id: id });
+        }
+    }
+
+    /// Marks the player as active, should be called for every meaningful input
+    pub fn update_last_action(&self) {
+        self.last_action_time.store(Instant::now());
+    }
+
+    /// How long the player has not moved, chatted or interacted
+    pub fn idle_time(&self) -> Duration {
+        self.last_action_time.load().elapsed()
+    }
+
+    /// How far chunks are sent to the player. What the client asked for, but at most the
+    /// configured view distance
+    pub fn view_distance(&self) -> u8 {
+        let max = self
+            .view_distance_override
+            .load()
+            .unwrap_or(BASIC_CONFIG.get().view_distance)
+            .max(2);
+        (self.config.lock().view_distance.max(0) as u8).clamp(2, max)
+    }
+
+    /// The language of the player's client, e.g. `en_us`
+    pub fn locale(&self) -> String {
+        self.config.lock().locale.clone()
+    }
+
+    /// The skin layers and the main arm the player shows, everyone sees them on the player model
+    pub fn model_packets(&self) -> [CSetEntityMetadata<u8>; 2] {
+        let config = self.config.lock();
+        let entity_id = self.entity_id();
+        [
+            CSetEntityMetadata::new(
+                entity_id.into(),
+                Metadata::new(metadata::SKIN_PARTS, VarInt(0), config.skin_parts),
+            ),
+            CSetEntityMetadata::new(
+                entity_id.into(),
+                Metadata::new(metadata::MAIN_HAND, VarInt(0), config.main_hand as u8),
+            ),
+        ]
+    }
+
+    /// How far around the player blocks and entities are ticked, never further than chunks are sent
+    pub fn simulation_distance(&self) -> u8 {
+        self.simulation_distance_override
+            .load()
+            .unwrap_or(BASIC_CONFIG.get().simulation_distance)
+            .min(self.view_distance())
+    }
+
+    /// Replaces the configured view and simulation distance for this player, None goes back to
+    /// the configured one
+    pub fn set_distances(&self, view_distance: Option<u8>, simulation_distance: Option<u8>) {
+        self.view_distance_override.store(view_distance);
+        self.simulation_distance_override.store(simulation_distance);
+        player_chunker::update_distances(&self.living_entity.entity.world(), self);
+    }
+
+    /// The current chat session, None if the player does not sign their messages
+    pub fn chat_session(&self) -> Option<Arc<ChatSession>> {
+        self.chat.lock().session()
+    }
+
     /// Removes the Player out of the current World
     pub async fn remove(&self) {
-        self.living_entity.entity.world.remove_player(self);
+        self.living_entity.entity.world().remove_player(self);
+    }
+
+    /// Moves the Player into another World, to `position`
+    pub async fn change_world(self: &Arc<Self>, world: Arc<World>, position: Vector3<f64>) {
+        let old_world = self.living_entity.entity.world();
+        if Arc::ptr_eq(&old_world, &world) {
+            return;
+        }
+        // Set first, so a disconnect from now on removes the Player from the new world
+        self.living_entity.entity.set_world(world.clone());
+        old_world.remove_player(self);
+        // The players of the old world are not in the player list anymore
+        let others: Vec<_> = old_world
+            .current_players
+            .lock()
+            .values()
+            .map(|other| other.gameprofile.id)
+            .collect();
+        if !others.is_empty() {
+            self.client
+                .send_packet(&CRemovePlayerInfo::new(others.len().into(), &others));
+        }
+
+        world.add_player(self.client.id, self.clone());
+        if self.client.closed.load(Ordering::Relaxed) {
+            world.remove_player(self);
+            return;
+        }
+        // Keep the attributes and meta data
+        self.send_respawn(&world, 0x03);
+        world.spawn_in(self.clone(), position).await;
+        // The client forgets the effects with the world
+        self.send_effects();
+    }
+
+    /// Respawns the Player after they died, at their bed or respawn anchor if they have one.
+    /// Otherwise players who died in the Nether or the End respawn in the main world, like in
+    /// vanilla
+    pub async fn respawn(self: &Arc<Self>, server: &Server) {
+        if self.living_entity.health.load() > 0.0 {
+            return;
+        }
+        let world = self.living_entity.entity.world();
+        // The items and some of the experience were dropped when they died, the rest is lost
+        if !world.game_rule_bool(game_rules::KEEP_INVENTORY) {
+            self.set_experience(Experience::default());
+        }
+        let (target, position) = bed::respawn_position(server, self).unwrap_or_else(|| {
+            let target = if world.dimension == Dimension::OverWorld {
+                world.clone()
+            } else {
+                server.default_world().clone()
+            };
+            let position = target.spawn_position.load();
+            (target, position)
+        });
+        if Arc::ptr_eq(&world, &target) {
+            self.send_respawn(&world, 0x00);
+            world.spawn_in(self.clone(), position).await;
+        } else {
+            self.change_world(target, position).await;
+        }
+        self.living_entity
+            .invulnerable_ticks
+            .store(0, Ordering::Relaxed);
+        self.living_entity.fall_distance.store(0.0);
+        self.food_exhaustion.store(0.0);
+        self.eating.store(None);
+        self.drawing_bow.store(None);
+        // Dead players lose their effects and the modifiers, the client forgot them already
+        self.living_entity.effects.lock().clear();
+        self.living_entity.attributes.lock().clear_modifiers();
+        self.living_entity.entity.set_sprinting(false);
+        self.living_entity.entity.set_glowing(false);
+        self.living_entity.entity.set_invisible(false);
+        self.send_attributes(&EntityAttribute::ALL);
+        self.set_absorption(0.0);
+        self.set_health(self.living_entity.max_health(), 20, 5.0);
+        self.set_container_content(None);
+    }
+
+    /// Tells the client it is in `world` now, `data_kept` decides which of its data it keeps
+    fn send_respawn(&self, world: &World, data_kept: u8) {
+        let gamemode = self.gamemode.load().to_u8().unwrap();
+        self.client.send_packet(&CRespawn::new(
+            world.dimension.type_id().into(),
+            &world.dimension_name,
+            world.hashed_seed(),
+            gamemode,
+            gamemode as i8,
+            false,
+            false,
+            None,
+            0.into(),
+            data_kept,
+        ));
     }
 
     pub const fn entity_id(&self) -> EntityId {
         self.living_entity.entity.entity_id
     }
 
-    /// Updates the current abilities the Player has
-    pub fn send_abilties_update(&mut self) {
+    /// Whether the player plays on Bedrock and joined through Geyser with Floodgate
+    pub fn is_bedrock(&self) -> bool {
+        self.client.bedrock.lock().is_some()
+    }
+
+    /// Changes the abilities of the player and tells the client. They stop flying if they may
+    /// not fly anymore
+    pub fn update_abilities(&self, update: impl FnOnce(&mut PlayerAbilities)) {
+        {
+            let mut abilities = self.abilities.lock();
+            update(&mut abilities);
+            abilities.flying &= abilities.allow_flying;
+        }
+        self.send_abilities_update();
+    }
+
+    /// Lets the player fly or takes it away, like `/fly` commands do
+    pub fn set_allow_flight(&self, allow_flight: bool) {
+        self.update_abilities(|abilities| abilities.allow_flying = allow_flight);
+    }
+
+    /// Makes the player fly or land, only players who may fly can fly
+    pub fn set_flying(&self, flying: bool) {
+        self.update_abilities(|abilities| abilities.flying = flying);
+    }
+
+    /// Changes how fast the player flies, 0.05 by default
+    pub fn set_fly_speed(&self, fly_speed: f32) {
+        self.update_abilities(|abilities| abilities.fly_speed = fly_speed);
+    }
+
+    /// Protects the player from all damage but the void, or takes the protection away
+    pub fn set_invulnerable(&self, invulnerable: bool) {
+        self.update_abilities(|abilities| abilities.invulnerable = invulnerable);
+    }
+
+    /// Tells the client the current abilities the Player has
+    pub fn send_abilities_update(&self) {
         let mut b = 0i8;
-        let abilities = &self.abilities;
+        let abilities = *self.abilities.lock();
 
         if abilities.invulnerable {
             b |= 1;
@@ -159,7 +533,7 @@ impl Player {
         if abilities.allow_flying {
             b |= 4;
         }
-        if abilities.creative {
+        if abilities.instant_build {
             b |= 8;
         }
         self.client.send_packet(&CPlayerAbilities::new(
@@ -181,9 +555,12 @@ impl Player {
         }
         let teleport_id = i + 1;
         let entity = &self.living_entity.entity;
+        // Teleported players get out of their vehicle, like in vanilla
+        entity.world().leave_vehicle(self);
         entity.set_pos(x, y, z);
         entity.set_rotation(yaw, pitch);
         *self.awaiting_teleport.lock() = Some((teleport_id.into(), Vector3::new(x, y, z)));
+        self.broadcast_movement(true);
         self.client.send_packet(&CSyncPlayerPosition::new(
             x,
             y,
@@ -195,6 +572,33 @@ impl Player {
         ));
     }
 
+    /// Shows the players who see this Player where they moved since the last time, with their
+    /// rotation if `rotated`
+    pub fn broadcast_movement(&self, rotated: bool) {
+        let entity = &self.living_entity.entity;
+        let position = entity.pos.load();
+        let from = self.last_position.swap(position);
+        let (yaw, pitch) = (
+            angle_byte(entity.yaw.load()),
+            angle_byte(entity.pitch.load()),
+        );
+        let world = entity.world();
+        world.broadcast_movement(
+            self.entity_id(),
+            from,
+            position,
+            (yaw, pitch),
+            rotated,
+            entity.on_ground.load(Ordering::Relaxed),
+        );
+        if rotated {
+            world.broadcast_tracked(
+                self.entity_id(),
+                &CHeadRot::new(self.entity_id().into(), yaw),
+            );
+        }
+    }
+
     pub fn block_interaction_range(&self) -> f64 {
         if self.gamemode.load() == GameMode::Creative {
             5.0
@@ -203,6 +607,74 @@ impl Player {
         }
     }
 
+    /// Acknowledges a block change the client predicted, once the tick ends.
+    /// Block updates sent before correct the client's prediction
+    pub fn acknowledge_block_change(&self, sequence: VarInt) {
+        self.block_change_sequence
+            .fetch_max(sequence.0, Ordering::Relaxed);
+    }
+
+    /// Starts or stops sprinting, the other players see it and the player moves faster
+    pub fn set_sprinting(&self, sprinting: bool) {
+        let entity = &self.living_entity.entity;
+        if entity.sprinting.load(Ordering::Relaxed) != sprinting {
+            entity.set_sprinting(sprinting);
+            let attribute = EntityAttribute::MovementSpeed;
+            if sprinting {
+                let modifier = Modifier::new(
+                    SPRINT_MODIFIER,
+                    SPRINT_SPEED,
+                    AttributeOperation::AddMultipliedTotal,
+                );
+                self.add_attribute_modifier(attribute, modifier);
+            } else {
+                self.remove_attribute_modifier(attribute, SPRINT_MODIFIER);
+            }
+        }
+    }
+
+    /// Starts or stops sneaking, the other players see the player crouch
+    pub fn set_sneaking(&self, sneaking: bool) {
+        self.living_entity.entity.set_sneaking(sneaking);
+        self.update_pose();
+    }
+
+    /// Updates the pose the other players see from what the player does. Gliding stops once
+    /// the player lands, like in vanilla
+    pub fn update_pose(&self) {
+        let entity = &self.living_entity.entity;
+        if entity.on_ground.load(Ordering::Relaxed) {
+            entity.set_fall_flying(false);
+        }
+        let pose = if entity.fall_flying.load(Ordering::Relaxed) {
+            EntityPose::FallFlying
+        } else if entity.sneaking.load(Ordering::Relaxed) && !self.abilities.lock().flying {
+            EntityPose::Crouching
+        } else {
+            EntityPose::Standing
+        };
+        entity.set_pose(pose);
+    }
+
+    /// The box the player takes up in the world
+    pub fn bounding_box(&self) -> BoundingBox {
+        let entity = &self.living_entity.entity;
+        let pos = entity.pos.load();
+        let height = if entity.sneaking.load(Ordering::Relaxed) {
+            1.5
+        } else {
+            1.8
+        };
+        BoundingBox::new(
+            pos.x - 0.3,
+            pos.y,
+            pos.z - 0.3,
+            pos.x + 0.3,
+            pos.y + height,
+            pos.z + 0.3,
+        )
+    }
+
     pub fn can_interact_with_block_at(&self, pos: &WorldPosition, additional_range: f64) -> bool {
         let d = self.block_interaction_range() + additional_range;
         let box_pos = BoundingBox::from_block(pos);
@@ -215,6 +687,15 @@ impl Player {
         }) < d * d
     }
 
+    /// Sends the Player to another server, the client disconnects on its own and
+    /// joins `host` with the transfer intent
+    pub fn transfer(&self, host: &str, port: u16) {
+        log::info!("Transferring {} to {host}:{port}", self.gameprofile.name);
+        self.client
+            .send_packet(&CTransfer::new(host, VarInt(i32::from(port))));
+        self.client.flush();
+    }
+
     /// Kicks the Client with a reason depending on the connection state
     pub fn kick(&self, reason: TextComponent) {
         assert!(!self
@@ -225,6 +706,9 @@ impl Player {
         self.client
             .try_send_packet(&CPlayDisconnect::new(&reason))
             .unwrap_or_else(|_| self.client.close());
+        self.client
+            .try_flush()
+            .unwrap_or_else(|_| self.client.close());
         log::info!(
             "Kicked {} for {}",
             self.gameprofile.name,
@@ -241,6 +725,92 @@ impl Player {
             .send_packet(&CSetHealth::new(health, food.into(), food_saturation));
     }
 
+    pub fn set_experience(&self, experience: Experience) {
+        self.experience.store(experience);
+        self.client.send_packet(&CSetExperience::new(
+            experience.progress,
+            experience.level.into(),
+            experience.total.into(),
+        ));
+    }
+
+    /// Sets the extra health which is lost first, everyone sees the golden hearts
+    pub fn set_absorption(&self, absorption: f32) {
+        self.living_entity.absorption.store(absorption);
+        self.living_entity
+            .entity
+            .world()
+            .broadcast_packet_all(&CSetEntityMetadata::new(
+                self.entity_id().into(),
+                Metadata::new(metadata::ABSORPTION, 3.into(), absorption),
+            ));
+    }
+
+    /// Heals the player up to their max health
+    pub fn heal(&self, amount: f32) {
+        self.living_entity.heal(amount);
+        self.client.send_packet(&CSetHealth::new(
+            self.living_entity.health.load(),
+            self.food.load(Ordering::Relaxed).into(),
+            self.food_saturation.load(),
+        ));
+    }
+
+    /// Hurts the player and kills them once their health runs out. The worn armor reduces the
+    /// damage, unless `source` bypasses it. Invulnerable players, like in creative and spectator
+    /// mode, can only be hurt by the void. Returns whether the player was hurt
+    pub fn damage(&self, amount: f32, source: DamageSource) -> bool {
+        if self.abilities.lock().invulnerable && !source.bypasses_invulnerability() {
+            return false;
+        }
+        let absorption = self.living_entity.absorption.load();
+        let health_before = self.living_entity.health.load();
+        let (armor, toughness) = self.inventory.lock().armor_points();
+        let protection = self.protection(&source);
+        if !self
+            .living_entity
+            .damage(amount, &source, armor, toughness, protection)
+        {
+            return false;
+        }
+        self.add_exhaustion(source.exhaustion());
+        self.wake_up(true);
+        // In tenths of a health point, after armor and absorption took their share
+        let taken = health_before - self.living_entity.health.load();
+        self.increment_custom_stat(stats::DAMAGE_TAKEN, (taken * 10.0).round() as i32);
+        if self.living_entity.absorption.load() != absorption {
+            self.set_absorption(self.living_entity.absorption.load());
+        }
+        // The hurt animation of PVP hits can be turned off
+        if source.attacker().is_none() || ADVANCED_CONFIG.get().pvp.hurt_animation {
+            let entity_id = VarInt(self.entity_id());
+            self.living_entity
+                .entity
+                .world()
+                .broadcast_packet_all(&CHurtAnimation::new(
+                    &entity_id,
+                    self.living_entity.entity.yaw.load(),
+                ));
+        }
+        let health = self.living_entity.health.load();
+        self.client.send_packet(&CSetHealth::new(
+            health,
+            self.food.load(Ordering::Relaxed).into(),
+            self.food_saturation.load(),
+        ));
+        if health == 0.0 {
+            self.increment_custom_stat(stats::DEATHS, 1);
+            self.stats
+                .lock()
+                .set(stats::CUSTOM, stats::TIME_SINCE_DEATH, 0);
+            self.living_entity.entity.world().leave_vehicle(self);
+            self.drop_death_experience();
+            self.drop_inventory();
+            self.living_entity.kill();
+        }
+        true
+    }
+
     pub fn set_gamemode(&self, gamemode: GameMode) {
         // We could send the same gamemode without problems. But why waste bandwidth ?
         let current_gamemode = self.gamemode.load();
@@ -249,11 +819,12 @@ impl Player {
             "Setting the same gamemode as already is"
         );
         self.gamemode.store(gamemode);
-        // So a little story time. I actually made an abilties_from_gamemode function. I looked at vanilla and they always send the abilties from the gamemode. But the funny thing actually is. That the client
-        // does actually use the same method and set the abilties when receiving the CGameEvent gamemode packet. Just Mojang nonsense
+        // The client also sets the abilities of the gamemode when receiving the CGameEvent
+        // gamemode packet, still they are sent like in vanilla so both agree
+        self.update_abilities(|abilities| *abilities = PlayerAbilities::for_gamemode(gamemode));
         self.living_entity
             .entity
-            .world
+            .world()
             .broadcast_packet_all(&CPlayerInfoUpdate::new(
                 0x04,
                 &[pumpkin_protocol::client::play::Player {
@@ -271,12 +842,24 @@ impl Player {
         self.client
             .send_packet(&CSystemChatMessage::new(text, false));
     }
+
+    /// Shows a message above the hotbar instead of in the chat
+    pub fn send_action_bar_message(&self, text: TextComponent) {
+        self.client
+            .send_packet(&CSystemChatMessage::new(text, true));
+    }
 }
 
 impl Player {
-    pub async fn process_packets(&self, server: &Arc<Server>) {
-        let mut packets = self.client.client_packets_queue.lock();
-        while let Some(mut packet) = packets.pop() {
+    pub async fn process_packets(self: &Arc<Self>, server: &Arc<Server>) {
+        loop {
+            let packet = self.client.client_packets_queue.lock().pop_front();
+            let Some(mut packet) = packet else {
+                break;
+            };
+            if !self.client.translate_packet(&mut packet) {
+                continue;
+            }
             match self.handle_play_packet(server, &mut packet).await {
                 Ok(_) => {}
                 Err(e) => {
@@ -297,7 +880,7 @@ impl Player {
     }
 
     pub async fn handle_play_packet(
-        &self,
+        self: &Arc<Self>,
         server: &Arc<Server>,
         packet: &mut RawPacket,
     ) -> Result<(), Box<dyn PumpkinError>> {
@@ -328,9 +911,24 @@ impl Player {
                 self.handle_player_ground(SSetPlayerGround::read(bytebuf)?);
                 Ok(())
             }
+            SMoveVehicle::PACKET_ID => {
+                self.handle_move_vehicle(SMoveVehicle::read(bytebuf)?);
+                Ok(())
+            }
+            SPaddleBoat::PACKET_ID => {
+                self.handle_paddle_boat(SPaddleBoat::read(bytebuf)?);
+                Ok(())
+            }
+            SPlayerInput::PACKET_ID => {
+                self.handle_player_input(SPlayerInput::read(bytebuf)?);
+                Ok(())
+            }
+            SPlayerAbilities::PACKET_ID => {
+                self.handle_player_abilities(SPlayerAbilities::read(bytebuf)?);
+                Ok(())
+            }
             SPlayerCommand::PACKET_ID => {
-                self.handle_player_command(SPlayerCommand::read(bytebuf)?)
-                    .await;
+                self.handle_player_command(SPlayerCommand::read(bytebuf)?);
                 Ok(())
             }
             SSwingArm::PACKET_ID => {
@@ -338,11 +936,25 @@ impl Player {
                 Ok(())
             }
             SChatMessage::PACKET_ID => {
-                self.handle_chat_message(SChatMessage::read(bytebuf)?).await;
+                self.handle_chat_message(server, SChatMessage::read(bytebuf)?)
+                    .await;
+                Ok(())
+            }
+            SPlayerSession::PACKET_ID => {
+                self.handle_player_session(server, SPlayerSession::read(bytebuf)?);
+                Ok(())
+            }
+            SMessageAcknowledgement::PACKET_ID => {
+                self.handle_message_acknowledgement(SMessageAcknowledgement::read(bytebuf)?);
+                Ok(())
+            }
+            SClientCommand::PACKET_ID => {
+                self.handle_client_command(server, SClientCommand::read(bytebuf)?)
+                    .await;
                 Ok(())
             }
             SClientInformationPlay::PACKET_ID => {
-                self.handle_client_information_play(SClientInformationPlay::read(bytebuf)?);
+                self.handle_client_information_play(server, SClientInformationPlay::read(bytebuf)?);
                 Ok(())
             }
             SInteract::PACKET_ID => {
@@ -351,12 +963,17 @@ impl Player {
                 Ok(())
             }
             SPlayerAction::PACKET_ID => {
-                self.handle_player_action(SPlayerAction::read(bytebuf)?)
+                self.handle_player_action(server, SPlayerAction::read(bytebuf)?)
                     .await;
                 Ok(())
             }
             SUseItemOn::PACKET_ID => {
-                self.handle_use_item_on(SUseItemOn::read(bytebuf)?).await;
+                self.handle_use_item_on(server, SUseItemOn::read(bytebuf)?)
+                    .await;
+                Ok(())
+            }
+            SUpdateSign::PACKET_ID => {
+                self.handle_update_sign(server, SUpdateSign::read(bytebuf)?);
                 Ok(())
             }
             SUseItem::PACKET_ID => {
@@ -380,16 +997,33 @@ impl Player {
                     .await?;
                 Ok(())
             }
+            SClickContainerButton::PACKET_ID => {
+                self.handle_click_container_button(server, SClickContainerButton::read(bytebuf)?);
+                Ok(())
+            }
+            SRenameItem::PACKET_ID => {
+                self.handle_rename_item(server, SRenameItem::read(bytebuf)?);
+                Ok(())
+            }
             SCloseContainer::PACKET_ID => {
                 self.handle_close_container(server, SCloseContainer::read(bytebuf)?);
                 Ok(())
             }
+            SPlaceRecipe::PACKET_ID => {
+                self.handle_place_recipe(server, SPlaceRecipe::read(bytebuf)?);
+                Ok(())
+            }
+            SChangeRecipeBookSettings::PACKET_ID => {
+                self.handle_change_recipe_book_settings(SChangeRecipeBookSettings::read(bytebuf)?);
+                Ok(())
+            }
+            SSetSeenRecipe::PACKET_ID => {
+                // Every recipe is unlocked from the start, so none is shown as new
+                SSetSeenRecipe::read(bytebuf)?;
+                Ok(())
+            }
             SKeepAlive::PACKET_ID => {
-                self.client
-                    .keep_alive_sender
-                    .send(SKeepAlive::read(bytebuf)?.keep_alive_id)
-                    .await
-                    .unwrap();
+                self.handle_keep_alive(SKeepAlive::read(bytebuf)?);
                 Ok(())
             }
             _ => {
@@ -403,6 +1037,7 @@ impl Player {
 /// Represents a player's abilities and special powers.
 ///
 /// This struct contains information about the player's current abilities, such as flight, invulnerability, and creative mode.
+#[derive(Clone, Copy)]
 pub struct PlayerAbilities {
     /// Indicates whether the player is invulnerable to damage.
     pub invulnerable: bool,
@@ -410,22 +1045,44 @@ pub struct PlayerAbilities {
     pub flying: bool,
     /// Indicates whether the player is allowed to fly (if enabled).
     pub allow_flying: bool,
-    /// Indicates whether the player is in creative mode.
-    pub creative: bool,
+    /// Indicates whether the player breaks blocks instantly, like in creative mode.
+    pub instant_build: bool,
     /// The player's flying speed.
     pub fly_speed: f32,
     /// The field of view adjustment when the player is walking or sprinting.
     pub walk_speed_fov: f32,
 }
 
+impl PlayerAbilities {
+    /// The abilities a player gets with the gamemode, like in vanilla
+    pub fn for_gamemode(gamemode: GameMode) -> Self {
+        let default = Self::default();
+        match gamemode {
+            GameMode::Creative => Self {
+                invulnerable: true,
+                allow_flying: true,
+                instant_build: true,
+                ..default
+            },
+            GameMode::Spectator => Self {
+                invulnerable: true,
+                flying: true,
+                allow_flying: true,
+                ..default
+            },
+            GameMode::Undefined | GameMode::Survival | GameMode::Adventure => default,
+        }
+    }
+}
+
 impl Default for PlayerAbilities {
     fn default() -> Self {
         Self {
             invulnerable: false,
             flying: false,
             allow_flying: false,
-            creative: false,
-            fly_speed: 0.5,
+            instant_build: false,
+            fly_speed: 0.05,
             walk_speed_fov: 0.1,
         }
     }
@@ -440,6 +1097,13 @@ pub enum Hand {
     Off,
 }
 
+/// The arm the player holds the item of their main hand with, the player model shows it
+#[derive(FromPrimitive, Clone, Copy, PartialEq, Eq)]
+pub enum Arm {
+    Left,
+    Right,
+}
+
 /// Represents the player's chat mode settings.
 #[derive(FromPrimitive, Clone)]
 pub enum ChatMode {
@@ -0,0 +1,139 @@
+use pumpkin_protocol::client::play::{CSetHealth, CSetHeldItem};
+use pumpkin_world::player_data::PlayerData;
+
+use super::{
+    attributes::EntityAttribute,
+    player::{Experience, Player, PlayerAbilities},
+};
+
+/// The vanilla slot of the offhand in the player data
+const OFFHAND_SLOT: i8 = -106;
+
+/// The slot of the player inventory for a vanilla slot of the player data
+fn inventory_slot(slot: i8) -> Option<usize> {
+    match slot {
+        // The hotbar comes last in the player inventory
+        0..=8 => Some(slot as usize + 36),
+        9..=35 => Some(slot as usize),
+        // From the boots to the helmet, the player inventory starts with the helmet
+        100..=103 => Some(108 - slot as usize),
+        OFFHAND_SLOT => Some(45),
+        _ => None,
+    }
+}
+
+/// The vanilla slot of the player data for a slot of the player inventory, None for the crafting
+/// grid
+fn data_slot(slot: usize) -> Option<i8> {
+    match slot {
+        5..=8 => Some(108 - slot as i8),
+        9..=35 => Some(slot as i8),
+        36..=44 => Some(slot as i8 - 36),
+        45 => Some(OFFHAND_SLOT),
+        _ => None,
+    }
+}
+
+impl Player {
+    /// What is kept of the player once they leave
+    pub fn to_player_data(&self) -> PlayerData {
+        let entity = &self.living_entity.entity;
+        let world = entity.world();
+        let experience = self.experience.load();
+        let inventory = self.inventory.lock();
+        PlayerData {
+            position: entity.pos.load(),
+            yaw: entity.yaw.load(),
+            pitch: entity.pitch.load(),
+            health: self.living_entity.health.load(),
+            absorption: self.living_entity.absorption.load(),
+            food: self.food.load(std::sync::atomic::Ordering::Relaxed),
+            food_saturation: self.food_saturation.load(),
+            food_exhaustion: self.food_exhaustion.load(),
+            experience_level: experience.level,
+            experience_progress: experience.progress,
+            total_experience: experience.total,
+            game_mode: self.gamemode.load(),
+            dimension: world.dimension_name.clone(),
+            world: Some(world.name.clone()),
+            selected_slot: inventory.selected() as i32,
+            inventory: inventory
+                .slots()
+                .into_iter()
+                .enumerate()
+                .filter_map(|(slot, item)| Some((data_slot(slot)?, *item?)))
+                .collect(),
+            spawn_point: self.spawn_point.lock().clone(),
+            enchantment_seed: self
+                .enchantment_seed
+                .load(std::sync::atomic::Ordering::Relaxed),
+            attribute_bases: {
+                let attributes = self.living_entity.attributes.lock();
+                EntityAttribute::ALL
+                    .into_iter()
+                    .map(|attribute| (attribute.name().to_string(), attributes.base(attribute)))
+                    .collect()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Restores what was kept of the player when they left. Only changes the state on the
+    /// Server, `send_player_data` tells the client once it joined
+    pub fn load_player_data(&self, data: &PlayerData) {
+        let entity = &self.living_entity.entity;
+        entity.set_pos(data.position.x, data.position.y, data.position.z);
+        entity.set_rotation(data.yaw, data.pitch);
+        self.living_entity.health.store(data.health);
+        self.living_entity.absorption.store(data.absorption);
+        self.food
+            .store(data.food, std::sync::atomic::Ordering::Relaxed);
+        self.food_saturation.store(data.food_saturation);
+        self.food_exhaustion.store(data.food_exhaustion);
+        self.experience.store(Experience {
+            level: data.experience_level,
+            progress: data.experience_progress,
+            total: data.total_experience,
+        });
+        self.gamemode.store(data.game_mode);
+        *self.abilities.lock() = PlayerAbilities::for_gamemode(data.game_mode);
+        self.spawn_point.lock().clone_from(&data.spawn_point);
+        self.enchantment_seed
+            .store(data.enchantment_seed, std::sync::atomic::Ordering::Relaxed);
+        {
+            let mut attributes = self.living_entity.attributes.lock();
+            for (name, base) in &data.attribute_bases {
+                if let Some(attribute) = EntityAttribute::from_name(name) {
+                    attributes.set_base(attribute, *base);
+                }
+            }
+        }
+        let mut inventory = self.inventory.lock();
+        inventory.set_selected(data.selected_slot.clamp(0, 8) as usize);
+        for (slot, item) in &data.inventory {
+            let Some(slot) = inventory_slot(*slot) else {
+                continue;
+            };
+            if let Ok(inventory_slot) = inventory.get_slot(slot) {
+                *inventory_slot = Some(*item);
+            }
+        }
+    }
+
+    /// Sends the health, experience, attributes and inventory, the client starts out with the defaults
+    pub fn send_player_data(&self) {
+        self.client.send_packet(&CSetHealth::new(
+            self.living_entity.health.load(),
+            self.food.load(std::sync::atomic::Ordering::Relaxed).into(),
+            self.food_saturation.load(),
+        ));
+        if self.living_entity.absorption.load() > 0.0 {
+            self.set_absorption(self.living_entity.absorption.load());
+        }
+        self.set_experience(self.experience.load());
+        self.send_attributes(&EntityAttribute::ALL);
+        let selected = self.inventory.lock().selected();
+        self.client.send_packet(&CSetHeldItem::new(selected as i8));
+        self.set_container_content(None);
+    }
+}
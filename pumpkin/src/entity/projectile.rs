@@ -0,0 +1,178 @@
+use pumpkin_core::{math::vector3::Vector3, GameMode};
+use pumpkin_inventory::player::PlayerInventory;
+use pumpkin_world::global_registry::{self, ITEM_REGISTRY};
+
+use crate::world::projectile::{self, Launch, Pickup, ProjectileKind};
+
+use super::{
+    food::hand_item,
+    player::{Hand, Player},
+};
+
+/// Bows are fully drawn after this many ticks, like in vanilla
+const FULL_DRAW_TICKS: f32 = 20.0;
+/// Arrows of a fully drawn bow fly this fast
+const ARROW_SPEED: f64 = 3.0;
+/// Thrown items fly this fast
+const THROW_SPEED: f64 = 1.5;
+/// Projectiles start this far below the eyes, like in vanilla
+const LAUNCH_HEIGHT: f64 = 0.1;
+
+/// The bow the player is drawing and for how long
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DrawingBow {
+    pub off_hand: bool,
+    pub ticks: u32,
+}
+
+/// How strong a bow drawn for this many ticks shoots, from 0 to 1, like in vanilla
+fn bow_power(ticks: u32) -> f32 {
+    let power = ticks as f32 / FULL_DRAW_TICKS;
+    ((power * power + power * 2.0) / 3.0).min(1.0)
+}
+
+/// The slot of the arrow a bow shoots. Like in vanilla arrows in the hands come first, then
+/// the ones in the hotbar and the rest of the inventory
+fn arrow_slot(inventory: &mut PlayerInventory, arrow: u32) -> Option<usize> {
+    let held = 36 + inventory.selected();
+    [45, held]
+        .into_iter()
+        .chain(36..45)
+        .chain(9..36)
+        .find(|slot| {
+            inventory
+                .get_slot(*slot)
+                .is_ok_and(|item| item.is_some_and(|item| item.item_id == arrow))
+        })
+}
+
+impl Player {
+    /// Uses a bow or throws the item in `hand`. Returns false if it is neither
+    pub fn use_projectile_item(&self, hand: &Hand) -> bool {
+        let off_hand = matches!(hand, Hand::Off);
+        let Some(item) = *hand_item(&mut self.inventory.lock(), off_hand) else {
+            return false;
+        };
+        if let Some(kind) = ProjectileKind::thrown(&item) {
+            self.throw_projectile(off_hand, kind);
+            return true;
+        }
+        if global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id) != Some("minecraft:bow")
+        {
+            return false;
+        }
+        // Players in creative mode don't need arrows
+        let has_arrows = self.gamemode.load() == GameMode::Creative
+            || global_registry::find_protocol_id(ITEM_REGISTRY, "minecraft:arrow")
+                .is_some_and(|arrow| arrow_slot(&mut self.inventory.lock(), arrow).is_some());
+        if has_arrows {
+            self.drawing_bow
+                .store(Some(DrawingBow { off_hand, ticks: 0 }));
+            self.set_hand_active(Some(off_hand));
+        }
+        true
+    }
+
+    /// Counts how long the player draws their bow, they stop if they switch to another item
+    pub(super) fn tick_bow(&self) {
+        let Some(mut drawing) = self.drawing_bow.load() else {
+            return;
+        };
+        let still_held =
+            hand_item(&mut self.inventory.lock(), drawing.off_hand).is_some_and(|item| {
+                global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id)
+                    == Some("minecraft:bow")
+            });
+        if !still_held || self.living_entity.health.load() <= 0.0 {
+            self.drawing_bow.store(None);
+            self.set_hand_active(None);
+            return;
+        }
+        drawing.ticks += 1;
+        self.drawing_bow.store(Some(drawing));
+    }
+
+    /// Shoots an arrow once the player lets go of their bow, the longer they drew it the
+    /// faster the arrow flies
+    pub fn release_bow(&self) {
+        let Some(drawing) = self.drawing_bow.take() else {
+            return;
+        };
+        self.set_hand_active(None);
+        let power = bow_power(drawing.ticks);
+        if power < 0.1 {
+            return;
+        }
+        let creative = self.gamemode.load() == GameMode::Creative;
+        if !creative {
+            let Some(arrow) = global_registry::find_protocol_id(ITEM_REGISTRY, "minecraft:arrow")
+            else {
+                return;
+            };
+            {
+                let mut inventory = self.inventory.lock();
+                let Some(slot) = arrow_slot(&mut inventory, arrow) else {
+                    return;
+                };
+                let Ok(slot) = inventory.get_slot(slot) else {
+                    return;
+                };
+                if let Some(item) = slot {
+                    item.item_count -= 1;
+                    if item.item_count == 0 {
+                        *slot = None;
+                    }
+                }
+            }
+            self.set_container_content(None);
+        }
+        self.launch(
+            ProjectileKind::Arrow,
+            f64::from(power) * ARROW_SPEED,
+            power >= 1.0,
+            if creative {
+                Pickup::CreativeOnly
+            } else {
+                Pickup::Allowed
+            },
+        );
+    }
+
+    /// Throws one of the items in the hand, e.g. a snowball
+    fn throw_projectile(&self, off_hand: bool, kind: ProjectileKind) {
+        if self.gamemode.load() != GameMode::Creative {
+            {
+                let mut inventory = self.inventory.lock();
+                let slot = hand_item(&mut inventory, off_hand);
+                if let Some(item) = slot {
+                    item.item_count -= 1;
+                    if item.item_count == 0 {
+                        *slot = None;
+                    }
+                }
+            }
+            self.set_container_content(None);
+        }
+        // TODO: Ender pearls have a cooldown
+        self.launch(kind, THROW_SPEED, false, Pickup::Disallowed);
+    }
+
+    /// Launches a projectile from the player's eyes where they look
+    fn launch(&self, kind: ProjectileKind, speed: f64, critical: bool, pickup: Pickup) {
+        let entity = &self.living_entity.entity;
+        let position = entity.pos.load().add(&Vector3::new(
+            0.0,
+            f64::from(entity.standing_eye_height) - LAUNCH_HEIGHT,
+            0.0,
+        ));
+        let direction = projectile::look_direction(entity.yaw.load(), entity.pitch.load());
+        entity.world().launch_projectile(Launch {
+            kind,
+            position,
+            velocity: projectile::launch_velocity(direction, speed, 1.0),
+            owner: Some(self.entity_id()),
+            critical,
+            pickup,
+        });
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::atomic::Ordering;
+
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_entity::pose::EntityPose;
+use pumpkin_protocol::client::play::{CSetEntityMetadata, Metadata};
+
+use super::{metadata, player::Player};
+
+/// Players have to sleep this many ticks before the night can be skipped, like in vanilla
+const DEEP_SLEEP_TICKS: i32 = 100;
+
+impl Player {
+    pub fn is_sleeping(&self) -> bool {
+        self.sleeping.load().is_some()
+    }
+
+    /// Whether the player slept long enough to skip the night
+    pub fn is_sleeping_long_enough(&self) -> bool {
+        self.is_sleeping() && self.sleep_timer.load(Ordering::Relaxed) >= DEEP_SLEEP_TICKS
+    }
+
+    /// Lays the player down at `position` in the bed with its head at `head`
+    pub fn start_sleeping(&self, head: WorldPosition, position: Vector3<f64>) {
+        let entity = &self.living_entity.entity;
+        self.teleport(
+            position.x,
+            position.y,
+            position.z,
+            entity.yaw.load(),
+            entity.pitch.load(),
+        );
+        self.sleeping.store(Some(head));
+        self.sleep_timer.store(0, Ordering::Relaxed);
+        entity.set_pose(EntityPose::Sleeping);
+        // The client only lies down once it knows where its bed is
+        self.client.send_packet(&entity.pose_packet());
+        let packet = self.sleeping_packet();
+        entity.world().broadcast_tracked(self.entity_id(), &packet);
+        self.client.send_packet(&packet);
+    }
+
+    /// Gets the player out of their bed, `announce` shows the others how many players still
+    /// sleep
+    pub fn wake_up(&self, announce: bool) {
+        self.living_entity.entity.world().leave_bed(self, announce);
+    }
+
+    /// Tells a client where the bed of the player is, they show the player lying in it
+    pub fn sleeping_packet(&self) -> CSetEntityMetadata<Option<WorldPosition>> {
+        CSetEntityMetadata::new(
+            self.entity_id().into(),
+            Metadata::new(metadata::SLEEPING_POSITION, 11.into(), self.sleeping.load()),
+        )
+    }
+
+    pub(super) fn tick_sleep(&self) {
+        if self.is_sleeping() {
+            let timer = self.sleep_timer.load(Ordering::Relaxed);
+            self.sleep_timer
+                .store((timer + 1).min(DEEP_SLEEP_TICKS), Ordering::Relaxed);
+        }
+    }
+}
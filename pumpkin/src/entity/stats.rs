@@ -0,0 +1,102 @@
+use std::sync::atomic::Ordering;
+
+use pumpkin_core::math::vector3::Vector3;
+use pumpkin_protocol::client::play::{CAwardStatistics, Statistic};
+use pumpkin_world::{
+    global_registry,
+    stats::{self, CUSTOM},
+};
+
+use super::player::Player;
+
+/// Horizontal moves in the air shorter than this many centimeters are not counted as flying,
+/// like in vanilla
+const MIN_FLY_DISTANCE: i32 = 25;
+
+impl Player {
+    /// Adds `amount` to a statistic, e.g. `stats::MINED` and the id of the block
+    pub fn increment_stat(&self, category: &str, stat: &str, amount: i32) {
+        if amount != 0 {
+            self.stats.lock().increment(category, stat, amount);
+        }
+    }
+
+    /// Adds `amount` to a statistic of the `minecraft:custom` category, e.g. `stats::DEATHS`
+    pub fn increment_custom_stat(&self, stat: &str, amount: i32) {
+        self.increment_stat(CUSTOM, stat, amount);
+    }
+
+    /// Sends all statistics, the client asks for them when the player opens the statistics menu
+    pub fn send_stats(&self) {
+        let statistics: Vec<Statistic> = self
+            .stats
+            .lock()
+            .iter()
+            .filter_map(|(category, stat, value)| {
+                let category_id =
+                    global_registry::find_protocol_id("minecraft:stat_type", category)?;
+                let statistic_id =
+                    global_registry::find_protocol_id(stats::category_registry(category)?, stat)?;
+                Some(Statistic {
+                    category_id: (category_id as i32).into(),
+                    statistic_id: (statistic_id as i32).into(),
+                    value: value.into(),
+                })
+            })
+            .collect();
+        self.client.send_packet(&CAwardStatistics::new(&statistics));
+    }
+
+    /// Counts the time the player plays
+    pub(super) fn tick_stats(&self) {
+        let alive = self.living_entity.health.load() > 0.0;
+        let sneaking = self.living_entity.entity.sneaking.load(Ordering::Relaxed);
+        let mut player_stats = self.stats.lock();
+        player_stats.increment(CUSTOM, stats::PLAY_TIME, 1);
+        player_stats.increment(CUSTOM, stats::TOTAL_WORLD_TIME, 1);
+        if alive {
+            player_stats.increment(CUSTOM, stats::TIME_SINCE_DEATH, 1);
+            player_stats.increment(CUSTOM, stats::TIME_SINCE_REST, 1);
+        }
+        if sneaking {
+            player_stats.increment(CUSTOM, stats::SNEAK_TIME, 1);
+        }
+    }
+
+    /// Counts how far the player walked, sprinted, sneaked, flew and how often they jumped
+    pub(crate) fn add_movement_stats(
+        &self,
+        from: Vector3<f64>,
+        to: Vector3<f64>,
+        was_on_ground: bool,
+    ) {
+        if self.awaiting_teleport.lock().is_some() {
+            return;
+        }
+        let entity = &self.living_entity.entity;
+        let on_ground = entity.on_ground.load(Ordering::Relaxed);
+        if was_on_ground && !on_ground && to.y > from.y {
+            self.increment_custom_stat(stats::JUMP, 1);
+        }
+        let delta = to.sub(&from);
+        let horizontal = (delta.x.hypot(delta.z) * 100.0).round() as i32;
+        let stat = if on_ground {
+            if entity.sprinting.load(Ordering::Relaxed) {
+                stats::SPRINT_ONE_CM
+            } else if entity.sneaking.load(Ordering::Relaxed) {
+                stats::CROUCH_ONE_CM
+            } else {
+                stats::WALK_ONE_CM
+            }
+        } else if entity.fall_flying.load(Ordering::Relaxed) {
+            let distance = (delta.length() * 100.0).round() as i32;
+            self.increment_custom_stat(stats::AVIATE_ONE_CM, distance);
+            return;
+        } else if horizontal > MIN_FLY_DISTANCE {
+            stats::FLY_ONE_CM
+        } else {
+            return;
+        };
+        self.increment_custom_stat(stat, horizontal);
+    }
+}
@@ -0,0 +1,113 @@
+use pumpkin_core::{
+    math::{position::WorldPosition, vector3::Vector3},
+    GameMode,
+};
+use pumpkin_entity::EntityId;
+use pumpkin_world::global_registry::{self, ITEM_REGISTRY};
+
+use crate::world::{player_chunker, projectile, vehicle::VehicleKind, World};
+
+use super::{
+    food::hand_item,
+    player::{Hand, Player},
+};
+
+impl Player {
+    /// Places the boat in `hand` on the water or block the player looks at. Returns false if
+    /// it is no boat
+    pub fn place_boat(&self, hand: &Hand) -> bool {
+        let off_hand = matches!(hand, Hand::Off);
+        let Some(item) = *hand_item(&mut self.inventory.lock(), off_hand) else {
+            return false;
+        };
+        let kind = global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id)
+            .and_then(VehicleKind::of_item);
+        let Some(kind @ VehicleKind::Boat(_)) = kind else {
+            return false;
+        };
+        let entity = &self.living_entity.entity;
+        let world = entity.world();
+        let eyes = entity.pos.load().add(&Vector3::new(
+            0.0,
+            f64::from(entity.standing_eye_height),
+            0.0,
+        ));
+        let direction = projectile::look_direction(entity.yaw.load(), entity.pitch.load());
+        if let Some(spot) = world.boat_spot(eyes, direction, self.block_interaction_range()) {
+            world.place_vehicle(kind, spot, entity.yaw.load());
+            self.use_up_hand_item(off_hand);
+        }
+        true
+    }
+
+    /// Puts the minecart in the main or off hand on the rail the player clicked
+    pub fn place_minecart(&self, world: &World, rail: &WorldPosition, off_hand: bool) {
+        let yaw = self.living_entity.entity.yaw.load();
+        if world.place_minecart(rail, yaw) {
+            self.use_up_hand_item(off_hand);
+        }
+    }
+
+    /// Takes one of the items in the hand, unless the player is in creative mode
+    fn use_up_hand_item(&self, off_hand: bool) {
+        if self.gamemode.load() == GameMode::Creative {
+            return;
+        }
+        {
+            let mut inventory = self.inventory.lock();
+            let slot = hand_item(&mut inventory, off_hand);
+            if let Some(item) = slot {
+                item.item_count -= 1;
+                if item.item_count == 0 {
+                    *slot = None;
+                }
+            }
+        }
+        self.set_container_content(None);
+    }
+
+    /// Hits a boat or minecart with the held item. Returns false if the entity is no vehicle
+    pub fn attack_vehicle(&self, entity_id: EntityId) -> bool {
+        let strength = self.attack_strength();
+        let damage = self.attack_damage() * (0.2 + strength * strength * 0.8);
+        let world = self.living_entity.entity.world();
+        if !world.hit_vehicle(self, entity_id, damage) {
+            return false;
+        }
+        self.reset_attack_strength();
+        true
+    }
+
+    /// What the player rides, None if they ride nothing
+    pub fn vehicle(&self) -> Option<EntityId> {
+        self.living_entity
+            .entity
+            .world()
+            .vehicle_of(self.entity_id())
+    }
+
+    /// Gets out of the vehicle the player rides, they end up on top of it
+    pub fn dismount(&self) {
+        let entity = &self.living_entity.entity;
+        if let Some(exit) = entity.world().leave_vehicle(self) {
+            self.teleport(
+                exit.x,
+                exit.y,
+                exit.z,
+                entity.yaw.load(),
+                entity.pitch.load(),
+            );
+        }
+    }
+
+    /// Moves the player along with the vehicle they ride. The other players see them in it, so
+    /// only the Server has to know where they are
+    pub fn follow_vehicle(&self, seat: Vector3<f64>) {
+        let entity = &self.living_entity.entity;
+        entity.set_pos(seat.x, seat.y, seat.z);
+        self.last_position.store(seat);
+        // Vehicles land softly
+        self.living_entity.fall_distance.store(0.0);
+        player_chunker::update_position(entity, self);
+    }
+}
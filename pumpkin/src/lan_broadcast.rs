@@ -0,0 +1,48 @@
+use std::{
+    net::{Ipv4Addr, SocketAddrV4},
+    time::Duration,
+};
+
+use pumpkin_config::{lan_broadcast::LANBroadcastConfig, BASIC_CONFIG};
+use tokio::net::UdpSocket;
+
+use crate::server::Server;
+
+/// The multicast group clients listen on for LAN games
+const MULTICAST_ADDRESS: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(224, 0, 2, 60), 4445);
+/// How often vanilla announces a LAN game
+const BROADCAST_INTERVAL: Duration = Duration::from_millis(1500);
+
+/// Announces the Server in the local network until it shuts down
+pub async fn run(config: &LANBroadcastConfig, server: &Server) {
+    let socket = match UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await {
+        Ok(socket) => socket,
+        Err(err) => {
+            log::error!("Failed to start LAN broadcast: {err}");
+            return;
+        }
+    };
+
+    let basic = BASIC_CONFIG.get();
+    let motd = config.motd.as_deref().unwrap_or(&basic.motd);
+    let port = config.port.unwrap_or(basic.server_address.port());
+    // The client reads everything between the tags, so they can't be part of the MOTD
+    let motd = motd.replace("[MOTD]", "").replace("[/MOTD]", "");
+    let advertisement = format!("[MOTD]{motd}[/MOTD][AD]{port}[/AD]");
+    log::info!("Announcing the Server in the local network on port {port}");
+
+    let mut interval = tokio::time::interval(BROADCAST_INTERVAL);
+    while server.is_running() {
+        tokio::select! {
+            _ = interval.tick() => {
+                if let Err(err) = socket
+                    .send_to(advertisement.as_bytes(), MULTICAST_ADDRESS)
+                    .await
+                {
+                    log::debug!("Failed to send LAN broadcast: {err}");
+                }
+            }
+            () = server.wait_for_shutdown() => break,
+        }
+    }
+}
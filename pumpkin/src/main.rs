@@ -11,29 +11,47 @@
 #![expect(clippy::significant_drop_tightening)]
 #![expect(clippy::future_not_send)]
 #![expect(clippy::single_call_fn)]
-#![expect(clippy::await_holding_lock)]
 
 #[cfg(target_os = "wasi")]
 compile_error!("Compiling for WASI targets is not supported!");
 
+use clap::Parser;
 use log::LevelFilter;
-use mio::net::TcpListener;
-use mio::{Events, Interest, Poll, Token};
 
-use client::{interrupted, Client};
-use pumpkin_protocol::client::play::CKeepAlive;
+use client::{
+    connection::{ConnectionReader, ConnectionWriter},
+    status::{self, Handshake},
+    Client,
+};
+use metrics::METRICS;
+use pumpkin_config::{messages::format_message, BASIC_CONFIG, MESSAGES};
+use pumpkin_core::text::TextComponent;
+use pumpkin_plugins::plugin_loader::PluginLoader;
 use pumpkin_protocol::ConnectionState;
-use server::Server;
-use std::collections::HashMap;
-use std::io::{self, Read};
+use pumpkin_world::stats;
+use server::{ticker::Ticker, Server};
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
 use std::time::Duration;
-
-// Setup some tokens to allow us to identify which event is for which socket.
-
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+#[cfg(unix)]
+use tokio::net::UnixListener;
+use tokio::time::Instant;
+
+pub mod chat;
+pub mod cli;
 pub mod client;
+pub mod cluster;
 pub mod commands;
 pub mod entity;
 pub mod error;
+pub mod lan_broadcast;
+pub mod metrics;
 pub mod proxy;
 pub mod rcon;
 pub mod server;
@@ -41,7 +59,7 @@ pub mod world;
 
 fn scrub_address(ip: &str) -> String {
     use pumpkin_config::BASIC_CONFIG;
-    if BASIC_CONFIG.scrub_ips {
+    if BASIC_CONFIG.get().scrub_ips {
         ip.chars()
             .map(|ch| if ch == '.' || ch == ':' { ch } else { 'x' })
             .collect()
@@ -52,21 +70,21 @@ fn scrub_address(ip: &str) -> String {
 
 fn init_logger() {
     use pumpkin_config::ADVANCED_CONFIG;
-    if ADVANCED_CONFIG.logging.enabled {
+    if ADVANCED_CONFIG.get().logging.enabled {
         let mut logger = simple_logger::SimpleLogger::new();
 
-        if !ADVANCED_CONFIG.logging.timestamp {
+        if !ADVANCED_CONFIG.get().logging.timestamp {
             logger = logger.without_timestamps();
         }
 
-        if ADVANCED_CONFIG.logging.env {
+        if ADVANCED_CONFIG.get().logging.env {
             logger = logger.env();
         }
 
-        logger = logger.with_level(convert_logger_filter(ADVANCED_CONFIG.logging.level));
+        logger = logger.with_level(convert_logger_filter(ADVANCED_CONFIG.get().logging.level));
 
-        logger = logger.with_colors(ADVANCED_CONFIG.logging.color);
-        logger = logger.with_threads(ADVANCED_CONFIG.logging.threads);
+        logger = logger.with_colors(ADVANCED_CONFIG.get().logging.color);
+        logger = logger.with_threads(ADVANCED_CONFIG.get().logging.threads);
         logger.init().unwrap()
     }
 }
@@ -82,15 +100,20 @@ const fn convert_logger_filter(level: pumpkin_config::logging::LevelFilter) -> L
     }
 }
 
-fn main() -> io::Result<()> {
-    use std::sync::Arc;
+/// The address reported for clients connected through the Unix socket
+const UNIX_PEER_ADDRESS: SocketAddr = SocketAddr::new(std::net::IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+
+/// Unique id for each incoming connection.
+static NEXT_CLIENT_ID: AtomicUsize = AtomicUsize::new(0);
 
-    use entity::player::Player;
-    use pumpkin_config::{ADVANCED_CONFIG, BASIC_CONFIG};
-    use pumpkin_core::text::{color::NamedColor, TextComponent};
-    use pumpkin_plugins::plugin_loader::PluginLoader;
+fn main() -> io::Result<()> {
+    use pumpkin_config::ADVANCED_CONFIG;
     use rcon::RCONServer;
 
+    // The overrides have to be set before the configuration gets loaded
+    let args = cli::Args::parse();
+    pumpkin_config::overrides::set_overrides(args.config_overrides());
+
     init_logger();
 
     let rt = tokio::runtime::Builder::new_multi_thread()
@@ -98,19 +121,9 @@ fn main() -> io::Result<()> {
         .build()
         .unwrap();
 
-    ctrlc::set_handler(|| {
-        log::warn!(
-            "{}",
-            TextComponent::text("Stopping Server")
-                .color_named(NamedColor::Red)
-                .to_pretty_console()
-        );
-        std::process::exit(0);
-    })
-    .unwrap();
     // ensure rayon is built outside of tokio scope
     rayon::ThreadPoolBuilder::new().build_global().unwrap();
-    rt.block_on(async {
+    let result = rt.block_on(async {
         let default_panic = std::panic::take_hook();
         std::panic::set_hook(Box::new(move |info| {
             default_panic(info);
@@ -118,40 +131,74 @@ fn main() -> io::Result<()> {
             std::process::exit(1);
         }));
 
-        const SERVER: Token = Token(0);
         use std::time::Instant;
 
         let time = Instant::now();
 
-        // Create a poll instance.
-        let mut poll = Poll::new()?;
-        // Create storage for events.
-        let mut events = Events::with_capacity(128);
-
         // Setup the TCP server socket.
-        let addr = BASIC_CONFIG.server_address;
-        let mut listener = TcpListener::bind(addr)?;
-
-        // Register the server with poll we can receive events for it.
-        poll.registry()
-            .register(&mut listener, SERVER, Interest::READABLE)?;
-
-        // Unique token for each incoming connection.
-        let mut unique_id = SERVER.0 + 1;
+        let addr = BASIC_CONFIG.get().server_address;
+        let listener = TcpListener::bind(addr).await?;
+
+        #[cfg(unix)]
+        let unix_listener = match &BASIC_CONFIG.get().server_unix_socket {
+            Some(path) => {
+                // A stale socket file from a previous run would make bind fail
+                if path.exists() {
+                    std::fs::remove_file(path)?;
+                }
+                let unix_listener = UnixListener::bind(path)?;
+                log::info!("Listening on Unix socket {}", path.display());
+                Some(unix_listener)
+            }
+            None => None,
+        };
+        #[cfg(not(unix))]
+        if BASIC_CONFIG.get().server_unix_socket.is_some() {
+            log::warn!(
+                "Unix sockets are not supported on this platform, ignoring server_unix_socket"
+            );
+        }
 
-        let use_console = ADVANCED_CONFIG.commands.use_console;
-        let rcon = ADVANCED_CONFIG.rcon.clone();
+        let use_console = ADVANCED_CONFIG.get().commands.use_console;
+        let rcon = ADVANCED_CONFIG.get().rcon.clone();
 
         // Plugin loading.
         let mut plugins = PluginLoader::new();
-        log::info!("Loading plugins from root directory...");
-        plugins.load_plugins_from_directory("./plugins");
-        log::info!("Loaded {} plugins.", plugins.get_plugins().len());
+        if args.no_plugins {
+            log::info!("Not loading plugins, --no-plugins was passed");
+        } else {
+            log::info!("Loading plugins from root directory...");
+            plugins.load_plugins_from_directory("./plugins");
+            log::info!("Loaded {} plugins.", plugins.get_plugins().len());
+        }
+
+        let server = Arc::new(Server::new(plugins));
 
-        let mut clients: HashMap<usize, Arc<Client>> = HashMap::new();
-        let mut players: HashMap<usize, Arc<Player>> = HashMap::new();
+        {
+            let server = server.clone();
+            // Handles Ctrl-C, on Unix SIGTERM is handled together with SIGHUP below
+            ctrlc::set_handler(move || handle_stop_signal(&server)).unwrap();
+        }
+
+        {
+            let server = server.clone();
+            tokio::spawn(async move {
+                Ticker::default().run(&server).await;
+            });
+        }
+
+        if let Some(auth_client) = server.auth_client.clone() {
+            let server = server.clone();
+            tokio::spawn(async move {
+                match chat::session::fetch_mojang_keys(&auth_client).await {
+                    Ok(keys) => *server.mojang_public_keys.write() = keys,
+                    Err(err) => log::warn!(
+                        "Failed to fetch Mojang public keys, chat sessions can't be verified: {err}"
+                    ),
+                }
+            });
+        }
 
-        let server = Arc::new(Server::new());
         log::info!("Started Server took {}ms", time.elapsed().as_millis());
         log::info!("You now can connect to the server, Listening on {}", addr);
 
@@ -176,146 +223,292 @@ fn main() -> io::Result<()> {
                 }
             });
         }
-        if rcon.enabled {
+
+        #[cfg(unix)]
+        {
             let server = server.clone();
             tokio::spawn(async move {
-                RCONServer::new(&rcon, &server).await.unwrap();
+                if let Err(err) = handle_unix_signals(&server).await {
+                    log::warn!("Failed to listen for SIGTERM and SIGHUP: {err}");
+                }
             });
         }
-        loop {
-            if let Err(err) = poll.poll(&mut events, None) {
-                if interrupted(&err) {
-                    continue;
-                }
-                return Err(err);
-            }
 
-            for event in events.iter() {
-                match event.token() {
-                    s if s == SERVER => loop {
-                        // Received an event for the TCP server socket, which
-                        // indicates we can accept an connection.
-                        let (mut connection, address) = match listener.accept() {
-                            Ok((connection, address)) => (connection, address),
-                            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
-                                // If we get a `WouldBlock` error we know our
-                                // listener has no more incoming connections queued,
-                                // so we can return to polling and wait for some
-                                // more.
-                                break;
-                            }
-                            Err(e) => {
-                                // If it was any other kind of error, something went
-                                // wrong and we terminate with an error.
-                                return Err(e);
-                            }
-                        };
-                        if let Err(e) = connection.set_nodelay(true) {
-                            log::warn!("failed to set TCP_NODELAY {e}");
-                        }
+        if ADVANCED_CONFIG.get().metrics.enabled {
+            let server = server.clone();
+            tokio::spawn(async move {
+                metrics::run(&ADVANCED_CONFIG.get().metrics, &server).await;
+            });
+        }
 
-                        log::info!(
-                            "Accepted connection from: {}",
-                            scrub_address(&format!("{}", address))
-                        );
+        if ADVANCED_CONFIG.get().cluster.enabled {
+            let server = server.clone();
+            tokio::spawn(async move {
+                cluster::run(&ADVANCED_CONFIG.get().cluster, &server).await;
+            });
+        }
 
-                        unique_id += 1;
-                        let id = unique_id;
-                        poll.registry().register(
-                            &mut connection,
-                            Token(id),
-                            Interest::READABLE.add(Interest::WRITABLE),
-                        )?;
-                        let keep_alive = tokio::sync::mpsc::channel(1024);
-                        let client =
-                            Arc::new(Client::new(id, connection, addr, keep_alive.0.into()));
-
-                        {
-                            let client = client.clone();
-                            let mut receiver = keep_alive.1;
-                            tokio::spawn(async move {
-                                let mut interval = tokio::time::interval(Duration::from_secs(1));
-                                loop {
-                                    interval.tick().await;
-                                    let now = std::time::Instant::now();
-                                    if client.connection_state.load() == ConnectionState::Play {
-                                        if now.duration_since(client.last_alive_received.load())
-                                            >= Duration::from_secs(15)
-                                        {
-                                            dbg!("no keep alive");
-                                            client.kick("No keep alive received");
-                                            break;
-                                        }
-                                        let random = rand::random::<i64>();
-                                        client.send_packet(&CKeepAlive {
-                                            keep_alive_id: random,
-                                        });
-                                        if let Some(id) = receiver.recv().await {
-                                            if id == random {
-                                                client.last_alive_received.store(now);
-                                            }
-                                        }
-                                    } else {
-                                        client.last_alive_received.store(now);
-                                    }
-                                }
-                            });
-                        }
-                        clients.insert(id, client);
-                    },
-                    // Maybe received an event for a TCP connection.
-                    token => {
-                        // poll Player
-                        if let Some(player) = players.get_mut(&token.0) {
-                            player.client.poll(event).await;
-                            let closed = player
-                                .client
-                                .closed
-                                .load(std::sync::atomic::Ordering::Relaxed);
-                            if !closed {
-                                player.process_packets(&server).await;
-                            }
-                            if closed {
-                                if let Some(player) = players.remove(&token.0) {
-                                    player.remove().await;
-                                    let connection = &mut player.client.connection.lock();
-                                    poll.registry().deregister(connection.by_ref())?;
-                                }
-                            }
-                        };
-
-                        // Poll current Clients (non players)
-                        let (done, make_player) = if let Some(client) = clients.get_mut(&token.0) {
-                            client.poll(event).await;
-                            let closed = client.closed.load(std::sync::atomic::Ordering::Relaxed);
-                            if !closed {
-                                client.process_packets(&server).await;
-                            }
-                            (
-                                closed,
-                                client
-                                    .make_player
-                                    .load(std::sync::atomic::Ordering::Relaxed),
-                            )
-                        } else {
-                            (false, false)
-                        };
-                        if done || make_player {
-                            if let Some(client) = clients.remove(&token.0) {
-                                if done {
-                                    let connection = &mut client.connection.lock();
-                                    poll.registry().deregister(connection.by_ref())?;
-                                } else if make_player {
-                                    let id = client.id;
-                                    let (player, world) = server.add_player(id, client).await;
-                                    players.insert(id, player.clone());
-                                    world.spawn_player(&BASIC_CONFIG, player).await;
-                                }
-                            }
+        if ADVANCED_CONFIG.get().lan_broadcast.enabled {
+            let server = server.clone();
+            tokio::spawn(async move {
+                lan_broadcast::run(&ADVANCED_CONFIG.get().lan_broadcast, &server).await;
+            });
+        }
+
+        let rcon_handle = rcon.enabled.then(|| {
+            let server = server.clone();
+            tokio::spawn(async move {
+                RCONServer::new(&rcon, &server).await.unwrap();
+            })
+        });
+
+        let mut accept_tasks = Vec::new();
+        {
+            let server = server.clone();
+            accept_tasks.push(tokio::spawn(async move {
+                loop {
+                    let (connection, address) = match listener.accept().await {
+                        Ok(accepted) => accepted,
+                        Err(e) => {
+                            log::error!("Failed to accept connection: {e}");
+                            continue;
                         }
+                    };
+                    if let Err(e) = connection.set_nodelay(true) {
+                        log::warn!("failed to set TCP_NODELAY {e}");
                     }
+
+                    log::info!(
+                        "Accepted connection from: {}",
+                        scrub_address(&format!("{}", address))
+                    );
+
+                    tokio::spawn(handle_connection(server.clone(), connection, address));
+                }
+            }));
+        }
+        #[cfg(unix)]
+        if let Some(unix_listener) = unix_listener {
+            let server = server.clone();
+            accept_tasks.push(tokio::spawn(async move {
+                loop {
+                    let connection = match unix_listener.accept().await {
+                        Ok((connection, _)) => connection,
+                        Err(e) => {
+                            log::error!("Failed to accept Unix socket connection: {e}");
+                            continue;
+                        }
+                    };
+
+                    log::info!("Accepted connection from Unix socket");
+
+                    // Unix sockets have no peer IP, proxies are expected to forward the real one
+                    tokio::spawn(handle_connection(
+                        server.clone(),
+                        connection,
+                        UNIX_PEER_ADDRESS,
+                    ));
+                }
+            }));
+        }
+
+        server.wait_for_shutdown().await;
+
+        // Stop accepting new connections
+        for accept_task in accept_tasks {
+            accept_task.abort();
+        }
+        #[cfg(unix)]
+        if let Some(path) = &BASIC_CONFIG.get().server_unix_socket {
+            let _ = std::fs::remove_file(path);
+        }
+
+        shutdown(&server, rcon_handle).await;
+        Ok(())
+    });
+    // The console task may still be blocked on reading stdin, so we don't wait for it
+    rt.shutdown_background();
+    result
+}
+
+/// Starts the graceful shutdown, a second stop signal forces it
+fn handle_stop_signal(server: &Server) {
+    use pumpkin_core::text::color::NamedColor;
+    if !server.request_shutdown() {
+        log::warn!("Forcing shutdown");
+        std::process::exit(1);
+    }
+    log::warn!(
+        "{}",
+        TextComponent::text("Stopping Server")
+            .color_named(NamedColor::Red)
+            .to_pretty_console()
+    );
+}
+
+/// SIGTERM stops the Server, SIGHUP reloads the configuration
+#[cfg(unix)]
+async fn handle_unix_signals(server: &Server) -> io::Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut hangup = signal(SignalKind::hangup())?;
+    loop {
+        tokio::select! {
+            Some(()) = terminate.recv() => handle_stop_signal(server),
+            Some(()) = hangup.recv() => {
+                log::info!("Received SIGHUP, reloading configuration");
+                if let Err(err) = server.reload_config() {
+                    log::error!("Failed to reload configuration: {err}");
                 }
             }
+            else => return Ok(()),
         }
-    })
+    }
+}
+
+/// Drives a single connection, first as a Client and once it reaches the Play state as a Player
+async fn handle_connection<S>(server: Arc<Server>, connection: S, address: SocketAddr)
+where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
+    let (reader, writer) = tokio::io::split(connection);
+    let mut reader: ConnectionReader = Box::new(reader);
+    let writer: ConnectionWriter = Box::new(writer);
+
+    // Server list pings are answered without creating a Client
+    let received = match status::read_handshake(&mut reader).await {
+        Some(Handshake::Status(decoder)) => {
+            status::handle_status(&server, decoder, reader, writer).await;
+            return;
+        }
+        Some(Handshake::Other(received)) => received,
+        None => return,
+    };
+
+    let id = NEXT_CLIENT_ID.fetch_add(1, Ordering::Relaxed);
+    let client = Arc::new(Client::new(id, writer, address));
+    client.receive(&server, &received);
+    client.process_packets(&server).await;
+    client.flush();
+
+    // Handshake, Login and Config
+    let mut state = client.connection_state.load();
+    let mut deadline = state_deadline(state);
+    while !client.closed.load(Ordering::Relaxed) && !client.make_player.load(Ordering::Relaxed) {
+        let current_state = client.connection_state.load();
+        if current_state != state {
+            state = current_state;
+            deadline = state_deadline(state);
+        }
+        if tokio::time::timeout_at(deadline, client.poll(&server, &mut reader))
+            .await
+            .is_err()
+        {
+            log::debug!("Closing connection {id}, it stalled in the {state:?} state");
+            METRICS.record_connection_reaped(state);
+            if state == ConnectionState::HandShake {
+                client.close();
+            } else {
+                client.kick(&format_message(&MESSAGES.get().timed_out, &[]));
+            }
+            return;
+        }
+        client.process_packets(&server).await;
+        client.flush();
+    }
+    if client.closed.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let (player, world) = server.add_player(id, client).await;
+    world
+        .spawn_player(
+            &BASIC_CONFIG.get(),
+            player.clone(),
+            &server.dimension_names(),
+        )
+        .await;
+    player.send_recipe_book(&server);
+    server.scoreboard.send(&server, &player);
+    // The player count changed for everyone
+    server.refresh_tab_list();
+    server.refresh_status();
+    player.client.flush();
+
+    while !player.client.closed.load(Ordering::Relaxed) {
+        // Packets which arrived together with the switch to Play are already queued
+        player.process_packets(&server).await;
+        player.client.flush();
+        player.client.poll(&server, &mut reader).await;
+    }
+    player.close_container(&server);
+    player.increment_custom_stat(stats::LEAVE_GAME, 1);
+    // During a shutdown all players are saved before they get kicked
+    if server.is_running() {
+        server.save_player(&player);
+    }
+    player.remove().await;
+    server.scoreboard.forget(&player);
+    server.refresh_tab_list();
+    server.refresh_status();
+}
+
+/// When a connection which just entered `state` gets closed if it does not progress
+fn state_deadline(state: ConnectionState) -> Instant {
+    use pumpkin_config::ADVANCED_CONFIG;
+    let config = &ADVANCED_CONFIG.get().connection_timeout;
+    let timeout = match state {
+        ConnectionState::Login | ConnectionState::Transfer => config.login,
+        ConnectionState::Config => config.config,
+        _ => config.handshake,
+    };
+    Instant::now() + Duration::from_secs(timeout)
+}
+
+/// Runs the graceful shutdown sequence, called once the Server stopped accepting connections
+async fn shutdown(server: &Server, rcon_handle: Option<tokio::task::JoinHandle<()>>) {
+    server.save_players();
+    log::info!("Kicking all players");
+    let players: Vec<_> = server
+        .worlds
+        .iter()
+        .flat_map(|world| {
+            world
+                .current_players
+                .lock()
+                .values()
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    for player in &players {
+        if !player.client.closed.load(Ordering::Relaxed) {
+            player.kick(TextComponent::text(&format_message(
+                &MESSAGES.get().server_closed,
+                &[],
+            )));
+        }
+    }
+    // Make sure the disconnect messages actually arrive
+    for player in &players {
+        player.client.wait_for_writer().await;
+    }
+
+    server.backups.wait().await;
+    // A restored backup replaces the worlds, so there is nothing to save
+    if !server.backups.restore_pending(server) {
+        server.save_worlds();
+    }
+
+    log::info!("Unloading plugins");
+    server.plugins.write().unload_plugins();
+
+    if let Some(rcon_handle) = rcon_handle {
+        // The RCON loop notices the shutdown on its own
+        if let Err(err) = rcon_handle.await {
+            log::error!("RCON task failed: {err}");
+        }
+    }
+
+    log::info!("Server stopped");
 }
@@ -0,0 +1,309 @@
+use std::{
+    collections::VecDeque,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use pumpkin_config::metrics::MetricsConfig;
+use pumpkin_protocol::ConnectionState;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::{
+    client::net_stats::NetStatsSnapshot,
+    server::{ticker::TICKS_PER_SECOND, Server},
+};
+
+/// Metrics which are recorded outside of the Server, e.g. by clients
+pub static METRICS: Metrics = Metrics::new();
+
+/// How many ticks TPS and MSPT are averaged over
+const TICK_HISTORY: usize = 100;
+/// Requests larger than this are not valid metric scrapes
+const MAX_REQUEST_SIZE: usize = 8192;
+/// Scrapers which don't send their request in time get disconnected
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct Metrics {
+    packets_received: AtomicU64,
+    packets_sent: AtomicU64,
+    /// Connections closed for not reaching Play in time, by the state they stalled in
+    reaped_handshake: AtomicU64,
+    reaped_login: AtomicU64,
+    reaped_config: AtomicU64,
+    /// The start and duration of the most recent ticks
+    ticks: Mutex<VecDeque<(Instant, Duration)>>,
+}
+
+impl Metrics {
+    const fn new() -> Self {
+        Self {
+            packets_received: AtomicU64::new(0),
+            packets_sent: AtomicU64::new(0),
+            reaped_handshake: AtomicU64::new(0),
+            reaped_login: AtomicU64::new(0),
+            reaped_config: AtomicU64::new(0),
+            ticks: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn record_packets_received(&self, count: u64) {
+        self.packets_received.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn record_packet_sent(&self) {
+        self.packets_sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a connection which got closed for stalling in `state`
+    pub fn record_connection_reaped(&self, state: ConnectionState) {
+        let counter = match state {
+            ConnectionState::Login | ConnectionState::Transfer => &self.reaped_login,
+            ConnectionState::Config => &self.reaped_config,
+            _ => &self.reaped_handshake,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_tick(&self, start: Instant, duration: Duration) {
+        let mut ticks = self.ticks.lock();
+        if ticks.len() == TICK_HISTORY {
+            ticks.pop_front();
+        }
+        ticks.push_back((start, duration));
+    }
+
+    /// The average ticks per second and milliseconds per tick
+    fn tick_stats(&self) -> (f64, f64) {
+        let ticks = self.ticks.lock();
+        let (Some((first, _)), Some((last, _))) = (ticks.front(), ticks.back()) else {
+            return (TICKS_PER_SECOND as f64, 0.0);
+        };
+        let total: Duration = ticks.iter().map(|(_, duration)| *duration).sum();
+        let mspt = total.as_secs_f64() * 1000.0 / ticks.len() as f64;
+        let elapsed = last.duration_since(*first).as_secs_f64();
+        let tps = if elapsed > 0.0 {
+            ((ticks.len() - 1) as f64 / elapsed).min(TICKS_PER_SECOND as f64)
+        } else {
+            TICKS_PER_SECOND as f64
+        };
+        (tps, mspt)
+    }
+}
+
+/// Renders all metrics in the Prometheus text format
+fn render(server: &Server) -> String {
+    let mut out = String::new();
+    let mut gauge = |name: &str, help: &str, value: &dyn std::fmt::Display| {
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} gauge");
+        let _ = writeln!(out, "{name} {value}");
+    };
+
+    let (tps, mspt) = METRICS.tick_stats();
+    let chunks: usize = server
+        .worlds
+        .iter()
+        .map(|world| world.level.loaded_chunk_count())
+        .sum();
+    gauge(
+        "pumpkin_players_online",
+        "Players currently online",
+        &server.get_player_count(),
+    );
+    gauge("pumpkin_tps", "Ticks per second", &tps);
+    gauge("pumpkin_mspt", "Milliseconds per tick", &mspt);
+    gauge(
+        "pumpkin_chunks_loaded",
+        "Chunks loaded in all worlds",
+        &chunks,
+    );
+
+    let mut counter = |name: &str, help: &str, value: u64| {
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} counter");
+        let _ = writeln!(out, "{name} {value}");
+    };
+    counter(
+        "pumpkin_packets_received_total",
+        "Packets received from clients",
+        METRICS.packets_received.load(Ordering::Relaxed),
+    );
+    counter(
+        "pumpkin_packets_sent_total",
+        "Packets sent to clients",
+        METRICS.packets_sent.load(Ordering::Relaxed),
+    );
+
+    let name = "pumpkin_connections_reaped_total";
+    let _ = writeln!(
+        out,
+        "# HELP {name} Connections closed for not reaching Play in time"
+    );
+    let _ = writeln!(out, "# TYPE {name} counter");
+    for (state, counter) in [
+        ("handshake", &METRICS.reaped_handshake),
+        ("login", &METRICS.reaped_login),
+        ("config", &METRICS.reaped_config),
+    ] {
+        let _ = writeln!(
+            out,
+            "{name}{{state=\"{state}\"}} {}",
+            counter.load(Ordering::Relaxed)
+        );
+    }
+
+    let players: Vec<_> = server
+        .get_all_players()
+        .iter()
+        .map(|player| {
+            (
+                escape_label(&player.gameprofile.name),
+                player.client.net_stats.snapshot(),
+            )
+        })
+        .collect();
+    if !players.is_empty() {
+        let per_player: [(&str, &str, fn(&NetStatsSnapshot) -> u64); 4] = [
+            (
+                "pumpkin_player_bytes_received_total",
+                "Bytes received from each player",
+                |stats| stats.bytes_received,
+            ),
+            (
+                "pumpkin_player_bytes_sent_total",
+                "Bytes sent to each player",
+                |stats| stats.bytes_sent,
+            ),
+            (
+                "pumpkin_player_packets_received_total",
+                "Packets received from each player",
+                |stats| stats.packets_received,
+            ),
+            (
+                "pumpkin_player_packets_sent_total",
+                "Packets sent to each player",
+                |stats| stats.packets_sent,
+            ),
+        ];
+        for (name, help, value) in per_player {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} counter");
+            for (player, stats) in &players {
+                let _ = writeln!(out, "{name}{{player=\"{player}\"}} {}", value(stats));
+            }
+        }
+    }
+
+    let plugins = server.plugins.read();
+    let timings = plugins.get_timings();
+    if !timings.is_empty() {
+        let _ = writeln!(
+            out,
+            "# HELP pumpkin_plugin_events_total Events fired to each plugin"
+        );
+        let _ = writeln!(out, "# TYPE pumpkin_plugin_events_total counter");
+        for plugin in timings {
+            let _ = writeln!(
+                out,
+                "pumpkin_plugin_events_total{{plugin=\"{}\"}} {}",
+                escape_label(&plugin.name),
+                plugin.events()
+            );
+        }
+        let _ = writeln!(
+            out,
+            "# HELP pumpkin_plugin_event_seconds_total Time each plugin spent handling events"
+        );
+        let _ = writeln!(out, "# TYPE pumpkin_plugin_event_seconds_total counter");
+        for plugin in timings {
+            let _ = writeln!(
+                out,
+                "pumpkin_plugin_event_seconds_total{{plugin=\"{}\"}} {}",
+                escape_label(&plugin.name),
+                plugin.time().as_secs_f64()
+            );
+        }
+    }
+    out
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Serves the metrics endpoint until the Server shuts down
+pub async fn run(config: &MetricsConfig, server: &Arc<Server>) {
+    let listener = match TcpListener::bind(config.address).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            log::error!(
+                "Failed to start metrics endpoint on {}: {err}",
+                config.address
+            );
+            return;
+        }
+    };
+    log::info!("Metrics endpoint listening on {}", config.address);
+
+    while server.is_running() {
+        let connection = tokio::select! {
+            connection = listener.accept() => connection,
+            () = server.wait_for_shutdown() => break,
+        };
+        let Ok((stream, _)) = connection else {
+            continue;
+        };
+        let server = server.clone();
+        tokio::spawn(async move {
+            if let Err(err) = handle_request(stream, &server).await {
+                log::debug!("Failed to answer metrics request: {err}");
+            }
+        });
+    }
+}
+
+async fn handle_request(mut stream: TcpStream, server: &Server) -> std::io::Result<()> {
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    // We only care about the request line, but read the whole head so the client is not reset
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        let n = tokio::time::timeout(REQUEST_TIMEOUT, stream.read(&mut buf))
+            .await
+            .map_err(|_| std::io::ErrorKind::TimedOut)??;
+        if n == 0 {
+            return Ok(());
+        }
+        request.extend_from_slice(&buf[..n]);
+        if request.len() > MAX_REQUEST_SIZE {
+            return respond(&mut stream, "413 Payload Too Large", "").await;
+        }
+    }
+
+    let request_line = request.split(|&b| b == b'\r').next().unwrap_or_default();
+    let mut parts = request_line.split(|&b| b == b' ');
+    match (parts.next(), parts.next()) {
+        (Some(b"GET"), Some(b"/metrics")) => respond(&mut stream, "200 OK", &render(server)).await,
+        (Some(b"GET"), _) => respond(&mut stream, "404 Not Found", "").await,
+        _ => respond(&mut stream, "405 Method Not Allowed", "").await,
+    }
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, body: &str) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
@@ -0,0 +1,161 @@
+use std::{net::IpAddr, sync::LazyLock};
+
+use aes_gcm::{aead::Aead, Aes128Gcm, KeyInit, Nonce};
+use base64::{engine::general_purpose, Engine};
+use pumpkin_config::{floodgate::FloodgateConfig, ADVANCED_CONFIG};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::client::authentication::GameProfile;
+
+/// Every Floodgate payload starts with this, followed by the format version
+const IDENTIFIER: &str = "^Floodgate^";
+/// The format version we understand, encoded as `version + 0x3E`
+const VERSION: u8 = 0x3E;
+/// Separates the IV from the encrypted data
+const SPLITTER: char = '!';
+/// The fields of the decrypted data, separated by `\0`
+const FIELD_COUNT: usize = 12;
+/// Java usernames are at most this long
+const MAX_USERNAME_LENGTH: usize = 16;
+
+#[derive(Error, Debug)]
+pub enum FloodgateError {
+    #[error("Floodgate key is not loaded")]
+    MissingKey,
+    #[error("Unsupported Floodgate data version")]
+    UnsupportedVersion,
+    #[error("Malformed Floodgate data")]
+    Malformed,
+    #[error("Failed to decrypt Floodgate data, is the key the same as Geyser's?")]
+    Decrypt,
+}
+
+/// The data Geyser sends about a Bedrock player
+#[derive(Debug, Clone)]
+pub struct BedrockData {
+    /// The Bedrock username, may contain spaces
+    pub username: String,
+    /// The Xbox user id
+    pub xuid: u64,
+    /// The operating system of the device, as Floodgate's `DeviceOs` id
+    pub device_os: i32,
+    pub language_code: String,
+    /// 0 = classic, 1 = pocket
+    pub ui_profile: i32,
+    /// 0 = unknown, 1 = keyboard and mouse, 2 = touch, 3 = controller, 4 = VR
+    pub input_mode: i32,
+    /// The real IP of the player
+    pub ip: Option<IpAddr>,
+    /// Whether the data went through a proxy before reaching us
+    pub from_proxy: bool,
+}
+
+impl BedrockData {
+    /// The profile Bedrock players get, they have no Mojang account
+    pub fn game_profile(&self, config: &FloodgateConfig) -> GameProfile {
+        let username = if config.replace_spaces {
+            self.username.replace(' ', "_")
+        } else {
+            self.username.clone()
+        };
+        let mut name = format!("{}{username}", config.username_prefix);
+        name.truncate(
+            name.char_indices()
+                .nth(MAX_USERNAME_LENGTH)
+                .map_or(name.len(), |(index, _)| index),
+        );
+        GameProfile {
+            // Same as Floodgate, so player data stays the same when switching servers
+            id: Uuid::from_u64_pair(0, self.xuid),
+            name,
+            properties: vec![],
+            profile_actions: None,
+        }
+    }
+}
+
+static KEY: LazyLock<Option<Aes128Gcm>> = LazyLock::new(|| {
+    let path = &ADVANCED_CONFIG.get().floodgate.key_file;
+    match std::fs::read(path) {
+        Ok(key) => {
+            let cipher = Aes128Gcm::new_from_slice(&key).ok();
+            if cipher.is_none() {
+                log::error!("Floodgate key {path} is not a valid AES-128 key");
+            }
+            cipher
+        }
+        Err(err) => {
+            log::error!("Failed to load Floodgate key {path}: {err}");
+            None
+        }
+    }
+});
+
+/// Finds the Floodgate data in the server address of a handshake.
+/// Returns the server address without it and the data, if there is any
+pub fn split_server_address(server_address: &str) -> (String, Option<&str>) {
+    let mut data = None;
+    let address = server_address
+        .split('\0')
+        .filter(|part| {
+            if part.starts_with(IDENTIFIER) {
+                data = Some(*part);
+                false
+            } else {
+                true
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\0");
+    (address, data)
+}
+
+/// Decrypts and parses the Floodgate data Geyser put in the handshake
+pub fn decode(data: &str) -> Result<BedrockData, FloodgateError> {
+    let cipher = KEY.as_ref().ok_or(FloodgateError::MissingKey)?;
+    let data = data
+        .strip_prefix(IDENTIFIER)
+        .ok_or(FloodgateError::Malformed)?;
+    let (&version, data) = data
+        .as_bytes()
+        .split_first()
+        .ok_or(FloodgateError::Malformed)?;
+    if version != VERSION {
+        return Err(FloodgateError::UnsupportedVersion);
+    }
+    let data = std::str::from_utf8(data).map_err(|_| FloodgateError::Malformed)?;
+    let (iv, encrypted) = data.split_once(SPLITTER).ok_or(FloodgateError::Malformed)?;
+    let iv = general_purpose::STANDARD
+        .decode(iv)
+        .map_err(|_| FloodgateError::Malformed)?;
+    let encrypted = general_purpose::STANDARD
+        .decode(encrypted)
+        .map_err(|_| FloodgateError::Malformed)?;
+    if iv.len() != 12 {
+        return Err(FloodgateError::Malformed);
+    }
+
+    let decrypted = cipher
+        .decrypt(Nonce::from_slice(&iv), encrypted.as_slice())
+        .map_err(|_| FloodgateError::Decrypt)?;
+    let decrypted = String::from_utf8(decrypted).map_err(|_| FloodgateError::Malformed)?;
+    let fields: Vec<&str> = decrypted.split('\0').collect();
+    if fields.len() != FIELD_COUNT {
+        return Err(FloodgateError::Malformed);
+    }
+
+    let parse_int = |field: &str| field.parse().map_err(|_| FloodgateError::Malformed);
+    // fields[0] is the version of Geyser's data, fields[8] the linked Java account and
+    // fields[10..] are only used by the Floodgate plugin itself
+    Ok(BedrockData {
+        username: fields[1].to_string(),
+        xuid: fields[2].parse().map_err(|_| FloodgateError::Malformed)?,
+        device_os: parse_int(fields[3])?,
+        language_code: fields[4].to_string(),
+        ui_profile: parse_int(fields[5])?,
+        input_mode: parse_int(fields[6])?,
+        ip: fields[7].parse().ok(),
+        from_proxy: fields[9] == "1",
+    })
+}
@@ -2,7 +2,7 @@ use std::net::SocketAddr;
 
 use bytes::{BufMut, BytesMut};
 use hmac::{Hmac, Mac};
-use pumpkin_config::proxy::VelocityConfig;
+use pumpkin_config::{messages::format_message, proxy::VelocityConfig, MESSAGES};
 use pumpkin_protocol::{
     bytebuf::ByteBuffer, client::login::CLoginPluginRequest, server::login::SLoginPluginResponse,
 };
@@ -65,6 +65,6 @@ pub fn receive_plugin_response(
         *client.address.lock() = addr;
         todo!()
     } else {
-        client.kick("This server requires you to connect with Velocity.")
+        client.kick(&format_message(&MESSAGES.get().velocity_required, &[]))
     }
 }
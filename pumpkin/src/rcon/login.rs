@@ -0,0 +1,69 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+use pumpkin_config::RCONConfig;
+
+/// The wrong passwords one address sent lately
+struct Failures {
+    count: u32,
+    last: Instant,
+}
+
+/// Keeps addresses from guessing the password, addresses which sent too many wrong passwords
+/// are banned for a while
+#[derive(Default)]
+pub struct LoginLimiter {
+    failures: HashMap<IpAddr, Failures>,
+}
+
+impl LoginLimiter {
+    /// Whether the address may not connect, because it is not allowed or still banned
+    pub fn is_blocked(&mut self, config: &RCONConfig, address: IpAddr) -> bool {
+        if !config.allowed_addresses.is_empty() && !config.allowed_addresses.contains(&address) {
+            return true;
+        }
+        let ban_duration = Duration::from_secs(config.ban_duration);
+        // Bans end and failures are forgotten after a while
+        self.failures
+            .retain(|_, failures| failures.last.elapsed() < ban_duration);
+        config.max_failed_logins > 0
+            && self
+                .failures
+                .get(&address)
+                .is_some_and(|failures| failures.count >= config.max_failed_logins)
+    }
+
+    /// Counts a wrong password, returns true if the address is banned now
+    pub fn fail(&mut self, config: &RCONConfig, address: IpAddr) -> bool {
+        let failures = self.failures.entry(address).or_insert(Failures {
+            count: 0,
+            last: Instant::now(),
+        });
+        failures.count += 1;
+        failures.last = Instant::now();
+        config.max_failed_logins > 0 && failures.count >= config.max_failed_logins
+    }
+
+    /// Forgets the wrong passwords of an address once it logged in
+    pub fn succeed(&mut self, address: IpAddr) {
+        self.failures.remove(&address);
+    }
+}
+
+/// Compares the passwords in constant time, so how long it takes tells nothing about how much
+/// of the password was right
+pub fn password_matches(given: &str, password: &str) -> bool {
+    let given = given.as_bytes();
+    let password = password.as_bytes();
+    // Like this every byte of the password is looked at, even if the lengths differ
+    let difference = password
+        .iter()
+        .enumerate()
+        .fold(given.len() ^ password.len(), |difference, (i, byte)| {
+            difference | usize::from(given.get(i).copied().unwrap_or(0) ^ byte)
+        });
+    difference == 0
+}
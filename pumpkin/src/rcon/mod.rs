@@ -2,18 +2,21 @@ use std::{
     collections::HashMap,
     io::{self, Read, Write},
     net::SocketAddr,
+    time::Duration,
 };
 
+use login::LoginLimiter;
 use mio::{
     net::{TcpListener, TcpStream},
     Events, Interest, Poll, Token,
 };
-use packet::{ClientboundPacket, Packet, PacketError, ServerboundPacket};
+use packet::{fragments, ClientboundPacket, Packet, PacketError, ServerboundPacket};
 use pumpkin_config::{RCONConfig, ADVANCED_CONFIG};
 use thiserror::Error;
 
-use crate::server::Server;
+use crate::{commands::CommandSender, server::Server};
 
+mod login;
 mod packet;
 
 #[derive(Debug, Error)]
@@ -28,6 +31,9 @@ pub enum RCONError {
 
 const SERVER: Token = Token(0);
 
+/// How often the RCON loop checks if the Server is shutting down
+const SHUTDOWN_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
 pub struct RCONServer;
 
 impl RCONServer {
@@ -47,9 +53,15 @@ impl RCONServer {
         let mut connections: HashMap<usize, RCONClient> = HashMap::new();
 
         let password = config.password.clone();
+        let mut limiter = LoginLimiter::default();
 
         loop {
-            poll.poll(&mut events, None).unwrap();
+            poll.poll(&mut events, Some(SHUTDOWN_CHECK_INTERVAL))
+                .unwrap();
+            if !server.is_running() {
+                log::info!("Stopping RCON");
+                return Ok(Self);
+            }
 
             for event in events.iter() {
                 match event.token() {
@@ -76,6 +88,10 @@ impl RCONServer {
                         {
                             break;
                         }
+                        if limiter.is_blocked(config, address.ip()) {
+                            log::debug!("RCON ({address}): Refused the connection of a not allowed or banned address");
+                            continue;
+                        }
 
                         unique_id += 1;
                         let token = unique_id;
@@ -91,13 +107,13 @@ impl RCONServer {
 
                     token => {
                         let done = if let Some(client) = connections.get_mut(&token.0) {
-                            client.handle(server, &password).await
+                            client.handle(server, &password, &mut limiter).await
                         } else {
                             false
                         };
                         if done {
                             if let Some(mut client) = connections.remove(&token.0) {
-                                let config = &ADVANCED_CONFIG.rcon;
+                                let config = &ADVANCED_CONFIG.get().rcon;
                                 if config.logging.log_quit {
                                     log::info!(
                                         "RCON ({}): Client closed connection",
@@ -114,11 +130,25 @@ impl RCONServer {
     }
 }
 
+/// Runs a command for an RCON client, its output is what it would have sent to the console
+/// with one line per message
+fn execute_command(server: &Server, command: &str) -> String {
+    let mut output = Vec::new();
+    server.command_dispatcher.handle_command(
+        &mut CommandSender::Rcon(&mut output),
+        server,
+        command,
+    );
+    output.join("\n")
+}
+
 pub struct RCONClient {
     connection: TcpStream,
     address: SocketAddr,
     logged_in: bool,
     incoming: Vec<u8>,
+    /// Packets the socket didn't take yet
+    outgoing: Vec<u8>,
     closed: bool,
 }
 
@@ -129,11 +159,17 @@ impl RCONClient {
             address,
             logged_in: false,
             incoming: Vec::new(),
+            outgoing: Vec::new(),
             closed: false,
         }
     }
 
-    pub async fn handle(&mut self, server: &Server, password: &str) -> bool {
+    pub async fn handle(
+        &mut self,
+        server: &Server,
+        password: &str,
+        limiter: &mut LoginLimiter,
+    ) -> bool {
         if !self.closed {
             loop {
                 match self.read_bytes() {
@@ -148,63 +184,94 @@ impl RCONClient {
                 }
             }
             // If we get a close here, we might have a reply, which we still want to write.
-            let _ = self.poll(server, password).await.map_err(|e| {
+            let _ = self.poll(server, password, limiter).await.map_err(|e| {
                 log::error!("RCON error: {e}");
                 self.closed = true;
             });
         }
-        self.closed
+        if let Err(e) = self.flush() {
+            log::error!("could not send packet: {e}");
+            return true;
+        }
+        // Closed clients are kept until they got everything they are owed
+        self.closed && self.outgoing.is_empty()
     }
 
-    async fn poll(&mut self, server: &Server, password: &str) -> Result<(), PacketError> {
+    async fn poll(
+        &mut self,
+        server: &Server,
+        password: &str,
+        limiter: &mut LoginLimiter,
+    ) -> Result<(), PacketError> {
         loop {
             let packet = match self.receive_packet().await? {
                 Some(p) => p,
                 None => return Ok(()),
             };
 
-            let config = &ADVANCED_CONFIG.rcon;
+            let config = &ADVANCED_CONFIG.get().rcon;
             match packet.get_type() {
                 ServerboundPacket::Auth => {
-                    let body = packet.get_body();
-                    if !body.is_empty() && packet.get_body() == password {
-                        self.send(ClientboundPacket::AuthResponse, packet.get_id(), "".into())
-                            .await?;
+                    if !password.is_empty() && login::password_matches(packet.get_body(), password)
+                    {
+                        limiter.succeed(self.address.ip());
+                        self.send(ClientboundPacket::AuthResponse, packet.get_id(), "".into());
                         if config.logging.log_logged_successfully {
                             log::info!("RCON ({}): Client logged in successfully", self.address);
                         }
                         self.logged_in = true;
                     } else {
+                        let banned = limiter.fail(config, self.address.ip());
                         if config.logging.log_wrong_password {
                             log::info!("RCON ({}): Client has tried wrong password", self.address);
                         }
-                        self.send(ClientboundPacket::AuthResponse, -1, "".into())
-                            .await?;
+                        if banned {
+                            log::warn!(
+                                "RCON ({}): Banned the address for {} seconds after too many wrong passwords",
+                                self.address,
+                                config.ban_duration
+                            );
+                        }
+                        self.send(ClientboundPacket::AuthResponse, -1, "".into());
                         self.closed = true;
                     }
                 }
                 ServerboundPacket::ExecCommand => {
                     if self.logged_in {
-                        let mut output = Vec::new();
-                        let dispatcher = server.command_dispatcher.clone();
-                        dispatcher.handle_command(
-                            &mut crate::commands::CommandSender::Rcon(&mut output),
-                            server,
-                            packet.get_body(),
-                        );
-                        for line in output {
-                            if config.logging.log_commands {
-                                log::info!("RCON ({}): {}", self.address, line);
-                            }
-                            self.send(ClientboundPacket::Output, packet.get_id(), line)
-                                .await?;
+                        let command = packet.get_body();
+                        if config.logging.log_commands {
+                            log::info!("RCON ({}): {}", self.address, command);
                         }
+                        let output = execute_command(server, command);
+                        self.send_output(packet.get_id(), &output);
+                    } else {
+                        // Like in vanilla, commands before logging in are answered as failed logins
+                        self.send(ClientboundPacket::AuthResponse, -1, "".into());
+                        self.closed = true;
                     }
                 }
+                ServerboundPacket::ResponseValue => {
+                    // Sent back right away, it arrives after the output of the commands before it
+                    self.send(ClientboundPacket::Output, packet.get_id(), "".into());
+                }
             }
         }
     }
 
+    /// Sends the output of a command, long output is split over several packets which clients
+    /// join again. Clients wait for a response, even if the command had no output
+    fn send_output(&mut self, id: i32, output: &str) {
+        let fragments = fragments(output);
+        let fragmented = fragments.len() > 1;
+        for fragment in fragments {
+            self.send(ClientboundPacket::Output, id, fragment.to_string());
+        }
+        // An empty packet tells clients that the output ended
+        if fragmented {
+            self.send(ClientboundPacket::Output, id, "".into());
+        }
+    }
+
     fn read_bytes(&mut self) -> io::Result<bool> {
         let mut buf = [0; 1460];
         let n = self.connection.read(&mut buf)?;
@@ -215,16 +282,25 @@ impl RCONClient {
         Ok(false)
     }
 
-    async fn send(
-        &mut self,
-        packet: ClientboundPacket,
-        id: i32,
-        body: String,
-    ) -> Result<(), PacketError> {
-        let buf = packet.write_buf(id, body);
-        self.connection
-            .write(&buf)
-            .map_err(PacketError::FailedSend)?;
+    /// Queues a packet, it is written once the socket takes it
+    fn send(&mut self, packet: ClientboundPacket, id: i32, body: String) {
+        self.outgoing.extend_from_slice(&packet.write_buf(id, body));
+    }
+
+    /// Writes as much of the queued packets as the socket takes without blocking, the rest
+    /// follows once the socket is writable again
+    fn flush(&mut self) -> io::Result<()> {
+        while !self.outgoing.is_empty() {
+            match self.connection.write(&self.outgoing) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(n) => {
+                    self.outgoing.drain(..n);
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
         Ok(())
     }
 
@@ -16,6 +16,9 @@ pub enum ServerboundPacket {
     /// This packet type represents a command issued to the server by a client. This can be a ConCommand such as kill <player> or weather clear.
     /// The response will vary depending on the command issued.
     ExecCommand = 3,
+    /// An empty response which the server sends back, clients send it after a command to know
+    /// once all parts of the command's output arrived
+    ResponseValue = 0,
 }
 
 impl ServerboundPacket {
@@ -23,6 +26,7 @@ impl ServerboundPacket {
         match n {
             3 => Self::Auth,
             2 => Self::ExecCommand,
+            0 => Self::ResponseValue,
             _ => Self::Auth,
         }
     }
@@ -55,14 +59,32 @@ impl ClientboundPacket {
     }
 }
 
+/// The longest body of a packet the server sends, longer output is split over several packets
+pub const MAX_BODY_LENGTH: usize = 4096;
+
+/// Splits the body into parts which fit into one packet each, characters are never split
+pub fn fragments(body: &str) -> Vec<&str> {
+    let mut fragments = Vec::new();
+    let mut rest = body;
+    while rest.len() > MAX_BODY_LENGTH {
+        let mut end = MAX_BODY_LENGTH;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (fragment, left) = rest.split_at(end);
+        fragments.push(fragment);
+        rest = left;
+    }
+    fragments.push(rest);
+    fragments
+}
+
 #[derive(Error, Debug)]
 pub enum PacketError {
     #[error("invalid length")]
     InvalidLength,
     #[error("failed to read packet")]
     FailedRead(std::io::Error),
-    #[error("failed to send packet")]
-    FailedSend(std::io::Error),
     #[error("invalid Packet String body")]
     InvalidBody(FromUtf8Error),
 }
@@ -118,3 +140,35 @@ impl Packet {
         self.id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{fragments, MAX_BODY_LENGTH};
+
+    #[test]
+    fn short_body_is_one_fragment() {
+        assert_eq!(fragments(""), [""]);
+        let body = "a".repeat(MAX_BODY_LENGTH);
+        assert_eq!(fragments(&body), [body.as_str()]);
+    }
+
+    #[test]
+    fn long_body_is_split_at_the_limit() {
+        let body = "a".repeat(MAX_BODY_LENGTH + 1);
+        let fragments = fragments(&body);
+        assert_eq!(fragments.len(), 2);
+        assert_eq!(fragments[0].len(), MAX_BODY_LENGTH);
+        assert_eq!(fragments[1], "a");
+    }
+
+    #[test]
+    fn characters_are_not_split() {
+        // The three byte character would cross the limit
+        let body = format!("{}€€", "a".repeat(MAX_BODY_LENGTH - 1));
+        let fragments = fragments(&body);
+        assert_eq!(fragments.len(), 2);
+        assert_eq!(fragments[0], "a".repeat(MAX_BODY_LENGTH - 1));
+        assert_eq!(fragments[1], "€€");
+        assert_eq!(fragments.concat(), body);
+    }
+}
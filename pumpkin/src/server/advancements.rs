@@ -0,0 +1,172 @@
+use std::{collections::HashMap, path::Path, sync::atomic::AtomicU64, sync::atomic::Ordering};
+
+use pumpkin_plugins::plugin_loader::PluginLoader;
+use pumpkin_protocol::{
+    client::play::{AdvancementDisplay, AdvancementEntry},
+    slot::Slot,
+};
+use pumpkin_world::{
+    advancement::{self, Advancement},
+    global_registry::{self, ITEM_REGISTRY},
+};
+
+use super::{ticker::TICKS_PER_SECOND, Server};
+
+/// All advancements players can get: the built-in ones, those of the datapacks of the main world
+/// and those plugins add
+pub struct Advancements {
+    /// Parents come before their children
+    advancements: Vec<(String, Advancement)>,
+    /// Where every advancement is shown in its tab
+    positions: HashMap<String, (f32, f32)>,
+    ticks: AtomicU64,
+}
+
+impl Advancements {
+    pub fn load(root_folder: &Path, plugins: &PluginLoader) -> Self {
+        let mut loaded = advancement::load_advancements(root_folder);
+        plugins.for_each(|plugin| loaded.extend(plugin.advancements()));
+
+        // Like vanilla, advancements whose parent is missing are left out, and so are their
+        // children
+        let mut advancements: Vec<(String, Advancement)> = Vec::new();
+        loop {
+            let ready: Vec<String> = loaded
+                .iter()
+                .filter(|(_, advancement)| {
+                    advancement.parent.as_ref().map_or(true, |parent| {
+                        advancements.iter().any(|(id, _)| id == parent)
+                    })
+                })
+                .map(|(id, _)| id.clone())
+                .collect();
+            if ready.is_empty() {
+                break;
+            }
+            let mut ready: Vec<(String, Advancement)> = ready
+                .into_iter()
+                .filter_map(|id| loaded.remove_entry(&id))
+                .collect();
+            ready.sort_by(|(a, _), (b, _)| a.cmp(b));
+            advancements.extend(ready);
+        }
+        for id in loaded.keys() {
+            log::warn!("Skipping advancement {id}, its parent is missing");
+        }
+        log::info!("Loaded {} advancements", advancements.len());
+
+        let positions = layout(&advancements);
+        Self {
+            advancements,
+            positions,
+            ticks: AtomicU64::new(0),
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Advancement> {
+        self.advancements
+            .iter()
+            .find(|(other, _)| other == id)
+            .map(|(_, advancement)| advancement)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(String, Advancement)> {
+        self.advancements.iter()
+    }
+
+    /// How a client learns about an advancement, `requirements` has to be
+    /// `advancement.requirements()`
+    pub fn entry<'a>(
+        &'a self,
+        id: &'a str,
+        advancement: &'a Advancement,
+        requirements: &'a [Vec<String>],
+    ) -> AdvancementEntry<'a> {
+        let display = advancement.display.as_ref().map(|display| {
+            let (x, y) = self.positions.get(id).copied().unwrap_or_default();
+            let icon = global_registry::find_protocol_id(ITEM_REGISTRY, &display.icon.id)
+                .map_or_else(
+                    || Slot::new(0.into(), 0.into()),
+                    |item_id| {
+                        Slot::new(
+                            i32::from(display.icon.count).into(),
+                            (item_id as i32).into(),
+                        )
+                    },
+                );
+            AdvancementDisplay {
+                title: &display.title,
+                description: &display.description,
+                icon,
+                frame: (display.frame as i32).into(),
+                background: display.background.as_deref(),
+                show_toast: display.show_toast,
+                hidden: display.hidden,
+                x,
+                y,
+            }
+        });
+        AdvancementEntry {
+            id,
+            parent: advancement.parent.as_deref(),
+            display,
+            requirements,
+            sends_telemetry_data: advancement.sends_telemetry_event,
+        }
+    }
+
+    /// Sends the advancements to players who just joined and checks the criteria which do not
+    /// fire on their own
+    pub fn tick(&self, server: &Server) {
+        let ticks = self.ticks.fetch_add(1, Ordering::Relaxed);
+        // Nothing tells when the inventory changes, so it is checked once a second
+        let check_inventory = ticks % TICKS_PER_SECOND == 0;
+        for player in server.get_all_players() {
+            player.tick_advancements(server, check_inventory);
+        }
+    }
+}
+
+/// Places the advancements of every tab, like a tree growing to the right. Every advancement
+/// without children gets its own row and parents are in the row of their first child
+fn layout(advancements: &[(String, Advancement)]) -> HashMap<String, (f32, f32)> {
+    let mut children: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (id, advancement) in advancements {
+        if let Some(parent) = &advancement.parent {
+            children.entry(parent).or_default().push(id);
+        }
+    }
+    let mut positions = HashMap::new();
+    for (id, advancement) in advancements {
+        if advancement.parent.is_none() {
+            place(id, 0, &mut 0, &children, &mut positions);
+        }
+    }
+    positions
+}
+
+/// Places `id` and its children, returns the row it ended up in
+fn place(
+    id: &str,
+    column: u16,
+    next_row: &mut u16,
+    children: &HashMap<&str, Vec<&str>>,
+    positions: &mut HashMap<String, (f32, f32)>,
+) -> u16 {
+    let row = match children.get(id) {
+        Some(children_ids) => {
+            let mut first_row = None;
+            for child in children_ids {
+                let row = place(child, column + 1, next_row, children, positions);
+                first_row.get_or_insert(row);
+            }
+            first_row.unwrap_or(*next_row)
+        }
+        None => {
+            *next_row += 1;
+            *next_row - 1
+        }
+    };
+    positions.insert(id.to_string(), (f32::from(column), f32::from(row)));
+    row
+}
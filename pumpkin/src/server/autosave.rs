@@ -0,0 +1,118 @@
+use std::time::Instant;
+
+use parking_lot::Mutex;
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_core::math::vector2::Vector2;
+
+use super::{ticker::TICKS_PER_SECOND, Server};
+
+/// An autosave which did not write all of its chunks yet
+struct RunningSave {
+    /// The chunks still to save, with the index of their world
+    pending: Vec<(usize, Vector2<i32>)>,
+    saved: usize,
+    started: Instant,
+}
+
+#[derive(Default)]
+struct AutosaveState {
+    /// Ticks since the last autosave started
+    ticks: u64,
+    running: Option<RunningSave>,
+}
+
+/// Periodically saves changed chunks, spread over several ticks so a save never stalls the Server
+#[derive(Default)]
+pub struct Autosave {
+    state: Mutex<AutosaveState>,
+}
+
+impl Autosave {
+    /// Starts an autosave once the interval passed and saves the next chunks of a running one
+    pub fn tick(&self, server: &Server) {
+        let config = &ADVANCED_CONFIG.get().autosave;
+        // The region files must not change while a backup is written, the autosave resumes after
+        if server.backups.is_running() {
+            return;
+        }
+        let mut state = self.state.lock();
+        if state.running.is_none() {
+            if !config.enabled {
+                return;
+            }
+            state.ticks += 1;
+            if state.ticks < config.interval * TICKS_PER_SECOND {
+                return;
+            }
+            state.ticks = 0;
+            for world in &server.worlds {
+                world.save_level_data();
+            }
+            server
+                .scoreboard
+                .save(server.default_world().level.root_folder());
+            server.save_players();
+
+            let pending: Vec<_> = server
+                .worlds
+                .iter()
+                .enumerate()
+                .flat_map(|(index, world)| {
+                    world
+                        .level
+                        .dirty_chunks()
+                        .into_iter()
+                        .map(move |at| (index, at))
+                })
+                .collect();
+            if pending.is_empty() {
+                return;
+            }
+            log::info!("Saving the world, {} chunks changed", pending.len());
+            state.running = Some(RunningSave {
+                pending,
+                saved: 0,
+                started: Instant::now(),
+            });
+        }
+
+        let Some(running) = &mut state.running else {
+            return;
+        };
+        for _ in 0..config.chunks_per_tick {
+            let Some((world, at)) = running.pending.pop() else {
+                break;
+            };
+            if server.worlds[world].level.save_chunk(at) {
+                running.saved += 1;
+            }
+        }
+        if !running.pending.is_empty() {
+            return;
+        }
+
+        let Some(running) = state.running.take() else {
+            return;
+        };
+        drop(state);
+        let duration = running.started.elapsed();
+        log::info!(
+            "Saved the world, {} chunks in {}ms",
+            running.saved,
+            duration.as_millis()
+        );
+        server.fire_world_save(running.saved, duration, true);
+    }
+
+    /// Immediately saves the chunks a running autosave did not get to yet and stops it
+    pub fn save_pending(&self, server: &Server) -> usize {
+        let Some(running) = self.state.lock().running.take() else {
+            return 0;
+        };
+        running
+            .pending
+            .into_iter()
+            .filter(|(world, at)| server.worlds[*world].level.save_chunk(*at))
+            .count()
+    }
+}
@@ -0,0 +1,299 @@
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use parking_lot::Mutex;
+use pumpkin_config::ADVANCED_CONFIG;
+use thiserror::Error;
+
+use super::{ticker::TICKS_PER_SECOND, Server};
+
+/// Backups are the archives with this extension in the backup folder
+const EXTENSION: &str = ".tar.zst";
+/// Archives are unpacked here before they replace the world folders
+const RESTORE_FOLDER: &str = ".restore";
+
+#[derive(Error, Debug)]
+pub enum BackupError {
+    #[error("A backup is already being written")]
+    AlreadyRunning,
+    #[error("There is no backup {0}")]
+    NotFound(String),
+    #[error("{0}")]
+    Io(#[from] io::Error),
+}
+
+/// An archive in the backup folder
+pub struct BackupInfo {
+    /// When the backup was written, e.g. `2024-10-16_18-30-00`
+    pub name: String,
+    pub path: PathBuf,
+    /// The size of the archive in bytes
+    pub size: u64,
+}
+
+/// Writes the folders of all worlds into zstd compressed tar archives, on a schedule and with
+/// `/backup now`. Saving pauses while an archive is written, so the region files don't change
+/// while they are copied
+#[derive(Default)]
+pub struct Backups {
+    /// Ticks since the last scheduled backup
+    ticks: AtomicU64,
+    running: Arc<AtomicBool>,
+    /// The backup which replaces the worlds once the Server stopped
+    pending_restore: Mutex<Option<BackupInfo>>,
+}
+
+impl Backups {
+    /// Starts a scheduled backup once the interval passed
+    pub fn tick(&self, server: &Server) {
+        let config = &ADVANCED_CONFIG.get().backup;
+        if !config.enabled {
+            return;
+        }
+        if self.ticks.fetch_add(1, Ordering::Relaxed) + 1 < config.interval * TICKS_PER_SECOND {
+            return;
+        }
+        self.ticks.store(0, Ordering::Relaxed);
+        if let Err(err) = self.start(server, |_| {}) {
+            log::warn!("Skipping the scheduled backup: {err}");
+        }
+    }
+
+    /// Whether an archive is being written, saving chunks is paused until it is done
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Acquire)
+    }
+
+    /// Saves all worlds and writes them into a new archive in the background. `done` is called
+    /// once the archive was written, old backups are deleted afterwards
+    pub fn start(
+        &self,
+        server: &Server,
+        done: impl FnOnce(&Result<BackupInfo, BackupError>) + Send + 'static,
+    ) -> Result<(), BackupError> {
+        if self.running.swap(true, Ordering::AcqRel) {
+            return Err(BackupError::AlreadyRunning);
+        }
+        // Everything changed goes into the region files first, they don't change until the
+        // archive is written
+        server.save_worlds();
+        let folders = world_folders(server);
+        let running = self.running.clone();
+        log::info!("Writing a backup");
+        tokio::task::spawn_blocking(move || {
+            let started = Instant::now();
+            let result = write_backup(&folders);
+            running.store(false, Ordering::Release);
+            match &result {
+                Ok(backup) => {
+                    log::info!(
+                        "Wrote backup {}, {}MB in {}ms",
+                        backup.name,
+                        backup.size / (1024 * 1024),
+                        started.elapsed().as_millis()
+                    );
+                    if let Err(err) = delete_old_backups() {
+                        log::error!("Failed to delete old backups: {err}");
+                    }
+                }
+                Err(err) => log::error!("Failed to write a backup: {err}"),
+            }
+            done(&result);
+        });
+        Ok(())
+    }
+
+    /// Stops the Server, the worlds are replaced by the backup once it stopped
+    pub fn restore(&self, server: &Server, name: &str) -> Result<(), BackupError> {
+        if self.is_running() {
+            return Err(BackupError::AlreadyRunning);
+        }
+        let backup = list()?
+            .into_iter()
+            .find(|backup| backup.name == name)
+            .ok_or_else(|| BackupError::NotFound(name.to_string()))?;
+        *self.pending_restore.lock() = Some(backup);
+        server.request_shutdown();
+        Ok(())
+    }
+
+    /// Waits until the archive being written is done
+    pub async fn wait(&self) {
+        if self.is_running() {
+            log::info!("Waiting for the backup to be written");
+        }
+        while self.is_running() {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    /// Replaces the world folders with the backup `/backup restore` picked. Returns false if no
+    /// backup is restored, the worlds should be saved then
+    pub fn restore_pending(&self, server: &Server) -> bool {
+        let Some(backup) = self.pending_restore.lock().take() else {
+            return false;
+        };
+        log::info!("Restoring backup {}", backup.name);
+        match unpack_backup(&backup.path, &world_folders(server)) {
+            Ok(()) => log::info!("Restored backup {}", backup.name),
+            Err(err) => log::error!("Failed to restore backup {}: {err}", backup.name),
+        }
+        true
+    }
+}
+
+/// The backups in the backup folder, the oldest first
+pub fn list() -> io::Result<Vec<BackupInfo>> {
+    let folder = &ADVANCED_CONFIG.get().backup.folder;
+    if !folder.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(folder)? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        let Some(name) = file_name.strip_suffix(EXTENSION) else {
+            continue;
+        };
+        backups.push(BackupInfo {
+            name: name.to_string(),
+            path: entry.path(),
+            size: entry.metadata()?.len(),
+        });
+    }
+    // The names are timestamps, so they sort by age
+    backups.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(backups)
+}
+
+/// The folders of all worlds, the Nether and the End are stored inside of the main world
+fn world_folders(server: &Server) -> Vec<PathBuf> {
+    let roots: Vec<_> = server
+        .worlds
+        .iter()
+        .map(|world| world.level.root_folder().to_path_buf())
+        .collect();
+    let mut folders: Vec<PathBuf> = Vec::new();
+    for root in &roots {
+        let nested = roots
+            .iter()
+            .any(|other| other != root && root.starts_with(other));
+        if !nested && !folders.contains(root) {
+            folders.push(root.clone());
+        }
+    }
+    folders
+}
+
+/// The name of a world folder inside of an archive
+fn archive_name(folder: &Path) -> PathBuf {
+    folder
+        .file_name()
+        .map_or_else(|| PathBuf::from("world"), PathBuf::from)
+}
+
+fn write_backup(folders: &[PathBuf]) -> Result<BackupInfo, BackupError> {
+    let config = &ADVANCED_CONFIG.get().backup;
+    fs::create_dir_all(&config.folder)?;
+    let name = timestamp(SystemTime::now());
+    let path = config.folder.join(format!("{name}{EXTENSION}"));
+    // A backup which failed halfway never looks like a complete one
+    let partial = config.folder.join(format!("{name}{EXTENSION}.part"));
+
+    let encoder = zstd::Encoder::new(File::create(&partial)?, config.compression_level)?;
+    let mut archive = tar::Builder::new(encoder);
+    for folder in folders.iter().filter(|folder| folder.exists()) {
+        archive.append_dir_all(archive_name(folder), folder)?;
+    }
+    archive.into_inner()?.finish()?;
+    fs::rename(&partial, &path)?;
+
+    Ok(BackupInfo {
+        name,
+        size: fs::metadata(&path)?.len(),
+        path,
+    })
+}
+
+/// Deletes the oldest backups when there are too many and those which are too old
+fn delete_old_backups() -> io::Result<()> {
+    let config = &ADVANCED_CONFIG.get().backup;
+    let backups = list()?;
+    let excess = match config.max_backups {
+        0 => 0,
+        max => backups.len().saturating_sub(max),
+    };
+    let max_age =
+        (config.max_age_days > 0).then(|| Duration::from_secs(config.max_age_days * 24 * 60 * 60));
+    for (index, backup) in backups.iter().enumerate() {
+        let expired = match max_age {
+            Some(max_age) => fs::metadata(&backup.path)?
+                .modified()?
+                .elapsed()
+                .is_ok_and(|age| age > max_age),
+            None => false,
+        };
+        if index < excess || expired {
+            fs::remove_file(&backup.path)?;
+            log::info!("Deleted old backup {}", backup.name);
+        }
+    }
+    Ok(())
+}
+
+/// Unpacks an archive and replaces the world folders which are in it
+fn unpack_backup(archive: &Path, folders: &[PathBuf]) -> io::Result<()> {
+    let unpacked = ADVANCED_CONFIG.get().backup.folder.join(RESTORE_FOLDER);
+    if unpacked.exists() {
+        fs::remove_dir_all(&unpacked)?;
+    }
+    tar::Archive::new(zstd::Decoder::new(File::open(archive)?)?).unpack(&unpacked)?;
+    for folder in folders {
+        let world = unpacked.join(archive_name(folder));
+        if !world.exists() {
+            continue;
+        }
+        if folder.exists() {
+            fs::remove_dir_all(folder)?;
+        }
+        fs::rename(&world, folder)?;
+    }
+    fs::remove_dir_all(&unpacked)
+}
+
+/// A UTC timestamp which sorts like the time, e.g. `2024-10-16_18-30-00`
+fn timestamp(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    let (days, time_of_day) = (seconds / 86400, seconds % 86400);
+    // Converts days since 1970 into a date, see http://howardhinnant.github.io/date_algorithms.html
+    let days = days as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}_{:02}-{:02}-{:02}",
+        time_of_day / 3600,
+        time_of_day / 60 % 60,
+        time_of_day % 60
+    )
+}
@@ -1,59 +1,102 @@
-use std::{fs::File, path::Path};
+use std::{fs::File, path::Path, sync::Arc};
 
 use base64::{engine::general_purpose, Engine as _};
-use pumpkin_config::{BasicConfiguration, BASIC_CONFIG};
+use parking_lot::{Mutex, RwLock};
+use pumpkin_config::{BasicConfiguration, ADVANCED_CONFIG, BASIC_CONFIG};
 use pumpkin_protocol::{
-    client::{config::CPluginMessage, status::CStatusResponse},
-    Players, Sample, StatusResponse, VarInt, Version, CURRENT_MC_PROTOCOL,
+    client::config::CPluginMessage, Players, Sample, StatusResponse, VarInt, Version,
+    CURRENT_MC_PROTOCOL,
 };
 
+use crate::{chat::secure_chat_enforced, client::Client};
+
 use super::CURRENT_MC_VERSION;
 
 pub struct CachedStatus {
-    _status_response: StatusResponse,
+    status_response: Mutex<StatusResponse>,
     // We cache the json response here so we don't parse it every time someone makes a Status request.
     // Keep in mind that we must parse this again, when the StatusResponse changes which usually happen when a player joins or leaves
-    status_response_json: String,
+    status_response_json: RwLock<Arc<str>>,
 }
 
 pub struct CachedBranding {
     /// Cached Server brand buffer so we don't have to rebuild them every time a player joins
-    cached_server_brand: Vec<u8>,
+    cached_server_brand: RwLock<Arc<[u8]>>,
 }
 
 impl CachedBranding {
     pub fn new() -> Self {
-        let cached_server_brand = Self::build_brand();
         Self {
-            cached_server_brand,
+            cached_server_brand: RwLock::new(Self::build_brand()),
         }
     }
-    pub fn get_branding(&self) -> CPluginMessage {
-        CPluginMessage::new("minecraft:brand", &self.cached_server_brand)
+    pub fn send_branding(&self, client: &Client) {
+        let brand = self.cached_server_brand.read().clone();
+        client.send_packet(&CPluginMessage::new("minecraft:brand", &brand));
+    }
+    /// Rebuilds the brand from the current configuration
+    pub fn rebuild(&self) {
+        *self.cached_server_brand.write() = Self::build_brand();
     }
-    fn build_brand() -> Vec<u8> {
-        let brand = "Pumpkin";
+    fn build_brand() -> Arc<[u8]> {
+        let brand = BASIC_CONFIG
+            .get()
+            .server_brand
+            .clone()
+            .unwrap_or_else(|| format!("Pumpkin {}", env!("CARGO_PKG_VERSION")));
         let mut buf = vec![];
         let _ = VarInt(brand.len() as i32).encode(&mut buf);
         buf.extend_from_slice(brand.as_bytes());
-        buf
+        buf.into()
     }
 }
 
 impl CachedStatus {
     pub fn new() -> Self {
-        let status_response = Self::build_response(&BASIC_CONFIG);
-        let status_response_json = serde_json::to_string(&status_response)
-            .expect("Failed to parse Status response into JSON");
+        let status_response = Self::build_response(&BASIC_CONFIG.get());
+        let status_response_json = Self::to_json(&status_response);
 
         Self {
-            _status_response: status_response,
-            status_response_json,
+            status_response: Mutex::new(status_response),
+            status_response_json: RwLock::new(status_response_json),
+        }
+    }
+
+    /// The cached JSON of the status response
+    pub fn get_status_json(&self) -> Arc<str> {
+        self.status_response_json.read().clone()
+    }
+
+    /// Rebuilds the response from the current configuration, the online players are kept
+    pub fn rebuild(&self) {
+        let mut status_response = self.status_response.lock();
+        let players = status_response.players.take();
+        *status_response = Self::build_response(&BASIC_CONFIG.get());
+        if let (Some(new_players), Some(players)) = (&mut status_response.players, players) {
+            new_players.online = players.online;
+            new_players.sample = players.sample;
+        }
+        *self.status_response_json.write() = Self::to_json(&status_response);
+    }
+
+    /// Updates the online players, the JSON only gets rebuilt when they actually changed
+    pub fn update_players(&self, online: u32, sample: Vec<Sample>) {
+        let mut status_response = self.status_response.lock();
+        let Some(players) = &mut status_response.players else {
+            return;
+        };
+        if players.online == online && players.sample == sample {
+            return;
         }
+        players.online = online;
+        players.sample = sample;
+        *self.status_response_json.write() = Self::to_json(&status_response);
     }
 
-    pub fn get_status(&self) -> CStatusResponse<'_> {
-        CStatusResponse::new(&self.status_response_json)
+    fn to_json(status_response: &StatusResponse) -> Arc<str> {
+        serde_json::to_string(status_response)
+            .expect("Failed to parse Status response into JSON")
+            .into()
     }
 
     pub fn build_response(config: &BasicConfiguration) -> StatusResponse {
@@ -72,14 +115,12 @@ impl CachedStatus {
             players: Some(Players {
                 max: config.max_players,
                 online: 0,
-                sample: vec![Sample {
-                    name: "".into(),
-                    id: "".into(),
-                }],
+                sample: Vec::new(),
             }),
             description: config.motd.clone(),
             favicon: icon,
-            enforce_secure_chat: false,
+            enforce_secure_chat: secure_chat_enforced(),
+            prevents_chat_reports: ADVANCED_CONFIG.get().chat.no_chat_reports,
         }
     }
 
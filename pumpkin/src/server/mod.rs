@@ -1,36 +1,74 @@
+use advancements::Advancements;
+use autosave::Autosave;
+use backup::Backups;
 use connection_cache::{CachedBranding, CachedStatus};
 use key_store::KeyStore;
+use operators::Operators;
 use parking_lot::{Mutex, RwLock};
-use pumpkin_config::BASIC_CONFIG;
-use pumpkin_core::GameMode;
+use pumpkin_config::{
+    messages::format_message, worlds::WorldConfig, ReloadSummary, ADVANCED_CONFIG, BASIC_CONFIG,
+    MESSAGES,
+};
+use pumpkin_core::{text::TextComponent, GameMode, GeneratorType};
 use pumpkin_entity::EntityId;
 use pumpkin_inventory::drag_handler::DragHandler;
 use pumpkin_inventory::{Container, OpenContainer};
+use pumpkin_plugins::{
+    events::{PlayerIdleKickEvent, PlayerStatsSaveEvent, WorldLoadEvent, WorldSaveEvent},
+    plugin_loader::PluginLoader,
+};
 use pumpkin_protocol::client::login::CEncryptionRequest;
-use pumpkin_protocol::client::status::CStatusResponse;
-use pumpkin_protocol::{client::config::CPluginMessage, ClientPacket};
+use pumpkin_protocol::{ClientPacket, Sample};
 use pumpkin_registry::Registry;
-use pumpkin_world::dimension::Dimension;
+use pumpkin_world::{
+    advancement::AdvancementProgress,
+    dimension::Dimension,
+    level_data::LevelData,
+    player_data::PlayerData,
+    stats::PlayerStats,
+    world_gen::{FlatPreset, GeneratorSettings, Seed, StructureType},
+};
+use recipes::Recipes;
+use rsa::RsaPublicKey;
+use scheduler::Scheduler;
+use scoreboard::Scoreboard;
+use skin_cache::SkinCache;
 use std::collections::HashMap;
 use std::{
+    path::PathBuf,
     sync::{
-        atomic::{AtomicI32, Ordering},
+        atomic::{AtomicBool, AtomicI32, Ordering},
         Arc,
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
+use tab_list::TabList;
+use tokio::sync::Notify;
 
 use crate::client::EncryptionError;
+use crate::cluster::Cluster;
 use crate::{
     client::Client,
     commands::{default_dispatcher, dispatcher::CommandDispatcher},
-    entity::player::Player,
-    world::World,
+    entity::{advancement::PlayerAdvancements, player::Player},
+    world::{portal, World},
 };
 
+pub mod advancements;
+mod autosave;
+pub mod backup;
 mod connection_cache;
 mod key_store;
+pub mod operators;
+pub mod recipes;
+pub mod scheduler;
+pub mod scoreboard;
+pub mod skin_cache;
+pub mod tab_list;
+pub mod ticker;
 pub const CURRENT_MC_VERSION: &str = "1.21.1";
+/// How many players are shown when hovering the player count in the server list, like vanilla
+const STATUS_SAMPLE_SIZE: usize = 12;
 
 pub struct Server {
     key_store: KeyStore,
@@ -38,6 +76,7 @@ pub struct Server {
     server_branding: CachedBranding,
 
     pub command_dispatcher: Arc<CommandDispatcher<'static>>,
+    /// Declared before `plugins`, so generators from plugins are dropped before their libraries
     pub worlds: Vec<Arc<World>>,
 
     /// Cache the registry so we don't have to parse it every time a player joins
@@ -49,17 +88,48 @@ pub struct Server {
 
     /// Used for Authentication, None is Online mode is disabled
     pub auth_client: Option<reqwest::Client>,
+    /// The keys Mojang signs player chat keys with, fetched on startup when Online mode is enabled
+    pub mojang_public_keys: RwLock<Vec<RsaPublicKey>>,
+
+    /// The players in `ops.json`
+    pub operators: Operators,
+    /// Runs Tasks on the main tick
+    pub scheduler: Scheduler,
+    /// The loaded plugins, events get fired to them
+    pub plugins: RwLock<PluginLoader>,
+    /// The player list header and footer
+    pub tab_list: TabList,
+    /// Skins of players, so they show up when the session server is down or in offline mode
+    pub skin_cache: SkinCache,
+    /// The other servers in the cluster, if it is enabled
+    pub cluster: Cluster,
+    /// The advancements players can get
+    pub advancements: Advancements,
+    /// The recipes players can craft
+    pub recipes: Recipes,
+    /// The objectives, scores and teams of all worlds
+    pub scoreboard: Scoreboard,
+    /// Saves changed chunks every few minutes
+    autosave: Autosave,
+    /// Writes the worlds into archives on a schedule and with `/backup`
+    pub backups: Backups,
+
+    /// Cleared once a shutdown was requested
+    running: AtomicBool,
+    /// Notifies the main task, so it starts the shutdown sequence
+    shutdown_notify: Notify,
 }
 
 impl Server {
-    #[allow(clippy::new_without_default)]
-    pub fn new() -> Self {
+    pub fn new(plugins: PluginLoader) -> Self {
         // TODO: only create when needed
 
-        let auth_client = if BASIC_CONFIG.online_mode {
+        let auth_client = if BASIC_CONFIG.get().online_mode {
+            let auth_config = &ADVANCED_CONFIG.get().authentication;
             Some(
                 reqwest::Client::builder()
-                    .timeout(Duration::from_millis(5000))
+                    .connect_timeout(Duration::from_millis(auth_config.connect_timeout_ms))
+                    .timeout(Duration::from_millis(auth_config.timeout_ms))
                     .build()
                     .expect("Failed to to make reqwest client"),
             )
@@ -71,40 +141,296 @@ impl Server {
         let command_dispatcher = default_dispatcher();
         // log::info!("Loading Plugins");
 
-        let world = World::load(Dimension::OverWorld.into_level(
-            // TODO: load form config
-            "./world".parse().unwrap(),
-        ));
+        let main_world = BASIC_CONFIG.get().main_world();
+        let mut worlds = vec![Arc::new(load_world(
+            &main_world,
+            BASIC_CONFIG.get().level_name.clone(),
+            "minecraft:overworld".to_string(),
+            Dimension::OverWorld,
+            None,
+            &plugins,
+        ))];
+        let main_seed = Some(worlds[0].seed);
+        // The Nether and the End are stored in the folder of the main world, like in vanilla
+        if BASIC_CONFIG.get().allow_nether {
+            worlds.push(Arc::new(load_world(
+                &WorldConfig {
+                    name: format!("{}_nether", main_world.name),
+                    // The dimensions always share the seed of the overworld
+                    seed: String::new(),
+                    // Like vanilla, only the overworld has spawn chunks and spawn protection
+                    spawn_protection: 0,
+                    spawn_chunk_radius: 0,
+                    ..main_world.clone()
+                },
+                BASIC_CONFIG.get().level_name.clone(),
+                "minecraft:the_nether".to_string(),
+                Dimension::Nether,
+                main_seed,
+                &plugins,
+            )));
+        }
+        if BASIC_CONFIG.get().allow_end {
+            let end = load_world(
+                &WorldConfig {
+                    name: format!("{}_the_end", main_world.name),
+                    seed: String::new(),
+                    spawn_protection: 0,
+                    spawn_chunk_radius: 0,
+                    ..main_world.clone()
+                },
+                BASIC_CONFIG.get().level_name.clone(),
+                "minecraft:the_end".to_string(),
+                Dimension::End,
+                main_seed,
+                &plugins,
+            );
+            end.set_spawn_position(portal::END_SPAWN);
+            worlds.push(Arc::new(end));
+        }
+        for config in &ADVANCED_CONFIG.get().worlds {
+            if worlds.iter().any(|world| world.name == config.name) {
+                log::error!(
+                    "Not loading world {}, the main world or one of its dimensions has the same name",
+                    config.name
+                );
+                continue;
+            }
+            worlds.push(Arc::new(load_world(
+                config,
+                PathBuf::from(&config.name),
+                format!("pumpkin:{}", config.name),
+                Dimension::OverWorld,
+                main_seed,
+                &plugins,
+            )));
+        }
+        for world in &worlds {
+            world.keep_spawn_loaded();
+            let event = WorldLoadEvent {
+                name: &world.name,
+                level: &world.level,
+            };
+            plugins.for_each(|plugin| plugin.on_world_load(&event));
+        }
+        let advancements = Advancements::load(worlds[0].level.root_folder(), &plugins);
+        let recipes = Recipes::load(worlds[0].level.root_folder());
+        let scoreboard = Scoreboard::load(worlds[0].level.root_folder());
         Self {
             cached_registry: Registry::get_static(),
             open_containers: RwLock::new(HashMap::new()),
             drag_handler: DragHandler::new(),
             // 0 is invalid
             entity_id: 2.into(),
-            worlds: vec![Arc::new(world)],
+            worlds,
             command_dispatcher: Arc::new(command_dispatcher),
             auth_client,
+            mojang_public_keys: RwLock::new(Vec::new()),
             key_store: KeyStore::new(),
             server_listing: CachedStatus::new(),
             server_branding: CachedBranding::new(),
+            operators: Operators::load(),
+            scheduler: Scheduler::default(),
+            plugins: RwLock::new(plugins),
+            tab_list: TabList::default(),
+            skin_cache: SkinCache::new(&ADVANCED_CONFIG.get().skins),
+            cluster: Cluster::default(),
+            advancements,
+            recipes,
+            scoreboard,
+            autosave: Autosave::default(),
+            backups: Backups::default(),
+            running: AtomicBool::new(true),
+            shutdown_notify: Notify::new(),
+        }
+    }
+
+    /// Runs a single Server tick, `deadline` is the end of this tick's time budget
+    pub fn tick(&self, deadline: Instant) {
+        for world in &self.worlds {
+            world.tick(self);
+        }
+        portal::tick(self);
+        self.kick_idle_players();
+        self.advancements.tick(self);
+        self.scoreboard.tick(self);
+        self.autosave.tick(self);
+        self.backups.tick(self);
+        self.scheduler.tick(self, deadline);
+    }
+
+    /// Kicks players who were idle for longer than `player_idle_timeout`, unless a plugin cancels it
+    fn kick_idle_players(&self) {
+        if BASIC_CONFIG.get().player_idle_timeout == 0 {
+            return;
+        }
+        let timeout = Duration::from_secs(u64::from(BASIC_CONFIG.get().player_idle_timeout) * 60);
+        for player in self.get_all_players() {
+            let idle_time = player.idle_time();
+            if idle_time < timeout || player.client.closed.load(Ordering::Relaxed) {
+                continue;
+            }
+            let mut event = PlayerIdleKickEvent {
+                player_name: &player.gameprofile.name,
+                player_uuid: player.gameprofile.id,
+                idle_time,
+                cancelled: false,
+            };
+            self.plugins
+                .read()
+                .for_each(|plugin| plugin.on_player_idle_kick(&mut event));
+            if event.cancelled {
+                // Check again once another timeout passed
+                player.update_last_action();
+            } else {
+                player.kick(TextComponent::text(&format_message(
+                    &MESSAGES.get().idle_timeout,
+                    &[],
+                )));
+            }
+        }
+    }
+
+    /// Returns false once a shutdown was requested
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// Requests a graceful shutdown, the actual shutdown sequence runs in the main loop.
+    /// Returns false if a shutdown was already requested
+    pub fn request_shutdown(&self) -> bool {
+        if !self.running.swap(false, Ordering::Relaxed) {
+            return false;
+        }
+        // Several tasks wait for the shutdown, they all have to be woken up
+        self.shutdown_notify.notify_waiters();
+        true
+    }
+
+    /// Waits until a shutdown was requested
+    pub async fn wait_for_shutdown(&self) {
+        // Created before checking, so a shutdown in between is not missed
+        let notified = self.shutdown_notify.notified();
+        if self.is_running() {
+            notified.await;
         }
     }
 
     pub async fn add_player(&self, id: usize, client: Arc<Client>) -> (Arc<Player>, Arc<World>) {
         let entity_id = self.new_entity_id();
-        let gamemode = match BASIC_CONFIG.default_gamemode {
+        let gamemode = match BASIC_CONFIG.get().default_gamemode {
             GameMode::Undefined => GameMode::Survival,
             game_mode => game_mode,
         };
-        // Basically the default world
-        // TODO: select default from config
-        let world = &self.worlds[0];
+        let uuid = client.gameprofile.lock().as_ref().map(|profile| profile.id);
+        let data = uuid.and_then(|uuid| self.read_player_data(uuid));
+        // Players come back into the world they left, unless it does not exist anymore
+        let saved_world = data.as_ref().and_then(|data| {
+            data.world
+                .as_deref()
+                .and_then(|name| self.get_world(name))
+                .or_else(|| {
+                    self.worlds
+                        .iter()
+                        .find(|world| world.dimension_name == data.dimension)
+                        .cloned()
+                })
+        });
+        let world = saved_world
+            .clone()
+            .unwrap_or_else(|| self.default_world().clone());
 
         let player = Arc::new(Player::new(client, world.clone(), entity_id, gamemode));
+        if let Some(data) = &data {
+            player.load_player_data(data);
+        }
+        if let Some(uuid) = uuid {
+            *player.advancements.lock() = PlayerAdvancements::new(self.read_advancements(uuid));
+            *player.stats.lock() = self.read_stats(uuid);
+        }
+        // Players who left while they were dead come back at the spawn
+        let alive = data.as_ref().is_some_and(|data| data.health > 0.0);
+        if saved_world.is_none() || !alive {
+            let spawn = world.spawn_position.load();
+            player
+                .living_entity
+                .entity
+                .set_pos(spawn.x, spawn.y, spawn.z);
+            player.living_entity.health.store(20.0);
+        }
         world.add_player(id, player.clone());
         (player, world.clone())
     }
 
+    /// The saved data of a player, None if they never played before
+    fn read_player_data(&self, uuid: uuid::Uuid) -> Option<PlayerData> {
+        let root_folder = self.default_world().level.root_folder();
+        match PlayerData::read(root_folder, &uuid.to_string()) {
+            Ok(data) => data,
+            Err(err) => {
+                log::error!("Failed to read the player data of {uuid}: {err}");
+                None
+            }
+        }
+    }
+
+    /// The advancement progress of a player, empty if they never played before
+    fn read_advancements(&self, uuid: uuid::Uuid) -> AdvancementProgress {
+        let root_folder = self.default_world().level.root_folder();
+        AdvancementProgress::read(root_folder, &uuid.to_string()).unwrap_or_else(|err| {
+            log::error!("Failed to read the advancements of {uuid}: {err}");
+            AdvancementProgress::default()
+        })
+    }
+
+    /// The statistics of a player, empty if they never played before
+    fn read_stats(&self, uuid: uuid::Uuid) -> PlayerStats {
+        let root_folder = self.default_world().level.root_folder();
+        PlayerStats::read(root_folder, &uuid.to_string()).unwrap_or_else(|err| {
+            log::error!("Failed to read the statistics of {uuid}: {err}");
+            PlayerStats::default()
+        })
+    }
+
+    /// Saves what is kept of a player while they are offline, into the main world like vanilla
+    pub fn save_player(&self, player: &Player) {
+        let root_folder = self.default_world().level.root_folder();
+        let uuid = player.gameprofile.id;
+        if let Err(err) = player
+            .to_player_data()
+            .write(root_folder, &uuid.to_string())
+        {
+            log::error!("Failed to save the player data of {uuid}: {err}");
+        }
+        if let Err(err) = player
+            .advancements
+            .lock()
+            .progress
+            .write(root_folder, &uuid.to_string())
+        {
+            log::error!("Failed to save the advancements of {uuid}: {err}");
+        }
+        let stats = player.stats.lock();
+        let event = PlayerStatsSaveEvent {
+            player_name: &player.gameprofile.name,
+            player_uuid: uuid,
+            stats: &stats,
+        };
+        self.plugins
+            .read()
+            .for_each(|plugin| plugin.on_player_stats_save(&event));
+        if let Err(err) = stats.write(root_folder, &uuid.to_string()) {
+            log::error!("Failed to save the statistics of {uuid}: {err}");
+        }
+    }
+
+    /// Saves the data of all online players
+    pub fn save_players(&self) {
+        for player in self.get_all_players() {
+            self.save_player(&player);
+        }
+    }
+
     pub fn try_get_container(
         &self,
         player_id: EntityId,
@@ -117,6 +443,36 @@ impl Server {
             .cloned()
     }
 
+    /// The main world, new players join it
+    pub fn default_world(&self) -> &Arc<World> {
+        &self.worlds[0]
+    }
+
+    /// Gets a World by name
+    pub fn get_world(&self, name: &str) -> Option<Arc<World>> {
+        self.worlds.iter().find(|world| world.name == name).cloned()
+    }
+
+    /// The world players travel to through portals into a dimension.
+    /// Portals into the Overworld lead to the main world, None if the dimension is disabled
+    pub fn get_dimension(&self, dimension: Dimension) -> Option<Arc<World>> {
+        if dimension == Dimension::OverWorld {
+            return Some(self.default_world().clone());
+        }
+        self.worlds
+            .iter()
+            .find(|world| world.dimension == dimension)
+            .cloned()
+    }
+
+    /// The dimension names of all worlds, clients have to know them when they join
+    pub fn dimension_names(&self) -> Vec<&str> {
+        self.worlds
+            .iter()
+            .map(|world| world.dimension_name.as_str())
+            .collect()
+    }
+
     /// Sends a Packet to all Players in all worlds
     pub fn broadcast_packet_all<P>(&self, packet: &P)
     where
@@ -127,6 +483,45 @@ impl Server {
         }
     }
 
+    /// Returns the amount of Players in all worlds
+    pub fn get_player_count(&self) -> usize {
+        self.worlds
+            .iter()
+            .map(|world| world.current_players.lock().len())
+            .sum()
+    }
+
+    /// Returns the Players of all worlds
+    pub fn get_all_players(&self) -> Vec<Arc<Player>> {
+        self.worlds
+            .iter()
+            .flat_map(|world| {
+                world
+                    .current_players
+                    .lock()
+                    .values()
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Sends the current tab list header and footer to every player, e.g. after a player joined or left
+    pub fn refresh_tab_list(&self) {
+        for world in &self.worlds {
+            let players: Vec<_> = world.current_players.lock().values().cloned().collect();
+            for player in players {
+                self.tab_list.send(self, &player);
+            }
+        }
+    }
+
+    /// Changes the tab list header and footer for every player
+    pub fn set_tab_list(&self, header: String, footer: String) {
+        self.tab_list.set(header, footer);
+        self.refresh_tab_list();
+    }
+
     /// Searches every world for a player by name
     pub fn get_player_by_name(&self, name: &str) -> Option<Arc<Player>> {
         for world in self.worlds.iter() {
@@ -143,12 +538,86 @@ impl Server {
         self.entity_id.fetch_add(1, Ordering::SeqCst)
     }
 
-    pub fn get_branding(&self) -> CPluginMessage<'_> {
-        self.server_branding.get_branding()
+    pub fn send_branding(&self, client: &Client) {
+        self.server_branding.send_branding(client);
     }
 
-    pub fn get_status(&self) -> CStatusResponse<'_> {
-        self.server_listing.get_status()
+    /// Lets plugins know the worlds were saved
+    fn fire_world_save(&self, saved_chunks: usize, duration: Duration, autosave: bool) {
+        let event = WorldSaveEvent {
+            saved_chunks,
+            duration,
+            autosave,
+        };
+        self.plugins
+            .read()
+            .for_each(|plugin| plugin.on_world_save(&event));
+    }
+
+    /// Reloads all configuration files and refreshes everything derived from them, e.g. the MOTD
+    pub fn reload_config(&self) -> Result<ReloadSummary, String> {
+        let summary = pumpkin_config::reload()?;
+        self.server_listing.rebuild();
+        self.server_branding.rebuild();
+        self.refresh_status();
+        self.refresh_tab_list();
+        Ok(summary)
+    }
+
+    /// Writes all changed chunks of all worlds to disk, returns how many chunks were saved
+    pub fn save_worlds(&self) -> usize {
+        log::info!("Saving the world");
+        let started = Instant::now();
+        for world in &self.worlds {
+            world.save_level_data();
+        }
+        self.scoreboard
+            .save(self.default_world().level.root_folder());
+        self.save_players();
+        let saved = self.autosave.save_pending(self)
+            + self
+                .worlds
+                .iter()
+                .map(|world| world.level.save())
+                .sum::<usize>();
+        let duration = started.elapsed();
+        log::info!(
+            "Saved the world, {saved} chunks in {}ms",
+            duration.as_millis()
+        );
+        self.fire_world_save(saved, duration, false);
+        saved
+    }
+
+    /// The status response JSON, cached until the player list changes
+    pub fn get_status_json(&self) -> Arc<str> {
+        self.server_listing.get_status_json()
+    }
+
+    /// Updates the player count and sample shown in the server list, e.g. after a player joined or left
+    pub fn refresh_status(&self) {
+        let players = self.get_all_players();
+        let sample = players
+            .iter()
+            .filter(|player| {
+                player
+                    .client
+                    .config
+                    .lock()
+                    .as_ref()
+                    .is_some_and(|config| config.server_listing)
+            })
+            .take(STATUS_SAMPLE_SIZE)
+            .map(|player| Sample {
+                name: player.gameprofile.name.clone(),
+                id: player.gameprofile.id.to_string(),
+            })
+            .collect();
+        let mut online = players.len() as u32;
+        if ADVANCED_CONFIG.get().cluster.global_player_count {
+            online += self.cluster.remote_player_count();
+        }
+        self.server_listing.update_players(online, sample);
     }
 
     pub fn encryption_request<'a>(
@@ -168,3 +637,89 @@ impl Server {
         self.key_store.get_digest(secret)
     }
 }
+
+/// Loads a world from `folder`, chunks which don't exist yet are generated as configured.
+/// The Nether and the End always use their own generators.
+/// Worlds without a configured seed use the one of the main world, the main world a random one
+fn load_world(
+    config: &WorldConfig,
+    folder: PathBuf,
+    dimension_name: String,
+    dimension: Dimension,
+    main_seed: Option<Seed>,
+    plugins: &PluginLoader,
+) -> World {
+    let mut level_data =
+        LevelData::read(&dimension.level_folder(folder.clone())).unwrap_or_else(|err| {
+            log::error!(
+                "Failed to read the level data of world {}: {err}",
+                config.name
+            );
+            LevelData::default()
+        });
+    // A world keeps the seed it was generated with, even if the configured one changes
+    let seed = match level_data.seed {
+        Some(seed) => Seed(seed),
+        None if !config.seed.is_empty() => Seed::from(config.seed.as_str()),
+        None => main_seed.unwrap_or_else(Seed::random),
+    };
+    level_data.seed = Some(seed.0);
+    let generator = match dimension {
+        Dimension::OverWorld => generator_settings(config, seed, plugins),
+        Dimension::Nether => GeneratorSettings::Nether,
+        Dimension::End => GeneratorSettings::End,
+    };
+    let level = dimension.into_level(folder, seed, generator);
+    World::load(config, dimension_name, dimension, level, level_data)
+}
+
+/// The generator a world is configured with, an invalid flat preset falls back to the default one
+/// and an unknown generator to the normal one
+fn generator_settings(
+    config: &WorldConfig,
+    seed: Seed,
+    plugins: &PluginLoader,
+) -> GeneratorSettings {
+    match &config.generator {
+        GeneratorType::Normal => GeneratorSettings::Normal {
+            structures: enabled_structures(),
+        },
+        GeneratorType::Flat => {
+            let preset =
+                FlatPreset::parse(&config.flat_layers, &config.flat_biome).unwrap_or_else(|err| {
+                    log::error!("Invalid flat generator preset, using the default one: {err}");
+                    FlatPreset::default()
+                });
+            GeneratorSettings::Flat(preset)
+        }
+        GeneratorType::Custom(name) => {
+            let mut generator = None;
+            plugins.for_each(|plugin| {
+                if generator.is_none() {
+                    generator = plugin.chunk_generator(name, seed);
+                }
+            });
+            generator.map_or_else(
+                || {
+                    log::error!("No plugin provides the generator {name}, using the normal one");
+                    GeneratorSettings::Normal {
+                        structures: enabled_structures(),
+                    }
+                },
+                GeneratorSettings::Custom,
+            )
+        }
+    }
+}
+
+/// The structures which are turned on in the config
+fn enabled_structures() -> Vec<StructureType> {
+    let config = &ADVANCED_CONFIG.get().structures;
+    StructureType::ALL
+        .into_iter()
+        .filter(|structure| match structure {
+            StructureType::Dungeon => config.dungeons,
+            StructureType::SurfaceRuins => config.surface_ruins,
+        })
+        .collect()
+}
@@ -0,0 +1,53 @@
+use std::{collections::HashMap, fs, io::ErrorKind, path::Path};
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// The same file vanilla keeps its operators in
+const OPERATORS_FILE: &str = "ops.json";
+
+#[derive(Deserialize)]
+struct Operator {
+    uuid: Uuid,
+    level: u8,
+}
+
+/// The players listed in `ops.json`, read once at startup
+#[derive(Default)]
+pub struct Operators {
+    levels: HashMap<Uuid, u8>,
+}
+
+impl Operators {
+    pub fn load() -> Self {
+        let content = match fs::read_to_string(Path::new(OPERATORS_FILE)) {
+            Ok(content) => content,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Self::default(),
+            Err(err) => {
+                log::error!("Failed to read {OPERATORS_FILE}: {err}");
+                return Self::default();
+            }
+        };
+        match serde_json::from_str::<Vec<Operator>>(&content) {
+            Ok(operators) => Self {
+                levels: operators
+                    .into_iter()
+                    .map(|operator| (operator.uuid, operator.level))
+                    .collect(),
+            },
+            Err(err) => {
+                log::error!("Failed to parse {OPERATORS_FILE}: {err}");
+                Self::default()
+            }
+        }
+    }
+
+    /// The operator level of a player, None if the player is no operator
+    pub fn level(&self, uuid: &Uuid) -> Option<u8> {
+        self.levels.get(uuid).copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+}
@@ -0,0 +1,146 @@
+use std::path::Path;
+
+use pumpkin_protocol::{
+    client::play::{CookingBlock, RecipeData, RecipeEntry},
+    slot::Slot,
+};
+use pumpkin_world::{
+    global_registry::{self, ITEM_REGISTRY},
+    item::ItemStack,
+    recipe::{self, Ingredient, Recipe, RecipeResult},
+};
+
+/// All recipes players can craft: the built-in ones and those of the datapacks of the main world
+pub struct Recipes {
+    /// Sorted by their id, so the first matching recipe is always the same
+    recipes: Vec<(String, Recipe)>,
+}
+
+impl Recipes {
+    pub fn load(root_folder: &Path) -> Self {
+        let mut recipes: Vec<(String, Recipe)> = recipe::load_recipes(root_folder)
+            .into_iter()
+            .filter(|(_, recipe)| !matches!(recipe, Recipe::Unsupported))
+            .collect();
+        recipes.sort_by(|(a, _), (b, _)| a.cmp(b));
+        log::info!("Loaded {} recipes", recipes.len());
+        Self { recipes }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Recipe> {
+        self.recipes
+            .iter()
+            .find(|(other, _)| other == id)
+            .map(|(_, recipe)| recipe)
+    }
+
+    pub fn ids(&self) -> Vec<&str> {
+        self.recipes.iter().map(|(id, _)| id.as_str()).collect()
+    }
+
+    /// The recipe the items in a crafting grid of `width` make with its id, the grid goes row by
+    /// row
+    pub fn find_crafting(
+        &self,
+        grid: &[Option<ItemStack>],
+        width: usize,
+    ) -> Option<(&str, &Recipe)> {
+        let mut items = Vec::with_capacity(grid.len());
+        for item in grid {
+            match item {
+                Some(item) => {
+                    items.push(Some(global_registry::find_minecraft_id(
+                        ITEM_REGISTRY,
+                        item.item_id,
+                    )?));
+                }
+                None => items.push(None),
+            }
+        }
+        self.recipes
+            .iter()
+            .find(|(_, recipe)| recipe.matches(&items, width))
+            .map(|(id, recipe)| (id.as_str(), recipe))
+    }
+
+    /// The recipe the furnace-like block with the id cooks an item with, and its id
+    pub fn find_cooking(&self, block: &str, item: &ItemStack) -> Option<(&str, &Recipe)> {
+        let item = global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id)?;
+        self.recipes
+            .iter()
+            .find(|(_, recipe)| {
+                recipe
+                    .cooking(block)
+                    .is_some_and(|cooking| cooking.ingredient.test(item))
+            })
+            .map(|(id, recipe)| (id.as_str(), recipe))
+    }
+
+    /// How the client learns about every recipe
+    pub fn entries(&self) -> Vec<RecipeEntry<'_>> {
+        self.recipes
+            .iter()
+            .filter_map(|(id, recipe)| {
+                let data = match recipe {
+                    Recipe::Shaped(recipe) => {
+                        let (width, height) = recipe.size();
+                        RecipeData::Shaped {
+                            group: &recipe.group,
+                            category: (recipe.category as i32).into(),
+                            width: (width as i32).into(),
+                            height: (height as i32).into(),
+                            ingredients: (0..height)
+                                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                                .map(|(x, y)| recipe.ingredient(x, y).map_or_else(Vec::new, slots))
+                                .collect(),
+                            result: result_slot(&recipe.result)?,
+                            show_notification: recipe.show_notification,
+                        }
+                    }
+                    Recipe::Shapeless(recipe) => RecipeData::Shapeless {
+                        group: &recipe.group,
+                        category: (recipe.category as i32).into(),
+                        ingredients: recipe.ingredients.iter().map(slots).collect(),
+                        result: result_slot(&recipe.result)?,
+                    },
+                    Recipe::Smelting(cooking)
+                    | Recipe::Blasting(cooking)
+                    | Recipe::Smoking(cooking) => RecipeData::Cooking {
+                        block: match recipe {
+                            Recipe::Blasting(_) => CookingBlock::BlastFurnace,
+                            Recipe::Smoking(_) => CookingBlock::Smoker,
+                            _ => CookingBlock::Furnace,
+                        },
+                        group: &cooking.group,
+                        category: (cooking.category as i32).into(),
+                        ingredient: slots(&cooking.ingredient),
+                        result: result_slot(&cooking.result)?,
+                        experience: cooking.experience,
+                        cooking_time: recipe.cooking_time()?.into(),
+                    },
+                    Recipe::Unsupported => return None,
+                };
+                Some(RecipeEntry { id, data })
+            })
+            .collect()
+    }
+}
+
+/// The items the result of a recipe is made of, None if the item does not exist
+pub fn result_stack(result: &RecipeResult) -> Option<ItemStack> {
+    let item_id = global_registry::find_protocol_id(ITEM_REGISTRY, &result.id)?;
+    Some(ItemStack::new(result.count, item_id))
+}
+
+fn result_slot(result: &RecipeResult) -> Option<Slot> {
+    result_stack(result).map(|item| Slot::from(&item))
+}
+
+fn slots(ingredient: &Ingredient) -> Vec<Slot> {
+    ingredient
+        .items()
+        .into_iter()
+        .filter_map(|item| global_registry::find_protocol_id(ITEM_REGISTRY, item))
+        .map(|item_id| Slot::new(1.into(), (item_id as i32).into()))
+        .collect()
+}
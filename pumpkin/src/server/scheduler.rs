@@ -0,0 +1,78 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Instant,
+};
+
+use parking_lot::Mutex;
+
+use super::Server;
+
+/// A Task which runs on the Server tick
+pub type Task = Box<dyn FnMut(&Server) + Send + Sync>;
+
+struct ScheduledTask {
+    /// The tick this Task should run at
+    run_at: u64,
+    /// When set, the Task gets rescheduled every `period` ticks
+    period: Option<u64>,
+    task: Task,
+}
+
+/// Runs Tasks scheduled by the Server or Plugins on the main tick.
+///
+/// Tasks only run while the current tick has budget left, everything else is deferred to the next tick.
+#[derive(Default)]
+pub struct Scheduler {
+    current_tick: AtomicU64,
+    tasks: Mutex<Vec<ScheduledTask>>,
+}
+
+impl Scheduler {
+    /// Runs the Task once after `delay` ticks
+    pub fn run_later(&self, delay: u64, task: Task) {
+        self.schedule(delay, None, task);
+    }
+
+    /// Runs the Task every `period` ticks, starting after `delay` ticks
+    pub fn run_repeating(&self, delay: u64, period: u64, task: Task) {
+        self.schedule(delay, Some(period.max(1)), task);
+    }
+
+    fn schedule(&self, delay: u64, period: Option<u64>, task: Task) {
+        let run_at = self.current_tick.load(Ordering::Relaxed) + delay.max(1);
+        self.tasks.lock().push(ScheduledTask {
+            run_at,
+            period,
+            task,
+        });
+    }
+
+    /// Runs all due Tasks until `deadline` is reached
+    pub(super) fn tick(&self, server: &Server, deadline: Instant) {
+        let tick = self.current_tick.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut due: Vec<ScheduledTask> = {
+            let mut tasks = self.tasks.lock();
+            let (due, pending) = tasks.drain(..).partition(|task| task.run_at <= tick);
+            *tasks = pending;
+            due
+        };
+        // Oldest Tasks first, so deferred Tasks don't starve
+        due.sort_by_key(|task| task.run_at);
+
+        let mut rescheduled = Vec::new();
+        let mut due = due.into_iter();
+        for mut task in due.by_ref() {
+            (task.task)(server);
+            if let Some(period) = task.period {
+                task.run_at = tick + period;
+                rescheduled.push(task);
+            }
+            if Instant::now() >= deadline {
+                break;
+            }
+        }
+        // Tasks we didn't get to stay due and run next tick
+        rescheduled.extend(due);
+        self.tasks.lock().extend(rescheduled);
+    }
+}
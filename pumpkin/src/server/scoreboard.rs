@@ -0,0 +1,471 @@
+use std::{collections::HashMap, path::Path, sync::atomic::Ordering};
+
+use parking_lot::{Mutex, MutexGuard};
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_core::text::TextComponent;
+use pumpkin_protocol::{
+    client::play::{
+        CDisplayObjective, CResetScore, CUpdateObjectives, CUpdateScore, CUpdateTeams, TeamInfo,
+        TeamMethod,
+    },
+    VarInt,
+};
+use pumpkin_world::scoreboard::{
+    Criterion, DisplaySlot, Objective, RenderType, ScoreboardData, ScoreboardError, Team,
+};
+use uuid::Uuid;
+
+use crate::entity::player::Player;
+
+use super::Server;
+
+/// The protocol id of the color of teams without one
+const NO_COLOR: i32 = 21;
+/// Clients push their own player unless its team never collides. While entity pushing is off
+/// the players in no other team are in this one, commands can't name a team like it
+const NO_PUSHING_TEAM: &str = "pumpkin nopush";
+
+/// The objectives, scores and teams, shared by all worlds like in vanilla. Changes are sent to
+/// all players right away. No other lock is taken while the scoreboard is locked
+pub struct Scoreboard {
+    data: Mutex<ScoreboardData>,
+    /// The last seen value of the statistics objectives count, by player and objective. Only
+    /// what players do while an objective exists adds to their score
+    counted: Mutex<HashMap<(Uuid, String), i32>>,
+}
+
+fn objective_packet(objective: &Objective, create: bool) -> CUpdateObjectives<'_> {
+    let display_name = TextComponent::text(&objective.display_name);
+    let render_type = VarInt(match objective.render_type {
+        RenderType::Integer => 0,
+        RenderType::Hearts => 1,
+    });
+    if create {
+        CUpdateObjectives::create(&objective.name, display_name, render_type)
+    } else {
+        CUpdateObjectives::update(&objective.name, display_name, render_type)
+    }
+}
+
+fn entity_pushing() -> bool {
+    ADVANCED_CONFIG.get().collisions.entity_pushing
+}
+
+fn team_info(team: &Team) -> TeamInfo<'_> {
+    TeamInfo {
+        display_name: TextComponent::text(&team.display_name),
+        friendly_flags: i8::from(team.friendly_fire) | i8::from(team.see_friendly_invisibles) << 1,
+        name_tag_visibility: team.name_tag_visibility.name(),
+        collision_rule: if entity_pushing() {
+            team.collision_rule.name()
+        } else {
+            "never"
+        },
+        color: VarInt(team.color.map_or(NO_COLOR, |color| color as i32)),
+        prefix: TextComponent::text(&team.prefix),
+        suffix: TextComponent::text(&team.suffix),
+    }
+}
+
+/// Like no team at all, except that its members don't push anything
+fn no_pushing_info() -> TeamInfo<'static> {
+    TeamInfo {
+        display_name: TextComponent::text(""),
+        friendly_flags: 1,
+        name_tag_visibility: "always",
+        collision_rule: "never",
+        color: VarInt(NO_COLOR),
+        prefix: TextComponent::text(""),
+        suffix: TextComponent::text(""),
+    }
+}
+
+fn display_packet(slot: DisplaySlot, objective: Option<&str>) -> CDisplayObjective<'_> {
+    CDisplayObjective::new(i32::from(slot.id()).into(), objective.unwrap_or(""))
+}
+
+impl Scoreboard {
+    /// Reads the scoreboard of the main world in `root_folder`
+    pub fn load(root_folder: &Path) -> Self {
+        let data = ScoreboardData::read(root_folder).unwrap_or_else(|err| {
+            log::error!("Failed to read the scoreboard: {err}");
+            ScoreboardData::default()
+        });
+        Self {
+            data: Mutex::new(data),
+            counted: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn save(&self, root_folder: &Path) {
+        let data = self.data.lock().clone();
+        if let Err(err) = data.write(root_folder) {
+            log::error!("Failed to save the scoreboard: {err}");
+        }
+    }
+
+    /// The scoreboard as it is, to look things up
+    pub fn data(&self) -> MutexGuard<'_, ScoreboardData> {
+        self.data.lock()
+    }
+
+    /// Sends the whole scoreboard to a player who just joined
+    pub fn send(&self, server: &Server, player: &Player) {
+        let data = self.data.lock().clone();
+        for objective in data.objectives() {
+            player
+                .client
+                .send_packet(&objective_packet(objective, true));
+        }
+        for holder in data.holders() {
+            for (objective, score) in data.scores_of(holder) {
+                player
+                    .client
+                    .send_packet(&CUpdateScore::new(holder, objective, score.into()));
+            }
+        }
+        for (slot, objective) in data.display_slots() {
+            player
+                .client
+                .send_packet(&display_packet(slot, Some(objective)));
+        }
+        for team in data.teams() {
+            let members: Vec<String> = team.members.iter().cloned().collect();
+            player.client.send_packet(&CUpdateTeams::new(
+                &team.name,
+                TeamMethod::Create(team_info(team), &members),
+            ));
+        }
+        if !entity_pushing() {
+            let teamless: Vec<String> = server
+                .get_all_players()
+                .iter()
+                .map(|player| player.gameprofile.name.clone())
+                .filter(|name| data.team_of(name).is_none())
+                .collect();
+            player.client.send_packet(&CUpdateTeams::new(
+                NO_PUSHING_TEAM,
+                TeamMethod::Create(no_pushing_info(), &teamless),
+            ));
+            if data.team_of(&player.gameprofile.name).is_none() {
+                server.broadcast_packet_all(&CUpdateTeams::new(
+                    NO_PUSHING_TEAM,
+                    TeamMethod::AddEntities(&[player.gameprofile.name.clone()]),
+                ));
+            }
+        }
+    }
+
+    /// Puts holders who left their team into the team which doesn't push, if pushing is off
+    fn stop_pushing(server: &Server, members: &[String]) {
+        if !entity_pushing() || members.is_empty() {
+            return;
+        }
+        server.broadcast_packet_all(&CUpdateTeams::new(
+            NO_PUSHING_TEAM,
+            TeamMethod::AddEntities(members),
+        ));
+    }
+
+    /// Forgets what was counted for a player who left, it starts over once they are back
+    pub fn forget(&self, player: &Player) {
+        let uuid = player.gameprofile.id;
+        self.counted.lock().retain(|(player, _), _| *player != uuid);
+    }
+
+    pub fn add_objective(
+        &self,
+        server: &Server,
+        name: &str,
+        criterion: &str,
+        display_name: Option<&str>,
+    ) -> Result<(), ScoreboardError> {
+        let objective = self
+            .data
+            .lock()
+            .add_objective(name, criterion, display_name)?
+            .clone();
+        server.broadcast_packet_all(&objective_packet(&objective, true));
+        Ok(())
+    }
+
+    pub fn remove_objective(&self, server: &Server, name: &str) -> Result<(), ScoreboardError> {
+        self.data.lock().remove_objective(name)?;
+        self.counted
+            .lock()
+            .retain(|(_, objective), _| objective != name);
+        // Clients clear the scores and slots of the objective themselves
+        server.broadcast_packet_all(&CUpdateObjectives::remove(name));
+        Ok(())
+    }
+
+    /// Changes how an objective is shown, e.g. its display name
+    pub fn modify_objective(
+        &self,
+        server: &Server,
+        name: &str,
+        modify: impl FnOnce(&mut Objective),
+    ) -> Result<(), ScoreboardError> {
+        let objective = {
+            let mut data = self.data.lock();
+            let objective = data.objective_mut(name)?;
+            modify(objective);
+            objective.clone()
+        };
+        server.broadcast_packet_all(&objective_packet(&objective, false));
+        Ok(())
+    }
+
+    /// Shows an objective in a slot, None clears the slot
+    pub fn set_display_slot(
+        &self,
+        server: &Server,
+        slot: DisplaySlot,
+        objective: Option<&str>,
+    ) -> Result<(), ScoreboardError> {
+        self.data.lock().set_display_slot(slot, objective)?;
+        server.broadcast_packet_all(&display_packet(slot, objective));
+        Ok(())
+    }
+
+    /// Sets a score, objectives which follow what players do can't be set
+    pub fn set_score(
+        &self,
+        server: &Server,
+        holder: &str,
+        objective: &str,
+        value: i32,
+    ) -> Result<(), ScoreboardError> {
+        let changed = {
+            let mut data = self.data.lock();
+            data.writable_objective(objective)?;
+            data.set_score(holder, objective, value)?
+        };
+        if changed {
+            server.broadcast_packet_all(&CUpdateScore::new(holder, objective, value.into()));
+        }
+        Ok(())
+    }
+
+    /// Adds to a score and returns the new one, objectives which follow what players do can't be
+    /// changed
+    pub fn add_score(
+        &self,
+        server: &Server,
+        holder: &str,
+        objective: &str,
+        amount: i32,
+    ) -> Result<i32, ScoreboardError> {
+        let value = {
+            let mut data = self.data.lock();
+            data.writable_objective(objective)?;
+            data.add_score(holder, objective, amount)?
+        };
+        server.broadcast_packet_all(&CUpdateScore::new(holder, objective, value.into()));
+        Ok(value)
+    }
+
+    /// Removes the score of a holder in an objective, or all of their scores. Returns how many
+    /// were removed
+    pub fn reset_scores(
+        &self,
+        server: &Server,
+        holder: &str,
+        objective: Option<&str>,
+    ) -> Result<usize, ScoreboardError> {
+        let reset = {
+            let mut data = self.data.lock();
+            if let Some(objective) = objective {
+                data.objective(objective)?;
+            }
+            data.reset_scores(holder, objective)
+        };
+        if !reset.is_empty() {
+            server.broadcast_packet_all(&CResetScore::new(holder, objective));
+        }
+        Ok(reset.len())
+    }
+
+    pub fn add_team(
+        &self,
+        server: &Server,
+        name: &str,
+        display_name: Option<&str>,
+    ) -> Result<(), ScoreboardError> {
+        let team = self.data.lock().add_team(name, display_name)?.clone();
+        server.broadcast_packet_all(&CUpdateTeams::new(
+            &team.name,
+            TeamMethod::Create(team_info(&team), &[]),
+        ));
+        Ok(())
+    }
+
+    pub fn remove_team(&self, server: &Server, name: &str) -> Result<(), ScoreboardError> {
+        let team = self.data.lock().remove_team(name)?;
+        server.broadcast_packet_all(&CUpdateTeams::new(name, TeamMethod::Remove));
+        let members: Vec<String> = team.members.into_iter().collect();
+        Self::stop_pushing(server, &members);
+        Ok(())
+    }
+
+    /// Changes the options of a team, e.g. its color
+    pub fn modify_team(
+        &self,
+        server: &Server,
+        name: &str,
+        modify: impl FnOnce(&mut Team),
+    ) -> Result<(), ScoreboardError> {
+        let team = {
+            let mut data = self.data.lock();
+            let team = data.team_mut(name)?;
+            modify(team);
+            team.clone()
+        };
+        server.broadcast_packet_all(&CUpdateTeams::new(
+            &team.name,
+            TeamMethod::Update(team_info(&team)),
+        ));
+        Ok(())
+    }
+
+    /// Makes holders members of a team, they leave their old teams
+    pub fn join_team(
+        &self,
+        server: &Server,
+        team: &str,
+        members: &[String],
+    ) -> Result<(), ScoreboardError> {
+        {
+            let mut data = self.data.lock();
+            data.team(team)?;
+            for member in members {
+                data.join_team(team, member)?;
+            }
+        }
+        // Clients take them out of their old teams by themselves
+        server.broadcast_packet_all(&CUpdateTeams::new(team, TeamMethod::AddEntities(members)));
+        Ok(())
+    }
+
+    /// Takes holders out of their teams, returns how many were in one
+    pub fn leave_team(&self, server: &Server, members: &[String]) -> usize {
+        let mut left: HashMap<String, Vec<String>> = HashMap::new();
+        {
+            let mut data = self.data.lock();
+            for member in members {
+                if let Some(team) = data.leave_team(member) {
+                    left.entry(team).or_default().push(member.clone());
+                }
+            }
+        }
+        for (team, members) in &left {
+            server.broadcast_packet_all(&CUpdateTeams::new(
+                team,
+                TeamMethod::RemoveEntities(members),
+            ));
+            Self::stop_pushing(server, members);
+        }
+        left.values().map(Vec::len).sum()
+    }
+
+    /// Takes all members out of a team, returns how many there were
+    pub fn empty_team(&self, server: &Server, name: &str) -> Result<usize, ScoreboardError> {
+        let members: Vec<String> = self
+            .data
+            .lock()
+            .team(name)?
+            .members
+            .iter()
+            .cloned()
+            .collect();
+        Ok(self.leave_team(server, &members))
+    }
+
+    /// Whether one player can hurt another, members of a team without friendly fire can't
+    pub fn can_hurt(&self, attacker: &Player, target: &Player) -> bool {
+        let data = self.data.lock();
+        match data.team_of(&attacker.gameprofile.name) {
+            Some(team) => team.friendly_fire || !team.members.contains(&target.gameprofile.name),
+            None => true,
+        }
+    }
+
+    /// Whether a player pushes mobs, which are in no team
+    pub fn pushes_mobs(&self, player: &Player) -> bool {
+        self.data
+            .lock()
+            .team_of(&player.gameprofile.name)
+            .map_or(true, |team| team.collision_rule.pushes(false))
+    }
+
+    /// Updates the scores of the objectives which follow what players do
+    pub fn tick(&self, server: &Server) {
+        let objectives: Vec<Objective> = self
+            .data
+            .lock()
+            .objectives()
+            .filter(|objective| objective.criterion != Criterion::Dummy)
+            .cloned()
+            .collect();
+        if objectives.is_empty() {
+            return;
+        }
+        // A score is either set to the current value or increased by what was counted
+        let mut changes: Vec<(String, String, Result<i32, i32>)> = Vec::new();
+        for player in server.get_all_players() {
+            let name = &player.gameprofile.name;
+            for objective in &objectives {
+                let experience = player.experience.load();
+                let current = match objective.criterion {
+                    Criterion::Health => player.living_entity.health.load().ceil() as i32,
+                    Criterion::Food => player.food.load(Ordering::Relaxed),
+                    Criterion::Level => experience.level,
+                    Criterion::Xp => experience.total,
+                    _ => {
+                        let counted = {
+                            let stats = player.stats.lock();
+                            objective.criterion.counted_stats().into_iter().fold(
+                                0,
+                                |sum: i32, (category, stat)| {
+                                    sum.saturating_add(stats.get(category, stat))
+                                },
+                            )
+                        };
+                        let key = (player.gameprofile.id, objective.name.clone());
+                        match self.counted.lock().insert(key, counted) {
+                            Some(last) if counted > last => changes.push((
+                                name.clone(),
+                                objective.name.clone(),
+                                Err(counted - last),
+                            )),
+                            _ => {}
+                        }
+                        continue;
+                    }
+                };
+                changes.push((name.clone(), objective.name.clone(), Ok(current)));
+            }
+        }
+
+        let mut updates = Vec::new();
+        {
+            let mut data = self.data.lock();
+            for (holder, objective, change) in changes {
+                let value = match change {
+                    Ok(value) => match data.set_score(&holder, &objective, value) {
+                        Ok(true) => value,
+                        _ => continue,
+                    },
+                    Err(amount) => match data.add_score(&holder, &objective, amount) {
+                        Ok(value) => value,
+                        Err(_) => continue,
+                    },
+                };
+                updates.push((holder, objective, value));
+            }
+        }
+        for (holder, objective, value) in updates {
+            server.broadcast_packet_all(&CUpdateScore::new(&holder, &objective, value.into()));
+        }
+    }
+}
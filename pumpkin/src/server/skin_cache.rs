@@ -0,0 +1,137 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use pumpkin_config::{skins::SkinConfig, ADVANCED_CONFIG};
+use pumpkin_protocol::Property;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A cache file of a single player
+#[derive(Serialize, Deserialize)]
+struct CachedSkin {
+    /// When the properties were fetched, in epoch seconds
+    fetched_at: u64,
+    properties: Vec<Property>,
+}
+
+#[derive(Deserialize)]
+struct ProfileLookup {
+    id: Uuid,
+}
+
+#[derive(Deserialize)]
+struct Profile {
+    properties: Vec<Property>,
+}
+
+/// Caches the signed skin and cape properties of players on disk, keyed by their name
+pub struct SkinCache {
+    dir: PathBuf,
+    ttl: Duration,
+    /// Used to fetch skins of offline mode players, the auth client only exists in online mode
+    http_client: reqwest::Client,
+}
+
+impl SkinCache {
+    pub fn new(config: &SkinConfig) -> Self {
+        let auth_config = &ADVANCED_CONFIG.get().authentication;
+        Self {
+            dir: PathBuf::from(&config.cache_dir),
+            ttl: Duration::from_secs(config.cache_ttl_secs),
+            http_client: reqwest::Client::builder()
+                .connect_timeout(Duration::from_millis(auth_config.connect_timeout_ms))
+                .timeout(Duration::from_millis(auth_config.timeout_ms))
+                .build()
+                .expect("Failed to to make reqwest client"),
+        }
+    }
+
+    /// The cache file of a player, None if the name can't belong to a premium account
+    fn path(&self, name: &str) -> Option<PathBuf> {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return None;
+        }
+        Some(self.dir.join(format!("{}.json", name.to_lowercase())))
+    }
+
+    /// Stores the properties of a player, e.g. after they got authenticated
+    pub async fn store(&self, name: &str, properties: &[Property]) {
+        let Some(path) = self.path(name) else {
+            return;
+        };
+        let cached = CachedSkin {
+            fetched_at: now_secs(),
+            properties: properties.to_vec(),
+        };
+        let Ok(json) = serde_json::to_vec(&cached) else {
+            return;
+        };
+        if let Err(err) = tokio::fs::create_dir_all(&self.dir).await {
+            log::warn!("Failed to create skin cache directory: {err}");
+            return;
+        }
+        if let Err(err) = tokio::fs::write(path, json).await {
+            log::warn!("Failed to cache skin of {name}: {err}");
+        }
+    }
+
+    /// Returns the cached properties of a player, expired ones only when `allow_expired` is set
+    pub async fn cached(&self, name: &str, allow_expired: bool) -> Option<Vec<Property>> {
+        let json = tokio::fs::read(self.path(name)?).await.ok()?;
+        let cached: CachedSkin = serde_json::from_slice(&json).ok()?;
+        let age = Duration::from_secs(now_secs().saturating_sub(cached.fetched_at));
+        (allow_expired || age < self.ttl).then_some(cached.properties)
+    }
+
+    /// Returns the properties of the premium account called `name`, from the cache if possible
+    pub async fn fetch(&self, name: &str) -> Option<Vec<Property>> {
+        self.path(name)?;
+        if let Some(properties) = self.cached(name, false).await {
+            return Some(properties);
+        }
+        match self.fetch_from_mojang(name).await {
+            Ok(properties) => {
+                self.store(name, &properties).await;
+                Some(properties)
+            }
+            Err(err) => {
+                log::debug!("Failed to fetch skin of {name}: {err}");
+                // Better an outdated skin than none
+                self.cached(name, true).await
+            }
+        }
+    }
+
+    async fn fetch_from_mojang(&self, name: &str) -> Result<Vec<Property>, reqwest::Error> {
+        let config = &ADVANCED_CONFIG.get().skins;
+        let lookup: ProfileLookup = self
+            .http_client
+            .get(config.profile_lookup_url.replace("{username}", name))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let profile: Profile = self
+            .http_client
+            .get(
+                config
+                    .profile_url
+                    .replace("{uuid}", &lookup.id.simple().to_string()),
+            )
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(profile.properties)
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
@@ -0,0 +1,68 @@
+use parking_lot::RwLock;
+use pumpkin_config::{ADVANCED_CONFIG, BASIC_CONFIG};
+use pumpkin_core::text::TextComponent;
+use pumpkin_plugins::events::TabListEvent;
+use pumpkin_protocol::client::play::CTabListHeaderFooter;
+
+use crate::entity::player::Player;
+
+use super::Server;
+
+/// The header and footer of the player list, shared by all players
+pub struct TabList {
+    header: RwLock<String>,
+    footer: RwLock<String>,
+}
+
+impl Default for TabList {
+    fn default() -> Self {
+        let config = &ADVANCED_CONFIG.get().tab_list;
+        Self {
+            header: RwLock::new(config.header.clone()),
+            footer: RwLock::new(config.footer.clone()),
+        }
+    }
+}
+
+impl TabList {
+    /// Replaces the header and footer, players only see the change after the next refresh
+    pub fn set(&self, header: String, footer: String) {
+        *self.header.write() = header;
+        *self.footer.write() = footer;
+    }
+
+    /// Sends the header and footer to a single player, nothing is sent when both are empty
+    pub fn send(&self, server: &Server, player: &Player) {
+        let online = server.get_player_count().to_string();
+        let max_players = BASIC_CONFIG.get().max_players.to_string();
+        let gameprofile = &player.gameprofile;
+        let stats = player.stats.lock().clone();
+        let replace = |text: &str| {
+            let text = text
+                .replace("{player}", &gameprofile.name)
+                .replace("{online}", &online)
+                .replace("{max_players}", &max_players)
+                .replace("{motd}", &BASIC_CONFIG.get().motd);
+            stats.replace_placeholders(&text)
+        };
+
+        let mut event = TabListEvent {
+            player_name: &gameprofile.name,
+            player_uuid: gameprofile.id,
+            header: replace(&self.header.read()),
+            footer: replace(&self.footer.read()),
+        };
+        server
+            .plugins
+            .read()
+            .for_each(|plugin| plugin.on_tab_list(&mut event));
+
+        if event.header.is_empty() && event.footer.is_empty() {
+            return;
+        }
+        player.client.send_packet(&CTabListHeaderFooter::new(
+            TextComponent::text(&event.header),
+            TextComponent::text(&event.footer),
+        ));
+    }
+}
@@ -0,0 +1,58 @@
+use std::time::{Duration, Instant};
+
+use crate::metrics::METRICS;
+
+use super::Server;
+
+/// The amount of ticks per second the Server runs at
+pub const TICKS_PER_SECOND: u64 = 20;
+/// The time budget of a single tick
+pub const TICK_DURATION: Duration = Duration::from_millis(1000 / TICKS_PER_SECOND);
+
+/// When the Server falls further behind than this, we skip the missed ticks instead of catching up
+const MAX_CATCH_UP: Duration = Duration::from_secs(2);
+
+/// Drives the Server at a fixed tick rate.
+///
+/// When a tick takes longer than its budget, the following ticks run back to back until the Server caught up again.
+pub struct Ticker {
+    next_tick: Instant,
+}
+
+impl Default for Ticker {
+    fn default() -> Self {
+        Self {
+            next_tick: Instant::now(),
+        }
+    }
+}
+
+impl Ticker {
+    /// Ticks the Server until a shutdown was requested
+    pub async fn run(&mut self, server: &Server) {
+        while server.is_running() {
+            let tick_start = Instant::now();
+            let behind = tick_start.saturating_duration_since(self.next_tick);
+            if behind > MAX_CATCH_UP {
+                log::warn!(
+                    "Can't keep up! Is the server overloaded? Running {}ms or {} ticks behind",
+                    behind.as_millis(),
+                    behind.as_millis() / TICK_DURATION.as_millis()
+                );
+                self.next_tick = tick_start;
+            }
+
+            server.tick(tick_start + TICK_DURATION);
+
+            let tick_time = tick_start.elapsed();
+            METRICS.record_tick(tick_start, tick_time);
+            if tick_time > TICK_DURATION {
+                log::debug!("Tick took {}ms", tick_time.as_millis());
+            }
+
+            self.next_tick += TICK_DURATION;
+            // When we are behind this returns instantly, so we catch up
+            tokio::time::sleep_until(self.next_tick.into()).await;
+        }
+    }
+}
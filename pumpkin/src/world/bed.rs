@@ -0,0 +1,498 @@
+use std::sync::{atomic::Ordering, Arc};
+
+use pumpkin_core::{
+    math::{position::WorldPosition, vector2::Vector2, vector3::Vector3},
+    text::TextComponent,
+    GameMode,
+};
+use pumpkin_entity::pose::EntityPose;
+use pumpkin_protocol::client::play::{Animation, CEntityAnimation, CSystemChatMessage};
+use pumpkin_world::{
+    block::BlockId,
+    dimension::Dimension,
+    game_rules,
+    global_registry::{self, ITEM_REGISTRY},
+    player_data::SpawnPoint,
+    stats,
+};
+
+use crate::{entity::player::Player, server::Server};
+
+use super::{redstone::offset_of, weather::WeatherType, World};
+
+/// How far players can be from a bed to sleep in it, like in vanilla
+const BED_REACH_HORIZONTAL: f64 = 3.0;
+const BED_REACH_VERTICAL: f64 = 2.0;
+/// Players can't sleep while a monster is this close to their bed
+const MONSTER_RANGE_HORIZONTAL: f64 = 8.0;
+const MONSTER_RANGE_VERTICAL: f64 = 5.0;
+/// How strong beds outside of the main world and respawn anchors outside of the Nether explode
+const BAD_RESPAWN_POINT_POWER: f32 = 5.0;
+/// How high above the bottom of the bed sleeping players lie
+const SLEEPING_HEIGHT: f64 = 0.6875;
+/// How high beds are, players wake up standing on top of them
+const BED_HEIGHT: f64 = 0.5625;
+const MAX_ANCHOR_CHARGES: u8 = 4;
+
+pub fn is_bed(block: &BlockId) -> bool {
+    block.category() == Some("minecraft:bed")
+}
+
+/// The head of the bed with one of its halves at `position`
+pub fn bed_head(block: &BlockId, position: &WorldPosition) -> Option<WorldPosition> {
+    if block.property("part")? == "head" {
+        return Some(*position);
+    }
+    let facing = offset_of(block.property("facing")?)?;
+    Some(WorldPosition(position.0.add(&facing)))
+}
+
+/// The other half of the bed at `position`, None if there is no bed or it lost its other half
+pub fn other_half(
+    world: &World,
+    position: &WorldPosition,
+    block: &BlockId,
+) -> Option<(WorldPosition, BlockId)> {
+    if !is_bed(block) {
+        return None;
+    }
+    let facing = offset_of(block.property("facing")?)?;
+    let other = if block.property("part")? == "head" {
+        WorldPosition(position.0.sub(&facing))
+    } else {
+        WorldPosition(position.0.add(&facing))
+    };
+    let other_block = world.level.get_block(&other).filter(is_bed)?;
+    (other_block.property("part") != block.property("part")).then_some((other, other_block))
+}
+
+/// The direction a player looks in, e.g. the head of a bed they place points away from them
+fn horizontal_facing(yaw: f32) -> &'static str {
+    match ((yaw / 90.0).round() as i32).rem_euclid(4) {
+        0 => "south",
+        1 => "west",
+        2 => "north",
+        _ => "east",
+    }
+}
+
+fn center(position: &WorldPosition) -> Vector3<f64> {
+    Vector3::new(
+        f64::from(position.0.x) + 0.5,
+        f64::from(position.0.y) + 0.5,
+        f64::from(position.0.z) + 0.5,
+    )
+}
+
+/// Where players stand up when they leave the bed with its head at `head`
+pub fn standing_position(head: &WorldPosition) -> Vector3<f64> {
+    let center = center(head);
+    Vector3::new(center.x, f64::from(head.0.y) + BED_HEIGHT, center.z)
+}
+
+/// Places the bed with its foot at `foot` and its head in front of it, where the player looks.
+/// Returns false if something is in the way of the head
+pub fn place_bed(world: &World, player: &Player, foot: &WorldPosition, bed: BlockId) -> bool {
+    let facing = horizontal_facing(player.living_entity.entity.yaw.load());
+    let Some(offset) = offset_of(facing) else {
+        return false;
+    };
+    let head = WorldPosition(foot.0.add(&offset));
+    if !world
+        .level
+        .get_block(&head)
+        .is_some_and(|block| block.is_replaceable())
+        || world.is_occupied(&head)
+    {
+        return false;
+    }
+    let half = |part: &str| {
+        bed.with_property("facing", facing)
+            .and_then(|block| block.with_property("part", part))
+    };
+    let (Some(foot_block), Some(head_block)) = (half("foot"), half("head")) else {
+        return false;
+    };
+    world.set_block(foot, foot_block);
+    world.set_block(&head, head_block);
+    true
+}
+
+/// Marks both halves of the bed as occupied or free
+pub fn set_occupied(world: &World, head: &WorldPosition, occupied: bool) {
+    let Some(block) = world.level.get_block(head).filter(is_bed) else {
+        return;
+    };
+    let value = if occupied { "true" } else { "false" };
+    let other = other_half(world, head, &block);
+    if let Some(changed) = block.with_property("occupied", value) {
+        world.set_block(head, changed);
+    }
+    if let Some((position, other_block)) = other {
+        if let Some(changed) = other_block.with_property("occupied", value) {
+            world.set_block(&position, changed);
+        }
+    }
+}
+
+/// Lets the player sleep in the bed at `position`, or tells them why they can't.
+/// Returns false if there is no bed
+pub fn use_bed(server: &Server, world: &World, player: &Player, position: &WorldPosition) -> bool {
+    let Some(block) = world.level.get_block(position).filter(is_bed) else {
+        return false;
+    };
+    let Some(head) = bed_head(&block, position) else {
+        return false;
+    };
+    if world.dimension != Dimension::OverWorld {
+        // Beds explode outside of the main world, like in vanilla
+        if let Some((other, _)) = other_half(world, position, &block) {
+            world.set_block(&other, BlockId::default());
+        }
+        world.set_block(position, BlockId::default());
+        world.explode(server, center(&head), BAD_RESPAWN_POINT_POWER, true);
+        return true;
+    }
+    if player.is_sleeping() {
+        return true;
+    }
+    if block.property("occupied") == Some("true") {
+        player.send_action_bar_message(TextComponent::translate(
+            "block.minecraft.bed.occupied",
+            vec![],
+        ));
+        return true;
+    }
+
+    let bed = center(&head);
+    let offset = player.living_entity.entity.pos.load().sub(&bed);
+    if offset.x.abs() > BED_REACH_HORIZONTAL
+        || offset.z.abs() > BED_REACH_HORIZONTAL
+        || offset.y.abs() > BED_REACH_VERTICAL
+    {
+        player.send_action_bar_message(TextComponent::translate(
+            "block.minecraft.bed.too_far_away",
+            vec![],
+        ));
+        return true;
+    }
+    let above = WorldPosition(head.0.add(&Vector3::new(0, 1, 0)));
+    if world
+        .level
+        .get_block(&above)
+        .is_some_and(|block| block.has_collision())
+    {
+        player.send_action_bar_message(TextComponent::translate(
+            "block.minecraft.bed.obstructed",
+            vec![],
+        ));
+        return true;
+    }
+
+    // The spawn point moves even if the player can't sleep yet
+    set_spawn_point(world, player, &head);
+    if !world.is_sleep_time() {
+        player.send_action_bar_message(TextComponent::translate(
+            "block.minecraft.bed.no_sleep",
+            vec![],
+        ));
+        return true;
+    }
+    if player.gamemode.load() != GameMode::Creative
+        && world.is_monster_near(bed, MONSTER_RANGE_HORIZONTAL, MONSTER_RANGE_VERTICAL)
+    {
+        player.send_action_bar_message(TextComponent::translate(
+            "block.minecraft.bed.not_safe",
+            vec![],
+        ));
+        return true;
+    }
+
+    player.start_sleeping(
+        head,
+        Vector3::new(bed.x, f64::from(head.0.y) + SLEEPING_HEIGHT, bed.z),
+    );
+    set_occupied(world, &head, true);
+    player.increment_custom_stat(stats::SLEEP_IN_BED, 1);
+    player
+        .stats
+        .lock()
+        .set(stats::CUSTOM, stats::TIME_SINCE_REST, 0);
+    player.trigger(server, "minecraft:slept_in_bed", |_| true);
+    world.announce_sleep_status();
+    true
+}
+
+/// Charges the respawn anchor at `position` with the glowstone the player holds, or sets their
+/// spawn point there. Outside of the Nether charged anchors explode instead.
+/// Returns false if there is no respawn anchor or it has no charges
+pub fn use_respawn_anchor(
+    server: &Server,
+    world: &World,
+    player: &Player,
+    position: &WorldPosition,
+) -> bool {
+    let Some(block) = world
+        .level
+        .get_block(position)
+        .filter(|block| block.category() == Some("minecraft:respawn_anchor"))
+    else {
+        return false;
+    };
+    let charges = anchor_charges(&block);
+    let holds_glowstone = player.inventory.lock().held_item().is_some_and(|item| {
+        global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id)
+            == Some("minecraft:glowstone")
+    });
+    if holds_glowstone && charges < MAX_ANCHOR_CHARGES {
+        if let Some(charged) = block.with_property("charges", &(charges + 1).to_string()) {
+            world.set_block(position, charged);
+        }
+        if player.gamemode.load() != GameMode::Creative {
+            let mut inventory = player.inventory.lock();
+            let held = inventory.held_item_mut();
+            if let Some(item) = held {
+                item.item_count = item.item_count.saturating_sub(1);
+                if item.item_count == 0 {
+                    *held = None;
+                }
+            }
+        }
+        return true;
+    }
+    if charges == 0 {
+        return false;
+    }
+    if world.dimension != Dimension::Nether {
+        world.set_block(position, BlockId::default());
+        world.explode(server, center(position), BAD_RESPAWN_POINT_POWER, true);
+        return true;
+    }
+    set_spawn_point(world, player, position);
+    true
+}
+
+fn anchor_charges(block: &BlockId) -> u8 {
+    block
+        .property("charges")
+        .and_then(|charges| charges.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Moves the spawn point of the player to the bed or respawn anchor at `position`, they are told
+/// if it changed
+fn set_spawn_point(world: &World, player: &Player, position: &WorldPosition) {
+    let spawn_point = SpawnPoint {
+        position: position.0,
+        dimension: world.dimension_name.clone(),
+        world: Some(world.name.clone()),
+        angle: player.living_entity.entity.yaw.load(),
+        forced: false,
+    };
+    let changed = {
+        let mut current = player.spawn_point.lock();
+        let changed = current.as_ref().map_or(true, |current| {
+            current.position != spawn_point.position || current.world != spawn_point.world
+        });
+        *current = Some(spawn_point);
+        changed
+    };
+    if changed {
+        player.send_system_message(TextComponent::translate(
+            "block.minecraft.set_spawn",
+            vec![],
+        ));
+    }
+}
+
+/// The world and position the player respawns at, None for the spawn of the world. Respawning
+/// at a respawn anchor uses up one of its charges. Players whose bed or respawn anchor is gone
+/// lose their spawn point
+pub fn respawn_position(server: &Server, player: &Player) -> Option<(Arc<World>, Vector3<f64>)> {
+    let spawn_point = player.spawn_point.lock().clone()?;
+    let world = spawn_point
+        .world
+        .as_deref()
+        .and_then(|name| server.get_world(name))
+        .or_else(|| {
+            server
+                .worlds
+                .iter()
+                .find(|world| world.dimension_name == spawn_point.dimension)
+                .cloned()
+        });
+    if let Some(world) = world {
+        if let Some(position) = respawn_at(&world, &spawn_point) {
+            return Some((world, position));
+        }
+    }
+    player.send_system_message(TextComponent::translate(
+        "block.minecraft.spawn.not_valid",
+        vec![],
+    ));
+    *player.spawn_point.lock() = None;
+    None
+}
+
+fn respawn_at(world: &World, spawn_point: &SpawnPoint) -> Option<Vector3<f64>> {
+    let position = WorldPosition(spawn_point.position);
+    world
+        .level
+        .load_chunk(Vector2::new(position.0.x >> 4, position.0.z >> 4));
+    let block = world.level.get_block(&position)?;
+    if is_bed(&block) {
+        return Some(standing_position(&bed_head(&block, &position)?));
+    }
+    if block.category() == Some("minecraft:respawn_anchor") {
+        let charges = anchor_charges(&block);
+        if charges == 0 {
+            return None;
+        }
+        if let Some(used) = block.with_property("charges", &(charges - 1).to_string()) {
+            world.set_block(&position, used);
+        }
+        let center = center(&position);
+        return Some(Vector3::new(
+            center.x,
+            f64::from(position.0.y) + 1.0,
+            center.z,
+        ));
+    }
+    spawn_point.forced.then(|| {
+        let center = center(&position);
+        Vector3::new(center.x, f64::from(position.0.y), center.z)
+    })
+}
+
+impl World {
+    /// Whether players can go to bed, like in vanilla at night and during thunderstorms
+    pub fn is_sleep_time(&self) -> bool {
+        const DAY_SKY_DARKNESS: u8 = 4;
+        self.sky_darkness() >= DAY_SKY_DARKNESS || self.weather() == WeatherType::Thunder
+    }
+
+    /// How many players sleep, how many of them slept long enough and how many have to sleep to
+    /// skip the night, see the `playersSleepingPercentage` game rule
+    pub fn sleep_status(&self) -> (usize, usize, usize) {
+        let percentage = self
+            .game_rule_int(game_rules::PLAYERS_SLEEPING_PERCENTAGE)
+            .max(0) as usize;
+        let players = self.current_players.lock();
+        let active = players
+            .values()
+            .filter(|player| player.gamemode.load() != GameMode::Spectator)
+            .count();
+        let sleeping = players
+            .values()
+            .filter(|player| player.is_sleeping())
+            .count();
+        let deep = players
+            .values()
+            .filter(|player| player.is_sleeping_long_enough())
+            .count();
+        let needed = (active * percentage).div_ceil(100).max(1);
+        (sleeping, deep, needed)
+    }
+
+    /// Shows everyone in the world how many players sleep and how many have to
+    pub fn announce_sleep_status(&self) {
+        // Above 100% the night is never skipped
+        if self.game_rule_int(game_rules::PLAYERS_SLEEPING_PERCENTAGE) > 100 {
+            return;
+        }
+        let (sleeping, _, needed) = self.sleep_status();
+        let (sleeping_text, needed_text) = (sleeping.to_string(), needed.to_string());
+        let message = if sleeping >= needed {
+            TextComponent::translate("sleep.skipping_night", vec![])
+        } else {
+            TextComponent::translate(
+                "sleep.players_sleeping",
+                vec![
+                    TextComponent::text(&sleeping_text),
+                    TextComponent::text(&needed_text),
+                ],
+            )
+        };
+        self.broadcast_packet_all(&CSystemChatMessage::new(message, true));
+    }
+
+    /// Gets the player out of their bed in this world, they stand up on top of it. `announce`
+    /// shows the others how many players still sleep
+    pub fn leave_bed(&self, player: &Player, announce: bool) {
+        let Some(head) = player.sleeping.take() else {
+            return;
+        };
+        player.sleep_timer.store(0, Ordering::Relaxed);
+        set_occupied(self, &head, false);
+        let entity = &player.living_entity.entity;
+        entity.set_pose(EntityPose::Standing);
+        player.client.send_packet(&entity.pose_packet());
+        let entity_id = player.entity_id();
+        let packet = player.sleeping_packet();
+        self.broadcast_tracked(entity_id, &packet);
+        player.client.send_packet(&packet);
+        let animation = CEntityAnimation::new(entity_id.into(), Animation::LeaveBed as u8);
+        self.broadcast_tracked(entity_id, &animation);
+        player.client.send_packet(&animation);
+        // Players who left the world are placed in the other world instead
+        if self.current_players.lock().contains_key(&player.client.id) {
+            let position = standing_position(&head);
+            player.teleport(
+                position.x,
+                position.y,
+                position.z,
+                entity.yaw.load(),
+                entity.pitch.load(),
+            );
+        }
+        if announce {
+            self.announce_sleep_status();
+        }
+    }
+
+    /// Wakes players once it is day or their bed is gone and skips the night once enough players
+    /// slept long enough
+    pub(super) fn tick_sleeping(&self, server: &Server) {
+        let players: Vec<Arc<Player>> = self
+            .current_players
+            .lock()
+            .values()
+            .filter(|player| player.is_sleeping())
+            .cloned()
+            .collect();
+        if players.is_empty() {
+            return;
+        }
+        let sleep_time = self.is_sleep_time();
+        for player in &players {
+            let in_bed = player.sleeping.load().is_some_and(|head| {
+                self.level
+                    .get_block(&head)
+                    .is_some_and(|block| is_bed(&block))
+            });
+            if !sleep_time || !in_bed {
+                player.wake_up(true);
+            }
+        }
+
+        let (sleeping, deep, needed) = self.sleep_status();
+        if sleeping == 0 || deep < needed {
+            return;
+        }
+        if self.game_rule_bool(game_rules::DO_DAYLIGHT_CYCLE) {
+            let time = self.time_of_day.load(Ordering::Relaxed);
+            self.time_of_day
+                .store(time + 24000 - time.rem_euclid(24000), Ordering::Relaxed);
+            self.broadcast_packet_all(&self.time_packet());
+        }
+        for player in &players {
+            player.wake_up(false);
+        }
+        // Like in vanilla, sleeping through the night also ends the rain
+        if self.game_rule_bool(game_rules::DO_WEATHER_CYCLE) && self.weather() != WeatherType::Clear
+        {
+            self.weather.lock().reset_cycle();
+            self.fire_weather_change(server, WeatherType::Clear);
+        }
+    }
+}
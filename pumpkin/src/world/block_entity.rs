@@ -0,0 +1,141 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use pumpkin_core::math::position::WorldPosition;
+use pumpkin_inventory::{Chest, OpenContainer};
+use pumpkin_protocol::client::play::{CBlockAction, CBlockEntityData};
+use pumpkin_world::{block::BlockEntity, global_registry, item::ItemStack};
+
+use crate::{entity::player::Player, server::Server};
+
+use super::{furnace::FURNACES, World};
+
+/// Container ids of opened chests, furnaces and crafting tables, 0 is used by `/echest`
+pub(crate) static NEXT_CONTAINER_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Block entities which open as a chest and animate their lid
+const CHESTS: [&str; 2] = ["minecraft:chest", "minecraft:trapped_chest"];
+
+/// Block action which tells clients how many players look into a chest
+const CHEST_VIEWERS_ACTION: u8 = 1;
+
+impl World {
+    /// Replaces a block entity and shows the change to all players
+    pub fn set_block_entity(&self, entity: BlockEntity) {
+        if !self.level.set_block_entity(entity.clone()) {
+            return;
+        }
+        if let Some(packet) = CBlockEntityData::new(&entity) {
+            self.broadcast_packet_all(&packet);
+        }
+    }
+}
+
+/// Opens the chest at `position` for the player, the others see its lid open.
+/// Returns false if there is no chest
+pub fn open_chest(
+    server: &Server,
+    world: &World,
+    player: &Player,
+    position: &WorldPosition,
+) -> bool {
+    let Some(entity) = world
+        .level
+        .get_block_entity(position)
+        .filter(|entity| CHESTS.contains(&entity.id.as_str()))
+    else {
+        return false;
+    };
+    player.close_container(server);
+
+    let player_id = player.entity_id();
+    // Players looking into the same chest share its container, so they see each others changes
+    let (container_id, viewers) = {
+        let mut open_block_entities = world.open_block_entities.lock();
+        let mut open_containers = server.open_containers.write();
+        let container_id = *open_block_entities
+            .entry(position.0)
+            .or_insert_with(|| NEXT_CONTAINER_ID.fetch_add(1, Ordering::Relaxed));
+        let container = open_containers.entry(container_id).or_insert_with(|| {
+            let mut items = [None; 27];
+            for (slot, item) in entity.items() {
+                if let Some(slot) = items.get_mut(slot) {
+                    *slot = Some(item);
+                }
+            }
+            OpenContainer::new(player_id, Box::new(Chest::with_items(items)))
+        });
+        container.add_player(player_id);
+        (container_id, container.all_player_ids().len())
+    };
+
+    player.open_container.store(Some(container_id));
+    player.open_container(server, "minecraft:generic_9x3");
+    play_chest_animation(world, position, viewers);
+    true
+}
+
+/// Called after a player closed a container. If it was a chest or furnace its items are saved,
+/// and the others see the lid of a chest close once the last player closed it
+pub fn close_block_container(server: &Server, world: &World, container_id: u64) {
+    let (position, items, viewers) = {
+        let mut open_block_entities = world.open_block_entities.lock();
+        let Some(position) = open_block_entities
+            .iter()
+            .find(|(_, id)| **id == container_id)
+            .map(|(position, _)| *position)
+        else {
+            return;
+        };
+        let mut open_containers = server.open_containers.write();
+        let Some(open_container) = open_containers.get(&container_id) else {
+            open_block_entities.remove(&position);
+            return;
+        };
+        let items: Vec<Option<ItemStack>> = open_container
+            .container()
+            .lock()
+            .all_slots_ref()
+            .into_iter()
+            .map(|item| item.copied())
+            .collect();
+        let viewers = open_container.all_player_ids().len();
+        if viewers == 0 {
+            open_containers.remove(&container_id);
+            open_block_entities.remove(&position);
+        }
+        (WorldPosition(position), items, viewers)
+    };
+
+    // The block may have been broken while it was open
+    let Some(mut entity) = world.level.get_block_entity(&position).filter(|entity| {
+        CHESTS.contains(&entity.id.as_str()) || FURNACES.contains(&entity.id.as_str())
+    }) else {
+        return;
+    };
+    entity.set_items(&items);
+    let is_chest = CHESTS.contains(&entity.id.as_str());
+    world.level.set_block_entity(entity);
+    if is_chest {
+        play_chest_animation(world, &position, viewers);
+    }
+}
+
+/// Opens or closes the lid of a chest, depending on whether someone looks into it
+fn play_chest_animation(world: &World, position: &WorldPosition, viewers: usize) {
+    let Some((block, _)) = world
+        .level
+        .get_block(position)
+        .and_then(|block| block.registry_state())
+    else {
+        return;
+    };
+    let Some(block_type) = global_registry::find_protocol_id("minecraft:block", block) else {
+        return;
+    };
+    world.broadcast_packet_all(&CBlockAction::new(
+        position,
+        CHEST_VIEWERS_ACTION,
+        viewers.min(u8::MAX as usize) as u8,
+        (block_type as i32).into(),
+    ));
+}
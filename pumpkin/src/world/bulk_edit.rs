@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use pumpkin_core::math::{position::WorldPosition, vector2::Vector2, vector3::Vector3};
+use pumpkin_protocol::client::play::CUpdateSectionBlocks;
+use pumpkin_world::block::BlockId;
+
+use super::World;
+
+/// A box of blocks in a world, both corners are inside of it
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Region {
+    min: Vector3<i32>,
+    max: Vector3<i32>,
+}
+
+impl Region {
+    /// The box between two corners, in any order
+    pub fn new(from: &WorldPosition, to: &WorldPosition) -> Self {
+        let (from, to) = (from.0, to.0);
+        Self {
+            min: Vector3::new(from.x.min(to.x), from.y.min(to.y), from.z.min(to.z)),
+            max: Vector3::new(from.x.max(to.x), from.y.max(to.y), from.z.max(to.z)),
+        }
+    }
+
+    /// How many blocks are in the region
+    pub fn volume(&self) -> u64 {
+        let size = |min: i32, max: i32| u64::from(min.abs_diff(max)) + 1;
+        size(self.min.x, self.max.x) * size(self.min.y, self.max.y) * size(self.min.z, self.max.z)
+    }
+
+    /// The chunks the region covers
+    pub fn chunks(&self) -> impl Iterator<Item = Vector2<i32>> {
+        let (min, max) = (self.min, self.max);
+        (min.x >> 4..=max.x >> 4)
+            .flat_map(move |x| (min.z >> 4..=max.z >> 4).map(move |z| Vector2::new(x, z)))
+    }
+
+    /// The blocks of the region inside of a chunk
+    fn blocks_in(&self, chunk: Vector2<i32>) -> impl Iterator<Item = Vector3<i32>> {
+        let min_x = self.min.x.max(chunk.x * 16);
+        let max_x = self.max.x.min(chunk.x * 16 + 15);
+        let min_z = self.min.z.max(chunk.z * 16);
+        let max_z = self.max.z.min(chunk.z * 16 + 15);
+        let (min_y, max_y) = (self.min.y, self.max.y);
+        (min_x..=max_x).flat_map(move |x| {
+            (min_z..=max_z).flat_map(move |z| (min_y..=max_y).map(move |y| Vector3::new(x, y, z)))
+        })
+    }
+}
+
+impl World {
+    /// Sets all blocks of a region to the same block. Returns how many blocks changed
+    pub fn fill(&self, region: &Region, block: BlockId) -> usize {
+        self.edit_region(region, |_| Some(block))
+    }
+
+    /// Replaces all blocks of a region which are `from` with `to`. Returns how many blocks changed
+    pub fn replace(&self, region: &Region, from: BlockId, to: BlockId) -> usize {
+        self.edit_region(region, |old| (old == from).then_some(to))
+    }
+
+    /// Changes the blocks of a region a chunk at a time. `edit` gets the old block and returns the
+    /// new one, or None to keep it.
+    ///
+    /// Unlike `set_block` this doesn't update the neighbours of the changed blocks, like vanilla's
+    /// `/fill`. The light of a chunk is updated once after all of its blocks changed and players
+    /// get one packet for every changed chunk section. Loads the chunks of the region, so it
+    /// blocks and should not be called from async code directly. Returns how many blocks changed
+    pub fn edit_region(
+        &self,
+        region: &Region,
+        mut edit: impl FnMut(BlockId) -> Option<BlockId>,
+    ) -> usize {
+        let mut changed = 0;
+        for chunk in region.chunks() {
+            self.level.load_chunk(chunk);
+            let blocks = self
+                .level
+                .edit_blocks(chunk, region.blocks_in(chunk), |_, old| edit(old));
+            changed += blocks.len();
+            self.send_changed_blocks(&blocks);
+        }
+        changed
+    }
+
+    /// Sets many blocks at once, with the same batching as `edit_region`. Returns how many blocks
+    /// changed
+    pub fn set_blocks(&self, blocks: impl IntoIterator<Item = (WorldPosition, BlockId)>) -> usize {
+        let mut by_chunk: HashMap<Vector2<i32>, HashMap<Vector3<i32>, BlockId>> = HashMap::new();
+        for (WorldPosition(position), block) in blocks {
+            by_chunk
+                .entry(Vector2::new(position.x >> 4, position.z >> 4))
+                .or_default()
+                .insert(position, block);
+        }
+        let mut changed = 0;
+        for (chunk, blocks) in by_chunk {
+            self.level.load_chunk(chunk);
+            let changed_blocks =
+                self.level
+                    .edit_blocks(chunk, blocks.keys().copied(), |position, _| {
+                        blocks.get(&position).copied()
+                    });
+            changed += changed_blocks.len();
+            self.send_changed_blocks(&changed_blocks);
+        }
+        changed
+    }
+
+    /// Shows changed blocks to all players, with one packet for every chunk section
+    fn send_changed_blocks(&self, blocks: &[(Vector3<i32>, BlockId)]) {
+        let mut sections: HashMap<Vector3<i32>, Vec<(Vector3<i32>, i32)>> = HashMap::new();
+        for (position, block) in blocks {
+            sections
+                .entry(Vector3::new(
+                    position.x >> 4,
+                    position.y >> 4,
+                    position.z >> 4,
+                ))
+                .or_default()
+                .push((*position, block.get_id_mojang_repr()));
+        }
+        for (section, blocks) in sections {
+            self.broadcast_packet_all(&CUpdateSectionBlocks::new(section, &blocks));
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use pumpkin_core::{
+    math::{boundingbox::BoundingBox, vector2::Vector2, vector3::Vector3},
+    GameMode,
+};
+
+use crate::server::Server;
+
+use super::World;
+
+/// How hard two overlapping entities push each other apart every tick. In vanilla both of them
+/// push, so each gets pushed twice
+const PUSH_STRENGTH: f64 = 0.1;
+/// Entities this close to the center of another one don't know which way to go
+const MIN_DISTANCE: f64 = 0.01;
+
+/// Bounding boxes sorted into the chunks they overlap, so looking for the ones which intersect a
+/// box only checks the entities around it instead of all of them
+pub(super) struct EntityGrid {
+    boxes: Vec<BoundingBox>,
+    chunks: HashMap<Vector2<i32>, Vec<usize>>,
+}
+
+/// The chunks a bounding box overlaps, mostly just one
+fn chunks_of(bounding_box: &BoundingBox) -> impl Iterator<Item = Vector2<i32>> {
+    let (min_x, max_x) = (
+        (bounding_box.min_x.floor() as i32) >> 4,
+        (bounding_box.max_x.floor() as i32) >> 4,
+    );
+    let (min_z, max_z) = (
+        (bounding_box.min_z.floor() as i32) >> 4,
+        (bounding_box.max_z.floor() as i32) >> 4,
+    );
+    (min_x..=max_x).flat_map(move |x| (min_z..=max_z).map(move |z| Vector2::new(x, z)))
+}
+
+impl EntityGrid {
+    pub fn new(boxes: Vec<BoundingBox>) -> Self {
+        let mut chunks: HashMap<_, Vec<usize>> = HashMap::new();
+        for (index, bounding_box) in boxes.iter().enumerate() {
+            for chunk in chunks_of(bounding_box) {
+                chunks.entry(chunk).or_default().push(index);
+            }
+        }
+        Self { boxes, chunks }
+    }
+
+    /// The indices of the other boxes which intersect the one at `index`, every one once
+    pub fn intersecting(&self, index: usize) -> Vec<usize> {
+        let bounding_box = &self.boxes[index];
+        let mut found: Vec<usize> = chunks_of(bounding_box)
+            .filter_map(|chunk| self.chunks.get(&chunk))
+            .flatten()
+            .copied()
+            .filter(|other| *other != index && self.boxes[*other].intersects(bounding_box))
+            .collect();
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+}
+
+/// How much an entity at `from` pushes one at `to` away, sideways only. Like in vanilla the push
+/// gets stronger the closer they are
+pub(super) fn push(from: Vector3<f64>, to: Vector3<f64>) -> Option<Vector3<f64>> {
+    let (x, z) = (to.x - from.x, to.z - from.z);
+    let distance = x.abs().max(z.abs());
+    if distance < MIN_DISTANCE {
+        return None;
+    }
+    let root = distance.sqrt();
+    let scale = (1.0 / root).min(1.0) / root * PUSH_STRENGTH;
+    Some(Vector3::new(x * scale, 0.0, z * scale))
+}
+
+impl World {
+    /// The players mobs bump into, with their bounding boxes. Players in a team whose collision
+    /// rule leaves other teams alone don't push mobs
+    pub(super) fn pushing_players(&self, server: &Server) -> Vec<(BoundingBox, Vector3<f64>)> {
+        self.current_players
+            .lock()
+            .values()
+            .filter(|player| {
+                player.gamemode.load() != GameMode::Spectator
+                    && player.living_entity.health.load() > 0.0
+                    && !player.is_sleeping()
+                    && server.scoreboard.pushes_mobs(player)
+            })
+            .map(|player| {
+                (
+                    player.bounding_box(),
+                    player.living_entity.entity.pos.load(),
+                )
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashSet;
+
+use pumpkin_core::{
+    math::{vector2::Vector2, vector3::Vector3},
+    GameMode,
+};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_protocol::client::play::{CPickupItem, CSpawnExperienceOrb};
+use pumpkin_world::WORLD_LOWEST_Y;
+
+use crate::{client::Client, server::Server};
+
+use super::{
+    is_simulated,
+    physics::{self, Body},
+    tracking::Tracked,
+    World,
+};
+
+/// Orbs fly towards players closer than this
+const ATTRACTION_RANGE: f64 = 8.0;
+/// Players closer than this to an orb collect it
+const PICKUP_RANGE: f64 = 1.0;
+/// Orbs which were not collected after this many ticks are gone, like in vanilla
+const MAX_AGE: u32 = 6000;
+/// Orbs this far below the world are gone
+const VOID_DEPTH: f64 = 64.0;
+/// The values orbs are split into, clients show bigger orbs for higher values
+const ORB_SIZES: [i32; 11] = [2477, 1237, 617, 307, 149, 73, 37, 17, 7, 3, 1];
+
+/// Experience lying around until a player collects it
+pub struct ExperienceOrb {
+    entity_id: EntityId,
+    body: Body,
+    value: i32,
+    /// Ticks since it was dropped
+    age: u32,
+}
+
+impl ExperienceOrb {
+    fn spawn_packet(&self) -> CSpawnExperienceOrb {
+        CSpawnExperienceOrb::new(
+            self.entity_id.into(),
+            self.body.synced_position().x,
+            self.body.synced_position().y,
+            self.body.synced_position().z,
+            self.value.min(i16::MAX.into()) as i16,
+        )
+    }
+}
+
+/// The biggest orb value which fits into `amount`
+fn orb_size(amount: i32) -> i32 {
+    ORB_SIZES
+        .iter()
+        .copied()
+        .find(|size| *size <= amount)
+        .unwrap_or(1)
+}
+
+impl World {
+    /// Drops experience at a position, it is split into orbs with the next tick
+    pub fn drop_experience(&self, position: Vector3<f64>, amount: i32) {
+        if amount > 0 {
+            self.experience_drops.lock().push((position, amount));
+        }
+    }
+
+    /// Spawns the dropped experience, lets orbs fly to close players and gives it to the player
+    /// who collects them. Orbs outside of the simulated chunks wait
+    pub(super) fn tick_experience_orbs(&self, server: &Server, simulated: &HashSet<Vector2<i32>>) {
+        let drops = std::mem::take(&mut *self.experience_drops.lock());
+        for (position, mut amount) in drops {
+            while amount > 0 {
+                let value = orb_size(amount);
+                amount -= value;
+                // Orbs spread out a little, like in vanilla
+                let velocity = Vector3::new(
+                    (rand::random::<f64>() * 0.2 - 0.1) * 2.0,
+                    rand::random::<f64>() * 0.2 * 2.0,
+                    (rand::random::<f64>() * 0.2 - 0.1) * 2.0,
+                );
+                let orb = ExperienceOrb {
+                    entity_id: server.new_entity_id(),
+                    body: Body::new(position, velocity),
+                    value,
+                    age: 0,
+                };
+                self.experience_orbs.lock().push(orb);
+            }
+        }
+
+        let mut orbs = std::mem::take(&mut *self.experience_orbs.lock());
+        orbs.retain_mut(|orb| {
+            if !is_simulated(simulated, orb.body.position) {
+                return true;
+            }
+            orb.age += 1;
+            if orb.age > MAX_AGE || orb.body.position.y < f64::from(WORLD_LOWEST_Y) - VOID_DEPTH {
+                return false;
+            }
+            if self.collect_orb(orb) {
+                return false;
+            }
+            if self.step_physics(&mut orb.body, &physics::EXPERIENCE_ORB) {
+                self.sync_body(orb.entity_id, &mut orb.body, &physics::EXPERIENCE_ORB);
+            }
+            true
+        });
+        self.experience_orbs.lock().append(&mut orbs);
+    }
+
+    /// Pulls the orb towards the closest player and gives it to them once they touch it.
+    /// Returns whether the orb was collected
+    fn collect_orb(&self, orb: &mut ExperienceOrb) -> bool {
+        let closest = self
+            .current_players
+            .lock()
+            .values()
+            .filter(|player| {
+                player.gamemode.load() != GameMode::Spectator
+                    && player.living_entity.health.load() > 0.0
+            })
+            .map(|player| {
+                let pos = player.living_entity.entity.pos.load();
+                // Orbs fly towards the eyes, like in vanilla
+                let eyes = pos.add(&Vector3::new(
+                    0.0,
+                    f64::from(player.living_entity.entity.standing_eye_height) / 2.0,
+                    0.0,
+                ));
+                (eyes.sub(&orb.body.position), player.clone())
+            })
+            .min_by(|(a, _), (b, _)| a.length().total_cmp(&b.length()));
+        let Some((direction, player)) = closest else {
+            return false;
+        };
+        let distance = direction.length();
+        if distance < PICKUP_RANGE && player.try_collect_experience() {
+            // The players who see the orb see it fly into the player, it is removed afterwards
+            self.broadcast_tracked(
+                orb.entity_id,
+                &CPickupItem::new(orb.entity_id.into(), player.entity_id().into(), 1.into()),
+            );
+            player.add_experience_points(orb.value);
+            return true;
+        }
+        if distance < ATTRACTION_RANGE && distance > 0.0 {
+            let pull = 1.0 - distance / ATTRACTION_RANGE;
+            orb.body.velocity = orb
+                .body
+                .velocity
+                .add(&(direction.normalize() * (pull * pull * 0.1)));
+        }
+        false
+    }
+
+    /// Adds the experience orbs to the entities players may see
+    pub(super) fn track_experience_orbs(&self, tracked: &mut Vec<Tracked>) {
+        tracked.extend(self.experience_orbs.lock().iter().map(|orb| Tracked {
+            entity_id: orb.entity_id,
+            entity_type: EntityType::ExperienceOrb,
+            position: orb.body.position,
+        }));
+    }
+
+    /// Shows the experience orbs among `entity_ids` to a client which just started to see them
+    pub(super) fn send_experience_orbs(&self, client: &Client, entity_ids: &HashSet<EntityId>) {
+        for orb in self
+            .experience_orbs
+            .lock()
+            .iter()
+            .filter(|orb| entity_ids.contains(&orb.entity_id))
+        {
+            client.send_packet(&orb.spawn_packet());
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::{HashMap, HashSet};
+
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_core::{
+    math::{boundingbox::BoundingBox, position::WorldPosition, vector3::Vector3},
+    GameMode,
+};
+use pumpkin_protocol::client::play::CExplosion;
+use pumpkin_world::{block::BlockId, game_rules};
+use rand::Rng;
+
+use crate::{entity::damage::DamageSource, server::Server};
+
+use super::World;
+
+/// Rays go from the center to this many points on every edge of a cube around it, like in vanilla
+const RAYS_PER_EDGE: i32 = 16;
+/// How far apart the points are at which a ray checks for blocks
+const RAY_STEP: f64 = 0.3;
+/// How far apart the points are at which exposure checks look for blocks in the way
+const EXPOSURE_STEP: f64 = 0.2;
+
+fn block_at(point: Vector3<f64>) -> WorldPosition {
+    WorldPosition(Vector3::new(
+        point.x.floor() as i32,
+        point.y.floor() as i32,
+        point.z.floor() as i32,
+    ))
+}
+
+impl World {
+    /// Lets an explosion go off, destroying blocks around it and hurting and pushing away the
+    /// players close to it. `power` is 4 for TNT, like in vanilla. Explosions which may not
+    /// `break_blocks`, like those of creepers without `mobGriefing`, only hurt players
+    pub fn explode(&self, server: &Server, center: Vector3<f64>, power: f32, break_blocks: bool) {
+        let destroy_blocks = break_blocks && ADVANCED_CONFIG.get().explosions.block_damage;
+        let blocks = if destroy_blocks {
+            self.exploded_blocks(center, power)
+        } else {
+            Vec::new()
+        };
+        let knockbacks = self.hurt_players(center, power);
+
+        let origin = block_at(center).0;
+        let records: Vec<_> = blocks
+            .iter()
+            .map(|position| {
+                let offset = position.0.sub(&origin);
+                (offset.x as i8, offset.y as i8, offset.z as i8)
+            })
+            .collect();
+        for (id, player) in self.current_players.lock().iter() {
+            let knockback = knockbacks.get(id).map_or((0.0, 0.0, 0.0), |knockback| {
+                (knockback.x as f32, knockback.y as f32, knockback.z as f32)
+            });
+            player.client.send_packet(&CExplosion::new(
+                center.x,
+                center.y,
+                center.z,
+                power,
+                &records,
+                knockback,
+                destroy_blocks,
+            ));
+        }
+
+        let mut rng = rand::thread_rng();
+        let drop_items = self.game_rule_bool(game_rules::DO_TILE_DROPS);
+        for position in blocks {
+            let Some(block) = self.level.get_block(&position) else {
+                continue;
+            };
+            if block.registry_state().map(|(registry_id, _)| registry_id) == Some("minecraft:tnt") {
+                // TNT caught in an explosion goes off a lot sooner than lit TNT, like in vanilla
+                self.prime_tnt(server, &position, rng.gen_range(10..30));
+            } else {
+                // Bigger explosions destroy more of the items, like in vanilla
+                if drop_items && rng.gen::<f32>() < 1.0 / power {
+                    for item in block.drops_ignoring_tool() {
+                        self.drop_block_item(&position, item);
+                    }
+                }
+                self.set_block(&position, BlockId::default());
+            }
+        }
+    }
+
+    /// The blocks an explosion destroys. Rays go out from the center and lose strength with
+    /// distance and with every block they pass, depending on how well the block resists
+    fn exploded_blocks(&self, center: Vector3<f64>, power: f32) -> Vec<WorldPosition> {
+        let mut rng = rand::thread_rng();
+        let mut blocks = HashSet::new();
+        let last = RAYS_PER_EDGE - 1;
+        for x in 0..RAYS_PER_EDGE {
+            for y in 0..RAYS_PER_EDGE {
+                for z in 0..RAYS_PER_EDGE {
+                    // Only the points on the surface of the cube
+                    if ![x, y, z].iter().any(|axis| *axis == 0 || *axis == last) {
+                        continue;
+                    }
+                    let direction = Vector3::new(
+                        f64::from(x) / f64::from(last) * 2.0 - 1.0,
+                        f64::from(y) / f64::from(last) * 2.0 - 1.0,
+                        f64::from(z) / f64::from(last) * 2.0 - 1.0,
+                    )
+                    .normalize();
+                    let mut strength = power * rng.gen_range(0.7..1.3);
+                    let mut point = center;
+                    while strength > 0.0 {
+                        let position = block_at(point);
+                        // Explosions don't reach into chunks which are not loaded
+                        let Some(block) = self.level.get_block(&position) else {
+                            break;
+                        };
+                        if !block.is_air() {
+                            strength -= (block.blast_resistance() + 0.3) * 0.3;
+                            if strength > 0.0 {
+                                blocks.insert(position.0);
+                            }
+                        }
+                        point = point.add(&(direction * RAY_STEP));
+                        strength -= (RAY_STEP * 0.75) as f32;
+                    }
+                }
+            }
+        }
+        blocks.into_iter().map(WorldPosition).collect()
+    }
+
+    /// Hurts the players close to an explosion, less the further away and the more covered they
+    /// are. Returns how far each player gets pushed away, by their client id
+    fn hurt_players(&self, center: Vector3<f64>, power: f32) -> HashMap<usize, Vector3<f64>> {
+        let reach = f64::from(power) * 2.0;
+        let players: Vec<_> = self
+            .current_players
+            .lock()
+            .iter()
+            .map(|(id, player)| (*id, player.clone()))
+            .collect();
+        let mut knockbacks = HashMap::new();
+        for (id, player) in players {
+            if player.gamemode.load() == GameMode::Spectator {
+                continue;
+            }
+            let entity = &player.living_entity.entity;
+            let position = entity.pos.load();
+            let distance = position.sub(&center).length() / reach;
+            if distance > 1.0 {
+                continue;
+            }
+            let eyes = position.add(&Vector3::new(
+                0.0,
+                f64::from(entity.standing_eye_height),
+                0.0,
+            ));
+            let direction = eyes.sub(&center);
+            if direction.length() == 0.0 {
+                continue;
+            }
+            let impact = (1.0 - distance) * self.exposure(center, &player.bounding_box());
+            let damage = (impact * impact + impact) / 2.0 * 7.0 * reach + 1.0;
+            player.damage(damage as f32, DamageSource::Explosion);
+            knockbacks.insert(id, direction.normalize() * impact);
+        }
+        knockbacks
+    }
+
+    /// How much of a bounding box an explosion reaches, from 0 for fully covered to 1.
+    /// Checks whether blocks are in the way of points spread over the box, like in vanilla
+    fn exposure(&self, center: Vector3<f64>, bounding_box: &BoundingBox) -> f64 {
+        let step_x = 1.0 / ((bounding_box.max_x - bounding_box.min_x) * 2.0 + 1.0);
+        let step_y = 1.0 / ((bounding_box.max_y - bounding_box.min_y) * 2.0 + 1.0);
+        let step_z = 1.0 / ((bounding_box.max_z - bounding_box.min_z) * 2.0 + 1.0);
+        // Centers the points on the box
+        let offset_x = (1.0 - (1.0 / step_x).floor() * step_x) / 2.0;
+        let offset_z = (1.0 - (1.0 / step_z).floor() * step_z) / 2.0;
+
+        let mut reached = 0;
+        let mut points = 0;
+        let mut x = 0.0;
+        while x <= 1.0 {
+            let mut y = 0.0;
+            while y <= 1.0 {
+                let mut z = 0.0;
+                while z <= 1.0 {
+                    let point = Vector3::new(
+                        bounding_box.min_x
+                            + (bounding_box.max_x - bounding_box.min_x) * x
+                            + offset_x,
+                        bounding_box.min_y + (bounding_box.max_y - bounding_box.min_y) * y,
+                        bounding_box.min_z
+                            + (bounding_box.max_z - bounding_box.min_z) * z
+                            + offset_z,
+                    );
+                    if !self.is_blocked(point, center) {
+                        reached += 1;
+                    }
+                    points += 1;
+                    z += step_z;
+                }
+                y += step_y;
+            }
+            x += step_x;
+        }
+        f64::from(reached) / f64::from(points.max(1))
+    }
+
+    /// Whether a block entities collide with is between two points
+    fn is_blocked(&self, from: Vector3<f64>, to: Vector3<f64>) -> bool {
+        let difference = to.sub(&from);
+        let steps = (difference.length() / EXPOSURE_STEP).ceil() as i32;
+        let mut last = None;
+        for step in 0..steps {
+            let position = block_at(from.add(&(difference * (f64::from(step) / f64::from(steps)))));
+            if last == Some(position.0) {
+                continue;
+            }
+            last = Some(position.0);
+            if self
+                .level
+                .get_block(&position)
+                .is_some_and(|block| block.has_collision())
+            {
+                return true;
+            }
+        }
+        false
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::HashSet;
+
+use pumpkin_core::math::{position::WorldPosition, vector2::Vector2, vector3::Vector3};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_protocol::client::play::CSpawnEntity;
+use pumpkin_world::{block::BlockId, game_rules, WORLD_LOWEST_Y};
+use uuid::Uuid;
+
+use crate::{client::Client, server::Server};
+
+use super::{
+    is_simulated,
+    physics::{self, Body},
+    tracking::Tracked,
+    World,
+};
+
+/// Falling blocks which didn't land after this many ticks are dropped, like in vanilla
+const MAX_FALL_TICKS: u32 = 600;
+/// Falling blocks this far below the world are gone
+const VOID_DEPTH: f64 = 64.0;
+
+/// A block falling down because there was nothing below it, e.g. sand or an anvil
+pub struct FallingBlock {
+    entity_id: EntityId,
+    uuid: Uuid,
+    block: BlockId,
+    body: Body,
+    /// Ticks since it started falling
+    age: u32,
+}
+
+impl FallingBlock {
+    fn spawn_packet(&self) -> CSpawnEntity {
+        CSpawnEntity::new(
+            self.entity_id.into(),
+            self.uuid,
+            (EntityType::FallingBlock as i32).into(),
+            self.body.synced_position().x,
+            self.body.synced_position().y,
+            self.body.synced_position().z,
+            0.0,
+            0.0,
+            0.0,
+            // Clients show the block state given here
+            self.block.get_id_mojang_repr().into(),
+            self.body.velocity.x as f32,
+            self.body.velocity.y as f32,
+            self.body.velocity.z as f32,
+        )
+    }
+}
+
+/// What happened to a falling block during a tick
+enum Fall {
+    Falling,
+    /// It hit the ground, the block belongs at the position
+    Landed(WorldPosition),
+    /// It fell for too long or into the void
+    Gone,
+}
+
+impl World {
+    /// Checks the block and the block above later, they may have to fall
+    pub(super) fn queue_gravity_check(&self, position: &WorldPosition) {
+        let mut checks = self.gravity_checks.lock();
+        checks.insert(position.0);
+        checks.insert(position.0.add(&Vector3::new(0, 1, 0)));
+    }
+
+    /// Turns blocks which lost their support into falling blocks and moves the falling ones.
+    /// Falling blocks outside of the simulated chunks wait
+    pub(super) fn tick_falling_blocks(&self, server: &Server, simulated: &HashSet<Vector2<i32>>) {
+        let checks = std::mem::take(&mut *self.gravity_checks.lock());
+        for position in checks {
+            self.start_falling(server, WorldPosition(position));
+        }
+
+        let mut falling = std::mem::take(&mut *self.falling_blocks.lock());
+        falling.retain_mut(|block| {
+            if !is_simulated(simulated, block.body.position) {
+                return true;
+            }
+            match self.fall(block) {
+                Fall::Falling => {
+                    // Clients simulate the fall as well, this only keeps them in sync
+                    self.sync_body(block.entity_id, &mut block.body, &physics::FALLING_BLOCK);
+                    true
+                }
+                Fall::Landed(position) => {
+                    if self
+                        .level
+                        .get_block(&position)
+                        .is_some_and(|old| old.can_fall_through())
+                    {
+                        self.set_block(&position, block.block);
+                    } else if self.game_rule_bool(game_rules::DO_ENTITY_DROPS) {
+                        // A block which can't be placed drops as an item
+                        for item in block.block.drops_ignoring_tool() {
+                            self.drop_block_item(&position, item);
+                        }
+                    }
+                    false
+                }
+                Fall::Gone => false,
+            }
+        });
+        self.falling_blocks.lock().append(&mut falling);
+    }
+
+    /// Lets the block fall if it has gravity and nothing below it
+    fn start_falling(&self, server: &Server, position: WorldPosition) {
+        let Some(block) = self.level.get_block(&position) else {
+            return;
+        };
+        if !block.has_gravity() || position.0.y <= WORLD_LOWEST_Y as i32 {
+            return;
+        }
+        let below = WorldPosition(position.0.add(&Vector3::new(0, -1, 0)));
+        if !self
+            .level
+            .get_block(&below)
+            .is_some_and(|below| below.can_fall_through())
+        {
+            return;
+        }
+        let falling = FallingBlock {
+            entity_id: server.new_entity_id(),
+            uuid: Uuid::new_v4(),
+            block,
+            body: Body::new(
+                Vector3::new(
+                    position.0.x as f64 + 0.5,
+                    position.0.y as f64,
+                    position.0.z as f64 + 0.5,
+                ),
+                Vector3::new(0.0, 0.0, 0.0),
+            ),
+            age: 0,
+        };
+        self.set_block(&position, BlockId::default());
+        self.falling_blocks.lock().push(falling);
+    }
+
+    /// Moves a falling block by one tick, it lands once it stands on a block
+    fn fall(&self, block: &mut FallingBlock) -> Fall {
+        block.age += 1;
+        let body = &mut block.body;
+        if block.age > MAX_FALL_TICKS || body.position.y < WORLD_LOWEST_Y as f64 - VOID_DEPTH {
+            return Fall::Gone;
+        }
+        if !self.step_physics(body, &physics::FALLING_BLOCK) || !body.on_ground {
+            return Fall::Falling;
+        }
+        // The block belongs where the center of the falling block is
+        let center = body.position.add(&Vector3::new(0.0, 0.5, 0.0));
+        Fall::Landed(WorldPosition(Vector3::new(
+            center.x.floor() as i32,
+            center.y.floor() as i32,
+            center.z.floor() as i32,
+        )))
+    }
+
+    /// Adds the falling blocks to the entities players may see
+    pub(super) fn track_falling_blocks(&self, tracked: &mut Vec<Tracked>) {
+        tracked.extend(self.falling_blocks.lock().iter().map(|block| Tracked {
+            entity_id: block.entity_id,
+            entity_type: EntityType::FallingBlock,
+            position: block.body.position,
+        }));
+    }
+
+    /// Shows the falling blocks among `entity_ids` to a client which just started to see them
+    pub(super) fn send_falling_blocks(&self, client: &Client, entity_ids: &HashSet<EntityId>) {
+        for block in self
+            .falling_blocks
+            .lock()
+            .iter()
+            .filter(|block| entity_ids.contains(&block.entity_id))
+        {
+            client.send_packet(&block.spawn_packet());
+        }
+    }
+}
@@ -0,0 +1,380 @@
+use std::{collections::HashSet, sync::atomic::Ordering};
+
+use pumpkin_core::math::{position::WorldPosition, vector2::Vector2};
+use pumpkin_inventory::{window_property, Furnace, OpenContainer, WindowType};
+use pumpkin_world::{
+    block::BlockEntity,
+    global_registry::{self, ITEM_REGISTRY},
+    item::ItemStack,
+    recipe,
+};
+use rand::Rng;
+
+use crate::{
+    entity::player::Player,
+    server::{recipes::result_stack, Server},
+};
+
+use super::{block_entity::NEXT_CONTAINER_ID, World};
+
+/// Block entities which cook items, their ids are the ids of their menus too
+pub const FURNACES: [&str; 3] = [
+    "minecraft:furnace",
+    "minecraft:blast_furnace",
+    "minecraft:smoker",
+];
+
+/// How far a furnace got, stored in its block entity like in vanilla
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct FurnaceState {
+    /// How many more ticks the burning fuel lasts
+    lit_time: i16,
+    /// How many ticks the burning fuel lasted in total, the flame in the window shrinks from it
+    lit_duration: i16,
+    cooking_progress: i16,
+    cooking_total_time: i16,
+}
+
+impl FurnaceState {
+    fn read(entity: &BlockEntity) -> Self {
+        Self {
+            lit_time: entity.short("BurnTime"),
+            // Vanilla only stores this since 1.21.2, older furnaces show the flame as full
+            lit_duration: match entity.short("lit_total_time") {
+                0 => entity.short("BurnTime"),
+                duration => duration,
+            },
+            cooking_progress: entity.short("CookTime"),
+            cooking_total_time: entity.short("CookTimeTotal"),
+        }
+    }
+
+    fn write(&self, entity: &mut BlockEntity) {
+        entity.set_short("BurnTime", self.lit_time);
+        entity.set_short("lit_total_time", self.lit_duration);
+        entity.set_short("CookTime", self.cooking_progress);
+        entity.set_short("CookTimeTotal", self.cooking_total_time);
+    }
+
+    fn is_lit(&self) -> bool {
+        self.lit_time > 0
+    }
+
+    /// The values of the progress bars in the window of the furnace
+    fn properties(&self) -> [(window_property::Furnace, i16); 4] {
+        [
+            (window_property::Furnace::FireIcon, self.lit_time),
+            (
+                window_property::Furnace::MaximumFuelBurnTime,
+                self.lit_duration,
+            ),
+            (
+                window_property::Furnace::ProgressArrow,
+                self.cooking_progress,
+            ),
+            (
+                window_property::Furnace::MaximumProgress,
+                self.cooking_total_time,
+            ),
+        ]
+    }
+
+    /// Burns the fuel and cooks the input of the furnace `block` for one tick like vanilla.
+    /// Returns the recipe if an item finished cooking, and whether the items changed
+    fn tick(
+        &mut self,
+        server: &Server,
+        block: &str,
+        items: &mut [Option<ItemStack>; 3],
+    ) -> (Option<String>, bool) {
+        if self.is_lit() {
+            self.lit_time -= 1;
+        }
+        let found = items[Furnace::INPUT_SLOT]
+            .as_ref()
+            .and_then(|input| server.recipes.find_cooking(block, input));
+        let result = found
+            .and_then(|(_, recipe)| recipe.result())
+            .and_then(result_stack)
+            .filter(|result| fits(items[Furnace::RESULT_SLOT].as_ref(), result));
+        let (Some((id, cooking)), Some(result)) = (found, result) else {
+            // Without anything to cook the progress goes back
+            self.cooking_progress = if self.is_lit() {
+                0
+            } else {
+                (self.cooking_progress - 2).max(0)
+            };
+            return (None, false);
+        };
+
+        let mut items_changed = false;
+        if !self.is_lit() {
+            if let Some(ticks) = items[Furnace::FUEL_SLOT]
+                .as_ref()
+                .and_then(|fuel| global_registry::find_minecraft_id(ITEM_REGISTRY, fuel.item_id))
+                .and_then(recipe::fuel)
+            {
+                self.lit_time = i16::try_from(ticks).unwrap_or(i16::MAX);
+                self.lit_duration = self.lit_time;
+                take_fuel(&mut items[Furnace::FUEL_SLOT]);
+                items_changed = true;
+            }
+        }
+        if !self.is_lit() {
+            self.cooking_progress = (self.cooking_progress - 2).max(0);
+            return (None, items_changed);
+        }
+
+        self.cooking_total_time = cooking
+            .cooking_time()
+            .map_or(i16::MAX, |time| i16::try_from(time).unwrap_or(i16::MAX));
+        self.cooking_progress += 1;
+        if self.cooking_progress < self.cooking_total_time {
+            return (None, items_changed);
+        }
+        self.cooking_progress = 0;
+        match &mut items[Furnace::RESULT_SLOT] {
+            Some(output) => output.item_count += result.item_count,
+            output => *output = Some(result),
+        }
+        if let Some(input) = &mut items[Furnace::INPUT_SLOT] {
+            input.item_count -= 1;
+            if input.item_count == 0 {
+                items[Furnace::INPUT_SLOT] = None;
+            }
+        }
+        (Some(id.to_string()), true)
+    }
+}
+
+/// Whether a cooked item fits onto the items in the result slot
+fn fits(output: Option<&ItemStack>, result: &ItemStack) -> bool {
+    output.map_or(true, |output| {
+        output.item_id == result.item_id
+            && output.item_count + result.item_count <= output.max_stack_size()
+    })
+}
+
+/// Burns one item of the fuel, a bucket of lava leaves its bucket behind
+fn take_fuel(fuel: &mut Option<ItemStack>) {
+    let Some(item) = fuel else {
+        return;
+    };
+    item.item_count -= 1;
+    if item.item_count == 0 {
+        *fuel = global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id)
+            .and_then(recipe::remainder)
+            .and_then(|remainder| global_registry::find_protocol_id(ITEM_REGISTRY, remainder))
+            .map(|item_id| ItemStack::new(1, item_id));
+    }
+}
+
+fn window_type(block: &str) -> &'static WindowType {
+    match block {
+        "minecraft:blast_furnace" => &WindowType::BlastFurnace,
+        "minecraft:smoker" => &WindowType::Smoker,
+        _ => &WindowType::Furnace,
+    }
+}
+
+/// The id of the furnace-like block which has the window, its recipes are cooked in it
+pub fn block(window_type: &WindowType) -> &'static str {
+    match window_type {
+        WindowType::BlastFurnace => "minecraft:blast_furnace",
+        WindowType::Smoker => "minecraft:smoker",
+        _ => "minecraft:furnace",
+    }
+}
+
+impl World {
+    /// Burns fuel and cooks items in the furnaces, blast furnaces and smokers of the simulated
+    /// chunks
+    pub(super) fn tick_furnaces(&self, server: &Server, simulated: &HashSet<Vector2<i32>>) {
+        for chunk in simulated {
+            let furnaces = self
+                .level
+                .block_entities(*chunk, |entity| FURNACES.contains(&entity.id.as_str()));
+            for entity in furnaces {
+                self.tick_furnace(server, entity);
+            }
+        }
+    }
+
+    fn tick_furnace(&self, server: &Server, mut entity: BlockEntity) {
+        let before = FurnaceState::read(&entity);
+        // While players look into the furnace its items are in their window
+        let open = self
+            .open_block_entities
+            .lock()
+            .get(&entity.position)
+            .and_then(|container_id| {
+                let open_containers = server.open_containers.read();
+                let open_container = open_containers.get(container_id)?;
+                Some((
+                    open_container.container().clone(),
+                    open_container.all_player_ids(),
+                ))
+            });
+        let mut container = open.as_ref().map(|(container, _)| container.lock());
+        let mut items = [None; 3];
+        match &container {
+            Some(container) => {
+                for (slot, item) in items.iter_mut().zip(container.all_slots_ref()) {
+                    *slot = item.copied();
+                }
+            }
+            None => {
+                for (slot, item) in entity.items() {
+                    if let Some(slot) = items.get_mut(slot) {
+                        *slot = Some(item);
+                    }
+                }
+            }
+        }
+
+        let mut state = before;
+        let (cooked, items_changed) = state.tick(server, &entity.id, &mut items);
+        if state == before && !items_changed {
+            return;
+        }
+        if items_changed {
+            if let Some(container) = &mut container {
+                for (slot, item) in container.all_slots().into_iter().zip(items) {
+                    *slot = item;
+                }
+            }
+            entity.set_items(&items);
+        }
+        if let Some(recipe) = cooked {
+            let mut recipes_used = entity.recipes_used();
+            *recipes_used.entry(recipe).or_default() += 1;
+            entity.set_recipes_used(recipes_used);
+        }
+        state.write(&mut entity);
+        let position = WorldPosition(entity.position);
+        self.level.set_block_entity(entity);
+
+        if before.is_lit() != state.is_lit() {
+            let lit = if state.is_lit() { "true" } else { "false" };
+            if let Some(block) = self
+                .level
+                .get_block(&position)
+                .and_then(|block| block.with_property("lit", lit))
+            {
+                self.set_block(&position, block);
+            }
+        }
+
+        let (Some(container), Some((_, viewers))) = (&mut container, &open) else {
+            return;
+        };
+        for viewer in viewers {
+            let Some(player) = self.get_player_by_entityid(*viewer) else {
+                continue;
+            };
+            if items_changed {
+                player.set_container_content(Some(&mut **container));
+            }
+            for ((property, value), (_, old)) in
+                state.properties().into_iter().zip(before.properties())
+            {
+                if value != old {
+                    player.set_container_property(window_property::WindowProperty::new(
+                        property, value,
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Gives a player the experience of everything a furnace cooked since someone last took it.
+    /// Like in vanilla, the fraction of a point is the chance of getting one more
+    pub fn take_furnace_experience(&self, server: &Server, container_id: u64, player: &Player) {
+        let Some(position) = self
+            .open_block_entities
+            .lock()
+            .iter()
+            .find(|(_, id)| **id == container_id)
+            .map(|(position, _)| *position)
+        else {
+            return;
+        };
+        let Some(mut entity) = self
+            .level
+            .get_block_entity(&WorldPosition(position))
+            .filter(|entity| FURNACES.contains(&entity.id.as_str()))
+        else {
+            return;
+        };
+        let recipes_used = entity.recipes_used();
+        if recipes_used.is_empty() {
+            return;
+        }
+        let mut rng = rand::thread_rng();
+        let mut experience = 0;
+        for (id, count) in recipes_used {
+            let Some(recipe) = server
+                .recipes
+                .get(&id)
+                .and_then(|recipe| recipe.cooking(&entity.id))
+            else {
+                continue;
+            };
+            let points = recipe.experience * count as f32;
+            let whole = points.floor();
+            experience += whole as i32 + i32::from(rng.gen::<f32>() < points - whole);
+        }
+        entity.set_recipes_used(Default::default());
+        self.level.set_block_entity(entity);
+        self.drop_experience(player.living_entity.entity.pos.load(), experience);
+    }
+}
+
+/// Opens the furnace, blast furnace or smoker at `position` for the player.
+/// Returns false if there is none
+pub fn open_furnace(
+    server: &Server,
+    world: &World,
+    player: &Player,
+    position: &WorldPosition,
+) -> bool {
+    let Some(entity) = world
+        .level
+        .get_block_entity(position)
+        .filter(|entity| FURNACES.contains(&entity.id.as_str()))
+    else {
+        return false;
+    };
+    player.close_container(server);
+
+    let player_id = player.entity_id();
+    // Players looking into the same furnace share its container, like with chests
+    let container_id = {
+        let mut open_block_entities = world.open_block_entities.lock();
+        let mut open_containers = server.open_containers.write();
+        let container_id = *open_block_entities
+            .entry(position.0)
+            .or_insert_with(|| NEXT_CONTAINER_ID.fetch_add(1, Ordering::Relaxed));
+        let container = open_containers.entry(container_id).or_insert_with(|| {
+            let mut items = [None; 3];
+            for (slot, item) in entity.items() {
+                if let Some(slot) = items.get_mut(slot) {
+                    *slot = Some(item);
+                }
+            }
+            OpenContainer::new(
+                player_id,
+                Box::new(Furnace::with_items(window_type(&entity.id), items)),
+            )
+        });
+        container.add_player(player_id);
+        container_id
+    };
+
+    player.open_container.store(Some(container_id));
+    player.open_container(server, &entity.id);
+    for (property, value) in FurnaceState::read(&entity).properties() {
+        player.set_container_property(window_property::WindowProperty::new(property, value));
+    }
+    true
+}
@@ -0,0 +1,293 @@
+use std::{collections::HashSet, sync::Arc};
+
+use pumpkin_core::{
+    math::{position::WorldPosition, vector2::Vector2, vector3::Vector3},
+    GameMode,
+};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_protocol::{
+    client::play::{CPickupItem, CSetEntityMetadata, CSpawnEntity, Metadata},
+    slot::Slot,
+};
+use pumpkin_world::{item::ItemStack, WORLD_LOWEST_Y};
+use uuid::Uuid;
+
+use crate::{client::Client, entity::player::Player, server::Server};
+
+use super::{
+    is_simulated,
+    physics::{self, Body},
+    tracking::Tracked,
+    World,
+};
+
+/// Ticks until items dropped by blocks can be collected, like in vanilla
+const DEFAULT_PICKUP_DELAY: u32 = 10;
+/// Ticks until items thrown by players can be collected, so they don't collect them right away
+const THROW_PICKUP_DELAY: u32 = 40;
+/// Items which were not collected after this many ticks are gone, like in vanilla
+const MAX_AGE: u32 = 6000;
+/// Items this far below the world are gone
+const VOID_DEPTH: f64 = 64.0;
+/// Items look for stacks of the same item to merge with every this many ticks
+const MERGE_INTERVAL: u32 = 20;
+/// Items closer than this merge
+const MERGE_RANGE: f64 = 0.5;
+/// Players collect items this far around their bounding box, like in vanilla
+const PICKUP_RANGE: (f64, f64, f64) = (1.0, 0.5, 1.0);
+
+/// An item lying around until a player collects it
+pub struct ItemEntity {
+    entity_id: EntityId,
+    uuid: Uuid,
+    item: ItemStack,
+    body: Body,
+    /// Ticks until players can collect it
+    pickup_delay: u32,
+    /// Ticks since it was dropped
+    age: u32,
+}
+
+/// An item which was dropped this tick, it becomes an item entity with the next tick
+pub struct ItemDrop {
+    position: Vector3<f64>,
+    velocity: Vector3<f64>,
+    item: ItemStack,
+    pickup_delay: u32,
+}
+
+impl ItemEntity {
+    fn spawn_packet(&self) -> CSpawnEntity {
+        CSpawnEntity::new(
+            self.entity_id.into(),
+            self.uuid,
+            (EntityType::Item as i32).into(),
+            self.body.synced_position().x,
+            self.body.synced_position().y,
+            self.body.synced_position().z,
+            0.0,
+            0.0,
+            0.0,
+            0.into(),
+            self.body.velocity.x as f32,
+            self.body.velocity.y as f32,
+            self.body.velocity.z as f32,
+        )
+    }
+
+    /// Tells clients which item it is and how many, they show nothing without it
+    fn item_packet(&self) -> CSetEntityMetadata<Slot> {
+        CSetEntityMetadata::new(
+            self.entity_id.into(),
+            Metadata::new(8, 7.into(), Slot::from(&self.item)),
+        )
+    }
+
+    fn is_full(&self) -> bool {
+        self.item.item_count >= self.item.max_stack_size()
+    }
+}
+
+impl World {
+    /// Drops an item out of a block which was broken, it pops out of the block's center
+    pub fn drop_block_item(&self, position: &WorldPosition, item: ItemStack) {
+        let spread = || rand::random::<f64>() * 0.5 - 0.25;
+        let position = Vector3::new(
+            f64::from(position.0.x) + 0.5 + spread(),
+            f64::from(position.0.y) + 0.5 + spread() - 0.125,
+            f64::from(position.0.z) + 0.5 + spread(),
+        );
+        let velocity = Vector3::new(
+            rand::random::<f64>() * 0.2 - 0.1,
+            0.2,
+            rand::random::<f64>() * 0.2 - 0.1,
+        );
+        self.queue_item(position, velocity, item, DEFAULT_PICKUP_DELAY);
+    }
+
+    /// Drops an item where an entity was, e.g. a boat which broke
+    pub fn drop_entity_item(&self, position: Vector3<f64>, item: ItemStack) {
+        let velocity = Vector3::new(
+            rand::random::<f64>() * 0.2 - 0.1,
+            0.2,
+            rand::random::<f64>() * 0.2 - 0.1,
+        );
+        self.queue_item(position, velocity, item, DEFAULT_PICKUP_DELAY);
+    }
+
+    /// Throws an item, e.g. when players drop it out of their inventory
+    pub fn throw_item(&self, position: Vector3<f64>, velocity: Vector3<f64>, item: ItemStack) {
+        self.queue_item(position, velocity, item, THROW_PICKUP_DELAY);
+    }
+
+    fn queue_item(
+        &self,
+        position: Vector3<f64>,
+        velocity: Vector3<f64>,
+        item: ItemStack,
+        pickup_delay: u32,
+    ) {
+        if item.item_count > 0 {
+            self.item_drops.lock().push(ItemDrop {
+                position,
+                velocity,
+                item,
+                pickup_delay,
+            });
+        }
+    }
+
+    /// Spawns the dropped items, moves them, merges stacks of the same item and gives them to
+    /// the players who collect them. Items outside of the simulated chunks wait
+    pub(super) fn tick_items(&self, server: &Server, simulated: &HashSet<Vector2<i32>>) {
+        let drops = std::mem::take(&mut *self.item_drops.lock());
+        self.items
+            .lock()
+            .extend(drops.into_iter().map(|queued| ItemEntity {
+                entity_id: server.new_entity_id(),
+                uuid: Uuid::new_v4(),
+                item: queued.item,
+                body: Body::new(queued.position, queued.velocity),
+                pickup_delay: queued.pickup_delay,
+                age: 0,
+            }));
+
+        let players: Vec<Arc<Player>> = self
+            .current_players
+            .lock()
+            .values()
+            .filter(|player| {
+                player.gamemode.load() != GameMode::Spectator
+                    && player.living_entity.health.load() > 0.0
+            })
+            .cloned()
+            .collect();
+        let mut items = std::mem::take(&mut *self.items.lock());
+        items.retain_mut(|item| {
+            if !is_simulated(simulated, item.body.position) {
+                return true;
+            }
+            item.age += 1;
+            item.pickup_delay = item.pickup_delay.saturating_sub(1);
+            if item.age > MAX_AGE || item.body.position.y < f64::from(WORLD_LOWEST_Y) - VOID_DEPTH {
+                return false;
+            }
+            if self.step_physics(&mut item.body, &physics::ITEM) {
+                self.sync_body(item.entity_id, &mut item.body, &physics::ITEM);
+            }
+            if item.pickup_delay == 0 && self.collect_item(item, &players) {
+                return false;
+            }
+            true
+        });
+        self.merge_items(&mut items, simulated);
+        self.items.lock().append(&mut items);
+    }
+
+    /// Gives as much of the item as fits to the first player touching it. Returns whether all
+    /// of it was collected
+    fn collect_item(&self, item: &mut ItemEntity, players: &[Arc<Player>]) -> bool {
+        let bounding_box = item.body.bounding_box(&physics::ITEM);
+        let (x, y, z) = PICKUP_RANGE;
+        for player in players {
+            if !player
+                .bounding_box()
+                .expand(x, y, z)
+                .intersects(&bounding_box)
+            {
+                continue;
+            }
+            let mut left = Some(item.item);
+            player.inventory.lock().insert(&mut left);
+            let left = left.map_or(0, |left| left.item_count);
+            let collected = item.item.item_count - left;
+            if collected == 0 {
+                continue;
+            }
+            // The players who see the item see it fly into the player
+            self.broadcast_tracked(
+                item.entity_id,
+                &CPickupItem::new(
+                    item.entity_id.into(),
+                    player.entity_id().into(),
+                    i32::from(collected).into(),
+                ),
+            );
+            player.set_container_content(None);
+            if left == 0 {
+                return true;
+            }
+            item.item.item_count = left;
+            self.broadcast_tracked(item.entity_id, &item.item_packet());
+        }
+        false
+    }
+
+    /// Merges items of the same kind lying close to each other, the smaller stack goes into
+    /// the bigger one like in vanilla
+    fn merge_items(&self, items: &mut Vec<ItemEntity>, simulated: &HashSet<Vector2<i32>>) {
+        for i in 0..items.len() {
+            let item = &items[i];
+            if item.item.item_count == 0
+                || item.age % MERGE_INTERVAL != 0
+                || item.is_full()
+                || !is_simulated(simulated, item.body.position)
+            {
+                continue;
+            }
+            let bounding_box =
+                item.body
+                    .bounding_box(&physics::ITEM)
+                    .expand(MERGE_RANGE, 0.0, MERGE_RANGE);
+            let other = (0..items.len()).find(|j| {
+                let other = &items[*j];
+                *j != i
+                    && other.item.item_count > 0
+                    && other.item == item.item
+                    && !other.is_full()
+                    && bounding_box.intersects(&other.body.bounding_box(&physics::ITEM))
+            });
+            let Some(j) = other else {
+                continue;
+            };
+            let (into, from) = if items[i].item.item_count >= items[j].item.item_count {
+                (i, j)
+            } else {
+                (j, i)
+            };
+            let moved = (items[into].item.max_stack_size() - items[into].item.item_count)
+                .min(items[from].item.item_count);
+            items[into].item.item_count += moved;
+            items[from].item.item_count -= moved;
+            items[into].pickup_delay = items[into].pickup_delay.max(items[from].pickup_delay);
+            items[into].age = items[into].age.min(items[from].age);
+            self.broadcast_tracked(items[into].entity_id, &items[into].item_packet());
+            if items[from].item.item_count > 0 {
+                self.broadcast_tracked(items[from].entity_id, &items[from].item_packet());
+            }
+        }
+        items.retain(|item| item.item.item_count > 0);
+    }
+
+    /// Adds the items to the entities players may see
+    pub(super) fn track_items(&self, tracked: &mut Vec<Tracked>) {
+        tracked.extend(self.items.lock().iter().map(|item| Tracked {
+            entity_id: item.entity_id,
+            entity_type: EntityType::Item,
+            position: item.body.position,
+        }));
+    }
+
+    /// Shows the items among `entity_ids` to a client which just started to see them
+    pub(super) fn send_items(&self, client: &Client, entity_ids: &HashSet<EntityId>) {
+        for item in self
+            .items
+            .lock()
+            .iter()
+            .filter(|item| entity_ids.contains(&item.entity_id))
+        {
+            client.send_packet(&item.spawn_packet());
+            client.send_packet(&item.item_packet());
+        }
+    }
+}
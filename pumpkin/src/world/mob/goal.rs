@@ -0,0 +1,795 @@
+use pumpkin_config::BASIC_CONFIG;
+use pumpkin_core::{
+    math::{position::WorldPosition, vector3::Vector3},
+    Difficulty, GameMode,
+};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_protocol::client::play::{Animation, CEntityAnimation};
+use pumpkin_world::{global_registry, stats};
+use rand::Rng;
+
+use crate::{
+    entity::{damage::DamageSource, metadata::CREEPER_STATE, player::Player},
+    world::{
+        projectile::{self, Launch, Pickup, ProjectileKind},
+        World,
+    },
+};
+
+use super::{follow_range, Mob, Navigation, TICKS_PER_PATH_NODE};
+
+/// Mobs find at most this many paths per tick all together, the others try again next tick
+const MAX_PATHS_PER_TICK: usize = 16;
+/// Hostile mobs look for a target this often
+const TARGET_CHECK_INTERVAL: u32 = 10;
+/// Ticks between two attacks of a mob
+const ATTACK_INTERVAL: u32 = 20;
+/// Players are pushed away this much when a mob hits them
+const ATTACK_KNOCKBACK: f64 = 0.4;
+/// Creepers start to swell when their target is closer than this and stop once it is further
+/// away than the second range. They explode after swelling for `FUSE` ticks
+const SWELL_RANGE: f64 = 3.0;
+const STOP_SWELL_RANGE: f64 = 7.0;
+const FUSE: u32 = 30;
+pub const CREEPER_POWER: f32 = 3.0;
+/// Skeletons shoot at targets closer than this, after drawing their bow for some ticks
+const RANGED_ATTACK_RANGE: f64 = 15.0;
+const BOW_DRAW_TICKS: u32 = 20;
+/// Arrows of mobs fly this fast
+const MOB_ARROW_SPEED: f64 = 1.6;
+/// How far away mobs wander to, horizontally and vertically
+const WANDER_RANGE: i32 = 10;
+const WANDER_HEIGHT: i32 = 7;
+/// How far away fleeing mobs run to
+const FLEE_RANGE: i32 = 16;
+/// Random positions tried when a mob looks for somewhere to go
+const POSITION_TRIES: u32 = 10;
+/// The chance per tick to look at a player or around, like in vanilla
+const LOOK_CHANCE: f64 = 0.02;
+
+/// A player as mobs see it, taken once per tick
+pub struct PlayerView {
+    pub entity_id: EntityId,
+    pub position: Vector3<f64>,
+    pub eyes: Vector3<f64>,
+    /// Players in creative mode are ignored by hostile mobs
+    pub attackable: bool,
+}
+
+impl PlayerView {
+    pub fn new(player: &Player) -> Self {
+        let entity = &player.living_entity.entity;
+        let position = entity.pos.load();
+        Self {
+            entity_id: player.entity_id(),
+            position,
+            eyes: position.add(&Vector3::new(
+                0.0,
+                f64::from(entity.standing_eye_height),
+                0.0,
+            )),
+            attackable: player.gamemode.load() != GameMode::Creative
+                && player.living_entity.health.load() > 0.0,
+        }
+    }
+}
+
+/// What the mobs know about the world this tick, and how much work they may still do
+pub struct AiContext<'a> {
+    players: &'a [PlayerView],
+    /// The types and positions of all mobs
+    mobs: &'a [(EntityType, Vector3<f64>)],
+    paths_left: usize,
+}
+
+impl<'a> AiContext<'a> {
+    pub fn new(players: &'a [PlayerView], mobs: &'a [(EntityType, Vector3<f64>)]) -> Self {
+        Self {
+            players,
+            mobs,
+            paths_left: MAX_PATHS_PER_TICK,
+        }
+    }
+
+    fn player(&self, entity_id: EntityId) -> Option<&PlayerView> {
+        self.players
+            .iter()
+            .find(|player| player.entity_id == entity_id)
+    }
+
+    /// The closest player within `range`, only attackable ones if `attackable` is set
+    fn closest_player(
+        &self,
+        position: Vector3<f64>,
+        range: f64,
+        attackable: bool,
+    ) -> Option<&PlayerView> {
+        self.players
+            .iter()
+            .filter(|player| !attackable || player.attackable)
+            .map(|player| (player, player.position.sub(&position).length_squared()))
+            .filter(|(_, distance)| *distance <= range * range)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(player, _)| player)
+    }
+}
+
+/// What a mob runs away from
+#[derive(Clone, Copy, Debug)]
+pub enum Threat {
+    Players,
+    Mob(EntityType),
+}
+
+/// What a mob wants to do. Goals which move the mob or turn its head exclude each other, the
+/// one with the lower priority number wins
+#[derive(Clone, Copy, Debug)]
+pub enum Goal {
+    /// Walks to random places close by
+    Wander { speed: f64 },
+    /// Runs away from a threat closer than `range`
+    Flee {
+        threat: Threat,
+        range: f64,
+        speed: f64,
+    },
+    /// Walks to the target and hits it
+    MeleeAttack { speed: f64, repath_in: u32 },
+    /// Walks towards the target until it is in range and shoots arrows at it
+    RangedAttack {
+        speed: f64,
+        repath_in: u32,
+        draw_ticks: u32,
+    },
+    /// Swells up next to the target and explodes, like creepers
+    Explode,
+    /// Looks at the closest player within `range` for a while
+    LookAtPlayer {
+        range: f64,
+        player: Option<EntityId>,
+        ticks_left: u32,
+    },
+    /// Stands still and looks into a random direction for a while
+    LookAround { yaw: f32, ticks_left: u32 },
+}
+
+/// The parts of a mob a goal controls
+const MOVE: u8 = 1;
+const LOOK: u8 = 2;
+
+impl Goal {
+    const fn controls(&self) -> u8 {
+        match self {
+            Self::Wander { .. } | Self::Flee { .. } | Self::Explode => MOVE,
+            Self::MeleeAttack { .. } | Self::RangedAttack { .. } | Self::LookAround { .. } => {
+                MOVE | LOOK
+            }
+            Self::LookAtPlayer { .. } => LOOK,
+        }
+    }
+
+    /// Ticks between the checks whether the goal should start, so idle mobs stay cheap
+    const fn check_interval(&self) -> u32 {
+        match self {
+            Self::Wander { .. } => 20,
+            Self::Flee { .. } => 5,
+            Self::MeleeAttack { .. } | Self::RangedAttack { .. } | Self::Explode => 2,
+            Self::LookAtPlayer { .. } | Self::LookAround { .. } => 1,
+        }
+    }
+}
+
+/// A goal of a mob and whether it runs right now
+#[derive(Clone, Copy, Debug)]
+pub struct GoalSlot {
+    priority: u8,
+    goal: Goal,
+    running: bool,
+    /// Ticks until the goal checks whether it should start
+    next_check: u32,
+}
+
+fn slot(priority: u8, goal: Goal) -> GoalSlot {
+    GoalSlot {
+        priority,
+        goal,
+        running: false,
+        // Mobs which spawned together don't all start at once
+        next_check: rand::thread_rng().gen_range(0..goal.check_interval()),
+    }
+}
+
+const fn wander(speed: f64) -> Goal {
+    Goal::Wander { speed }
+}
+
+const fn look_at_player(range: f64) -> Goal {
+    Goal::LookAtPlayer {
+        range,
+        player: None,
+        ticks_left: 0,
+    }
+}
+
+const LOOK_AROUND: Goal = Goal::LookAround {
+    yaw: 0.0,
+    ticks_left: 0,
+};
+
+const fn melee_attack(speed: f64) -> Goal {
+    Goal::MeleeAttack {
+        speed,
+        repath_in: 0,
+    }
+}
+
+const fn ranged_attack(speed: f64) -> Goal {
+    Goal::RangedAttack {
+        speed,
+        repath_in: 0,
+        draw_ticks: 0,
+    }
+}
+
+/// The goals of a mob, with their priorities in vanilla
+pub fn goals_for(entity_type: EntityType) -> Vec<GoalSlot> {
+    match entity_type {
+        EntityType::Zombie | EntityType::Husk | EntityType::ZombieVillager => vec![
+            slot(2, melee_attack(1.0)),
+            slot(7, wander(1.0)),
+            slot(8, look_at_player(8.0)),
+            slot(8, LOOK_AROUND),
+        ],
+        EntityType::Skeleton | EntityType::Stray => vec![
+            slot(
+                3,
+                Goal::Flee {
+                    threat: Threat::Mob(EntityType::Wolf),
+                    range: 6.0,
+                    speed: 1.2,
+                },
+            ),
+            slot(4, ranged_attack(1.0)),
+            slot(5, wander(1.0)),
+            slot(6, look_at_player(8.0)),
+            slot(6, LOOK_AROUND),
+        ],
+        EntityType::Spider => vec![
+            slot(4, melee_attack(1.0)),
+            slot(5, wander(0.8)),
+            slot(6, look_at_player(8.0)),
+            slot(6, LOOK_AROUND),
+        ],
+        EntityType::Creeper => vec![
+            slot(2, Goal::Explode),
+            slot(4, melee_attack(1.0)),
+            slot(5, wander(0.8)),
+            slot(6, look_at_player(8.0)),
+            slot(6, LOOK_AROUND),
+        ],
+        EntityType::Rabbit => vec![
+            slot(
+                4,
+                Goal::Flee {
+                    threat: Threat::Players,
+                    range: 8.0,
+                    speed: 2.2,
+                },
+            ),
+            slot(11, wander(0.6)),
+            slot(11, look_at_player(10.0)),
+        ],
+        EntityType::Chicken | EntityType::Cow | EntityType::Pig | EntityType::Sheep => vec![
+            slot(6, wander(1.0)),
+            slot(7, look_at_player(6.0)),
+            slot(8, LOOK_AROUND),
+        ],
+        // TODO: Endermen get angry when players look at them, witches throw potions
+        EntityType::Enderman | EntityType::Witch | EntityType::Wolf => vec![
+            slot(7, wander(1.0)),
+            slot(8, look_at_player(8.0)),
+            slot(8, LOOK_AROUND),
+        ],
+        EntityType::Arrow
+        | EntityType::Boat
+        | EntityType::EnderPearl
+        | EntityType::ExperienceOrb
+        | EntityType::FallingBlock
+        | EntityType::Item
+        | EntityType::Minecart
+        | EntityType::Snowball
+        | EntityType::Tnt
+        | EntityType::Player => Vec::new(),
+    }
+}
+
+/// Whether mobs of the type go after players
+pub(super) const fn is_hostile(entity_type: EntityType) -> bool {
+    matches!(
+        entity_type,
+        EntityType::Zombie
+            | EntityType::Husk
+            | EntityType::ZombieVillager
+            | EntityType::Skeleton
+            | EntityType::Stray
+            | EntityType::Spider
+            | EntityType::Creeper
+    )
+}
+
+/// The damage of the mob's attacks, like in vanilla
+const fn attack_damage(entity_type: EntityType) -> f32 {
+    match entity_type {
+        EntityType::Zombie | EntityType::Husk | EntityType::ZombieVillager => 3.0,
+        EntityType::Skeleton | EntityType::Stray | EntityType::Spider => 2.0,
+        _ => 0.0,
+    }
+}
+
+/// Ticks between two arrows of a mob, they shoot faster on hard difficulty like in vanilla
+fn ranged_attack_interval() -> u32 {
+    if BASIC_CONFIG.get().default_difficulty == Difficulty::Hard {
+        20
+    } else {
+        40
+    }
+}
+
+/// Mobs hit harder on higher difficulties, like in vanilla
+fn scale_for_difficulty(damage: f32) -> f32 {
+    match BASIC_CONFIG.get().default_difficulty {
+        Difficulty::Peaceful => 0.0,
+        Difficulty::Easy => (damage / 2.0 + 1.0).min(damage),
+        Difficulty::Normal => damage,
+        Difficulty::Hard => damage * 1.5,
+    }
+}
+
+impl World {
+    /// Lets the goals of a mob decide what it does this tick. Goals which can't go on stop
+    /// first, then goals which want to start take over from the less important ones
+    pub(super) fn tick_goals(&self, mob: &mut Mob, ai: &mut AiContext) {
+        self.update_target(mob, ai);
+        let was_swelling = mob.swelling;
+        mob.attack_cooldown = mob.attack_cooldown.saturating_sub(1);
+
+        for index in 0..mob.goals.len() {
+            let mut slot = mob.goals[index];
+            if slot.running && !self.can_continue(&slot.goal, mob, ai) {
+                slot.running = false;
+                stop_goal(&slot.goal, mob);
+            }
+            mob.goals[index] = slot;
+        }
+        for index in 0..mob.goals.len() {
+            let mut slot = mob.goals[index];
+            if slot.running {
+                continue;
+            }
+            if slot.next_check > 0 {
+                mob.goals[index].next_check -= 1;
+                continue;
+            }
+            slot.next_check = slot.goal.check_interval() - 1;
+            let controls = slot.goal.controls();
+            let blocked = mob.goals.iter().any(|other| {
+                other.running
+                    && other.goal.controls() & controls != 0
+                    && other.priority <= slot.priority
+            });
+            if !blocked && self.start_goal(&mut slot.goal, mob, ai) {
+                for other in 0..mob.goals.len() {
+                    let GoalSlot { goal, running, .. } = mob.goals[other];
+                    if running && goal.controls() & controls != 0 {
+                        mob.goals[other].running = false;
+                        stop_goal(&goal, mob);
+                    }
+                }
+                slot.running = true;
+            }
+            mob.goals[index] = slot;
+        }
+        for index in 0..mob.goals.len() {
+            let mut slot = mob.goals[index];
+            if slot.running {
+                self.tick_goal(&mut slot.goal, mob, ai);
+            }
+            mob.goals[index] = slot;
+        }
+
+        if mob.swelling != was_swelling {
+            let state = if mob.swelling { 1 } else { -1 };
+            self.set_mob_data(mob, &CREEPER_STATE, state);
+        }
+        let swell = if mob.swelling {
+            mob.swell + 1
+        } else {
+            mob.swell.saturating_sub(1)
+        };
+        mob.swell = swell;
+        if swell >= FUSE {
+            mob.removed = true;
+        }
+    }
+
+    /// Hostile mobs pick the closest player they can attack and forget players which are gone
+    /// or too far away
+    fn update_target(&self, mob: &mut Mob, ai: &AiContext) {
+        if !is_hostile(mob.entity_type) {
+            return;
+        }
+        let range = follow_range(mob.entity_type);
+        if let Some(target) = mob.target {
+            let keeps = ai.player(target).is_some_and(|player| {
+                player.attackable
+                    && player.position.sub(&mob.body.position).length_squared() <= range * range
+            });
+            if !keeps {
+                mob.target = None;
+            }
+        }
+        if mob.target.is_some() {
+            return;
+        }
+        if mob.target_check > 0 {
+            mob.target_check -= 1;
+            return;
+        }
+        mob.target_check = TARGET_CHECK_INTERVAL;
+        mob.target = ai
+            .closest_player(mob.body.position, range, true)
+            .map(|player| player.entity_id);
+    }
+
+    /// Whether a goal which doesn't run should start now, it prepares itself if it does
+    fn start_goal(&self, goal: &mut Goal, mob: &mut Mob, ai: &mut AiContext) -> bool {
+        let mut rng = rand::thread_rng();
+        match goal {
+            Goal::Wander { speed } => {
+                // Wandering mobs take a break in between
+                if rng.gen_range(0..6) != 0 {
+                    return false;
+                }
+                let Some(destination) = self.random_position(mob, WANDER_RANGE, None) else {
+                    return false;
+                };
+                self.navigate(mob, ai, destination, *speed)
+            }
+            Goal::Flee {
+                threat,
+                range,
+                speed,
+            } => {
+                let Some(from) = closest_threat(mob, ai, *threat, *range) else {
+                    return false;
+                };
+                let Some(destination) = self.random_position(mob, FLEE_RANGE, Some(from)) else {
+                    return false;
+                };
+                self.navigate(mob, ai, destination, *speed)
+            }
+            Goal::MeleeAttack { speed, repath_in } => {
+                let Some(target) = mob.target.and_then(|target| ai.player(target)) else {
+                    return false;
+                };
+                *repath_in = 0;
+                let destination = block_at(target.position);
+                self.navigate(mob, ai, destination, *speed)
+            }
+            Goal::RangedAttack {
+                repath_in,
+                draw_ticks,
+                ..
+            } => {
+                *repath_in = 0;
+                *draw_ticks = 0;
+                mob.target.and_then(|target| ai.player(target)).is_some()
+            }
+            Goal::Explode => {
+                mob.target
+                    .and_then(|target| ai.player(target))
+                    .is_some_and(|target| {
+                        target.position.sub(&mob.body.position).length_squared()
+                            < SWELL_RANGE * SWELL_RANGE
+                    })
+            }
+            Goal::LookAtPlayer {
+                range,
+                player,
+                ticks_left,
+            } => {
+                if rng.gen::<f64>() >= LOOK_CHANCE {
+                    return false;
+                }
+                let Some(closest) = ai.closest_player(mob.eyes(), *range, false) else {
+                    return false;
+                };
+                *player = Some(closest.entity_id);
+                *ticks_left = 40 + rng.gen_range(0..40);
+                true
+            }
+            Goal::LookAround { yaw, ticks_left } => {
+                if rng.gen::<f64>() >= LOOK_CHANCE {
+                    return false;
+                }
+                *yaw = rng.gen_range(-180.0..180.0);
+                *ticks_left = 20 + rng.gen_range(0..20);
+                true
+            }
+        }
+    }
+
+    /// Whether a running goal goes on
+    fn can_continue(&self, goal: &Goal, mob: &Mob, ai: &AiContext) -> bool {
+        match goal {
+            Goal::Wander { .. } | Goal::Flee { .. } => mob.navigation.is_some(),
+            Goal::MeleeAttack { .. } | Goal::RangedAttack { .. } => mob.target.is_some(),
+            Goal::Explode => {
+                mob.target
+                    .and_then(|target| ai.player(target))
+                    .is_some_and(|target| {
+                        target.position.sub(&mob.body.position).length_squared()
+                            < STOP_SWELL_RANGE * STOP_SWELL_RANGE
+                    })
+            }
+            Goal::LookAtPlayer {
+                range,
+                player,
+                ticks_left,
+            } => {
+                *ticks_left > 0
+                    && (*player)
+                        .and_then(|player| ai.player(player))
+                        .is_some_and(|player| {
+                            player.eyes.sub(&mob.eyes()).length_squared() <= range * range
+                        })
+            }
+            Goal::LookAround { ticks_left, .. } => *ticks_left > 0,
+        }
+    }
+
+    /// What a running goal does every tick
+    fn tick_goal(&self, goal: &mut Goal, mob: &mut Mob, ai: &mut AiContext) {
+        match goal {
+            Goal::Wander { .. } | Goal::Flee { .. } => {}
+            Goal::MeleeAttack { speed, repath_in } => {
+                let Some(target) = mob.target.and_then(|target| ai.player(target)) else {
+                    return;
+                };
+                let (position, eyes) = (target.position, target.eyes);
+                let target_id = target.entity_id;
+                mob.look_at = Some(eyes);
+                // Following a moving target needs a new path every now and then
+                *repath_in = repath_in.saturating_sub(1);
+                if *repath_in == 0 && self.navigate(mob, ai, block_at(position), *speed) {
+                    *repath_in = 4 + rand::thread_rng().gen_range(0..7);
+                }
+                // Like in vanilla, mobs reach twice their width plus the player's width
+                let reach = mob.physics.width * 2.0;
+                let distance = position.sub(&mob.body.position).length_squared();
+                if mob.attack_cooldown == 0 && distance <= reach * reach + 0.6 {
+                    self.mob_attack(mob, target_id);
+                }
+            }
+            Goal::RangedAttack {
+                speed,
+                repath_in,
+                draw_ticks,
+            } => {
+                let Some(target) = mob.target.and_then(|target| ai.player(target)) else {
+                    return;
+                };
+                let (position, eyes) = (target.position, target.eyes);
+                mob.look_at = Some(eyes);
+                let in_range = position.sub(&mob.body.position).length_squared()
+                    <= RANGED_ATTACK_RANGE * RANGED_ATTACK_RANGE;
+                // Mobs stand still to shoot and walk closer otherwise
+                *repath_in = repath_in.saturating_sub(1);
+                if in_range {
+                    mob.navigation = None;
+                } else if *repath_in == 0 && self.navigate(mob, ai, block_at(position), *speed) {
+                    *repath_in = 4 + rand::thread_rng().gen_range(0..7);
+                }
+                if !in_range || mob.attack_cooldown > 0 {
+                    *draw_ticks = 0;
+                    return;
+                }
+                *draw_ticks += 1;
+                if *draw_ticks >= BOW_DRAW_TICKS {
+                    *draw_ticks = 0;
+                    // Arrows aim a third up the target, like in vanilla
+                    let aim = Vector3::new(
+                        position.x,
+                        position.y + (eyes.y - position.y) / 3.0,
+                        position.z,
+                    );
+                    self.shoot_arrow(mob, aim);
+                }
+            }
+            Goal::Explode => {
+                let Some(target) = mob.target.and_then(|target| ai.player(target)) else {
+                    return;
+                };
+                mob.look_at = Some(target.eyes);
+                mob.navigation = None;
+                mob.swelling = true;
+            }
+            Goal::LookAtPlayer {
+                player, ticks_left, ..
+            } => {
+                *ticks_left = ticks_left.saturating_sub(1);
+                mob.look_at = (*player)
+                    .and_then(|player| ai.player(player))
+                    .map(|player| player.eyes);
+            }
+            Goal::LookAround { yaw, ticks_left } => {
+                *ticks_left = ticks_left.saturating_sub(1);
+                let radians = f64::from(*yaw).to_radians();
+                mob.look_at = Some(mob.eyes().add(&Vector3::new(
+                    -radians.sin(),
+                    0.0,
+                    radians.cos(),
+                )));
+            }
+        }
+    }
+
+    /// Finds a path for the mob to walk along, unless too many paths were found this tick
+    fn navigate(
+        &self,
+        mob: &mut Mob,
+        ai: &mut AiContext,
+        destination: WorldPosition,
+        speed: f64,
+    ) -> bool {
+        if ai.paths_left == 0 {
+            return false;
+        }
+        ai.paths_left -= 1;
+        let height = mob.physics.height.ceil() as i32;
+        let Some(path) = self.find_path(mob.feet(), destination, height) else {
+            return false;
+        };
+        mob.navigation = Some(Navigation {
+            ticks_left: path.len() as u32 * TICKS_PER_PATH_NODE,
+            path,
+            speed,
+        });
+        true
+    }
+
+    /// A random position close to the mob where it can stand. With `away_from`, only positions
+    /// further away from there than the mob is count
+    fn random_position(
+        &self,
+        mob: &Mob,
+        range: i32,
+        away_from: Option<Vector3<f64>>,
+    ) -> Option<WorldPosition> {
+        let mut rng = rand::thread_rng();
+        let feet = mob.feet();
+        let height = mob.physics.height.ceil() as i32;
+        for _ in 0..POSITION_TRIES {
+            let column = Vector3::new(
+                feet.0.x + rng.gen_range(-range..=range),
+                feet.0.y,
+                feet.0.z + rng.gen_range(-range..=range),
+            );
+            if let Some(from) = away_from {
+                let center = Vector3::new(f64::from(column.x), from.y, f64::from(column.z));
+                if center.sub(&from).length_squared()
+                    <= mob.body.position.sub(&from).length_squared()
+                {
+                    continue;
+                }
+            }
+            // The highest spot in the column around the mob's height
+            let found = (-WANDER_HEIGHT..=WANDER_HEIGHT).rev().find_map(|y| {
+                let position = WorldPosition(column.add(&Vector3::new(0, y, 0)));
+                self.can_stand_at(&position, height).then_some(position)
+            });
+            if found.is_some() {
+                return found;
+            }
+        }
+        None
+    }
+
+    /// Shoots an arrow at `aim`, a bit above it so it arcs down there
+    fn shoot_arrow(&self, mob: &mut Mob, aim: Vector3<f64>) {
+        mob.attack_cooldown = ranged_attack_interval();
+        let position = mob.eyes().sub(&Vector3::new(0.0, 0.1, 0.0));
+        let delta = aim.sub(&position);
+        let direction = Vector3::new(delta.x, delta.y + delta.x.hypot(delta.z) * 0.2, delta.z);
+        // Mobs aim better on higher difficulties, like in vanilla
+        let inaccuracy = match BASIC_CONFIG.get().default_difficulty {
+            Difficulty::Peaceful => 14.0,
+            Difficulty::Easy => 10.0,
+            Difficulty::Normal => 6.0,
+            Difficulty::Hard => 2.0,
+        };
+        self.launch_projectile(Launch {
+            kind: ProjectileKind::Arrow,
+            position,
+            velocity: projectile::launch_velocity(direction, MOB_ARROW_SPEED, inaccuracy),
+            owner: Some(mob.entity_id),
+            critical: false,
+            pickup: Pickup::Disallowed,
+        });
+    }
+
+    /// Hits the player with the mob's attack, it pushes them away
+    fn mob_attack(&self, mob: &mut Mob, target: EntityId) {
+        mob.attack_cooldown = ATTACK_INTERVAL;
+        let damage = scale_for_difficulty(attack_damage(mob.entity_type));
+        if damage <= 0.0 {
+            return;
+        }
+        self.broadcast_tracked(
+            mob.entity_id,
+            &CEntityAnimation::new(mob.entity_id.into(), Animation::SwingMainArm as u8),
+        );
+        let Some(player) = self.get_player_by_entityid(target) else {
+            return;
+        };
+        if !player.damage(damage, DamageSource::Attack(mob.entity_id)) {
+            return;
+        }
+        player.knockback_from(ATTACK_KNOCKBACK, mob.body.position);
+        if player.living_entity.health.load() <= 0.0 {
+            if let Some(id) =
+                global_registry::find_minecraft_id("minecraft:entity_type", mob.entity_type as u32)
+            {
+                player.increment_stat(stats::KILLED_BY, id, 1);
+            }
+        }
+    }
+}
+
+/// Cleans up after a goal which stopped
+fn stop_goal(goal: &Goal, mob: &mut Mob) {
+    match goal {
+        Goal::Wander { .. }
+        | Goal::Flee { .. }
+        | Goal::MeleeAttack { .. }
+        | Goal::RangedAttack { .. } => {
+            mob.navigation = None;
+        }
+        // The creeper shrinks again
+        Goal::Explode => mob.swelling = false,
+        Goal::LookAtPlayer { .. } | Goal::LookAround { .. } => {}
+    }
+}
+
+/// Where the closest threat of the kind within `range` is
+fn closest_threat(mob: &Mob, ai: &AiContext, threat: Threat, range: f64) -> Option<Vector3<f64>> {
+    let position = mob.body.position;
+    let positions: Vec<_> = match threat {
+        Threat::Players => ai
+            .players
+            .iter()
+            .filter(|player| player.attackable)
+            .map(|player| player.position)
+            .collect(),
+        Threat::Mob(entity_type) => ai
+            .mobs
+            .iter()
+            .filter(|(other, _)| *other == entity_type)
+            .map(|(_, position)| *position)
+            .collect(),
+    };
+    positions
+        .into_iter()
+        .map(|other| (other, other.sub(&position).length_squared()))
+        .filter(|(_, distance)| *distance <= range * range)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(other, _)| other)
+}
+
+fn block_at(position: Vector3<f64>) -> WorldPosition {
+    WorldPosition(Vector3::new(
+        position.x.floor() as i32,
+        position.y.floor() as i32,
+        position.z.floor() as i32,
+    ))
+}
@@ -0,0 +1,643 @@
+use std::collections::{HashMap, HashSet};
+
+mod goal;
+
+use goal::{AiContext, GoalSlot, PlayerView};
+use pumpkin_config::{ADVANCED_CONFIG, BASIC_CONFIG};
+use pumpkin_core::{
+    math::{boundingbox::BoundingBox, position::WorldPosition, vector2::Vector2, vector3::Vector3},
+    Difficulty, GameMode,
+};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_protocol::{
+    client::play::{
+        CHeadRot, CSetEntityData, CSetEquipment, CSpawnEntity, CUpdateEntityRot, EquipmentSlot,
+    },
+    slot::Slot,
+};
+use pumpkin_world::{
+    game_rules,
+    global_registry::{self, ITEM_REGISTRY},
+    WORLD_LOWEST_Y,
+};
+use rand::Rng;
+use uuid::Uuid;
+
+use crate::{
+    client::Client,
+    entity::{
+        metadata::{self, metadata_values, DataKey, EntityData, CUSTOM_NAME},
+        player::Player,
+    },
+    server::Server,
+};
+
+use super::{
+    collision::{self, EntityGrid},
+    is_simulated,
+    natural_spawner::SpawnCategory,
+    pathfinding::Path,
+    physics::{Body, Physics},
+    tracking::{angle_byte, Tracked},
+    World,
+};
+
+/// Mobs which despawn are gone right away once no player is closer than this
+const DESPAWN_RANGE: f64 = 128.0;
+/// Mobs which despawn may vanish at random once no player is closer than this
+const RANDOM_DESPAWN_RANGE: f64 = 32.0;
+/// The chance per tick of such a mob to vanish is 1 in this, like in vanilla
+const RANDOM_DESPAWN_CHANCE: u32 = 800;
+/// Mobs this far below the world are gone
+const VOID_DEPTH: f64 = 64.0;
+/// How fast mobs jump up, like in vanilla
+const JUMP_VELOCITY: f64 = 0.42;
+/// How much of their speed mobs can put into moving while they are in the air
+const AIR_ACCELERATION: f64 = 0.02;
+/// Mobs give up on a path after this many ticks per block of it, e.g. when they are stuck
+const TICKS_PER_PATH_NODE: u32 = 20;
+/// How far the head can turn away from the body and how fast it turns, in degrees
+const MAX_HEAD_TURN: f32 = 75.0;
+const HEAD_TURN_SPEED: f32 = 30.0;
+
+/// The base movement speed of the type, like in vanilla
+const fn movement_speed(entity_type: EntityType) -> f64 {
+    match entity_type {
+        EntityType::Cow => 0.2,
+        EntityType::Zombie | EntityType::Husk | EntityType::ZombieVillager | EntityType::Sheep => {
+            0.23
+        }
+        EntityType::Spider | EntityType::Enderman | EntityType::Rabbit | EntityType::Wolf => 0.3,
+        _ => 0.25,
+    }
+}
+
+/// How far away the mob notices players, like in vanilla
+const fn follow_range(entity_type: EntityType) -> f64 {
+    match entity_type {
+        EntityType::Zombie | EntityType::Husk | EntityType::ZombieVillager => 35.0,
+        _ => 16.0,
+    }
+}
+
+/// Where a mob walks to and how fast
+struct Navigation {
+    path: Path,
+    /// Multiplies the mob's movement speed, fleeing mobs run
+    speed: f64,
+    /// Ticks until the mob gives up on the path
+    ticks_left: u32,
+}
+
+/// A mob which walks around by itself, its goals decide what it does
+pub struct Mob {
+    entity_id: EntityId,
+    uuid: Uuid,
+    entity_type: EntityType,
+    body: Body,
+    physics: Physics,
+    /// The body turns where the mob walks, the head where it looks
+    head_yaw: f32,
+    /// The body yaw, the pitch and the head yaw clients were told last
+    synced_rotation: (u8, u8, u8),
+    goals: Vec<GoalSlot>,
+    /// Where the goals want the mob to look this tick
+    look_at: Option<Vector3<f64>>,
+    navigation: Option<Navigation>,
+    /// The player the mob is after
+    target: Option<EntityId>,
+    /// Ticks until the mob looks for a target again
+    target_check: u32,
+    /// Ticks until the mob can attack again
+    attack_cooldown: u32,
+    /// Whether a creeper is about to explode and for how many ticks it has been swelling
+    swelling: bool,
+    swell: u32,
+    /// The entity metadata which differs from the defaults, e.g. a custom name
+    data: EntityData,
+    /// Set once the mob is gone, e.g. because it exploded
+    removed: bool,
+}
+
+impl Mob {
+    pub fn new(server: &Server, entity_type: EntityType, position: Vector3<f64>, yaw: f32) -> Self {
+        let mut body = Body::new(position, Vector3::new(0.0, 0.0, 0.0));
+        body.yaw = yaw;
+        Self {
+            entity_id: server.new_entity_id(),
+            uuid: Uuid::new_v4(),
+            entity_type,
+            body,
+            physics: Physics::mob(entity_type),
+            head_yaw: yaw,
+            synced_rotation: (angle_byte(yaw), 0, angle_byte(yaw)),
+            goals: goal::goals_for(entity_type),
+            look_at: None,
+            navigation: None,
+            target: None,
+            target_check: 0,
+            attack_cooldown: 0,
+            swelling: false,
+            swell: 0,
+            data: EntityData::default(),
+            removed: false,
+        }
+    }
+
+    pub const fn entity_id(&self) -> EntityId {
+        self.entity_id
+    }
+
+    fn spawn_packet(&self) -> CSpawnEntity {
+        CSpawnEntity::new(
+            self.entity_id.into(),
+            self.uuid,
+            (self.entity_type as i32).into(),
+            self.body.synced_position().x,
+            self.body.synced_position().y,
+            self.body.synced_position().z,
+            self.body.pitch,
+            self.body.yaw,
+            self.head_yaw,
+            0.into(),
+            self.body.velocity.x as f32,
+            self.body.velocity.y as f32,
+            self.body.velocity.z as f32,
+        )
+    }
+
+    /// Shows the item the mob holds, skeletons hold a bow
+    fn equipment_packet(&self) -> Option<CSetEquipment> {
+        let item = match self.entity_type {
+            EntityType::Skeleton | EntityType::Stray => "minecraft:bow",
+            _ => return None,
+        };
+        let item_id = global_registry::find_protocol_id(ITEM_REGISTRY, item)?;
+        Some(CSetEquipment::new(
+            self.entity_id.into(),
+            EquipmentSlot::MainHand,
+            Slot::new(1.into(), (item_id as i32).into()),
+        ))
+    }
+
+    /// The block the mob stands in, rounded so a mob standing on a block is not in it
+    fn feet(&self) -> WorldPosition {
+        WorldPosition(Vector3::new(
+            self.body.position.x.floor() as i32,
+            (self.body.position.y + 0.5).floor() as i32,
+            self.body.position.z.floor() as i32,
+        ))
+    }
+
+    fn eyes(&self) -> Vector3<f64> {
+        // Most mobs have their eyes at 85% of their height, like in vanilla
+        self.body
+            .position
+            .add(&Vector3::new(0.0, self.physics.height * 0.85, 0.0))
+    }
+
+    /// Whether the mob vanishes now, with the squared distance to the closest player
+    fn despawns(&self, closest_player: Option<f64>) -> bool {
+        let Some(category) = SpawnCategory::of(self.entity_type) else {
+            return false;
+        };
+        // Named mobs stay, like in vanilla
+        if self.data.get(&CUSTOM_NAME).is_some() {
+            return false;
+        }
+        if category == SpawnCategory::Monster
+            && BASIC_CONFIG.get().default_difficulty == Difficulty::Peaceful
+        {
+            return true;
+        }
+        // Mobs stay while there is nobody around who could see them go
+        let Some(distance) = closest_player else {
+            return false;
+        };
+        category.despawns()
+            && (distance > DESPAWN_RANGE * DESPAWN_RANGE
+                || (distance > RANDOM_DESPAWN_RANGE * RANDOM_DESPAWN_RANGE
+                    && rand::thread_rng().gen_range(0..RANDOM_DESPAWN_CHANCE) == 0))
+    }
+
+    /// Walks along the path, jumping up where it goes up
+    fn follow_path(&mut self) {
+        let Some(navigation) = &mut self.navigation else {
+            return;
+        };
+        navigation.ticks_left = navigation.ticks_left.saturating_sub(1);
+        // Like in vanilla, wide mobs reach a block sooner
+        let reach = if self.physics.width > 0.75 {
+            self.physics.width / 2.0
+        } else {
+            0.75 - self.physics.width / 2.0
+        };
+        let position = self.body.position;
+        let mut next = None;
+        while let Some(node) = navigation.path.next_node() {
+            let center = Vector3::new(
+                f64::from(node.0.x) + 0.5,
+                f64::from(node.0.y),
+                f64::from(node.0.z) + 0.5,
+            );
+            let delta = center.sub(&position);
+            if delta.x * delta.x + delta.z * delta.z < reach * reach && delta.y.abs() < 1.0 {
+                navigation.path.advance();
+                continue;
+            }
+            next = Some(delta);
+            break;
+        }
+        let Some(delta) = next.filter(|_| navigation.ticks_left > 0) else {
+            self.navigation = None;
+            return;
+        };
+        let speed = movement_speed(self.entity_type) * navigation.speed;
+        let acceleration = if self.body.on_ground {
+            speed * speed
+        } else {
+            AIR_ACCELERATION * speed
+        };
+        let horizontal = (delta.x * delta.x + delta.z * delta.z).sqrt();
+        if horizontal > 0.0 {
+            self.body.velocity.x += delta.x / horizontal * acceleration;
+            self.body.velocity.z += delta.z / horizontal * acceleration;
+            self.body.yaw = delta.z.atan2(delta.x).to_degrees() as f32 - 90.0;
+        }
+        if self.body.on_ground && delta.y > 0.5 {
+            self.body.velocity.y = JUMP_VELOCITY;
+        }
+    }
+
+    /// Turns the head to where the goals want the mob to look, or back to the body
+    fn turn_head(&mut self) {
+        let (yaw, pitch) = match self.look_at.take() {
+            Some(point) => {
+                let delta = point.sub(&self.eyes());
+                let horizontal = (delta.x * delta.x + delta.z * delta.z).sqrt();
+                (
+                    delta.z.atan2(delta.x).to_degrees() as f32 - 90.0,
+                    -delta.y.atan2(horizontal).to_degrees() as f32,
+                )
+            }
+            None => (self.body.yaw, 0.0),
+        };
+        self.head_yaw = approach_angle(self.head_yaw, yaw, HEAD_TURN_SPEED);
+        self.body.pitch = approach_angle(self.body.pitch, pitch, HEAD_TURN_SPEED);
+        // The body follows a head which turned too far
+        let turned = wrap_degrees(self.head_yaw - self.body.yaw);
+        if turned.abs() > MAX_HEAD_TURN {
+            self.body.yaw = self.head_yaw - MAX_HEAD_TURN.copysign(turned);
+        }
+    }
+}
+
+/// An angle in degrees between -180 and 180
+fn wrap_degrees(degrees: f32) -> f32 {
+    (degrees + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Turns `from` towards `to` by at most `max` degrees, the short way around
+fn approach_angle(from: f32, to: f32, max: f32) -> f32 {
+    from + wrap_degrees(to - from).clamp(-max, max)
+}
+
+impl World {
+    /// Adds a mob to the world, the players close to it see it with the next tick
+    pub fn add_mob(&self, mob: Mob) {
+        self.mobs.lock().push(mob);
+    }
+
+    /// Whether a hostile mob is at most `horizontal` blocks sideways and `vertical` blocks up or
+    /// down away from `center`
+    pub(super) fn is_monster_near(
+        &self,
+        center: Vector3<f64>,
+        horizontal: f64,
+        vertical: f64,
+    ) -> bool {
+        self.mobs.lock().iter().any(|mob| {
+            let offset = mob.body.position.sub(&center);
+            goal::is_hostile(mob.entity_type)
+                && offset.x.abs() <= horizontal
+                && offset.z.abs() <= horizontal
+                && offset.y.abs() <= vertical
+        })
+    }
+
+    /// How many mobs of the category there are in the world
+    pub(super) fn mob_count(&self, category: SpawnCategory) -> usize {
+        self.mobs
+            .lock()
+            .iter()
+            .filter(|mob| SpawnCategory::of(mob.entity_type) == Some(category))
+            .count()
+    }
+
+    /// Lets the mobs think and move and the ones far away from players despawn. Mobs outside
+    /// of the simulated chunks wait
+    pub(super) fn tick_mobs(&self, server: &Server, simulated: &HashSet<Vector2<i32>>) {
+        let players: Vec<_> = self
+            .current_players
+            .lock()
+            .values()
+            .filter(|player| player.gamemode.load() != GameMode::Spectator)
+            .map(|player| PlayerView::new(player))
+            .collect();
+        let others: Vec<_> = self
+            .mobs
+            .lock()
+            .iter()
+            .map(|mob| (mob.entity_type, mob.body.position))
+            .collect();
+        let mut ai = AiContext::new(&players, &others);
+        let mut mobs = std::mem::take(&mut *self.mobs.lock());
+        // Mobs riding another mob steer it, like the zombies of chicken jockeys
+        let (riding, steering) = {
+            let passengers = self.passengers.lock();
+            let riding: HashMap<EntityId, EntityId> = mobs
+                .iter()
+                .filter_map(|mob| Some((mob.entity_id, passengers.vehicle_of(mob.entity_id)?)))
+                .collect();
+            let steering: HashMap<EntityId, EntityId> = riding
+                .iter()
+                .filter(|(rider, vehicle)| {
+                    passengers.passengers_of(**vehicle).first() == Some(*rider)
+                        && mobs.iter().any(|mob| mob.entity_id == **vehicle)
+                })
+                .map(|(rider, vehicle)| (*vehicle, *rider))
+                .collect();
+            (riding, steering)
+        };
+        let mut steers = Vec::new();
+        let mut gone = Vec::new();
+        let mut explosions = Vec::new();
+        mobs.retain_mut(|mob| {
+            if !is_simulated(simulated, mob.body.position) {
+                return true;
+            }
+            let closest_player = players
+                .iter()
+                .map(|player| player.position.sub(&mob.body.position).length_squared())
+                .min_by(f64::total_cmp);
+            if mob.despawns(closest_player)
+                || mob.body.position.y < f64::from(WORLD_LOWEST_Y) - VOID_DEPTH
+            {
+                gone.push(mob.entity_id);
+                return false;
+            }
+            self.tick_goals(mob, &mut ai);
+            if mob.removed {
+                gone.push(mob.entity_id);
+                explosions.push(mob.body.position);
+                return false;
+            }
+            // Its rider decides where it goes
+            if !steering.contains_key(&mob.entity_id) {
+                mob.follow_path();
+            }
+            mob.turn_head();
+            if let Some(vehicle) = riding.get(&mob.entity_id) {
+                // It sits on its vehicle, see `move_passengers`. Where it wants to go moves the
+                // vehicle instead
+                if steering.get(vehicle) == Some(&mob.entity_id) {
+                    steers.push((*vehicle, mob.body.velocity, mob.body.yaw));
+                }
+                mob.body.velocity = Vector3::new(0.0, 0.0, 0.0);
+                self.sync_rotation(mob);
+                return true;
+            }
+            if self.step_physics(&mut mob.body, &mob.physics) {
+                self.sync_body(mob.entity_id, &mut mob.body, &mob.physics);
+            }
+            self.sync_rotation(mob);
+            true
+        });
+        for (vehicle, velocity, yaw) in steers {
+            let Some(vehicle) = mobs.iter_mut().find(|mob| mob.entity_id == vehicle) else {
+                continue;
+            };
+            vehicle.body.velocity.x += velocity.x;
+            vehicle.body.velocity.z += velocity.z;
+            if velocity.y > 0.0 && vehicle.body.on_ground {
+                vehicle.body.velocity.y = velocity.y;
+            }
+            vehicle.body.yaw = yaw;
+        }
+        self.mobs.lock().append(&mut mobs);
+        for entity_id in gone {
+            self.forget_rides(entity_id);
+        }
+        // Only creepers explode so far, they don't break blocks if mobs may not grief
+        let griefing = self.game_rule_bool(game_rules::MOB_GRIEFING);
+        for position in explosions {
+            self.explode(server, position, goal::CREEPER_POWER, griefing);
+        }
+    }
+
+    /// Mobs which overlap other mobs or players get pushed away from them, so they don't stack
+    /// up inside one another. Clients push their own player, so players are left alone
+    pub(super) fn push_mobs(&self, server: &Server, simulated: &HashSet<Vector2<i32>>) {
+        if !ADVANCED_CONFIG.get().collisions.entity_pushing {
+            return;
+        }
+        let players = self.pushing_players(server);
+        let mut mobs = self.mobs.lock();
+        // Vehicles and their passengers don't push each other off
+        let riding: HashSet<EntityId> = {
+            let passengers = self.passengers.lock();
+            mobs.iter()
+                .map(|mob| mob.entity_id)
+                .filter(|entity_id| passengers.contains(*entity_id))
+                .collect()
+        };
+        // The mobs come first, then the players
+        let mut positions: Vec<Vector3<f64>> = mobs.iter().map(|mob| mob.body.position).collect();
+        let mut boxes: Vec<BoundingBox> = mobs
+            .iter()
+            .map(|mob| mob.body.bounding_box(&mob.physics))
+            .collect();
+        for (bounding_box, position) in players {
+            boxes.push(bounding_box);
+            positions.push(position);
+        }
+        let grid = EntityGrid::new(boxes);
+        for (index, mob) in mobs.iter_mut().enumerate() {
+            if !is_simulated(simulated, mob.body.position) || riding.contains(&mob.entity_id) {
+                continue;
+            }
+            for other in grid.intersecting(index) {
+                if let Some(push) = collision::push(positions[other], mob.body.position) {
+                    mob.body.velocity = mob.body.velocity.add(&push);
+                }
+            }
+        }
+    }
+
+    /// The top of a mob, where its passengers sit. None if there is no such mob
+    pub(super) fn mob_top(&self, entity_id: EntityId) -> Option<Vector3<f64>> {
+        self.mobs
+            .lock()
+            .iter()
+            .find(|mob| mob.entity_id == entity_id)
+            .map(|mob| {
+                mob.body
+                    .position
+                    .add(&Vector3::new(0.0, mob.physics.height, 0.0))
+            })
+    }
+
+    /// Puts a mob which rides something onto its seat. Like in vanilla it walks with the legs of
+    /// its vehicle, so it is on the ground
+    pub(super) fn seat_mob(&self, entity_id: EntityId, seat: Vector3<f64>) {
+        if let Some(mob) = self
+            .mobs
+            .lock()
+            .iter_mut()
+            .find(|mob| mob.entity_id == entity_id)
+        {
+            mob.body.position = seat;
+            mob.body.on_ground = true;
+        }
+    }
+
+    /// Tells the players who see the mob where it looks once that changed
+    fn sync_rotation(&self, mob: &mut Mob) {
+        let rotation = (
+            angle_byte(mob.body.yaw),
+            angle_byte(mob.body.pitch),
+            angle_byte(mob.head_yaw),
+        );
+        let (yaw, pitch, head_yaw) = rotation;
+        if (yaw, pitch) != (mob.synced_rotation.0, mob.synced_rotation.1) {
+            self.broadcast_tracked(
+                mob.entity_id,
+                &CUpdateEntityRot::new(mob.entity_id.into(), yaw, pitch, mob.body.on_ground),
+            );
+        }
+        if head_yaw != mob.synced_rotation.2 {
+            self.broadcast_tracked(
+                mob.entity_id,
+                &CHeadRot::new(mob.entity_id.into(), head_yaw),
+            );
+        }
+        mob.synced_rotation = rotation;
+    }
+
+    /// The bounding boxes of all mobs, e.g. to find out what a projectile hits
+    pub(super) fn mob_bounding_boxes(&self) -> Vec<(EntityId, BoundingBox)> {
+        self.mobs
+            .lock()
+            .iter()
+            .map(|mob| (mob.entity_id, mob.body.bounding_box(&mob.physics)))
+            .collect()
+    }
+
+    /// Pushes a mob away from `from`, like a hit pushes players
+    pub(super) fn knock_back_mob(&self, entity_id: EntityId, strength: f64, from: Vector3<f64>) {
+        let mut mobs = self.mobs.lock();
+        let Some(mob) = mobs.iter_mut().find(|mob| mob.entity_id == entity_id) else {
+            return;
+        };
+        let away = Vector3::new(
+            mob.body.position.x - from.x,
+            0.0,
+            mob.body.position.z - from.z,
+        );
+        if away.length_squared() < 1.0E-5 {
+            return;
+        }
+        let push = away.normalize() * strength;
+        let velocity = mob.body.velocity;
+        mob.body.velocity = Vector3::new(
+            velocity.x / 2.0 + push.x,
+            if mob.body.on_ground {
+                (velocity.y / 2.0 + strength).min(0.4)
+            } else {
+                velocity.y
+            },
+            velocity.z / 2.0 + push.z,
+        );
+    }
+
+    /// Adds the mobs to the entities players may see
+    pub(super) fn track_mobs(&self, tracked: &mut Vec<Tracked>) {
+        tracked.extend(self.mobs.lock().iter().map(|mob| Tracked {
+            entity_id: mob.entity_id,
+            entity_type: mob.entity_type,
+            position: mob.body.position,
+        }));
+    }
+
+    /// Shows the mobs among `entity_ids` to a client which just started to see them
+    pub(super) fn send_mobs(&self, client: &Client, entity_ids: &HashSet<EntityId>) {
+        for mob in self
+            .mobs
+            .lock()
+            .iter()
+            .filter(|mob| entity_ids.contains(&mob.entity_id))
+        {
+            client.send_packet(&mob.spawn_packet());
+            if let Some(equipment) = mob.equipment_packet() {
+                client.send_packet(&equipment);
+            }
+            let entries = mob.data.entries();
+            if !entries.is_empty() {
+                let values = metadata_values(&entries);
+                client.send_packet(&CSetEntityData::new(mob.entity_id.into(), &values));
+            }
+        }
+    }
+
+    /// Changes an entry of the mob's metadata, the players who see it are told
+    fn set_mob_data<T: metadata::Tracked>(&self, mob: &mut Mob, key: &DataKey<T>, value: T) {
+        if !mob.data.set(key, value) {
+            return;
+        }
+        let entries = [mob.data.entry(key)];
+        let values = metadata_values(&entries);
+        self.broadcast_tracked(
+            mob.entity_id,
+            &CSetEntityData::new(mob.entity_id.into(), &values),
+        );
+    }
+
+    /// A player uses a name tag on a mob, it gets the name of the tag. Returns false if the
+    /// player holds no named name tag or the entity is no mob
+    pub fn name_mob(&self, player: &Player, entity_id: EntityId) -> bool {
+        let name = {
+            let inventory = player.inventory.lock();
+            let Some(item) = inventory.held_item() else {
+                return false;
+            };
+            let Some(name) = item.custom_name.as_ref().filter(|_| {
+                global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id)
+                    == Some("minecraft:name_tag")
+            }) else {
+                return false;
+            };
+            name.as_str().to_string()
+        };
+        {
+            let mut mobs = self.mobs.lock();
+            let Some(mob) = mobs.iter_mut().find(|mob| mob.entity_id == entity_id) else {
+                return false;
+            };
+            if !player.can_interact_with_entity(&mob.body.bounding_box(&mob.physics)) {
+                return true;
+            }
+            self.set_mob_data(mob, &CUSTOM_NAME, Some(name));
+        }
+        if player.gamemode.load() != GameMode::Creative {
+            let mut inventory = player.inventory.lock();
+            let held = inventory.held_item_mut();
+            if let Some(item) = held {
+                item.item_count = item.item_count.saturating_sub(1);
+                if item.item_count == 0 {
+                    *held = None;
+                }
+            }
+        }
+        true
+    }
+}
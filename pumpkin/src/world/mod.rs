@@ -1,25 +1,80 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc,
+    },
+};
 
+pub mod bed;
+pub mod block_entity;
+pub mod bulk_edit;
+mod collision;
+mod experience_orb;
+mod explosion;
+mod falling_block;
+pub mod furnace;
+mod item_entity;
+mod mob;
+mod natural_spawner;
+mod passengers;
+mod pathfinding;
+mod physics;
 pub mod player_chunker;
+mod player_info;
+pub mod portal;
+pub mod projectile;
+mod random_tick;
+pub mod redstone;
+mod schematic;
+pub mod tnt;
+pub mod tracking;
+pub mod vehicle;
+pub mod weather;
 
 use crate::{
-    client::Client,
+    chat::secure_chat_enforced,
     entity::{player::Player, Entity},
+    server::{ticker::TICKS_PER_SECOND, Server},
 };
+use crossbeam::atomic::AtomicCell;
+use experience_orb::ExperienceOrb;
+use falling_block::FallingBlock;
+use item_entity::{ItemDrop, ItemEntity};
+use mob::Mob;
 use num_traits::ToPrimitive;
 use parking_lot::Mutex;
-use pumpkin_config::BasicConfiguration;
-use pumpkin_core::math::vector2::Vector2;
-use pumpkin_entity::{entity_type::EntityType, EntityId};
+use passengers::Passengers;
+use player_info::PlayerInfoBatch;
+use projectile::{Launch, Projectile};
+use pumpkin_config::{worlds::WorldConfig, BasicConfiguration, ADVANCED_CONFIG};
+use pumpkin_core::{
+    math::{boundingbox::BoundingBox, position::WorldPosition, vector2::Vector2, vector3::Vector3},
+    GameMode,
+};
+use pumpkin_entity::EntityId;
+use pumpkin_plugins::events::{GameRuleChangeEvent, WeatherChangeEvent};
 use pumpkin_protocol::{
     client::play::{
-        CChunkData, CGameEvent, CLogin, CPlayerAbilities, CPlayerInfoUpdate, CRemoveEntities,
-        CRemovePlayerInfo, CSetEntityMetadata, CSpawnEntity, GameEvent, Metadata, PlayerAction,
+        CBlockUpdate, CEntityStatus, CGameEvent, CLogin, CPlayerInfoUpdate, CUpdateLight,
+        CUpdateTime, GameEvent,
     },
-    ClientPacket, VarInt,
+    ClientPacket,
+};
+use pumpkin_world::{
+    block::BlockId,
+    dimension::Dimension,
+    game_rules::{self, GameRuleError, GameRuleValue},
+    level::Level,
+    level_data::LevelData,
+    ticket::TicketType,
+    world_gen::Seed,
 };
-use pumpkin_world::level::Level;
-use tokio::sync::mpsc;
+use redstone::ScheduledTick;
+use sha2::{Digest, Sha256};
+use tnt::PrimedTnt;
+use vehicle::{Vehicle, VehiclePlacement};
+use weather::{Weather, WeatherType};
 
 /// Represents a Minecraft world, containing entities, players, and the underlying level data.
 ///
@@ -31,21 +86,425 @@ use tokio::sync::mpsc;
 /// - Stores and tracks active `Player` entities within the world.
 /// - Provides a central hub for interacting with the world's entities and environment.
 pub struct World {
+    /// The name players use to switch to this world
+    pub name: String,
+    /// The dimension name clients know this world by, every world has its own
+    pub dimension_name: String,
+    /// Decides how the world looks to clients and where its portals lead
+    pub dimension: Dimension,
     /// The underlying level, responsible for chunk management and terrain generation.
-    pub level: Arc<Mutex<Level>>,
+    pub level: Arc<Level>,
+    /// The seed the terrain is generated with
+    pub seed: Seed,
     /// A map of active players within the world, keyed by their unique token.
     pub current_players: Arc<Mutex<HashMap<usize, Arc<Player>>>>,
+    /// The total amount of ticks this world has been running
+    pub world_age: AtomicI64,
+    /// The current time of day in ticks, not wrapped at a full day
+    pub time_of_day: AtomicI64,
+    /// The `level.dat` of the world as it was read, with the game rules
+    level_data: Mutex<LevelData>,
+    weather: Mutex<Weather>,
+    /// Where players appear when they join or switch to this world, see `set_spawn_position`
+    pub spawn_position: AtomicCell<Vector3<f64>>,
+    /// How many chunks around the spawn always stay loaded
+    spawn_chunk_radius: u32,
+    /// Players who are no operator can't change blocks this many blocks around the spawn, 0 disables it
+    spawn_protection: u32,
+    /// Whether players can hurt each other here, see `pvp_enabled`
+    pvp: bool,
+    /// Player list changes which still have to be sent to the other players
+    player_info_batch: Mutex<PlayerInfoBatch>,
+    /// The container ids of the chests and furnaces players are looking into, by their position
+    open_block_entities: Mutex<HashMap<Vector3<i32>, u64>>,
+    /// Blocks which changed or lost their support, they may have to fall with the next tick
+    gravity_checks: Mutex<HashSet<Vector3<i32>>>,
+    falling_blocks: Mutex<Vec<FallingBlock>>,
+    /// Blocks next to a change, redstone components among them react with the next tick
+    block_updates: Mutex<HashSet<Vector3<i32>>>,
+    scheduled_ticks: Mutex<Vec<ScheduledTick>>,
+    primed_tnt: Mutex<Vec<PrimedTnt>>,
+    /// Experience which was dropped this tick, it becomes orbs with the next tick
+    experience_drops: Mutex<Vec<(Vector3<f64>, i32)>>,
+    experience_orbs: Mutex<Vec<ExperienceOrb>>,
+    /// Items which were dropped this tick, they become item entities with the next tick
+    item_drops: Mutex<Vec<ItemDrop>>,
+    items: Mutex<Vec<ItemEntity>>,
+    /// Projectiles which were launched this tick, they start to fly with the next tick
+    projectile_launches: Mutex<Vec<Launch>>,
+    projectiles: Mutex<Vec<Projectile>>,
+    /// Boats and minecarts which were placed this tick, they appear with the next tick
+    vehicle_placements: Mutex<Vec<VehiclePlacement>>,
+    vehicles: Mutex<Vec<Vehicle>>,
+    mobs: Mutex<Vec<Mob>>,
+    /// Who rides what, e.g. players in boats
+    passengers: Mutex<Passengers>,
+    /// The entities each player's client knows about, by the id of the client
+    tracked_entities: Mutex<HashMap<usize, HashSet<EntityId>>>,
     // TODO: entities
 }
 
+/// Whether an entity at a position is in one of the simulated chunks
+fn is_simulated(simulated: &HashSet<Vector2<i32>>, position: Vector3<f64>) -> bool {
+    simulated.contains(&Vector2::new(
+        (position.x.floor() as i32) >> 4,
+        (position.z.floor() as i32) >> 4,
+    ))
+}
+
 impl World {
-    pub fn load(level: Level) -> Self {
+    /// Creates the world from its `level.dat`, the seed has to be in there already
+    pub fn load(
+        config: &WorldConfig,
+        dimension_name: String,
+        dimension: Dimension,
+        level: Level,
+        level_data: LevelData,
+    ) -> Self {
         Self {
-            level: Arc::new(Mutex::new(level)),
+            seed: Seed(level_data.seed.unwrap_or_default()),
+            name: config.name.clone(),
+            dimension_name,
+            dimension,
+            level: Arc::new(level),
             current_players: Arc::new(Mutex::new(HashMap::new())),
+            world_age: AtomicI64::new(level_data.time),
+            time_of_day: AtomicI64::new(level_data.day_time),
+            weather: Mutex::new(Weather::from_level_data(&level_data)),
+            level_data: Mutex::new(level_data),
+            spawn_position: AtomicCell::new(Vector3::new(10.0, 120.0, 10.0)),
+            spawn_chunk_radius: config.spawn_chunk_radius,
+            spawn_protection: config.spawn_protection,
+            pvp: config.pvp,
+            player_info_batch: Mutex::new(PlayerInfoBatch::default()),
+            open_block_entities: Mutex::new(HashMap::new()),
+            gravity_checks: Mutex::new(HashSet::new()),
+            falling_blocks: Mutex::new(Vec::new()),
+            block_updates: Mutex::new(HashSet::new()),
+            scheduled_ticks: Mutex::new(Vec::new()),
+            primed_tnt: Mutex::new(Vec::new()),
+            experience_drops: Mutex::new(Vec::new()),
+            experience_orbs: Mutex::new(Vec::new()),
+            item_drops: Mutex::new(Vec::new()),
+            items: Mutex::new(Vec::new()),
+            projectile_launches: Mutex::new(Vec::new()),
+            projectiles: Mutex::new(Vec::new()),
+            vehicle_placements: Mutex::new(Vec::new()),
+            vehicles: Mutex::new(Vec::new()),
+            mobs: Mutex::new(Vec::new()),
+            passengers: Mutex::new(Passengers::default()),
+            tracked_entities: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Advances the world by one tick
+    pub fn tick(&self, server: &Server) {
+        let world_age = self.world_age.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.game_rule_bool(game_rules::DO_DAYLIGHT_CYCLE) {
+            self.time_of_day.fetch_add(1, Ordering::Relaxed);
+        }
+        // Clients advance the time by themselves, this only corrects them once a second
+        if world_age % TICKS_PER_SECOND as i64 == 0 {
+            self.broadcast_packet_all(&self.time_packet());
+        }
+
+        self.tick_weather(server);
+        self.tick_sleeping(server);
+        let simulated = self.simulated_chunks();
+        self.tick_falling_blocks(server, &simulated);
+        self.tick_random_blocks(&simulated);
+        self.tick_furnaces(server, &simulated);
+        self.tick_redstone(server, world_age);
+        self.tick_tnt(server, &simulated);
+        self.tick_experience_orbs(server, &simulated);
+        self.tick_items(server, &simulated);
+        self.tick_projectiles(server, &simulated);
+        self.tick_vehicles(server, &simulated);
+        self.spawn_mobs(server, &simulated, world_age);
+        self.tick_mobs(server, &simulated);
+        self.push_mobs(server, &simulated);
+        self.move_passengers();
+        self.flush_player_info();
+        self.update_tracking();
+        self.unload_unused_chunks(server, world_age);
+
+        let players: Vec<Arc<Player>> = self.current_players.lock().values().cloned().collect();
+        for player in &players {
+            player.tick();
+            player_chunker::send_chunks(self, player);
+        }
+        self.send_light_updates(&players);
+        // Write everything queued this tick in one go
+        for player in &players {
+            player.client.flush();
+        }
+    }
+
+    /// The chunks around the spawn which always stay loaded
+    fn spawn_chunks(&self) -> Vec<Vector2<i32>> {
+        let spawn = self.spawn_position.load();
+        let center = Vector2::new((spawn.x.floor() as i32) >> 4, (spawn.z.floor() as i32) >> 4);
+        let radius = self.spawn_chunk_radius as i32;
+        (-radius..=radius)
+            .flat_map(|x| (-radius..=radius).map(move |z| Vector2::new(center.x + x, center.z + z)))
+            .collect()
+    }
+
+    /// The chunks in the simulation distance of a player. Only there blocks tick randomly and
+    /// entities move, so a big view distance doesn't make ticking more expensive
+    fn simulated_chunks(&self) -> HashSet<Vector2<i32>> {
+        let mut chunks = HashSet::new();
+        for player in self.current_players.lock().values() {
+            let center = player.living_entity.entity.chunk_pos.load();
+            let distance = i32::from(player.simulation_distance());
+            for x in -distance..=distance {
+                for z in -distance..=distance {
+                    chunks.insert(Vector2::new(center.x + x, center.z + z));
+                }
+            }
+        }
+        chunks
+    }
+
+    /// Loads the chunks around the spawn and keeps them loaded
+    pub fn keep_spawn_loaded(&self) {
+        for at in self.spawn_chunks() {
+            self.level.add_ticket(at, TicketType::Spawn);
+            // Starts loading the chunk on the chunk workers, nobody has to wait for it
+            drop(self.level.get_chunk(at));
+        }
+    }
+
+    /// Moves the spawn, the chunks around the new spawn are kept loaded instead
+    pub fn set_spawn_position(&self, position: Vector3<f64>) {
+        let old_chunks = self.spawn_chunks();
+        self.spawn_position.store(position);
+        for at in old_chunks {
+            self.level.remove_ticket(at, TicketType::Spawn);
+        }
+        self.keep_spawn_loaded();
+    }
+
+    /// Whether spawn protection stops the player from changing the block.
+    /// Like vanilla, operators are never stopped and it is off while there are no operators
+    pub fn is_spawn_protected(
+        &self,
+        server: &Server,
+        player: &Player,
+        position: &WorldPosition,
+    ) -> bool {
+        if self.spawn_protection == 0
+            || server.operators.is_empty()
+            || server.operators.level(&player.gameprofile.id).is_some()
+        {
+            return false;
+        }
+        let spawn = self.spawn_position.load();
+        let distance_x = (position.0.x - spawn.x.floor() as i32).unsigned_abs();
+        let distance_z = (position.0.z - spawn.z.floor() as i32).unsigned_abs();
+        distance_x.max(distance_z) <= self.spawn_protection
+    }
+
+    /// The value of a game rule, None if the rule is unknown
+    pub fn game_rule(&self, name: &str) -> Option<GameRuleValue> {
+        self.level_data.lock().game_rules.get(name)
+    }
+
+    /// The value of a boolean game rule, see `game_rules` for their names
+    pub fn game_rule_bool(&self, name: &str) -> bool {
+        self.level_data.lock().game_rules.get_bool(name)
+    }
+
+    /// Whether players can hurt each other in this world, the advanced config can turn it off
+    /// for all worlds
+    pub fn pvp_enabled(&self) -> bool {
+        self.pvp && ADVANCED_CONFIG.get().pvp.enabled
+    }
+
+    /// The value of an integer game rule, see `game_rules` for their names
+    pub fn game_rule_int(&self, name: &str) -> i32 {
+        self.level_data.lock().game_rules.get_int(name)
+    }
+
+    /// Changes a game rule and tells the players in the world about rules their client knows
+    pub fn set_game_rule(
+        &self,
+        server: &Server,
+        name: &str,
+        value: &str,
+    ) -> Result<GameRuleValue, GameRuleError> {
+        let value = self.level_data.lock().game_rules.set(name, value)?;
+        match (name, value) {
+            // Stops or starts the sun on the clients
+            (game_rules::DO_DAYLIGHT_CYCLE, _) => self.broadcast_packet_all(&self.time_packet()),
+            (game_rules::DO_IMMEDIATE_RESPAWN, GameRuleValue::Bool(immediate)) => self
+                .broadcast_packet_all(&CGameEvent::new(
+                    GameEvent::EnabledRespawnScreen,
+                    if immediate { 1.0 } else { 0.0 },
+                )),
+            (game_rules::DO_LIMITED_CRAFTING, GameRuleValue::Bool(limited)) => self
+                .broadcast_packet_all(&CGameEvent::new(
+                    GameEvent::LimitedCrafting,
+                    if limited { 1.0 } else { 0.0 },
+                )),
+            (game_rules::REDUCED_DEBUG_INFO, GameRuleValue::Bool(reduced)) => {
+                // Every client only gets the status of its own player
+                for player in self.current_players.lock().values() {
+                    player.client.send_packet(&CEntityStatus::new(
+                        player.entity_id(),
+                        if reduced { 22 } else { 23 },
+                    ));
+                }
+            }
+            _ => {}
+        }
+        let value_string = value.to_string();
+        let event = GameRuleChangeEvent {
+            world: &self.name,
+            rule: name,
+            value: &value_string,
+        };
+        server
+            .plugins
+            .read()
+            .for_each(|plugin| plugin.on_game_rule_change(&event));
+        Ok(value)
+    }
+
+    pub fn weather(&self) -> WeatherType {
+        self.weather.lock().weather_type()
+    }
+
+    /// Changes the weather for `duration` ticks, for a random duration if None
+    pub fn set_weather(&self, server: &Server, weather: WeatherType, duration: Option<i32>) {
+        let changed = {
+            let mut state = self.weather.lock();
+            let old = state.weather_type();
+            state.set(weather, duration);
+            old != weather
+        };
+        // Clients see the change while the rain fades in or out over the next ticks
+        if changed {
+            self.fire_weather_change(server, weather);
+        }
+    }
+
+    /// Changes the weather by itself and shows clients how it fades
+    fn tick_weather(&self, server: &Server) {
+        let cycle = self.game_rule_bool(game_rules::DO_WEATHER_CYCLE);
+        let (changed, packets) = {
+            let mut weather = self.weather.lock();
+            let before = weather.snapshot();
+            let changed = weather.tick(cycle);
+            (changed, weather.change_packets(&before))
+        };
+        for packet in &packets {
+            self.broadcast_packet_all(packet);
+        }
+        if let Some(weather) = changed {
+            self.fire_weather_change(server, weather);
         }
     }
 
+    fn fire_weather_change(&self, server: &Server, weather: WeatherType) {
+        let event = WeatherChangeEvent {
+            world: &self.name,
+            raining: weather != WeatherType::Clear,
+            thundering: weather == WeatherType::Thunder,
+        };
+        server
+            .plugins
+            .read()
+            .for_each(|plugin| plugin.on_weather_change(&event));
+    }
+
+    /// The seed as clients get it, they only need it for biome blending and must not learn the real one.
+    /// Like vanilla this is the start of the SHA-256 hash of the seed
+    pub fn hashed_seed(&self) -> i64 {
+        let hash = Sha256::digest(self.seed.0.to_le_bytes());
+        i64::from_le_bytes(hash[..8].try_into().unwrap())
+    }
+
+    /// Tells clients the current time
+    pub fn time_packet(&self) -> CUpdateTime {
+        CUpdateTime::new(
+            self.world_age.load(Ordering::Relaxed),
+            self.time_of_day.load(Ordering::Relaxed),
+            self.game_rule_bool(game_rules::DO_DAYLIGHT_CYCLE),
+        )
+    }
+
+    /// How much darker the sky light is than during the day, from 0 at noon to 11 at midnight.
+    /// Rain and thunder darken it further, the formula is the one of vanilla
+    pub fn sky_darkness(&self) -> u8 {
+        let day =
+            (self.time_of_day.load(Ordering::Relaxed) as f64 / 24000.0 - 0.25).rem_euclid(1.0);
+        let sun_angle = (day * 2.0 + (0.5 - (day * std::f64::consts::PI).cos() / 2.0)) / 3.0;
+        let mut brightness =
+            1.0 - (1.0 - ((sun_angle * std::f64::consts::TAU).cos() * 2.0 + 0.5)).clamp(0.0, 1.0);
+        let (rain, thunder) = self.weather.lock().levels();
+        brightness *= 1.0 - f64::from(rain) * 5.0 / 16.0;
+        brightness *= 1.0 - f64::from(thunder) * 5.0 / 16.0;
+        ((1.0 - brightness) * 11.0) as u8
+    }
+
+    /// Writes the time and the game rules of the world into its `level.dat`
+    pub fn save_level_data(&self) {
+        let mut level_data = self.level_data.lock().clone();
+        level_data.time = self.world_age.load(Ordering::Relaxed);
+        level_data.day_time = self.time_of_day.load(Ordering::Relaxed);
+        self.weather.lock().write_level_data(&mut level_data);
+        if let Err(err) = self.level.write_level_data(&level_data) {
+            log::error!(
+                "Failed to save the level data of world {}: {err}",
+                self.name
+            );
+        }
+    }
+
+    /// Sends the light which changed since the last tick to the players seeing the chunks
+    fn send_light_updates(&self, players: &[Arc<Player>]) {
+        let updates = self.level.take_light_updates();
+        if updates.is_empty() {
+            return;
+        }
+        let views: Vec<_> = players
+            .iter()
+            .filter_map(|player| Some((player, (*player.chunk_view.lock())?)))
+            .collect();
+        for (at, sections) in updates {
+            let Some(chunk) = self.level.loaded_chunk(at) else {
+                continue;
+            };
+            let chunk = chunk.read();
+            let packet = CUpdateLight::new(&chunk, sections);
+            for (player, view) in &views {
+                if view.contains(at) {
+                    player.client.send_packet(&packet);
+                }
+            }
+        }
+    }
+
+    /// Unloads the chunks nobody needs anymore once the configured interval passed
+    fn unload_unused_chunks(&self, server: &Server, world_age: i64) {
+        let config = &ADVANCED_CONFIG.get().chunk_unloading;
+        if !config.enabled || world_age % (config.interval * TICKS_PER_SECOND) as i64 != 0 {
+            return;
+        }
+        let level = self.level.clone();
+        let name = self.name.clone();
+        // Changed chunks stay loaded while a backup is written, the region files must not change
+        let save = config.save && !server.backups.is_running();
+        // Saving the chunks blocks
+        tokio::task::spawn_blocking(move || {
+            let unloaded = level.unload_unused_chunks(save);
+            if unloaded > 0 {
+                log::debug!("Unloaded {unloaded} chunks of world {name}");
+            }
+        });
+    }
+
     /// Broadcasts a packet to all connected players within the world.
     ///
     /// Sends the specified packet to every player currently logged in to the server.
@@ -76,7 +535,13 @@ impl World {
         }
     }
 
-    pub async fn spawn_player(&self, base_config: &BasicConfiguration, player: Arc<Player>) {
+    /// Logs a new player into this world, `dimension_names` are the dimensions of all worlds
+    pub async fn spawn_player(
+        &self,
+        base_config: &BasicConfiguration,
+        player: Arc<Player>,
+        dimension_names: &[&str],
+    ) {
         // This code follows the vanilla packet order
         let entity_id = player.entity_id();
         let gamemode = player.gamemode.load();
@@ -86,172 +551,111 @@ impl World {
         player.client.send_packet(&CLogin::new(
             entity_id,
             base_config.hardcore,
-            &["minecraft:overworld"],
+            dimension_names,
             base_config.max_players.into(),
-            base_config.view_distance.into(), //  TODO: view distance
-            base_config.simulation_distance.into(), // TODO: sim view dinstance
-            false,
-            false,
-            false,
-            0.into(),
-            "minecraft:overworld",
-            0, // seed
+            i32::from(player.view_distance()).into(),
+            i32::from(player.simulation_distance()).into(),
+            self.game_rule_bool(game_rules::REDUCED_DEBUG_INFO),
+            !self.game_rule_bool(game_rules::DO_IMMEDIATE_RESPAWN),
+            self.game_rule_bool(game_rules::DO_LIMITED_CRAFTING),
+            self.dimension.type_id().into(),
+            &self.dimension_name,
+            self.hashed_seed(),
             gamemode.to_u8().unwrap(),
             base_config.default_gamemode.to_i8().unwrap(),
             false,
             false,
             None,
             0.into(),
-            false,
-        ));
-        dbg!("sending abilities");
-        // player abilities
-        // TODO: this is for debug purpose, remove later
-        player
-            .client
-            .send_packet(&CPlayerAbilities::new(0x02, 0.4, 0.1));
-
-        // teleport
-        let x = 10.0;
-        let y = 120.0;
-        let z = 10.0;
-        let yaw = 10.0;
-        let pitch = 10.0;
-        player.teleport(x, y, z, 10.0, 10.0);
-        let gameprofile = &player.gameprofile;
-        // first send info update to our new player, So he can see his Skin
-        // also send his info to everyone else
-        self.broadcast_packet_all(&CPlayerInfoUpdate::new(
-            0x01 | 0x08,
-            &[pumpkin_protocol::client::play::Player {
-                uuid: gameprofile.id,
-                actions: vec![
-                    PlayerAction::AddPlayer {
-                        name: &gameprofile.name,
-                        properties: &gameprofile.properties,
-                    },
-                    PlayerAction::UpdateListed(true),
-                ],
-            }],
+            secure_chat_enforced(),
         ));
+        // `Server::add_player` put the player where they left or at the spawn
+        let position = player.living_entity.entity.pos.load();
+        self.spawn_in(player.clone(), position).await;
+        player.send_player_data();
+    }
 
-        // here we send all the infos of already joined players
-        let mut entries = Vec::new();
+    /// Moves a player who just arrived in this world to `position`, sends them the player list
+    /// and the chunks around them. They see the entities close to them with the next tick
+    pub async fn spawn_in(&self, player: Arc<Player>, position: Vector3<f64>) {
+        let entity_id = player.entity_id();
+        player.send_abilities_update();
+
+        // teleport, players keep looking where they did
+        let entity = &player.living_entity.entity;
+        player.teleport(
+            position.x,
+            position.y,
+            position.z,
+            entity.yaw.load(),
+            entity.pitch.load(),
+        );
+        // first send info update to our new player, So he can see his Skin
+        // here we also send all the infos of already joined players
         {
             let current_players = self.current_players.lock();
-            for (_, playerr) in current_players
-                .iter()
-                .filter(|(c, _)| **c != player.client.id)
-            {
-                let gameprofile = &playerr.gameprofile;
-                entries.push(pumpkin_protocol::client::play::Player {
-                    uuid: gameprofile.id,
-                    actions: vec![
-                        PlayerAction::AddPlayer {
-                            name: &gameprofile.name,
-                            properties: &gameprofile.properties,
-                        },
-                        PlayerAction::UpdateListed(true),
-                    ],
-                })
-            }
+            let others: Vec<_> = current_players
+                .values()
+                .filter(|existing| existing.client.id != player.client.id)
+                .collect();
+            let own_session = player.chat_session();
+            let sessions: Vec<_> = others.iter().map(|other| other.chat_session()).collect();
+            let mut entries = vec![player_info::info_entry(&player, own_session.as_deref())];
+            entries.extend(
+                others
+                    .iter()
+                    .zip(&sessions)
+                    .map(|(other, session)| player_info::info_entry(other, session.as_deref())),
+            );
             player
                 .client
-                .send_packet(&CPlayerInfoUpdate::new(0x01 | 0x08, &entries));
+                .send_packet(&CPlayerInfoUpdate::new(player_info::INFO_ACTIONS, &entries));
         }
 
-        let gameprofile = &player.gameprofile;
-
-        // spawn player for every client
-        self.broadcast_packet_expect(
-            &[player.client.id],
-            // TODO: add velo
-            &CSpawnEntity::new(
-                entity_id.into(),
-                gameprofile.id,
-                (EntityType::Player as i32).into(),
-                x,
-                y,
-                z,
-                pitch,
-                yaw,
-                yaw,
-                0.into(),
-                0.0,
-                0.0,
-                0.0,
-            ),
-        );
-        // spawn players for our client
-        let token = player.client.id;
-        for (_, existing_player) in self.current_players.lock().iter().filter(|c| c.0 != &token) {
-            let entity = &existing_player.living_entity.entity;
-            let pos = entity.pos.load();
-            let gameprofile = &existing_player.gameprofile;
-            player.client.send_packet(&CSpawnEntity::new(
-                existing_player.entity_id().into(),
-                gameprofile.id,
-                (EntityType::Player as i32).into(),
-                pos.x,
-                pos.y,
-                pos.z,
-                entity.yaw.load(),
-                entity.pitch.load(),
-                entity.head_yaw.load(),
-                0.into(),
-                0.0,
-                0.0,
-                0.0,
-            ))
+        // The player sees their own skin layers and main arm in third person
+        for packet in player.model_packets() {
+            player.client.send_packet(&packet);
         }
-        // entity meta data
-        // set skin parts
-        if let Some(config) = player.client.config.lock().as_ref() {
-            let packet = CSetEntityMetadata::new(
-                entity_id.into(),
-                Metadata::new(17, VarInt(0), config.skin_parts),
-            );
-            self.broadcast_packet_all(&packet)
+
+        player.client.send_packet(&self.time_packet());
+        for packet in self.weather.lock().join_packets() {
+            player.client.send_packet(&packet);
         }
 
+        // The client forgot the entities it saw before, e.g. when respawning
+        self.tracked_entities
+            .lock()
+            .insert(player.client.id, HashSet::new());
+        // Everyone else gets the info with the next tick, the players close by see them then
+        self.player_info_batch.lock().add(player.clone());
+
         // Start waiting for level chunks, Sets the "Loading Terrain" screen
         player
             .client
             .send_packet(&CGameEvent::new(GameEvent::StartWaitingChunks, 0.0));
 
         // Spawn in initial chunks
-        player_chunker::player_join(self, player.clone()).await;
+        player_chunker::player_join(self, player.clone());
     }
 
-    async fn spawn_world_chunks(&self, client: &Client, chunks: Vec<Vector2<i32>>, distance: i32) {
-        let inst = std::time::Instant::now();
-        let (sender, mut chunk_receiver) = mpsc::channel(distance as usize);
+    /// Changes a block and shows the change to all players
+    pub fn set_block(&self, position: &WorldPosition, block: BlockId) {
+        self.level.set_block(position, block);
+        self.broadcast_packet_all(&CBlockUpdate::new(
+            position,
+            block.get_id_mojang_repr().into(),
+        ));
+        self.queue_gravity_check(position);
+        self.queue_block_updates(position);
+    }
 
-        let level = self.level.clone();
-        let closed = client.closed.load(std::sync::atomic::Ordering::Relaxed);
-        let chunks = Arc::new(chunks);
-        tokio::task::spawn_blocking(move || level.lock().fetch_chunks(&chunks, sender, closed));
-
-        while let Some(chunk_data) = chunk_receiver.recv().await {
-            // dbg!(chunk_pos);
-            #[cfg(debug_assertions)]
-            if chunk_data.position == (0, 0).into() {
-                use pumpkin_protocol::bytebuf::ByteBuffer;
-                let mut test = ByteBuffer::empty();
-                CChunkData(&chunk_data).write(&mut test);
-                let len = test.buf().len();
-                log::debug!(
-                    "Chunk packet size: {}B {}KB {}MB",
-                    len,
-                    len / 1024,
-                    len / (1024 * 1024)
-                );
-            }
-            if !client.closed.load(std::sync::atomic::Ordering::Relaxed) {
-                client.send_packet(&CChunkData(&chunk_data));
-            }
-        }
-        dbg!("DONE CHUNKS", inst.elapsed());
+    /// Whether a player stands in the block, spectators don't count
+    pub fn is_occupied(&self, position: &WorldPosition) -> bool {
+        let block = BoundingBox::from_block(position);
+        self.current_players.lock().values().any(|player| {
+            player.gamemode.load() != GameMode::Spectator
+                && player.bounding_box().intersects(&block)
+        })
     }
 
     /// Gets a Player by entity id
@@ -279,19 +683,25 @@ impl World {
     }
 
     pub fn remove_player(&self, player: &Player) {
-        self.current_players
+        // The player may have disconnected while switching worlds
+        if self
+            .current_players
             .lock()
             .remove(&player.client.id)
-            .unwrap();
-        let uuid = player.gameprofile.id;
-        self.broadcast_packet_expect(
-            &[player.client.id],
-            &CRemovePlayerInfo::new(1.into(), &[uuid]),
-        );
-        self.remove_entity(&player.living_entity.entity);
+            .is_none()
+        {
+            return;
+        }
+        self.forget_rides(player.entity_id());
+        // The bed gets free for the others
+        self.leave_bed(player, true);
+        player_chunker::player_leave(self, player);
+        self.tracked_entities.lock().remove(&player.client.id);
+        // Other players get notified with the next tick
+        self.player_info_batch.lock().remove(player);
     }
 
     pub fn remove_entity(&self, entity: &Entity) {
-        self.broadcast_packet_all(&CRemoveEntities::new(&[entity.entity_id.into()]))
+        self.untrack(entity.entity_id);
     }
 }
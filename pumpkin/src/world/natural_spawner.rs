@@ -0,0 +1,426 @@
+use std::collections::HashSet;
+
+use pumpkin_config::BASIC_CONFIG;
+use pumpkin_core::{
+    math::{position::WorldPosition, vector2::Vector2, vector3::Vector3},
+    Difficulty, GameMode,
+};
+use pumpkin_entity::entity_type::EntityType;
+use pumpkin_world::{biome::Biome, block::BlockId, game_rules, WORLD_LOWEST_Y};
+use rand::{seq::SliceRandom, Rng};
+
+use crate::server::Server;
+
+use super::{mob::Mob, physics::Physics, weather::WeatherType, World};
+
+/// The mob caps are for this many chunks, the area a single player simulates in vanilla
+const CAP_CHUNKS: usize = 17 * 17;
+/// Mobs don't spawn closer than this to a player or the world spawn
+const MIN_SPAWN_DISTANCE: f64 = 24.0;
+/// Mobs don't spawn further away than this from a player, they would despawn right away
+const MAX_SPAWN_DISTANCE: f64 = 128.0;
+/// The mobs of a group spawn at most this many blocks away from each other
+const GROUP_SPREAD: i32 = 5;
+/// Animals need at least this much light, monsters at most a random light level up to this
+const MIN_ANIMAL_LIGHT: u8 = 9;
+const MAX_MONSTER_LIGHT: u8 = 7;
+/// The sky darkness during thunder, monsters can spawn in the day then
+const THUNDER_SKY_DARKNESS: u8 = 10;
+/// How many zombies spawn riding a chicken. In vanilla 5% of the baby zombies do, which are 5% of
+/// all zombies
+const JOCKEY_CHANCE: f64 = 0.0025;
+
+/// The kinds of mobs which spawn by themselves, every category has its own mob cap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpawnCategory {
+    Monster,
+    Creature,
+}
+
+impl SpawnCategory {
+    const ALL: [Self; 2] = [Self::Monster, Self::Creature];
+
+    /// The category mobs of the type spawn in, None if they don't spawn by themselves
+    pub const fn of(entity_type: EntityType) -> Option<Self> {
+        match entity_type {
+            EntityType::Creeper
+            | EntityType::Enderman
+            | EntityType::Husk
+            | EntityType::Skeleton
+            | EntityType::Spider
+            | EntityType::Stray
+            | EntityType::Witch
+            | EntityType::Zombie
+            | EntityType::ZombieVillager => Some(Self::Monster),
+            EntityType::Chicken
+            | EntityType::Cow
+            | EntityType::Pig
+            | EntityType::Rabbit
+            | EntityType::Sheep
+            | EntityType::Wolf => Some(Self::Creature),
+            EntityType::Arrow
+            | EntityType::Boat
+            | EntityType::EnderPearl
+            | EntityType::ExperienceOrb
+            | EntityType::FallingBlock
+            | EntityType::Item
+            | EntityType::Minecart
+            | EntityType::Snowball
+            | EntityType::Tnt
+            | EntityType::Player => None,
+        }
+    }
+
+    /// How many mobs of the category there can be for every `CAP_CHUNKS` simulated chunks
+    const fn cap(self) -> usize {
+        match self {
+            Self::Monster => 70,
+            Self::Creature => 10,
+        }
+    }
+
+    /// Ticks between the spawn cycles, animals stay so they spawn rarely
+    const fn interval(self) -> i64 {
+        match self {
+            Self::Monster => 1,
+            Self::Creature => 400,
+        }
+    }
+
+    /// Whether mobs of the category despawn once no player is close
+    pub const fn despawns(self) -> bool {
+        matches!(self, Self::Monster)
+    }
+}
+
+/// A mob which can spawn in a biome, mobs with a higher weight are picked more often
+struct SpawnEntry {
+    entity_type: EntityType,
+    weight: u32,
+    min_group: u32,
+    max_group: u32,
+}
+
+const fn entry(entity_type: EntityType, weight: u32, min_group: u32, max_group: u32) -> SpawnEntry {
+    SpawnEntry {
+        entity_type,
+        weight,
+        min_group,
+        max_group,
+    }
+}
+
+// The spawn lists are the vanilla ones, without the mobs the Server doesn't know yet
+const MONSTERS: [SpawnEntry; 7] = [
+    entry(EntityType::Spider, 100, 4, 4),
+    entry(EntityType::Zombie, 95, 4, 4),
+    entry(EntityType::ZombieVillager, 5, 1, 1),
+    entry(EntityType::Skeleton, 100, 4, 4),
+    entry(EntityType::Creeper, 100, 4, 4),
+    entry(EntityType::Enderman, 10, 1, 4),
+    entry(EntityType::Witch, 5, 1, 1),
+];
+const DESERT_MONSTERS: [SpawnEntry; 8] = [
+    entry(EntityType::Spider, 100, 4, 4),
+    entry(EntityType::Zombie, 19, 4, 4),
+    entry(EntityType::ZombieVillager, 1, 1, 1),
+    entry(EntityType::Husk, 80, 4, 4),
+    entry(EntityType::Skeleton, 100, 4, 4),
+    entry(EntityType::Creeper, 100, 4, 4),
+    entry(EntityType::Enderman, 10, 1, 4),
+    entry(EntityType::Witch, 5, 1, 1),
+];
+const SNOWY_MONSTERS: [SpawnEntry; 8] = [
+    entry(EntityType::Spider, 100, 4, 4),
+    entry(EntityType::Zombie, 95, 4, 4),
+    entry(EntityType::ZombieVillager, 5, 1, 1),
+    entry(EntityType::Skeleton, 20, 4, 4),
+    entry(EntityType::Stray, 80, 4, 4),
+    entry(EntityType::Creeper, 100, 4, 4),
+    entry(EntityType::Enderman, 10, 1, 4),
+    entry(EntityType::Witch, 5, 1, 1),
+];
+const END_MONSTERS: [SpawnEntry; 1] = [entry(EntityType::Enderman, 10, 4, 4)];
+const FARM_ANIMALS: [SpawnEntry; 4] = [
+    entry(EntityType::Sheep, 12, 4, 4),
+    entry(EntityType::Pig, 10, 4, 4),
+    entry(EntityType::Chicken, 10, 4, 4),
+    entry(EntityType::Cow, 8, 4, 4),
+];
+const FOREST_ANIMALS: [SpawnEntry; 5] = [
+    entry(EntityType::Sheep, 12, 4, 4),
+    entry(EntityType::Pig, 10, 4, 4),
+    entry(EntityType::Chicken, 10, 4, 4),
+    entry(EntityType::Cow, 8, 4, 4),
+    entry(EntityType::Wolf, 5, 4, 4),
+];
+const TAIGA_ANIMALS: [SpawnEntry; 6] = [
+    entry(EntityType::Sheep, 12, 4, 4),
+    entry(EntityType::Pig, 10, 4, 4),
+    entry(EntityType::Chicken, 10, 4, 4),
+    entry(EntityType::Cow, 8, 4, 4),
+    entry(EntityType::Wolf, 8, 4, 4),
+    entry(EntityType::Rabbit, 4, 2, 3),
+];
+const SNOWY_ANIMALS: [SpawnEntry; 1] = [entry(EntityType::Rabbit, 10, 2, 3)];
+const DESERT_ANIMALS: [SpawnEntry; 1] = [entry(EntityType::Rabbit, 4, 2, 3)];
+
+/// The mobs of a category which spawn in a biome
+fn spawn_list(biome: Biome, category: SpawnCategory) -> &'static [SpawnEntry] {
+    match (category, biome) {
+        (SpawnCategory::Monster, Biome::Desert) => &DESERT_MONSTERS,
+        (SpawnCategory::Monster, Biome::SnowyPlains) => &SNOWY_MONSTERS,
+        (SpawnCategory::Monster, Biome::TheEnd) => &END_MONSTERS,
+        // TODO: The mobs of the nether
+        (_, Biome::NetherWastes) => &[],
+        (SpawnCategory::Monster, _) => &MONSTERS,
+        (
+            SpawnCategory::Creature,
+            Biome::Plains | Biome::BirchForest | Biome::Savanna | Biome::Jungle | Biome::Swamp,
+        ) => &FARM_ANIMALS,
+        (SpawnCategory::Creature, Biome::Forest) => &FOREST_ANIMALS,
+        (SpawnCategory::Creature, Biome::Taiga | Biome::SnowyTaiga) => &TAIGA_ANIMALS,
+        (SpawnCategory::Creature, Biome::SnowyPlains | Biome::SnowySlopes) => &SNOWY_ANIMALS,
+        (SpawnCategory::Creature, Biome::Desert) => &DESERT_ANIMALS,
+        (SpawnCategory::Creature, _) => &[],
+    }
+}
+
+/// Picks a random entry, by weight
+fn pick_entry(entries: &[SpawnEntry]) -> Option<&SpawnEntry> {
+    let total: u32 = entries.iter().map(|entry| entry.weight).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = rand::thread_rng().gen_range(0..total);
+    entries.iter().find(|entry| {
+        if roll < entry.weight {
+            return true;
+        }
+        roll -= entry.weight;
+        false
+    })
+}
+
+/// Whether an animal of the type spawns on the block, most only spawn on grass
+fn animal_spawns_on(entity_type: EntityType, block: &BlockId) -> bool {
+    let on = |registry_id: &str| block.is_in(registry_id);
+    match entity_type {
+        EntityType::Rabbit => {
+            on("minecraft:grass_block")
+                || on("minecraft:snow")
+                || on("minecraft:snow_block")
+                || on("minecraft:sand")
+        }
+        EntityType::Wolf => {
+            on("minecraft:grass_block")
+                || on("minecraft:snow")
+                || on("minecraft:podzol")
+                || on("minecraft:coarse_dirt")
+        }
+        _ => on("minecraft:grass_block"),
+    }
+}
+
+impl World {
+    /// Lets mobs spawn by themselves in the chunks players simulate, until the mob cap of
+    /// their category is reached. Monsters only spawn in the dark and never on peaceful
+    pub(super) fn spawn_mobs(
+        &self,
+        server: &Server,
+        simulated: &HashSet<Vector2<i32>>,
+        world_age: i64,
+    ) {
+        if simulated.is_empty() || !self.game_rule_bool(game_rules::DO_MOB_SPAWNING) {
+            return;
+        }
+        let players: Vec<_> = self
+            .current_players
+            .lock()
+            .values()
+            .filter(|player| player.gamemode.load() != GameMode::Spectator)
+            .map(|player| player.living_entity.entity.pos.load())
+            .collect();
+        let mut rng = rand::thread_rng();
+        for category in SpawnCategory::ALL {
+            if world_age % category.interval() != 0
+                || (category == SpawnCategory::Monster
+                    && BASIC_CONFIG.get().default_difficulty == Difficulty::Peaceful)
+            {
+                continue;
+            }
+            let cap = category.cap() * simulated.len() / CAP_CHUNKS;
+            let mut count = self.mob_count(category);
+            // The chunks take turns in a random order, so the ones which come first don't get
+            // all the mobs
+            let mut chunks: Vec<_> = simulated.iter().copied().collect();
+            chunks.shuffle(&mut rng);
+            for chunk in chunks {
+                if count >= cap {
+                    break;
+                }
+                count += self.spawn_group(server, chunk, category, &players, cap - count);
+            }
+        }
+    }
+
+    /// Tries to spawn a group of mobs at a random position in the chunk, returns how many
+    /// spawned
+    fn spawn_group(
+        &self,
+        server: &Server,
+        chunk: Vector2<i32>,
+        category: SpawnCategory,
+        players: &[Vector3<f64>],
+        limit: usize,
+    ) -> usize {
+        let mut rng = rand::thread_rng();
+        let Some(top) = self.level.top_y(chunk) else {
+            return 0;
+        };
+        let y = rng.gen_range(i32::from(WORLD_LOWEST_Y)..=top);
+        let start = WorldPosition(Vector3::new(
+            chunk.x * 16 + rng.gen_range(0..16),
+            y,
+            chunk.z * 16 + rng.gen_range(0..16),
+        ));
+        if self
+            .level
+            .get_block(&start)
+            .map_or(true, |block| block.has_collision())
+        {
+            return 0;
+        }
+        let Some(biome) = self.level.get_biome(&start) else {
+            return 0;
+        };
+        let Some(entry) = pick_entry(spawn_list(biome, category)) else {
+            return 0;
+        };
+        let group = rng.gen_range(entry.min_group..=entry.max_group) as usize;
+        let (mut x, mut z) = (start.0.x, start.0.z);
+        let mut spawned = 0;
+        for _ in 0..group.min(limit) {
+            x += rng.gen_range(0..=GROUP_SPREAD) - rng.gen_range(0..=GROUP_SPREAD);
+            z += rng.gen_range(0..=GROUP_SPREAD) - rng.gen_range(0..=GROUP_SPREAD);
+            let position = WorldPosition(Vector3::new(x, y, z));
+            if !self.can_spawn_at(entry.entity_type, category, &position, players) {
+                continue;
+            }
+            let center = Vector3::new(f64::from(x) + 0.5, f64::from(y), f64::from(z) + 0.5);
+            let yaw = rng.gen_range(0.0..360.0);
+            let mob = Mob::new(server, entry.entity_type, center, yaw);
+            if matches!(
+                entry.entity_type,
+                EntityType::Zombie | EntityType::Husk | EntityType::ZombieVillager
+            ) && rng.gen_bool(JOCKEY_CHANCE)
+            {
+                let chicken = Mob::new(server, EntityType::Chicken, center, yaw);
+                let (rider, vehicle) = (mob.entity_id(), chicken.entity_id());
+                self.add_mob(chicken);
+                self.add_mob(mob);
+                self.start_riding(rider, vehicle);
+            } else {
+                self.add_mob(mob);
+            }
+            spawned += 1;
+        }
+        spawned
+    }
+
+    /// Whether a mob can spawn with its feet in the block, like in vanilla
+    fn can_spawn_at(
+        &self,
+        entity_type: EntityType,
+        category: SpawnCategory,
+        position: &WorldPosition,
+        players: &[Vector3<f64>],
+    ) -> bool {
+        let center = Vector3::new(
+            f64::from(position.0.x) + 0.5,
+            f64::from(position.0.y),
+            f64::from(position.0.z) + 0.5,
+        );
+        let distance_to = |other: &Vector3<f64>| other.sub(&center).length_squared();
+        let Some(closest_player) = players.iter().map(distance_to).min_by(f64::total_cmp) else {
+            return false;
+        };
+        if closest_player < MIN_SPAWN_DISTANCE * MIN_SPAWN_DISTANCE
+            || closest_player > MAX_SPAWN_DISTANCE * MAX_SPAWN_DISTANCE
+            || distance_to(&self.spawn_position.load()) < MIN_SPAWN_DISTANCE * MIN_SPAWN_DISTANCE
+        {
+            return false;
+        }
+        // The biome could be another one than where the group started
+        let Some(biome) = self.level.get_biome(position) else {
+            return false;
+        };
+        if !spawn_list(biome, category)
+            .iter()
+            .any(|entry| entry.entity_type == entity_type)
+        {
+            return false;
+        }
+
+        let below = WorldPosition(position.0.add(&Vector3::new(0, -1, 0)));
+        let Some(ground) = self.level.get_block(&below) else {
+            return false;
+        };
+        let fits = match category {
+            SpawnCategory::Monster => {
+                ground.has_collision()
+                    && !ground.is_in("minecraft:bedrock")
+                    && !ground.is_in("minecraft:barrier")
+            }
+            SpawnCategory::Creature => animal_spawns_on(entity_type, &ground),
+        };
+        if !fits || !self.has_room_for(entity_type, position) {
+            return false;
+        }
+
+        let Some((sky_light, block_light)) = self.level.get_light_levels(position) else {
+            return false;
+        };
+        match category {
+            SpawnCategory::Monster => self.is_dark_enough(sky_light, block_light),
+            SpawnCategory::Creature => sky_light.max(block_light) >= MIN_ANIMAL_LIGHT,
+        }
+    }
+
+    /// Whether the blocks where the mob would be are free, neither solid nor fluids
+    fn has_room_for(&self, entity_type: EntityType, position: &WorldPosition) -> bool {
+        let physics = Physics::mob(entity_type);
+        let half_width = physics.width / 2.0;
+        let min_x = (f64::from(position.0.x) + 0.5 - half_width).floor() as i32;
+        let max_x = (f64::from(position.0.x) + 0.5 + half_width).ceil() as i32;
+        let min_z = (f64::from(position.0.z) + 0.5 - half_width).floor() as i32;
+        let max_z = (f64::from(position.0.z) + 0.5 + half_width).ceil() as i32;
+        let max_y = position.0.y + physics.height.ceil() as i32;
+        (min_x..max_x).all(|x| {
+            (position.0.y..max_y).all(|y| {
+                (min_z..max_z).all(|z| {
+                    self.level
+                        .get_block(&WorldPosition(Vector3::new(x, y, z)))
+                        .is_some_and(|block| {
+                            !block.has_collision() && block.category() != Some("minecraft:liquid")
+                        })
+                })
+            })
+        })
+    }
+
+    /// Whether it is dark enough for monsters. Like in vanilla there is a random light level
+    /// they spawn below, the sky light only counts at night or in a thunderstorm
+    fn is_dark_enough(&self, sky_light: u8, block_light: u8) -> bool {
+        let mut rng = rand::thread_rng();
+        if sky_light > rng.gen_range(0..32) || block_light > 0 {
+            return false;
+        }
+        let darkness = if self.weather() == WeatherType::Thunder {
+            THUNDER_SKY_DARKNESS
+        } else {
+            self.sky_darkness()
+        };
+        sky_light.saturating_sub(darkness) <= rng.gen_range(0..=MAX_MONSTER_LIGHT)
+    }
+}
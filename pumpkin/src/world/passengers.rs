@@ -0,0 +1,210 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use pumpkin_core::math::vector3::Vector3;
+use pumpkin_entity::EntityId;
+use pumpkin_protocol::{client::play::CSetPassengers, VarInt};
+
+use crate::{client::Client, entity::player::Player};
+
+use super::World;
+
+/// Who rides what. Every entity rides at most one other entity, which may ride yet another one,
+/// e.g. a player in a boat or a zombie on a chicken
+#[derive(Default)]
+pub(super) struct Passengers {
+    /// The passengers of every vehicle in the order they got on, the first one steers
+    riders: HashMap<EntityId, Vec<EntityId>>,
+    /// The vehicle every passenger rides
+    vehicles: HashMap<EntityId, EntityId>,
+}
+
+impl Passengers {
+    pub fn vehicle_of(&self, passenger: EntityId) -> Option<EntityId> {
+        self.vehicles.get(&passenger).copied()
+    }
+
+    pub fn passengers_of(&self, vehicle: EntityId) -> &[EntityId] {
+        self.riders.get(&vehicle).map_or(&[], Vec::as_slice)
+    }
+
+    /// Whether the entity rides something or is ridden
+    pub fn contains(&self, entity: EntityId) -> bool {
+        self.vehicles.contains_key(&entity) || self.riders.contains_key(&entity)
+    }
+
+    /// Whether `entity` rides `vehicle`, right on it or on top of its passengers
+    fn is_on(&self, entity: EntityId, vehicle: EntityId) -> bool {
+        let mut current = entity;
+        while let Some(next) = self.vehicle_of(current) {
+            if next == vehicle {
+                return true;
+            }
+            current = next;
+        }
+        false
+    }
+
+    /// Puts `passenger` onto `vehicle`. Returns false if it rides something already or the
+    /// vehicle rides it, an entity can't carry itself
+    fn mount(&mut self, passenger: EntityId, vehicle: EntityId) -> bool {
+        if passenger == vehicle
+            || self.vehicles.contains_key(&passenger)
+            || self.is_on(vehicle, passenger)
+        {
+            return false;
+        }
+        self.vehicles.insert(passenger, vehicle);
+        self.riders.entry(vehicle).or_default().push(passenger);
+        true
+    }
+
+    /// Takes `passenger` off its vehicle, returns the vehicle
+    fn dismount(&mut self, passenger: EntityId) -> Option<EntityId> {
+        let vehicle = self.vehicles.remove(&passenger)?;
+        if let Some(riders) = self.riders.get_mut(&vehicle) {
+            riders.retain(|rider| *rider != passenger);
+            if riders.is_empty() {
+                self.riders.remove(&vehicle);
+            }
+        }
+        Some(vehicle)
+    }
+
+    /// Forgets an entity which is gone, its passengers ride nothing anymore. Returns the vehicle
+    /// it rode
+    fn remove(&mut self, entity: EntityId) -> Option<EntityId> {
+        let vehicle = self.dismount(entity);
+        for passenger in self.riders.remove(&entity).unwrap_or_default() {
+            self.vehicles.remove(&passenger);
+        }
+        vehicle
+    }
+
+    /// The vehicles with their passengers, every vehicle before the ones riding it. Moving the
+    /// passengers in this order moves the ones on top along with the ones below
+    fn in_riding_order(&self) -> Vec<(EntityId, Vec<EntityId>)> {
+        let mut queue: VecDeque<EntityId> = self
+            .riders
+            .keys()
+            .filter(|vehicle| !self.vehicles.contains_key(vehicle))
+            .copied()
+            .collect();
+        let mut order = Vec::new();
+        while let Some(vehicle) = queue.pop_front() {
+            if let Some(passengers) = self.riders.get(&vehicle) {
+                queue.extend(passengers);
+                order.push((vehicle, passengers.clone()));
+            }
+        }
+        order
+    }
+}
+
+impl World {
+    /// The entity an entity rides, None if it rides nothing
+    pub fn vehicle_of(&self, passenger: EntityId) -> Option<EntityId> {
+        self.passengers.lock().vehicle_of(passenger)
+    }
+
+    /// The passengers of an entity, the first one steers
+    pub fn passengers_of(&self, vehicle: EntityId) -> Vec<EntityId> {
+        self.passengers.lock().passengers_of(vehicle).to_vec()
+    }
+
+    /// The passenger who steers a vehicle
+    pub fn driver_of(&self, vehicle: EntityId) -> Option<EntityId> {
+        self.passengers
+            .lock()
+            .passengers_of(vehicle)
+            .first()
+            .copied()
+    }
+
+    /// Lets an entity ride another one and tells the players who see it. Returns false if it
+    /// rides something already or the other one rides it
+    pub fn start_riding(&self, passenger: EntityId, vehicle: EntityId) -> bool {
+        if !self.passengers.lock().mount(passenger, vehicle) {
+            return false;
+        }
+        self.broadcast_passengers(vehicle);
+        true
+    }
+
+    /// Gets an entity off what it rides and tells the players who see it. Returns what it rode
+    pub fn stop_riding(&self, passenger: EntityId) -> Option<EntityId> {
+        let vehicle = self.passengers.lock().dismount(passenger)?;
+        self.broadcast_passengers(vehicle);
+        Some(vehicle)
+    }
+
+    /// Takes a player off what they ride. Returns where they get off, on top of it, None if they
+    /// ride nothing
+    pub fn leave_vehicle(&self, player: &Player) -> Option<Vector3<f64>> {
+        let vehicle = self.stop_riding(player.entity_id())?;
+        // They get out on top, like out of a minecart in a tunnel
+        self.vehicle_top(vehicle).or_else(|| self.mob_top(vehicle))
+    }
+
+    /// Called once an entity is gone. It stops riding, and clients let its passengers get off
+    /// once they see it vanish
+    pub(super) fn forget_rides(&self, entity: EntityId) {
+        let vehicle = self.passengers.lock().remove(entity);
+        if let Some(vehicle) = vehicle {
+            self.broadcast_passengers(vehicle);
+        }
+    }
+
+    fn broadcast_passengers(&self, vehicle: EntityId) {
+        let passengers: Vec<VarInt> = self
+            .passengers_of(vehicle)
+            .into_iter()
+            .map(VarInt::from)
+            .collect();
+        self.broadcast_tracked(vehicle, &CSetPassengers::new(vehicle.into(), &passengers));
+    }
+
+    /// Tells a client which just started to see some entities who rides them and what they
+    /// ride, once it knows both
+    pub(super) fn send_passengers(&self, client: &Client, entity_ids: &HashSet<EntityId>) {
+        let Some(seen) = self.tracked_entities.lock().get(&client.id).cloned() else {
+            return;
+        };
+        let rides: Vec<(EntityId, Vec<VarInt>)> = {
+            let graph = self.passengers.lock();
+            let vehicles: HashSet<EntityId> = entity_ids
+                .iter()
+                .flat_map(|entity| [Some(*entity), graph.vehicle_of(*entity)])
+                .flatten()
+                .filter(|vehicle| seen.contains(vehicle))
+                .collect();
+            vehicles
+                .into_iter()
+                .filter(|vehicle| !graph.passengers_of(*vehicle).is_empty())
+                .map(|vehicle| {
+                    let passengers = graph.passengers_of(vehicle);
+                    (vehicle, passengers.iter().map(|id| (*id).into()).collect())
+                })
+                .collect()
+        };
+        for (vehicle, passengers) in rides {
+            client.send_packet(&CSetPassengers::new(vehicle.into(), &passengers));
+        }
+    }
+
+    /// Moves the passengers along with their vehicles, after those moved this tick. Clients
+    /// put passengers onto their seats by themselves, only the Server has to know where they are
+    pub(super) fn move_passengers(&self) {
+        let rides = self.passengers.lock().in_riding_order();
+        for (vehicle, passengers) in rides {
+            let Some(seat) = self.vehicle_seat(vehicle).or_else(|| self.mob_top(vehicle)) else {
+                continue;
+            };
+            for passenger in passengers {
+                match self.get_player_by_entityid(passenger) {
+                    Some(player) => player.follow_vehicle(seat),
+                    None => self.seat_mob(passenger, seat),
+                }
+            }
+        }
+    }
+}
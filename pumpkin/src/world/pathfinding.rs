@@ -0,0 +1,208 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+
+use super::World;
+
+/// A search looks at no more positions than this, so finding a path never gets expensive.
+/// Targets further away get a path to the closest position found
+const MAX_VISITED_NODES: usize = 256;
+/// Mobs walk down drops up to this many blocks
+const MAX_DROP: i32 = 3;
+/// The costs of the steps, diagonal steps are longer
+const STEP_COST: u32 = 10;
+const DIAGONAL_STEP_COST: u32 = 14;
+const JUMP_COST: u32 = 10;
+const DROP_COST: u32 = 5;
+
+/// The eight horizontal directions, the diagonal ones last
+const DIRECTIONS: [(i32, i32); 8] = [
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+/// The blocks a mob walks through to get somewhere, where its feet are
+pub struct Path {
+    nodes: Vec<WorldPosition>,
+    next: usize,
+}
+
+impl Path {
+    /// The next block to walk to, None once the mob arrived
+    pub fn next_node(&self) -> Option<WorldPosition> {
+        self.nodes.get(self.next).copied()
+    }
+
+    pub fn advance(&mut self) {
+        self.next += 1;
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.next >= self.nodes.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+}
+
+fn offset(position: &WorldPosition, x: i32, y: i32, z: i32) -> WorldPosition {
+    WorldPosition(position.0.add(&Vector3::new(x, y, z)))
+}
+
+/// Positions are queued as tuples, vectors can't be ordered
+const fn key(position: &Vector3<i32>) -> (i32, i32, i32) {
+    (position.x, position.y, position.z)
+}
+
+/// An estimate of the cost between two positions, never more than the real one
+fn heuristic(from: &WorldPosition, to: &WorldPosition) -> u32 {
+    let delta = to.0.sub(&from.0);
+    let distance = f64::from(delta.x * delta.x + delta.y * delta.y + delta.z * delta.z).sqrt();
+    (distance * f64::from(STEP_COST)) as u32
+}
+
+impl World {
+    /// Whether a mob can pass the block, blocks which are not loaded count as solid.
+    /// Mobs don't swim yet, so they avoid fluids
+    fn is_passable(&self, position: &WorldPosition) -> bool {
+        self.level.get_block(position).is_some_and(|block| {
+            !block.has_collision() && block.category() != Some("minecraft:liquid")
+        })
+    }
+
+    /// Whether a mob `height` blocks tall fits with its feet into the block
+    fn has_headroom(&self, position: &WorldPosition, height: i32) -> bool {
+        (0..height).all(|y| self.is_passable(&offset(position, 0, y, 0)))
+    }
+
+    /// Whether a mob `height` blocks tall can stand with its feet in the block. Only the column
+    /// of the block counts, wide mobs may scrape along walls
+    pub(super) fn can_stand_at(&self, position: &WorldPosition, height: i32) -> bool {
+        self.has_headroom(position, height)
+            && self
+                .level
+                .get_block(&offset(position, 0, -1, 0))
+                .is_some_and(|block| block.has_collision())
+    }
+
+    /// Where a mob ends up when it steps from `from` into the column next to it, with the
+    /// extra cost of jumping up or dropping down
+    fn step(
+        &self,
+        from: &WorldPosition,
+        x: i32,
+        z: i32,
+        height: i32,
+    ) -> Option<(WorldPosition, u32)> {
+        let next = offset(from, x, 0, z);
+        if self.can_stand_at(&next, height) {
+            return Some((next, 0));
+        }
+        let above = offset(&next, 0, 1, 0);
+        // Jumping needs room above the mob's head
+        if self.can_stand_at(&above, height) && self.is_passable(&offset(from, 0, height, 0)) {
+            return Some((above, JUMP_COST));
+        }
+        if !self.has_headroom(&next, height) {
+            return None;
+        }
+        (1..=MAX_DROP).find_map(|drop| {
+            let below = offset(&next, 0, -drop, 0);
+            if self.can_stand_at(&below, height) {
+                return Some(Some((below, DROP_COST * drop as u32)));
+            }
+            // Something is in the way of the fall, the mob would land higher
+            (!self.is_passable(&below)).then_some(None)
+        })?
+    }
+
+    /// Finds a way for a mob `height` blocks tall to walk from `from` to `to` with A*. If the
+    /// target can't be reached, the path leads to the closest position found. None if the mob
+    /// can't get any closer
+    pub(super) fn find_path(
+        &self,
+        from: WorldPosition,
+        to: WorldPosition,
+        height: i32,
+    ) -> Option<Path> {
+        // The cost to get there and the position it was reached from
+        let mut visited: HashMap<Vector3<i32>, (u32, Option<Vector3<i32>>)> = HashMap::new();
+        let mut open = BinaryHeap::new();
+        visited.insert(from.0, (0, None));
+        open.push(Reverse((heuristic(&from, &to), 0, key(&from.0))));
+        let mut closest = (heuristic(&from, &to), from.0);
+
+        while let Some(Reverse((_, cost, queued))) = open.pop() {
+            let position = Vector3::new(queued.0, queued.1, queued.2);
+            if position == to.0 {
+                closest = (0, position);
+                break;
+            }
+            if visited.len() >= MAX_VISITED_NODES {
+                break;
+            }
+            // A cheaper way there was found after this one was queued
+            if visited.get(&position).is_some_and(|(best, _)| *best < cost) {
+                continue;
+            }
+            let current = WorldPosition(position);
+            for (index, (x, z)) in DIRECTIONS.iter().enumerate() {
+                let diagonal = index >= 4;
+                // Mobs don't cut corners
+                if diagonal
+                    && (!self.has_headroom(&offset(&current, *x, 0, 0), height)
+                        || !self.has_headroom(&offset(&current, 0, 0, *z), height))
+                {
+                    continue;
+                }
+                let Some((next, extra)) = self.step(&current, *x, *z, height) else {
+                    continue;
+                };
+                let step_cost = if diagonal {
+                    DIAGONAL_STEP_COST
+                } else {
+                    STEP_COST
+                };
+                let next_cost = cost + step_cost + extra;
+                if visited
+                    .get(&next.0)
+                    .is_some_and(|(best, _)| *best <= next_cost)
+                {
+                    continue;
+                }
+                visited.insert(next.0, (next_cost, Some(position)));
+                let estimate = heuristic(&next, &to);
+                if estimate < closest.0 {
+                    closest = (estimate, next.0);
+                }
+                open.push(Reverse((next_cost + estimate, next_cost, key(&next.0))));
+            }
+        }
+
+        let (_, end) = closest;
+        if end == from.0 {
+            return None;
+        }
+        let mut nodes = vec![WorldPosition(end)];
+        let mut position = end;
+        while let Some((_, Some(parent))) = visited.get(&position) {
+            if *parent == from.0 {
+                break;
+            }
+            nodes.push(WorldPosition(*parent));
+            position = *parent;
+        }
+        nodes.reverse();
+        Some(Path { nodes, next: 0 })
+    }
+}
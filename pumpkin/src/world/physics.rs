@@ -0,0 +1,487 @@
+use pumpkin_core::math::{boundingbox::BoundingBox, position::WorldPosition, vector3::Vector3};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_protocol::client::play::CEntityVelocity;
+use pumpkin_world::{WORLD_LOWEST_Y, WORLD_MAX_Y};
+
+use super::{tracking::angle_byte, World};
+
+/// Touching blocks closer than this counts as standing on them
+const EPSILON: f64 = 1.0E-7;
+/// Buoyant entities rise this much faster every tick in water, up to `MAX_RISE_SPEED`
+const BUOYANCY: f64 = 5.0E-4;
+const MAX_RISE_SPEED: f64 = 0.06;
+/// Velocity changes bigger than this are sent right away, smaller ones with the next update
+const VELOCITY_IMPULSE: f64 = 0.02;
+/// Entities don't move faster than this many blocks per tick, like in vanilla
+const MAX_SPEED: f64 = 10.0;
+
+/// How an entity which is not a player moves on its own, the values are the vanilla ones
+#[derive(Clone, Copy, Debug)]
+pub struct Physics {
+    /// How much faster it falls every tick
+    pub gravity: f64,
+    /// How much of its speed it keeps every tick
+    pub drag: f64,
+    /// How much of its horizontal speed it keeps every tick on the ground
+    pub ground_friction: f64,
+    /// How much of its speed it keeps every tick in water or lava
+    pub fluid_drag: f64,
+    /// Whether it floats up in water instead of sinking, like items
+    pub buoyant: bool,
+    pub width: f64,
+    pub height: f64,
+    /// Ticks between the positions sent to clients, they predict the movement in between
+    pub update_interval: u32,
+}
+
+pub const FALLING_BLOCK: Physics = Physics {
+    gravity: 0.04,
+    drag: 0.98,
+    ground_friction: 0.7,
+    fluid_drag: 0.98,
+    buoyant: false,
+    width: 0.98,
+    height: 0.98,
+    update_interval: 20,
+};
+
+pub const TNT: Physics = Physics {
+    gravity: 0.04,
+    drag: 0.98,
+    ground_friction: 0.7,
+    fluid_drag: 0.98,
+    buoyant: false,
+    width: 0.98,
+    height: 0.98,
+    update_interval: 10,
+};
+
+pub const EXPERIENCE_ORB: Physics = Physics {
+    gravity: 0.03,
+    drag: 0.98,
+    ground_friction: 0.6,
+    fluid_drag: 0.99,
+    buoyant: true,
+    width: 0.5,
+    height: 0.5,
+    update_interval: 20,
+};
+
+pub const ITEM: Physics = Physics {
+    gravity: 0.04,
+    drag: 0.98,
+    ground_friction: 0.6,
+    fluid_drag: 0.99,
+    buoyant: true,
+    width: 0.25,
+    height: 0.25,
+    update_interval: 20,
+};
+
+/// Projectiles don't collide like other entities, they hit what is in their way
+pub const ARROW: Physics = Physics {
+    gravity: 0.05,
+    drag: 0.99,
+    ground_friction: 1.0,
+    fluid_drag: 0.6,
+    buoyant: false,
+    width: 0.5,
+    height: 0.5,
+    update_interval: 20,
+};
+
+pub const THROWN_ITEM: Physics = Physics {
+    gravity: 0.03,
+    drag: 0.99,
+    ground_friction: 1.0,
+    fluid_drag: 0.8,
+    buoyant: false,
+    width: 0.25,
+    height: 0.25,
+    update_interval: 10,
+};
+
+/// Boats float on water on their own, see `tick_vehicles`
+pub const BOAT: Physics = Physics {
+    gravity: 0.04,
+    drag: 0.9,
+    ground_friction: 0.5,
+    fluid_drag: 0.9,
+    buoyant: false,
+    width: 1.375,
+    height: 0.5625,
+    update_interval: 3,
+};
+
+/// Minecarts on rails follow the rails instead
+pub const MINECART: Physics = Physics {
+    gravity: 0.04,
+    drag: 0.95,
+    ground_friction: 0.5,
+    fluid_drag: 0.95,
+    buoyant: false,
+    width: 0.98,
+    height: 0.7,
+    update_interval: 3,
+};
+
+/// Mobs get their width and height from their type, see `Physics::mob`
+pub const MOB: Physics = Physics {
+    gravity: 0.08,
+    drag: 0.98,
+    // Vanilla keeps 0.546 of the speed on most blocks, after the drag
+    ground_friction: 0.557,
+    fluid_drag: 0.8,
+    buoyant: false,
+    width: 0.6,
+    height: 1.8,
+    update_interval: 3,
+};
+
+impl Physics {
+    /// How a mob of the type moves
+    pub const fn mob(entity_type: EntityType) -> Self {
+        let (width, height) = entity_type.dimensions();
+        Self {
+            width,
+            height,
+            ..MOB
+        }
+    }
+}
+
+/// Where an entity which is not a player is and how it moves
+#[derive(Clone, Copy, Debug)]
+pub struct Body {
+    /// The bottom center of the entity
+    pub position: Vector3<f64>,
+    /// In blocks per tick
+    pub velocity: Vector3<f64>,
+    pub on_ground: bool,
+    /// In degrees, like the rotation of players
+    pub yaw: f32,
+    pub pitch: f32,
+    /// Ticks since clients were last told where it is
+    ticks_since_sync: u32,
+    /// The position and the velocity clients were told last
+    synced_position: Vector3<f64>,
+    synced_velocity: Vector3<f64>,
+}
+
+impl Body {
+    pub const fn new(position: Vector3<f64>, velocity: Vector3<f64>) -> Self {
+        Self {
+            position,
+            velocity,
+            on_ground: false,
+            yaw: 0.0,
+            pitch: 0.0,
+            ticks_since_sync: 0,
+            synced_position: position,
+            synced_velocity: velocity,
+        }
+    }
+
+    pub fn bounding_box(&self, physics: &Physics) -> BoundingBox {
+        let half_width = physics.width / 2.0;
+        BoundingBox::new(
+            self.position.x - half_width,
+            self.position.y,
+            self.position.z - half_width,
+            self.position.x + half_width,
+            self.position.y + physics.height,
+            self.position.z + half_width,
+        )
+    }
+
+    /// Where clients which see it think it is, the next movement starts from there
+    pub const fn synced_position(&self) -> Vector3<f64> {
+        self.synced_position
+    }
+
+    /// The block the bottom center is in
+    pub fn block_pos(&self) -> WorldPosition {
+        WorldPosition(Vector3::new(
+            self.position.x.floor() as i32,
+            self.position.y.floor() as i32,
+            self.position.z.floor() as i32,
+        ))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(super) enum Fluid {
+    Water,
+    Lava,
+}
+
+#[derive(Clone, Copy)]
+enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl World {
+    /// Moves a body by one tick, like vanilla does for entities which are not players: gravity
+    /// or buoyancy first, then the movement stops at blocks in the way and the drag slows it
+    /// down. Returns false if the body waits because blocks around it are not loaded
+    pub(super) fn step_physics(&self, body: &mut Body, physics: &Physics) -> bool {
+        let in_fluid = self.fluid_at(&body.block_pos());
+        let mut velocity = body.velocity;
+        if in_fluid == Some(Fluid::Water) && physics.buoyant {
+            if velocity.y < MAX_RISE_SPEED {
+                velocity.y += BUOYANCY;
+            }
+        } else {
+            velocity.y -= physics.gravity;
+        }
+        velocity = Vector3::new(
+            velocity.x.clamp(-MAX_SPEED, MAX_SPEED),
+            velocity.y.clamp(-MAX_SPEED, MAX_SPEED),
+            velocity.z.clamp(-MAX_SPEED, MAX_SPEED),
+        );
+
+        // Like in vanilla the vertical movement comes first, then the bigger horizontal one
+        let mut bounding_box = body.bounding_box(physics);
+        let horizontal = if velocity.x.abs() < velocity.z.abs() {
+            [Axis::Z, Axis::X]
+        } else {
+            [Axis::X, Axis::Z]
+        };
+        let mut moved = Vector3::new(0.0, 0.0, 0.0);
+        for axis in [Axis::Y, horizontal[0], horizontal[1]] {
+            let wanted = axis.get(&velocity);
+            let Some(delta) = self.clip_movement(&bounding_box, axis, wanted) else {
+                return false;
+            };
+            axis.offset(&mut bounding_box, delta);
+            axis.set(&mut moved, delta);
+        }
+        body.position = body.position.add(&moved);
+
+        let hit_vertical = moved.y != velocity.y;
+        body.on_ground = hit_vertical && velocity.y < 0.0;
+        if hit_vertical {
+            velocity.y = 0.0;
+        }
+        if moved.x != velocity.x {
+            velocity.x = 0.0;
+        }
+        if moved.z != velocity.z {
+            velocity.z = 0.0;
+        }
+
+        let drag = if in_fluid.is_some() {
+            physics.fluid_drag
+        } else {
+            physics.drag
+        };
+        velocity = velocity * drag;
+        if body.on_ground {
+            velocity = velocity.multiply(physics.ground_friction, 1.0, physics.ground_friction);
+        }
+        body.velocity = velocity;
+        true
+    }
+
+    /// How far a bounding box can move along an axis until it hits a block, None if the blocks
+    /// on the way are not loaded
+    fn clip_movement(&self, bounding_box: &BoundingBox, axis: Axis, delta: f64) -> Option<f64> {
+        if delta == 0.0 {
+            return Some(0.0);
+        }
+        let mut swept = BoundingBox::new(
+            bounding_box.min_x,
+            bounding_box.min_y,
+            bounding_box.min_z,
+            bounding_box.max_x,
+            bounding_box.max_y,
+            bounding_box.max_z,
+        );
+        axis.extend(&mut swept, delta);
+        let mut delta = delta;
+        for x in swept.min_x.floor() as i32..swept.max_x.ceil() as i32 {
+            for y in swept.min_y.floor() as i32..swept.max_y.ceil() as i32 {
+                // Nothing stops entities above or below the world
+                if !(i32::from(WORLD_LOWEST_Y)..i32::from(WORLD_MAX_Y)).contains(&y) {
+                    continue;
+                }
+                for z in swept.min_z.floor() as i32..swept.max_z.ceil() as i32 {
+                    let position = WorldPosition(Vector3::new(x, y, z));
+                    if !self.level.get_block(&position)?.has_collision() {
+                        continue;
+                    }
+                    // Blocks have no shapes yet, all of them are full cubes
+                    let block = BoundingBox::from_block(&position);
+                    delta = axis.clip(bounding_box, &block, delta);
+                }
+            }
+        }
+        Some(delta)
+    }
+
+    /// Whether a bounding box overlaps a block with collision. Blocks which are not loaded are
+    /// in the way too
+    pub(crate) fn collides(&self, bounding_box: &BoundingBox) -> bool {
+        let inner = bounding_box.expand(-EPSILON, -EPSILON, -EPSILON);
+        for x in inner.min_x.floor() as i32..inner.max_x.ceil() as i32 {
+            for y in inner.min_y.floor() as i32..inner.max_y.ceil() as i32 {
+                if !(i32::from(WORLD_LOWEST_Y)..i32::from(WORLD_MAX_Y)).contains(&y) {
+                    continue;
+                }
+                for z in inner.min_z.floor() as i32..inner.max_z.ceil() as i32 {
+                    let position = WorldPosition(Vector3::new(x, y, z));
+                    match self.level.get_block(&position) {
+                        Some(block) if !block.has_collision() => {}
+                        _ => return true,
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// Whether there is nothing but air in a bounding box, blocks which are not loaded count
+    /// as something
+    pub(crate) fn only_air(&self, bounding_box: &BoundingBox) -> bool {
+        for x in bounding_box.min_x.floor() as i32..bounding_box.max_x.ceil() as i32 {
+            for y in bounding_box.min_y.floor() as i32..bounding_box.max_y.ceil() as i32 {
+                if !(i32::from(WORLD_LOWEST_Y)..i32::from(WORLD_MAX_Y)).contains(&y) {
+                    continue;
+                }
+                for z in bounding_box.min_z.floor() as i32..bounding_box.max_z.ceil() as i32 {
+                    let position = WorldPosition(Vector3::new(x, y, z));
+                    if !self
+                        .level
+                        .get_block(&position)
+                        .is_some_and(|block| block.is_air())
+                    {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// The fluid in the block, if there is one
+    pub(super) fn fluid_at(&self, position: &WorldPosition) -> Option<Fluid> {
+        let block = self.level.get_block(position)?;
+        match block.registry_state()?.0 {
+            "minecraft:water" | "minecraft:bubble_column" => Some(Fluid::Water),
+            "minecraft:lava" => Some(Fluid::Lava),
+            _ => None,
+        }
+    }
+
+    /// Tells the players who see a body where it is every `update_interval` ticks, and right
+    /// away when its velocity changed a lot. Clients move it on their own in between
+    pub(super) fn sync_body(&self, entity_id: EntityId, body: &mut Body, physics: &Physics) {
+        body.ticks_since_sync += 1;
+        let impulse = body.velocity.sub(&body.synced_velocity).length() > VELOCITY_IMPULSE;
+        if body.ticks_since_sync < physics.update_interval && !impulse {
+            return;
+        }
+        body.ticks_since_sync = 0;
+        self.broadcast_movement(
+            entity_id,
+            body.synced_position,
+            body.position,
+            (angle_byte(body.yaw), angle_byte(body.pitch)),
+            false,
+            body.on_ground,
+        );
+        body.synced_position = body.position;
+        if body.velocity != body.synced_velocity {
+            body.synced_velocity = body.velocity;
+            self.broadcast_tracked(
+                entity_id,
+                &CEntityVelocity::new(
+                    &entity_id.into(),
+                    body.velocity.x as f32,
+                    body.velocity.y as f32,
+                    body.velocity.z as f32,
+                ),
+            );
+        }
+    }
+}
+
+impl Axis {
+    fn get(self, vector: &Vector3<f64>) -> f64 {
+        match self {
+            Self::X => vector.x,
+            Self::Y => vector.y,
+            Self::Z => vector.z,
+        }
+    }
+
+    fn set(self, vector: &mut Vector3<f64>, value: f64) {
+        match self {
+            Self::X => vector.x = value,
+            Self::Y => vector.y = value,
+            Self::Z => vector.z = value,
+        }
+    }
+
+    /// Moves the bounding box along the axis
+    fn offset(self, bounding_box: &mut BoundingBox, delta: f64) {
+        match self {
+            Self::X => {
+                bounding_box.min_x += delta;
+                bounding_box.max_x += delta;
+            }
+            Self::Y => {
+                bounding_box.min_y += delta;
+                bounding_box.max_y += delta;
+            }
+            Self::Z => {
+                bounding_box.min_z += delta;
+                bounding_box.max_z += delta;
+            }
+        }
+    }
+
+    /// Grows the bounding box in the direction of `delta`
+    fn extend(self, bounding_box: &mut BoundingBox, delta: f64) {
+        let (min, max) = match self {
+            Self::X => (&mut bounding_box.min_x, &mut bounding_box.max_x),
+            Self::Y => (&mut bounding_box.min_y, &mut bounding_box.max_y),
+            Self::Z => (&mut bounding_box.min_z, &mut bounding_box.max_z),
+        };
+        if delta < 0.0 {
+            *min += delta;
+        } else {
+            *max += delta;
+        }
+    }
+
+    /// Shortens `delta` so the moving box stops at `block`, if the block is in its way
+    fn clip(self, moving: &BoundingBox, block: &BoundingBox, delta: f64) -> f64 {
+        // The block is only in the way if it overlaps on the other two axes
+        let overlaps = |min_a: f64, max_a: f64, min_b: f64, max_b: f64| {
+            min_a < max_b - EPSILON && max_a > min_b + EPSILON
+        };
+        let (x, y, z) = (
+            overlaps(moving.min_x, moving.max_x, block.min_x, block.max_x),
+            overlaps(moving.min_y, moving.max_y, block.min_y, block.max_y),
+            overlaps(moving.min_z, moving.max_z, block.min_z, block.max_z),
+        );
+        let (in_way, moving_min, moving_max, block_min, block_max) = match self {
+            Self::X => (y && z, moving.min_x, moving.max_x, block.min_x, block.max_x),
+            Self::Y => (x && z, moving.min_y, moving.max_y, block.min_y, block.max_y),
+            Self::Z => (x && y, moving.min_z, moving.max_z, block.min_z, block.max_z),
+        };
+        if !in_way {
+            return delta;
+        }
+        if delta > 0.0 && moving_max <= block_min + EPSILON {
+            delta.min(block_min - moving_max)
+        } else if delta < 0.0 && moving_min >= block_max - EPSILON {
+            delta.max(block_max - moving_min)
+        } else {
+            delta
+        }
+    }
+}
@@ -1,62 +1,152 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
+
+use pumpkin_config::ADVANCED_CONFIG;
 
-use pumpkin_config::BASIC_CONFIG;
 use pumpkin_core::math::{
     get_section_cord, position::WorldPosition, vector2::Vector2, vector3::Vector3,
 };
-use pumpkin_protocol::client::play::{CCenterChunk, CUnloadChunk};
-use pumpkin_world::cylindrical_chunk_iterator::Cylindrical;
+use pumpkin_protocol::client::play::{
+    CCenterChunk, CSetChunkCacheRadius, CSetSimulationDistance, CUnloadChunk,
+};
+use pumpkin_world::{cylindrical_chunk_iterator::Cylindrical, ticket::TicketType};
 
 use crate::entity::{player::Player, Entity};
 
 use super::World;
 
-fn get_view_distance(player: &Player) -> i8 {
-    player
-        .config
-        .lock()
-        .view_distance
-        .clamp(2, BASIC_CONFIG.view_distance as i8)
+/// How fast a player has to move, in blocks per tick, so chunks in front of them are preferred
+/// over chunks next to them. Flying with an elytra is about this fast
+const FULL_DIRECTION_SPEED: f64 = 1.5;
+/// Even at full speed chunks ahead are never preferred over much closer ones
+const MAX_DIRECTION_BIAS: f64 = 0.75;
+/// How much the movement of the last tick changes the direction, smooths out single movements
+const MOVEMENT_SMOOTHING: f64 = 0.2;
+
+/// The chunks waiting to be sent to a Player and where the Player is moving
+#[derive(Default)]
+pub struct ChunkSendQueue {
+    pending: HashSet<Vector2<i32>>,
+    /// Where the player was in the last tick
+    last_position: Option<Vector3<f64>>,
+    /// How far the player moves every tick, on average
+    movement: Option<Vector2<f64>>,
 }
 
-pub async fn player_join(world: &World, player: Arc<Player>) {
+impl ChunkSendQueue {
+    fn track_movement(&mut self, position: Vector3<f64>) {
+        if let Some(last) = self.last_position.replace(position) {
+            let moved = Vector2::new(position.x - last.x, position.z - last.z);
+            let movement = self.movement.unwrap_or(moved);
+            self.movement =
+                Some(movement * (1.0 - MOVEMENT_SMOOTHING) + moved * MOVEMENT_SMOOTHING);
+        }
+    }
+
+    /// The pending chunks, the ones which should be sent first first. Chunks count as closer the
+    /// further they are in front of the player and the faster the player moves
+    fn prioritized(&self, position: Vector3<f64>, direction: bool) -> Vec<Vector2<i32>> {
+        let movement = self
+            .movement
+            .filter(|_| direction)
+            .unwrap_or(Vector2::new(0.0, 0.0));
+        let speed = movement.length();
+        let bias = (speed / FULL_DIRECTION_SPEED).min(1.0) * MAX_DIRECTION_BIAS;
+        let mut chunks: Vec<_> = self
+            .pending
+            .iter()
+            .map(|chunk| {
+                // In chunks from the player to the center of the chunk
+                let offset = Vector2::new(
+                    (f64::from(chunk.x) * 16.0 + 8.0 - position.x) / 16.0,
+                    (f64::from(chunk.z) * 16.0 + 8.0 - position.z) / 16.0,
+                );
+                let ahead = if speed > 0.0 {
+                    (offset.x * movement.x + offset.z * movement.z) / speed
+                } else {
+                    0.0
+                };
+                (offset.length() - ahead * bias, *chunk)
+            })
+            .collect();
+        chunks.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        chunks.into_iter().map(|(_, chunk)| chunk).collect()
+    }
+}
+
+/// Sends the most important pending chunks to a Player which are loaded, at most as many as the
+/// budget of a tick allows. Loading starts for the ones which should be sent next
+pub fn send_chunks(world: &World, player: &Player) {
+    let config = &ADVANCED_CONFIG.get().chunk_sending;
+    let position = player.living_entity.entity.pos.load();
+    let mut queue = player.chunk_queue.lock();
+    queue.track_movement(position);
+    if queue.pending.is_empty() {
+        return;
+    }
+    let budget = config.chunks_per_tick as usize;
+    let mut sent = 0;
+    let mut loading = 0;
+    for at in queue.prioritized(position, config.prioritize_direction) {
+        if sent >= budget || loading >= budget * 2 {
+            break;
+        }
+        match world.level.loaded_chunk(at) {
+            Some(chunk) => {
+                player.client.send_chunk(&world.level, &chunk.read());
+                queue.pending.remove(&at);
+                sent += 1;
+            }
+            None => {
+                // Starts loading the chunk on the chunk workers, it is sent once it is loaded
+                drop(world.level.get_chunk(at));
+                loading += 1;
+            }
+        }
+    }
+}
+
+pub fn player_join(world: &World, player: Arc<Player>) {
+    // The client forgets all chunks when it (re)spawns, so start over with the whole view
+    player_leave(world, &player);
     let new_watched = chunk_section_from_pos(&player.living_entity.entity.block_pos.load());
     player.watched_section.store(new_watched);
-    let watched_section = new_watched;
     let chunk_pos = player.living_entity.entity.chunk_pos.load();
     player.client.send_packet(&CCenterChunk {
         chunk_x: chunk_pos.x.into(),
         chunk_z: chunk_pos.z.into(),
     });
-    let view_distance = get_view_distance(&player) as i32;
-    dbg!(view_distance);
-    let old_cylindrical = Cylindrical::new(
-        Vector2::new(watched_section.x, watched_section.z),
-        view_distance,
-    );
-    let new_cylindrical = Cylindrical::new(Vector2::new(chunk_pos.x, chunk_pos.z), view_distance);
-    let mut loading_chunks = Vec::new();
-    Cylindrical::for_each_changed_chunk(
-        old_cylindrical,
-        new_cylindrical,
-        |chunk_pos| {
-            loading_chunks.push(chunk_pos);
-        },
-        |chunk_pos| {
-            player
-                .client
-                .send_packet(&CUnloadChunk::new(chunk_pos.x, chunk_pos.z));
-        },
-        true,
-    );
-    if !loading_chunks.is_empty() {
-        world
-            .spawn_world_chunks(&player.client, loading_chunks, view_distance)
-            .await;
+    send_view(world, &player);
+}
+
+/// Tells the client the view and simulation distance of a Player, chunks are sent or unloaded
+/// if the view distance changed
+pub fn update_distances(world: &World, player: &Player) {
+    let view_distance = i32::from(player.view_distance());
+    player
+        .client
+        .send_packet(&CSetChunkCacheRadius::new(view_distance.into()));
+    player.client.send_packet(&CSetSimulationDistance::new(
+        i32::from(player.simulation_distance()).into(),
+    ));
+    let changed = player
+        .chunk_view
+        .lock()
+        .is_some_and(|view| view.view_distance != view_distance);
+    if changed {
+        send_view(world, player);
     }
 }
 
-pub async fn update_position(entity: &Entity, player: &Player) {
+/// Releases the chunks a Player saw, so they can be unloaded once nobody else sees them
+pub fn player_leave(world: &World, player: &Player) {
+    let Some(view) = player.chunk_view.lock().take() else {
+        return;
+    };
+    player.chunk_queue.lock().pending.clear();
+    view.for_each_chunk(|chunk_pos| world.level.remove_ticket(chunk_pos, TicketType::Player));
+}
+
+pub fn update_position(entity: &Entity, player: &Player) {
     let current_watched = player.watched_section.load();
     let new_watched = chunk_section_from_pos(&entity.block_pos.load());
     if current_watched != new_watched {
@@ -65,36 +155,43 @@ pub async fn update_position(entity: &Entity, player: &Player) {
             chunk_x: chunk_pos.x.into(),
             chunk_z: chunk_pos.z.into(),
         });
-
-        let view_distance = get_view_distance(player) as i32;
-        let old_cylindrical = Cylindrical::new(
-            Vector2::new(current_watched.x, current_watched.z),
-            view_distance,
-        );
-        let new_cylindrical =
-            Cylindrical::new(Vector2::new(chunk_pos.x, chunk_pos.z), view_distance);
         player.watched_section.store(new_watched);
-        let mut loading_chunks = Vec::new();
-        Cylindrical::for_each_changed_chunk(
-            old_cylindrical,
-            new_cylindrical,
-            |chunk_pos| {
-                loading_chunks.push(chunk_pos);
-            },
-            |chunk_pos| {
+        send_view(&entity.world(), player);
+    }
+}
+
+/// Moves the view of a Player to the chunk they are in
+fn send_view(world: &World, player: &Player) {
+    let chunk_pos = player.living_entity.entity.chunk_pos.load();
+    let view_distance = i32::from(player.view_distance());
+    let new_cylindrical = Cylindrical::new(Vector2::new(chunk_pos.x, chunk_pos.z), view_distance);
+    move_view(world, player, new_cylindrical);
+}
+
+/// Moves the view of a Player. Chunks entering it get a ticket and are queued to be sent, chunks
+/// leaving it lose theirs and get unloaded on the client.
+fn move_view(world: &World, player: &Player, new_cylindrical: Cylindrical) {
+    let mut chunk_view = player.chunk_view.lock();
+    let old_cylindrical = chunk_view.replace(new_cylindrical);
+    let mut queue = player.chunk_queue.lock();
+    Cylindrical::for_each_changed_chunk(
+        old_cylindrical.unwrap_or(new_cylindrical),
+        new_cylindrical,
+        |chunk_pos| {
+            world.level.add_ticket(chunk_pos, TicketType::Player);
+            queue.pending.insert(chunk_pos);
+        },
+        |chunk_pos| {
+            world.level.remove_ticket(chunk_pos, TicketType::Player);
+            // Chunks which were not sent yet are just not sent anymore
+            if !queue.pending.remove(&chunk_pos) {
                 player
                     .client
                     .send_packet(&CUnloadChunk::new(chunk_pos.x, chunk_pos.z));
-            },
-            false,
-        );
-        if !loading_chunks.is_empty() {
-            entity
-                .world
-                .spawn_world_chunks(&player.client, loading_chunks, view_distance)
-                .await;
-        }
-    }
+            }
+        },
+        old_cylindrical.is_none(),
+    );
 }
 
 const fn chunk_section_from_pos(block_pos: &WorldPosition) -> Vector3<i32> {
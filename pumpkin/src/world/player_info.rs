@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use pumpkin_entity::entity_type::EntityType;
+use pumpkin_protocol::client::play::{
+    CPlayerInfoUpdate, CRemovePlayerInfo, CSpawnEntity, PlayerAction,
+};
+
+use crate::{chat::session::ChatSession, entity::player::Player};
+
+use super::World;
+
+/// Player list changes which get sent to the other players once per tick.
+///
+/// When many players join or leave at once (e.g. after a restart) this sends a single
+/// packet per tick instead of one for every player
+#[derive(Default)]
+pub(super) struct PlayerInfoBatch {
+    /// Players which joined this tick, in join order
+    added: Vec<Arc<Player>>,
+    /// Players which left this tick
+    removed: Vec<uuid::Uuid>,
+}
+
+impl PlayerInfoBatch {
+    pub fn add(&mut self, player: Arc<Player>) {
+        self.added.push(player);
+    }
+
+    pub fn remove(&mut self, player: &Player) {
+        self.added
+            .retain(|added| added.client.id != player.client.id);
+        self.removed.push(player.gameprofile.id);
+    }
+
+    /// Whether the player joined and the others don't know about them yet
+    pub fn contains(&self, player: &Player) -> bool {
+        self.added
+            .iter()
+            .any(|added| added.client.id == player.client.id)
+    }
+
+    const fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// The actions every entry built by `info_entry` contains
+pub(super) const INFO_ACTIONS: i8 = 0x01 | 0x02 | 0x08;
+
+/// The player list entry of a Player, as sent to other players
+pub(super) fn info_entry<'a>(
+    player: &'a Player,
+    chat_session: Option<&'a ChatSession>,
+) -> pumpkin_protocol::client::play::Player<'a> {
+    let gameprofile = &player.gameprofile;
+    pumpkin_protocol::client::play::Player {
+        uuid: gameprofile.id,
+        actions: vec![
+            PlayerAction::AddPlayer {
+                name: &gameprofile.name,
+                properties: &gameprofile.properties,
+            },
+            PlayerAction::InitializeChat(chat_session.map(ChatSession::init_chat)),
+            PlayerAction::UpdateListed(true),
+        ],
+    }
+}
+
+/// Spawns the Player entity where the other players were told it is, the next movement starts
+/// from there
+pub(super) fn spawn_packet(player: &Player) -> CSpawnEntity {
+    let entity = &player.living_entity.entity;
+    let pos = player.last_position.load();
+    CSpawnEntity::new(
+        player.entity_id().into(),
+        player.gameprofile.id,
+        (EntityType::Player as i32).into(),
+        pos.x,
+        pos.y,
+        pos.z,
+        entity.pitch.load(),
+        entity.yaw.load(),
+        entity.head_yaw.load(),
+        0.into(),
+        0.0,
+        0.0,
+        0.0,
+    )
+}
+
+impl World {
+    /// Sends all player list changes of this tick to the players in this world. The player
+    /// entities are spawned and removed by the tracking
+    pub(super) fn flush_player_info(&self) {
+        let batch = {
+            let mut batch = self.player_info_batch.lock();
+            if batch.is_empty() {
+                return;
+            }
+            std::mem::take(&mut *batch)
+        };
+        let players: Vec<Arc<Player>> = self.current_players.lock().values().cloned().collect();
+
+        if !batch.removed.is_empty() {
+            let remove_info = CRemovePlayerInfo::new(batch.removed.len().into(), &batch.removed);
+            for player in &players {
+                player.client.send_packet(&remove_info);
+            }
+        }
+
+        if batch.added.is_empty() {
+            return;
+        }
+        let sessions: Vec<_> = batch
+            .added
+            .iter()
+            .map(|added| added.chat_session())
+            .collect();
+        let entries: Vec<_> = batch
+            .added
+            .iter()
+            .zip(&sessions)
+            .map(|(added, session)| info_entry(added, session.as_deref()))
+            .collect();
+        for player in &players {
+            // Players which joined this tick already received everyone who joined before them
+            let start = batch
+                .added
+                .iter()
+                .position(|added| added.client.id == player.client.id)
+                .map_or(0, |index| index + 1);
+            let entries = &entries[start..];
+            if entries.is_empty() {
+                continue;
+            }
+            player
+                .client
+                .send_packet(&CPlayerInfoUpdate::new(INFO_ACTIONS, entries));
+        }
+    }
+}
@@ -0,0 +1,506 @@
+//! Nether portals link the Overworld with the Nether, end portals lead into the End and back.
+//!
+//! Nether portals get lit with flint and steel inside an obsidian frame. Players travel once they
+//! stood in one long enough and arrive at a portal near the same position in the other dimension,
+//! which gets built when there is none yet. End portals open once every frame around them has an eye.
+
+use std::{collections::HashMap, sync::Arc};
+
+use pumpkin_core::{
+    math::{position::WorldPosition, vector2::Vector2, vector3::Vector3},
+    GameMode,
+};
+use pumpkin_world::{
+    block::{BlockId, BlockState},
+    dimension::Dimension,
+    ticket::TicketType,
+    world_gen::{NETHER_CEILING, NETHER_FLOOR},
+    WORLD_LOWEST_Y, WORLD_MAX_Y,
+};
+
+use crate::{entity::player::Player, server::Server};
+
+use super::World;
+
+/// Players in survival have to stand in a nether portal this many ticks before they travel, like in vanilla
+const NETHER_PORTAL_DELAY: u32 = 80;
+/// The inside of a nether portal may be at most this wide and high
+const MAX_PORTAL_SIZE: i32 = 21;
+const MIN_PORTAL_WIDTH: i32 = 2;
+const MIN_PORTAL_HEIGHT: i32 = 3;
+/// How far away from where a player arrives an existing nether portal gets linked
+const PORTAL_SEARCH_RADIUS: i32 = 16;
+/// Where players arrive in the End, on top of an obsidian platform
+pub const END_SPAWN: Vector3<f64> = Vector3::new(100.5, 49.0, 0.5);
+
+/// Whether a player stands in a portal
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PortalState {
+    Outside,
+    /// Stands in a nether portal for this many ticks
+    Inside(u32),
+    /// Traveled through a portal and did not step out of it yet, so they don't travel right back
+    Traveled,
+}
+
+#[derive(Clone, Copy)]
+enum Axis {
+    X,
+    Z,
+}
+
+impl Axis {
+    /// One block along the axis
+    const fn offset(self) -> (i32, i32) {
+        match self {
+            Axis::X => (1, 0),
+            Axis::Z => (0, 1),
+        }
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Axis::X => "x",
+            Axis::Z => "z",
+        }
+    }
+}
+
+/// The inside of an obsidian frame a nether portal fits in
+struct PortalFrame {
+    axis: Axis,
+    /// The lowest block of the inside on the negative side of the axis
+    corner: Vector3<i32>,
+    width: i32,
+    height: i32,
+}
+
+impl PortalFrame {
+    /// The block `along` the axis and `up` from the corner
+    fn at(&self, along: i32, up: i32) -> WorldPosition {
+        offset(self.corner, self.axis, along, up)
+    }
+}
+
+fn offset(position: Vector3<i32>, axis: Axis, along: i32, up: i32) -> WorldPosition {
+    let (x, z) = axis.offset();
+    WorldPosition(Vector3::new(
+        position.x + x * along,
+        position.y + up,
+        position.z + z * along,
+    ))
+}
+
+/// A block state by its registry id and properties, the properties have to be complete
+fn block(registry_id: &str, properties: &[(&str, &str)]) -> BlockId {
+    let properties: Option<HashMap<String, String>> = (!properties.is_empty()).then(|| {
+        properties
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    });
+    BlockState::new(registry_id, properties.as_ref())
+        .expect("Portal blocks are vanilla blocks")
+        .into()
+}
+
+/// The registry id of the block at a position, None if its chunk is not loaded
+fn block_name(world: &World, position: &WorldPosition) -> Option<&'static str> {
+    let (name, _) = world.level.get_block(position)?.registry_state()?;
+    Some(name)
+}
+
+fn is_obsidian(world: &World, position: &WorldPosition) -> bool {
+    block_name(world, position) == Some("minecraft:obsidian")
+}
+
+/// Portals can only be lit where there is nothing but air and fire
+fn is_portal_air(world: &World, position: &WorldPosition) -> bool {
+    world.level.get_block(position).is_some_and(|block| {
+        block.is_air() || block_name(world, position) == Some("minecraft:fire")
+    })
+}
+
+/// Lights a nether portal when `inside` is in an obsidian frame. Returns whether a portal was lit
+pub fn light_nether_portal(world: &World, inside: WorldPosition) -> bool {
+    for axis in [Axis::X, Axis::Z] {
+        if let Some(frame) = find_frame(world, inside.0, axis) {
+            let portal = block("minecraft:nether_portal", &[("axis", axis.name())]);
+            for along in 0..frame.width {
+                for up in 0..frame.height {
+                    world.set_block(&frame.at(along, up), portal);
+                }
+            }
+            return true;
+        }
+    }
+    false
+}
+
+/// Finds the complete obsidian frame around `inside`
+fn find_frame(world: &World, inside: Vector3<i32>, axis: Axis) -> Option<PortalFrame> {
+    if !is_portal_air(world, &WorldPosition(inside)) {
+        return None;
+    }
+    // Go down to the bottom of the frame, then to its negative side
+    let mut corner = inside;
+    for _ in 0..MAX_PORTAL_SIZE {
+        let below = offset(corner, axis, 0, -1);
+        if !is_portal_air(world, &below) {
+            break;
+        }
+        corner = below.0;
+    }
+    for _ in 0..MAX_PORTAL_SIZE {
+        let next = offset(corner, axis, -1, 0);
+        if !is_portal_air(world, &next) {
+            break;
+        }
+        corner = next.0;
+    }
+    if !is_obsidian(world, &offset(corner, axis, -1, 0)) {
+        return None;
+    }
+    let width = (0..=MAX_PORTAL_SIZE)
+        .find(|along| !is_portal_air(world, &offset(corner, axis, *along, 0)))?;
+    if width < MIN_PORTAL_WIDTH || !is_obsidian(world, &offset(corner, axis, width, 0)) {
+        return None;
+    }
+    let mut frame = PortalFrame {
+        axis,
+        corner,
+        width,
+        height: 0,
+    };
+
+    // Every row needs obsidian on both sides, until a row of obsidian closes the frame
+    loop {
+        let up = frame.height;
+        if (0..width).all(|along| is_obsidian(world, &frame.at(along, up))) {
+            break;
+        }
+        let is_open_row = (0..width).all(|along| is_portal_air(world, &frame.at(along, up)))
+            && is_obsidian(world, &frame.at(-1, up))
+            && is_obsidian(world, &frame.at(width, up));
+        if !is_open_row || up >= MAX_PORTAL_SIZE {
+            return None;
+        }
+        frame.height += 1;
+    }
+    let has_bottom = (0..width).all(|along| is_obsidian(world, &frame.at(along, -1)));
+    (has_bottom && frame.height >= MIN_PORTAL_HEIGHT).then_some(frame)
+}
+
+/// Puts an eye of ender into an end portal frame and opens the portal once all frames around it have one.
+/// Returns whether the eye was put in
+pub fn insert_eye(world: &World, frame: WorldPosition) -> bool {
+    let Some((name, properties)) = world
+        .level
+        .get_block(&frame)
+        .and_then(|block| block.registry_state())
+    else {
+        return false;
+    };
+    if name != "minecraft:end_portal_frame"
+        || properties.get("eye").map(String::as_str) != Some("false")
+    {
+        return false;
+    }
+    let mut properties = properties.clone();
+    properties.insert("eye".to_string(), "true".to_string());
+    let Ok(filled) = BlockState::new(name, Some(&properties)) else {
+        return false;
+    };
+    world.set_block(&frame, filled.into());
+
+    // The portal is 3x3 blocks, the frame could be on any side of it
+    let frame = frame.0;
+    for center_x in frame.x - 2..=frame.x + 2 {
+        for center_z in frame.z - 2..=frame.z + 2 {
+            let center = Vector3::new(center_x, frame.y, center_z);
+            if end_portal_ring(center)
+                .iter()
+                .all(|position| has_eye(world, position))
+            {
+                let portal = block("minecraft:end_portal", &[]);
+                for x in -1..=1 {
+                    for z in -1..=1 {
+                        let position = Vector3::new(center.x + x, center.y, center.z + z);
+                        world.set_block(&WorldPosition(position), portal);
+                    }
+                }
+                return true;
+            }
+        }
+    }
+    true
+}
+
+/// The 12 frames around the 3x3 end portal at `center`
+fn end_portal_ring(center: Vector3<i32>) -> Vec<WorldPosition> {
+    (-1..=1)
+        .flat_map(|i| [(i, -2), (i, 2), (-2, i), (2, i)])
+        .map(|(x, z)| WorldPosition(Vector3::new(center.x + x, center.y, center.z + z)))
+        .collect()
+}
+
+fn has_eye(world: &World, position: &WorldPosition) -> bool {
+    world
+        .level
+        .get_block(position)
+        .and_then(|block| block.registry_state())
+        .is_some_and(|(name, properties)| {
+            name == "minecraft:end_portal_frame"
+                && properties.get("eye").map(String::as_str) == Some("true")
+        })
+}
+
+/// Sends players who stood in a portal long enough to the other dimension
+pub fn tick(server: &Server) {
+    for world in &server.worlds {
+        let players: Vec<Arc<Player>> = world.current_players.lock().values().cloned().collect();
+        for player in players {
+            tick_player(server, world, player);
+        }
+    }
+}
+
+fn tick_player(server: &Server, world: &Arc<World>, player: Arc<Player>) {
+    let position = player.living_entity.entity.block_pos.load();
+    let block = block_name(world, &position);
+    let state = player.portal.load();
+    let next = match (block, state) {
+        (Some("minecraft:nether_portal" | "minecraft:end_portal"), PortalState::Traveled) => {
+            PortalState::Traveled
+        }
+        (Some("minecraft:nether_portal"), state) => {
+            let ticks = match state {
+                PortalState::Inside(ticks) => ticks + 1,
+                _ => 1,
+            };
+            let delay = if player.gamemode.load() == GameMode::Creative {
+                1
+            } else {
+                NETHER_PORTAL_DELAY
+            };
+            if ticks < delay {
+                PortalState::Inside(ticks)
+            } else {
+                travel_through_nether_portal(server, world, player.clone());
+                PortalState::Traveled
+            }
+        }
+        (Some("minecraft:end_portal"), _) => {
+            travel_through_end_portal(server, world, player.clone());
+            PortalState::Traveled
+        }
+        _ => PortalState::Outside,
+    };
+    player.portal.store(next);
+}
+
+/// Sends a player to the nether portal linked with the one they stand in, from the Nether to the
+/// main world and from everywhere else into the Nether
+fn travel_through_nether_portal(server: &Server, from: &World, player: Arc<Player>) {
+    let to = if from.dimension == Dimension::Nether {
+        Dimension::OverWorld
+    } else {
+        Dimension::Nether
+    };
+    let Some(target) = server.get_dimension(to) else {
+        return;
+    };
+    // One block in the Nether is 8 in the Overworld
+    let scale = from.dimension.coordinate_scale() / to.coordinate_scale();
+    let position = player.living_entity.entity.pos.load();
+    let (min_y, max_y) = portal_heights(to);
+    let near = Vector3::new(
+        (position.x * scale).floor() as i32,
+        (position.y.floor() as i32).clamp(min_y, max_y),
+        (position.z * scale).floor() as i32,
+    );
+    tokio::spawn(async move {
+        let world = target.clone();
+        // Chunks may have to be generated, which blocks
+        let arrival =
+            tokio::task::spawn_blocking(move || find_or_create_nether_portal(&world, near)).await;
+        match arrival {
+            Ok(arrival) => player.change_world(target, arrival).await,
+            Err(err) => log::error!("Failed to find a nether portal: {err}"),
+        }
+    });
+}
+
+/// Sends a player into the End, or from the End back to the spawn of the main world
+fn travel_through_end_portal(server: &Server, from: &World, player: Arc<Player>) {
+    let to = if from.dimension == Dimension::End {
+        Dimension::OverWorld
+    } else {
+        Dimension::End
+    };
+    let Some(target) = server.get_dimension(to) else {
+        return;
+    };
+    tokio::spawn(async move {
+        let arrival = if to == Dimension::End {
+            let world = target.clone();
+            let built = tokio::task::spawn_blocking(move || build_end_platform(&world)).await;
+            if let Err(err) = built {
+                log::error!("Failed to build the End platform: {err}");
+                return;
+            }
+            END_SPAWN
+        } else {
+            target.spawn_position.load()
+        };
+        player.change_world(target, arrival).await;
+    });
+}
+
+/// The lowest and highest block a nether portal's inside may start at in a dimension
+fn portal_heights(dimension: Dimension) -> (i32, i32) {
+    let (lowest, highest) = match dimension {
+        Dimension::Nether => (NETHER_FLOOR, NETHER_CEILING),
+        Dimension::OverWorld | Dimension::End => (WORLD_LOWEST_Y as i32, WORLD_MAX_Y as i32 - 1),
+    };
+    // Room for the bedrock floor and ceiling, and the frame
+    (lowest + 6, highest - MIN_PORTAL_HEIGHT - 6)
+}
+
+/// Loads all chunks with a block within `radius` of `center` and keeps them loaded until
+/// `release_chunks` is called with the returned chunks
+fn load_chunks_around(world: &World, center: Vector3<i32>, radius: i32) -> Vec<Vector2<i32>> {
+    let mut chunks = Vec::new();
+    for chunk_x in (center.x - radius) >> 4..=(center.x + radius) >> 4 {
+        for chunk_z in (center.z - radius) >> 4..=(center.z + radius) >> 4 {
+            let at = Vector2::new(chunk_x, chunk_z);
+            world.level.add_ticket(at, TicketType::Portal);
+            world.level.load_chunk(at);
+            chunks.push(at);
+        }
+    }
+    chunks
+}
+
+fn release_chunks(world: &World, chunks: Vec<Vector2<i32>>) {
+    for at in chunks {
+        world.level.remove_ticket(at, TicketType::Portal);
+    }
+}
+
+/// Finds the nether portal closest to `near`, or builds a new one there.
+/// Returns where players arrive, at the bottom of the portal
+fn find_or_create_nether_portal(world: &World, near: Vector3<i32>) -> Vector3<f64> {
+    let chunks = load_chunks_around(world, near, PORTAL_SEARCH_RADIUS + 2);
+    let corner =
+        find_nether_portal(world, near).unwrap_or_else(|| build_nether_portal(world, near));
+    release_chunks(world, chunks);
+    Vector3::new(
+        f64::from(corner.x) + 0.5,
+        f64::from(corner.y),
+        f64::from(corner.z) + 0.5,
+    )
+}
+
+/// The lowest block of the nether portal closest to `near`
+fn find_nether_portal(world: &World, near: Vector3<i32>) -> Option<Vector3<i32>> {
+    let (min_y, max_y) = portal_heights(world.dimension);
+    let mut closest: Option<(i32, Vector3<i32>)> = None;
+    for x in near.x - PORTAL_SEARCH_RADIUS..=near.x + PORTAL_SEARCH_RADIUS {
+        for z in near.z - PORTAL_SEARCH_RADIUS..=near.z + PORTAL_SEARCH_RADIUS {
+            for y in min_y..=max_y + MIN_PORTAL_HEIGHT {
+                let position = Vector3::new(x, y, z);
+                if block_name(world, &WorldPosition(position)) != Some("minecraft:nether_portal") {
+                    continue;
+                }
+                let distance = (x - near.x).pow(2) + (y - near.y).pow(2) + (z - near.z).pow(2);
+                if closest.map_or(true, |(closest, _)| distance < closest) {
+                    closest = Some((distance, position));
+                }
+            }
+        }
+    }
+    let (_, mut portal) = closest?;
+    loop {
+        let below = offset(portal, Axis::X, 0, -1);
+        if block_name(world, &below) != Some("minecraft:nether_portal") {
+            break;
+        }
+        portal = below.0;
+    }
+    Some(portal)
+}
+
+/// Builds a 2x3 nether portal along the X axis, on the ground at or below `near` if there is room.
+/// Returns its lowest block on the negative side
+fn build_nether_portal(world: &World, near: Vector3<i32>) -> Vector3<i32> {
+    let (min_y, max_y) = portal_heights(world.dimension);
+    let has_room = |corner: Vector3<i32>| {
+        (-1..=2).all(|along| {
+            let ground = block_name(world, &offset(corner, Axis::X, along, -1));
+            let is_solid = ground.is_some_and(|ground| {
+                !matches!(
+                    ground,
+                    "minecraft:air" | "minecraft:cave_air" | "minecraft:lava" | "minecraft:water"
+                )
+            });
+            is_solid
+                && (0..=MIN_PORTAL_HEIGHT)
+                    .all(|up| is_portal_air(world, &offset(corner, Axis::X, along, up)))
+        })
+    };
+    let site = (min_y..=near.y.clamp(min_y, max_y))
+        .rev()
+        .map(|y| Vector3::new(near.x, y, near.z))
+        .find(|corner| has_room(*corner));
+
+    let obsidian = block("minecraft:obsidian", &[]);
+    let corner = site.unwrap_or_else(|| {
+        // No ground to stand on, e.g. above a lava sea. Carve out some room with a floor
+        let corner = Vector3::new(near.x, near.y.clamp(min_y, max_y), near.z);
+        let air = BlockId::default();
+        for x in -1..=2 {
+            for z in -1..=1 {
+                let position = Vector3::new(corner.x + x, corner.y, corner.z + z);
+                world.set_block(&offset(position, Axis::X, 0, -1), obsidian);
+                for up in 0..MIN_PORTAL_HEIGHT {
+                    world.set_block(&offset(position, Axis::X, 0, up), air);
+                }
+            }
+        }
+        corner
+    });
+
+    let portal = block("minecraft:nether_portal", &[("axis", Axis::X.name())]);
+    for along in -1..=MIN_PORTAL_WIDTH {
+        for up in -1..=MIN_PORTAL_HEIGHT {
+            let is_frame =
+                along == -1 || along == MIN_PORTAL_WIDTH || up == -1 || up == MIN_PORTAL_HEIGHT;
+            let block = if is_frame { obsidian } else { portal };
+            world.set_block(&offset(corner, Axis::X, along, up), block);
+        }
+    }
+    corner
+}
+
+/// Builds the 5x5 obsidian platform players arrive on in the End, and clears the room above it
+fn build_end_platform(world: &World) {
+    let center = Vector3::new(
+        END_SPAWN.x.floor() as i32,
+        END_SPAWN.y.floor() as i32,
+        END_SPAWN.z.floor() as i32,
+    );
+    let chunks = load_chunks_around(world, center, 2);
+    let obsidian = block("minecraft:obsidian", &[]);
+    let air = BlockId::default();
+    for x in -2..=2 {
+        for z in -2..=2 {
+            let position = Vector3::new(center.x + x, center.y, center.z + z);
+            world.set_block(&offset(position, Axis::X, 0, -1), obsidian);
+            for up in 0..3 {
+                world.set_block(&offset(position, Axis::X, 0, up), air);
+            }
+        }
+    }
+    release_chunks(world, chunks);
+}
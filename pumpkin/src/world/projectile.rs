@@ -0,0 +1,567 @@
+use std::{collections::HashSet, sync::Arc};
+
+use pumpkin_core::{
+    math::{boundingbox::BoundingBox, position::WorldPosition, vector2::Vector2, vector3::Vector3},
+    GameMode,
+};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_protocol::client::play::{
+    CEntityStatus, CPickupItem, CSetEntityMetadata, CSpawnEntity, Metadata,
+};
+use pumpkin_world::{
+    global_registry::{self, ITEM_REGISTRY},
+    item::ItemStack,
+    WORLD_LOWEST_Y, WORLD_MAX_Y,
+};
+use rand::Rng;
+use uuid::Uuid;
+
+use crate::{
+    client::Client,
+    entity::{damage::DamageSource, player::Player},
+    server::Server,
+};
+
+use super::{
+    is_simulated,
+    physics::{self, Body, Physics},
+    tracking::Tracked,
+    World,
+};
+
+/// Arrows hurt this much per block per tick they fly, like in vanilla
+const ARROW_DAMAGE: f64 = 2.0;
+/// Arrows stuck in a block vanish after this many ticks, flying ones after `MAX_AGE`
+const MAX_STUCK_AGE: u32 = 1200;
+const MAX_AGE: u32 = 1200;
+/// Projectiles this far below the world are gone
+const VOID_DEPTH: f64 = 64.0;
+/// Projectiles check for blocks and entities in their way at points this far apart
+const TRACE_STEP: f64 = 0.1;
+/// Entities are hit a bit outside of their bounding box, like in vanilla
+const HIT_MARGIN: f64 = 0.3;
+/// Players and mobs hit by a projectile are pushed away this much
+const KNOCKBACK: f64 = 0.4;
+/// Players who land with an ender pearl take this much damage
+const ENDER_PEARL_DAMAGE: f32 = 5.0;
+/// Players collect stuck arrows this far around their bounding box
+const PICKUP_RANGE: (f64, f64, f64) = (1.0, 0.5, 1.0);
+/// The entity event which shows the particles of a thrown item breaking
+const BREAK_PARTICLES: i8 = 3;
+
+/// What flies through the air
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProjectileKind {
+    Arrow,
+    Snowball,
+    EnderPearl,
+}
+
+impl ProjectileKind {
+    const fn entity_type(self) -> EntityType {
+        match self {
+            Self::Arrow => EntityType::Arrow,
+            Self::Snowball => EntityType::Snowball,
+            Self::EnderPearl => EntityType::EnderPearl,
+        }
+    }
+
+    const fn physics(self) -> &'static Physics {
+        match self {
+            Self::Arrow => &physics::ARROW,
+            Self::Snowball | Self::EnderPearl => &physics::THROWN_ITEM,
+        }
+    }
+
+    /// The kind of projectile the item is thrown as, None if it can't be thrown
+    pub fn thrown(item: &ItemStack) -> Option<Self> {
+        match global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id)? {
+            "minecraft:snowball" => Some(Self::Snowball),
+            "minecraft:ender_pearl" => Some(Self::EnderPearl),
+            _ => None,
+        }
+    }
+}
+
+/// Who may collect an arrow once it is stuck in a block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pickup {
+    /// Everyone, they get an arrow
+    Allowed,
+    /// Players in creative mode, they get nothing
+    CreativeOnly,
+    Disallowed,
+}
+
+/// A projectile which was launched this tick, it starts to fly with the next tick
+pub struct Launch {
+    pub kind: ProjectileKind,
+    pub position: Vector3<f64>,
+    pub velocity: Vector3<f64>,
+    /// The entity which shot or threw it
+    pub owner: Option<EntityId>,
+    /// Critical arrows hurt more, clients show particles behind them
+    pub critical: bool,
+    pub pickup: Pickup,
+}
+
+/// An arrow, snowball or ender pearl
+pub struct Projectile {
+    entity_id: EntityId,
+    uuid: Uuid,
+    kind: ProjectileKind,
+    body: Body,
+    owner: Option<EntityId>,
+    /// The owner can't be hit until the projectile got away from them
+    left_owner: bool,
+    /// The player an arrow bounced off, it doesn't hit them again
+    bounced_off: Option<EntityId>,
+    critical: bool,
+    pickup: Pickup,
+    /// The block an arrow is stuck in
+    stuck_in: Option<WorldPosition>,
+    /// Ticks since it was launched or got stuck
+    age: u32,
+}
+
+/// What a projectile hit on its way
+enum Hit {
+    Block,
+    Player(Arc<Player>),
+    Mob(EntityId),
+}
+
+/// A player or a mob a projectile may hit
+struct Target {
+    entity_id: EntityId,
+    bounding_box: BoundingBox,
+    player: Option<Arc<Player>>,
+}
+
+/// What happened to a projectile during a tick
+enum Flight {
+    Flying,
+    /// It waits because the blocks in its way are not loaded
+    Waiting,
+    Gone,
+}
+
+/// The direction an entity looks at with the rotation, like in vanilla
+pub fn look_direction(yaw: f32, pitch: f32) -> Vector3<f64> {
+    let (yaw, pitch) = (f64::from(yaw).to_radians(), f64::from(pitch).to_radians());
+    Vector3::new(
+        -yaw.sin() * pitch.cos(),
+        -pitch.sin(),
+        yaw.cos() * pitch.cos(),
+    )
+}
+
+/// The velocity of a projectile launched into `direction`, it deviates more the higher the
+/// inaccuracy is, like in vanilla
+pub fn launch_velocity(direction: Vector3<f64>, speed: f64, inaccuracy: f64) -> Vector3<f64> {
+    let mut rng = rand::thread_rng();
+    // Roughly the gaussian spread of vanilla
+    let mut spread = || (rng.gen::<f64>() - rng.gen::<f64>()) * 0.0075 * inaccuracy;
+    let direction = direction.normalize();
+    Vector3::new(
+        direction.x + spread(),
+        direction.y + spread(),
+        direction.z + spread(),
+    ) * speed
+}
+
+/// The yaw and the pitch of a projectile flying with the velocity, arrows point that way
+fn rotation(velocity: Vector3<f64>) -> (f32, f32) {
+    let horizontal = velocity.x.hypot(velocity.z);
+    (
+        velocity.x.atan2(velocity.z).to_degrees() as f32,
+        velocity.y.atan2(horizontal).to_degrees() as f32,
+    )
+}
+
+impl Projectile {
+    fn spawn_packet(&self) -> CSpawnEntity {
+        CSpawnEntity::new(
+            self.entity_id.into(),
+            self.uuid,
+            (self.kind.entity_type() as i32).into(),
+            self.body.synced_position().x,
+            self.body.synced_position().y,
+            self.body.synced_position().z,
+            self.body.pitch,
+            self.body.yaw,
+            self.body.yaw,
+            // Clients know who shot it
+            self.owner.unwrap_or_default().into(),
+            self.body.velocity.x as f32,
+            self.body.velocity.y as f32,
+            self.body.velocity.z as f32,
+        )
+    }
+
+    /// Critical arrows leave particles behind
+    fn critical_packet(&self) -> CSetEntityMetadata<i8> {
+        CSetEntityMetadata::new(
+            self.entity_id.into(),
+            Metadata::new(8, 0.into(), i8::from(self.critical)),
+        )
+    }
+
+    /// How much an arrow hurts, faster arrows hurt more
+    fn damage(&self) -> f32 {
+        let mut damage = (self.body.velocity.length() * ARROW_DAMAGE).ceil() as i32;
+        if self.critical {
+            damage += rand::thread_rng().gen_range(0..damage / 2 + 2);
+        }
+        damage as f32
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        self.body.bounding_box(self.kind.physics())
+    }
+}
+
+impl World {
+    /// Launches a projectile, it starts to fly with the next tick
+    pub fn launch_projectile(&self, launch: Launch) {
+        self.projectile_launches.lock().push(launch);
+    }
+
+    /// Moves the projectiles and lets them hit what is in their way. Projectiles outside of
+    /// the simulated chunks wait
+    pub(super) fn tick_projectiles(&self, server: &Server, simulated: &HashSet<Vector2<i32>>) {
+        let launches = std::mem::take(&mut *self.projectile_launches.lock());
+        self.projectiles
+            .lock()
+            .extend(launches.into_iter().map(|launch| {
+                let mut body = Body::new(launch.position, launch.velocity);
+                (body.yaw, body.pitch) = rotation(launch.velocity);
+                Projectile {
+                    entity_id: server.new_entity_id(),
+                    uuid: Uuid::new_v4(),
+                    kind: launch.kind,
+                    body,
+                    owner: launch.owner,
+                    left_owner: launch.owner.is_none(),
+                    bounced_off: None,
+                    critical: launch.critical,
+                    pickup: launch.pickup,
+                    stuck_in: None,
+                    age: 0,
+                }
+            }));
+
+        let mut targets: Vec<Target> = self
+            .current_players
+            .lock()
+            .values()
+            .filter(|player| {
+                player.gamemode.load() != GameMode::Spectator
+                    && player.living_entity.health.load() > 0.0
+            })
+            .map(|player| Target {
+                entity_id: player.entity_id(),
+                bounding_box: player.bounding_box(),
+                player: Some(player.clone()),
+            })
+            .collect();
+        targets.extend(
+            self.mob_bounding_boxes()
+                .into_iter()
+                .map(|(entity_id, bounding_box)| Target {
+                    entity_id,
+                    bounding_box,
+                    player: None,
+                }),
+        );
+
+        let mut projectiles = std::mem::take(&mut *self.projectiles.lock());
+        projectiles.retain_mut(|projectile| {
+            if !is_simulated(simulated, projectile.body.position) {
+                return true;
+            }
+            projectile.age += 1;
+            let max_age = if projectile.stuck_in.is_some() {
+                MAX_STUCK_AGE
+            } else {
+                MAX_AGE
+            };
+            if projectile.age > max_age
+                || projectile.body.position.y < f64::from(WORLD_LOWEST_Y) - VOID_DEPTH
+            {
+                return false;
+            }
+            if projectile.stuck_in.is_some() {
+                return self.tick_stuck_arrow(projectile, &targets);
+            }
+            match self.fly(server, projectile, &targets) {
+                Flight::Flying => {
+                    let physics = projectile.kind.physics();
+                    self.sync_body(projectile.entity_id, &mut projectile.body, physics);
+                    true
+                }
+                Flight::Waiting => true,
+                Flight::Gone => false,
+            }
+        });
+        self.projectiles.lock().append(&mut projectiles);
+    }
+
+    /// Moves a flying projectile by one tick. It stops at the first block or entity on its way
+    fn fly(&self, server: &Server, projectile: &mut Projectile, targets: &[Target]) -> Flight {
+        if !projectile.left_owner {
+            let bounding_box = projectile.bounding_box();
+            projectile.left_owner = !targets.iter().any(|target| {
+                Some(target.entity_id) == projectile.owner
+                    && target
+                        .bounding_box
+                        .expand(1.0, 1.0, 1.0)
+                        .intersects(&bounding_box)
+            });
+        }
+        let from = projectile.body.position;
+        let delta = projectile.body.velocity;
+        let steps = (delta.length() / TRACE_STEP).ceil().max(1.0) as u32;
+        let mut reached = from;
+        let mut hit = None;
+        for step in 1..=steps {
+            let point = from.add(&(delta * (f64::from(step) / f64::from(steps))));
+            let position = WorldPosition(Vector3::new(
+                point.x.floor() as i32,
+                point.y.floor() as i32,
+                point.z.floor() as i32,
+            ));
+            let Some(block) = self.level.get_block(&position) else {
+                // Nothing stops projectiles above or below the world
+                if point.y < f64::from(WORLD_LOWEST_Y) || point.y >= f64::from(WORLD_MAX_Y) {
+                    reached = point;
+                    continue;
+                }
+                return Flight::Waiting;
+            };
+            if block.has_collision() {
+                hit = Some((Hit::Block, Some(position)));
+                break;
+            }
+            let target = targets.iter().find(|target| {
+                (projectile.left_owner || Some(target.entity_id) != projectile.owner)
+                    && Some(target.entity_id) != projectile.bounced_off
+                    && target
+                        .bounding_box
+                        .expand(HIT_MARGIN, HIT_MARGIN, HIT_MARGIN)
+                        .squared_magnitude(point)
+                        == 0.0
+            });
+            reached = point;
+            if let Some(target) = target {
+                let hit_entity = match &target.player {
+                    Some(player) => Hit::Player(player.clone()),
+                    None => Hit::Mob(target.entity_id),
+                };
+                hit = Some((hit_entity, None));
+                break;
+            }
+        }
+        projectile.body.position = reached;
+        if let Some((hit, block)) = hit {
+            return self.hit(server, projectile, hit, block);
+        }
+
+        let physics = projectile.kind.physics();
+        let drag = if self.fluid_at(&projectile.body.block_pos()).is_some() {
+            physics.fluid_drag
+        } else {
+            physics.drag
+        };
+        let mut velocity = projectile.body.velocity * drag;
+        velocity.y -= physics.gravity;
+        projectile.body.velocity = velocity;
+        (projectile.body.yaw, projectile.body.pitch) = rotation(velocity);
+        Flight::Flying
+    }
+
+    /// What happens when a projectile hits something. Arrows stick in blocks, thrown items
+    /// break and ender pearls take their owner with them
+    fn hit(
+        &self,
+        server: &Server,
+        projectile: &mut Projectile,
+        hit: Hit,
+        block: Option<WorldPosition>,
+    ) -> Flight {
+        let position = projectile.body.position;
+        let direction = projectile.body.velocity;
+        let owner = projectile.owner.unwrap_or(projectile.entity_id);
+        // Players only hurt each other where PVP is enabled, and not their team mates if the
+        // team has no friendly fire
+        let thrower = projectile
+            .owner
+            .and_then(|owner| self.get_player_by_entityid(owner));
+        let harmless = thrower.is_some_and(|thrower| {
+            !self.pvp_enabled()
+                || matches!(&hit, Hit::Player(player) if !server.scoreboard.can_hurt(&thrower, player))
+        });
+        match projectile.kind {
+            ProjectileKind::Arrow => match hit {
+                Hit::Block => {
+                    projectile.stuck_in = block;
+                    projectile.age = 0;
+                    projectile.body.velocity = Vector3::new(0.0, 0.0, 0.0);
+                    projectile.critical = false;
+                    // Clients see it stop where it is stuck
+                    self.sync_body(projectile.entity_id, &mut projectile.body, &physics::ARROW);
+                    self.broadcast_tracked(projectile.entity_id, &projectile.critical_packet());
+                    return Flight::Flying;
+                }
+                Hit::Player(player) => {
+                    if !harmless
+                        && player.damage(projectile.damage(), DamageSource::Projectile(owner))
+                    {
+                        player.knockback_from(KNOCKBACK, position.sub(&direction));
+                        return Flight::Gone;
+                    }
+                    // Arrows bounce off players who can't be hurt
+                    projectile.body.velocity = direction * -0.1;
+                    projectile.bounced_off = Some(player.entity_id());
+                    return Flight::Flying;
+                }
+                // TODO: Mobs are only pushed until they have health
+                Hit::Mob(entity_id) => {
+                    self.knock_back_mob(entity_id, KNOCKBACK, position.sub(&direction));
+                    return Flight::Gone;
+                }
+            },
+            ProjectileKind::Snowball => match hit {
+                Hit::Block => {}
+                // Snowballs don't hurt, they only push
+                Hit::Player(player) => {
+                    if !harmless && player.damage(0.0, DamageSource::Projectile(owner)) {
+                        player.knockback_from(KNOCKBACK, position.sub(&direction));
+                    }
+                }
+                Hit::Mob(entity_id) => {
+                    self.knock_back_mob(entity_id, KNOCKBACK, position.sub(&direction));
+                }
+            },
+            ProjectileKind::EnderPearl => {
+                if let Hit::Player(player) = &hit {
+                    player.damage(0.0, DamageSource::Projectile(owner));
+                }
+                self.teleport_with_pearl(projectile.owner, position);
+            }
+        }
+        self.broadcast_tracked(
+            projectile.entity_id,
+            &CEntityStatus::new(projectile.entity_id, BREAK_PARTICLES),
+        );
+        Flight::Gone
+    }
+
+    /// Takes the player who threw an ender pearl to where it landed, they take some damage
+    fn teleport_with_pearl(&self, owner: Option<EntityId>, position: Vector3<f64>) {
+        let Some(player) = owner.and_then(|owner| self.get_player_by_entityid(owner)) else {
+            return;
+        };
+        if player.living_entity.health.load() <= 0.0 {
+            return;
+        }
+        let entity = &player.living_entity.entity;
+        player.teleport(
+            position.x,
+            position.y,
+            position.z,
+            entity.yaw.load(),
+            entity.pitch.load(),
+        );
+        player.living_entity.fall_distance.store(0.0);
+        player.damage(ENDER_PEARL_DAMAGE, DamageSource::Fall);
+    }
+
+    /// An arrow stuck in a block falls once the block is gone, players touching it may collect
+    /// it. Returns whether the arrow stays
+    fn tick_stuck_arrow(&self, projectile: &mut Projectile, targets: &[Target]) -> bool {
+        let Some(stuck_in) = projectile.stuck_in else {
+            return true;
+        };
+        match self.level.get_block(&stuck_in) {
+            Some(block) if !block.has_collision() => {
+                let mut rng = rand::thread_rng();
+                projectile.stuck_in = None;
+                projectile.body.velocity = Vector3::new(
+                    rng.gen::<f64>() * 0.2,
+                    rng.gen::<f64>() * 0.2,
+                    rng.gen::<f64>() * 0.2,
+                );
+                return true;
+            }
+            Some(_) => {}
+            None => return true,
+        }
+        if projectile.pickup == Pickup::Disallowed {
+            return true;
+        }
+        let bounding_box = projectile.bounding_box();
+        let (x, y, z) = PICKUP_RANGE;
+        let collector = targets.iter().find_map(|target| {
+            target.player.as_ref().filter(|_| {
+                target
+                    .bounding_box
+                    .expand(x, y, z)
+                    .intersects(&bounding_box)
+            })
+        });
+        let Some(player) = collector else {
+            return true;
+        };
+        match projectile.pickup {
+            Pickup::Allowed => {
+                let Some(arrow) =
+                    global_registry::find_protocol_id(ITEM_REGISTRY, "minecraft:arrow")
+                else {
+                    return true;
+                };
+                let mut left = Some(ItemStack::new(1, arrow));
+                player.inventory.lock().insert(&mut left);
+                if left.is_some() {
+                    return true;
+                }
+                player.set_container_content(None);
+            }
+            Pickup::CreativeOnly if player.gamemode.load() == GameMode::Creative => {}
+            Pickup::CreativeOnly | Pickup::Disallowed => return true,
+        }
+        self.broadcast_tracked(
+            projectile.entity_id,
+            &CPickupItem::new(
+                projectile.entity_id.into(),
+                player.entity_id().into(),
+                1.into(),
+            ),
+        );
+        false
+    }
+
+    /// Adds the projectiles to the entities players may see
+    pub(super) fn track_projectiles(&self, tracked: &mut Vec<Tracked>) {
+        tracked.extend(self.projectiles.lock().iter().map(|projectile| Tracked {
+            entity_id: projectile.entity_id,
+            entity_type: projectile.kind.entity_type(),
+            position: projectile.body.position,
+        }));
+    }
+
+    /// Shows the projectiles among `entity_ids` to a client which just started to see them
+    pub(super) fn send_projectiles(&self, client: &Client, entity_ids: &HashSet<EntityId>) {
+        for projectile in self
+            .projectiles
+            .lock()
+            .iter()
+            .filter(|projectile| entity_ids.contains(&projectile.entity_id))
+        {
+            client.send_packet(&projectile.spawn_packet());
+            if projectile.critical {
+                client.send_packet(&projectile.critical_packet());
+            }
+        }
+    }
+}
@@ -0,0 +1,358 @@
+use std::collections::{HashSet, VecDeque};
+
+use pumpkin_core::math::{position::WorldPosition, vector2::Vector2, vector3::Vector3};
+use pumpkin_world::{
+    block::{BlockId, BlockState, MAX_LIGHT},
+    game_rules,
+};
+use rand::Rng;
+
+use super::World;
+
+/// Crops and saplings need at least this much light to grow, like in vanilla
+const MIN_GROWTH_LIGHT: u8 = 9;
+/// Leaves further away from a log than this decay
+const MAX_LEAF_DISTANCE: u8 = 7;
+/// How far away water keeps farmland moist
+const FARMLAND_WATER_RANGE: i32 = 4;
+/// Blocks which keep the farmland below them from turning back into dirt
+const FARMLAND_CROP_CATEGORIES: [&str; 6] = [
+    "minecraft:attached_stem",
+    "minecraft:beetroot",
+    "minecraft:carrot",
+    "minecraft:crop",
+    "minecraft:potato",
+    "minecraft:stem",
+];
+
+/// The six blocks sharing a face with a block
+const NEIGHBORS: [Vector3<i32>; 6] = [
+    Vector3::new(1, 0, 0),
+    Vector3::new(-1, 0, 0),
+    Vector3::new(0, 1, 0),
+    Vector3::new(0, -1, 0),
+    Vector3::new(0, 0, 1),
+    Vector3::new(0, 0, -1),
+];
+
+fn offset(position: &WorldPosition, x: i32, y: i32, z: i32) -> WorldPosition {
+    WorldPosition(position.0.add(&Vector3::new(x, y, z)))
+}
+
+fn default_state(registry_id: &str) -> Option<BlockId> {
+    BlockState::new(registry_id, None).ok().map(Into::into)
+}
+
+fn is_liquid(block: &BlockId) -> bool {
+    block.category() == Some("minecraft:liquid")
+}
+
+fn is_water(block: &BlockId) -> bool {
+    block
+        .registry_state()
+        .is_some_and(|(registry_id, _)| registry_id == "minecraft:water")
+}
+
+impl World {
+    /// Gives random blocks in the chunks around players a tick, `randomTickSpeed` of them per
+    /// chunk section. This is what makes crops grow and leaves decay
+    pub(super) fn tick_random_blocks(&self, simulated: &HashSet<Vector2<i32>>) {
+        let Ok(per_section) = u32::try_from(self.game_rule_int(game_rules::RANDOM_TICK_SPEED))
+        else {
+            return;
+        };
+        if per_section == 0 {
+            return;
+        }
+        for chunk in simulated {
+            for (position, block) in self.level.random_tick_blocks(*chunk, per_section) {
+                self.random_tick(&position, block);
+            }
+        }
+    }
+
+    fn random_tick(&self, position: &WorldPosition, block: BlockId) {
+        match block.category() {
+            Some("minecraft:crop" | "minecraft:carrot" | "minecraft:potato") => {
+                self.grow_crop(position, block);
+            }
+            // Beetroots grow slower than other crops
+            Some("minecraft:beetroot") => {
+                if rand::thread_rng().gen_ratio(2, 3) {
+                    self.grow_crop(position, block);
+                }
+            }
+            Some("minecraft:sapling") => self.grow_sapling(position, block),
+            Some("minecraft:grass" | "minecraft:mycelium") => self.spread_grass(position, block),
+            Some("minecraft:leaves") => self.decay_leaves(position, block),
+            Some("minecraft:farm") => self.water_farmland(position, block),
+            _ => {}
+        }
+    }
+
+    fn set_block_if_changed(&self, position: &WorldPosition, old: BlockId, new: BlockId) {
+        if old != new {
+            self.set_block(position, new);
+        }
+    }
+
+    /// Ages a crop by one stage, faster on moist farmland like in vanilla
+    fn grow_crop(&self, position: &WorldPosition, block: BlockId) {
+        if self.level.get_light(position).unwrap_or(0) < MIN_GROWTH_LIGHT {
+            return;
+        }
+        let Some(age) = block.property("age").and_then(|age| age.parse::<u8>().ok()) else {
+            return;
+        };
+        // There is no state for the next age once the crop is fully grown
+        let Some(grown) = block.with_property("age", &(age + 1).to_string()) else {
+            return;
+        };
+
+        let mut growth_speed = 1.0;
+        for x in -1..=1 {
+            for z in -1..=1 {
+                let Some(soil) = self.level.get_block(&offset(position, x, -1, z)) else {
+                    continue;
+                };
+                let mut speed = if soil.category() != Some("minecraft:farm") {
+                    0.0
+                } else if soil.property("moisture") == Some("0") {
+                    1.0
+                } else {
+                    3.0
+                };
+                if x != 0 || z != 0 {
+                    speed /= 4.0;
+                }
+                growth_speed += speed;
+            }
+        }
+        let chance = (25.0 / growth_speed) as u32 + 1;
+        if rand::thread_rng().gen_ratio(1, chance) {
+            self.set_block(position, grown);
+        }
+    }
+
+    fn grow_sapling(&self, position: &WorldPosition, block: BlockId) {
+        let above = offset(position, 0, 1, 0);
+        if self.level.get_light(&above).unwrap_or(0) < MIN_GROWTH_LIGHT
+            || !rand::thread_rng().gen_ratio(1, 7)
+        {
+            return;
+        }
+        match block.property("stage") {
+            Some("0") => {
+                if let Some(grown) = block.with_property("stage", "1") {
+                    self.set_block(position, grown);
+                }
+            }
+            Some(_) => self.grow_tree(position, block),
+            None => {}
+        }
+    }
+
+    /// Grows a small tree of the wood of the sapling. Every sapling grows an oak shaped tree,
+    /// the bigger trees of vanilla are not generated yet
+    fn grow_tree(&self, position: &WorldPosition, sapling: BlockId) {
+        let Some((registry_id, _)) = sapling.registry_state() else {
+            return;
+        };
+        let Some(wood) = registry_id.strip_suffix("_sapling") else {
+            return;
+        };
+        let (Some(log), Some(leaves)) = (
+            default_state(&format!("{wood}_log")),
+            default_state(&format!("{wood}_leaves")),
+        ) else {
+            return;
+        };
+
+        let height = rand::thread_rng().gen_range(4..=6);
+        for y in 1..height {
+            if !self
+                .level
+                .get_block(&offset(position, 0, y, 0))
+                .is_some_and(|block| block.is_replaceable())
+            {
+                return;
+            }
+        }
+
+        for y in 0..height {
+            self.set_block(&offset(position, 0, y, 0), log);
+        }
+        let mut placed_leaves = Vec::new();
+        let mut rng = rand::thread_rng();
+        for y in height - 3..=height {
+            let top = y > height - 2;
+            let radius: i32 = if top { 1 } else { 2 };
+            for x in -radius..=radius {
+                for z in -radius..=radius {
+                    let corner = x.abs() == radius && z.abs() == radius;
+                    if corner && (top || rng.gen_bool(0.5)) {
+                        continue;
+                    }
+                    if x == 0 && z == 0 && y < height {
+                        continue;
+                    }
+                    let leaf = offset(position, x, y, z);
+                    if self
+                        .level
+                        .get_block(&leaf)
+                        .is_some_and(|block| block.is_replaceable())
+                    {
+                        self.set_block(&leaf, leaves);
+                        placed_leaves.push(leaf);
+                    }
+                }
+            }
+        }
+        for leaf in placed_leaves {
+            if let Some(distance) = self.leaf_distance(&leaf) {
+                if let Some(block) = leaves.with_property("distance", &distance.to_string()) {
+                    self.set_block(&leaf, block);
+                }
+            }
+        }
+    }
+
+    /// How many blocks the leaves are away from a log, following other leaves like in vanilla.
+    /// `MAX_LEAF_DISTANCE` if there is no log close enough, None if that's unknown because
+    /// a chunk on the way is not loaded
+    fn leaf_distance(&self, position: &WorldPosition) -> Option<u8> {
+        let mut visited = HashSet::from([position.0]);
+        let mut queue = VecDeque::from([(position.0, 0)]);
+        while let Some((current, distance)) = queue.pop_front() {
+            for direction in &NEIGHBORS {
+                let neighbor = current.add(direction);
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                let block = self.level.get_block(&WorldPosition(neighbor))?;
+                if block.is_log() {
+                    return Some(distance + 1);
+                }
+                if distance + 1 < MAX_LEAF_DISTANCE - 1
+                    && block.category() == Some("minecraft:leaves")
+                {
+                    queue.push_back((neighbor, distance + 1));
+                }
+            }
+        }
+        Some(MAX_LEAF_DISTANCE)
+    }
+
+    fn decay_leaves(&self, position: &WorldPosition, block: BlockId) {
+        if block.property("persistent") == Some("true") {
+            return;
+        }
+        let Some(distance) = self.leaf_distance(position) else {
+            return;
+        };
+        if distance >= MAX_LEAF_DISTANCE {
+            // TODO: Leaves drop saplings and sticks once there are loot tables
+            self.set_block(position, BlockId::default());
+        } else if let Some(new) = block.with_property("distance", &distance.to_string()) {
+            self.set_block_if_changed(position, block, new);
+        }
+    }
+
+    /// Whether grass can stay on a block, it needs light and no liquid on top
+    fn can_be_grass(&self, position: &WorldPosition) -> Option<bool> {
+        let above = self.level.get_block(&offset(position, 0, 1, 0))?;
+        Some(!is_liquid(&above) && above.light_opacity() < MAX_LIGHT)
+    }
+
+    /// Turns grass without light into dirt, or spreads it to dirt nearby
+    fn spread_grass(&self, position: &WorldPosition, block: BlockId) {
+        match self.can_be_grass(position) {
+            Some(false) => {
+                if let Some(dirt) = default_state("minecraft:dirt") {
+                    self.set_block(position, dirt);
+                }
+                return;
+            }
+            Some(true) => {}
+            None => return,
+        }
+        if self
+            .level
+            .get_light(&offset(position, 0, 1, 0))
+            .unwrap_or(0)
+            < MIN_GROWTH_LIGHT
+        {
+            return;
+        }
+        let Some((registry_id, _)) = block.registry_state() else {
+            return;
+        };
+        let Some(grass) = default_state(registry_id) else {
+            return;
+        };
+        let mut rng = rand::thread_rng();
+        for _ in 0..4 {
+            let target = offset(
+                position,
+                rng.gen_range(-1..=1),
+                rng.gen_range(-3..=1),
+                rng.gen_range(-1..=1),
+            );
+            let is_dirt = self.level.get_block(&target).is_some_and(|target| {
+                target
+                    .registry_state()
+                    .is_some_and(|(registry_id, _)| registry_id == "minecraft:dirt")
+            });
+            if is_dirt && self.can_be_grass(&target) == Some(true) {
+                self.set_block(&target, grass);
+            }
+        }
+    }
+
+    /// Keeps farmland near water moist, dry farmland without a crop turns back into dirt
+    fn water_farmland(&self, position: &WorldPosition, block: BlockId) {
+        let Some(moisture) = block
+            .property("moisture")
+            .and_then(|moisture| moisture.parse::<u8>().ok())
+        else {
+            return;
+        };
+        let mut near_water = false;
+        'search: for x in -FARMLAND_WATER_RANGE..=FARMLAND_WATER_RANGE {
+            for y in 0..=1 {
+                for z in -FARMLAND_WATER_RANGE..=FARMLAND_WATER_RANGE {
+                    match self.level.get_block(&offset(position, x, y, z)) {
+                        Some(block) if is_water(&block) => {
+                            near_water = true;
+                            break 'search;
+                        }
+                        Some(_) => {}
+                        // Better not dry out farmland next to chunks which are not loaded
+                        None => return,
+                    }
+                }
+            }
+        }
+
+        if near_water {
+            if let Some(moist) = block.with_property("moisture", "7") {
+                self.set_block_if_changed(position, block, moist);
+            }
+        } else if moisture > 0 {
+            if let Some(drier) = block.with_property("moisture", &(moisture - 1).to_string()) {
+                self.set_block(position, drier);
+            }
+        } else {
+            let has_crop = self
+                .level
+                .get_block(&offset(position, 0, 1, 0))
+                .and_then(|above| above.category())
+                .is_some_and(|category| FARMLAND_CROP_CATEGORIES.contains(&category));
+            if !has_crop {
+                if let Some(dirt) = default_state("minecraft:dirt") {
+                    self.set_block(position, dirt);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,731 @@
+//! Redstone dust, the blocks powering it and the blocks it powers.
+//!
+//! Changed blocks queue updates for the blocks around them, which are handled once per tick
+//! until nothing changes anymore. Things which happen later, like a button popping back out
+//! or a redstone torch turning off, are scheduled ticks.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use pumpkin_core::{
+    math::{boundingbox::BoundingBox, position::WorldPosition, vector3::Vector3},
+    GameMode,
+};
+use pumpkin_world::block::{BlockId, BlockState, MAX_LIGHT};
+
+use crate::server::Server;
+
+use super::{tnt, World};
+
+/// The strongest redstone signal
+const MAX_POWER: u8 = 15;
+/// How many blocks a piston can push at once, like in vanilla
+const MAX_PUSHED_BLOCKS: usize = 12;
+/// Dust networks are recalculated as a whole, this keeps a huge one from stalling the tick
+const MAX_WIRE_NETWORK: usize = 4096;
+/// Handling updates queues more updates, changes left after this many rounds wait for the next tick
+const MAX_UPDATE_ROUNDS: usize = 64;
+/// How long redstone torches take to turn on or off
+const TORCH_DELAY: i64 = 2;
+/// How long redstone lamps stay lit after losing power
+const LAMP_OFF_DELAY: i64 = 4;
+/// How often pressure plates check whether something still stands on them
+const PRESSURE_PLATE_DELAY: i64 = 20;
+const WEIGHTED_PRESSURE_PLATE_DELAY: i64 = 10;
+
+/// Blocks pistons can't move, besides unbreakable blocks and blocks with a block entity
+const IMMOVABLE_BLOCKS: [&str; 6] = [
+    "minecraft:crying_obsidian",
+    "minecraft:obsidian",
+    "minecraft:piston_head",
+    "minecraft:moving_piston",
+    "minecraft:reinforced_deepslate",
+    "minecraft:respawn_anchor",
+];
+
+const HORIZONTAL_DIRECTIONS: [&str; 4] = ["north", "east", "south", "west"];
+const DIRECTIONS: [&str; 6] = ["north", "east", "south", "west", "up", "down"];
+
+/// Something happening to a block later, e.g. a pressed button popping back out
+pub(super) struct ScheduledTick {
+    position: Vector3<i32>,
+    /// The world age at which it happens
+    due: i64,
+}
+
+/// The offset of a direction as used by block properties, e.g. the `facing` of a piston
+pub(super) fn offset_of(direction: &str) -> Option<Vector3<i32>> {
+    match direction {
+        "north" => Some(Vector3::new(0, 0, -1)),
+        "east" => Some(Vector3::new(1, 0, 0)),
+        "south" => Some(Vector3::new(0, 0, 1)),
+        "west" => Some(Vector3::new(-1, 0, 0)),
+        "up" => Some(Vector3::new(0, 1, 0)),
+        "down" => Some(Vector3::new(0, -1, 0)),
+        _ => None,
+    }
+}
+
+fn relative(position: &WorldPosition, offset: Vector3<i32>) -> WorldPosition {
+    WorldPosition(position.0.add(&offset))
+}
+
+fn neighbors(position: &WorldPosition) -> impl Iterator<Item = WorldPosition> + '_ {
+    DIRECTIONS
+        .iter()
+        .filter_map(|direction| offset_of(direction))
+        .map(|offset| relative(position, offset))
+}
+
+fn registry_id(block: &BlockId) -> &'static str {
+    block
+        .registry_state()
+        .map_or("", |(registry_id, _)| registry_id)
+}
+
+fn default_state(registry_id: &str) -> Option<BlockId> {
+    BlockState::new(registry_id, None).ok().map(Into::into)
+}
+
+fn is_wire(block: &BlockId) -> bool {
+    block.category() == Some("minecraft:redstone_wire")
+}
+
+fn power_property(block: &BlockId, name: &str) -> u8 {
+    block
+        .property(name)
+        .and_then(|power| power.parse().ok())
+        .unwrap_or(0)
+}
+
+fn is_powered(block: &BlockId) -> bool {
+    block.property("powered") == Some("true")
+}
+
+/// Whether redstone power goes through the block, solid blocks like stone do this
+fn is_conductor(block: &BlockId) -> bool {
+    block.has_collision()
+        && block.light_opacity() >= MAX_LIGHT
+        && !matches!(
+            block.category(),
+            Some("minecraft:powered" | "minecraft:piston_base")
+        )
+}
+
+/// Whether redstone dust next to the block points into it
+fn is_power_component(block: &BlockId) -> bool {
+    matches!(
+        block.category(),
+        Some(
+            "minecraft:button"
+                | "minecraft:lever"
+                | "minecraft:powered"
+                | "minecraft:pressure_plate"
+                | "minecraft:redstone_torch"
+                | "minecraft:redstone_wall_torch"
+                | "minecraft:weighted_pressure_plate"
+        )
+    )
+}
+
+/// Whether a piston can push or pull the block
+fn is_movable(block: &BlockId) -> bool {
+    !block.is_unbreakable()
+        && !IMMOVABLE_BLOCKS.contains(&registry_id(block))
+        && !(block.category() == Some("minecraft:piston_base")
+            && block.property("extended") == Some("true"))
+}
+
+/// The block a lever, button or redstone torch hangs on
+fn attached_to(position: &WorldPosition, block: &BlockId) -> Option<WorldPosition> {
+    let facing = || block.property("facing").and_then(offset_of);
+    match block.category()? {
+        "minecraft:redstone_torch" => Some(relative(position, Vector3::new(0, -1, 0))),
+        "minecraft:redstone_wall_torch" => Some(relative(position, facing()? * -1)),
+        "minecraft:button" | "minecraft:lever" => match block.property("face")? {
+            "floor" => Some(relative(position, Vector3::new(0, -1, 0))),
+            "ceiling" => Some(relative(position, Vector3::new(0, 1, 0))),
+            _ => Some(relative(position, facing()? * -1)),
+        },
+        _ => None,
+    }
+}
+
+/// How long a button stays pressed, wooden buttons stay pressed longer
+fn button_ticks(block: &BlockId) -> i64 {
+    match registry_id(block) {
+        "minecraft:stone_button" | "minecraft:polished_blackstone_button" => 20,
+        _ => 30,
+    }
+}
+
+/// The power a block gives to the block next to it. Strong power also goes through the
+/// solid block it reaches, e.g. from a lever into the block it hangs on
+fn emitted_power(
+    position: &WorldPosition,
+    block: &BlockId,
+    target: &WorldPosition,
+    strong: bool,
+    wires: bool,
+) -> u8 {
+    let below = relative(position, Vector3::new(0, -1, 0));
+    match block.category() {
+        Some("minecraft:powered") if !strong => MAX_POWER,
+        Some("minecraft:button" | "minecraft:lever") if is_powered(block) => {
+            if !strong || attached_to(position, block).is_some_and(|at| at.0 == target.0) {
+                MAX_POWER
+            } else {
+                0
+            }
+        }
+        Some("minecraft:pressure_plate" | "minecraft:weighted_pressure_plate") => {
+            let power = if block.category() == Some("minecraft:pressure_plate") {
+                if is_powered(block) {
+                    MAX_POWER
+                } else {
+                    0
+                }
+            } else {
+                power_property(block, "power")
+            };
+            if !strong || below.0 == target.0 {
+                power
+            } else {
+                0
+            }
+        }
+        Some("minecraft:redstone_torch" | "minecraft:redstone_wall_torch")
+            if block.property("lit") == Some("true") =>
+        {
+            let powers = if strong {
+                target.0 == relative(position, Vector3::new(0, 1, 0)).0
+            } else {
+                attached_to(position, block).is_none_or(|at| at.0 != target.0)
+            };
+            if powers {
+                MAX_POWER
+            } else {
+                0
+            }
+        }
+        // Dust powers the block below it and the blocks it points into, strongly as well
+        Some("minecraft:redstone_wire") if wires => {
+            let direction = target.0.sub(&position.0);
+            let points_at = match direction.y {
+                -1 => true,
+                1 => false,
+                _ => HORIZONTAL_DIRECTIONS.iter().any(|name| {
+                    offset_of(name) == Some(direction)
+                        && block.property(name).is_some_and(|side| side != "none")
+                }),
+            };
+            if points_at {
+                power_property(block, "power")
+            } else {
+                0
+            }
+        }
+        _ => 0,
+    }
+}
+
+/// The dust block with its sides pointing to what it connects to. Dust connecting to
+/// nothing is a cross, dust connecting to one side is a line
+fn wire_shape(block: &BlockId, connections: &[(&str, &str, WorldPosition)]) -> BlockId {
+    let mut sides: HashMap<&str, &str> = connections
+        .iter()
+        .map(|(name, connection, _)| (*name, *connection))
+        .collect();
+    if let [(name, _, _)] = connections {
+        let opposite = match *name {
+            "north" => "south",
+            "south" => "north",
+            "east" => "west",
+            _ => "east",
+        };
+        sides.insert(opposite, "side");
+    }
+    let cross = sides.is_empty();
+    HORIZONTAL_DIRECTIONS.iter().fold(*block, |block, name| {
+        let side = sides
+            .get(name)
+            .copied()
+            .unwrap_or(if cross { "side" } else { "none" });
+        block.with_property(name, side).unwrap_or(block)
+    })
+}
+
+impl World {
+    /// Queues updates for the block and the blocks around it, they may react to the change.
+    /// Redstone reaches through solid blocks, so blocks two steps away get an update as well
+    pub(super) fn queue_block_updates(&self, position: &WorldPosition) {
+        let mut updates = self.block_updates.lock();
+        for x in -2i32..=2 {
+            for y in -2i32..=2 {
+                for z in -2i32..=2 {
+                    if x.abs() + y.abs() + z.abs() <= 2 {
+                        updates.insert(position.0.add(&Vector3::new(x, y, z)));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Lets the block do something in `delay` ticks, unless it already waits for that
+    fn schedule_tick(&self, position: &WorldPosition, delay: i64) {
+        let due = self.world_age.load(std::sync::atomic::Ordering::Relaxed) + delay;
+        let mut scheduled = self.scheduled_ticks.lock();
+        if !scheduled.iter().any(|tick| tick.position == position.0) {
+            scheduled.push(ScheduledTick {
+                position: position.0,
+                due,
+            });
+        }
+    }
+
+    /// Runs the scheduled ticks which are due, presses pressure plates and handles the queued
+    /// block updates
+    pub(super) fn tick_redstone(&self, server: &Server, world_age: i64) {
+        let due: Vec<_> = {
+            let mut scheduled = self.scheduled_ticks.lock();
+            let (due, later): (Vec<_>, Vec<_>) = std::mem::take(&mut *scheduled)
+                .into_iter()
+                .partition(|tick| tick.due <= world_age);
+            *scheduled = later;
+            due
+        };
+        for tick in due {
+            self.scheduled_tick(&WorldPosition(tick.position));
+        }
+
+        self.press_pressure_plates();
+
+        for _ in 0..MAX_UPDATE_ROUNDS {
+            let updates = std::mem::take(&mut *self.block_updates.lock());
+            if updates.is_empty() {
+                break;
+            }
+            // Recalculating a dust network updates all of its dust at once
+            let mut updated_wires = HashSet::new();
+            for position in updates {
+                if !updated_wires.contains(&position) {
+                    self.update_block(server, &WorldPosition(position), &mut updated_wires);
+                }
+            }
+        }
+    }
+
+    /// Lets a block react to a change next to it
+    fn update_block(
+        &self,
+        server: &Server,
+        position: &WorldPosition,
+        updated_wires: &mut HashSet<Vector3<i32>>,
+    ) {
+        let Some(block) = self.level.get_block(position) else {
+            return;
+        };
+        match block.category() {
+            Some("minecraft:redstone_wire") => self.update_wire_network(position, updated_wires),
+            Some("minecraft:redstone_torch" | "minecraft:redstone_wall_torch") => {
+                if self.torch_should_be_lit(position, &block)
+                    != (block.property("lit") == Some("true"))
+                {
+                    self.schedule_tick(position, TORCH_DELAY);
+                }
+            }
+            Some("minecraft:redstone_lamp") => {
+                let lit = block.property("lit") == Some("true");
+                if self.received_power(position, true) > 0 {
+                    if !lit {
+                        self.set_property(position, &block, "lit", "true");
+                    }
+                } else if lit {
+                    self.schedule_tick(position, LAMP_OFF_DELAY);
+                }
+            }
+            Some("minecraft:piston_base") => self.update_piston(position, &block),
+            Some("minecraft:tnt") => {
+                if self.received_power(position, true) > 0 {
+                    self.prime_tnt(server, position, tnt::DEFAULT_FUSE);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn scheduled_tick(&self, position: &WorldPosition) {
+        let Some(block) = self.level.get_block(position) else {
+            return;
+        };
+        match block.category() {
+            Some("minecraft:button") => {
+                if is_powered(&block) {
+                    self.set_property(position, &block, "powered", "false");
+                }
+            }
+            Some("minecraft:redstone_torch" | "minecraft:redstone_wall_torch") => {
+                let lit = self.torch_should_be_lit(position, &block);
+                self.set_property(position, &block, "lit", if lit { "true" } else { "false" });
+            }
+            Some("minecraft:redstone_lamp") => {
+                if self.received_power(position, true) == 0 {
+                    self.set_property(position, &block, "lit", "false");
+                }
+            }
+            Some("minecraft:pressure_plate" | "minecraft:weighted_pressure_plate") => {
+                self.update_pressure_plate(position, &block);
+            }
+            _ => {}
+        }
+    }
+
+    /// Changes a property of a block, if the block has it
+    fn set_property(&self, position: &WorldPosition, block: &BlockId, name: &str, value: &str) {
+        if let Some(changed) = block.with_property(name, value) {
+            if changed != *block {
+                self.set_block(position, changed);
+            }
+        }
+    }
+
+    /// Flips a lever or presses a button a player clicked.
+    /// Returns false if the block is neither
+    pub fn use_redstone_block(&self, position: &WorldPosition) -> bool {
+        let Some(block) = self.level.get_block(position) else {
+            return false;
+        };
+        match block.category() {
+            Some("minecraft:lever") => {
+                let powered = if is_powered(&block) { "false" } else { "true" };
+                self.set_property(position, &block, "powered", powered);
+                true
+            }
+            Some("minecraft:button") => {
+                if !is_powered(&block) {
+                    self.set_property(position, &block, "powered", "true");
+                    self.schedule_tick(position, button_ticks(&block));
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The power a block gets from the block next to it. Solid blocks pass on the strong power
+    /// they get. Dust ignores the power of other dust, that is what its own power level is for
+    fn power_from(&self, target: &WorldPosition, source: &WorldPosition, wires: bool) -> u8 {
+        let Some(block) = self.level.get_block(source) else {
+            return 0;
+        };
+        if !is_conductor(&block) {
+            return emitted_power(source, &block, target, false, wires);
+        }
+        neighbors(source)
+            .filter_map(|neighbor| {
+                let block = self.level.get_block(&neighbor)?;
+                Some(emitted_power(&neighbor, &block, source, true, wires))
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The strongest power the block gets from any side
+    fn received_power(&self, position: &WorldPosition, wires: bool) -> u8 {
+        neighbors(position)
+            .map(|neighbor| self.power_from(position, &neighbor, wires))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Redstone torches turn off while the block they hang on is powered
+    fn torch_should_be_lit(&self, position: &WorldPosition, block: &BlockId) -> bool {
+        attached_to(position, block)
+            .is_none_or(|attached| self.power_from(position, &attached, true) == 0)
+    }
+
+    /// The dust connected to the dust at the position, with how it connects in every direction
+    fn wire_connections(
+        &self,
+        position: &WorldPosition,
+    ) -> Vec<(&'static str, &'static str, WorldPosition)> {
+        let above_conducts = self
+            .level
+            .get_block(&relative(position, Vector3::new(0, 1, 0)))
+            .is_some_and(|block| is_conductor(&block));
+        let mut connections = Vec::new();
+        for name in HORIZONTAL_DIRECTIONS {
+            let Some(offset) = offset_of(name) else {
+                continue;
+            };
+            let side = relative(position, offset);
+            let Some(side_block) = self.level.get_block(&side) else {
+                continue;
+            };
+            if is_wire(&side_block) {
+                connections.push((name, "side", side));
+                continue;
+            }
+            let up = relative(&side, Vector3::new(0, 1, 0));
+            if !above_conducts
+                && self
+                    .level
+                    .get_block(&up)
+                    .is_some_and(|block| is_wire(&block))
+            {
+                // Dust climbs up the side of solid blocks
+                let connection = if is_conductor(&side_block) {
+                    "up"
+                } else {
+                    "side"
+                };
+                connections.push((name, connection, up));
+                continue;
+            }
+            let down = relative(&side, Vector3::new(0, -1, 0));
+            if !is_conductor(&side_block)
+                && self
+                    .level
+                    .get_block(&down)
+                    .is_some_and(|block| is_wire(&block))
+            {
+                connections.push((name, "side", down));
+                continue;
+            }
+            if is_power_component(&side_block) {
+                connections.push((name, "side", side));
+            }
+        }
+        connections
+    }
+
+    /// Recalculates the power and shape of all dust connected to the dust at the position.
+    /// Power spreads from the dust next to a power source, losing one level per block
+    fn update_wire_network(
+        &self,
+        start: &WorldPosition,
+        updated_wires: &mut HashSet<Vector3<i32>>,
+    ) {
+        let mut network: HashMap<Vector3<i32>, Vec<(&str, &str, WorldPosition)>> = HashMap::new();
+        let mut queue = VecDeque::from([*start]);
+        while let Some(position) = queue.pop_front() {
+            if network.contains_key(&position.0) || network.len() >= MAX_WIRE_NETWORK {
+                continue;
+            }
+            let connections = self.wire_connections(&position);
+            for (_, _, connected) in &connections {
+                if !network.contains_key(&connected.0)
+                    && self
+                        .level
+                        .get_block(connected)
+                        .is_some_and(|block| is_wire(&block))
+                {
+                    queue.push_back(*connected);
+                }
+            }
+            network.insert(position.0, connections);
+        }
+
+        let mut power: HashMap<Vector3<i32>, u8> = network
+            .keys()
+            .map(|position| {
+                let position = WorldPosition(*position);
+                (position.0, self.received_power(&position, false))
+            })
+            .collect();
+        // Handles the strongest dust first, so every dust is only lowered once
+        let mut by_power: Vec<Vec<Vector3<i32>>> = vec![Vec::new(); MAX_POWER as usize + 1];
+        for (position, level) in &power {
+            by_power[*level as usize].push(*position);
+        }
+        for level in (1..=MAX_POWER).rev() {
+            let positions = std::mem::take(&mut by_power[level as usize]);
+            for position in positions {
+                if power[&position] != level {
+                    continue;
+                }
+                for (_, _, connected) in &network[&position] {
+                    if let Some(connected_power) = power.get_mut(&connected.0) {
+                        if *connected_power < level - 1 {
+                            *connected_power = level - 1;
+                            by_power[level as usize - 1].push(connected.0);
+                        }
+                    }
+                }
+            }
+        }
+
+        for (position, connections) in &network {
+            updated_wires.insert(*position);
+            let position = WorldPosition(*position);
+            let Some(block) = self.level.get_block(&position) else {
+                continue;
+            };
+            let shaped = wire_shape(&block, connections);
+            let powered = shaped
+                .with_property("power", &power[&position.0].to_string())
+                .unwrap_or(shaped);
+            if powered != block {
+                self.set_block(&position, powered);
+            }
+        }
+    }
+
+    /// Presses the pressure plates players stand on
+    fn press_pressure_plates(&self) {
+        let positions: Vec<_> = self
+            .current_players
+            .lock()
+            .values()
+            .filter(|player| player.gamemode.load() != GameMode::Spectator)
+            .map(|player| player.living_entity.entity.block_pos.load())
+            .collect();
+        for position in positions {
+            let Some(block) = self.level.get_block(&position) else {
+                continue;
+            };
+            let pressed = match block.category() {
+                Some("minecraft:pressure_plate") => is_powered(&block),
+                Some("minecraft:weighted_pressure_plate") => power_property(&block, "power") > 0,
+                _ => continue,
+            };
+            if !pressed {
+                self.update_pressure_plate(&position, &block);
+            }
+        }
+    }
+
+    /// Powers a pressure plate as long as players stand on it.
+    /// Weighted plates get stronger the more players stand on them
+    fn update_pressure_plate(&self, position: &WorldPosition, block: &BlockId) {
+        let plate = BoundingBox::new(
+            position.0.x as f64,
+            position.0.y as f64,
+            position.0.z as f64,
+            position.0.x as f64 + 1.0,
+            position.0.y as f64 + 0.25,
+            position.0.z as f64 + 1.0,
+        );
+        let players = self
+            .current_players
+            .lock()
+            .values()
+            .filter(|player| {
+                player.gamemode.load() != GameMode::Spectator
+                    && player.bounding_box().intersects(&plate)
+            })
+            .count();
+        if block.category() == Some("minecraft:pressure_plate") {
+            let pressed = players > 0;
+            self.set_property(
+                position,
+                block,
+                "powered",
+                if pressed { "true" } else { "false" },
+            );
+            if pressed {
+                self.schedule_tick(position, PRESSURE_PLATE_DELAY);
+            }
+        } else {
+            let max_weight = match registry_id(block) {
+                "minecraft:light_weighted_pressure_plate" => 15.0,
+                _ => 150.0,
+            };
+            let weight = (players as f64).min(max_weight);
+            let power = (weight / max_weight * f64::from(MAX_POWER)).ceil() as u8;
+            self.set_property(position, block, "power", &power.to_string());
+            if power > 0 {
+                self.schedule_tick(position, WEIGHTED_PRESSURE_PLATE_DELAY);
+            }
+        }
+    }
+
+    /// Extends a piston which got powered and retracts one which lost its power.
+    /// Pistons move the blocks right away, without the animation of vanilla
+    fn update_piston(&self, position: &WorldPosition, block: &BlockId) {
+        let Some(facing) = block.property("facing") else {
+            return;
+        };
+        let Some(front) = offset_of(facing) else {
+            return;
+        };
+        let front_position = relative(position, front);
+        // Pistons don't get power from the block they push against
+        let powered = neighbors(position)
+            .filter(|neighbor| neighbor.0 != front_position.0)
+            .any(|neighbor| self.power_from(position, &neighbor, true) > 0);
+        let extended = block.property("extended") == Some("true");
+        if powered && !extended {
+            self.extend_piston(position, block, facing, front);
+        } else if !powered && extended {
+            self.retract_piston(position, block, front);
+        }
+    }
+
+    fn extend_piston(
+        &self,
+        position: &WorldPosition,
+        block: &BlockId,
+        facing: &str,
+        front: Vector3<i32>,
+    ) {
+        let mut pushed = Vec::new();
+        let mut next = relative(position, front);
+        loop {
+            let Some(next_block) = self.level.get_block(&next) else {
+                return;
+            };
+            // Pistons break e.g. grass in their way
+            if next_block.is_replaceable() {
+                break;
+            }
+            if pushed.len() == MAX_PUSHED_BLOCKS
+                || !is_movable(&next_block)
+                || self.level.get_block_entity(&next).is_some()
+            {
+                return;
+            }
+            pushed.push((next, next_block));
+            next = relative(&next, front);
+        }
+
+        let sticky = registry_id(block) == "minecraft:sticky_piston";
+        let Some(head) = default_state("minecraft:piston_head")
+            .and_then(|head| head.with_property("facing", facing))
+            .and_then(|head| head.with_property("type", if sticky { "sticky" } else { "normal" }))
+        else {
+            return;
+        };
+        self.set_property(position, block, "extended", "true");
+        for (from, pushed_block) in pushed.iter().rev() {
+            self.set_block(&relative(from, front), *pushed_block);
+        }
+        self.set_block(&relative(position, front), head);
+    }
+
+    fn retract_piston(&self, position: &WorldPosition, block: &BlockId, front: Vector3<i32>) {
+        self.set_property(position, block, "extended", "false");
+        let head = relative(position, front);
+        if !self
+            .level
+            .get_block(&head)
+            .is_some_and(|head| head.category() == Some("minecraft:piston_head"))
+        {
+            return;
+        }
+        // Sticky pistons pull the block in front of their head back with them
+        let pulled = relative(&head, front);
+        let pulled_block = self.level.get_block(&pulled).filter(|pulled_block| {
+            registry_id(block) == "minecraft:sticky_piston"
+                && !pulled_block.is_replaceable()
+                && is_movable(pulled_block)
+                && self.level.get_block_entity(&pulled).is_none()
+        });
+        match pulled_block {
+            Some(pulled_block) => {
+                self.set_block(&head, pulled_block);
+                self.set_block(&pulled, BlockId::default());
+            }
+            None => self.set_block(&head, BlockId::default()),
+        }
+    }
+}
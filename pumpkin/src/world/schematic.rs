@@ -0,0 +1,37 @@
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_world::schematic::{Rotation, Schematic};
+
+use super::World;
+
+impl World {
+    /// Pastes a schematic with its lowest corner at `origin`, turned around the vertical axis.
+    /// The blocks are set in batches like with `set_blocks`, which loads the chunks they are in,
+    /// so it blocks and should not be called from async code directly.
+    /// Returns how many blocks changed
+    pub fn paste(
+        &self,
+        schematic: &Schematic,
+        origin: &WorldPosition,
+        rotation: Rotation,
+    ) -> usize {
+        let place = |position: Vector3<i32>| {
+            WorldPosition(
+                origin
+                    .0
+                    .add(&rotation.rotate_position(position, schematic.size)),
+            )
+        };
+        let pasted = self.set_blocks(
+            schematic
+                .blocks
+                .iter()
+                .map(|(position, block)| (place(*position), rotation.rotate_block(*block))),
+        );
+        for entity in &schematic.block_entities {
+            let mut entity = entity.clone();
+            entity.position = place(entity.position).0;
+            self.set_block_entity(entity);
+        }
+        pasted
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::HashSet;
+
+use pumpkin_core::math::{position::WorldPosition, vector2::Vector2, vector3::Vector3};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_protocol::{
+    client::play::{CSetEntityMetadata, CSpawnEntity, Metadata},
+    VarInt,
+};
+use pumpkin_world::block::BlockId;
+use uuid::Uuid;
+
+use crate::{client::Client, server::Server};
+
+use super::{
+    is_simulated,
+    physics::{self, Body},
+    tracking::Tracked,
+    World,
+};
+
+/// How many ticks lit TNT takes to explode, like in vanilla
+pub const DEFAULT_FUSE: u32 = 80;
+/// The power of a TNT explosion
+const POWER: f32 = 4.0;
+/// Lit TNT hops up a little, into a random direction
+const JUMP_VELOCITY: f64 = 0.2;
+const JUMP_SPREAD: f64 = 0.02;
+
+/// Lit TNT which explodes once its fuse ran out
+pub struct PrimedTnt {
+    entity_id: EntityId,
+    uuid: Uuid,
+    body: Body,
+    /// Ticks until it explodes
+    fuse: u32,
+}
+
+impl PrimedTnt {
+    fn spawn_packet(&self) -> CSpawnEntity {
+        CSpawnEntity::new(
+            self.entity_id.into(),
+            self.uuid,
+            (EntityType::Tnt as i32).into(),
+            self.body.synced_position().x,
+            self.body.synced_position().y,
+            self.body.synced_position().z,
+            0.0,
+            0.0,
+            0.0,
+            0.into(),
+            self.body.velocity.x as f32,
+            self.body.velocity.y as f32,
+            self.body.velocity.z as f32,
+        )
+    }
+
+    /// Clients count down the fuse by themselves, this tells them where it's at
+    fn fuse_packet(&self) -> CSetEntityMetadata<VarInt> {
+        CSetEntityMetadata::new(
+            self.entity_id.into(),
+            Metadata::new(8, 1.into(), VarInt(self.fuse as i32)),
+        )
+    }
+}
+
+impl World {
+    /// Replaces a TNT block with lit TNT, which explodes after `fuse` ticks
+    pub fn prime_tnt(&self, server: &Server, position: &WorldPosition, fuse: u32) {
+        self.set_block(position, BlockId::default());
+        let angle = rand::random::<f64>() * std::f64::consts::TAU;
+        let tnt = PrimedTnt {
+            entity_id: server.new_entity_id(),
+            uuid: Uuid::new_v4(),
+            body: Body::new(
+                Vector3::new(
+                    position.0.x as f64 + 0.5,
+                    position.0.y as f64,
+                    position.0.z as f64 + 0.5,
+                ),
+                Vector3::new(
+                    -angle.sin() * JUMP_SPREAD,
+                    JUMP_VELOCITY,
+                    -angle.cos() * JUMP_SPREAD,
+                ),
+            ),
+            fuse,
+        };
+        self.primed_tnt.lock().push(tnt);
+    }
+
+    /// Moves lit TNT and lets the TNT whose fuse ran out explode. TNT outside of the simulated
+    /// chunks waits
+    pub(super) fn tick_tnt(&self, server: &Server, simulated: &HashSet<Vector2<i32>>) {
+        let mut primed = std::mem::take(&mut *self.primed_tnt.lock());
+        let mut exploding = Vec::new();
+        primed.retain_mut(|tnt| {
+            if !is_simulated(simulated, tnt.body.position) {
+                return true;
+            }
+            tnt.fuse = tnt.fuse.saturating_sub(1);
+            if tnt.fuse == 0 {
+                exploding.push(tnt.body.position);
+                return false;
+            }
+            if self.step_physics(&mut tnt.body, &physics::TNT) {
+                self.sync_body(tnt.entity_id, &mut tnt.body, &physics::TNT);
+            }
+            true
+        });
+        self.primed_tnt.lock().append(&mut primed);
+        for position in exploding {
+            // The explosion starts a bit above the bottom, like in vanilla
+            self.explode(
+                server,
+                position.add(&Vector3::new(0.0, 0.0625, 0.0)),
+                POWER,
+                true,
+            );
+        }
+    }
+
+    /// Adds the lit TNT to the entities players may see
+    pub(super) fn track_primed_tnt(&self, tracked: &mut Vec<Tracked>) {
+        tracked.extend(self.primed_tnt.lock().iter().map(|tnt| Tracked {
+            entity_id: tnt.entity_id,
+            entity_type: EntityType::Tnt,
+            position: tnt.body.position,
+        }));
+    }
+
+    /// Shows the lit TNT among `entity_ids` to a client which just started to see it
+    pub(super) fn send_primed_tnt(&self, client: &Client, entity_ids: &HashSet<EntityId>) {
+        for tnt in self
+            .primed_tnt
+            .lock()
+            .iter()
+            .filter(|tnt| entity_ids.contains(&tnt.entity_id))
+        {
+            client.send_packet(&tnt.spawn_packet());
+            client.send_packet(&tnt.fuse_packet());
+        }
+    }
+}
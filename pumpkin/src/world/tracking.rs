@@ -0,0 +1,209 @@
+use std::{collections::HashSet, sync::Arc};
+
+use pumpkin_core::math::vector3::Vector3;
+use pumpkin_entity::{entity_type::EntityType, pose::EntityPose, EntityId};
+use pumpkin_protocol::{
+    client::play::{CRemoveEntities, CTeleportEntitiy, CUpdateEntityPos, CUpdateEntityPosRot},
+    ClientPacket, VarInt,
+};
+
+use crate::{client::Client, entity::player::Player};
+
+use super::{player_info, World};
+
+/// An entity players see once they are close enough
+pub(super) struct Tracked {
+    pub entity_id: EntityId,
+    pub entity_type: EntityType,
+    pub position: Vector3<f64>,
+}
+
+/// An angle in degrees as clients get it, in 256ths of a full turn
+pub fn angle_byte(degrees: f32) -> u8 {
+    (degrees.rem_euclid(360.0) * 256.0 / 360.0) as u8
+}
+
+/// How far an entity moved in 4096ths of a block, as movement packets send it. None if it
+/// moved further than they can carry, about 8 blocks
+pub fn position_delta(from: Vector3<f64>, to: Vector3<f64>) -> Option<(i16, i16, i16)> {
+    // Both positions are rounded, so clients adding up the deltas don't drift away
+    let delta = |from: f64, to: f64| {
+        i16::try_from((to * 4096.0).round() as i64 - (from * 4096.0).round() as i64).ok()
+    };
+    Some((
+        delta(from.x, to.x)?,
+        delta(from.y, to.y)?,
+        delta(from.z, to.z)?,
+    ))
+}
+
+impl World {
+    /// Sends a packet about an entity to the players who see it
+    pub fn broadcast_tracked<P>(&self, entity_id: EntityId, packet: &P)
+    where
+        P: ClientPacket,
+    {
+        let viewers: Vec<usize> = self
+            .tracked_entities
+            .lock()
+            .iter()
+            .filter(|(_, seen)| seen.contains(&entity_id))
+            .map(|(client_id, _)| *client_id)
+            .collect();
+        if viewers.is_empty() {
+            return;
+        }
+        let current_players = self.current_players.lock();
+        for client_id in viewers {
+            if let Some(player) = current_players.get(&client_id) {
+                player.client.send_packet(packet);
+            }
+        }
+    }
+
+    /// Tells the players who see an entity that it moved from `from` to `to`, with its
+    /// rotation if it turned. It is teleported if it moved too far for a movement packet
+    pub fn broadcast_movement(
+        &self,
+        entity_id: EntityId,
+        from: Vector3<f64>,
+        to: Vector3<f64>,
+        (yaw, pitch): (u8, u8),
+        rotated: bool,
+        on_ground: bool,
+    ) {
+        let id = entity_id.into();
+        match position_delta(from, to) {
+            Some((0, 0, 0)) if !rotated => {}
+            Some((x, y, z)) if rotated => self.broadcast_tracked(
+                entity_id,
+                &CUpdateEntityPosRot::new(id, x, y, z, yaw, pitch, on_ground),
+            ),
+            Some((x, y, z)) => {
+                self.broadcast_tracked(entity_id, &CUpdateEntityPos::new(id, x, y, z, on_ground));
+            }
+            None => self.broadcast_tracked(
+                entity_id,
+                &CTeleportEntitiy::new(id, to.x, to.y, to.z, yaw, pitch, on_ground),
+            ),
+        }
+    }
+
+    /// Hides an entity which is gone from the players who saw it
+    pub(super) fn untrack(&self, entity_id: EntityId) {
+        self.broadcast_tracked(entity_id, &CRemoveEntities::new(&[entity_id.into()]));
+        for seen in self.tracked_entities.lock().values_mut() {
+            seen.remove(&entity_id);
+        }
+    }
+
+    /// Shows the players the entities which came into their range and hides the ones which
+    /// left it or are gone. Players see entities as far as the type's tracking range, but not
+    /// further than their view distance
+    pub(super) fn update_tracking(&self) {
+        let players: Vec<Arc<Player>> = self.current_players.lock().values().cloned().collect();
+        let mut entities: Vec<Tracked> = {
+            // Clients ignore players which are not in their player list yet
+            let batch = self.player_info_batch.lock();
+            players
+                .iter()
+                .filter(|player| {
+                    player.living_entity.health.load() > 0.0 && !batch.contains(player)
+                })
+                .map(|player| Tracked {
+                    entity_id: player.entity_id(),
+                    entity_type: EntityType::Player,
+                    position: player.last_position.load(),
+                })
+                .collect()
+        };
+        self.track_falling_blocks(&mut entities);
+        self.track_primed_tnt(&mut entities);
+        self.track_experience_orbs(&mut entities);
+        self.track_items(&mut entities);
+        self.track_projectiles(&mut entities);
+        self.track_vehicles(&mut entities);
+        self.track_mobs(&mut entities);
+
+        let mut changes = Vec::new();
+        {
+            let mut tracked = self.tracked_entities.lock();
+            for player in &players {
+                let position = player.living_entity.entity.pos.load();
+                let view_distance = i32::from(player.view_distance());
+                let visible: HashSet<EntityId> = entities
+                    .iter()
+                    .filter(|entity| {
+                        let range =
+                            f64::from(entity.entity_type.tracking_range().min(view_distance) * 16);
+                        let (x, z) = (
+                            entity.position.x - position.x,
+                            entity.position.z - position.z,
+                        );
+                        entity.entity_id != player.entity_id() && x * x + z * z <= range * range
+                    })
+                    .map(|entity| entity.entity_id)
+                    .collect();
+                let seen = tracked.entry(player.client.id).or_default();
+                let hidden: Vec<VarInt> = seen
+                    .difference(&visible)
+                    .map(|entity_id| (*entity_id).into())
+                    .collect();
+                let shown: HashSet<EntityId> = visible.difference(seen).copied().collect();
+                *seen = visible;
+                if !hidden.is_empty() || !shown.is_empty() {
+                    changes.push((player, hidden, shown));
+                }
+            }
+        }
+        for (player, hidden, shown) in changes {
+            if !hidden.is_empty() {
+                player.client.send_packet(&CRemoveEntities::new(&hidden));
+            }
+            if !shown.is_empty() {
+                self.send_entities(&player.client, &shown, &players);
+            }
+        }
+    }
+
+    /// Spawns the entities among `entity_ids` for a client which just started to see them
+    fn send_entities(
+        &self,
+        client: &Client,
+        entity_ids: &HashSet<EntityId>,
+        players: &[Arc<Player>],
+    ) {
+        for player in players
+            .iter()
+            .filter(|player| entity_ids.contains(&player.entity_id()))
+        {
+            client.send_packet(&player_info::spawn_packet(player));
+            for packet in player.model_packets() {
+                client.send_packet(&packet);
+            }
+            for packet in player.equipment_packets() {
+                client.send_packet(&packet);
+            }
+            // Players who already sneak, sprint or glide
+            let entity = &player.living_entity.entity;
+            if entity.flags() != 0 {
+                client.send_packet(&entity.flags_packet());
+            }
+            if entity.pose.load() != EntityPose::Standing {
+                client.send_packet(&entity.pose_packet());
+            }
+            if player.is_sleeping() {
+                client.send_packet(&player.sleeping_packet());
+            }
+        }
+        self.send_falling_blocks(client, entity_ids);
+        self.send_primed_tnt(client, entity_ids);
+        self.send_experience_orbs(client, entity_ids);
+        self.send_items(client, entity_ids);
+        self.send_projectiles(client, entity_ids);
+        self.send_vehicles(client, entity_ids);
+        self.send_mobs(client, entity_ids);
+        // Only once the client knows the vehicles and their passengers
+        self.send_passengers(client, entity_ids);
+    }
+}
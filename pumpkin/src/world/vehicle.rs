@@ -0,0 +1,663 @@
+use std::collections::HashSet;
+
+use pumpkin_core::{
+    math::{boundingbox::BoundingBox, position::WorldPosition, vector2::Vector2, vector3::Vector3},
+    GameMode,
+};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_protocol::{
+    client::play::{CSetEntityMetadata, CSpawnEntity, Metadata},
+    VarInt,
+};
+use pumpkin_world::{
+    block::BlockId,
+    game_rules,
+    global_registry::{self, ITEM_REGISTRY},
+    item::ItemStack,
+    WORLD_LOWEST_Y,
+};
+use uuid::Uuid;
+
+use crate::{client::Client, entity::player::Player, server::Server};
+
+use super::{
+    is_simulated,
+    physics::{self, Body, Fluid, Physics},
+    projectile,
+    tracking::Tracked,
+    World,
+};
+
+/// The boat items by the wood type clients know, like in vanilla
+const BOAT_ITEMS: [&str; 9] = [
+    "minecraft:oak_boat",
+    "minecraft:spruce_boat",
+    "minecraft:birch_boat",
+    "minecraft:jungle_boat",
+    "minecraft:acacia_boat",
+    "minecraft:cherry_boat",
+    "minecraft:dark_oak_boat",
+    "minecraft:mangrove_boat",
+    "minecraft:bamboo_raft",
+];
+/// Vehicles break once the damage of the hits adds up to more than this, like in vanilla
+const BREAK_DAMAGE: f32 = 40.0;
+/// Hits count this many times their damage, so a few punches break a vehicle
+const HIT_MULTIPLIER: f32 = 10.0;
+/// Vehicles shake this many ticks after a hit
+const HURT_TICKS: i32 = 10;
+/// Vehicles this far below the world are gone
+const VOID_DEPTH: f64 = 64.0;
+/// Passengers sit this high above the bottom of their vehicle
+const RIDE_HEIGHT: f64 = 0.1875;
+/// Clients may move the boat they steer this much further than it went the tick before,
+/// squared like in vanilla
+const MAX_STEER_DISTANCE_SQUARED: f64 = 100.0;
+/// Boats float up this fast in water, their hull reaches this deep
+const BOAT_BUOYANCY: f64 = 0.06;
+const BOAT_DRAFT: f64 = 0.1;
+/// Minecarts on rails move at most this many blocks per tick, like in vanilla
+const MAX_RAIL_SPEED: f64 = 0.4;
+/// How much of their speed minecarts on rails keep every tick, with and without passengers
+const RIDDEN_RAIL_FRICTION: f64 = 0.997;
+const EMPTY_RAIL_FRICTION: f64 = 0.96;
+/// Rails going uphill slow minecarts down this much every tick
+const SLOPE_ACCELERATION: f64 = 0.0078125;
+/// Powered rails speed minecarts up this much every tick, unpowered ones brake them
+const POWERED_RAIL_BOOST: f64 = 0.06;
+/// Riders get a minecart which stands still moving this fast where they look
+const RIDER_PUSH: f64 = 0.1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VehicleKind {
+    /// With the wood type clients know, an index into `BOAT_ITEMS`
+    Boat(u8),
+    Minecart,
+}
+
+impl VehicleKind {
+    /// The vehicle an item places, None if it places none
+    pub fn of_item(item: &str) -> Option<Self> {
+        if item == "minecraft:minecart" {
+            return Some(Self::Minecart);
+        }
+        BOAT_ITEMS
+            .iter()
+            .position(|boat| *boat == item)
+            .map(|wood| Self::Boat(wood as u8))
+    }
+
+    /// The item it drops when it breaks
+    fn item(self) -> &'static str {
+        match self {
+            Self::Boat(wood) => BOAT_ITEMS[usize::from(wood)],
+            Self::Minecart => "minecraft:minecart",
+        }
+    }
+
+    const fn entity_type(self) -> EntityType {
+        match self {
+            Self::Boat(_) => EntityType::Boat,
+            Self::Minecart => EntityType::Minecart,
+        }
+    }
+
+    const fn physics(self) -> &'static Physics {
+        match self {
+            Self::Boat(_) => &physics::BOAT,
+            Self::Minecart => &physics::MINECART,
+        }
+    }
+
+    /// How many players fit in, like in vanilla
+    const fn seats(self) -> usize {
+        match self {
+            Self::Boat(_) => 2,
+            Self::Minecart => 1,
+        }
+    }
+}
+
+/// A boat or minecart players ride in, see `Passengers` for who rides it
+pub struct Vehicle {
+    entity_id: EntityId,
+    uuid: Uuid,
+    kind: VehicleKind,
+    body: Body,
+    /// Adds up with hits and goes down again over time, it breaks at `BREAK_DAMAGE`
+    damage: f32,
+    /// Ticks it still shakes from the last hit, and to which side
+    hurt_ticks: i32,
+    hurt_direction: i32,
+    /// Whether the rider of a minecart wants to go forward
+    pushed: bool,
+}
+
+/// A vehicle which was placed this tick, it appears with the next tick
+pub struct VehiclePlacement {
+    kind: VehicleKind,
+    position: Vector3<f64>,
+    yaw: f32,
+}
+
+impl Vehicle {
+    fn spawn_packet(&self) -> CSpawnEntity {
+        CSpawnEntity::new(
+            self.entity_id.into(),
+            self.uuid,
+            (self.kind.entity_type() as i32).into(),
+            self.body.synced_position().x,
+            self.body.synced_position().y,
+            self.body.synced_position().z,
+            0.0,
+            self.body.yaw.rem_euclid(360.0),
+            0.0,
+            0.into(),
+            self.body.velocity.x as f32,
+            self.body.velocity.y as f32,
+            self.body.velocity.z as f32,
+        )
+    }
+
+    /// Tells clients which wood a boat is made of, they show oak without it
+    fn wood_packet(&self) -> Option<CSetEntityMetadata<VarInt>> {
+        let VehicleKind::Boat(wood) = self.kind else {
+            return None;
+        };
+        Some(CSetEntityMetadata::new(
+            self.entity_id.into(),
+            Metadata::new(11, 1.into(), VarInt(i32::from(wood))),
+        ))
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        self.body.bounding_box(self.kind.physics())
+    }
+
+    /// Where its passengers are
+    fn seat(&self) -> Vector3<f64> {
+        self.body.position.add(&Vector3::new(0.0, RIDE_HEIGHT, 0.0))
+    }
+}
+
+/// Whether minecarts can ride on the block
+fn is_rail(block: &BlockId) -> bool {
+    matches!(
+        block.category(),
+        Some("minecraft:rail" | "minecraft:powered_rail" | "minecraft:detector_rail")
+    )
+}
+
+/// The two neighbours a rail of the shape connects, as horizontal block offsets
+fn rail_ends(shape: &str) -> Option<[(i32, i32); 2]> {
+    Some(match shape {
+        "north_south" | "ascending_north" | "ascending_south" => [(0, -1), (0, 1)],
+        "east_west" | "ascending_east" | "ascending_west" => [(-1, 0), (1, 0)],
+        "south_east" => [(0, 1), (1, 0)],
+        "south_west" => [(0, 1), (-1, 0)],
+        "north_west" => [(0, -1), (-1, 0)],
+        "north_east" => [(0, -1), (1, 0)],
+        _ => return None,
+    })
+}
+
+/// The direction a rail of the shape goes uphill, None if it is flat
+fn rail_ascent(shape: &str) -> Option<(i32, i32)> {
+    match shape {
+        "ascending_east" => Some((1, 0)),
+        "ascending_west" => Some((-1, 0)),
+        "ascending_north" => Some((0, -1)),
+        "ascending_south" => Some((0, 1)),
+        _ => None,
+    }
+}
+
+impl World {
+    /// Places a vehicle, it appears with the next tick
+    pub fn place_vehicle(&self, kind: VehicleKind, position: Vector3<f64>, yaw: f32) {
+        self.vehicle_placements.lock().push(VehiclePlacement {
+            kind,
+            position,
+            yaw,
+        });
+    }
+
+    /// Where a boat placed by a player looking from `eyes` into `direction` would be, on the
+    /// first block or water they look at within `range`. None if there is no room for it
+    pub fn boat_spot(
+        &self,
+        eyes: Vector3<f64>,
+        direction: Vector3<f64>,
+        range: f64,
+    ) -> Option<Vector3<f64>> {
+        let step = direction.normalize() * 0.1;
+        let mut point = eyes;
+        for _ in 0..(range * 10.0) as usize {
+            let next = point.add(&step);
+            let block = WorldPosition(Vector3::new(
+                next.x.floor() as i32,
+                next.y.floor() as i32,
+                next.z.floor() as i32,
+            ));
+            let hit = self.fluid_at(&block) == Some(Fluid::Water)
+                || self
+                    .level
+                    .get_block(&block)
+                    .is_some_and(|block| block.has_collision());
+            if hit {
+                let body = Body::new(point, Vector3::new(0.0, 0.0, 0.0));
+                let bounding_box = body.bounding_box(&physics::BOAT).expand(-0.1, -0.1, -0.1);
+                return (!self.collides(&bounding_box)).then_some(point);
+            }
+            point = next;
+        }
+        None
+    }
+
+    /// Places a minecart on a rail, like in vanilla it sits a bit higher on sloped rails.
+    /// Returns false if the block is no rail
+    pub fn place_minecart(&self, rail: &WorldPosition, yaw: f32) -> bool {
+        let Some(block) = self.level.get_block(rail) else {
+            return false;
+        };
+        if !is_rail(&block) {
+            return false;
+        }
+        let sloped = block.property("shape").and_then(rail_ascent).is_some();
+        let position = Vector3::new(
+            f64::from(rail.0.x) + 0.5,
+            f64::from(rail.0.y) + if sloped { 0.5 } else { 0.0625 },
+            f64::from(rail.0.z) + 0.5,
+        );
+        self.place_vehicle(VehicleKind::Minecart, position, yaw);
+        true
+    }
+
+    /// Lets a player get into a vehicle they clicked, if they reach it and there is room.
+    /// Returns false if the entity is no vehicle
+    pub fn enter_vehicle(&self, player: &Player, vehicle_id: EntityId) -> bool {
+        let seat = {
+            let vehicles = self.vehicles.lock();
+            let Some(vehicle) = vehicles
+                .iter()
+                .find(|vehicle| vehicle.entity_id == vehicle_id)
+            else {
+                return false;
+            };
+            if player.gamemode.load() == GameMode::Spectator
+                || self.passengers_of(vehicle_id).len() >= vehicle.kind.seats()
+                || !player.can_interact_with_entity(&vehicle.bounding_box())
+            {
+                return true;
+            }
+            vehicle.seat()
+        };
+        if self.start_riding(player.entity_id(), vehicle_id) {
+            player.follow_vehicle(seat);
+        }
+        true
+    }
+
+    /// Where the passengers of a vehicle sit, None if there is no such vehicle
+    pub(super) fn vehicle_seat(&self, vehicle_id: EntityId) -> Option<Vector3<f64>> {
+        self.vehicles
+            .lock()
+            .iter()
+            .find(|vehicle| vehicle.entity_id == vehicle_id)
+            .map(Vehicle::seat)
+    }
+
+    /// The top of a vehicle, None if there is no such vehicle
+    pub(super) fn vehicle_top(&self, vehicle_id: EntityId) -> Option<Vector3<f64>> {
+        self.vehicles
+            .lock()
+            .iter()
+            .find(|vehicle| vehicle.entity_id == vehicle_id)
+            .map(|vehicle| {
+                vehicle
+                    .body
+                    .position
+                    .add(&Vector3::new(0.0, vehicle.kind.physics().height, 0.0))
+            })
+    }
+
+    /// A player hits a vehicle, it breaks once it took enough damage or right away if they are
+    /// in creative mode. Returns false if the entity is no vehicle
+    pub fn hit_vehicle(&self, player: &Player, vehicle_id: EntityId, damage: f32) -> bool {
+        let mut vehicles = self.vehicles.lock();
+        let Some(index) = vehicles
+            .iter()
+            .position(|vehicle| vehicle.entity_id == vehicle_id)
+        else {
+            return false;
+        };
+        let vehicle = &mut vehicles[index];
+        if !player.can_interact_with_entity(&vehicle.bounding_box()) {
+            return true;
+        }
+        vehicle.hurt_ticks = HURT_TICKS;
+        vehicle.hurt_direction = -vehicle.hurt_direction;
+        vehicle.damage += damage * HIT_MULTIPLIER;
+        let creative = player.gamemode.load() == GameMode::Creative;
+        if !creative && vehicle.damage <= BREAK_DAMAGE {
+            let (entity_id, hurt_ticks, hurt_direction, damage) = (
+                vehicle.entity_id,
+                vehicle.hurt_ticks,
+                vehicle.hurt_direction,
+                vehicle.damage,
+            );
+            drop(vehicles);
+            self.broadcast_hurt(entity_id, hurt_ticks, hurt_direction, damage);
+            return true;
+        }
+        let vehicle = vehicles.remove(index);
+        drop(vehicles);
+        self.forget_rides(vehicle.entity_id);
+        if !creative && self.game_rule_bool(game_rules::DO_ENTITY_DROPS) {
+            if let Some(item_id) =
+                global_registry::find_protocol_id(ITEM_REGISTRY, vehicle.kind.item())
+            {
+                self.drop_entity_item(vehicle.body.position, ItemStack::new(1, item_id));
+            }
+        }
+        true
+    }
+
+    /// Tells the players who see a vehicle that it was hit, it shakes for a while
+    fn broadcast_hurt(&self, entity_id: EntityId, hurt_ticks: i32, direction: i32, damage: f32) {
+        for (index, value) in [(8, hurt_ticks), (9, direction)] {
+            self.broadcast_tracked(
+                entity_id,
+                &CSetEntityMetadata::new(
+                    entity_id.into(),
+                    Metadata::new(index, 1.into(), VarInt(value)),
+                ),
+            );
+        }
+        self.broadcast_tracked(
+            entity_id,
+            &CSetEntityMetadata::new(entity_id.into(), Metadata::new(10, 3.into(), damage)),
+        );
+    }
+
+    /// Moves the boat a player steers to where their client put it. Returns where it really
+    /// is if the client moved it too far or into blocks, the client has to put it back there
+    pub fn steer_boat(
+        &self,
+        player: &Player,
+        position: Vector3<f64>,
+        yaw: f32,
+        pitch: f32,
+    ) -> Option<(Vector3<f64>, f32, f32)> {
+        let vehicle_id = self.vehicle_of(player.entity_id())?;
+        if self.driver_of(vehicle_id) != Some(player.entity_id()) {
+            return None;
+        }
+        let mut vehicles = self.vehicles.lock();
+        let vehicle = vehicles.iter_mut().find(|vehicle| {
+            vehicle.entity_id == vehicle_id && matches!(vehicle.kind, VehicleKind::Boat(_))
+        })?;
+        let body = &mut vehicle.body;
+        let moved = position.sub(&body.position);
+        let mut moved_to = *body;
+        moved_to.position = position;
+        let too_far =
+            moved.length_squared() - body.velocity.length_squared() > MAX_STEER_DISTANCE_SQUARED;
+        // Boats only get into blocks they were stuck in already
+        let shrink = |body: &Body| {
+            body.bounding_box(&physics::BOAT)
+                .expand(-0.0625, -0.0625, -0.0625)
+        };
+        let into_blocks = !self.collides(&shrink(body)) && self.collides(&shrink(&moved_to));
+        if too_far || into_blocks {
+            if too_far {
+                log::warn!(
+                    "{} moved their boat too quickly! {moved:?}",
+                    player.gameprofile.name
+                );
+            }
+            return Some((body.position, body.yaw, body.pitch));
+        }
+        body.velocity = moved;
+        body.position = position;
+        body.yaw = yaw;
+        body.pitch = pitch;
+        None
+    }
+
+    /// Shows the other players which paddles of a boat move
+    pub fn paddle_boat(&self, player: &Player, left: bool, right: bool) {
+        let Some(vehicle_id) = self.vehicle_of(player.entity_id()) else {
+            return;
+        };
+        let steers = self.driver_of(vehicle_id) == Some(player.entity_id())
+            && self.vehicles.lock().iter().any(|vehicle| {
+                vehicle.entity_id == vehicle_id && matches!(vehicle.kind, VehicleKind::Boat(_))
+            });
+        if !steers {
+            return;
+        }
+        for (index, paddle) in [(12, left), (13, right)] {
+            self.broadcast_tracked(
+                vehicle_id,
+                &CSetEntityMetadata::new(vehicle_id.into(), Metadata::new(index, 8.into(), paddle)),
+            );
+        }
+    }
+
+    /// The rider of a minecart wants it to go forward or not
+    pub fn push_vehicle(&self, player: &Player, forward: bool) {
+        let Some(vehicle_id) = self.vehicle_of(player.entity_id()) else {
+            return;
+        };
+        let drives = self.driver_of(vehicle_id) == Some(player.entity_id());
+        if let Some(vehicle) = self
+            .vehicles
+            .lock()
+            .iter_mut()
+            .find(|vehicle| vehicle.entity_id == vehicle_id)
+        {
+            vehicle.pushed = forward && drives;
+        }
+    }
+
+    /// Spawns the placed vehicles and moves the others. Boats float and minecarts follow the
+    /// rails, boats with a driver are moved by the driver's client. Vehicles outside of the
+    /// simulated chunks wait
+    pub(super) fn tick_vehicles(&self, server: &Server, simulated: &HashSet<Vector2<i32>>) {
+        let placements = std::mem::take(&mut *self.vehicle_placements.lock());
+        self.vehicles
+            .lock()
+            .extend(placements.into_iter().map(|placement| {
+                let mut body = Body::new(placement.position, Vector3::new(0.0, 0.0, 0.0));
+                body.yaw = placement.yaw;
+                Vehicle {
+                    entity_id: server.new_entity_id(),
+                    uuid: Uuid::new_v4(),
+                    kind: placement.kind,
+                    body,
+                    damage: 0.0,
+                    hurt_ticks: 0,
+                    hurt_direction: 1,
+                    pushed: false,
+                }
+            }));
+
+        let mut vehicles = std::mem::take(&mut *self.vehicles.lock());
+        let mut gone = Vec::new();
+        vehicles.retain_mut(|vehicle| {
+            if !is_simulated(simulated, vehicle.body.position) {
+                return true;
+            }
+            vehicle.hurt_ticks = (vehicle.hurt_ticks - 1).max(0);
+            vehicle.damage = (vehicle.damage - 1.0).max(0.0);
+            if vehicle.body.position.y < f64::from(WORLD_LOWEST_Y) - VOID_DEPTH {
+                gone.push(vehicle.entity_id);
+                return false;
+            }
+            let physics = vehicle.kind.physics();
+            let moved = match vehicle.kind {
+                // The driver's client moves it, see `steer_boat`
+                VehicleKind::Boat(_) if self.driver_of(vehicle.entity_id).is_some() => true,
+                VehicleKind::Boat(_) => {
+                    self.float_boat(&mut vehicle.body);
+                    self.step_physics(&mut vehicle.body, physics)
+                }
+                VehicleKind::Minecart => {
+                    self.move_on_rail(vehicle) || self.step_physics(&mut vehicle.body, physics)
+                }
+            };
+            if moved {
+                self.sync_body(vehicle.entity_id, &mut vehicle.body, physics);
+            }
+            true
+        });
+        self.vehicles.lock().append(&mut vehicles);
+        for vehicle in gone {
+            self.forget_rides(vehicle);
+        }
+    }
+
+    /// Boats in water float up until their hull is out of it
+    fn float_boat(&self, body: &mut Body) {
+        let hull = WorldPosition(Vector3::new(
+            body.position.x.floor() as i32,
+            (body.position.y + BOAT_DRAFT).floor() as i32,
+            body.position.z.floor() as i32,
+        ));
+        if self.fluid_at(&hull) == Some(Fluid::Water) {
+            body.velocity.y += BOAT_BUOYANCY;
+        }
+    }
+
+    /// Moves a minecart along the rail it is on, like in vanilla slopes and powered rails
+    /// speed it up or slow it down. Returns false if it is on no rail
+    fn move_on_rail(&self, vehicle: &mut Vehicle) -> bool {
+        let position = vehicle.body.position;
+        let feet = WorldPosition(Vector3::new(
+            position.x.floor() as i32,
+            position.y.floor() as i32,
+            position.z.floor() as i32,
+        ));
+        // Minecarts going down a slope are a bit above the rail
+        let Some((rail_pos, rail)) = [feet, WorldPosition(feet.0.sub(&Vector3::new(0, 1, 0)))]
+            .into_iter()
+            .find_map(|pos| {
+                let block = self.level.get_block(&pos)?;
+                is_rail(&block).then_some((pos, block))
+            })
+        else {
+            return false;
+        };
+        let Some(shape) = rail.property("shape") else {
+            return false;
+        };
+        let Some(ends) = rail_ends(shape) else {
+            return false;
+        };
+        let ascent = rail_ascent(shape);
+
+        let mut velocity = vehicle.body.velocity;
+        if let Some((x, z)) = ascent {
+            velocity.x -= f64::from(x) * SLOPE_ACCELERATION;
+            velocity.z -= f64::from(z) * SLOPE_ACCELERATION;
+        }
+        let direction = Vector3::new(
+            f64::from(ends[1].0 - ends[0].0),
+            0.0,
+            f64::from(ends[1].1 - ends[0].1),
+        )
+        .normalize();
+        // The minecart keeps its speed, but goes the way of the rail
+        let along = velocity.x * direction.x + velocity.z * direction.z;
+        let mut speed = velocity.x.hypot(velocity.z).copysign(along);
+        let driver = self.driver_of(vehicle.entity_id);
+        if vehicle.pushed && speed.abs() < 0.01 {
+            let rider = driver.and_then(|id| self.get_player_by_entityid(id));
+            if let Some(rider) = rider {
+                let look = projectile::look_direction(rider.living_entity.entity.yaw.load(), 0.0);
+                speed += RIDER_PUSH * (look.x * direction.x + look.z * direction.z);
+            }
+        }
+        if rail.registry_state().map(|(id, _)| id) == Some("minecraft:powered_rail") {
+            if rail.property("powered") == Some("true") {
+                if speed.abs() > 0.01 {
+                    speed += POWERED_RAIL_BOOST.copysign(speed);
+                }
+            } else if speed.abs() < 0.03 {
+                speed = 0.0;
+            } else {
+                speed *= 0.5;
+            }
+        }
+        let friction = if driver.is_none() {
+            EMPTY_RAIL_FRICTION
+        } else {
+            RIDDEN_RAIL_FRICTION
+        };
+        speed = speed.clamp(-MAX_RAIL_SPEED, MAX_RAIL_SPEED) * friction;
+
+        // It stays on the line between the ends of the rail
+        let (center_x, center_z) = (f64::from(rail_pos.0.x) + 0.5, f64::from(rail_pos.0.z) + 0.5);
+        let (start_x, start_z) = (
+            center_x + f64::from(ends[0].0) * 0.5,
+            center_z + f64::from(ends[0].1) * 0.5,
+        );
+        let progress = (position.x - start_x) * direction.x + (position.z - start_z) * direction.z;
+        let x = start_x + direction.x * (progress + speed);
+        let z = start_z + direction.z * (progress + speed);
+        let height = ascent.map_or(0.0, |(up_x, up_z)| {
+            let climbed = |up: i32, at: f64, block: i32| match up.signum() {
+                1 => at - f64::from(block),
+                -1 => f64::from(block) + 1.0 - at,
+                _ => 0.0,
+            };
+            (climbed(up_x, x, rail_pos.0.x) + climbed(up_z, z, rail_pos.0.z)).clamp(0.0, 1.0)
+        });
+        let moved_to = Vector3::new(x, f64::from(rail_pos.0.y) + height, z);
+
+        // It stops at blocks in the way, e.g. at the end of the track
+        let in_front = WorldPosition(Vector3::new(
+            moved_to.x.floor() as i32,
+            (moved_to.y + 0.1).floor() as i32,
+            moved_to.z.floor() as i32,
+        ));
+        let blocked = self
+            .level
+            .get_block(&in_front)
+            .is_none_or(|block| block.has_collision());
+        let body = &mut vehicle.body;
+        body.on_ground = true;
+        if blocked {
+            body.velocity = Vector3::new(0.0, 0.0, 0.0);
+            return true;
+        }
+        body.position = moved_to;
+        body.velocity = Vector3::new(direction.x * speed, 0.0, direction.z * speed);
+        true
+    }
+
+    /// Adds the vehicles to the entities players may see
+    pub(super) fn track_vehicles(&self, tracked: &mut Vec<Tracked>) {
+        tracked.extend(self.vehicles.lock().iter().map(|vehicle| Tracked {
+            entity_id: vehicle.entity_id,
+            entity_type: vehicle.kind.entity_type(),
+            position: vehicle.body.position,
+        }));
+    }
+
+    /// Shows the vehicles among `entity_ids` to a client which just started to see them
+    pub(super) fn send_vehicles(&self, client: &Client, entity_ids: &HashSet<EntityId>) {
+        for vehicle in self
+            .vehicles
+            .lock()
+            .iter()
+            .filter(|vehicle| entity_ids.contains(&vehicle.entity_id))
+        {
+            client.send_packet(&vehicle.spawn_packet());
+            if let Some(wood) = vehicle.wood_packet() {
+                client.send_packet(&wood);
+            }
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_protocol::client::play::{CGameEvent, GameEvent};
+use pumpkin_world::level_data::LevelData;
+use rand::Rng;
+
+use crate::server::ticker::TICKS_PER_SECOND;
+
+/// How much the rain and thunder get stronger or weaker per tick, they fade in and out over 5 seconds
+const LEVEL_STEP: f32 = 0.01;
+/// Clients show rain once it is stronger than this
+const VISIBLE_RAIN_LEVEL: f32 = 0.2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WeatherType {
+    Clear,
+    Rain,
+    Thunder,
+}
+
+/// The weather of a world. Like in vanilla rain and thunder start and stop independently,
+/// thunder only shows while it rains
+pub struct Weather {
+    raining: bool,
+    /// Ticks until it starts or stops raining, a new duration is picked at 0
+    rain_time: i32,
+    thundering: bool,
+    /// Ticks until it starts or stops thundering, a new duration is picked at 0
+    thunder_time: i32,
+    /// Ticks the weather stays clear, set by `/weather clear`
+    clear_weather_time: i32,
+    /// How strong the rain is to clients, from 0 to 1
+    rain_level: f32,
+    /// How strong the thunder is to clients, from 0 to 1
+    thunder_level: f32,
+}
+
+impl Weather {
+    pub fn from_level_data(level_data: &LevelData) -> Self {
+        Self {
+            raining: level_data.raining,
+            rain_time: level_data.rain_time,
+            thundering: level_data.thundering,
+            thunder_time: level_data.thunder_time,
+            clear_weather_time: level_data.clear_weather_time,
+            rain_level: if level_data.raining { 1.0 } else { 0.0 },
+            thunder_level: if level_data.thundering { 1.0 } else { 0.0 },
+        }
+    }
+
+    pub fn write_level_data(&self, level_data: &mut LevelData) {
+        level_data.raining = self.raining;
+        level_data.rain_time = self.rain_time;
+        level_data.thundering = self.thundering;
+        level_data.thunder_time = self.thunder_time;
+        level_data.clear_weather_time = self.clear_weather_time;
+    }
+
+    pub fn weather_type(&self) -> WeatherType {
+        match (self.raining, self.thundering) {
+            (true, true) => WeatherType::Thunder,
+            (true, false) => WeatherType::Rain,
+            (false, _) => WeatherType::Clear,
+        }
+    }
+
+    /// Changes the weather for `duration` ticks, for a random duration if None
+    pub fn set(&mut self, weather: WeatherType, duration: Option<i32>) {
+        let duration = duration.unwrap_or_else(|| random_duration(weather));
+        match weather {
+            WeatherType::Clear => {
+                self.clear_weather_time = duration;
+                self.rain_time = 0;
+                self.thunder_time = 0;
+            }
+            WeatherType::Rain | WeatherType::Thunder => {
+                self.clear_weather_time = 0;
+                self.rain_time = duration;
+                self.thunder_time = duration;
+            }
+        }
+        self.raining = weather != WeatherType::Clear;
+        self.thundering = weather == WeatherType::Thunder;
+    }
+
+    /// Advances the weather by one tick, it only changes by itself if `cycle` is set.
+    /// Returns the new weather if it changed
+    pub fn tick(&mut self, cycle: bool) -> Option<WeatherType> {
+        let old = self.weather_type();
+        if cycle {
+            if self.clear_weather_time > 0 {
+                self.clear_weather_time -= 1;
+                // Pick new durations once the clear weather is over
+                self.rain_time = if self.raining { 0 } else { 1 };
+                self.thunder_time = if self.thundering { 0 } else { 1 };
+                self.raining = false;
+                self.thundering = false;
+            } else {
+                Self::advance(
+                    &mut self.thundering,
+                    &mut self.thunder_time,
+                    WeatherType::Thunder,
+                );
+                Self::advance(&mut self.raining, &mut self.rain_time, WeatherType::Rain);
+            }
+        }
+        self.thunder_level = step(self.thunder_level, self.thundering);
+        self.rain_level = step(self.rain_level, self.raining);
+        let new = self.weather_type();
+        (new != old).then_some(new)
+    }
+
+    /// Stops the rain and the thunder, new durations are picked on the next tick. Like in vanilla
+    /// this happens when players sleep through the night
+    pub fn reset_cycle(&mut self) {
+        self.raining = false;
+        self.rain_time = 0;
+        self.thundering = false;
+        self.thunder_time = 0;
+    }
+
+    /// Counts down until rain or thunder starts or stops, picking a new duration when it ran out
+    fn advance(active: &mut bool, time: &mut i32, weather: WeatherType) {
+        if *time > 0 {
+            *time -= 1;
+            if *time == 0 {
+                *active = !*active;
+            }
+        } else if *active {
+            *time = random_duration(weather);
+        } else {
+            *time = random_duration(WeatherType::Clear);
+        }
+    }
+
+    /// How strong the rain and the thunder are, from 0 to 1
+    pub const fn levels(&self) -> (f32, f32) {
+        (self.rain_level, self.thunder_level)
+    }
+
+    /// Whether clients show rain
+    pub fn is_raining(&self) -> bool {
+        self.rain_level > VISIBLE_RAIN_LEVEL
+    }
+
+    /// The packets which show the weather to a client which just joined the world
+    pub fn join_packets(&self) -> Vec<CGameEvent> {
+        if !self.is_raining() {
+            return Vec::new();
+        }
+        vec![
+            CGameEvent::new(GameEvent::BeginRaining, 0.0),
+            CGameEvent::new(GameEvent::RainLevelChange, self.rain_level),
+            CGameEvent::new(GameEvent::ThunderLevelChange, self.thunder_level),
+        ]
+    }
+
+    /// The packets which show clients how the weather changed since `before`
+    pub fn change_packets(&self, before: &WeatherSnapshot) -> Vec<CGameEvent> {
+        let mut packets = Vec::new();
+        if before.raining != self.is_raining() {
+            let event = if before.raining {
+                GameEvent::EndRaining
+            } else {
+                GameEvent::BeginRaining
+            };
+            packets.push(CGameEvent::new(event, 0.0));
+        }
+        if before.rain_level != self.rain_level {
+            packets.push(CGameEvent::new(GameEvent::RainLevelChange, self.rain_level));
+        }
+        if before.thunder_level != self.thunder_level {
+            packets.push(CGameEvent::new(
+                GameEvent::ThunderLevelChange,
+                self.thunder_level,
+            ));
+        }
+        packets
+    }
+
+    /// What clients currently see of the weather
+    pub fn snapshot(&self) -> WeatherSnapshot {
+        WeatherSnapshot {
+            raining: self.is_raining(),
+            rain_level: self.rain_level,
+            thunder_level: self.thunder_level,
+        }
+    }
+}
+
+/// What clients saw of the weather at some point, see `Weather::change_packets`
+pub struct WeatherSnapshot {
+    raining: bool,
+    rain_level: f32,
+    thunder_level: f32,
+}
+
+fn step(level: f32, active: bool) -> f32 {
+    let step = if active { LEVEL_STEP } else { -LEVEL_STEP };
+    (level + step).clamp(0.0, 1.0)
+}
+
+/// A random duration in ticks for a weather as configured
+fn random_duration(weather: WeatherType) -> i32 {
+    let config = &ADVANCED_CONFIG.get().weather;
+    let (min, max) = match weather {
+        WeatherType::Clear => (config.min_clear_duration, config.max_clear_duration),
+        WeatherType::Rain => (config.min_rain_duration, config.max_rain_duration),
+        WeatherType::Thunder => (config.min_thunder_duration, config.max_thunder_duration),
+    };
+    let seconds = rand::thread_rng().gen_range(min..=max.max(min));
+    (seconds as u64 * TICKS_PER_SECOND).min(i32::MAX as u64) as i32
+}